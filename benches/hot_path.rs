@@ -0,0 +1,187 @@
+//! Benchmarks for the code the overlap loop runs on every market WebSocket message.
+//!
+//! `cargo bench`, or `cargo bench -- process_message` for one group. Criterion compares each run with the
+//! previous one under `target/criterion`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dashmap::DashMap;
+use polymarket_arbitrage_bot::adapters::polymarket::ws_market::{process_message, BestPrices, PricesSnapshot};
+use polymarket_arbitrage_bot::domain::arbitrage::select_arb_legs;
+use polymarket_arbitrage_bot::domain::fees::LegFees;
+use polymarket_arbitrage_bot::utils::slug_builder::{build_updown_slug, parse_updown_slug};
+use polymarket_arbitrage_bot::utils::time_windows::period_start_et_unix_at;
+use rust_decimal_macros::dec;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// A Tuesday afternoon, clear of any DST change.
+const TS: i64 = 1_767_726_123;
+
+fn bench_select_arb_legs(c: &mut Criterion) {
+    let fees = LegFees {
+        bps_15: 100.0,
+        bps_5: 100.0,
+    };
+    let mut group = c.benchmark_group("select_arb_legs");
+    // 15m Up + 5m Down at 0.95: an arb.
+    group.bench_function("arb", |b| {
+        b.iter(|| {
+            select_arb_legs(
+                black_box(Some(dec!(0.45))),
+                black_box(Some(dec!(0.56))),
+                black_box(Some(dec!(0.51))),
+                black_box(Some(dec!(0.50))),
+                dec!(0.99),
+                &fees,
+                "15up",
+                "15down",
+                "5up",
+                "5down",
+            )
+        })
+    });
+    // Both pairs above the threshold: the common case.
+    group.bench_function("none", |b| {
+        b.iter(|| {
+            select_arb_legs(
+                black_box(Some(dec!(0.52))),
+                black_box(Some(dec!(0.49))),
+                black_box(Some(dec!(0.51))),
+                black_box(Some(dec!(0.50))),
+                dec!(0.99),
+                &fees,
+                "15up",
+                "15down",
+                "5up",
+                "5down",
+            )
+        })
+    });
+    group.finish();
+}
+
+fn bench_process_message(c: &mut Criterion) {
+    let prices: PricesSnapshot = Arc::new(DashMap::new());
+    let level = |price: f64| json!({ "price": price.to_string(), "size": "120.5" });
+    let book = json!({
+        "event_type": "book",
+        "asset_id": "15up",
+        "market": "0xc15",
+        "bids": (0..20).map(|i| level(0.44 - i as f64 * 0.01)).collect::<Vec<_>>(),
+        "asks": (0..20).map(|i| level(0.45 + i as f64 * 0.01)).collect::<Vec<_>>(),
+        "timestamp": "1767726123000",
+    })
+    .to_string();
+    let price_change = json!({
+        "event_type": "price_change",
+        "market": "0xc15",
+        "price_changes": [
+            {
+                "asset_id": "15up", "price": "0.45", "size": "80", "side": "SELL",
+                "best_bid": "0.44", "best_ask": "0.45",
+            },
+            {
+                "asset_id": "15down", "price": "0.55", "size": "60", "side": "BUY",
+                "best_bid": "0.55", "best_ask": "0.56",
+            },
+        ],
+        "timestamp": "1767726123000",
+    })
+    .to_string();
+
+    let mut group = c.benchmark_group("process_message");
+    group.bench_function("book", |b| b.iter(|| process_message(black_box(&book), &prices, None)));
+    group.bench_function("price_change", |b| b.iter(|| process_message(black_box(&price_change), &prices, None)));
+    group.finish();
+}
+
+/// The overlap loop's read of the four asks while a WS task keeps writing the same map, against the
+/// `tokio::sync::RwLock<HashMap>` the snapshot used to be.
+fn bench_prices_snapshot(c: &mut Criterion) {
+    const TOKENS: [&str; 4] = ["15up", "15down", "5up", "5down"];
+    let quote = |i: u64| BestPrices {
+        bid: Some(0.40 + (i % 10) as f64 * 0.01),
+        ask: Some(0.41 + (i % 10) as f64 * 0.01),
+        ask_size: Some(100.0),
+        received_at_ms: Some(i as i64),
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("prices_snapshot");
+
+    let sharded: PricesSnapshot = Arc::new(DashMap::new());
+    let stop = Arc::new(AtomicBool::new(false));
+    let writer = {
+        let (prices, stop) = (Arc::clone(&sharded), Arc::clone(&stop));
+        std::thread::spawn(move || {
+            let mut i = 0u64;
+            while !stop.load(Ordering::Relaxed) {
+                prices.insert(TOKENS[(i % 4) as usize].to_string(), quote(i));
+                i += 1;
+            }
+        })
+    };
+    group.bench_function("dashmap", |b| {
+        b.iter(|| TOKENS.map(|token| sharded.get(black_box(token)).and_then(|p| p.ask)))
+    });
+    stop.store(true, Ordering::Relaxed);
+    writer.join().unwrap();
+
+    let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let locked = Arc::new(RwLock::new(HashMap::<String, BestPrices>::new()));
+    stop.store(false, Ordering::Relaxed);
+    let writer = {
+        let (prices, stop) = (Arc::clone(&locked), Arc::clone(&stop));
+        std::thread::spawn(move || {
+            let mut i = 0u64;
+            while !stop.load(Ordering::Relaxed) {
+                prices.blocking_write().insert(TOKENS[(i % 4) as usize].to_string(), quote(i));
+                i += 1;
+            }
+        })
+    };
+    group.bench_function("tokio_rwlock", |b| {
+        b.to_async(&rt).iter(|| async {
+            let snap = locked.read().await;
+            TOKENS.map(|token| snap.get(black_box(token)).and_then(|p| p.ask))
+        })
+    });
+    stop.store(true, Ordering::Relaxed);
+    writer.join().unwrap();
+    group.finish();
+}
+
+fn bench_period_start(c: &mut Criterion) {
+    let mut group = c.benchmark_group("period_start_et_unix_at");
+    for minutes in [5, 15, 60] {
+        group.bench_function(format!("{}m", minutes), |b| {
+            b.iter(|| period_start_et_unix_at(black_box(TS), minutes))
+        });
+    }
+    group.finish();
+}
+
+fn bench_slugs(c: &mut Criterion) {
+    let mut group = c.benchmark_group("slugs");
+    group.bench_function("parse_updown_slug", |b| {
+        b.iter(|| parse_updown_slug(black_box("btc-updown-15m-1767726000")))
+    });
+    for minutes in [15, 60] {
+        group.bench_function(format!("build_updown_slug {}m", minutes), |b| {
+            b.iter(|| build_updown_slug("btc", minutes, black_box(1_767_726_000)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_select_arb_legs,
+    bench_process_message,
+    bench_prices_snapshot,
+    bench_period_start,
+    bench_slugs
+);
+criterion_main!(benches);
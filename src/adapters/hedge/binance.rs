@@ -0,0 +1,105 @@
+//! Binance USDⓈ-M futures (live or testnet) as a `Hedger`: HMAC-signed REST, market orders only.
+
+use super::Hedger;
+use crate::utils::proxy;
+use crate::utils::secret::Secret;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::future::{BoxFuture, FutureExt};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+
+const LIVE_URL: &str = "https://fapi.binance.com";
+const TESTNET_URL: &str = "https://testnet.binancefuture.com";
+const RECV_WINDOW_MS: u64 = 5_000;
+
+pub struct BinanceFuturesHedger {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    api_secret: Secret,
+}
+
+#[derive(Debug, Deserialize)]
+struct PositionRisk {
+    #[serde(rename = "positionAmt")]
+    position_amt: String,
+}
+
+impl BinanceFuturesHedger {
+    pub fn new(api_key: String, api_secret: Secret, testnet: bool) -> Self {
+        Self {
+            client: proxy::client_builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            base_url: if testnet { TESTNET_URL } else { LIVE_URL }.to_string(),
+            api_key,
+            api_secret,
+        }
+    }
+
+    /// `params` plus timestamp, recvWindow, and the HMAC-SHA256 signature of the whole query.
+    fn signed_query(&self, params: &str) -> Result<String> {
+        let query = format!(
+            "{}&timestamp={}&recvWindow={}",
+            params,
+            Utc::now().timestamp_millis(),
+            RECV_WINDOW_MS
+        );
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.api_secret.expose().as_bytes())
+            .map_err(|e| anyhow::anyhow!("Failed to create HMAC: {}", e))?;
+        mac.update(query.as_bytes());
+        Ok(format!("{}&signature={}", query, hex::encode(mac.finalize().into_bytes())))
+    }
+
+    async fn send(&self, method: reqwest::Method, path: &str, params: &str) -> Result<String> {
+        let url = format!("{}{}?{}", self.base_url, path, self.signed_query(params)?);
+        let response = self
+            .client
+            .request(method, &url)
+            .header("X-MBX-APIKEY", &self.api_key)
+            .send()
+            .await
+            .context(format!("Binance request {} failed", path))?;
+        let status = response.status();
+        let body = response.text().await.context("Read Binance response")?;
+        if !status.is_success() {
+            anyhow::bail!("Binance {} returned {}: {}", path, status, body);
+        }
+        Ok(body)
+    }
+}
+
+impl Hedger for BinanceFuturesHedger {
+    fn name(&self) -> &str {
+        "binance-futures"
+    }
+
+    fn position<'a>(&'a self, perp_symbol: &'a str) -> BoxFuture<'a, Result<f64>> {
+        async move {
+            let body = self
+                .send(reqwest::Method::GET, "/fapi/v2/positionRisk", &format!("symbol={}", perp_symbol))
+                .await?;
+            let positions: Vec<PositionRisk> = serde_json::from_str(&body).context("Parse Binance positionRisk")?;
+            Ok(positions
+                .iter()
+                .filter_map(|p| p.position_amt.parse::<f64>().ok())
+                .sum())
+        }
+        .boxed()
+    }
+
+    fn trade<'a>(&'a self, perp_symbol: &'a str, qty: f64) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let side = if qty > 0.0 { "BUY" } else { "SELL" };
+            // Rounded so float noise from the caller's step rounding doesn't reach the wire.
+            let quantity = (qty.abs() * 1e8).round() / 1e8;
+            let params = format!("symbol={}&side={}&type=MARKET&quantity={}", perp_symbol, side, quantity);
+            self.send(reqwest::Method::POST, "/fapi/v1/order", &params).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,19 @@
+//! Perpetual futures venues used to offset the spot delta of open Polymarket legs.
+
+pub mod binance;
+
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+
+pub use binance::BinanceFuturesHedger;
+
+/// A venue holding one signed perp position per symbol (positive = long).
+pub trait Hedger: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Current position in base units of `perp_symbol` (e.g. "BTCUSDT").
+    fn position<'a>(&'a self, perp_symbol: &'a str) -> BoxFuture<'a, Result<f64>>;
+
+    /// Buy (`qty` > 0) or sell (`qty` < 0) `qty` base units at market.
+    fn trade<'a>(&'a self, perp_symbol: &'a str, qty: f64) -> BoxFuture<'a, Result<()>>;
+}
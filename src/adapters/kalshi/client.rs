@@ -0,0 +1,383 @@
+//! REST client for the Kalshi trade API v2. Requests are signed with the account's RSA key (RSA-PSS over
+//! timestamp + method + path). Token ids are "<market ticker>:yes" / "<market ticker>:no"; prices on the wire
+//! are cents and map to 0..1 like Polymarket's, and order statuses map onto the CLOB's ("live", "matched").
+
+use crate::adapters::venue::{PredictionMarketVenue, VenueMarket};
+use crate::config::KalshiConfig;
+use crate::models::{OrderBook, OrderBookEntry, OrderRequest, OrderResponse, OrderStatus, OrderType, RedeemResponse};
+use crate::utils::proxy;
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use futures_util::future::{BoxFuture, FutureExt};
+use rsa::pkcs1::DecodeRsaPrivateKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::pss::BlindedSigningKey;
+use rsa::signature::{RandomizedSigner, SignatureEncoding};
+use rsa::RsaPrivateKey;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+
+pub struct KalshiApi {
+    client: reqwest::Client,
+    api_url: String,
+    key_id: Option<String>,
+    signing_key: Option<BlindedSigningKey<Sha256>>,
+}
+
+/// One binary market of a series.
+#[derive(Debug, Clone, Deserialize)]
+pub struct KalshiMarket {
+    pub ticker: String,
+    pub close_time: DateTime<Utc>,
+    /// Spot the market resolves against ("yes" when the settlement price is at or above it).
+    #[serde(default)]
+    pub floor_strike: Option<f64>,
+    /// "yes" / "no" once settled, empty before.
+    #[serde(default)]
+    pub result: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketsPage {
+    markets: Vec<KalshiMarket>,
+    #[serde(default)]
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketEnvelope {
+    market: KalshiMarket,
+}
+
+/// Resting bids per side as [price cents, contracts], ascending by price.
+#[derive(Debug, Deserialize)]
+struct Orderbook {
+    #[serde(default)]
+    yes: Option<Vec<[i64; 2]>>,
+    #[serde(default)]
+    no: Option<Vec<[i64; 2]>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderbookEnvelope {
+    orderbook: Orderbook,
+}
+
+#[derive(Debug, Deserialize)]
+struct KalshiOrder {
+    order_id: String,
+    status: String,
+    #[serde(default)]
+    fill_count: Option<i64>,
+    #[serde(default)]
+    remaining_count: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderEnvelope {
+    order: KalshiOrder,
+}
+
+impl KalshiApi {
+    /// Reads the private key PEM (PKCS#1 or PKCS#8) if configured; without one only market data works.
+    pub fn from_config(config: &KalshiConfig) -> Result<Self> {
+        let signing_key = match config.private_key_path.as_deref() {
+            Some(path) => {
+                let pem = std::fs::read_to_string(path).context(format!("Failed to read Kalshi key {}", path))?;
+                let key = RsaPrivateKey::from_pkcs1_pem(&pem)
+                    .or_else(|_| RsaPrivateKey::from_pkcs8_pem(&pem))
+                    .map_err(|e| anyhow::anyhow!("Invalid Kalshi private key {}: {}", path, e))?;
+                Some(BlindedSigningKey::<Sha256>::new(key))
+            }
+            None => None,
+        };
+        Ok(Self {
+            client: proxy::client_builder()
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .expect("Failed to create HTTP client"),
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            key_id: config.key_id.clone(),
+            signing_key,
+        })
+    }
+
+    pub fn can_trade(&self) -> bool {
+        self.key_id.is_some() && self.signing_key.is_some()
+    }
+
+    /// Open markets of `series` (e.g. "KXBTC15M"), following the cursor.
+    pub async fn open_markets(&self, series: &str) -> Result<Vec<KalshiMarket>> {
+        let mut markets = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let mut query = format!("series_ticker={}&status=open&limit=200", series);
+            if let Some(c) = cursor.as_deref() {
+                query.push_str(&format!("&cursor={}", c));
+            }
+            let body = self.send(reqwest::Method::GET, "/markets", Some(&query), None).await?;
+            let page: MarketsPage = serde_json::from_str(&body).context("Parse Kalshi markets")?;
+            markets.extend(page.markets);
+            match page.cursor.filter(|c| !c.is_empty()) {
+                Some(c) => cursor = Some(c),
+                None => return Ok(markets),
+            }
+        }
+    }
+
+    pub async fn market(&self, ticker: &str) -> Result<KalshiMarket> {
+        let body = self
+            .send(reqwest::Method::GET, &format!("/markets/{}", ticker), None, None)
+            .await?;
+        let envelope: MarketEnvelope = serde_json::from_str(&body).context("Parse Kalshi market")?;
+        Ok(envelope.market)
+    }
+
+    /// "KXBTC15M-...:yes" for `ticker` and `yes`.
+    pub fn token_id(ticker: &str, yes: bool) -> String {
+        format!("{}:{}", ticker, if yes { "yes" } else { "no" })
+    }
+
+    /// (ticker, yes) of a token id built by `token_id`.
+    fn parse_token(token_id: &str) -> Result<(&str, bool)> {
+        match token_id.rsplit_once(':') {
+            Some((ticker, "yes")) => Ok((ticker, true)),
+            Some((ticker, "no")) => Ok((ticker, false)),
+            _ => anyhow::bail!("Not a Kalshi token id: {}", token_id),
+        }
+    }
+
+    /// KALSHI-ACCESS-* headers: the RSA-PSS signature of timestamp (ms) + method + path (no query).
+    fn auth_headers(&self, method: &reqwest::Method, path: &str) -> Result<[(&'static str, String); 3]> {
+        let (Some(key_id), Some(signing_key)) = (self.key_id.as_deref(), self.signing_key.as_ref()) else {
+            anyhow::bail!("Kalshi key_id and private_key_path are required for trading");
+        };
+        let timestamp = Utc::now().timestamp_millis().to_string();
+        let message = format!("{}{}{}", timestamp, method.as_str(), path);
+        let signature = signing_key.sign_with_rng(&mut rand::thread_rng(), message.as_bytes());
+        Ok([
+            ("KALSHI-ACCESS-KEY", key_id.to_string()),
+            ("KALSHI-ACCESS-TIMESTAMP", timestamp),
+            (
+                "KALSHI-ACCESS-SIGNATURE",
+                base64::engine::general_purpose::STANDARD.encode(signature.to_bytes()),
+            ),
+        ])
+    }
+
+    /// Market data is public; portfolio endpoints are signed.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        endpoint: &str,
+        query: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> Result<String> {
+        let url = match query {
+            Some(q) => format!("{}{}?{}", self.api_url, endpoint, q),
+            None => format!("{}{}", self.api_url, endpoint),
+        };
+        let mut request = self.client.request(method.clone(), &url);
+        if endpoint.starts_with("/portfolio") {
+            let path = reqwest::Url::parse(&url).context("Kalshi URL")?.path().to_string();
+            for (name, value) in self.auth_headers(&method, &path)? {
+                request = request.header(name, value);
+            }
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+        let response = request
+            .send()
+            .await
+            .context(format!("Kalshi request {} failed", endpoint))?;
+        let status = response.status();
+        let text = response.text().await.context("Read Kalshi response")?;
+        if !status.is_success() {
+            anyhow::bail!("Kalshi {} returned {}: {}", endpoint, status, text);
+        }
+        Ok(text)
+    }
+}
+
+/// The CLOB's vocabulary for a Kalshi order status.
+fn clob_status(status: &str) -> String {
+    match status {
+        "resting" => "live",
+        "executed" => "matched",
+        "canceled" => "canceled",
+        other => other,
+    }
+    .to_string()
+}
+
+fn cents_to_price(cents: i64) -> Decimal {
+    Decimal::new(cents, 2)
+}
+
+impl PredictionMarketVenue for KalshiApi {
+    /// Bids are the side's own bids; asks come from the opposite side's bids (a yes ask at p is a no bid at 1 - p).
+    fn get_orderbook<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<OrderBook>> {
+        async move {
+            let (ticker, yes) = Self::parse_token(token_id)?;
+            let body = self
+                .send(reqwest::Method::GET, &format!("/markets/{}/orderbook", ticker), None, None)
+                .await?;
+            let book = serde_json::from_str::<OrderbookEnvelope>(&body)
+                .context("Parse Kalshi orderbook")?
+                .orderbook;
+            let (own, other) = if yes { (book.yes, book.no) } else { (book.no, book.yes) };
+            let level = |price: i64, size: i64| OrderBookEntry {
+                price: cents_to_price(price),
+                size: Decimal::from(size),
+            };
+            let mut bids: Vec<OrderBookEntry> = own.unwrap_or_default().iter().map(|[p, s]| level(*p, *s)).collect();
+            bids.reverse();
+            let asks = other
+                .unwrap_or_default()
+                .iter()
+                .rev()
+                .map(|[p, s]| level(100 - p, *s))
+                .collect();
+            Ok(OrderBook {
+                bids,
+                asks,
+                tick_size: Some(cents_to_price(1)),
+                min_order_size: Some(Decimal::ONE),
+            })
+        }
+        .boxed()
+    }
+
+    /// The open market of `series` (e.g. "KXBTC15M") closing at `close_unix`.
+    fn find_market<'a>(&'a self, series: &'a str, close_unix: i64) -> BoxFuture<'a, Result<Option<VenueMarket>>> {
+        async move {
+            Ok(self
+                .open_markets(series)
+                .await?
+                .into_iter()
+                .find(|m| m.close_time.timestamp() == close_unix)
+                .map(|m| VenueMarket {
+                    yes_token: Self::token_id(&m.ticker, true),
+                    no_token: Self::token_id(&m.ticker, false),
+                    id: m.ticker,
+                    close_unix,
+                    strike: m.floor_strike,
+                }))
+        }
+        .boxed()
+    }
+
+    /// Limit order in whole contracts; "FOK" maps to fill-or-kill, anything else rests.
+    fn place_order<'a>(&'a self, order: &'a OrderRequest) -> BoxFuture<'a, Result<OrderResponse>> {
+        async move {
+            let (ticker, yes) = Self::parse_token(&order.token_id)?;
+            let count = order.size.parse::<f64>().context("Order size")?.floor() as i64;
+            let cents = (order.price.parse::<f64>().context("Order price")? * 100.0).round() as i64;
+            if count < 1 || !(1..=99).contains(&cents) {
+                anyhow::bail!("Kalshi order out of range: {} contracts at {} cents", count, cents);
+            }
+            let mut body = json!({
+                "ticker": ticker,
+                "client_order_id": format!("{}-{}", ticker, Utc::now().timestamp_nanos_opt().unwrap_or_default()),
+                "side": if yes { "yes" } else { "no" },
+                "action": order.side.as_str().to_lowercase(),
+                "count": count,
+                "type": "limit",
+            });
+            body[if yes { "yes_price" } else { "no_price" }] = json!(cents);
+            if order.order_type == OrderType::Fok {
+                body["time_in_force"] = json!("fill_or_kill");
+            }
+            let text = self
+                .send(reqwest::Method::POST, "/portfolio/orders", None, Some(body))
+                .await?;
+            let placed = serde_json::from_str::<OrderEnvelope>(&text)
+                .context("Parse Kalshi order")?
+                .order;
+            Ok(OrderResponse {
+                order_id: Some(placed.order_id),
+                status: clob_status(&placed.status),
+                message: None,
+                latency: None,
+            })
+        }
+        .boxed()
+    }
+
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<()>> {
+        async move {
+            self.send(reqwest::Method::DELETE, &format!("/portfolio/orders/{}", order_id), None, None)
+                .await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn get_order_status<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderStatus>> {
+        async move {
+            let text = self
+                .send(reqwest::Method::GET, &format!("/portfolio/orders/{}", order_id), None, None)
+                .await?;
+            let order = serde_json::from_str::<OrderEnvelope>(&text)
+                .context("Parse Kalshi order")?
+                .order;
+            let filled = order.fill_count.unwrap_or(0);
+            Ok(OrderStatus {
+                id: Some(order.order_id),
+                status: Some(clob_status(&order.status)),
+                original_size: Some((filled + order.remaining_count.unwrap_or(0)).to_string()),
+                size_matched: Some(filled.to_string()),
+            })
+        }
+        .boxed()
+    }
+
+    /// `condition_id` is the market ticker.
+    fn resolved_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>> {
+        async move {
+            let market = self.market(condition_id).await?;
+            Ok(match market.result.as_str() {
+                "yes" => Some((Self::token_id(condition_id, true), "Yes".to_string())),
+                "no" => Some((Self::token_id(condition_id, false), "No".to_string())),
+                _ => None,
+            })
+        }
+        .boxed()
+    }
+
+    /// Kalshi credits settled positions to the balance itself; nothing to send.
+    fn redeem<'a>(
+        &'a self,
+        condition_id: &'a str,
+        _token_id: &'a str,
+        _outcome: &'a str,
+    ) -> BoxFuture<'a, Result<RedeemResponse>> {
+        async move {
+            Ok(RedeemResponse {
+                success: true,
+                message: Some(format!("{} settled by Kalshi", condition_id)),
+                transaction_hash: None,
+                amount_redeemed: None,
+            })
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_ids_round_trip() {
+        let token = KalshiApi::token_id("KXBTC15M-25OCT161415-15", false);
+        assert_eq!(token, "KXBTC15M-25OCT161415-15:no");
+        assert_eq!(KalshiApi::parse_token(&token).unwrap(), ("KXBTC15M-25OCT161415-15", false));
+        assert!(KalshiApi::parse_token("123456").is_err());
+        assert_eq!(clob_status("executed"), "matched");
+    }
+}
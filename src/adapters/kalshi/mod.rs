@@ -0,0 +1,5 @@
+//! Kalshi (CFTC-regulated exchange) as a `PredictionMarketVenue`, for its 15-minute crypto up/down series.
+
+pub mod client;
+
+pub use client::{KalshiApi, KalshiMarket};
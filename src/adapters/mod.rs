@@ -1 +1,5 @@
+pub mod hedge;
+pub mod kalshi;
 pub mod polymarket;
+pub mod signer;
+pub mod venue;
@@ -1,7 +1,8 @@
 use crate::adapters::polymarket::PolymarketApi;
-use crate::models::{Market, MarketDetails, OrderBook, TokenPrice};
+use crate::models::{Market, MarketDetails, Midpoint, OrderBook, PricePoint, Spread, TokenPrice};
 use anyhow::Result;
 use rust_decimal::Decimal;
+use std::collections::HashMap;
 
 pub async fn get_market_by_slug(api: &PolymarketApi, slug: &str) -> Result<Market> {
     api.get_market_by_slug(slug).await
@@ -15,10 +16,35 @@ pub async fn get_orderbook(api: &PolymarketApi, token_id: &str) -> Result<OrderB
     api.get_orderbook(token_id).await
 }
 
+pub async fn get_orderbooks(api: &PolymarketApi, token_ids: &[&str]) -> Result<HashMap<String, OrderBook>> {
+    api.get_orderbooks(token_ids).await
+}
+
 pub async fn get_price(api: &PolymarketApi, token_id: &str, side: &str) -> Result<Decimal> {
     api.get_price(token_id, side).await
 }
 
+pub async fn get_prices(api: &PolymarketApi, token_ids: &[&str], side: &str) -> Result<HashMap<String, Decimal>> {
+    api.get_prices(token_ids, side).await
+}
+
+pub async fn get_midpoint(api: &PolymarketApi, token_id: &str) -> Result<Midpoint> {
+    api.get_midpoint(token_id).await
+}
+
+pub async fn get_spread(api: &PolymarketApi, token_id: &str) -> Result<Spread> {
+    api.get_spread(token_id).await
+}
+
+pub async fn get_price_history(
+    api: &PolymarketApi,
+    token_id: &str,
+    interval_mins: u32,
+    range: (i64, i64),
+) -> Result<Vec<PricePoint>> {
+    api.get_price_history(token_id, interval_mins, range).await
+}
+
 pub async fn get_best_price(api: &PolymarketApi, token_id: &str) -> Result<Option<TokenPrice>> {
     api.get_best_price(token_id).await
 }
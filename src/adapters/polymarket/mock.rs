@@ -0,0 +1,688 @@
+//! In-memory `PolymarketClient` for tests and dry runs: markets, books, balances, and outcomes are set up
+//! front, orders fill in full at their limit price unless rejections (or resting GTC orders) are switched on, and
+//! every order, cancel, and redemption is recorded for assertions.
+
+use crate::adapters::polymarket::venue::{find_updown_market, PolymarketClient};
+use crate::adapters::venue::{
+    AmbiguousPost, PredictionMarketVenue, RedemptionStatus, UnconfirmedRedemption, VenueMarket,
+};
+use crate::domain::ticks::OrderRules;
+use crate::models::{
+    BalanceAllowance, BalanceAsset, Fill, Market, MarketDetails, MarketToken, OpenOrder, OrderBook, OrderBookEntry,
+    OrderRequest, OrderResponse, OrderStatus, OrderType, RedeemResponse, Side, TokenPrice, TradeHistoryParams,
+};
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::future::{self, BoxFuture};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, MutexGuard};
+
+const MOCK_TX_HASH: &str = "0xmock";
+
+#[derive(Default)]
+pub struct MockPolymarketApi {
+    state: Mutex<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    wallet: Option<String>,
+    usdc_balance: f64,
+    /// USDC deposits to the wallet as (block, amount), and the current block for `recent_usdc_inbound`.
+    deposits: Vec<(u64, f64)>,
+    head_block: u64,
+    /// By condition id.
+    markets: HashMap<String, MarketDetails>,
+    slugs: HashMap<String, String>,
+    books: HashMap<String, OrderBook>,
+    reject_orders: bool,
+    /// Posts to accept before every further one fails with an error; None accepts them all.
+    fail_orders_after: Option<usize>,
+    /// GTC and GTD orders rest unmatched until `fill_resting`, instead of filling at once.
+    rest_orders: bool,
+    /// FAK orders match at most this many shares, and the rest is cancelled; None fills them in full.
+    fak_depth: Option<f64>,
+    /// Shares matched so far of each order placed while `rest_orders` was on.
+    size_matched: HashMap<String, f64>,
+    /// Posts still to fail with `AmbiguousPost`, and whether those reach the book anyway.
+    ambiguous_posts: (usize, bool),
+    /// Lookups of a submitted order still to miss it, as while the CLOB's listings lag behind a post.
+    lagging_lookups: usize,
+    orders: Vec<(String, OrderRequest)>,
+    /// Resting orders reported by `get_open_orders`, until cancelled.
+    open_orders: Vec<OpenOrder>,
+    cancelled: Vec<String>,
+    fills: Vec<Fill>,
+    redeemed: Vec<(String, String)>,
+    /// Condition ids whose next redemption fails.
+    failing_redeems: HashSet<String>,
+    /// Condition ids whose next redemption is sent but left unconfirmed, with the tx hash it reports.
+    unconfirmed_redeems: HashMap<String, String>,
+    /// `redemption_status` by tx hash; unknown hashes are `Failed`.
+    redemption_statuses: HashMap<String, RedemptionStatus>,
+    /// Gas in USD reported by `estimate_redemption_cost_usd` for any batch.
+    redemption_cost_usd: f64,
+    /// CTF payout winner index by condition id, when it differs from the `resolve`d winner.
+    onchain_winners: HashMap<String, Option<usize>>,
+}
+
+impl MockPolymarketApi {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wallet reported by `wallet_address`; None (the default) behaves like a monitor-only client.
+    pub fn with_wallet(self, wallet: &str, usdc_balance: f64) -> Self {
+        {
+            let mut state = self.lock();
+            state.wallet = Some(wallet.to_string());
+            state.usdc_balance = usdc_balance;
+        }
+        self
+    }
+
+    /// Record a USDC deposit of `amount` to the wallet in `block`, and move the chain head to it.
+    pub fn deposit_usdc(&self, block: u64, amount: f64) {
+        let mut state = self.lock();
+        state.deposits.push((block, amount));
+        state.head_block = state.head_block.max(block);
+    }
+
+    pub fn set_head_block(&self, block: u64) {
+        self.lock().head_block = block;
+    }
+
+    /// List an open binary market under `slug` with one token per (token id, outcome).
+    pub fn add_market(&self, slug: &str, condition_id: &str, tokens: &[(&str, &str)]) {
+        let details = MarketDetails {
+            condition_id: condition_id.to_string(),
+            question: slug.to_string(),
+            tokens: tokens
+                .iter()
+                .map(|(token_id, outcome)| MarketToken {
+                    outcome: outcome.to_string(),
+                    token_id: token_id.to_string(),
+                    winner: false,
+                })
+                .collect(),
+            active: true,
+            closed: false,
+            end_date_iso: String::new(),
+        };
+        let mut state = self.lock();
+        state.slugs.insert(slug.to_string(), condition_id.to_string());
+        state.markets.insert(condition_id.to_string(), details);
+    }
+
+    /// Replace the book of `token_id`; levels are (price, size), best first.
+    pub fn set_orderbook(&self, token_id: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) {
+        let levels = |levels: &[(f64, f64)]| {
+            levels
+                .iter()
+                .map(|&(price, size)| OrderBookEntry {
+                    price: Decimal::from_f64(price).unwrap_or_default(),
+                    size: Decimal::from_f64(size).unwrap_or_default(),
+                })
+                .collect()
+        };
+        let book = OrderBook {
+            bids: levels(bids),
+            asks: levels(asks),
+            tick_size: None,
+            min_order_size: None,
+        };
+        self.lock().books.insert(token_id.to_string(), book);
+    }
+
+    /// Close `condition_id` with `outcome` winning, as every resolution source will then report.
+    pub fn resolve(&self, condition_id: &str, outcome: &str) {
+        if let Some(market) = self.lock().markets.get_mut(condition_id) {
+            market.closed = true;
+            for token in &mut market.tokens {
+                token.winner = token.outcome.eq_ignore_ascii_case(outcome);
+            }
+        }
+    }
+
+    /// Report `index` (None = not reported yet) as the on-chain payout winner, regardless of `resolve`.
+    pub fn set_onchain_winner(&self, condition_id: &str, index: Option<usize>) {
+        self.lock().onchain_winners.insert(condition_id.to_string(), index);
+    }
+
+    /// Credit the wallet `size` of `token_id`, as if bought in an earlier session.
+    pub fn hold(&self, token_id: &str, size: f64) {
+        let mut state = self.lock();
+        let condition_id = state
+            .markets
+            .values()
+            .find(|m| m.tokens.iter().any(|t| t.token_id == token_id))
+            .map(|m| m.condition_id.clone());
+        state.fills.push(Fill {
+            token_id: Some(token_id.to_string()),
+            side: Side::Buy,
+            size,
+            price: 0.0,
+            timestamp: Utc::now().timestamp() as u64,
+            condition_id,
+            outcome: None,
+            transaction_hash: None,
+        });
+    }
+
+    /// Report `order` as resting, e.g. one left over from an earlier run.
+    pub fn add_open_order(&self, order: OpenOrder) {
+        self.lock().open_orders.push(order);
+    }
+
+    /// Answer orders with status "rejected" instead of filling them.
+    pub fn reject_orders(&self, reject: bool) {
+        self.lock().reject_orders = reject;
+    }
+
+    /// Accept the next `posts` order posts, then fail every later one with an error, as a CLOB that went away
+    /// between two legs would.
+    pub fn fail_orders_after(&self, posts: usize) {
+        let mut state = self.lock();
+        state.fail_orders_after = Some(state.orders.len() + posts);
+    }
+
+    /// Leave GTC and GTD orders resting (status "live") until `fill_resting` matches them, instead of filling them
+    /// at once.
+    pub fn rest_orders(&self, rest: bool) {
+        self.lock().rest_orders = rest;
+    }
+
+    /// Let FAK orders match only `size` shares, as against a thin book, cancelling the rest.
+    pub fn limit_fak_depth(&self, size: f64) {
+        self.lock().fak_depth = Some(size);
+    }
+
+    /// Match `size` more shares of resting order `order_id` at its limit price.
+    pub fn fill_resting(&self, order_id: &str, size: f64) {
+        let mut state = self.lock();
+        let Some(order) = state.orders.iter().find(|(id, _)| id == order_id).map(|(_, o)| o.clone()) else {
+            return;
+        };
+        *state.size_matched.entry(order_id.to_string()).or_default() += size;
+        let price = order.price.parse().unwrap_or(0.0);
+        state.usdc_balance -= size * price;
+        state.fills.push(Fill {
+            token_id: Some(order.token_id.clone()),
+            side: order.side,
+            size,
+            price,
+            timestamp: Utc::now().timestamp() as u64,
+            condition_id: None,
+            outcome: None,
+            transaction_hash: None,
+        });
+    }
+
+    /// Fail the next `count` posts with `AmbiguousPost`. With `taken` they still fill, as when the CLOB's answer
+    /// is what got lost; otherwise they never arrive.
+    pub fn fail_posts_ambiguously(&self, count: usize, taken: bool) {
+        self.lock().ambiguous_posts = (count, taken);
+    }
+
+    /// Let the next `count` `find_submitted_order` calls miss the order, as while the CLOB's listings lag.
+    pub fn lag_lookups(&self, count: usize) {
+        self.lock().lagging_lookups = count;
+    }
+
+    /// Orders placed so far, oldest first, rejected ones included.
+    pub fn orders(&self) -> Vec<OrderRequest> {
+        self.lock().orders.iter().map(|(_, order)| order.clone()).collect()
+    }
+
+    pub fn cancelled(&self) -> Vec<String> {
+        self.lock().cancelled.clone()
+    }
+
+    /// Fail the next redemption of `condition_id`, batched or not.
+    pub fn fail_next_redeem(&self, condition_id: &str) {
+        self.lock().failing_redeems.insert(condition_id.to_string());
+    }
+
+    /// Leave the next redemption of `condition_id` unconfirmed as `tx_hash`; it reports `Pending` until
+    /// `set_redemption_status` says otherwise.
+    pub fn leave_next_redeem_unconfirmed(&self, condition_id: &str, tx_hash: &str) {
+        let mut state = self.lock();
+        state.unconfirmed_redeems.insert(condition_id.to_string(), tx_hash.to_string());
+        state.redemption_statuses.insert(tx_hash.to_string(), RedemptionStatus::Pending);
+    }
+
+    pub fn set_redemption_status(&self, tx_hash: &str, status: RedemptionStatus) {
+        self.lock().redemption_statuses.insert(tx_hash.to_string(), status);
+    }
+
+    /// Report `usd` as the gas cost of every redemption batch (0 by default).
+    pub fn set_redemption_cost(&self, usd: f64) {
+        self.lock().redemption_cost_usd = usd;
+    }
+
+    /// (condition id, outcome) of every redemption sent so far, unconfirmed ones included.
+    pub fn redeemed(&self) -> Vec<(String, String)> {
+        self.lock().redeemed.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn market(&self, condition_id: &str) -> Result<MarketDetails> {
+        self.lock()
+            .markets
+            .get(condition_id)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("Market {} not found", condition_id))
+    }
+
+    fn winner(&self, condition_id: &str) -> Result<Option<(usize, MarketToken)>> {
+        let market = self.market(condition_id)?;
+        if !market.closed {
+            return Ok(None);
+        }
+        Ok(market.tokens.into_iter().enumerate().find(|(_, t)| t.winner))
+    }
+
+    fn redeem_one(&self, condition_id: &str, outcome: &str) -> Result<RedeemResponse> {
+        let mut state = self.lock();
+        if state.failing_redeems.remove(condition_id) {
+            anyhow::bail!("redeem of {} reverted", condition_id);
+        }
+        state.redeemed.push((condition_id.to_string(), outcome.to_string()));
+        if let Some(tx_hash) = state.unconfirmed_redeems.remove(condition_id) {
+            let message = format!("redeem of {} not final", condition_id);
+            return Err(UnconfirmedRedemption { tx_hash, message }.into());
+        }
+        Ok(RedeemResponse {
+            success: true,
+            message: None,
+            transaction_hash: Some(MOCK_TX_HASH.to_string()),
+            amount_redeemed: None,
+        })
+    }
+}
+
+impl PredictionMarketVenue for MockPolymarketApi {
+    fn get_orderbook<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<OrderBook>> {
+        let book = self.lock().books.get(token_id).cloned();
+        Box::pin(future::ready(
+            book.ok_or_else(|| anyhow::anyhow!("No orderbook for token {}", token_id)),
+        ))
+    }
+
+    fn find_market<'a>(&'a self, series: &'a str, close_unix: i64) -> BoxFuture<'a, Result<Option<VenueMarket>>> {
+        Box::pin(find_updown_market(self, series, close_unix))
+    }
+
+    fn place_order<'a>(&'a self, order: &'a OrderRequest) -> BoxFuture<'a, Result<OrderResponse>> {
+        let mut state = self.lock();
+        let ambiguous = state.ambiguous_posts.0 > 0;
+        if ambiguous {
+            state.ambiguous_posts.0 -= 1;
+            if !state.ambiguous_posts.1 {
+                return Box::pin(future::ready(Err(AmbiguousPost("mock post timed out".to_string()).into())));
+            }
+        }
+        if state.fail_orders_after.is_some_and(|accepted| state.orders.len() >= accepted) {
+            return Box::pin(future::ready(Err(anyhow::anyhow!("mock CLOB unavailable"))));
+        }
+        let order_id = format!("mock-{}", state.orders.len() + 1);
+        state.orders.push((order_id.clone(), order.clone()));
+        if state.reject_orders {
+            return Box::pin(future::ready(Ok(OrderResponse {
+                order_id: None,
+                status: "rejected".to_string(),
+                message: Some("rejected by mock".to_string()),
+                latency: None,
+            })));
+        }
+        if state.rest_orders && order.order_type.rests() {
+            state.size_matched.insert(order_id.clone(), 0.0);
+            return Box::pin(future::ready(Ok(OrderResponse {
+                order_id: Some(order_id),
+                status: "live".to_string(),
+                message: None,
+                latency: None,
+            })));
+        }
+        let condition_id = state
+            .markets
+            .values()
+            .find(|m| m.tokens.iter().any(|t| t.token_id == order.token_id))
+            .map(|m| m.condition_id.clone());
+        let (mut size, price): (f64, f64) = (order.size.parse().unwrap_or(0.0), order.price.parse().unwrap_or(0.0));
+        if let Some(depth) = state.fak_depth.filter(|_| order.order_type == OrderType::Fak) {
+            size = size.min(depth);
+            state.size_matched.insert(order_id.clone(), size);
+            state.cancelled.push(order_id.clone());
+        }
+        if order.side == Side::Buy {
+            state.usdc_balance -= size * price;
+        } else {
+            state.usdc_balance += size * price;
+        }
+        state.fills.push(Fill {
+            token_id: Some(order.token_id.clone()),
+            side: order.side,
+            size,
+            price,
+            timestamp: Utc::now().timestamp() as u64,
+            condition_id,
+            outcome: None,
+            transaction_hash: None,
+        });
+        if ambiguous {
+            return Box::pin(future::ready(Err(AmbiguousPost("mock post timed out".to_string()).into())));
+        }
+        Box::pin(future::ready(Ok(OrderResponse {
+            order_id: Some(order_id),
+            status: "matched".to_string(),
+            message: None,
+            latency: None,
+        })))
+    }
+
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<()>> {
+        let mut state = self.lock();
+        state.open_orders.retain(|o| o.id != order_id);
+        state.cancelled.push(order_id.to_string());
+        Box::pin(future::ready(Ok(())))
+    }
+
+    /// The latest order placed through this mock with the same token, side, price, and size.
+    fn find_submitted_order<'a>(
+        &'a self,
+        order: &'a OrderRequest,
+        _since_secs: i64,
+    ) -> BoxFuture<'a, Result<Option<OrderResponse>>> {
+        let mut state = self.lock();
+        if state.lagging_lookups > 0 {
+            state.lagging_lookups -= 1;
+            return Box::pin(future::ready(Ok(None)));
+        }
+        let found = state.orders.iter().rev().find_map(|(id, placed)| {
+            (placed.token_id == order.token_id
+                && placed.side == order.side
+                && placed.price == order.price
+                && placed.size == order.size)
+                .then(|| OrderResponse {
+                    order_id: Some(id.clone()),
+                    status: "matched".to_string(),
+                    message: None,
+                    latency: None,
+                })
+        });
+        Box::pin(future::ready(Ok(found)))
+    }
+
+    fn get_order_status<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderStatus>> {
+        let state = self.lock();
+        let status = state
+            .orders
+            .iter()
+            .find(|(id, _)| id == order_id)
+            .map(|(id, order)| match state.size_matched.get(id) {
+                Some(&matched) => {
+                    let full = matched >= order.size.parse().unwrap_or(0.0);
+                    let status = match (full, state.cancelled.contains(id)) {
+                        (true, _) => "matched",
+                        (false, true) => "canceled",
+                        (false, false) => "live",
+                    };
+                    OrderStatus {
+                        id: Some(id.clone()),
+                        status: Some(status.to_string()),
+                        original_size: Some(order.size.clone()),
+                        size_matched: Some(matched.to_string()),
+                    }
+                }
+                None => OrderStatus {
+                    id: Some(id.clone()),
+                    status: Some(if state.reject_orders { "canceled" } else { "matched" }.to_string()),
+                    original_size: Some(order.size.clone()),
+                    size_matched: Some(if state.reject_orders { "0" } else { order.size.as_str() }.to_string()),
+                },
+            })
+            .ok_or_else(|| anyhow::anyhow!("Order {} not found", order_id));
+        Box::pin(future::ready(status))
+    }
+
+    fn resolved_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>> {
+        let winner = self
+            .winner(condition_id)
+            .map(|w| w.map(|(_, t)| (t.token_id, t.outcome)));
+        Box::pin(future::ready(winner))
+    }
+
+    fn redeem<'a>(
+        &'a self,
+        condition_id: &'a str,
+        _token_id: &'a str,
+        outcome: &'a str,
+    ) -> BoxFuture<'a, Result<RedeemResponse>> {
+        Box::pin(future::ready(self.redeem_one(condition_id, outcome)))
+    }
+
+    fn redemption_status<'a>(&'a self, tx_hash: &'a str) -> BoxFuture<'a, Result<RedemptionStatus>> {
+        let status = self.lock().redemption_statuses.get(tx_hash).copied();
+        Box::pin(future::ready(Ok(status.unwrap_or(RedemptionStatus::Failed))))
+    }
+}
+
+impl PolymarketClient for MockPolymarketApi {
+    fn sync_clock(&self) -> BoxFuture<'_, Result<i64>> {
+        Box::pin(future::ready(Ok(0)))
+    }
+
+    fn check_credentials(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn wallet_address(&self) -> Option<String> {
+        self.lock().wallet.clone()
+    }
+
+    fn get_market_by_slug<'a>(&'a self, slug: &'a str) -> BoxFuture<'a, Result<Market>> {
+        let condition_id = self.lock().slugs.get(slug).cloned();
+        let market = match condition_id {
+            Some(condition_id) => self.market(&condition_id).map(|m| Market {
+                condition_id: m.condition_id,
+                market_id: None,
+                question: m.question,
+                slug: slug.to_string(),
+                end_date_iso: None,
+                active: m.active,
+                closed: m.closed,
+            }),
+            None => Err(anyhow::anyhow!("Market {} not found", slug)),
+        };
+        Box::pin(future::ready(market))
+    }
+
+    fn get_market<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<MarketDetails>> {
+        Box::pin(future::ready(self.market(condition_id)))
+    }
+
+    fn get_gamma_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>> {
+        self.resolved_winner(condition_id)
+    }
+
+    /// Gamma listings are not mocked; symbol discovery finds nothing.
+    fn get_gamma_markets_ending_between<'a>(&'a self, _end_min: &'a str, _end_max: &'a str) -> BoxFuture<'a, Result<Vec<Value>>> {
+        Box::pin(future::ready(Ok(Vec::new())))
+    }
+
+    fn get_onchain_winner_index<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<usize>>> {
+        let overridden = self.lock().onchain_winners.get(condition_id).copied();
+        let index = match overridden {
+            Some(index) => Ok(index),
+            None => self.winner(condition_id).map(|w| w.map(|(i, _)| i)),
+        };
+        Box::pin(future::ready(index))
+    }
+
+    fn get_best_price<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<Option<TokenPrice>>> {
+        let price = self.lock().books.get(token_id).map(|book| TokenPrice {
+            token_id: token_id.to_string(),
+            bid: book.bids.first().map(|l| l.price),
+            ask: book.asks.first().map(|l| l.price),
+        });
+        Box::pin(future::ready(Ok(price)))
+    }
+
+    /// The best level of each book: bids for "BUY", asks for "SELL", as the CLOB quotes them.
+    fn get_prices<'a>(&'a self, token_ids: &'a [&'a str], side: &'a str) -> BoxFuture<'a, Result<HashMap<String, Decimal>>> {
+        let state = self.lock();
+        let prices = token_ids
+            .iter()
+            .filter_map(|id| {
+                let book = state.books.get(*id)?;
+                let levels = if side.eq_ignore_ascii_case("BUY") { &book.bids } else { &book.asks };
+                Some((id.to_string(), levels.first()?.price))
+            })
+            .collect();
+        Box::pin(future::ready(Ok(prices)))
+    }
+
+    fn order_rules<'a>(&'a self, _token_id: &'a str) -> BoxFuture<'a, Result<OrderRules>> {
+        Box::pin(future::ready(Ok(OrderRules::default())))
+    }
+
+    fn prepare_orders<'a>(&'a self, _token_ids: &'a [&'a str]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn get_fills<'a>(&'a self, _wallet: &'a str, condition_ids: &'a [&'a str]) -> BoxFuture<'a, Result<Vec<Fill>>> {
+        let fills = self
+            .lock()
+            .fills
+            .iter()
+            .filter(|f| f.condition_id.as_deref().is_some_and(|c| condition_ids.contains(&c)))
+            .cloned()
+            .collect();
+        Box::pin(future::ready(Ok(fills)))
+    }
+
+    /// Fills of orders placed through this mock, newest first.
+    fn get_trade_history<'a>(&'a self, params: &'a TradeHistoryParams) -> BoxFuture<'a, Result<Vec<Fill>>> {
+        let mut fills: Vec<Fill> = self
+            .lock()
+            .fills
+            .iter()
+            .filter(|f| {
+                params.markets.is_empty()
+                    || f.condition_id.as_ref().is_some_and(|c| params.markets.contains(c))
+            })
+            .cloned()
+            .collect();
+        fills.reverse();
+        if params.limit > 0 {
+            fills.truncate(params.limit);
+        }
+        Box::pin(future::ready(Ok(fills)))
+    }
+
+    fn get_open_orders<'a>(&'a self, market: Option<&'a str>) -> BoxFuture<'a, Result<Vec<OpenOrder>>> {
+        let orders = self
+            .lock()
+            .open_orders
+            .iter()
+            .filter(|o| market.is_none_or(|m| o.market == m))
+            .cloned()
+            .collect();
+        Box::pin(future::ready(Ok(orders)))
+    }
+
+    /// The mock's USDC or net bought shares, with unlimited allowance.
+    fn get_balance_allowance<'a>(
+        &'a self,
+        asset: BalanceAsset,
+        token_id: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<BalanceAllowance>> {
+        let state = self.lock();
+        let balance = match asset {
+            BalanceAsset::Collateral => state.usdc_balance,
+            BalanceAsset::Conditional => state
+                .fills
+                .iter()
+                .filter(|f| f.token_id.as_deref() == token_id)
+                .map(|f| if f.side == Side::Buy { f.size } else { -f.size })
+                .sum(),
+        };
+        Box::pin(future::ready(Ok(BalanceAllowance { balance, allowance: f64::MAX })))
+    }
+
+    fn get_activity_trades<'a>(&'a self, _wallet: &'a str, start: u64) -> BoxFuture<'a, Result<Vec<Fill>>> {
+        let fills = self.lock().fills.iter().filter(|f| f.timestamp >= start).cloned().collect();
+        Box::pin(future::ready(Ok(fills)))
+    }
+
+    /// Net shares bought through this mock.
+    fn get_ctf_balance<'a>(&'a self, _owner: &'a str, token_id: &'a str) -> BoxFuture<'a, Result<f64>> {
+        let balance = self
+            .lock()
+            .fills
+            .iter()
+            .filter(|f| f.token_id.as_deref() == Some(token_id))
+            .map(|f| if f.side == Side::Buy { f.size } else { -f.size })
+            .sum();
+        Box::pin(future::ready(Ok(balance)))
+    }
+
+    fn get_usdc_balance<'a>(&'a self, _owner: &'a str) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(future::ready(Ok(self.lock().usdc_balance)))
+    }
+
+    fn recent_usdc_inbound<'a>(&'a self, _wallet: &'a str, lookback_blocks: u64) -> BoxFuture<'a, Result<Option<(u64, f64)>>> {
+        let state = self.lock();
+        let from_block = state.head_block.saturating_sub(lookback_blocks);
+        let latest = state.deposits.iter().rev().find(|(block, _)| *block >= from_block).copied();
+        Box::pin(future::ready(Ok(latest)))
+    }
+
+    /// Winning tokens of resolved markets the wallet still holds and hasn't redeemed yet.
+    fn get_redeemable_targets<'a>(&'a self, _wallet: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        let state = self.lock();
+        let mut targets: Vec<(String, String)> = state
+            .markets
+            .values()
+            .filter(|m| m.closed)
+            .filter_map(|m| m.tokens.iter().find(|t| t.winner).map(|t| (m, t)))
+            .filter(|(_, t)| {
+                let held: f64 = state
+                    .fills
+                    .iter()
+                    .filter(|f| f.token_id.as_deref() == Some(t.token_id.as_str()))
+                    .map(|f| if f.side == Side::Buy { f.size } else { -f.size })
+                    .sum();
+                held > 0.0
+            })
+            .map(|(m, t)| (m.condition_id.clone(), t.outcome.clone()))
+            .filter(|target| !state.redeemed.contains(target))
+            .collect();
+        targets.sort();
+        Box::pin(future::ready(Ok(targets)))
+    }
+
+    /// Redeems each target on its own, like an EOA.
+    fn redeem_tokens_batch<'a>(
+        &'a self,
+        targets: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<Vec<Result<RedeemResponse>>>> {
+        let results = targets.iter().map(|(cid, outcome)| self.redeem_one(cid, outcome)).collect();
+        Box::pin(future::ready(Ok(results)))
+    }
+
+    fn estimate_redemption_cost_usd<'a>(&'a self, _targets: &'a [(String, String)]) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(future::ready(Ok(self.lock().redemption_cost_usd)))
+    }
+
+    fn merge_positions<'a>(&'a self, _condition_id: &'a str, _shares: f64) -> BoxFuture<'a, Result<String>> {
+        Box::pin(future::ready(Ok(MOCK_TX_HASH.to_string())))
+    }
+}
@@ -1,9 +1,14 @@
 pub mod auth;
 pub mod client;
 pub mod markets;
+pub mod mock;
 pub mod orders;
+pub mod proxy_wallet;
 pub mod redeem;
+pub mod venue;
 pub mod ws_market;
+pub mod ws_recorder;
 pub mod ws_rtds;
 
 pub use client::PolymarketApi;
+pub use venue::PolymarketClient;
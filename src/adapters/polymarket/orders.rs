@@ -1,5 +1,8 @@
 use crate::adapters::polymarket::PolymarketApi;
-use crate::models::{OrderRequest, OrderResponse, OrderStatus};
+use crate::models::{
+    BalanceAllowance, BalanceAsset, CancelOutcome, Fill, OpenOrder, OrderRequest, OrderResponse, OrderStatus, OrderType,
+    Side, TradeHistoryParams,
+};
 use anyhow::Result;
 
 pub async fn place_order(api: &PolymarketApi, order: &OrderRequest) -> Result<OrderResponse> {
@@ -10,8 +13,8 @@ pub async fn place_market_order(
     api: &PolymarketApi,
     token_id: &str,
     amount: f64,
-    side: &str,
-    order_type: Option<&str>,
+    side: Side,
+    order_type: Option<OrderType>,
 ) -> Result<OrderResponse> {
     api.place_market_order(token_id, amount, side, order_type).await
 }
@@ -20,6 +23,34 @@ pub async fn cancel_order(api: &PolymarketApi, order_id: &str) -> Result<()> {
     api.cancel_order(order_id).await
 }
 
+pub async fn cancel_orders(api: &PolymarketApi, order_ids: &[&str]) -> Result<CancelOutcome> {
+    api.cancel_orders(order_ids).await
+}
+
+pub async fn cancel_all_for_market(api: &PolymarketApi, condition_id: &str) -> Result<CancelOutcome> {
+    api.cancel_market_orders(condition_id).await
+}
+
 pub async fn get_order_status(api: &PolymarketApi, order_id: &str) -> Result<OrderStatus> {
     api.get_order_status(order_id).await
 }
+
+pub async fn get_trade_history(api: &PolymarketApi, params: &TradeHistoryParams) -> Result<Vec<Fill>> {
+    api.get_trade_history(params).await
+}
+
+pub async fn get_open_orders(api: &PolymarketApi, market: Option<&str>) -> Result<Vec<OpenOrder>> {
+    api.get_open_orders(market).await
+}
+
+pub async fn get_fills(api: &PolymarketApi, wallet: &str, condition_ids: &[&str]) -> Result<Vec<Fill>> {
+    api.get_fills(wallet, condition_ids).await
+}
+
+pub async fn get_balance_allowance(
+    api: &PolymarketApi,
+    asset: BalanceAsset,
+    token_id: Option<&str>,
+) -> Result<BalanceAllowance> {
+    api.get_balance_allowance(asset, token_id).await
+}
@@ -0,0 +1,156 @@
+//! Expected Polymarket proxy / Safe address for an EOA (CREATE2 derivation used by Polymarket's factories).
+
+use crate::config::ContractAddresses;
+use alloy::primitives::{b256, keccak256, Address, B256};
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use std::str::FromStr;
+
+/// Init code hash of the proxies `contracts.proxy_wallet_factory` deploys (MagicLink / email users,
+/// signature_type 1).
+const PROXY_INIT_CODE_HASH: B256 = b256!("d21df8dc65880a8606f09fe0ce3df9b8869287ab0b058be05aa9e8af6330a00b");
+/// Init code hash of the Safes `contracts.safe_factory` deploys (browser wallet users, signature_type 2).
+const SAFE_INIT_CODE_HASH: B256 = b256!("2bce2127ff07fb632d16c8347c4ebf501f4841168bed00d9e6ef715ddb6fcecf");
+
+/// Proxy wallet of `factory`: salt = keccak256(abi.encodePacked(eoa)).
+pub fn derive_proxy_wallet(factory: Address, eoa: Address) -> Address {
+    factory.create2(keccak256(eoa.as_slice()), PROXY_INIT_CODE_HASH)
+}
+
+/// Gnosis Safe of `factory`: salt = keccak256(abi.encode(eoa)) (address left-padded to 32 bytes).
+pub fn derive_safe_wallet(factory: Address, eoa: Address) -> Address {
+    let mut encoded = [0u8; 32];
+    encoded[12..].copy_from_slice(eoa.as_slice());
+    factory.create2(keccak256(encoded), SAFE_INIT_CODE_HASH)
+}
+
+/// Expected funder address for the given signature type: None for EOA, and on a network without the
+/// wallet type's factory.
+pub fn expected_funder(
+    eoa: Address,
+    contracts: &ContractAddresses,
+    signature_type: Option<u8>,
+) -> Result<Option<Address>> {
+    let (factory, derive): (_, fn(Address, Address) -> Address) = match signature_type {
+        Some(1) => (&contracts.proxy_wallet_factory, derive_proxy_wallet),
+        Some(2) => (&contracts.safe_factory, derive_safe_wallet),
+        _ => return Ok(None),
+    };
+    let Some(factory) = factory.as_deref() else {
+        return Ok(None);
+    };
+    let factory = Address::from_str(factory).context(format!("Failed to parse factory address {}", factory))?;
+    Ok(Some(derive(factory, eoa)))
+}
+
+/// Fail fast when `proxy_wallet_address` does not match the address derived from the private key.
+/// Surfaces the common "wrong proxy / wrong signature_type" misconfiguration before any order is rejected.
+pub fn verify_proxy_wallet(
+    private_key: &str,
+    contracts: &ContractAddresses,
+    proxy_wallet_address: Option<&str>,
+    signature_type: Option<u8>,
+) -> Result<()> {
+    let signer = PrivateKeySigner::from_str(private_key)
+        .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?;
+    verify_funder(signer.address(), contracts, proxy_wallet_address, signature_type)
+}
+
+/// `verify_proxy_wallet` for the EOA of any signer, e.g. a remote one.
+pub fn verify_funder(
+    eoa: Address,
+    contracts: &ContractAddresses,
+    proxy_wallet_address: Option<&str>,
+    signature_type: Option<u8>,
+) -> Result<()> {
+    let Some(configured) = proxy_wallet_address else {
+        return Ok(());
+    };
+    let configured_addr = Address::from_str(configured.trim())
+        .context(format!("Failed to parse proxy_wallet_address: {}", configured))?;
+    // Proxy wallet set with EOA/missing signature type is treated as Proxy elsewhere (see place_order).
+    let sig_type = match signature_type {
+        Some(0) | None => Some(1),
+        other => other,
+    };
+    let Some(expected) = expected_funder(eoa, contracts, sig_type)? else {
+        return Ok(());
+    };
+    if expected == configured_addr {
+        return Ok(());
+    }
+    let proxy = expected_funder(eoa, contracts, Some(1))?;
+    let safe = expected_funder(eoa, contracts, Some(2))?;
+    let hint = if proxy == Some(configured_addr) {
+        " It matches the Proxy derivation: set signature_type to 1."
+    } else if safe == Some(configured_addr) {
+        " It matches the Gnosis Safe derivation: set signature_type to 2."
+    } else {
+        " It matches neither derivation: check that the signing key belongs to this Polymarket account."
+    };
+    anyhow::bail!(
+        "proxy_wallet_address {} does not match the address derived from the signing key (EOA {}): expected {} for signature_type {}.{}",
+        configured_addr,
+        eoa,
+        expected,
+        sig_type.unwrap_or(0),
+        hint
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Network;
+
+    const EOA: &str = "0x1111111111111111111111111111111111111111";
+
+    #[test]
+    fn proxy_and_safe_derivations_differ() {
+        let eoa = Address::from_str(EOA).unwrap();
+        let contracts = Network::Polygon.default_contracts();
+        let proxy = expected_funder(eoa, &contracts, Some(1)).unwrap();
+        let safe = expected_funder(eoa, &contracts, Some(2)).unwrap();
+        assert!(proxy.is_some() && safe.is_some());
+        assert_ne!(proxy, safe);
+        assert_eq!(expected_funder(eoa, &contracts, Some(0)).unwrap(), None);
+    }
+
+    #[test]
+    fn proxy_wallets_follow_the_configured_factory() {
+        let eoa = Address::from_str(EOA).unwrap();
+        let mut contracts = Network::Polygon.default_contracts();
+        let mainnet = expected_funder(eoa, &contracts, Some(1)).unwrap().unwrap();
+        contracts.proxy_wallet_factory = Some(Address::repeat_byte(0x22).to_string());
+        let other = expected_funder(eoa, &contracts, Some(1)).unwrap().unwrap();
+        assert_ne!(mainnet, other);
+        assert_eq!(other, derive_proxy_wallet(Address::repeat_byte(0x22), eoa));
+        // No factory on the network (Amoy): nothing to check a proxy against.
+        assert_eq!(expected_funder(eoa, &Network::Amoy.default_contracts(), Some(1)).unwrap(), None);
+        contracts.proxy_wallet_factory = Some("0xnope".to_string());
+        assert!(expected_funder(eoa, &contracts, Some(1)).is_err());
+    }
+
+    #[test]
+    fn safe_wallets_follow_the_configured_factory() {
+        let eoa = Address::from_str(EOA).unwrap();
+        let mut contracts = Network::Polygon.default_contracts();
+        let mainnet = expected_funder(eoa, &contracts, Some(2)).unwrap().unwrap();
+        contracts.safe_factory = Some(Address::repeat_byte(0x33).to_string());
+        let other = expected_funder(eoa, &contracts, Some(2)).unwrap().unwrap();
+        assert_ne!(mainnet, other);
+        assert_eq!(other, derive_safe_wallet(Address::repeat_byte(0x33), eoa));
+        assert_eq!(expected_funder(eoa, &Network::Amoy.default_contracts(), Some(2)).unwrap(), None);
+    }
+
+    #[test]
+    fn detects_signature_type_mismatch() {
+        let key = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+        let contracts = Network::Polygon.default_contracts();
+        let eoa = PrivateKeySigner::from_str(key).unwrap().address();
+        let safe = expected_funder(eoa, &contracts, Some(2)).unwrap().unwrap().to_string();
+        assert!(verify_proxy_wallet(key, &contracts, Some(&safe), Some(2)).is_ok());
+        let err = verify_proxy_wallet(key, &contracts, Some(&safe), Some(1)).unwrap_err();
+        assert!(err.to_string().contains("signature_type to 2"));
+    }
+}
@@ -0,0 +1,283 @@
+use crate::adapters::polymarket::PolymarketApi;
+use crate::adapters::venue::{PredictionMarketVenue, RedemptionStatus, VenueMarket};
+use crate::domain::ticks::OrderRules;
+use crate::models::{
+    BalanceAllowance, BalanceAsset, CancelOutcome, Fill, Market, MarketDetails, OpenOrder, OrderBook, OrderRequest,
+    OrderResponse, OrderStatus, Outcome, RedeemResponse, TokenPrice, TradeHistoryParams,
+};
+use crate::utils::slug_builder::{build_updown_slug, parse_price_to_beat_from_question, parse_updown_slug};
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// The Polymarket calls the trading services make, so they run against `PolymarketApi` or an in-memory
+/// `MockPolymarketApi` alike. Methods mirror the `PolymarketApi` ones of the same name.
+pub trait PolymarketClient: PredictionMarketVenue {
+    fn sync_clock(&self) -> BoxFuture<'_, Result<i64>>;
+
+    fn check_credentials(&self) -> BoxFuture<'_, Result<()>>;
+
+    fn wallet_address(&self) -> Option<String>;
+
+    fn get_market_by_slug<'a>(&'a self, slug: &'a str) -> BoxFuture<'a, Result<Market>>;
+
+    fn get_market<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<MarketDetails>>;
+
+    fn get_gamma_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>>;
+
+    fn get_gamma_markets_ending_between<'a>(&'a self, end_min: &'a str, end_max: &'a str) -> BoxFuture<'a, Result<Vec<Value>>>;
+
+    fn get_onchain_winner_index<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<usize>>>;
+
+    fn get_best_price<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<Option<TokenPrice>>>;
+
+    fn get_prices<'a>(&'a self, token_ids: &'a [&'a str], side: &'a str) -> BoxFuture<'a, Result<HashMap<String, Decimal>>>;
+
+    fn order_rules<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<OrderRules>>;
+
+    fn prepare_orders<'a>(&'a self, token_ids: &'a [&'a str]) -> BoxFuture<'a, Result<()>>;
+
+    fn get_fills<'a>(&'a self, wallet: &'a str, condition_ids: &'a [&'a str]) -> BoxFuture<'a, Result<Vec<Fill>>>;
+
+    fn get_trade_history<'a>(&'a self, params: &'a TradeHistoryParams) -> BoxFuture<'a, Result<Vec<Fill>>>;
+
+    fn get_open_orders<'a>(&'a self, market: Option<&'a str>) -> BoxFuture<'a, Result<Vec<OpenOrder>>>;
+
+    fn get_balance_allowance<'a>(
+        &'a self,
+        asset: BalanceAsset,
+        token_id: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<BalanceAllowance>>;
+
+    fn get_activity_trades<'a>(&'a self, wallet: &'a str, start: u64) -> BoxFuture<'a, Result<Vec<Fill>>>;
+
+    fn get_ctf_balance<'a>(&'a self, owner: &'a str, token_id: &'a str) -> BoxFuture<'a, Result<f64>>;
+
+    fn get_usdc_balance<'a>(&'a self, owner: &'a str) -> BoxFuture<'a, Result<f64>>;
+
+    fn recent_usdc_inbound<'a>(&'a self, wallet: &'a str, lookback_blocks: u64) -> BoxFuture<'a, Result<Option<(u64, f64)>>>;
+
+    /// (condition_id, outcome) of every resolved position `wallet` can still redeem.
+    fn get_redeemable_targets<'a>(&'a self, wallet: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>>;
+
+    /// Each target's result, in order; Err when the whole batch failed.
+    fn redeem_tokens_batch<'a>(
+        &'a self,
+        targets: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<Vec<Result<RedeemResponse>>>>;
+
+    fn estimate_redemption_cost_usd<'a>(&'a self, targets: &'a [(String, String)]) -> BoxFuture<'a, Result<f64>>;
+
+    /// Merge `shares` Up + Down sets of `condition_id` back into USDC; returns the transaction hash.
+    fn merge_positions<'a>(&'a self, condition_id: &'a str, shares: f64) -> BoxFuture<'a, Result<String>>;
+}
+
+/// `find_market` of a Polymarket client: `series` is an up/down series from `updown_series`, and the market is
+/// looked up by the slug of the period ending at `close_unix`.
+pub(crate) async fn find_updown_market<C: PolymarketClient + ?Sized>(
+    client: &C,
+    series: &str,
+    close_unix: i64,
+) -> Result<Option<VenueMarket>> {
+    let Some((symbol, period_mins, _)) = parse_updown_slug(&format!("{}-0", series)) else {
+        anyhow::bail!("{:?} is not an up/down series such as \"btc-updown-15m\"", series);
+    };
+    let slug = build_updown_slug(&symbol, period_mins, close_unix - period_mins * 60);
+    let market = match client.get_market_by_slug(&slug).await {
+        Ok(market) if market.active && !market.closed => market,
+        _ => return Ok(None),
+    };
+    let details = client.get_market(&market.condition_id).await?;
+    let token = |outcome: Outcome| {
+        details
+            .tokens
+            .iter()
+            .find(|t| Outcome::from_token_label(&t.outcome) == Some(outcome))
+            .map(|t| t.token_id.clone())
+            .ok_or_else(|| anyhow::anyhow!("{} has no {:?} token", slug, outcome))
+    };
+    Ok(Some(VenueMarket {
+        yes_token: token(Outcome::Up)?,
+        no_token: token(Outcome::Down)?,
+        strike: parse_price_to_beat_from_question(&market.question),
+        id: market.condition_id,
+        close_unix,
+    }))
+}
+
+impl PredictionMarketVenue for PolymarketApi {
+    fn get_orderbook<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<OrderBook>> {
+        Box::pin(PolymarketApi::get_orderbook(self, token_id))
+    }
+
+    fn find_market<'a>(&'a self, series: &'a str, close_unix: i64) -> BoxFuture<'a, Result<Option<VenueMarket>>> {
+        Box::pin(find_updown_market(self, series, close_unix))
+    }
+
+    fn server_now_unix(&self) -> i64 {
+        PolymarketApi::server_now_unix(self)
+    }
+
+    fn get_orderbooks<'a>(&'a self, token_ids: &'a [&'a str]) -> BoxFuture<'a, Result<HashMap<String, OrderBook>>> {
+        Box::pin(PolymarketApi::get_orderbooks(self, token_ids))
+    }
+
+    fn place_order<'a>(&'a self, order: &'a OrderRequest) -> BoxFuture<'a, Result<OrderResponse>> {
+        Box::pin(PolymarketApi::place_order(self, order))
+    }
+
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<()>> {
+        Box::pin(PolymarketApi::cancel_order(self, order_id))
+    }
+
+    fn cancel_orders<'a>(&'a self, order_ids: &'a [&'a str]) -> BoxFuture<'a, Result<CancelOutcome>> {
+        Box::pin(PolymarketApi::cancel_orders(self, order_ids))
+    }
+
+    fn get_order_status<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderStatus>> {
+        Box::pin(PolymarketApi::get_order_status(self, order_id))
+    }
+
+    fn find_submitted_order<'a>(
+        &'a self,
+        order: &'a OrderRequest,
+        since_secs: i64,
+    ) -> BoxFuture<'a, Result<Option<OrderResponse>>> {
+        Box::pin(PolymarketApi::find_submitted_order(self, order, since_secs))
+    }
+
+    /// From the CLOB market: a closed market flags its winning token.
+    fn resolved_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>> {
+        Box::pin(async move {
+            let market = self.get_market(condition_id).await?;
+            if !market.closed {
+                return Ok(None);
+            }
+            Ok(market
+                .tokens
+                .iter()
+                .find(|t| t.winner)
+                .map(|t| (t.token_id.clone(), t.outcome.clone())))
+        })
+    }
+
+    fn redeem<'a>(
+        &'a self,
+        condition_id: &'a str,
+        token_id: &'a str,
+        outcome: &'a str,
+    ) -> BoxFuture<'a, Result<RedeemResponse>> {
+        Box::pin(self.redeem_tokens(condition_id, token_id, outcome))
+    }
+
+    fn redemption_status<'a>(&'a self, tx_hash: &'a str) -> BoxFuture<'a, Result<RedemptionStatus>> {
+        Box::pin(PolymarketApi::redemption_status(self, tx_hash))
+    }
+}
+
+impl PolymarketClient for PolymarketApi {
+    fn sync_clock(&self) -> BoxFuture<'_, Result<i64>> {
+        Box::pin(PolymarketApi::sync_clock(self))
+    }
+
+    fn check_credentials(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(PolymarketApi::check_credentials(self))
+    }
+
+    fn wallet_address(&self) -> Option<String> {
+        PolymarketApi::wallet_address(self)
+    }
+
+    fn get_market_by_slug<'a>(&'a self, slug: &'a str) -> BoxFuture<'a, Result<Market>> {
+        Box::pin(PolymarketApi::get_market_by_slug(self, slug))
+    }
+
+    fn get_market<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<MarketDetails>> {
+        Box::pin(PolymarketApi::get_market(self, condition_id))
+    }
+
+    fn get_gamma_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>> {
+        Box::pin(PolymarketApi::get_gamma_winner(self, condition_id))
+    }
+
+    fn get_gamma_markets_ending_between<'a>(&'a self, end_min: &'a str, end_max: &'a str) -> BoxFuture<'a, Result<Vec<Value>>> {
+        Box::pin(PolymarketApi::get_gamma_markets_ending_between(self, end_min, end_max))
+    }
+
+    fn get_onchain_winner_index<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<usize>>> {
+        Box::pin(PolymarketApi::get_onchain_winner_index(self, condition_id))
+    }
+
+    fn get_best_price<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<Option<TokenPrice>>> {
+        Box::pin(PolymarketApi::get_best_price(self, token_id))
+    }
+
+    fn get_prices<'a>(&'a self, token_ids: &'a [&'a str], side: &'a str) -> BoxFuture<'a, Result<HashMap<String, Decimal>>> {
+        Box::pin(PolymarketApi::get_prices(self, token_ids, side))
+    }
+
+    fn order_rules<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<OrderRules>> {
+        Box::pin(PolymarketApi::order_rules(self, token_id))
+    }
+
+    fn prepare_orders<'a>(&'a self, token_ids: &'a [&'a str]) -> BoxFuture<'a, Result<()>> {
+        Box::pin(PolymarketApi::prepare_orders(self, token_ids))
+    }
+
+    fn get_fills<'a>(&'a self, wallet: &'a str, condition_ids: &'a [&'a str]) -> BoxFuture<'a, Result<Vec<Fill>>> {
+        Box::pin(PolymarketApi::get_fills(self, wallet, condition_ids))
+    }
+
+    fn get_trade_history<'a>(&'a self, params: &'a TradeHistoryParams) -> BoxFuture<'a, Result<Vec<Fill>>> {
+        Box::pin(PolymarketApi::get_trade_history(self, params))
+    }
+
+    fn get_open_orders<'a>(&'a self, market: Option<&'a str>) -> BoxFuture<'a, Result<Vec<OpenOrder>>> {
+        Box::pin(PolymarketApi::get_open_orders(self, market))
+    }
+
+    fn get_balance_allowance<'a>(
+        &'a self,
+        asset: BalanceAsset,
+        token_id: Option<&'a str>,
+    ) -> BoxFuture<'a, Result<BalanceAllowance>> {
+        Box::pin(PolymarketApi::get_balance_allowance(self, asset, token_id))
+    }
+
+    fn get_activity_trades<'a>(&'a self, wallet: &'a str, start: u64) -> BoxFuture<'a, Result<Vec<Fill>>> {
+        Box::pin(PolymarketApi::get_activity_trades(self, wallet, start))
+    }
+
+    fn get_ctf_balance<'a>(&'a self, owner: &'a str, token_id: &'a str) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(PolymarketApi::get_ctf_balance(self, owner, token_id))
+    }
+
+    fn get_usdc_balance<'a>(&'a self, owner: &'a str) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(PolymarketApi::get_usdc_balance(self, owner))
+    }
+
+    fn recent_usdc_inbound<'a>(&'a self, wallet: &'a str, lookback_blocks: u64) -> BoxFuture<'a, Result<Option<(u64, f64)>>> {
+        Box::pin(PolymarketApi::recent_usdc_inbound(self, wallet, lookback_blocks))
+    }
+
+    fn get_redeemable_targets<'a>(&'a self, wallet: &'a str) -> BoxFuture<'a, Result<Vec<(String, String)>>> {
+        Box::pin(PolymarketApi::get_redeemable_targets(self, wallet))
+    }
+
+    fn redeem_tokens_batch<'a>(
+        &'a self,
+        targets: &'a [(String, String)],
+    ) -> BoxFuture<'a, Result<Vec<Result<RedeemResponse>>>> {
+        Box::pin(PolymarketApi::redeem_tokens_batch(self, targets))
+    }
+
+    fn estimate_redemption_cost_usd<'a>(&'a self, targets: &'a [(String, String)]) -> BoxFuture<'a, Result<f64>> {
+        Box::pin(PolymarketApi::estimate_redemption_cost_usd(self, targets))
+    }
+
+    fn merge_positions<'a>(&'a self, condition_id: &'a str, shares: f64) -> BoxFuture<'a, Result<String>> {
+        Box::pin(PolymarketApi::merge_positions(self, condition_id, shares))
+    }
+}
@@ -1,20 +1,31 @@
 //! CLOB Market WebSocket: subscribe to asset_ids and stream best bid/ask updates.
+//!
+//! Messages carry the CLOB's `timestamp` (ms) and the book `hash` after the change. Per asset, a message older
+//! than the last one applied is dropped, and a repeated hash with different best prices (the same book can't
+//! have two bests) means the local state went wrong. Either marks the asset `out_of_sequence` until a REST
+//! snapshot replaces it (`reconcile_with_snapshot`), and counts toward `sequence_gap_count`.
 
 use anyhow::{Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
 use futures_util::{SinkExt, StreamExt};
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use serde::Deserialize;
-use std::collections::HashMap;
+use super::ws_recorder;
+use crate::events::{ArbEvent, EventBus};
+use crate::models::OrderBook;
+use crate::utils::proxy;
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::Message;
 
 const WS_MARKET_PATH: &str = "ws/market";
 
 #[derive(Debug, Deserialize)]
 struct WsBookLevel {
     price: String,
-    #[allow(dead_code)]
     size: String,
 }
 
@@ -29,6 +40,8 @@ struct WsBookMessage {
     buys: Vec<WsBookLevel>,
     #[serde(default, alias = "asks")]
     sells: Vec<WsBookLevel>,
+    #[serde(default)]
+    hash: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -39,6 +52,16 @@ struct WsPriceChangeItem {
     best_bid: Option<String>,
     #[serde(rename = "best_ask")]
     best_ask: Option<String>,
+    /// The level that changed: its price, new size, and side.
+    #[serde(default)]
+    price: Option<String>,
+    #[serde(default)]
+    size: Option<String>,
+    #[serde(default)]
+    side: Option<String>,
+    /// Book hash after the change.
+    #[serde(default)]
+    hash: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,13 +73,25 @@ struct WsPriceChangeMessage {
     price_changes: Vec<WsPriceChangeItem>,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct BestPrices {
     pub bid: Option<f64>,
     pub ask: Option<f64>,
+    /// Shares displayed at the best ask; None until a book or a change at that level reports it.
+    pub ask_size: Option<f64>,
+    /// Local receive time (unix ms) of the message that last moved this quote.
+    pub received_at_ms: Option<i64>,
+    /// CLOB timestamp (unix ms) of the last message applied; older ones are out of order.
+    pub server_ts_ms: Option<i64>,
+    /// Book hash reported by the last message applied.
+    pub hash: Option<String>,
+    /// A timestamp regression or hash mismatch was seen: the quote needs a REST snapshot before it is used.
+    pub out_of_sequence: bool,
 }
 
-pub type PricesSnapshot = Arc<RwLock<HashMap<String, BestPrices>>>;
+/// Best prices per token. Sharded, so the WS writer and the overlap loops reading it only contend on the
+/// shard of the token being touched, and neither side ever waits on an async lock.
+pub type PricesSnapshot = Arc<DashMap<String, BestPrices>>;
 
 fn parse_f64(s: &str) -> Option<f64> {
     s.trim().parse().ok()
@@ -72,11 +107,96 @@ fn is_placeholder_quote(bid: Option<f64>, ask: Option<f64>) -> bool {
 }
 
 const WS_RECONNECT_DELAY_SECS: u64 = 3;
+/// Quotes closer than this are the same price (tick is 0.01 or 0.001).
+const QUOTE_EPSILON: f64 = 1e-9;
+
+/// Process-wide REST cross-check counters: books compared and books the WS state disagreed with.
+static BOOK_CHECKS: AtomicU64 = AtomicU64::new(0);
+static BOOK_MISMATCHES: AtomicU64 = AtomicU64::new(0);
+/// Process-wide count of out-of-sequence market WS messages (timestamp regressions and hash mismatches).
+static SEQUENCE_GAPS: AtomicU64 = AtomicU64::new(0);
+
+/// Out-of-sequence market WS messages since startup.
+pub fn sequence_gap_count() -> u64 {
+    SEQUENCE_GAPS.load(Ordering::Relaxed)
+}
+
+/// Assets among `asset_ids` marked `out_of_sequence`, to refresh from REST.
+pub fn out_of_sequence_assets(prices: &PricesSnapshot, asset_ids: &[String]) -> Vec<String> {
+    asset_ids
+        .iter()
+        .filter(|id| prices.get(id.as_str()).is_some_and(|p| p.out_of_sequence))
+        .cloned()
+        .collect()
+}
+
+/// (books checked, mismatches corrected) since startup.
+pub fn book_check_counts() -> (u64, u64) {
+    (BOOK_CHECKS.load(Ordering::Relaxed), BOOK_MISMATCHES.load(Ordering::Relaxed))
+}
+
+/// Compare the WS-built best bid/ask for `asset_id` with a REST book snapshot and overwrite the WS state
+/// when they disagree. Returns the WS quote that was replaced, if any. Placeholder books are skipped,
+/// like on the WS path.
+pub fn reconcile_with_snapshot(
+    prices: &PricesSnapshot,
+    asset_id: &str,
+    book: &OrderBook,
+) -> Option<BestPrices> {
+    // REST levels aren't guaranteed best-first, so take the extremes.
+    let bid = book.bids.iter().filter_map(|l| l.price.to_f64()).reduce(f64::max);
+    let best_ask = book
+        .asks
+        .iter()
+        .filter_map(|l| Some((l.price.to_f64()?, l.size.to_f64()?)))
+        .reduce(|a, b| if b.0 < a.0 { b } else { a });
+    let ask = best_ask.map(|(price, _)| price);
+    let ask_size = best_ask.map(|(_, size)| size);
+    if is_placeholder_quote(bid, ask) {
+        // Placeholder quotes aren't applied on the WS path either, so there is nothing to correct.
+        if let Some(mut entry) = prices.get_mut(asset_id) {
+            entry.out_of_sequence = false;
+        }
+        return None;
+    }
+    BOOK_CHECKS.fetch_add(1, Ordering::Relaxed);
+    let same = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() < QUOTE_EPSILON,
+        (None, None) => true,
+        _ => false,
+    };
+    let mut entry = prices.entry(asset_id.to_string()).or_default();
+    entry.out_of_sequence = false;
+    if same(entry.bid, bid) && same(entry.ask, ask) {
+        entry.ask_size = ask_size;
+        return None;
+    }
+    BOOK_MISMATCHES.fetch_add(1, Ordering::Relaxed);
+    // Later WS messages are still checked against the newest timestamp seen; the REST book has no hash.
+    let server_ts_ms = entry.server_ts_ms;
+    let stale = std::mem::replace(
+        &mut *entry,
+        BestPrices {
+            bid,
+            ask,
+            ask_size,
+            received_at_ms: Some(Utc::now().timestamp_millis()),
+            server_ts_ms,
+            hash: None,
+            out_of_sequence: false,
+        },
+    );
+    Some(stale)
+}
 
+/// Stream best bid/ask of `asset_ids` into `prices`, publishing each change to `events` when given and
+/// signalling `updates` after every message applied.
 pub async fn run_market_ws(
     ws_base_url: &str,
     asset_ids: Vec<String>,
     prices: PricesSnapshot,
+    events: Option<EventBus>,
+    updates: watch::Sender<()>,
 ) -> Result<()> {
     let url = format!("{}/{}", ws_base_url.trim_end_matches('/'), WS_MARKET_PATH);
     let sub = serde_json::json!({
@@ -87,7 +207,7 @@ pub async fn run_market_ws(
 
     loop {
         info!("Connecting to market WebSocket: {}", url);
-        let (ws_stream, _) = match connect_async(&url).await {
+        let (ws_stream, _) = match proxy::connect_ws(&url).await {
             Ok(s) => s,
             Err(e) => {
                 error!(
@@ -118,8 +238,12 @@ pub async fn run_market_ws(
                     if text == "PONG" || text == "pong" {
                         continue;
                     }
-                    if let Err(e) = process_message(&text, &prices).await {
-                        debug!("WS parse error: {} for message: {}", e, &text[..text.len().min(200)]);
+                    ws_recorder::record(ws_recorder::SOURCE_MARKET, &text);
+                    match process_message(&text, &prices, events.as_ref()) {
+                        Ok(()) => {
+                            updates.send_replace(());
+                        }
+                        Err(e) => debug!("WS parse error: {} for message: {}", e, &text[..text.len().min(200)]),
                     }
                 }
                 Ok(Message::Ping(data)) => {
@@ -151,41 +275,115 @@ pub async fn run_market_ws(
     Ok(())
 }
 
-async fn process_message(text: &str, prices: &PricesSnapshot) -> Result<()> {
+fn publish_quote(events: Option<&EventBus>, token_id: &str, prices: &BestPrices) {
+    if let Some(events) = events {
+        events.publish(ArbEvent::Quote {
+            token_id: token_id.to_string(),
+            bid: prices.bid,
+            ask: prices.ask,
+            ts_ms: chrono::Utc::now().timestamp_millis(),
+        });
+    }
+}
+
+/// CLOB `timestamp` of a message: ms, as a string or a number.
+fn message_ts_ms(v: &serde_json::Value) -> Option<i64> {
+    match v.get("timestamp")? {
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        ts => ts.as_i64(),
+    }
+}
+
+/// Check a message for `asset_id` with CLOB time `ts_ms` and book `hash`, carrying best `bid`/`ask`, against
+/// the last one applied to `entry`. False when it is older and must be dropped. Regressions and hash mismatches
+/// mark the entry `out_of_sequence`.
+fn check_sequence(
+    entry: &mut BestPrices,
+    asset_id: &str,
+    ts_ms: Option<i64>,
+    hash: Option<&str>,
+    bid: Option<f64>,
+    ask: Option<f64>,
+) -> bool {
+    if let (Some(ts), Some(last)) = (ts_ms, entry.server_ts_ms) {
+        if ts < last {
+            SEQUENCE_GAPS.fetch_add(1, Ordering::Relaxed);
+            warn!("Market WS: {} message at {} after one at {}; dropped, refreshing from REST", asset_id, ts, last);
+            entry.out_of_sequence = true;
+            return false;
+        }
+    }
+    let differs = |new: Option<f64>, old: Option<f64>| match (new, old) {
+        (Some(new), Some(old)) => (new - old).abs() >= QUOTE_EPSILON,
+        _ => false,
+    };
+    if hash.is_some() && hash == entry.hash.as_deref() && (differs(bid, entry.bid) || differs(ask, entry.ask)) {
+        SEQUENCE_GAPS.fetch_add(1, Ordering::Relaxed);
+        warn!("Market WS: {} book hash unchanged but best prices moved; refreshing from REST", asset_id);
+        entry.out_of_sequence = true;
+    }
+    true
+}
+
+/// Apply one market WS message (`book` or `price_change`; others are ignored) to `prices`.
+pub fn process_message(text: &str, prices: &PricesSnapshot, events: Option<&EventBus>) -> Result<()> {
+    let received_at_ms = Some(Utc::now().timestamp_millis());
     let v: serde_json::Value = serde_json::from_str(text).context("Parse JSON")?;
     let event_type = v.get("event_type").and_then(|t| t.as_str());
+    let ts_ms = message_ts_ms(&v);
 
     if event_type == Some("book") {
         let book: WsBookMessage = serde_json::from_value(v).context("Parse book")?;
         let bid = book.buys.first().and_then(|b| parse_f64(&b.price));
         let ask = book.sells.first().and_then(|a| parse_f64(&a.price));
+        let ask_size = book.sells.first().and_then(|a| parse_f64(&a.size));
         if (bid.is_some() || ask.is_some()) && !is_placeholder_quote(bid, ask) {
-            let mut w = prices.write().await;
-            let entry = w.entry(book.asset_id).or_default();
+            let mut entry = prices.entry(book.asset_id.clone()).or_default();
+            if !check_sequence(&mut entry, &book.asset_id, ts_ms, book.hash.as_deref(), bid, ask) {
+                return Ok(());
+            }
             if let Some(b) = bid {
                 entry.bid = Some(b);
             }
             if let Some(a) = ask {
                 entry.ask = Some(a);
+                entry.ask_size = ask_size;
             }
+            entry.received_at_ms = received_at_ms;
+            entry.server_ts_ms = ts_ms.or(entry.server_ts_ms);
+            entry.hash = book.hash;
+            publish_quote(events, &book.asset_id, &entry);
         }
         return Ok(());
     }
 
     if event_type == Some("price_change") {
         let msg: WsPriceChangeMessage = serde_json::from_value(v).context("Parse price_change")?;
-        let mut w = prices.write().await;
         for pc in msg.price_changes {
             let bid = pc.best_bid.and_then(|s| parse_f64(&s));
             let ask = pc.best_ask.and_then(|s| parse_f64(&s));
             if (bid.is_some() || ask.is_some()) && !is_placeholder_quote(bid, ask) {
-                let entry = w.entry(pc.asset_id).or_default();
+                let mut entry = prices.entry(pc.asset_id.clone()).or_default();
+                if !check_sequence(&mut entry, &pc.asset_id, ts_ms, pc.hash.as_deref(), bid, ask) {
+                    continue;
+                }
                 if let Some(b) = bid {
                     entry.bid = Some(b);
                 }
                 if let Some(a) = ask {
+                    let level_is_best_ask = pc.side.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("SELL"))
+                        && pc.price.as_deref().and_then(parse_f64).is_some_and(|p| (p - a).abs() < QUOTE_EPSILON);
+                    if level_is_best_ask {
+                        entry.ask_size = pc.size.as_deref().and_then(parse_f64);
+                    } else if entry.ask.is_none_or(|prev| (prev - a).abs() >= QUOTE_EPSILON) {
+                        entry.ask_size = None;
+                    }
                     entry.ask = Some(a);
                 }
+                entry.received_at_ms = received_at_ms;
+                entry.server_ts_ms = ts_ms.or(entry.server_ts_ms);
+                entry.hash = pc.hash;
+                publish_quote(events, &pc.asset_id, &entry);
             }
         }
         return Ok(());
@@ -193,3 +391,134 @@ async fn process_message(text: &str, prices: &PricesSnapshot) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderBookEntry;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    fn price_change(ts: i64, hash: &str, bid: &str, ask: &str) -> String {
+        serde_json::json!({
+            "event_type": "price_change",
+            "timestamp": ts.to_string(),
+            "price_changes": [{ "asset_id": "15up", "best_bid": bid, "best_ask": ask, "hash": hash }],
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn older_messages_are_dropped_and_flag_the_asset() {
+        let prices: PricesSnapshot = Arc::new(DashMap::new());
+        let gaps = sequence_gap_count();
+        process_message(&price_change(2_000, "h2", "0.44", "0.46"), &prices, None).unwrap();
+        process_message(&price_change(1_000, "h1", "0.30", "0.32"), &prices, None).unwrap();
+        let quote = prices.get("15up").unwrap().clone();
+        assert_eq!((quote.bid, quote.ask, quote.server_ts_ms), (Some(0.44), Some(0.46), Some(2_000)));
+        assert!(quote.out_of_sequence);
+        assert!(sequence_gap_count() > gaps);
+        assert_eq!(out_of_sequence_assets(&prices, &["15up".to_string(), "15down".to_string()]), ["15up"]);
+
+        // A REST snapshot clears the flag.
+        let level = |price: &str| OrderBookEntry {
+            price: Decimal::from_str(price).unwrap(),
+            size: Decimal::from(100),
+        };
+        let book = OrderBook {
+            bids: vec![level("0.45")],
+            asks: vec![level("0.47")],
+            tick_size: None,
+            min_order_size: None,
+        };
+        reconcile_with_snapshot(&prices, "15up", &book);
+        assert!(!prices.get("15up").unwrap().out_of_sequence);
+        assert_eq!(prices.get("15up").unwrap().server_ts_ms, Some(2_000));
+    }
+
+    #[test]
+    fn a_rest_snapshot_corrects_a_diverged_ws_quote_from_its_best_levels() {
+        let prices: PricesSnapshot = Arc::new(DashMap::new());
+        process_message(&price_change(1_000, "h1", "0.40", "0.46"), &prices, None).unwrap();
+        let level = |price: &str, size: u32| OrderBookEntry {
+            price: Decimal::from_str(price).unwrap(),
+            size: Decimal::from(size),
+        };
+        let book = |bids: Vec<OrderBookEntry>, asks: Vec<OrderBookEntry>| OrderBook {
+            bids,
+            asks,
+            tick_size: None,
+            min_order_size: None,
+        };
+        // Levels out of order: the best bid is the highest and the best ask the lowest, with its size.
+        let rest = book(vec![level("0.41", 5), level("0.44", 10)], vec![level("0.48", 7), level("0.46", 30)]);
+        let (checked, mismatched) = book_check_counts();
+        let stale = reconcile_with_snapshot(&prices, "15up", &rest).expect("WS bid disagreed");
+        assert_eq!((stale.bid, stale.ask), (Some(0.40), Some(0.46)));
+        let quote = prices.get("15up").unwrap().clone();
+        assert_eq!((quote.bid, quote.ask, quote.ask_size), (Some(0.44), Some(0.46), Some(30.0)));
+        assert_eq!((quote.server_ts_ms, quote.hash), (Some(1_000), None));
+        let after = book_check_counts();
+        assert!(after.0 > checked && after.1 > mismatched);
+
+        // Agreeing again, and a placeholder book, leave the quote alone.
+        assert!(reconcile_with_snapshot(&prices, "15up", &rest).is_none());
+        let placeholder = book(vec![level("0.01", 1)], vec![level("0.99", 1)]);
+        assert!(reconcile_with_snapshot(&prices, "15up", &placeholder).is_none());
+        assert_eq!(prices.get("15up").unwrap().bid, Some(0.44));
+    }
+
+    #[test]
+    fn concurrent_writers_on_other_tokens_never_tear_a_quote() {
+        let prices: PricesSnapshot = Arc::new(DashMap::new());
+        let tokens = ["15up", "15down", "5up", "5down"];
+        let message = |token: &str, i: i64| {
+            let bid = 0.10 + (i % 70) as f64 / 100.0;
+            serde_json::json!({
+                "event_type": "price_change",
+                "timestamp": (1_000 + i).to_string(),
+                "price_changes": [{
+                    "asset_id": token,
+                    "best_bid": format!("{:.2}", bid),
+                    "best_ask": format!("{:.2}", bid + 0.02),
+                    "hash": format!("h{}", i),
+                }],
+            })
+            .to_string()
+        };
+        std::thread::scope(|scope| {
+            for token in tokens {
+                let prices = &prices;
+                scope.spawn(move || {
+                    for i in 0..500 {
+                        process_message(&message(token, i), prices, None).unwrap();
+                    }
+                });
+            }
+            scope.spawn(|| {
+                for _ in 0..2_000 {
+                    for token in tokens {
+                        if let Some(quote) = prices.get(token) {
+                            let (bid, ask) = (quote.bid.unwrap(), quote.ask.unwrap());
+                            assert!((ask - bid - 0.02).abs() < 1e-9, "{} torn: {}/{}", token, bid, ask);
+                        }
+                    }
+                }
+            });
+        });
+        for token in tokens {
+            let quote = prices.get(token).unwrap();
+            assert_eq!((quote.server_ts_ms, quote.out_of_sequence), (Some(1_499), false));
+        }
+    }
+
+    #[test]
+    fn same_hash_with_other_best_prices_flags_the_asset() {
+        let prices: PricesSnapshot = Arc::new(DashMap::new());
+        process_message(&price_change(1_000, "h1", "0.44", "0.46"), &prices, None).unwrap();
+        process_message(&price_change(1_000, "h1", "0.44", "0.46"), &prices, None).unwrap();
+        assert!(!prices.get("15up").unwrap().out_of_sequence, "a repeat of the same book is fine");
+        process_message(&price_change(1_500, "h1", "0.40", "0.46"), &prices, None).unwrap();
+        assert!(prices.get("15up").unwrap().out_of_sequence);
+    }
+}
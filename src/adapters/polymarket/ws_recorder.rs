@@ -0,0 +1,111 @@
+//! Recording of raw market WS and RTDS messages for `--replay` (see `services::replay`).
+//!
+//! When `polymarket.ws_record_dir` is set, every text message from the market and RTDS feeds, and each overlap
+//! the orchestrator enters, is appended as one JSON line with its receive time to `ws-<start unix>.jsonl` in that
+//! directory. Recording is process-wide and never blocks a feed: lines go through a channel to a writer thread.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, OnceLock};
+
+/// A market WebSocket message (`book`, `price_change`, ...).
+pub const SOURCE_MARKET: &str = "market";
+/// An RTDS message (Chainlink ticks).
+pub const SOURCE_RTDS: &str = "rtds";
+/// An overlap the orchestrator entered: its `PeriodContext` as JSON.
+pub const SOURCE_ROUND: &str = "round";
+
+static RECORDER: OnceLock<mpsc::Sender<String>> = OnceLock::new();
+
+/// One recorded message: local receive time, which feed it came from, and the raw text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedMessage {
+    pub ts_ms: i64,
+    pub source: String,
+    pub data: String,
+}
+
+/// Start recording to `dir`; a no-op when `dir` is empty or a recording is already running.
+pub fn start(dir: &str) -> Result<()> {
+    let dir = dir.trim();
+    if dir.is_empty() || RECORDER.get().is_some() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dir).with_context(|| format!("create {}", dir))?;
+    let path = Path::new(dir).join(format!("ws-{}.jsonl", Utc::now().timestamp()));
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("open {}", path.display()))?;
+    let (tx, rx) = mpsc::channel();
+    if RECORDER.set(tx).is_err() {
+        return Ok(());
+    }
+    std::thread::Builder::new()
+        .name("ws-recorder".to_string())
+        .spawn(move || run_writer(file, rx))
+        .context("spawn WS recorder thread")?;
+    info!("Recording market WS and RTDS messages to {}", path.display());
+    Ok(())
+}
+
+/// Append `data` from `source` to the recording, if one is running.
+pub fn record(source: &str, data: &str) {
+    let Some(tx) = RECORDER.get() else {
+        return;
+    };
+    let message = RecordedMessage {
+        ts_ms: Utc::now().timestamp_millis(),
+        source: source.to_string(),
+        data: data.to_string(),
+    };
+    if let Ok(line) = serde_json::to_string(&message) {
+        let _ = tx.send(line);
+    }
+}
+
+/// Write lines as they come, flushing whenever the queue runs dry.
+fn run_writer(file: File, rx: mpsc::Receiver<String>) {
+    let mut out = BufWriter::new(file);
+    while let Ok(line) = rx.recv() {
+        let mut pending = Some(line);
+        while let Some(line) = pending {
+            if let Err(e) = writeln!(out, "{}", line) {
+                warn!("WS recorder write failed: {}", e);
+            }
+            pending = rx.try_recv().ok();
+        }
+        if let Err(e) = out.flush() {
+            warn!("WS recorder flush failed: {}", e);
+        }
+    }
+}
+
+/// Every message recorded in the `*.jsonl` files of `dir`, oldest first. Unparseable lines are skipped.
+pub fn read_recordings(dir: &Path) -> Result<Vec<RecordedMessage>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("read {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    files.sort();
+    let mut messages = Vec::new();
+    for path in files {
+        let file = File::open(&path).with_context(|| format!("open {}", path.display()))?;
+        for line in BufReader::new(file).lines() {
+            let line = line.with_context(|| format!("read {}", path.display()))?;
+            if let Ok(message) = serde_json::from_str::<RecordedMessage>(&line) {
+                messages.push(message);
+            }
+        }
+    }
+    // Stable, so messages received in the same millisecond keep their order.
+    messages.sort_by_key(|m| m.ts_ms);
+    Ok(messages)
+}
@@ -1,18 +1,24 @@
-//! Price-to-beat from Polymarket RTDS Chainlink (crypto_prices_chainlink) for multiple symbols.
+//! Price-to-beat from Polymarket RTDS Chainlink (crypto_prices_chainlink) for multiple symbols, and
+//! optionally the latest spot tick and a rolling window of recent ticks per symbol.
 
-use crate::utils::time_windows::period_start_et_unix_at;
+use super::ws_recorder;
+use crate::domain::window::{period_start_et_unix_at, PeriodPair};
+use crate::utils::proxy;
+use crate::utils::supervisor::Supervisor;
 use anyhow::{Context, Result};
 use futures_util::{SinkExt, StreamExt};
 use log::{info, warn};
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
-use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_tungstenite::tungstenite::Message;
 
 const PING_INTERVAL_SECS: u64 = 5;
 const FEED_TS_CAPTURE_WINDOW_SECS: i64 = 2;
+/// How far back `SpotHistory` keeps ticks.
+pub const SPOT_HISTORY_SECS: i64 = 900;
 
 #[derive(Debug, Deserialize)]
 struct ChainlinkPayload {
@@ -56,6 +62,10 @@ struct ChainlinkMessage {
 }
 
 pub type PriceCacheMulti = Arc<RwLock<HashMap<String, HashMap<i64, f64>>>>;
+/// Latest Chainlink tick per symbol: (feed timestamp ms, price).
+pub type SpotCache = Arc<RwLock<HashMap<String, (i64, f64)>>>;
+/// Chainlink ticks per symbol over the last `SPOT_HISTORY_SECS`, oldest first: (feed timestamp ms, price).
+pub type SpotHistory = Arc<RwLock<HashMap<String, VecDeque<(i64, f64)>>>>;
 
 fn payload_symbol_to_key(s: &str) -> Option<String> {
     let s = s.trim().to_lowercase();
@@ -66,11 +76,41 @@ fn payload_symbol_to_key(s: &str) -> Option<String> {
     }
 }
 
+/// Symbol key ("btc"), feed timestamp in ms, and price of a `crypto_prices_chainlink` message; None for
+/// anything else.
+pub fn parse_chainlink_tick(text: &str) -> Option<(String, i64, f64)> {
+    let m = serde_json::from_str::<ChainlinkMessage>(text).ok()?;
+    if m.topic.as_deref() != Some("crypto_prices_chainlink") {
+        return None;
+    }
+    let p = m.payload?;
+    let key = payload_symbol_to_key(&p.symbol)?;
+    let ts_ms = if p.timestamp > 1_000_000_000_000 {
+        p.timestamp
+    } else {
+        p.timestamp * 1000
+    };
+    Some((key, ts_ms, p.value))
+}
+
+/// Append a tick to one symbol's `SpotHistory` (out-of-order ticks are dropped) and trim it to the window.
+pub fn push_tick(ticks: &mut VecDeque<(i64, f64)>, ts_ms: i64, value: f64) {
+    if ticks.back().is_none_or(|(last, _)| ts_ms > *last) {
+        ticks.push_back((ts_ms, value));
+    }
+    while ticks.front().is_some_and(|(ts, _)| *ts < ts_ms - SPOT_HISTORY_SECS * 1000) {
+        ticks.pop_front();
+    }
+}
+
 pub async fn run_rtds_chainlink_multi(
     ws_url: &str,
     symbols: &[String],
-    price_cache_15: PriceCacheMulti,
-    price_cache_5: PriceCacheMulti,
+    periods: PeriodPair,
+    price_cache_long: PriceCacheMulti,
+    price_cache_short: PriceCacheMulti,
+    spot: Option<SpotCache>,
+    history: Option<SpotHistory>,
 ) -> Result<()> {
     let url = ws_url.trim_end_matches('/');
     let symbol_set: HashSet<String> = symbols.iter().map(|s| s.to_lowercase()).collect();
@@ -79,7 +119,7 @@ pub async fn run_rtds_chainlink_multi(
         url, symbols
     );
 
-    let (mut ws_stream, _) = connect_async(url).await.context("RTDS connect failed")?;
+    let (mut ws_stream, _) = proxy::connect_ws(url).await.context("RTDS connect failed")?;
     let sub = serde_json::json!({
         "action": "subscribe",
         "subscriptions": [{
@@ -106,47 +146,36 @@ pub async fn run_rtds_chainlink_multi(
                 let msg = msg.context("RTDS stream error")?;
                 match msg {
                     Message::Text(text) => {
-                        if let Ok(m) = serde_json::from_str::<ChainlinkMessage>(&text) {
-                            if m.topic.as_deref() == Some("crypto_prices_chainlink") {
-                                if let Some(p) = m.payload {
-                                    let key = match payload_symbol_to_key(&p.symbol) {
-                                        Some(k) if symbol_set.contains(&k) => k,
-                                        _ => continue,
-                                    };
-                                    let ts_sec = if p.timestamp > 1_000_000_000_000 {
-                                        p.timestamp / 1000
-                                    } else {
-                                        p.timestamp
-                                    };
-                                    let period_15 = period_start_et_unix_at(ts_sec, 15);
-                                    let period_5 = period_start_et_unix_at(ts_sec, 5);
-                                    let in_capture_15 = ts_sec >= period_15
-                                        && ts_sec < period_15 + FEED_TS_CAPTURE_WINDOW_SECS;
-                                    let in_capture_5 = ts_sec >= period_5
-                                        && ts_sec < period_5 + FEED_TS_CAPTURE_WINDOW_SECS;
-                                    if in_capture_15 {
-                                        let mut cache = price_cache_15.write().await;
-                                        let per_symbol = cache.entry(key.clone()).or_default();
-                                        if !per_symbol.contains_key(&period_15) {
-                                            per_symbol.insert(period_15, p.value);
-                                            info!(
-                                                "RTDS Chainlink price-to-beat 15m {}: period {} -> {:.2} USD (feed_ts={})",
-                                                key, period_15, p.value, ts_sec
-                                            );
-                                        }
-                                    }
-                                    if in_capture_5 {
-                                        let mut cache = price_cache_5.write().await;
-                                        let per_symbol = cache.entry(key.clone()).or_default();
-                                        if !per_symbol.contains_key(&period_5) {
-                                            per_symbol.insert(period_5, p.value);
-                                            info!(
-                                                "RTDS Chainlink price-to-beat 5m {}: period {} -> {:.2} USD (feed_ts={})",
-                                                key, period_5, p.value, ts_sec
-                                            );
-                                        }
-                                    }
-                                }
+                        ws_recorder::record(ws_recorder::SOURCE_RTDS, &text);
+                        let Some((key, ts_ms, value)) = parse_chainlink_tick(&text) else {
+                            continue;
+                        };
+                        if !symbol_set.is_empty() && !symbol_set.contains(&key) {
+                            continue;
+                        }
+                        let ts_sec = ts_ms / 1000;
+                        if let Some(spot) = &spot {
+                            spot.write().await.insert(key.clone(), (ts_ms, value));
+                        }
+                        if let Some(history) = &history {
+                            push_tick(history.write().await.entry(key.clone()).or_default(), ts_ms, value);
+                        }
+                        for (period_mins, cache) in [
+                            (periods.long_period_mins, &price_cache_long),
+                            (periods.short_period_mins, &price_cache_short),
+                        ] {
+                            let period = period_start_et_unix_at(ts_sec, period_mins);
+                            if ts_sec >= period + FEED_TS_CAPTURE_WINDOW_SECS {
+                                continue;
+                            }
+                            let mut cache = cache.write().await;
+                            let per_symbol = cache.entry(key.clone()).or_default();
+                            if let Entry::Vacant(e) = per_symbol.entry(period) {
+                                e.insert(value);
+                                info!(
+                                    "RTDS Chainlink price-to-beat {} {}: period {} -> {:.2} USD (feed_ts={})",
+                                    PeriodPair::label(period_mins), key, period, value, ts_sec
+                                );
                             }
                         }
                     }
@@ -168,28 +197,42 @@ pub async fn run_rtds_chainlink_multi(
     Ok(())
 }
 
+/// Keep the RTDS Chainlink feed running in the background, restarted by `supervisor` if it panics.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_chainlink_multi_poller(
     rtds_ws_url: String,
     symbols: Vec<String>,
-    price_cache_15: PriceCacheMulti,
-    price_cache_5: PriceCacheMulti,
+    periods: PeriodPair,
+    price_cache_long: PriceCacheMulti,
+    price_cache_short: PriceCacheMulti,
+    spot: Option<SpotCache>,
+    history: Option<SpotHistory>,
+    supervisor: &Supervisor,
 ) -> Result<()> {
-    let cache_15 = Arc::clone(&price_cache_15);
-    let cache_5 = Arc::clone(&price_cache_5);
-
-    tokio::spawn(async move {
-        loop {
-            if let Err(e) = run_rtds_chainlink_multi(
-                &rtds_ws_url,
-                &symbols,
-                cache_15.clone(),
-                cache_5.clone(),
-            )
-            .await
-            {
-                warn!("RTDS Chainlink stream exited: {} (reconnecting in 5s)", e);
+    let cache_long = Arc::clone(&price_cache_long);
+    let cache_short = Arc::clone(&price_cache_short);
+
+    supervisor.spawn("RTDS Chainlink feed", move || {
+        let (rtds_ws_url, symbols) = (rtds_ws_url.clone(), symbols.clone());
+        let (cache_long, cache_short) = (Arc::clone(&cache_long), Arc::clone(&cache_short));
+        let (spot, history) = (spot.clone(), history.clone());
+        async move {
+            loop {
+                if let Err(e) = run_rtds_chainlink_multi(
+                    &rtds_ws_url,
+                    &symbols,
+                    periods,
+                    cache_long.clone(),
+                    cache_short.clone(),
+                    spot.clone(),
+                    history.clone(),
+                )
+                .await
+                {
+                    warn!("RTDS Chainlink stream exited: {} (reconnecting in 5s)", e);
+                }
+                tokio::time::sleep(Duration::from_secs(5)).await;
             }
-            tokio::time::sleep(Duration::from_secs(5)).await;
         }
     });
 
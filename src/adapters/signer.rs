@@ -0,0 +1,590 @@
+//! Who signs for the trading wallet. Orders, CLOB authentication, Safe transaction hashes, and redemption
+//! transactions all reduce to signing a 32-byte digest, so a `TxSigner` only has to do that: with the local
+//! `private_key`, through a remote signing service, or with a secp256k1 key held in AWS KMS or GCP Cloud KMS, so
+//! the raw key never sits on the trading box.
+
+use crate::config::{SignerConfig, SignerKind};
+use crate::utils::proxy;
+use crate::utils::secret::{redact_url, Secret};
+use alloy::consensus::SignableTransaction;
+use alloy::network::TxSigner as NetworkTxSigner;
+use alloy::primitives::{Address, ChainId, Signature, B256, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use futures_util::future::{BoxFuture, FutureExt};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const REMOTE_SIGN_TIMEOUT_SECS: u64 = 10;
+/// GCE / GKE metadata server, for an access token when `signer.token` isn't set.
+const GCP_METADATA_TOKEN_URL: &str =
+    "http://metadata.google.internal/computeMetadata/v1/instance/service-accounts/default/token";
+/// A metadata token is fetched again this long before it expires.
+const GCP_TOKEN_REFRESH_MARGIN_SECS: u64 = 60;
+/// Order of the secp256k1 group: a signature's `s` above half of it is flipped, as Ethereum only accepts low `s`.
+const SECP256K1_ORDER: U256 = U256::from_be_bytes([
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe, 0xba, 0xae, 0xdc,
+    0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+]);
+
+/// Signs digests for one EOA.
+pub trait TxSigner: Send + Sync {
+    fn address(&self) -> Address;
+
+    /// Sign `hash` as is (no EIP-191 prefix added): EIP-712 order and auth digests, Safe transaction hashes
+    /// (already prefixed by the caller), and transaction signing hashes.
+    fn sign_hash(&self, hash: B256) -> BoxFuture<'_, Result<Signature>>;
+}
+
+impl TxSigner for PrivateKeySigner {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+
+    fn sign_hash(&self, hash: B256) -> BoxFuture<'_, Result<Signature>> {
+        async move { Ok(Signer::sign_hash(self, &hash).await?) }.boxed()
+    }
+}
+
+/// Signing service reached over HTTP: `POST <url>` with `{"address": "0x..", "hash": "0x.."}` answers
+/// `{"signature": "0x<r><s><v>"}` (65 bytes). With a token, requests carry `Authorization: Bearer <token>`.
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    token: Option<Secret>,
+    address: Address,
+}
+
+#[derive(Deserialize)]
+struct RemoteSignature {
+    signature: String,
+}
+
+impl RemoteSigner {
+    pub fn new(url: &str, token: Option<Secret>, address: &str) -> Result<Self> {
+        if url.trim().is_empty() {
+            anyhow::bail!("signer.url is required for a remote signer");
+        }
+        let address = Address::from_str(address.trim())
+            .context(format!("signer.address {:?} is not an address", address))?;
+        Ok(Self {
+            client: proxy::client_builder()
+                .timeout(std::time::Duration::from_secs(REMOTE_SIGN_TIMEOUT_SECS))
+                .build()
+                .expect("Failed to create HTTP client"),
+            url: url.trim().to_string(),
+            token,
+            address,
+        })
+    }
+
+    async fn sign(&self, hash: B256) -> Result<Signature> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({ "address": self.address.to_string(), "hash": hash.to_string() }));
+        if let Some(token) = self.token.as_ref().filter(|t| !t.is_empty()) {
+            request = request.bearer_auth(token.expose());
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("remote signer {}: {}", redact_url(&self.url), e.without_url()))?;
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("remote signer {} answered {}", redact_url(&self.url), status);
+        }
+        let body: RemoteSignature = response.json().await.context("remote signer: unexpected response")?;
+        let bytes = hex::decode(body.signature.trim_start_matches("0x")).context("remote signer: signature is not hex")?;
+        let signature = Signature::from_raw(&bytes).context("remote signer: not a 65-byte signature")?;
+        // A signer holding another key would only surface later as rejected orders and failed redemptions.
+        let recovered = signature.recover_address_from_prehash(&hash).context("remote signer: bad signature")?;
+        if recovered != self.address {
+            anyhow::bail!("remote signer signed with {}, expected {}", recovered, self.address);
+        }
+        Ok(signature)
+    }
+}
+
+impl TxSigner for RemoteSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_hash(&self, hash: B256) -> BoxFuture<'_, Result<Signature>> {
+        self.sign(hash).boxed()
+    }
+}
+
+/// Static AWS credentials, as in the standard `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, and (for temporary
+/// credentials) `AWS_SESSION_TOKEN` variables.
+#[derive(Clone)]
+pub struct AwsCredentials {
+    pub access_key_id: String,
+    pub secret_access_key: Secret,
+    pub session_token: Option<Secret>,
+}
+
+impl AwsCredentials {
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let (Some(access_key_id), Some(secret)) = (var("AWS_ACCESS_KEY_ID"), var("AWS_SECRET_ACCESS_KEY")) else {
+            anyhow::bail!("the aws_kms signer needs AWS_ACCESS_KEY_ID and AWS_SECRET_ACCESS_KEY");
+        };
+        Ok(Self {
+            access_key_id,
+            secret_access_key: Secret::new(secret),
+            session_token: var("AWS_SESSION_TOKEN").map(Secret::new),
+        })
+    }
+}
+
+/// A secp256k1 key (`ECC_SECG_P256K1`) in AWS KMS, signing through the KMS `Sign` API with SigV4-signed requests.
+pub struct AwsKmsSigner {
+    client: reqwest::Client,
+    endpoint: String,
+    region: String,
+    key_id: String,
+    credentials: AwsCredentials,
+    address: Address,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AwsSignResponse {
+    signature: String,
+}
+
+impl AwsKmsSigner {
+    /// `endpoint` defaults to `https://kms.<region>.amazonaws.com`.
+    pub fn new(
+        key_id: &str,
+        region: &str,
+        endpoint: Option<&str>,
+        credentials: AwsCredentials,
+        address: &str,
+    ) -> Result<Self> {
+        if key_id.trim().is_empty() || region.trim().is_empty() {
+            anyhow::bail!("signer.key_id and signer.region are required for an aws_kms signer");
+        }
+        let endpoint = endpoint
+            .map(str::trim)
+            .filter(|e| !e.is_empty())
+            .map_or_else(|| format!("https://kms.{}.amazonaws.com", region.trim()), str::to_string);
+        Ok(Self {
+            client: kms_client(),
+            endpoint,
+            region: region.trim().to_string(),
+            key_id: key_id.trim().to_string(),
+            credentials,
+            address: parse_address(address)?,
+        })
+    }
+
+    async fn sign(&self, hash: B256) -> Result<Signature> {
+        let body = serde_json::json!({
+            "KeyId": self.key_id,
+            "Message": BASE64.encode(hash),
+            "MessageType": "DIGEST",
+            "SigningAlgorithm": "ECDSA_SHA_256",
+        })
+        .to_string();
+        let url = reqwest::Url::parse(&self.endpoint).context("signer.url is not a URL")?;
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1".to_string()),
+            ("host", host),
+            ("x-amz-date", amz_date.clone()),
+            ("x-amz-target", "TrentService.Sign".to_string()),
+        ];
+        if let Some(token) = &self.credentials.session_token {
+            headers.push(("x-amz-security-token", token.expose().to_string()));
+        }
+        headers.sort();
+        let authorization = sigv4_authorization(&self.credentials, &self.region, "kms", &amz_date, &headers, &body);
+
+        let mut request = self.client.post(url).header("authorization", authorization).body(body);
+        for (name, value) in headers.iter().filter(|(name, _)| *name != "host") {
+            request = request.header(*name, value);
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("AWS KMS {}: {}", self.endpoint, e.without_url()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("AWS KMS Sign answered {}: {}", status, text);
+        }
+        let body: AwsSignResponse = response.json().await.context("AWS KMS: unexpected Sign response")?;
+        let der = BASE64.decode(body.signature).context("AWS KMS: signature is not base64")?;
+        ethereum_signature(&der, hash, self.address).context("AWS KMS")
+    }
+}
+
+impl TxSigner for AwsKmsSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_hash(&self, hash: B256) -> BoxFuture<'_, Result<Signature>> {
+        self.sign(hash).boxed()
+    }
+}
+
+/// SigV4 `Authorization` header for a POST to `/` with `headers` (lowercase names, sorted, `host` included).
+fn sigv4_authorization(
+    credentials: &AwsCredentials,
+    region: &str,
+    service: &str,
+    amz_date: &str,
+    headers: &[(&str, String)],
+    body: &str,
+) -> String {
+    let date = &amz_date[..8];
+    let canonical_headers: String = headers.iter().map(|(name, value)| format!("{}:{}\n", name, value.trim())).collect();
+    let signed_headers = headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "POST\n/\n\n{}\n{}\n{}",
+        canonical_headers,
+        signed_headers,
+        hex::encode(Sha256::digest(body))
+    );
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request))
+    );
+    let key = sigv4_signing_key(credentials.secret_access_key.expose(), date, region, service);
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id,
+        scope,
+        signed_headers,
+        hex::encode(hmac_sha256(&key, string_to_sign.as_bytes()))
+    )
+}
+
+fn sigv4_signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// A secp256k1 key version (`EC_SIGN_SECP256K1_SHA256`) in GCP Cloud KMS, signing through `asymmetricSign`. The
+/// access token is `signer.token` when set, else one from the GCE / GKE metadata server, refreshed as it expires.
+pub struct GcpKmsSigner {
+    client: reqwest::Client,
+    endpoint: String,
+    key_version: String,
+    token: Option<Secret>,
+    metadata_token: Mutex<Option<(Secret, Instant)>>,
+    address: Address,
+}
+
+#[derive(Deserialize)]
+struct GcpSignResponse {
+    signature: String,
+}
+
+#[derive(Deserialize)]
+struct GcpAccessToken {
+    access_token: String,
+    expires_in: u64,
+}
+
+impl GcpKmsSigner {
+    /// `key_version` is the key version's resource name ("projects/../cryptoKeyVersions/1"); `endpoint` defaults
+    /// to `https://cloudkms.googleapis.com`.
+    pub fn new(key_version: &str, token: Option<Secret>, endpoint: Option<&str>, address: &str) -> Result<Self> {
+        if !key_version.trim().starts_with("projects/") {
+            anyhow::bail!("signer.key_id must be a GCP KMS key version name (projects/../cryptoKeyVersions/..)");
+        }
+        let endpoint = endpoint.map(str::trim).filter(|e| !e.is_empty()).unwrap_or("https://cloudkms.googleapis.com");
+        Ok(Self {
+            client: kms_client(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            key_version: key_version.trim().to_string(),
+            token: token.filter(|t| !t.is_empty()),
+            metadata_token: Mutex::new(None),
+            address: parse_address(address)?,
+        })
+    }
+
+    async fn access_token(&self) -> Result<Secret> {
+        if let Some(token) = &self.token {
+            return Ok(token.clone());
+        }
+        let cached = self.metadata_token.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        if let Some((token, _)) = cached.filter(|(_, expires)| Instant::now() < *expires) {
+            return Ok(token);
+        }
+        let token: GcpAccessToken = self
+            .client
+            .get(GCP_METADATA_TOKEN_URL)
+            .header("Metadata-Flavor", "Google")
+            .send()
+            .await
+            .context("GCP metadata server unreachable; set signer.token")?
+            .error_for_status()
+            .context("GCP metadata token")?
+            .json()
+            .await
+            .context("GCP metadata token: unexpected response")?;
+        let token_secret = Secret::new(token.access_token);
+        let lifetime = token.expires_in.saturating_sub(GCP_TOKEN_REFRESH_MARGIN_SECS);
+        *self.metadata_token.lock().unwrap_or_else(|e| e.into_inner()) =
+            Some((token_secret.clone(), Instant::now() + Duration::from_secs(lifetime)));
+        Ok(token_secret)
+    }
+
+    async fn sign(&self, hash: B256) -> Result<Signature> {
+        let token = self.access_token().await?;
+        let url = format!("{}/v1/{}:asymmetricSign", self.endpoint, self.key_version);
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(token.expose())
+            .json(&serde_json::json!({ "digest": { "sha256": BASE64.encode(hash) } }))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("GCP KMS {}: {}", self.endpoint, e.without_url()))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("GCP KMS asymmetricSign answered {}: {}", status, text);
+        }
+        let body: GcpSignResponse = response.json().await.context("GCP KMS: unexpected asymmetricSign response")?;
+        let der = BASE64.decode(body.signature).context("GCP KMS: signature is not base64")?;
+        ethereum_signature(&der, hash, self.address).context("GCP KMS")
+    }
+}
+
+impl TxSigner for GcpKmsSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign_hash(&self, hash: B256) -> BoxFuture<'_, Result<Signature>> {
+        self.sign(hash).boxed()
+    }
+}
+
+fn kms_client() -> reqwest::Client {
+    proxy::client_builder()
+        .timeout(std::time::Duration::from_secs(REMOTE_SIGN_TIMEOUT_SECS))
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+fn parse_address(address: &str) -> Result<Address> {
+    Address::from_str(address.trim()).context(format!("signer.address {:?} is not an address", address))
+}
+
+/// A KMS's DER-encoded ECDSA signature of `hash` as an Ethereum one: `s` made low, and the recovery bit the one
+/// that recovers `address` (KMS doesn't return it). An error when neither does: the key isn't `address`'s.
+pub fn ethereum_signature(der: &[u8], hash: B256, address: Address) -> Result<Signature> {
+    let (r, mut s) = der_signature(der).context("not a DER ECDSA signature")?;
+    if s > SECP256K1_ORDER >> 1 {
+        s = SECP256K1_ORDER - s;
+    }
+    for parity in [false, true] {
+        let signature = Signature::new(r, s, parity);
+        if signature.recover_address_from_prehash(&hash).ok() == Some(address) {
+            return Ok(signature);
+        }
+    }
+    anyhow::bail!("the key doesn't sign for {}", address)
+}
+
+/// (r, s) of a DER `SEQUENCE { INTEGER r, INTEGER s }`.
+fn der_signature(der: &[u8]) -> Option<(U256, U256)> {
+    let [0x30, len, body @ ..] = der else {
+        return None;
+    };
+    if *len as usize != body.len() {
+        return None;
+    }
+    let (r, rest) = der_integer(body)?;
+    let (s, rest) = der_integer(rest)?;
+    rest.is_empty().then_some((r, s))
+}
+
+fn der_integer(input: &[u8]) -> Option<(U256, &[u8])> {
+    let [0x02, len, rest @ ..] = input else {
+        return None;
+    };
+    let len = *len as usize;
+    if len == 0 || len > 33 || rest.len() < len {
+        return None;
+    }
+    let (int, rest) = rest.split_at(len);
+    let int = &int[int.iter().take_while(|b| **b == 0).count()..];
+    (int.len() <= 32).then(|| (U256::from_be_slice(int), rest))
+}
+
+/// The signer `signer` selects; None for the local kind, which signs with `private_key`. An error for a signer
+/// that is selected but can't be set up, which must not fall back to the private key.
+pub fn signer_from_config(signer: &SignerConfig) -> Result<Option<Arc<dyn TxSigner>>> {
+    let endpoint = Some(signer.url.as_str());
+    match signer.kind {
+        SignerKind::Local => Ok(None),
+        SignerKind::Remote => Ok(Some(Arc::new(RemoteSigner::new(
+            &signer.url,
+            signer.token.clone(),
+            &signer.address,
+        )?))),
+        SignerKind::AwsKms => Ok(Some(Arc::new(AwsKmsSigner::new(
+            &signer.key_id,
+            &signer.region,
+            endpoint,
+            AwsCredentials::from_env()?,
+            &signer.address,
+        )?))),
+        SignerKind::GcpKms => Ok(Some(Arc::new(GcpKmsSigner::new(
+            &signer.key_id,
+            signer.token.clone(),
+            endpoint,
+            &signer.address,
+        )?))),
+    }
+}
+
+/// A `TxSigner` as an alloy signer, for the CLOB client and the transaction provider.
+#[derive(Clone)]
+pub struct SharedSigner {
+    inner: Arc<dyn TxSigner>,
+    chain_id: Option<ChainId>,
+}
+
+impl SharedSigner {
+    pub fn new(inner: Arc<dyn TxSigner>, chain_id: Option<ChainId>) -> Self {
+        Self { inner, chain_id }
+    }
+}
+
+type SignFuture<'a> = Pin<Box<dyn Future<Output = alloy::signers::Result<Signature>> + Send + 'a>>;
+
+fn signer_error(e: anyhow::Error) -> alloy::signers::Error {
+    alloy::signers::Error::other(e)
+}
+
+// The alloy traits are `async_trait`s; these are their desugared forms.
+impl Signer for SharedSigner {
+    fn sign_hash<'a, 'b, 'c>(&'a self, hash: &'b B256) -> SignFuture<'c>
+    where
+        'a: 'c,
+        'b: 'c,
+        Self: 'c,
+    {
+        Box::pin(async move { self.inner.sign_hash(*hash).await.map_err(signer_error) })
+    }
+
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        self.chain_id
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        self.chain_id = chain_id;
+    }
+}
+
+impl NetworkTxSigner<Signature> for SharedSigner {
+    fn address(&self) -> Address {
+        self.inner.address()
+    }
+
+    fn sign_transaction<'a, 'b, 'c>(&'a self, tx: &'b mut dyn SignableTransaction<Signature>) -> SignFuture<'c>
+    where
+        'a: 'c,
+        'b: 'c,
+        Self: 'c,
+    {
+        Box::pin(async move {
+            if let Some(chain_id) = self.chain_id {
+                if !tx.set_chain_id_checked(chain_id) {
+                    return Err(alloy::signers::Error::TransactionChainIdMismatch {
+                        signer: chain_id,
+                        tx: tx.chain_id().unwrap_or_default(),
+                    });
+                }
+            }
+            self.inner.sign_hash(tx.signature_hash()).await.map_err(signer_error)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sigv4_signing_key_matches_the_aws_example() {
+        // From the AWS Signature Version 4 documentation.
+        let key = sigv4_signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex::encode(key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+    }
+
+    #[test]
+    fn parses_der_integers_with_a_sign_byte() {
+        let der = [0x30, 0x08, 0x02, 0x02, 0x00, 0x80, 0x02, 0x02, 0x01, 0x00];
+        assert_eq!(der_signature(&der), Some((U256::from(0x80), U256::from(0x100))));
+        assert_eq!(der_signature(&der[..9]), None);
+    }
+
+    #[test]
+    fn a_high_s_der_signature_is_made_low_s_with_the_recovery_bit_of_the_key() {
+        use alloy::primitives::keccak256;
+        use alloy::signers::k256::ecdsa::{Signature as DerSignature, SigningKey};
+        use alloy::signers::SignerSync;
+        let key = SigningKey::from_slice(&[0x42; 32]).unwrap();
+        let local = PrivateKeySigner::from_signing_key(key.clone());
+        // Sign until both recovery bits have come up.
+        let mut parities = Vec::new();
+        for i in 0u8.. {
+            let hash = keccak256([i]);
+            // k256 signs low-s; a KMS may return either, so hand over the high-s twin.
+            let (low, _) = key.sign_prehash_recoverable(hash.as_slice()).unwrap();
+            let high = DerSignature::from_scalars(low.r(), -*low.s()).unwrap();
+            let der = high.to_der();
+            assert!(der_signature(der.as_bytes()).unwrap().1 > SECP256K1_ORDER >> 1);
+
+            let signature = ethereum_signature(der.as_bytes(), hash, local.address()).unwrap();
+            assert_eq!(signature, local.sign_hash_sync(&hash).unwrap());
+            assert_eq!(signature.recover_address_from_prehash(&hash).unwrap(), local.address());
+            assert!(ethereum_signature(der.as_bytes(), hash, Address::repeat_byte(1)).is_err());
+            if !parities.contains(&signature.v()) {
+                parities.push(signature.v());
+            }
+            if parities.len() == 2 {
+                break;
+            }
+        }
+    }
+}
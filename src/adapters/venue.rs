@@ -0,0 +1,139 @@
+//! Exchange-neutral view of a binary prediction market venue, so market discovery, order flow and resolution
+//! can run against venues other than Polymarket.
+
+use crate::models::{CancelOutcome, OrderBook, OrderRequest, OrderResponse, OrderStatus, RedeemResponse};
+use anyhow::Result;
+use futures_util::future::BoxFuture;
+use std::collections::HashMap;
+use std::fmt;
+
+/// An order post that failed without an answer from the venue (timeout, dropped connection): the order may or
+/// may not have been taken. Callers look it up with `find_submitted_order` before posting it again.
+#[derive(Debug)]
+pub struct AmbiguousPost(pub String);
+
+impl fmt::Display for AmbiguousPost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for AmbiguousPost {}
+
+/// A redemption that was mined but not final when the wait for it ran out. Callers check `tx_hash` with
+/// `redemption_status` before redeeming again, so a slow confirmation doesn't turn into a second transaction.
+#[derive(Debug, Clone)]
+pub struct UnconfirmedRedemption {
+    pub tx_hash: String,
+    pub message: String,
+}
+
+impl fmt::Display for UnconfirmedRedemption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for UnconfirmedRedemption {}
+
+/// Where a redemption transaction left unconfirmed stands now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RedemptionStatus {
+    /// Final and successful: the positions are redeemed.
+    Redeemed,
+    /// Mined but not final yet; sending another would duplicate it.
+    Pending,
+    /// Reverted, or no longer on chain (reorged out): the redemption has to be sent again.
+    Failed,
+}
+
+/// One binary market as discovery finds it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VenueMarket {
+    /// What `resolved_winner` and `redeem` take: the condition id on Polymarket, the ticker on Kalshi.
+    pub id: String,
+    /// Token paying 1 when the price ends at or above the strike (Polymarket's Up, Kalshi's Yes).
+    pub yes_token: String,
+    pub no_token: String,
+    pub close_unix: i64,
+    /// Price the market resolves against, when the venue publishes it with the market.
+    pub strike: Option<f64>,
+}
+
+/// A venue listing binary markets whose outcome tokens pay 1 on the winning side.
+pub trait PredictionMarketVenue: Send + Sync {
+    fn get_orderbook<'a>(&'a self, token_id: &'a str) -> BoxFuture<'a, Result<OrderBook>>;
+
+    /// Books of several tokens by token id, in one request where the venue has a batch endpoint. Tokens without
+    /// a book are left out.
+    fn get_orderbooks<'a>(&'a self, token_ids: &'a [&'a str]) -> BoxFuture<'a, Result<HashMap<String, OrderBook>>> {
+        Box::pin(async move {
+            let books = futures_util::future::join_all(token_ids.iter().map(|id| self.get_orderbook(id))).await;
+            Ok(token_ids
+                .iter()
+                .zip(books)
+                .filter_map(|(id, book)| Some((id.to_string(), book.ok()?)))
+                .collect())
+        })
+    }
+
+    /// The open market of `series` closing at `close_unix`; None while the venue hasn't listed it. `series` is
+    /// in the venue's own terms: "btc-updown-15m" on Polymarket, "KXBTC15M" on Kalshi.
+    fn find_market<'a>(&'a self, series: &'a str, close_unix: i64) -> BoxFuture<'a, Result<Option<VenueMarket>>>;
+
+    fn place_order<'a>(&'a self, order: &'a OrderRequest) -> BoxFuture<'a, Result<OrderResponse>>;
+
+    /// The venue's clock in Unix seconds; local time unless the client syncs with the venue.
+    fn server_now_unix(&self) -> i64 {
+        chrono::Utc::now().timestamp()
+    }
+
+    fn cancel_order<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<()>>;
+
+    /// Cancel several orders, in one request where the venue has a batch endpoint.
+    fn cancel_orders<'a>(&'a self, order_ids: &'a [&'a str]) -> BoxFuture<'a, Result<CancelOutcome>> {
+        Box::pin(async move {
+            let results = futures_util::future::join_all(order_ids.iter().map(|id| self.cancel_order(id))).await;
+            let mut outcome = CancelOutcome::default();
+            for (id, result) in order_ids.iter().zip(results) {
+                match result {
+                    Ok(()) => outcome.canceled.push(id.to_string()),
+                    Err(e) => {
+                        outcome.not_canceled.insert(id.to_string(), e.to_string());
+                    }
+                }
+            }
+            Ok(outcome)
+        })
+    }
+
+    fn get_order_status<'a>(&'a self, order_id: &'a str) -> BoxFuture<'a, Result<OrderStatus>>;
+
+    /// The order `order` became if a post that failed with `AmbiguousPost` did reach the venue: a resting order
+    /// or a fill matching it, placed at or after `since_secs` (Unix). None when there is none, or the venue
+    /// can't tell; an error when the lookup itself failed and a retry could double the order.
+    fn find_submitted_order<'a>(
+        &'a self,
+        _order: &'a OrderRequest,
+        _since_secs: i64,
+    ) -> BoxFuture<'a, Result<Option<OrderResponse>>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Winning (token id, outcome) once the market has resolved; None while it is still open.
+    fn resolved_winner<'a>(&'a self, condition_id: &'a str) -> BoxFuture<'a, Result<Option<(String, String)>>>;
+
+    /// Redeem the winning `outcome` held in `condition_id` for collateral.
+    fn redeem<'a>(
+        &'a self,
+        condition_id: &'a str,
+        token_id: &'a str,
+        outcome: &'a str,
+    ) -> BoxFuture<'a, Result<RedeemResponse>>;
+
+    /// Where the redemption `tx_hash` of an `UnconfirmedRedemption` stands. `Failed` (send it again) on venues
+    /// that don't leave redemptions unconfirmed.
+    fn redemption_status<'a>(&'a self, _tx_hash: &'a str) -> BoxFuture<'a, Result<RedemptionStatus>> {
+        Box::pin(async { Ok(RedemptionStatus::Failed) })
+    }
+}
@@ -1,4 +1,13 @@
+use crate::adapters::signer::{signer_from_config, SharedSigner, TxSigner};
+use crate::adapters::venue::{AmbiguousPost, RedemptionStatus, UnconfirmedRedemption};
+use crate::config::{ContractAddresses, GasConfig, HttpConfig, Network, PolymarketConfig};
+use crate::domain::ticks::OrderRules;
+use crate::events::{ArbEvent, EventBus};
+use crate::domain::gas::{bump_fees, eip1559_fees, gas_limit_with_headroom, gwei_to_wei, GasPolicy};
 use crate::models::*;
+use crate::utils::proxy;
+use crate::utils::secret::{redact_url, Secret};
+use crate::utils::clock_offset::{offset_from_sample, parse_http_date_ms};
 use anyhow::{Context, Result};
 use reqwest::Client;
 use serde_json::Value;
@@ -6,22 +15,29 @@ use std::str::FromStr;
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 use hex;
-use log::{warn, error};
+use log::{info, warn, error};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 // Official SDK imports for proper order signing
 use polymarket_client_sdk::clob::{Client as ClobClient, Config as ClobConfig};
-use polymarket_client_sdk::clob::types::{Side, OrderType, SignatureType};
+use polymarket_client_sdk::clob::types::{OrderType as ClobOrderType, Side as ClobSide, SignatureType};
+use polymarket_client_sdk::auth::state::Authenticated;
+use polymarket_client_sdk::auth::Normal;
 use polymarket_client_sdk::POLYGON;
-use alloy::signers::local::LocalSigner;
+use alloy::network::EthereumWallet;
+use alloy::signers::local::PrivateKeySigner;
 use alloy::signers::Signer as _;
 use alloy::primitives::Address as AlloyAddress;
 use alloy::primitives::{Address, B256, U256, Bytes};
 use alloy::primitives::keccak256;
-use alloy::providers::{Provider, ProviderBuilder};
-use alloy::rpc::types::eth::TransactionRequest;
+use alloy::providers::fillers::TxFiller;
+use alloy::providers::{Provider, ProviderBuilder, ProviderLayer, RootProvider};
+use alloy::rpc::types::eth::{BlockNumberOrTag, Filter, TransactionRequest};
 use alloy::sol;
-use alloy_sol_types::SolCall;
+use alloy_sol_types::{eip712_domain, SolCall, SolStruct};
 
 sol! {
     interface IConditionalTokens {
@@ -31,43 +47,260 @@ sol! {
             bytes32 conditionId,
             uint256[] indexSets
         ) external;
+
+        function mergePositions(
+            address collateralToken,
+            bytes32 parentCollectionId,
+            bytes32 conditionId,
+            uint256[] partition,
+            uint256 amount
+        ) external;
+
+        function balanceOf(address account, uint256 id) external view returns (uint256);
+
+        function payoutNumerators(bytes32 conditionId, uint256 index) external view returns (uint256);
+
+        function payoutDenominator(bytes32 conditionId) external view returns (uint256);
+
+        function isApprovedForAll(address owner, address operator) external view returns (bool);
+
+        function setApprovalForAll(address operator, bool approved) external;
+    }
+
+    interface IProxyWalletFactory {
+        struct ProxyCall {
+            uint8 typeCode;
+            address to;
+            uint256 value;
+            bytes data;
+        }
+
+        function proxy(ProxyCall[] calls) external payable returns (bytes[] returnValues);
+    }
+
+    interface IGnosisSafe {
+        function getTransactionHash(
+            address to,
+            uint256 value,
+            bytes data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            uint256 _nonce
+        ) external view returns (bytes32);
+
+        function execTransaction(
+            address to,
+            uint256 value,
+            bytes data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            bytes signatures
+        ) external payable returns (bool success);
+    }
+
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+
+        function allowance(address owner, address spender) external view returns (uint256);
+
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
+
+    interface IMultiSend {
+        function multiSend(bytes transactions) external payable;
+    }
+
+    /// EIP-712 payload of CLOB L1 authentication (creating or deriving API credentials).
+    struct ClobAuth {
+        address address;
+        string timestamp;
+        uint256 nonce;
+        string message;
     }
 }
 
+const CLOB_AUTH_MESSAGE: &str = "This message attests that I control the given wallet";
+
+const DEFAULT_RPC_URL: &str = "https://polygon-rpc.com";
+const AMOY_RPC_URL: &str = "https://rpc-amoy.polygon.technology";
+const RPC_PROBE_TIMEOUT_SECS: u64 = 5;
+/// Block-depth polling interval while a redemption awaits finality (Polygon blocks are ~2s).
+const FINALITY_POLL_SECS: u64 = 4;
+const MAX_REORG_RESUBMISSIONS: u32 = 2;
+/// Outer gas limit for proxy/Safe redemptions: base + per redeemed condition.
+const REDEEM_GAS_BASE: u64 = 150_000;
+const REDEEM_GAS_PER_CALL: u64 = 250_000;
+const SAFE_TX_GAS_PER_REDEEM: u64 = 300_000;
+const GAMMA_PAGE_SIZE: usize = 500;
+const GAMMA_MAX_PAGES: usize = 10;
+
+/// Round trips per clock sync; the fastest one gives the tightest offset estimate.
+const CLOCK_SYNC_SAMPLES: usize = 3;
+/// Offsets beyond this are logged as a warning (HMAC timestamps are checked against server time).
+const CLOCK_OFFSET_WARN_MS: i64 = 2_000;
+
+/// USDC allowances below this (1M USDC, 6 decimals) are topped up to the maximum.
+const MIN_USDC_ALLOWANCE: u128 = 1_000_000_000_000;
+/// Up/Down markets have two outcome slots.
+const BINARY_OUTCOME_SLOTS: u64 = 2;
+/// Outcome tokens use 6 decimals like USDC.
+const TOKEN_DECIMALS_SCALE: f64 = 1_000_000.0;
+
 
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// An authenticated request refused as unauthorized (expired or revoked API credentials) after re-deriving the
+/// credentials didn't help, or after that failed. Later authenticated requests fail the same way without reaching
+/// the CLOB until restart.
+#[derive(Debug)]
+pub struct CredentialsRejected(pub String);
+
+impl std::fmt::Display for CredentialsRejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API credentials rejected: {}", self.0)
+    }
+}
+
+impl std::error::Error for CredentialsRejected {}
+
+/// A CLOB auth endpoint (`/auth/api-key`, `/auth/derive-api-key`) answered with an error status.
+#[derive(Debug)]
+struct AuthEndpointStatus(reqwest::StatusCode, String);
+
+impl std::fmt::Display for AuthEndpointStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.1)
+    }
+}
+
+impl std::error::Error for AuthEndpointStatus {}
+
+/// Whether re-deriving failed because the CLOB refused the key (400/401), rather than a transport error or an
+/// outage that a later attempt can get past.
+fn is_auth_refusal(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<AuthEndpointStatus>()
+        .is_some_and(|s| s.0 == reqwest::StatusCode::UNAUTHORIZED || s.0 == reqwest::StatusCode::BAD_REQUEST)
+}
+
+/// Attempts at re-deriving the credentials after a 401; the wait between attempts starts at
+/// `REDERIVE_BACKOFF_MS` and doubles.
+const REDERIVE_ATTEMPTS: u32 = 3;
+const REDERIVE_BACKOFF_MS: u64 = 500;
+
+/// Where the L2 credentials stand after 401s: they are re-derived whenever the ones in use are refused, until
+/// the CLOB refuses the re-derived ones too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CredentialHealth {
+    Configured,
+    Rederived,
+    Rejected,
+}
+
+/// Whether an SDK error is the CLOB answering 401.
+fn is_unauthorized(error: &polymarket_client_sdk::error::Error) -> bool {
+    use polymarket_client_sdk::error::{Status, StatusCode};
+    error
+        .downcast_ref::<Status>()
+        .is_some_and(|s| s.status_code == StatusCode::UNAUTHORIZED)
+}
+
+/// Authenticated CLOB client and order signer, built once and reused for every order so placement
+/// doesn't pay for a fresh API-key derivation round trip.
+struct TradingSession {
+    client: ClobClient<Authenticated<Normal>>,
+    signer: SharedSigner,
+}
+
 pub struct PolymarketApi {
     client: Client,
     gamma_url: String,
     clob_url: String,
     api_key: Option<String>,
-    api_secret: Option<String>,
-    api_passphrase: Option<String>,
-    private_key: Option<String>,
+    api_secret: Option<Secret>,
+    api_passphrase: Option<Secret>,
+    /// Nonce the credentials are re-derived with after a 401.
+    api_key_nonce: u64,
+    private_key: Option<Secret>,
+    /// Signs instead of `private_key` when set (see `adapters::signer`).
+    signer: Option<Arc<dyn TxSigner>>,
+    /// Why the configured signer couldn't be set up; nothing is signed then, not even with `private_key`.
+    signer_error: Option<String>,
     proxy_wallet_address: Option<String>,
     signature_type: Option<u8>,
-    rpc_url: Option<String>,
+    /// Polygon RPC endpoints in failover order.
+    rpc_urls: Vec<String>,
+    /// Index into `rpc_urls` of the endpoint that last worked.
+    active_rpc: AtomicUsize,
+    gas: GasConfig,
+    /// Per-category request timeouts; `client` was built with its connect and pool settings.
+    http: HttpConfig,
+    chain_id: u64,
+    /// CTF, collateral, exchange, and wallet-factory addresses for the configured network.
+    contracts: ContractAddresses,
+    /// Estimated CLOB server time minus local time (ms), applied to HMAC timestamps and expirations.
+    clock_offset_ms: AtomicI64,
     authenticated: Arc<tokio::sync::Mutex<bool>>,
+    /// Cached by `trading_session`; cleared when posting fails so the next order re-authenticates.
+    trading_session: tokio::sync::RwLock<Option<Arc<TradingSession>>>,
+    /// Parsed token ids, filled by `prepare_orders` ahead of the decision.
+    token_ids: std::sync::Mutex<HashMap<String, U256>>,
+    /// Tick size and minimum order size per token, read from its book on first use.
+    order_rules: std::sync::Mutex<HashMap<String, OrderRules>>,
+    /// Ids of orders this process placed or attributed to an ambiguous post, so `find_submitted_order` doesn't
+    /// hand one order to two legs.
+    claimed_orders: std::sync::Mutex<HashSet<String>>,
+    /// Credentials re-derived after a 401, used for HMAC headers instead of the configured ones.
+    derived_credentials: std::sync::RwLock<Option<ApiCredentials>>,
+    credential_health: tokio::sync::Mutex<CredentialHealth>,
+    /// Bumped on every re-derivation, so a 401 to a request signed with older credentials just retries.
+    credential_generation: AtomicU64,
+    /// Set with `CredentialHealth::Rejected`, so authenticated requests fail without waiting on the lock.
+    credentials_rejected: AtomicBool,
+    /// Bus for the `api_credentials` alert, set by `publish_alerts`.
+    alerts: std::sync::OnceLock<EventBus>,
+}
+
+/// What `PolymarketApi::new` connects with; the rest (gas, HTTP, network, signer) is set with the `with_*`
+/// methods.
+#[derive(Clone, Default)]
+pub struct ApiSettings {
+    pub gamma_url: String,
+    pub clob_url: String,
+    /// L2 credentials; all three are needed for HMAC-signed requests.
+    pub api_key: Option<String>,
+    pub api_secret: Option<Secret>,
+    pub api_passphrase: Option<Secret>,
+    pub private_key: Option<Secret>,
+    pub proxy_wallet_address: Option<String>,
+    pub signature_type: Option<u8>,
+    /// Tried in order; empty uses the public Polygon RPC.
+    pub rpc_urls: Vec<String>,
 }
 
 impl PolymarketApi {
-    pub fn new(
-        gamma_url: String,
-        clob_url: String,
-        api_key: Option<String>,
-        api_secret: Option<String>,
-        api_passphrase: Option<String>,
-        private_key: Option<String>,
-        proxy_wallet_address: Option<String>,
-        signature_type: Option<u8>,
-        rpc_url: Option<String>,
-    ) -> Self {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
+    pub fn new(settings: ApiSettings) -> Self {
+        let ApiSettings {
+            gamma_url,
+            clob_url,
+            api_key,
+            api_secret,
+            api_passphrase,
+            private_key,
+            proxy_wallet_address,
+            signature_type,
+            rpc_urls,
+        } = settings;
+        let http = HttpConfig::default();
+        let client = http_client(&http);
         Self {
             client,
             gamma_url,
@@ -75,23 +308,145 @@ impl PolymarketApi {
             api_key,
             api_secret,
             api_passphrase,
+            api_key_nonce: 0,
             private_key,
+            signer: None,
+            signer_error: None,
             proxy_wallet_address,
             signature_type,
-            rpc_url,
+            rpc_urls: if rpc_urls.is_empty() {
+                vec![DEFAULT_RPC_URL.to_string()]
+            } else {
+                rpc_urls
+            },
+            active_rpc: AtomicUsize::new(0),
+            gas: GasConfig::default(),
+            http,
+            chain_id: POLYGON,
+            contracts: Network::Polygon.default_contracts(),
+            clock_offset_ms: AtomicI64::new(0),
             authenticated: Arc::new(tokio::sync::Mutex::new(false)),
+            trading_session: tokio::sync::RwLock::new(None),
+            token_ids: std::sync::Mutex::new(HashMap::new()),
+            order_rules: std::sync::Mutex::new(HashMap::new()),
+            claimed_orders: std::sync::Mutex::new(HashSet::new()),
+            derived_credentials: std::sync::RwLock::new(None),
+            credential_health: tokio::sync::Mutex::new(CredentialHealth::Configured),
+            credential_generation: AtomicU64::new(0),
+            credentials_rejected: AtomicBool::new(false),
+            alerts: std::sync::OnceLock::new(),
+        }
+    }
+
+    /// Publish alerts (API credentials rejected) to `events`. Only the first bus set is used.
+    pub fn publish_alerts(&self, events: &EventBus) {
+        let _ = self.alerts.set(events.clone());
+    }
+
+    /// Client for `config`: endpoints, credentials or signer, RPCs, gas settings, and network contracts.
+    pub fn from_config(config: &PolymarketConfig) -> Self {
+        let api = Self::new(ApiSettings {
+            gamma_url: config.gamma_api_url.clone(),
+            clob_url: config.clob_api_url.clone(),
+            api_key: config.api_key.clone(),
+            api_secret: config.api_secret.clone(),
+            api_passphrase: config.api_passphrase.clone(),
+            private_key: config.private_key.clone(),
+            proxy_wallet_address: config.proxy_wallet_address.clone(),
+            signature_type: config.signature_type,
+            rpc_urls: config.rpc_url.clone(),
+        })
+        .with_gas_config(config.gas.clone())
+        .with_http_config(config.http.clone())
+        .with_network(config.network, config.contracts())
+        .with_api_key_nonce(config.api_key_nonce);
+        match signer_from_config(&config.signer) {
+            Ok(Some(signer)) => api.with_signer(signer),
+            Ok(None) => api,
+            Err(e) => {
+                error!("polymarket.signer: {:#}; orders and redemptions can't be signed", e);
+                Self {
+                    signer_error: Some(format!("{:#}", e)),
+                    ..api
+                }
+            }
+        }
+    }
+
+    /// Why the configured `polymarket.signer` is unusable, if it is; `bot::connect` refuses to start then.
+    pub fn signer_error(&self) -> Option<&str> {
+        self.signer_error.as_deref()
+    }
+
+    /// Sign with `signer` instead of the private key.
+    pub fn with_signer(mut self, signer: Arc<dyn TxSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Whether orders and transactions can be signed: a private key or an external signer is configured.
+    pub fn has_signer(&self) -> bool {
+        self.signer.is_some() || self.private_key.is_some()
+    }
+
+    /// EOA that signs orders and transactions.
+    pub fn signer_address(&self) -> Result<Address> {
+        Ok(self.signer()?.address())
+    }
+
+    /// The external signer, else one for the private key.
+    fn signer(&self) -> Result<SharedSigner> {
+        if let Some(e) = &self.signer_error {
+            anyhow::bail!("polymarket.signer: {}", e);
         }
+        let inner: Arc<dyn TxSigner> = match (&self.signer, &self.private_key) {
+            (Some(signer), _) => Arc::clone(signer),
+            (None, Some(private_key)) => Arc::new(
+                PrivateKeySigner::from_str(private_key.expose())
+                    .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?,
+            ),
+            (None, None) => anyhow::bail!(
+                "A private key or external signer is required for signing. Please set private_key or signer in config.json"
+            ),
+        };
+        Ok(SharedSigner::new(inner, Some(self.chain_id)))
+    }
+
+    /// Request timeouts and connection pool settings; rebuilds the HTTP client.
+    /// Re-derive the credentials with `nonce` (the one `create-api-key --nonce` used) after a 401.
+    pub fn with_api_key_nonce(mut self, nonce: u64) -> Self {
+        self.api_key_nonce = nonce;
+        self
+    }
+
+    pub fn with_http_config(mut self, http: HttpConfig) -> Self {
+        self.client = http_client(&http);
+        self.http = http;
+        self
+    }
+
+    /// Gas limit / EIP-1559 fee settings for redemption transactions.
+    pub fn with_gas_config(mut self, gas: GasConfig) -> Self {
+        self.gas = gas;
+        self
     }
     
+    /// Target `network` (chain id for signing) with the given contract addresses. On Amoy the public Amoy RPC
+    /// replaces the mainnet fallback when no rpc_url is configured.
+    pub fn with_network(mut self, network: Network, contracts: ContractAddresses) -> Self {
+        self.chain_id = network.chain_id();
+        self.contracts = contracts;
+        if network == Network::Amoy && self.rpc_urls == [DEFAULT_RPC_URL] {
+            self.rpc_urls = vec![AMOY_RPC_URL.to_string()];
+        }
+        self
+    }
+
     // Authenticate with Polymarket CLOB API
     pub async fn authenticate(&self) -> Result<()> {
-        let private_key = self.private_key.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Private key is required for authentication. Please set private_key in config.json"))?;
-        let signer = LocalSigner::from_str(private_key)
-            .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?
-            .with_chain_id(Some(POLYGON));
+        let signer = self.signer()?;
         
-        let mut auth_builder = ClobClient::new(&self.clob_url, ClobConfig::default())
+        let mut auth_builder = ClobClient::new(&self.clob_url, clob_config())
             .context("Failed to create CLOB client")?
             .authentication_builder(&signer);
         
@@ -141,6 +496,61 @@ impl PolymarketApi {
         Ok(())
     }
 
+    /// Estimate the local clock's offset from CLOB server time using the `Date` header of a few `/time`
+    /// round trips, and apply it to later HMAC timestamps and order expirations. Returns the offset in ms.
+    pub async fn sync_clock(&self) -> Result<i64> {
+        let url = format!("{}/time", self.clob_url);
+        let mut best: Option<(i64, i64)> = None;
+        for _ in 0..CLOCK_SYNC_SAMPLES {
+            let sent_ms = chrono::Utc::now().timestamp_millis();
+            let response = match self.client.get(&url).timeout(self.http.market_data_timeout()).send().await {
+                Ok(r) => r,
+                Err(e) => {
+                    warn!("Clock sync probe failed: {}", e);
+                    continue;
+                }
+            };
+            let received_ms = chrono::Utc::now().timestamp_millis();
+            let server_ms = response
+                .headers()
+                .get(reqwest::header::DATE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_http_date_ms);
+            // Without a Date header, fall back to the body (Unix seconds).
+            let server_ms = match server_ms {
+                Some(ms) => Some(ms),
+                None => response
+                    .text()
+                    .await
+                    .ok()
+                    .and_then(|body| body.trim().parse::<i64>().ok())
+                    .map(|secs| secs * 1000),
+            };
+            let Some(server_ms) = server_ms else {
+                continue;
+            };
+            let rtt = received_ms - sent_ms;
+            if best.is_none_or(|(best_rtt, _)| rtt < best_rtt) {
+                best = Some((rtt, offset_from_sample(sent_ms, received_ms, server_ms)));
+            }
+        }
+        let (rtt, offset) =
+            best.ok_or_else(|| anyhow::anyhow!("No usable server time from {}", url))?;
+        self.clock_offset_ms.store(offset, Ordering::Relaxed);
+        if offset.abs() >= CLOCK_OFFSET_WARN_MS {
+            warn!("Local clock is {} ms off CLOB server time (rtt {} ms); correcting timestamps", offset, rtt);
+        } else {
+            info!("Clock offset to CLOB server: {} ms (rtt {} ms)", offset, rtt);
+        }
+        Ok(offset)
+    }
+
+    /// Current CLOB server time (Unix seconds): local time corrected by the last `sync_clock` estimate.
+    pub fn server_now_unix(&self) -> i64 {
+        let now_ms = chrono::Utc::now().timestamp_millis() + self.clock_offset_ms.load(Ordering::Relaxed);
+        now_ms.div_euclid(1000)
+    }
+
     /// Generate HMAC-SHA256 signature for authenticated requests
     fn generate_signature(
         &self,
@@ -149,17 +559,12 @@ impl PolymarketApi {
         body: &str,
         timestamp: u64,
     ) -> Result<String> {
-        let secret = self.api_secret.as_ref()
+        let (_, secret, _) = self.l2_credentials()
             .ok_or_else(|| anyhow::anyhow!("API secret is required for authenticated requests"))?;
         
         let message = format!("{}{}{}{}", method, path, body, timestamp);
         
-        let secret_bytes = match base64::decode(secret) {
-            Ok(bytes) => bytes,
-            Err(_) => {
-                secret.as_bytes().to_vec()
-            }
-        };
+        let secret_bytes = decode_api_secret(secret.expose())?;
         
         // Create HMAC-SHA256 signature
         let mut mac = HmacSha256::new_from_slice(&secret_bytes)
@@ -179,31 +584,163 @@ impl PolymarketApi {
         path: &str,
         body: &str,
     ) -> Result<reqwest::RequestBuilder> {
-        if self.api_key.is_none() || self.api_secret.is_none() || self.api_passphrase.is_none() {
+        let Some((api_key, _, passphrase)) = self.l2_credentials() else {
             return Ok(request);
-        }
+        };
 
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let timestamp = self.server_now_unix() as u64;
         
         let signature = self.generate_signature(method, path, body, timestamp)?;
         
         let request = request
-            .header("POLY_API_KEY", self.api_key.as_ref().unwrap())
+            .header("POLY_API_KEY", api_key)
             .header("POLY_SIGNATURE", signature)
             .header("POLY_TIMESTAMP", timestamp.to_string())
-            .header("POLY_PASSPHRASE", self.api_passphrase.as_ref().unwrap());
+            .header("POLY_PASSPHRASE", passphrase.expose());
         
         Ok(request)
     }
 
+    /// API key, secret, and passphrase for HMAC headers: re-derived ones after a 401, else the configured ones.
+    fn l2_credentials(&self) -> Option<(String, Secret, Secret)> {
+        if let Some(derived) = self.derived_credentials.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            return Some((derived.api_key.clone(), derived.secret.clone(), derived.passphrase.clone()));
+        }
+        Some((self.api_key.clone()?, self.api_secret.clone()?, self.api_passphrase.clone()?))
+    }
+
+    /// Send an HMAC-signed `method path` (with `query`, and `body` as JSON when not empty). On a 401 the
+    /// credentials are re-derived and the request is sent once more (see `recover_credentials`).
+    async fn send_authenticated(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: &str,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}{}", self.clob_url, path);
+        let what = format!("{} {}", method, path);
+        self.ensure_credentials_usable(&what)?;
+        let mut retried = false;
+        loop {
+            let generation = self.credential_generation.load(Ordering::Acquire);
+            let mut request = self.client.request(method.clone(), &url).timeout(self.http.order_timeout()).query(query);
+            if !body.is_empty() {
+                request = request
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body.to_string());
+            }
+            let request = self.add_auth_headers(request, method.as_str(), path, body)?;
+            let response = request.send().await.context(format!("Failed to send {}", what))?;
+            if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+                return Ok(response);
+            }
+            if retried {
+                return Err(self.credentials_rejected(&what).await);
+            }
+            self.recover_credentials(&what, generation).await?;
+            retried = true;
+        }
+    }
+
+    /// Fail `what` without a request once the credentials were rejected for good.
+    fn ensure_credentials_usable(&self, what: &str) -> Result<()> {
+        if self.credentials_rejected.load(Ordering::Relaxed) {
+            return Err(CredentialsRejected(format!("{} not sent", what)).into());
+        }
+        Ok(())
+    }
+
+    /// `what`, signed with the credentials of `generation`, came back 401: re-derive the API credentials from the
+    /// signer and start a new trading session. Ok means retry the request. Only a refusal (400/401) from the auth
+    /// endpoints rejects the credentials for good and sends the `api_credentials` alert; transport errors and
+    /// outages are retried with backoff, and when they persist the request fails but the next 401 tries again.
+    async fn recover_credentials(&self, what: &str, generation: u64) -> Result<()> {
+        let mut health = self.credential_health.lock().await;
+        if *health == CredentialHealth::Rejected {
+            return Err(CredentialsRejected(what.to_string()).into());
+        }
+        // Re-derived by a concurrent request since this one was signed.
+        if self.credential_generation.load(Ordering::Acquire) != generation {
+            return Ok(());
+        }
+        warn!("{} was unauthorized; re-deriving the API credentials", what);
+        self.reset_trading_session().await;
+        let mut backoff = std::time::Duration::from_millis(REDERIVE_BACKOFF_MS);
+        let mut attempt = 1;
+        loop {
+            match self.create_or_derive_api_key(self.api_key_nonce).await {
+                Ok(credentials) => {
+                    info!("Re-derived API key {}", credentials.api_key);
+                    *self.derived_credentials.write().unwrap_or_else(|e| e.into_inner()) = Some(credentials);
+                    *health = CredentialHealth::Rederived;
+                    self.credential_generation.fetch_add(1, Ordering::AcqRel);
+                    return Ok(());
+                }
+                Err(e) if is_auth_refusal(&e) => {
+                    *health = CredentialHealth::Rejected;
+                    self.credentials_rejected.store(true, Ordering::Relaxed);
+                    let message = format!("{} was unauthorized and re-deriving the credentials failed: {}", what, e);
+                    self.alert_credentials(&message);
+                    return Err(CredentialsRejected(message).into());
+                }
+                Err(e) if attempt < REDERIVE_ATTEMPTS => {
+                    warn!("Re-deriving the API credentials failed ({}); retrying in {:?}", e, backoff);
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(e.context(format!("{} was unauthorized and the credentials couldn't be re-derived", what)))
+                }
+            }
+        }
+    }
+
+    /// `what` was unauthorized again with re-derived credentials.
+    async fn credentials_rejected(&self, what: &str) -> anyhow::Error {
+        let mut health = self.credential_health.lock().await;
+        let message = format!("{} is still unauthorized after re-deriving the credentials", what);
+        if *health != CredentialHealth::Rejected {
+            *health = CredentialHealth::Rejected;
+            self.credentials_rejected.store(true, Ordering::Relaxed);
+            self.alert_credentials(&message);
+        }
+        CredentialsRejected(message).into()
+    }
+
+    fn alert_credentials(&self, message: &str) {
+        error!("❌ {}; authenticated requests will fail until the credentials are fixed", message);
+        if let Some(events) = self.alerts.get() {
+            events.publish(ArbEvent::Alert {
+                kind: "api_credentials".to_string(),
+                subject: self.wallet_address().unwrap_or_else(|| "polymarket".to_string()),
+                message: message.to_string(),
+                ts_ms: chrono::Utc::now().timestamp_millis(),
+            });
+        }
+    }
+
+    /// Check the L2 API credentials with an authenticated request, re-deriving them when they are refused. Run at
+    /// startup and every `credential_check_secs`; Ok without a request when there are no credentials to check.
+    pub async fn check_credentials(&self) -> Result<()> {
+        if self.l2_credentials().is_none() {
+            return Ok(());
+        }
+        let response = self.send_authenticated(reqwest::Method::GET, "/auth/api-keys", &[], "").await?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("GET /auth/api-keys failed (status: {}): {}", status, error_text);
+        }
+        Ok(())
+    }
+
     // Get market by slug (e.g., "btc-updown-15m-1767726000")
     pub async fn get_market_by_slug(&self, slug: &str) -> Result<Market> {
         let url = format!("{}/events/slug/{}", self.gamma_url, slug);
         
-        let response = self.client.get(&url).send().await
+        let response = self.client.get(&url).timeout(self.http.market_data_timeout()).send().await
             .context(format!("Failed to fetch market by slug: {}", slug))?;
         
         let status = response.status();
@@ -225,6 +762,92 @@ impl PolymarketApi {
         anyhow::bail!("Invalid market response format: no markets array found")
     }
 
+    /// Open Gamma markets whose end date falls in [`end_min`, `end_max`] (ISO 8601), as raw JSON objects.
+    pub async fn get_gamma_markets_ending_between(&self, end_min: &str, end_max: &str) -> Result<Vec<Value>> {
+        let url = format!("{}/markets", self.gamma_url);
+        let mut markets = Vec::new();
+        for page in 0..GAMMA_MAX_PAGES {
+            let offset = (page * GAMMA_PAGE_SIZE).to_string();
+            let response = self
+                .client
+                .get(&url)
+                .timeout(self.http.market_data_timeout())
+                .query(&[
+                    ("active", "true"),
+                    ("closed", "false"),
+                    ("end_date_min", end_min),
+                    ("end_date_max", end_max),
+                    ("limit", &GAMMA_PAGE_SIZE.to_string()),
+                    ("offset", &offset),
+                ])
+                .send()
+                .await
+                .context("Failed to list Gamma markets")?;
+            if !response.status().is_success() {
+                anyhow::bail!("Failed to list Gamma markets (status: {})", response.status());
+            }
+            let json: Value = response.json().await.context("Failed to parse Gamma markets response")?;
+            let batch = json.as_array().cloned().unwrap_or_default();
+            let done = batch.len() < GAMMA_PAGE_SIZE;
+            markets.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(markets)
+    }
+
+    /// Winning (token_id, outcome) from Gamma's `outcomePrices` once the market is closed; None while unresolved.
+    pub async fn get_gamma_winner(&self, condition_id: &str) -> Result<Option<(String, String)>> {
+        let url = format!("{}/markets", self.gamma_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(self.http.market_data_timeout())
+            .query(&[("condition_ids", condition_id)])
+            .send()
+            .await
+            .context(format!("Failed to fetch Gamma market for condition_id: {}", condition_id))?;
+        if !response.status().is_success() {
+            anyhow::bail!("Failed to fetch Gamma market (status: {})", response.status());
+        }
+        let json: Value = response.json().await.context("Failed to parse Gamma markets response")?;
+        let Some(market) = json.as_array().and_then(|a| a.first()) else {
+            return Ok(None);
+        };
+        if !market.get("closed").and_then(|v| v.as_bool()).unwrap_or(false) {
+            return Ok(None);
+        }
+        // Outcome prices already move to 1/0 while a UMA proposal is pending or disputed; only trust
+        // them once the oracle has settled (markets listed without the field are taken as settled).
+        let uma_status = market.get("umaResolutionStatus").and_then(|v| v.as_str());
+        if uma_status.is_some_and(|s| !s.eq_ignore_ascii_case("resolved")) {
+            return Ok(None);
+        }
+        // Gamma encodes these arrays as JSON strings, e.g. "[\"Up\", \"Down\"]", but plain arrays
+        // and numeric prices are accepted too.
+        let list = |key: &str| -> Vec<String> {
+            let items: Vec<Value> = match market.get(key) {
+                Some(Value::String(s)) => serde_json::from_str(s).unwrap_or_default(),
+                Some(Value::Array(a)) => a.clone(),
+                _ => Vec::new(),
+            };
+            items
+                .into_iter()
+                .map(|v| match v {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                })
+                .collect()
+        };
+        let outcomes = list("outcomes");
+        let token_ids = list("clobTokenIds");
+        let winner = list("outcomePrices")
+            .iter()
+            .position(|p| p.parse::<f64>().map(|p| p >= 0.999).unwrap_or(false));
+        Ok(winner.and_then(|i| Some((token_ids.get(i)?.clone(), outcomes.get(i)?.clone()))))
+    }
+
     /// Fetch price-to-beat (openPrice) from Polymarket crypto-price API.
     /// Not available immediately at market start: 15m ~2 min, 5m ~30 sec. Call after delay and poll.
     /// variant: "fifteen" for 15m market, "fiveminute" for 5m market (per Polymarket platform).
@@ -240,6 +863,7 @@ impl PolymarketApi {
         let req = self
             .client
             .get(CRYPTO_PRICE_URL)
+            .timeout(self.http.market_data_timeout())
             .query(&[
                 ("symbol", symbol),
                 ("eventStartTime", event_start_iso),
@@ -272,6 +896,7 @@ impl PolymarketApi {
         let response = self
             .client
             .get(&url)
+            .timeout(self.http.market_data_timeout())
             .query(&params)
             .send()
             .await
@@ -285,6 +910,66 @@ impl PolymarketApi {
         Ok(orderbook)
     }
 
+    /// Books of several tokens in one `POST /books`, by token id. Tokens the CLOB has no book for are left out.
+    pub async fn get_orderbooks(&self, token_ids: &[&str]) -> Result<HashMap<String, OrderBook>> {
+        let url = format!("{}/books", self.clob_url);
+        let body: Vec<Value> = token_ids
+            .iter()
+            .map(|token_id| serde_json::json!({ "token_id": token_id }))
+            .collect();
+
+        let response = self
+            .client
+            .post(&url)
+            .timeout(self.http.market_data_timeout())
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to fetch orderbooks")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch orderbooks (status: {})", status);
+        }
+
+        let books: Vec<Value> = response
+            .json()
+            .await
+            .context("Failed to parse orderbooks response")?;
+
+        books
+            .into_iter()
+            .map(|book| {
+                let asset_id = book
+                    .get("asset_id")
+                    .and_then(|a| a.as_str())
+                    .ok_or_else(|| anyhow::anyhow!("Orderbook without asset_id"))?
+                    .to_string();
+                let book: OrderBook = serde_json::from_value(book)
+                    .context(format!("Failed to parse orderbook for token {}", asset_id))?;
+                Ok((asset_id, book))
+            })
+            .collect()
+    }
+
+    /// Tick size and minimum order size for `token_id`, from its book; cached after the first fetch.
+    pub async fn order_rules(&self, token_id: &str) -> Result<OrderRules> {
+        if let Some(rules) = self.order_rules.lock().unwrap().get(token_id) {
+            return Ok(*rules);
+        }
+        let book = self
+            .get_orderbook(token_id)
+            .await
+            .context(format!("Failed to fetch order rules for token {}", token_id))?;
+        let defaults = OrderRules::default();
+        let rules = OrderRules {
+            tick_size: book.tick_size.filter(|t| !t.is_zero()).unwrap_or(defaults.tick_size),
+            min_size: book.min_order_size.unwrap_or(defaults.min_size),
+        };
+        self.order_rules.lock().unwrap().insert(token_id.to_string(), rules);
+        Ok(rules)
+    }
+
     /// Get market details by condition ID
     pub async fn get_market(&self, condition_id: &str) -> Result<MarketDetails> {
         let url = format!("{}/markets/{}", self.clob_url, condition_id);
@@ -292,6 +977,7 @@ impl PolymarketApi {
         let response = self
             .client
             .get(&url)
+            .timeout(self.http.market_data_timeout())
             .send()
             .await
             .context(format!("Failed to fetch market for condition_id: {}", condition_id))?;
@@ -327,6 +1013,7 @@ impl PolymarketApi {
         let response = self
             .client
             .get(&url)
+            .timeout(self.http.market_data_timeout())
             .query(&params)
             .send()
             .await
@@ -354,117 +1041,323 @@ impl PolymarketApi {
         Ok(price)
     }
 
-    // Get best bid/ask prices for a token (from orderbook)
-    pub async fn get_best_price(&self, token_id: &str) -> Result<Option<TokenPrice>> {
-        let orderbook = self.get_orderbook(token_id).await?;
-        
-        let best_bid = orderbook.bids.first().map(|b| b.price);
-        let best_ask = orderbook.asks.first().map(|a| a.price);
+    /// Midpoint between the best bid and ask of `token_id`.
+    pub async fn get_midpoint(&self, token_id: &str) -> Result<Midpoint> {
+        let url = format!("{}/midpoint", self.clob_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(self.http.market_data_timeout())
+            .query(&[("token_id", token_id)])
+            .send()
+            .await
+            .context("Failed to fetch midpoint")?;
 
-        if best_ask.is_some() {
-            Ok(Some(TokenPrice {
-                token_id: token_id.to_string(),
-                bid: best_bid,
-                ask: best_ask,
-            }))
-        } else {
-            Ok(None)
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch midpoint (status: {})", status);
         }
+
+        response.json().await.context("Failed to parse midpoint response")
     }
 
-    // Place an order
-    pub async fn place_order(&self, order: &OrderRequest) -> Result<OrderResponse> {
-        let private_key = self.private_key.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Private key is required for order signing. Please set private_key in config.json"))?;
-        
-        let signer = LocalSigner::from_str(private_key)
-            .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?
-            .with_chain_id(Some(POLYGON));
-        
-        let mut auth_builder = ClobClient::new(&self.clob_url, ClobConfig::default())
-            .context("Failed to create CLOB client")?
-            .authentication_builder(&signer);
-        
-        if let Some(proxy_addr) = &self.proxy_wallet_address {
-            let funder_address = AlloyAddress::parse_checksummed(proxy_addr, None)
-                .context(format!("Failed to parse proxy_wallet_address: {}. Ensure it's a valid Ethereum address.", proxy_addr))?;
-            
-            auth_builder = auth_builder.funder(funder_address);
-            
-            let sig_type = match self.signature_type {
-                Some(1) => SignatureType::Proxy,
-                Some(2) => SignatureType::GnosisSafe,
-                Some(0) | None => SignatureType::Proxy, // Default to Proxy when proxy wallet is set
-                Some(n) => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
-            };
-            
-            auth_builder = auth_builder.signature_type(sig_type);
-        } else if let Some(sig_type_num) = self.signature_type {
-            // If signature type is set but no proxy wallet, validate it's EOA
-            let sig_type = match sig_type_num {
-                0 => SignatureType::Eoa,
-                1 | 2 => anyhow::bail!("signature_type {} requires proxy_wallet_address to be set", sig_type_num),
-                n => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
-            };
-            auth_builder = auth_builder.signature_type(sig_type);
-        }
-        
-        // Create CLOB client with authentication
-        let client = auth_builder
-            .authenticate()
+    /// Best ask minus best bid of `token_id`.
+    pub async fn get_spread(&self, token_id: &str) -> Result<Spread> {
+        let url = format!("{}/spread", self.clob_url);
+        let response = self
+            .client
+            .get(&url)
+            .timeout(self.http.market_data_timeout())
+            .query(&[("token_id", token_id)])
+            .send()
             .await
-            .context("Failed to authenticate with CLOB API. Check your API credentials.")?;
+            .context("Failed to fetch spread")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch spread (status: {})", status);
+        }
+
+        response.json().await.context("Failed to parse spread response")
+    }
+
+    /// Prices of `token_id` every `interval_mins` minutes over `range` (Unix seconds, start and end), from the
+    /// CLOB `/prices-history`. Closed markets keep their history.
+    pub async fn get_price_history(
+        &self,
+        token_id: &str,
+        interval_mins: u32,
+        range: (i64, i64),
+    ) -> Result<Vec<PricePoint>> {
+        let url = format!("{}/prices-history", self.clob_url);
+        let (start, end, fidelity) = (range.0.to_string(), range.1.to_string(), interval_mins.max(1).to_string());
+        let response = self
+            .client
+            .get(&url)
+            .timeout(self.http.market_data_timeout())
+            .query(&[
+                ("market", token_id),
+                ("startTs", start.as_str()),
+                ("endTs", end.as_str()),
+                ("fidelity", fidelity.as_str()),
+            ])
+            .send()
+            .await
+            .context("Failed to fetch price history")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch price history (status: {})", status);
+        }
+
+        let json: Value = response.json().await.context("Failed to parse price history response")?;
+        let history = json.get("history").cloned().unwrap_or_else(|| Value::Array(Vec::new()));
+        serde_json::from_value(history).context("Failed to parse price history points")
+    }
+
+    /// `get_price` for several tokens in one `POST /prices`, by token id. Tokens without a price on `side` are
+    /// left out.
+    pub async fn get_prices(&self, token_ids: &[&str], side: &str) -> Result<HashMap<String, rust_decimal::Decimal>> {
+        let url = format!("{}/prices", self.clob_url);
+        let body: Vec<Value> = token_ids
+            .iter()
+            .map(|token_id| serde_json::json!({ "token_id": token_id, "side": side }))
+            .collect();
+
+        let response = self
+            .client
+            .post(&url)
+            .timeout(self.http.market_data_timeout())
+            .json(&body)
+            .send()
+            .await
+            .context("Failed to fetch prices")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            anyhow::bail!("Failed to fetch prices (status: {})", status);
+        }
+
+        // {"<token_id>": {"BUY": "0.52"}, ...}
+        let json: Value = response
+            .json()
+            .await
+            .context("Failed to parse prices response")?;
+        let by_token = json
+            .as_object()
+            .ok_or_else(|| anyhow::anyhow!("Invalid prices response format"))?;
+
+        let mut prices = HashMap::with_capacity(by_token.len());
+        for (token_id, sides) in by_token {
+            let Some(price) = sides.get(side) else {
+                continue;
+            };
+            let price_str = price
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| price.to_string());
+            let price = rust_decimal::Decimal::from_str(&price_str)
+                .context(format!("Failed to parse price for token {}: {}", token_id, price_str))?;
+            prices.insert(token_id.clone(), price);
+        }
+
+        log::debug!("Batch prices (side={}) for {} of {} token(s)", side, prices.len(), token_ids.len());
+
+        Ok(prices)
+    }
+
+    // Get best bid/ask prices for a token (from orderbook)
+    pub async fn get_best_price(&self, token_id: &str) -> Result<Option<TokenPrice>> {
+        let orderbook = self.get_orderbook(token_id).await?;
         
-        let side = match order.side.as_str() {
-            "BUY" => Side::Buy,
-            "SELL" => Side::Sell,
-            _ => anyhow::bail!("Invalid order side: {}. Must be 'BUY' or 'SELL'", order.side),
-        };
+        let best_bid = orderbook.bids.first().map(|b| b.price);
+        let best_ask = orderbook.asks.first().map(|a| a.price);
+
+        if best_ask.is_some() {
+            Ok(Some(TokenPrice {
+                token_id: token_id.to_string(),
+                bid: best_bid,
+                ask: best_ask,
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    // Place an order
+    /// The cached authenticated CLOB client and signer, authenticating on first use.
+    async fn trading_session(&self) -> Result<Arc<TradingSession>> {
+        if let Some(session) = self.trading_session.read().await.as_ref() {
+            return Ok(Arc::clone(session));
+        }
+        let mut slot = self.trading_session.write().await;
+        if let Some(session) = slot.as_ref() {
+            return Ok(Arc::clone(session));
+        }
+        let session = Arc::new(self.new_trading_session().await?);
+        *slot = Some(Arc::clone(&session));
+        Ok(session)
+    }
+
+    /// Drop the cached session after a failed post; it may hold stale credentials.
+    async fn reset_trading_session(&self) {
+        *self.trading_session.write().await = None;
+    }
+
+    async fn new_trading_session(&self) -> Result<TradingSession> {
+        let signer = self.signer()?;
+        
+        let mut auth_builder = ClobClient::new(&self.clob_url, clob_config())
+            .context("Failed to create CLOB client")?
+            .authentication_builder(&signer);
+        
+        if let Some(proxy_addr) = &self.proxy_wallet_address {
+            let funder_address = AlloyAddress::parse_checksummed(proxy_addr, None)
+                .context(format!("Failed to parse proxy_wallet_address: {}. Ensure it's a valid Ethereum address.", proxy_addr))?;
+            
+            auth_builder = auth_builder.funder(funder_address);
+            
+            let sig_type = match self.signature_type {
+                Some(1) => SignatureType::Proxy,
+                Some(2) => SignatureType::GnosisSafe,
+                Some(0) | None => SignatureType::Proxy, // Default to Proxy when proxy wallet is set
+                Some(n) => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
+            };
+            
+            auth_builder = auth_builder.signature_type(sig_type);
+        } else if let Some(sig_type_num) = self.signature_type {
+            // If signature type is set but no proxy wallet, validate it's EOA
+            let sig_type = match sig_type_num {
+                0 => SignatureType::Eoa,
+                1 | 2 => anyhow::bail!("signature_type {} requires proxy_wallet_address to be set", sig_type_num),
+                n => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
+            };
+            auth_builder = auth_builder.signature_type(sig_type);
+        }
+        
+        let client = auth_builder
+            .authenticate()
+            .await
+            .context("Failed to authenticate with CLOB API. Check your API credentials.")?;
+        
+        Ok(TradingSession { client, signer })
+    }
+
+    fn token_id_u256(&self, token_id: &str) -> Result<U256> {
+        if let Some(id) = self.token_ids.lock().unwrap_or_else(|e| e.into_inner()).get(token_id) {
+            return Ok(*id);
+        }
+        let id = if token_id.starts_with("0x") {
+            U256::from_str_radix(token_id.trim_start_matches("0x"), 16)
+        } else {
+            U256::from_str_radix(token_id, 10)
+        }.context(format!("Failed to parse token_id as U256: {}", token_id))?;
+        self.token_ids.lock().unwrap_or_else(|e| e.into_inner()).insert(token_id.to_string(), id);
+        Ok(id)
+    }
+
+    /// Get everything but price and size ready for orders on `token_ids` before the decision: authenticate
+    /// and cache the client and signer, parse the token ids, and build (not sign or post) a throwaway
+    /// order per token so the SDK's tick-size, neg-risk, and fee-rate lookups are cached. Our own order rules
+    /// (tick and minimum size) are fetched here too.
+    pub async fn prepare_orders(&self, token_ids: &[&str]) -> Result<()> {
+        let session = self.trading_session().await?;
+        for token_id in token_ids {
+            self.order_rules(token_id).await?;
+            let id = self.token_id_u256(token_id)?;
+            session
+                .client
+                .limit_order()
+                .token_id(id)
+                .size(rust_decimal::Decimal::ONE)
+                .price(rust_decimal::Decimal::new(5, 1))
+                .side(ClobSide::Buy)
+                .build()
+                .await
+                .context(format!("Failed to prepare order template for token {}", token_id))?;
+        }
+        Ok(())
+    }
+
+    pub async fn place_order(&self, order: &OrderRequest) -> Result<OrderResponse> {
+        let started_at_ms = chrono::Utc::now().timestamp_millis();
         
         let price = rust_decimal::Decimal::from_str(&order.price)
             .context(format!("Failed to parse price: {}", order.price))?;
         let size = rust_decimal::Decimal::from_str(&order.size)
             .context(format!("Failed to parse size: {}", order.size))?;
+        let rules = self.order_rules(&order.token_id).await?;
+        let price = rules.round_price(price, order.side == Side::Buy)
+            .context(format!("Order for token {} rejected before submission", order.token_id))?;
+        let size = rules.round_size(size)
+            .context(format!("Order for token {} rejected before submission", order.token_id))?;
         
         eprintln!("📤 Creating and posting order: {} {} {} @ {}", 
-              order.side, order.size, order.token_id, order.price);
+              order.side, size, order.token_id, price);
 
-        let token_id_u256 = if order.token_id.starts_with("0x") {
-            U256::from_str_radix(order.token_id.trim_start_matches("0x"), 16)
-        } else {
-            U256::from_str_radix(&order.token_id, 10)
-        }.context(format!("Failed to parse token_id as U256: {}", order.token_id))?;
+        let token_id_u256 = self.token_id_u256(&order.token_id)?;
+        let expires_at = match &order.expiration {
+            Some(expiration) => Some(
+                expiration.parse::<i64>().ok()
+                    .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+                    .ok_or_else(|| anyhow::anyhow!("Invalid order expiration: {}", expiration))?,
+            ),
+            None => None,
+        };
 
-        let order_builder = client
-            .limit_order()
-            .token_id(token_id_u256)
-            .size(size)
-            .price(price)
-            .side(side);
-        
-        let signed_order = client.sign(&signer, order_builder.build().await?)
-            .await
-            .context("Failed to sign order")?;
-        
-        // Post order and capture detailed error information
-        let response = match client.post_order(signed_order).await {
-            Ok(resp) => resp,
-            Err(e) => {
-                // Log the full error details for debugging
-                error!("❌ Failed to post order. Error details: {:?}", e);
-                anyhow::bail!(
-                    "Failed to post order: {}\n\
-                    \n\
-                    Troubleshooting:\n\
-                    1. Check if you have sufficient USDC balance\n\
-                    2. Verify the token_id is valid and active\n\
-                    3. Check if the price is within valid range\n\
-                    4. Ensure your API credentials have trading permissions\n\
-                    5. Verify the order size meets minimum requirements",
-                    e
-                );
+        // A 401 means the order wasn't taken: re-derive the credentials and post once more on a new session.
+        self.ensure_credentials_usable("POST /order")?;
+        let mut retried = false;
+        let (response, signed_at_ms) = loop {
+            let generation = self.credential_generation.load(Ordering::Acquire);
+            let session = self.trading_session().await?;
+            let client = &session.client;
+            let mut order_builder = client
+                .limit_order()
+                .token_id(token_id_u256)
+                .size(size)
+                .price(price)
+                .side(clob_side(order.side))
+                .order_type(clob_order_type(order.order_type));
+            if let Some(expires_at) = expires_at {
+                order_builder = order_builder.expiration(expires_at);
+            }
+
+            let signed_order = client.sign(&session.signer, order_builder.build().await?)
+                .await
+                .context("Failed to sign order")?;
+            let signed_at_ms = chrono::Utc::now().timestamp_millis();
+
+            // Post order and capture detailed error information
+            match client.post_order(signed_order).await {
+                Ok(resp) => break (resp, signed_at_ms),
+                Err(e) if is_unauthorized(&e) => {
+                    if retried {
+                        return Err(self.credentials_rejected("POST /order").await);
+                    }
+                    self.recover_credentials("POST /order", generation).await?;
+                    retried = true;
+                }
+                Err(e) => {
+                    // Log the full error details for debugging
+                    error!("❌ Failed to post order. Error details: {:?}", e);
+                    self.reset_trading_session().await;
+                    // No answer from the CLOB: the order may still have been taken.
+                    return Err(anyhow::Error::new(AmbiguousPost(format!(
+                        "Failed to post order: {}\n\
+                        \n\
+                        Troubleshooting:\n\
+                        1. Check if you have sufficient USDC balance\n\
+                        2. Verify the token_id is valid and active\n\
+                        3. Check if the price is within valid range\n\
+                        4. Ensure your API credentials have trading permissions\n\
+                        5. Verify the order size meets minimum requirements",
+                        e
+                    ))));
+                }
             }
         };
+        let acked_at_ms = chrono::Utc::now().timestamp_millis();
         
         // Check if the response indicates failure even if the request succeeded
         if !response.success {
@@ -488,11 +1381,18 @@ impl PolymarketApi {
             );
         }
         
+        self.claim_order(&response.order_id);
+
         // Convert SDK response to our OrderResponse format
         let order_response = OrderResponse {
             order_id: Some(response.order_id.clone()),
             status: response.status.to_string(),
             message: Some(format!("Order placed successfully. Order ID: {}", response.order_id)),
+            latency: Some(OrderLatency {
+                started_at_ms,
+                signed_at_ms,
+                acked_at_ms,
+            }),
         };
         
         eprintln!("✅ Order placed successfully! Order ID: {}", response.order_id);
@@ -500,77 +1400,154 @@ impl PolymarketApi {
         Ok(order_response)
     }
 
+    /// Mark `order_id` as belonging to a leg; false when it already did.
+    fn claim_order(&self, order_id: &str) -> bool {
+        self.claimed_orders.lock().unwrap_or_else(|e| e.into_inner()).insert(order_id.to_string())
+    }
+
+    fn is_claimed(&self, order_id: &str) -> bool {
+        self.claimed_orders.lock().unwrap_or_else(|e| e.into_inner()).contains(order_id)
+    }
+
+    /// The order `order` became if its post failed ambiguously, placed at or after `since_secs`: a resting order
+    /// with the token, side, price, and size `place_order` rounds it to, or a taker order of the same token and
+    /// side matched at the limit or better for that size (for FAK, at most that size). Both come from the CLOB's
+    /// authenticated listings, which show a match at once rather than after settlement. The CLOB has no client
+    /// order ids, so orders are told apart by these fields, and ids already claimed by another leg are skipped.
+    pub async fn find_submitted_order(
+        &self,
+        order: &OrderRequest,
+        since_secs: i64,
+    ) -> Result<Option<OrderResponse>> {
+        use rust_decimal::Decimal;
+        if self.l2_credentials().is_none() {
+            anyhow::bail!("Looking up an order needs api_key, api_secret, and api_passphrase in config.json");
+        }
+        let buy = order.side == Side::Buy;
+        let price = Decimal::from_str(&order.price).context(format!("Failed to parse price: {}", order.price))?;
+        let size = Decimal::from_str(&order.size).context(format!("Failed to parse size: {}", order.size))?;
+        let rules = self.order_rules(&order.token_id).await?;
+        let (price, size) = (rules.round_price(price, buy)?, rules.round_size(size)?);
+        let decimal = |value: &str| Decimal::from_str(value).ok();
+
+        let resting = self.get_open_orders(None).await?.into_iter().find(|o| {
+            o.asset_id == order.token_id
+                && o.side == order.side
+                && decimal(&o.price) == Some(price)
+                && decimal(&o.original_size) == Some(size)
+                && o.created_at.is_none_or(|t| t >= since_secs)
+                && !self.is_claimed(&o.id)
+        });
+        if let Some(resting) = resting {
+            self.claim_order(&resting.id);
+            return Ok(Some(OrderResponse {
+                order_id: Some(resting.id),
+                status: if resting.status.is_empty() { "live".to_string() } else { resting.status },
+                message: Some("Found resting after an ambiguous post".to_string()),
+                latency: None,
+            }));
+        }
+
+        // Matched at once (FOK/FAK, or a marketable GTC): one trade per maker it crossed, summed per taker order.
+        let mut matched: Vec<(String, Decimal)> = Vec::new();
+        for trade in self.get_clob_trades(&order.token_id, since_secs).await? {
+            let at_limit = decimal(&trade.price).is_some_and(|p| if buy { p <= price } else { p >= price });
+            if !trade.trader_side.eq_ignore_ascii_case("TAKER")
+                || trade.side != order.side
+                || trade.status.eq_ignore_ascii_case("FAILED")
+                || !at_limit
+                || trade.match_time.parse::<i64>().is_ok_and(|t| t < since_secs)
+                || self.is_claimed(&trade.taker_order_id)
+            {
+                continue;
+            }
+            let traded = decimal(&trade.size).unwrap_or_default();
+            match matched.iter_mut().find(|(id, _)| *id == trade.taker_order_id) {
+                Some((_, total)) => *total += traded,
+                None => matched.push((trade.taker_order_id, traded)),
+            }
+        }
+        let fits = |total: Decimal| match order.order_type {
+            OrderType::Fak => total > Decimal::ZERO && total <= size,
+            _ => total == size,
+        };
+        let Some((order_id, _)) = matched.into_iter().find(|(_, total)| fits(*total)) else {
+            return Ok(None);
+        };
+        self.claim_order(&order_id);
+        Ok(Some(OrderResponse {
+            order_id: Some(order_id),
+            status: "matched".to_string(),
+            message: Some("Found filled after an ambiguous post".to_string()),
+            latency: None,
+        }))
+    }
+
+    /// This account's CLOB trades in `asset_id` matched at or after `after_secs`, from the authenticated
+    /// `/data/trades` listing, following its cursor to the last page.
+    pub async fn get_clob_trades(&self, asset_id: &str, after_secs: i64) -> Result<Vec<ClobTrade>> {
+        // The CLOB's cursor after the last page.
+        const END_CURSOR: &str = "LTE=";
+        let path = "/data/trades";
+        let after = after_secs.to_string();
+        let mut trades = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut params = vec![("asset_id", asset_id), ("after", after.as_str())];
+            if !cursor.is_empty() {
+                params.push(("next_cursor", &cursor));
+            }
+            let response = self
+                .send_authenticated(reqwest::Method::GET, path, &params, "")
+                .await
+                .context("Failed to fetch CLOB trades")?;
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to fetch CLOB trades (status: {}): {}", status, error_text);
+            }
+            let page: Value = response.json().await.context("Failed to parse CLOB trades response")?;
+            let data = page.get("data").cloned().unwrap_or_else(|| Value::Array(Vec::new()));
+            let data: Vec<ClobTrade> = serde_json::from_value(data).context("Failed to parse CLOB trades")?;
+            trades.extend(data);
+            match page.get("next_cursor").and_then(|c| c.as_str()) {
+                Some(next) if !next.is_empty() && next != END_CURSOR && next != cursor => cursor = next.to_string(),
+                _ => break,
+            }
+        }
+        Ok(trades)
+    }
+
     // Place a market order (FOK/FAK) for immediate execution
     pub async fn place_market_order(
         &self,
         token_id: &str,
         amount: f64,
-        side: &str,
-        order_type: Option<&str>, // "FOK" or "FAK", defaults to FOK
+        side: Side,
+        order_type: Option<OrderType>, // FOK or FAK, defaults to FOK
     ) -> Result<OrderResponse> {
-        let private_key = self.private_key.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Private key is required for order signing. Please set private_key in config.json"))?;
-        
-        let signer = LocalSigner::from_str(private_key)
-            .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?
-            .with_chain_id(Some(POLYGON));
-        
-        let mut auth_builder = ClobClient::new(&self.clob_url, ClobConfig::default())
-            .context("Failed to create CLOB client")?
-            .authentication_builder(&signer);
-        
-        if let Some(proxy_addr) = &self.proxy_wallet_address {
-            let funder_address = AlloyAddress::parse_checksummed(proxy_addr, None)
-                .context(format!("Failed to parse proxy_wallet_address: {}. Ensure it's a valid Ethereum address.", proxy_addr))?;
-            
-            auth_builder = auth_builder.funder(funder_address);
-            
-            let sig_type = match self.signature_type {
-                Some(1) => SignatureType::Proxy,
-                Some(2) => SignatureType::GnosisSafe,
-                Some(0) | None => SignatureType::Proxy, // Default to Proxy when proxy wallet is set
-                Some(n) => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
-            };
-            
-            auth_builder = auth_builder.signature_type(sig_type);
-        } else if let Some(sig_type_num) = self.signature_type {
-            // If signature type is set but no proxy wallet, validate it's EOA
-            let sig_type = match sig_type_num {
-                0 => SignatureType::Eoa,
-                1 | 2 => anyhow::bail!("signature_type {} requires proxy_wallet_address to be set", sig_type_num),
-                n => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
-            };
-            auth_builder = auth_builder.signature_type(sig_type);
-        }
-        
-        let client = auth_builder
-            .authenticate()
-            .await
-            .context("Failed to authenticate with CLOB API. Check your API credentials.")?;
+        let session = self.trading_session().await?;
+        let client = &session.client;
         
-        let side_enum = match side {
-            "BUY" => Side::Buy,
-            "SELL" => Side::Sell,
-            _ => anyhow::bail!("Invalid order side: {}. Must be 'BUY' or 'SELL'", side),
-        };
+        let side_enum = clob_side(side);
         
-        let order_type_enum = match order_type.unwrap_or("FOK") {
-            "FOK" => OrderType::FOK,
-            "FAK" => OrderType::FAK,
-            _ => OrderType::FOK, // Default to FOK
+        let order_type_enum = match order_type.unwrap_or(OrderType::Fok) {
+            OrderType::Fak => ClobOrderType::FAK,
+            _ => ClobOrderType::FOK, // Default to FOK
         };
         
-        use rust_decimal::{Decimal, RoundingStrategy};
-        use rust_decimal::prelude::*;
+        use rust_decimal::Decimal;
         
-        let amount_decimal = Decimal::from_f64_retain(amount)
-            .ok_or_else(|| anyhow::anyhow!("Failed to convert amount to Decimal"))?
-            .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero);
+        let rules = self.order_rules(token_id).await?;
+        let amount_decimal = rules.round_size(
+            Decimal::from_f64_retain(amount)
+                .ok_or_else(|| anyhow::anyhow!("Failed to convert amount to Decimal"))?,
+        )?;
         
         eprintln!("📤 Creating and posting MARKET order: {} {} {} (type: {:?})", 
               side, amount_decimal, token_id, order_type_enum);
         
-        let market_price = if matches!(side_enum, Side::Buy) {
+        let market_price = if side == Side::Buy {
             self.get_price(token_id, "SELL")
                 .await
                 .context("Failed to fetch ASK price for BUY order")?
@@ -583,11 +1560,7 @@ impl PolymarketApi {
         
         eprintln!("   Using current market price: ${:.4} for {} order", market_price, side);
 
-        let token_id_u256 = if token_id.starts_with("0x") {
-            U256::from_str_radix(token_id.trim_start_matches("0x"), 16)
-        } else {
-            U256::from_str_radix(token_id, 10)
-        }.context(format!("Failed to parse token_id as U256: {}", token_id))?;
+        let token_id_u256 = self.token_id_u256(token_id)?;
 
         let order_builder = client
             .limit_order()
@@ -596,35 +1569,30 @@ impl PolymarketApi {
             .price(market_price)
             .side(side_enum);
         
-        let signed_order = client.sign(&signer, order_builder.build().await?)
+        let signed_order = client.sign(&session.signer, order_builder.build().await?)
             .await
             .context("Failed to sign market order")?;
         
-        let final_price = if matches!(side_enum, Side::Sell) {
-            let price_f64 = f64::try_from(market_price).unwrap_or(0.0);
-            let adjusted_f64 = price_f64 * 0.995;
-            let rounded_f64 = (adjusted_f64 * 100.0).round() / 100.0;
-            let final_f64 = rounded_f64.max(0.01);
-            Decimal::from_f64_retain(final_f64)
-                .ok_or_else(|| anyhow::anyhow!("Failed to convert adjusted price to Decimal"))?
-                .round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+        let final_price = if side == Side::Sell {
+            // Half a percent under the bid, down to the tick grid but never below one tick.
+            let adjusted = (market_price * Decimal::new(995, 3) / rules.tick_size).floor() * rules.tick_size;
+            adjusted.max(rules.tick_size).normalize()
         } else {
-            // For BUY orders, also ensure 2 decimal places
-            market_price.round_dp_with_strategy(2, RoundingStrategy::MidpointAwayFromZero)
+            rules.round_price(market_price, true)?
         };
         
         // If price was adjusted, rebuild the order
-        let signed_order = if matches!(side_enum, Side::Sell) && final_price != market_price {
+        let signed_order = if final_price != market_price {
             let final_price_f64 = f64::try_from(final_price).unwrap_or(0.0);
             let market_price_f64 = f64::try_from(market_price).unwrap_or(0.0);
-            eprintln!("   ⚠️  Adjusting SELL price from ${:.4} to ${:.4} for immediate execution", market_price_f64, final_price_f64);
+            eprintln!("   ⚠️  Adjusting {} price from ${:.4} to ${:.4} (tick grid)", side, market_price_f64, final_price_f64);
             let adjusted_builder = client
                 .limit_order()
                 .token_id(token_id_u256)
                 .size(amount_decimal)
                 .price(final_price)
                 .side(side_enum);
-            client.sign(&signer, adjusted_builder.build().await?)
+            client.sign(&session.signer, adjusted_builder.build().await?)
                 .await
                 .context("Failed to sign adjusted market order")?
         } else {
@@ -641,6 +1609,7 @@ impl PolymarketApi {
             Err(e) => {
                 // Log the full error for debugging
                 error!("❌ SDK post_order error: {:?}", e);
+                self.reset_trading_session().await;
                 anyhow::bail!(
                     "Failed to post market order: {:?}\n\
                     \n\
@@ -670,6 +1639,7 @@ impl PolymarketApi {
             } else {
                 response.error_msg.clone()
             },
+            latency: None,
         };
         
         if response.success {
@@ -702,54 +1672,216 @@ impl PolymarketApi {
     
     /// Cancel an order by order ID
     pub async fn cancel_order(&self, order_id: &str) -> Result<()> {
-        let _private_key = self.private_key.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Private key is required for order cancellation. Please set private_key in config.json"))?;
-        
-        let signer = LocalSigner::from_str(_private_key)
-            .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?
-            .with_chain_id(Some(POLYGON));
-        
-        let mut auth_builder = ClobClient::new(&self.clob_url, ClobConfig::default())
-            .context("Failed to create CLOB client")?
-            .authentication_builder(&signer);
+        let session = self.trading_session().await?;
+        session.client.cancel_order(order_id).await
+            .context(format!("Failed to cancel order {}", order_id))?;
         
-        if let Some(proxy_addr) = &self.proxy_wallet_address {
-            let funder_address = AlloyAddress::parse_checksummed(proxy_addr, None)
-                .context(format!("Failed to parse proxy_wallet_address: {}. Ensure it's a valid Ethereum address.", proxy_addr))?;
-            
-            auth_builder = auth_builder.funder(funder_address);
-            
-            let sig_type = match self.signature_type {
-                Some(1) => SignatureType::Proxy,
-                Some(2) => SignatureType::GnosisSafe,
-                Some(0) | None => SignatureType::Proxy,
-                Some(n) => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
-            };
-            auth_builder = auth_builder.signature_type(sig_type);
-        } else if let Some(sig_type_num) = self.signature_type {
-            let sig_type = match sig_type_num {
-                0 => SignatureType::Eoa,
-                1 | 2 => anyhow::bail!("signature_type {} requires proxy_wallet_address to be set", sig_type_num),
-                n => anyhow::bail!("Invalid signature_type: {}. Must be 0 (EOA), 1 (Proxy), or 2 (GnosisSafe)", n),
+        Ok(())
+    }
+
+    /// Cancel several orders in one `DELETE /orders` request. Without the L2 API credentials, falls back to one
+    /// cancel per order through the trading session.
+    pub async fn cancel_orders(&self, order_ids: &[&str]) -> Result<CancelOutcome> {
+        if order_ids.is_empty() {
+            return Ok(CancelOutcome::default());
+        }
+        if self.api_key.is_none() || self.api_secret.is_none() || self.api_passphrase.is_none() {
+            let mut outcome = CancelOutcome::default();
+            for id in order_ids {
+                match self.cancel_order(id).await {
+                    Ok(()) => outcome.canceled.push(id.to_string()),
+                    Err(e) => {
+                        outcome.not_canceled.insert(id.to_string(), e.to_string());
+                    }
+                }
+            }
+            return Ok(outcome);
+        }
+        let body = serde_json::to_string(order_ids).context("Failed to serialize order ids")?;
+        self.send_cancel("/orders", body).await
+    }
+
+    /// Cancel every resting order of the account in `condition_id` (both outcomes) in one request. Needs the
+    /// L2 API credentials.
+    pub async fn cancel_market_orders(&self, condition_id: &str) -> Result<CancelOutcome> {
+        if self.api_key.is_none() || self.api_secret.is_none() || self.api_passphrase.is_none() {
+            anyhow::bail!("Cancelling a market's orders needs api_key, api_secret, and api_passphrase in config.json");
+        }
+        let body = serde_json::json!({ "market": condition_id, "asset_id": "" }).to_string();
+        self.send_cancel("/cancel-market-orders", body).await
+    }
+
+    /// Signed `DELETE path` with a JSON `body`, parsing the CLOB's canceled / not_canceled reply.
+    async fn send_cancel(&self, path: &str, body: String) -> Result<CancelOutcome> {
+        let response = self.send_authenticated(reqwest::Method::DELETE, path, &[], &body).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("DELETE {} failed (status: {}): {}", path, status, error_text);
+        }
+        response.json().await.context("Failed to parse cancel response")
+    }
+
+    /// The account's resting orders, in `market` (a condition id) when given. Needs the L2 API credentials
+    /// (`api_key`, `api_secret`, `api_passphrase`).
+    pub async fn get_open_orders(&self, market: Option<&str>) -> Result<Vec<OpenOrder>> {
+        // The CLOB's cursor after the last page.
+        const END_CURSOR: &str = "LTE=";
+        if self.api_key.is_none() || self.api_secret.is_none() || self.api_passphrase.is_none() {
+            anyhow::bail!("Listing open orders needs api_key, api_secret, and api_passphrase in config.json");
+        }
+        let path = "/data/orders";
+        let mut orders = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut params: Vec<(&str, &str)> = Vec::new();
+            if let Some(market) = market {
+                params.push(("market", market));
+            }
+            if !cursor.is_empty() {
+                params.push(("next_cursor", &cursor));
+            }
+            let response = self
+                .send_authenticated(reqwest::Method::GET, path, &params, "")
+                .await
+                .context("Failed to fetch open orders")?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response.text().await.unwrap_or_default();
+                anyhow::bail!("Failed to fetch open orders (status: {}): {}", status, error_text);
+            }
+
+            let page: Value = response.json().await.context("Failed to parse open orders response")?;
+            let data = page.get("data").cloned().unwrap_or_else(|| Value::Array(Vec::new()));
+            let data: Vec<OpenOrder> = serde_json::from_value(data).context("Failed to parse open orders")?;
+            orders.extend(data);
+            match page.get("next_cursor").and_then(|c| c.as_str()) {
+                Some(next) if !next.is_empty() && next != END_CURSOR && next != cursor => cursor = next.to_string(),
+                _ => break,
+            }
+        }
+        Ok(orders)
+    }
+
+    /// Balance and exchange allowance of the collateral or of outcome token `token_id`, from the CLOB's
+    /// `/balance-allowance`. The CLOB resolves the funder from the signature type, so proxy and Safe
+    /// wallets report their own balance. Needs the L2 API credentials.
+    pub async fn get_balance_allowance(&self, asset: BalanceAsset, token_id: Option<&str>) -> Result<BalanceAllowance> {
+        if self.api_key.is_none() || self.api_secret.is_none() || self.api_passphrase.is_none() {
+            anyhow::bail!("Querying balance/allowance needs api_key, api_secret, and api_passphrase in config.json");
+        }
+        // Same defaults as `authenticate`: a proxy wallet without a valid signature type trades as Proxy.
+        let signature_type = match (&self.proxy_wallet_address, self.signature_type) {
+            (Some(_), Some(2)) => 2,
+            (Some(_), _) => 1,
+            (None, _) => 0,
+        }
+        .to_string();
+        let path = "/balance-allowance";
+        let mut params = vec![("asset_type", asset.as_str()), ("signature_type", signature_type.as_str())];
+        if let Some(token_id) = token_id {
+            params.push(("token_id", token_id));
+        }
+        let response = self
+            .send_authenticated(reqwest::Method::GET, path, &params, "")
+            .await
+            .context("Failed to fetch balance/allowance")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Failed to fetch balance/allowance (status: {}): {}", status, error_text);
+        }
+
+        let body: Value = response.json().await.context("Failed to parse balance/allowance response")?;
+        // Amounts are integer strings in 6-decimal base units.
+        let amount = |v: &Value| -> Option<f64> {
+            let raw = match v {
+                Value::String(s) => s.parse::<f64>().ok()?,
+                v => v.as_f64()?,
             };
-            auth_builder = auth_builder.signature_type(sig_type);
+            Some(raw / TOKEN_DECIMALS_SCALE)
+        };
+        let balance = body
+            .get("balance")
+            .and_then(amount)
+            .context("balance/allowance response has no balance")?;
+        // One allowance per exchange contract (`allowances`), or a single `allowance` from older deployments.
+        let allowance = match body.get("allowances").and_then(|a| a.as_object()) {
+            Some(allowances) => allowances.values().filter_map(amount).fold(None, |min: Option<f64>, a| {
+                Some(min.map_or(a, |m| m.min(a)))
+            }),
+            None => body.get("allowance").and_then(amount),
         }
-        
-        let client = auth_builder
-            .authenticate()
+        .unwrap_or(0.0);
+        Ok(BalanceAllowance { balance, allowance })
+    }
+
+    /// L2 API credentials for the signer, tied to `nonce`: `POST /auth/api-key` creates them, and when the key
+    /// already has credentials for that nonce `GET /auth/derive-api-key` returns the existing ones. Both are
+    /// authenticated with an EIP-712 `ClobAuth` signature (L1 headers) rather than HMAC.
+    pub async fn create_or_derive_api_key(&self, nonce: u64) -> Result<ApiCredentials> {
+        match self.l1_auth_request(reqwest::Method::POST, "/auth/api-key", nonce).await {
+            Ok(credentials) => Ok(credentials),
+            Err(e) => {
+                info!("Creating API key failed ({}); deriving the existing one", e);
+                self.l1_auth_request(reqwest::Method::GET, "/auth/derive-api-key", nonce).await
+            }
+        }
+    }
+
+    async fn l1_auth_request(&self, method: reqwest::Method, path: &str, nonce: u64) -> Result<ApiCredentials> {
+        let signer = self.signer()?;
+        let address = signer.address();
+        let timestamp = self.server_now_unix().to_string();
+        let auth = ClobAuth {
+            address,
+            timestamp: timestamp.clone(),
+            nonce: U256::from(nonce),
+            message: CLOB_AUTH_MESSAGE.to_string(),
+        };
+        let domain = eip712_domain! {
+            name: "ClobAuthDomain",
+            version: "1",
+            chain_id: self.chain_id,
+        };
+        let signature = signer
+            .sign_hash(&auth.eip712_signing_hash(&domain))
             .await
-            .context("Failed to authenticate with CLOB API. Check your API credentials.")?;
-        
-        client.cancel_order(order_id).await
-            .context(format!("Failed to cancel order {}", order_id))?;
-        
-        Ok(())
+            .context("Failed to sign CLOB auth message")?;
+        let url = format!("{}{}", self.clob_url, path);
+        let response = self
+            .client
+            .request(method, &url)
+            .timeout(self.http.order_timeout())
+            .header("POLY_ADDRESS", address.to_string())
+            .header("POLY_SIGNATURE", format!("0x{}", hex::encode(signature.as_bytes())))
+            .header("POLY_TIMESTAMP", timestamp)
+            .header("POLY_NONCE", nonce.to_string())
+            .send()
+            .await
+            .context(format!("Failed to call {}", path))?;
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            let message = format!("{} failed (status: {}): {}", path, status, error_text);
+            return Err(AuthEndpointStatus(status, message).into());
+        }
+        response.json().await.context(format!("Failed to parse {} response", path))
     }
 
     /// Fetch order status (e.g. size_matched) to verify fill. Uses data API.
     pub async fn get_order_status(&self, order_id: &str) -> Result<OrderStatus> {
         let url = format!("https://data-api.polymarket.com/order/{}", order_id.trim_start_matches("0x"));
-        let response = self.client.get(&url).send().await.context("Failed to fetch order status")?;
+        let response = self
+            .client
+            .get(&url)
+            .timeout(self.http.data_api_timeout())
+            .send()
+            .await
+            .context("Failed to fetch order status")?;
         if !response.status().is_success() {
             anyhow::bail!("Order status request failed: {}", response.status());
         }
@@ -765,7 +1897,7 @@ impl PolymarketApi {
         let body = serde_json::to_string(order)
             .context("Failed to serialize order to JSON")?;
         
-        let mut request = self.client.post(&url).json(order);
+        let mut request = self.client.post(&url).timeout(self.http.order_timeout()).json(order);
         
         request = self.add_auth_headers(request, "POST", path, &body)
             .context("Failed to add authentication headers")?;
@@ -773,40 +1905,322 @@ impl PolymarketApi {
         eprintln!("📤 Posting order to Polymarket (HMAC): {} {} {} @ {}", 
               order.side, order.size, order.token_id, order.price);
 
-        let response = request
-            .send()
+        let response = request
+            .send()
+            .await
+            .context("Failed to place order")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            
+            if status == 401 || status == 403 {
+                anyhow::bail!(
+                    "Authentication failed (status: {}): {}\n\
+                    Troubleshooting:\n\
+                    1. Verify your API credentials (api_key, api_secret, api_passphrase) are correct\n\
+                    2. Verify your private_key is correct (required for order signing)\n\
+                    3. Check if your API key has trading permissions\n\
+                    4. Ensure your account has sufficient balance",
+                    status, error_text
+                );
+            }
+            
+            anyhow::bail!("Failed to place order (status: {}): {}", status, error_text);
+        }
+
+        let order_response: OrderResponse = response
+            .json()
+            .await
+            .context("Failed to parse order response")?;
+
+        eprintln!("✅ Order placed successfully: {:?}", order_response);
+        Ok(order_response)
+    }
+
+    /// Address holding positions: proxy wallet if configured, otherwise the signer's EOA.
+    pub fn wallet_address(&self) -> Option<String> {
+        if let Some(proxy) = &self.proxy_wallet_address {
+            return Some(proxy.clone());
+        }
+        Some(self.signer_address().ok()?.to_string())
+    }
+
+    /// Fetch the wallet's fills (data API trades) for the given condition IDs.
+    pub async fn get_fills(&self, wallet: &str, condition_ids: &[&str]) -> Result<Vec<Fill>> {
+        self.get_trade_history(&TradeHistoryParams {
+            user: wallet.to_string(),
+            markets: condition_ids.iter().map(|c| c.to_string()).collect(),
+            taker_only: false,
+            limit: 0,
+        })
+        .await
+    }
+
+    /// The wallet's fills from the data API trades listing, newest first, following the offset cursor page by
+    /// page until `params.limit` fills or the last page.
+    pub async fn get_trade_history(&self, params: &TradeHistoryParams) -> Result<Vec<Fill>> {
+        const PAGE_SIZE: usize = 500;
+        let url = "https://data-api.polymarket.com/trades";
+        let markets = &params.markets.join(",");
+        let taker_only = &params.taker_only.to_string();
+        paginate(PAGE_SIZE, params.limit, |page_size, offset| async move {
+            let (limit, offset_param) = (page_size.to_string(), offset.to_string());
+            let mut query = vec![
+                ("user", params.user.as_str()),
+                ("limit", limit.as_str()),
+                ("offset", offset_param.as_str()),
+                ("takerOnly", taker_only.as_str()),
+            ];
+            if !markets.is_empty() {
+                query.push(("market", markets.as_str()));
+            }
+            let response = self.client
+                .get(url)
+                .timeout(self.http.data_api_timeout())
+                .query(&query)
+                .send()
+                .await
+                .context("Failed to fetch fills")?;
+            if !response.status().is_success() {
+                anyhow::bail!("Data API returned {} for fills", response.status());
+            }
+            response.json::<Vec<Fill>>().await.context("Failed to parse fills response")
+        })
+        .await
+    }
+
+    /// The wallet's trades from the data API activity feed since `start` (Unix seconds), oldest first.
+    pub async fn get_activity_trades(&self, wallet: &str, start: u64) -> Result<Vec<Fill>> {
+        let url = "https://data-api.polymarket.com/activity";
+        let start = start.to_string();
+        let response = self.client
+            .get(url)
+            .timeout(self.http.data_api_timeout())
+            .query(&[
+                ("user", wallet),
+                ("type", "TRADE"),
+                ("start", start.as_str()),
+                ("limit", "500"),
+                ("sortBy", "TIMESTAMP"),
+                ("sortDirection", "ASC"),
+            ])
+            .send()
+            .await
+            .context("Failed to fetch activity")?;
+        if !response.status().is_success() {
+            anyhow::bail!("Data API returned {} for activity", response.status());
+        }
+        let fills: Vec<Fill> = response.json().await.context("Failed to parse activity response")?;
+        Ok(fills)
+    }
+
+    /// On-chain ERC-1155 balance (shares) of an outcome token held by `owner`, via CTF.balanceOf.
+    pub async fn get_ctf_balance(&self, owner: &str, token_id: &str) -> Result<f64> {
+        let owner = Address::from_str(owner)
+            .context(format!("Failed to parse owner address: {}", owner))?;
+        let id = if token_id.starts_with("0x") {
+            U256::from_str_radix(token_id.trim_start_matches("0x"), 16)
+        } else {
+            U256::from_str_radix(token_id, 10)
+        }
+        .context(format!("Failed to parse token_id as U256: {}", token_id))?;
+        let raw = self
+            .ctf_call(IConditionalTokens::balanceOfCall { account: owner, id })
+            .await
+            .context("CTF.balanceOf call failed")?;
+        Ok(f64::from(raw) / TOKEN_DECIMALS_SCALE)
+    }
+
+    /// USDC balance of `owner`.
+    pub async fn get_usdc_balance(&self, owner: &str) -> Result<f64> {
+        let owner = Address::from_str(owner)
+            .context(format!("Failed to parse owner address: {}", owner))?;
+        let usdc = Address::from_str(&self.contracts.usdc).context("Failed to parse USDC address")?;
+        let raw = self
+            .contract_call(usdc, IERC20::balanceOfCall { account: owner })
+            .await
+            .context("USDC.balanceOf call failed")?;
+        Ok(f64::from(raw) / TOKEN_DECIMALS_SCALE)
+    }
+
+    /// Most recent external USDC.e transfer into `wallet` within the last `lookback_blocks` blocks, as
+    /// (block number, amount in USDC). Used to hold trading while a deposit settles.
+    pub async fn recent_usdc_inbound(&self, wallet: &str, lookback_blocks: u64) -> Result<Option<(u64, f64)>> {
+        let wallet = Address::from_str(wallet).context(format!("Failed to parse wallet address: {}", wallet))?;
+        let usdc = Address::from_str(&self.contracts.usdc).context("Failed to parse USDC address")?;
+        let filter = Filter::new()
+            .address(usdc)
+            .event_signature(keccak256(b"Transfer(address,address,uint256)"))
+            .topic2(wallet.into_word());
+        let logs = self
+            .with_rpc_failover("USDC transfer lookup", |rpc_url| {
+                let filter = filter.clone();
+                async move {
+                    let provider = connect_rpc(ProviderBuilder::new(), &rpc_url).await
+                        .context("Failed to connect to Polygon RPC")?;
+                    let latest = provider.get_block_number().await.context("eth_blockNumber failed")?;
+                    let filter = filter.from_block(latest.saturating_sub(lookback_blocks));
+                    provider
+                        .get_logs(&filter)
+                        .await
+                        .context("eth_getLogs failed for USDC transfers")
+                }
+            })
+            .await?;
+        // Redemption payouts (from the CTF) and trade settlements (from the exchanges) aren't deposits.
+        let c = &self.contracts;
+        let internal: Vec<Address> = [&c.ctf, &c.ctf_exchange, &c.neg_risk_ctf_exchange]
+            .iter()
+            .filter_map(|a| Address::from_str(a).ok())
+            .collect();
+        Ok(logs.iter().rev().find_map(|log| {
+            let from = Address::from_word(*log.topics().get(1)?);
+            if internal.contains(&from) {
+                return None;
+            }
+            let block = log.block_number?;
+            let data = log.data().data.as_ref();
+            let amount = U256::from_be_slice(data.get(..32)?);
+            Some((block, f64::from(amount) / TOKEN_DECIMALS_SCALE))
+        }))
+    }
+
+    /// Winning outcome index from the CTF payout vector; None until the oracle has reported, or when the
+    /// payout is split rather than going to a single outcome.
+    pub async fn get_onchain_winner_index(&self, condition_id: &str) -> Result<Option<usize>> {
+        let condition_id_b256 = B256::from_str(condition_id.strip_prefix("0x").unwrap_or(condition_id))
+            .context(format!("Failed to parse condition_id as B256: {}", condition_id))?;
+        let denominator = self
+            .ctf_call(IConditionalTokens::payoutDenominatorCall { conditionId: condition_id_b256 })
             .await
-            .context("Failed to place order")?;
+            .context("CTF.payoutDenominator call failed")?;
+        if denominator.is_zero() {
+            return Ok(None);
+        }
+        for index in 0..BINARY_OUTCOME_SLOTS {
+            let numerator = self
+                .ctf_call(IConditionalTokens::payoutNumeratorsCall {
+                    conditionId: condition_id_b256,
+                    index: U256::from(index),
+                })
+                .await
+                .context("CTF.payoutNumerators call failed")?;
+            if numerator == denominator {
+                return Ok(Some(index as usize));
+            }
+        }
+        Ok(None)
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            
-            if status == 401 || status == 403 {
-                anyhow::bail!(
-                    "Authentication failed (status: {}): {}\n\
-                    Troubleshooting:\n\
-                    1. Verify your API credentials (api_key, api_secret, api_passphrase) are correct\n\
-                    2. Verify your private_key is correct (required for order signing)\n\
-                    3. Check if your API key has trading permissions\n\
-                    4. Ensure your account has sufficient balance",
-                    status, error_text
-                );
+    /// Read-only call against the CTF contract.
+    async fn ctf_call<C: SolCall>(&self, call: C) -> Result<C::Return> {
+        let ctf_address = Address::from_str(&self.contracts.ctf).context("Failed to parse CTF contract address")?;
+        self.contract_call(ctf_address, call).await
+    }
+
+    /// Read-only call against `to`, with RPC failover.
+    async fn contract_call<C: SolCall>(&self, to: Address, call: C) -> Result<C::Return> {
+        let calldata = Bytes::from(call.abi_encode());
+        let result = self
+            .with_rpc_failover("Contract call", |rpc_url| {
+                let tx = TransactionRequest::default()
+                    .to(to)
+                    .input(calldata.clone().into());
+                async move {
+                    let provider = connect_rpc(ProviderBuilder::new(), &rpc_url).await
+                        .context("Failed to connect to Polygon RPC")?;
+                    Ok(provider.call(tx).await?)
+                }
+            })
+            .await?;
+        C::abi_decode_returns(&result).context(format!("Failed to decode call result from {}", to))
+    }
+
+    /// RPC endpoints starting from the one that last worked.
+    fn rpc_rotation(&self) -> Vec<String> {
+        let start = self.active_rpc.load(Ordering::Relaxed);
+        let n = self.rpc_urls.len();
+        (0..n).map(|i| self.rpc_urls[(start + i) % n].clone()).collect()
+    }
+
+    fn mark_rpc_active(&self, rpc_url: &str) {
+        if let Some(i) = self.rpc_urls.iter().position(|u| u == rpc_url) {
+            if self.active_rpc.swap(i, Ordering::Relaxed) != i {
+                warn!("RPC failover: now using {}", redact_url(rpc_url));
             }
-            
-            anyhow::bail!("Failed to place order (status: {}): {}", status, error_text);
         }
+    }
 
-        let order_response: OrderResponse = response
-            .json()
-            .await
-            .context("Failed to parse order response")?;
+    /// Run `op` against each RPC endpoint in turn until one succeeds; connection errors, bad responses,
+    /// and timeouts move on to the next endpoint. The endpoint that worked is tried first next time.
+    async fn with_rpc_failover<T, F, Fut>(&self, what: &str, op: F) -> Result<T>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut last_err = None;
+        for rpc_url in self.rpc_rotation() {
+            let attempt = tokio::time::timeout(
+                self.http.rpc_timeout(),
+                op(rpc_url.clone()),
+            );
+            match attempt.await {
+                Ok(Ok(value)) => {
+                    self.mark_rpc_active(&rpc_url);
+                    return Ok(value);
+                }
+                Ok(Err(e)) => {
+                    warn!("{} via {} failed: {}", what, redact_url(&rpc_url), e);
+                    last_err = Some(e);
+                }
+                Err(_) => {
+                    warn!("{} via {} timed out", what, redact_url(&rpc_url));
+                    last_err = Some(anyhow::anyhow!("{} via {} timed out", what, redact_url(&rpc_url)));
+                }
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No RPC endpoints configured")))
+    }
 
-        eprintln!("✅ Order placed successfully: {:?}", order_response);
-        Ok(order_response)
+    /// First endpoint answering eth_blockNumber, for multi-step flows (redemption) that must stay on one node.
+    async fn healthy_rpc_url(&self) -> Result<String> {
+        for rpc_url in self.rpc_rotation() {
+            let probe = async {
+                let provider = connect_rpc(ProviderBuilder::new(), &rpc_url).await?;
+                provider.get_block_number().await?;
+                anyhow::Ok(())
+            };
+            match tokio::time::timeout(std::time::Duration::from_secs(RPC_PROBE_TIMEOUT_SECS), probe).await {
+                Ok(Ok(())) => {
+                    self.mark_rpc_active(&rpc_url);
+                    return Ok(rpc_url);
+                }
+                Ok(Err(e)) => warn!("RPC {} unavailable: {}", redact_url(&rpc_url), e),
+                Err(_) => warn!("RPC {} timed out", redact_url(&rpc_url)),
+            }
+        }
+        anyhow::bail!(
+            "No reachable Polygon RPC endpoint (tried {:?})",
+            self.rpc_urls.iter().map(|u| redact_url(u)).collect::<Vec<_>>()
+        )
     }
 
     pub async fn get_redeemable_positions(&self, wallet: &str) -> Result<Vec<String>> {
+        let mut condition_ids: Vec<String> = self
+            .get_redeemable_targets(wallet)
+            .await?
+            .into_iter()
+            .map(|(condition_id, _)| condition_id)
+            .collect();
+        condition_ids.sort();
+        condition_ids.dedup();
+        Ok(condition_ids)
+    }
+
+    /// Redeemable positions held by `wallet` as (condition_id, outcome) pairs, so the caller can build
+    /// the right index set for each; outcome defaults to "Up" when the Data API omits it.
+    pub async fn get_redeemable_targets(&self, wallet: &str) -> Result<Vec<(String, String)>> {
         let url = "https://data-api.polymarket.com/positions";
         let user = if wallet.starts_with("0x") {
             wallet.to_string()
@@ -815,6 +2229,7 @@ impl PolymarketApi {
         };
         let response = self.client
             .get(url)
+            .timeout(self.http.data_api_timeout())
             .query(&[("user", user.as_str()), ("redeemable", "true"), ("limit", "500")])
             .send()
             .await
@@ -823,7 +2238,7 @@ impl PolymarketApi {
             anyhow::bail!("Data API returned {} for redeemable positions", response.status());
         }
         let positions: Vec<Value> = response.json().await.unwrap_or_default();
-        let mut condition_ids: Vec<String> = positions
+        let mut targets: Vec<(String, String)> = positions
             .iter()
             .filter(|p| {
                 // Only include positions where the wallet actually holds tokens (size > 0)
@@ -833,13 +2248,17 @@ impl PolymarketApi {
                     .or_else(|| p.get("size").and_then(|s| s.as_str()).and_then(|s| s.parse::<f64>().ok()));
                 size.map(|s| s > 0.0).unwrap_or(false)
             })
-            .filter_map(|p| p.get("conditionId").and_then(|c| c.as_str()).map(|s| {
-                if s.starts_with("0x") { s.to_string() } else { format!("0x{}", s) }
-            }))
+            .filter_map(|p| {
+                let condition_id = p.get("conditionId").and_then(|c| c.as_str()).map(|s| {
+                    if s.starts_with("0x") { s.to_string() } else { format!("0x{}", s) }
+                })?;
+                let outcome = p.get("outcome").and_then(|o| o.as_str()).unwrap_or("Up").to_string();
+                Some((condition_id, outcome))
+            })
             .collect();
-        condition_ids.sort();
-        condition_ids.dedup();
-        Ok(condition_ids)
+        targets.sort();
+        targets.dedup();
+        Ok(targets)
     }
 
     pub async fn redeem_tokens(
@@ -848,84 +2267,257 @@ impl PolymarketApi {
         _token_id: &str,
         outcome: &str,
     ) -> Result<RedeemResponse> {
-        let private_key = self.private_key.as_ref()
-            .ok_or_else(|| anyhow::anyhow!("Private key is required for order signing. Please set private_key in config.json"))?;
-        
-        let signer = LocalSigner::from_str(private_key)
-            .context("Failed to create signer from private key. Ensure private_key is a valid hex string.")?
-            .with_chain_id(Some(POLYGON));
-        
-        let parse_address_hex = |s: &str| -> Result<Address> {
-            let hex_str = s.strip_prefix("0x").unwrap_or(s);
-            let bytes = hex::decode(hex_str).context("Invalid hex in address")?;
-            let len= bytes.len();
-            let arr: [u8; 20] = bytes.try_into().map_err(|_| anyhow::anyhow!("Address must be 20 bytes, got {}", len))?;
-            Ok(Address::from(arr))
-        };
+        self.redeem_tokens_batch(&[(condition_id.to_string(), outcome.to_string())])
+            .await?
+            .pop()
+            .unwrap_or_else(|| Err(anyhow::anyhow!("No redemption transaction sent")))
+    }
 
-        let collateral_token = parse_address_hex("0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174")
-            .context("Failed to parse USDC address")?;
+    /// Redeem several (condition_id, outcome) pairs in one transaction: one `proxy([...])` call for Polymarket
+    /// proxies, a MultiSend delegatecall for Gnosis Safes. An EOA redeems its own balance only when it is
+    /// `msg.sender`, so a multicall contract can't batch for it; EOA targets are sent one transaction each.
+    /// Returns each target's result in order; Err when the proxy/Safe transaction (shared by all) failed.
+    pub async fn redeem_tokens_batch(&self, targets: &[(String, String)]) -> Result<Vec<Result<RedeemResponse>>> {
+        if targets.is_empty() {
+            anyhow::bail!("No redemption targets");
+        }
+        let use_proxy = self.proxy_wallet_address.is_some();
+        let sig_type = self.signature_type.unwrap_or(1);
+        // Gnosis Safe path: use index sets [1, 2] in one call (matches working new_redeem.py claim())
+        let both_index_sets = use_proxy && sig_type == 2;
 
-        let condition_id_clean = condition_id.strip_prefix("0x").unwrap_or(condition_id);
-        let condition_id_b256 = B256::from_str(condition_id_clean)
+        let mut calls = Vec::with_capacity(targets.len());
+        for (condition_id, outcome) in targets {
+            eprintln!("Redeeming winning tokens for condition {} (outcome: {})", condition_id, outcome);
+            calls.push(self.redeem_calldata(condition_id, outcome, both_index_sets)?);
+        }
+
+        if use_proxy {
+            let res = self.send_redemption(&calls).await?;
+            return Ok(targets.iter().map(|_| Ok(res.clone())).collect());
+        }
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            results.push(self.send_redemption(std::slice::from_ref(&call)).await);
+        }
+        Ok(results)
+    }
+
+    /// Merge `shares` complete sets (one Up + one Down) of a binary condition back into USDC. Returns the tx hash.
+    pub async fn merge_positions(&self, condition_id: &str, shares: f64) -> Result<String> {
+        let ctf = Address::from_str(&self.contracts.ctf).context("Failed to parse CTF contract address")?;
+        let collateral_token = Address::from_str(&self.contracts.usdc).context("Failed to parse USDC address")?;
+        let condition_id_b256 = B256::from_str(condition_id.strip_prefix("0x").unwrap_or(condition_id))
             .context(format!("Failed to parse condition_id as B256: {}", condition_id))?;
+        let call = IConditionalTokens::mergePositionsCall {
+            collateralToken: collateral_token,
+            parentCollectionId: B256::ZERO,
+            conditionId: condition_id_b256,
+            partition: vec![U256::from(1), U256::from(2)],
+            amount: U256::from((shares * TOKEN_DECIMALS_SCALE).floor() as u128),
+        };
+        let (_, tx_hash, _) = self.send_wallet_calls(&[(ctf, call.abi_encode())]).await?;
+        Ok(format!("{:?}", tx_hash))
+    }
+
+    /// Whether `address` has contract code deployed (e.g. a proxy / Safe wallet that has been created).
+    pub async fn is_contract(&self, address: &str) -> Result<bool> {
+        let address = Address::from_str(address).context(format!("Failed to parse address: {}", address))?;
+        self.with_rpc_failover("eth_getCode", |rpc_url| async move {
+            let provider = connect_rpc(ProviderBuilder::new(), &rpc_url).await
+                .context("Failed to connect to Polygon RPC")?;
+            let code = provider.get_code_at(address).await.context("eth_getCode failed")?;
+            Ok(!code.is_empty())
+        })
+        .await
+    }
 
-        let index_set = if outcome.to_uppercase().contains("UP") || outcome == "1" {
-            U256::from(1)
+    /// Allowances the trading wallet still needs before orders can settle: USDC `approve` and CTF
+    /// `setApprovalForAll` for the CTF Exchange, NegRisk CTF Exchange, and NegRisk Adapter. Each entry is
+    /// (token contract, calldata, description).
+    pub async fn missing_approvals(&self) -> Result<Vec<(Address, Vec<u8>, String)>> {
+        let owner = self
+            .wallet_address()
+            .ok_or_else(|| anyhow::anyhow!("No wallet configured"))?;
+        let owner = Address::from_str(&owner).context("Failed to parse wallet address")?;
+        let c = &self.contracts;
+        let usdc = Address::from_str(&c.usdc).context("Failed to parse USDC address")?;
+        let ctf = Address::from_str(&c.ctf).context("Failed to parse CTF contract address")?;
+        let spenders = [
+            ("CTF Exchange", &c.ctf_exchange),
+            ("NegRisk CTF Exchange", &c.neg_risk_ctf_exchange),
+            ("NegRisk Adapter", &c.neg_risk_adapter),
+        ];
+        let mut missing = Vec::new();
+        for (name, spender) in spenders {
+            let spender = Address::from_str(spender).context(format!("Failed to parse {} address", name))?;
+            let allowance = self
+                .contract_call(usdc, IERC20::allowanceCall { owner, spender })
+                .await?;
+            if allowance < U256::from(MIN_USDC_ALLOWANCE) {
+                missing.push((
+                    usdc,
+                    IERC20::approveCall { spender, amount: U256::MAX }.abi_encode(),
+                    format!("USDC allowance for {}", name),
+                ));
+            }
+            let approved = self
+                .contract_call(ctf, IConditionalTokens::isApprovedForAllCall { owner, operator: spender })
+                .await?;
+            if !approved {
+                missing.push((
+                    ctf,
+                    IConditionalTokens::setApprovalForAllCall { operator: spender, approved: true }.abi_encode(),
+                    format!("CTF approval for {}", name),
+                ));
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Send the given approvals from the trading wallet: one tx for a proxy or Safe, one per approval
+    /// from an EOA. Returns the tx hashes.
+    pub async fn send_approvals(&self, approvals: &[(Address, Vec<u8>, String)]) -> Result<Vec<String>> {
+        let calls: Vec<(Address, Vec<u8>)> = approvals
+            .iter()
+            .map(|(to, data, _)| (*to, data.clone()))
+            .collect();
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+        let batches: Vec<&[(Address, Vec<u8>)]> = if self.proxy_wallet_address.is_some() {
+            vec![&calls[..]]
         } else {
-            U256::from(2)
+            calls.chunks(1).collect()
         };
+        let mut hashes = Vec::with_capacity(batches.len());
+        for batch in batches {
+            let (_, tx_hash, _) = self.send_wallet_calls(batch).await?;
+            hashes.push(format!("{:?}", tx_hash));
+        }
+        Ok(hashes)
+    }
 
-        eprintln!("Redeeming winning tokens for condition {} (outcome: {}, index_set: {})", 
-              condition_id, outcome, index_set);
-        
-        const CTF_CONTRACT: &str = "0x4d97dcd97ec945f40cf65f87097ace5ea0476045";
-        let rpc_url = self.rpc_url.as_deref().unwrap_or("https://polygon-rpc.com");
-        // Polymarket Proxy Wallet Factory (MagicLink users) – execute via factory.proxy([call])
-        const PROXY_WALLET_FACTORY: &str = "0xaB45c5A4B0c941a2F231C04C3f49182e1A254052";
-        
-        let ctf_address = parse_address_hex(CTF_CONTRACT)
-            .context("Failed to parse CTF contract address")?;
-        
-        let parent_collection_id = B256::ZERO;
+    /// Approximate USD gas cost of redeeming `targets` in one transaction: eth_estimateGas of each
+    /// redeemPositions call from the holding wallet, plus the proxy/Safe wrapper overhead, at the current
+    /// max fee and the Chainlink POL/USD price.
+    pub async fn estimate_redemption_cost_usd(&self, targets: &[(String, String)]) -> Result<f64> {
+        let holder = self
+            .wallet_address()
+            .ok_or_else(|| anyhow::anyhow!("No wallet configured"))?;
+        let holder = Address::from_str(&holder).context("Failed to parse wallet address")?;
+        let ctf_address = Address::from_str(&self.contracts.ctf).context("Failed to parse CTF contract address")?;
         let use_proxy = self.proxy_wallet_address.is_some();
-        let sig_type = self.signature_type.unwrap_or(1);
-        // Gnosis Safe path: use index sets [1, 2] in one call (matches working new_redeem.py claim())
-        let index_sets: Vec<U256> = if use_proxy && sig_type == 2 {
+        let both_index_sets = use_proxy && self.signature_type.unwrap_or(1) == 2;
+        let mut txs = Vec::with_capacity(targets.len());
+        for (condition_id, outcome) in targets {
+            txs.push(
+                TransactionRequest::default()
+                    .from(holder)
+                    .to(ctf_address)
+                    .input(Bytes::from(self.redeem_calldata(condition_id, outcome, both_index_sets)?).into()),
+            );
+        }
+        let pol_usd_feed = self
+            .contracts
+            .pol_usd_feed
+            .as_deref()
+            .ok_or_else(|| anyhow::anyhow!("No POL/USD feed configured for this network"))?;
+        let policy = self.gas.policy();
+        self.with_rpc_failover("Redemption cost estimate", |rpc_url| {
+            let txs = txs.clone();
+            async move {
+                let provider = connect_rpc(ProviderBuilder::new(), &rpc_url).await
+                    .context("Failed to connect to Polygon RPC")?;
+                let mut gas = if use_proxy { REDEEM_GAS_BASE } else { 0 };
+                for tx in txs {
+                    gas += provider.estimate_gas(tx).await.context("eth_estimateGas failed for redeem")?;
+                }
+                let (max_fee, _) = self.redemption_fees(&provider, &policy).await?;
+                let (pol_usd, _) = get_chainlink_btc_price_usd(&self.client, &rpc_url, pol_usd_feed)
+                    .await
+                    .map_err(|e| anyhow::anyhow!("POL/USD price unavailable: {}", e))?;
+                let cost_pol = gas_limit_with_headroom(gas, policy.limit_multiplier) as f64 * max_fee as f64 / 1e18;
+                Ok(cost_pol * pol_usd)
+            }
+        })
+        .await
+    }
+
+    /// ABI-encoded `redeemPositions` for one condition.
+    fn redeem_calldata(&self, condition_id: &str, outcome: &str, both_index_sets: bool) -> Result<Vec<u8>> {
+        let collateral_token = Address::from_str(&self.contracts.usdc)
+            .context("Failed to parse USDC address")?;
+        let condition_id_clean = condition_id.strip_prefix("0x").unwrap_or(condition_id);
+        let condition_id_b256 = B256::from_str(condition_id_clean)
+            .context(format!("Failed to parse condition_id as B256: {}", condition_id))?;
+
+        let index_sets: Vec<U256> = if both_index_sets {
             vec![U256::from(1), U256::from(2)]
+        } else if outcome.to_uppercase().contains("UP") || outcome == "1" {
+            vec![U256::from(1)]
         } else {
-            vec![index_set]
+            vec![U256::from(2)]
         };
-        
-        eprintln!("   Prepared redemption parameters:");
-        eprintln!("   - CTF Contract: {}", ctf_address);
-        eprintln!("   - Collateral token (USDC): {}", collateral_token);
-        eprintln!("   - Condition ID: {} ({:?})", condition_id, condition_id_b256);
-        eprintln!("   - Index set(s): {:?} (outcome: {})", index_sets, outcome);
-        
+        eprintln!("   - Condition ID: {} ({:?}), index set(s): {:?}", condition_id, condition_id_b256, index_sets);
+
         // Encode redeemPositions via alloy sol! (matches Polymarket rs-clob-client / Gnosis CTF ABI)
         let redeem_call = IConditionalTokens::redeemPositionsCall {
             collateralToken: collateral_token,
-            parentCollectionId: parent_collection_id,
+            parentCollectionId: B256::ZERO,
             conditionId: condition_id_b256,
-            indexSets: index_sets.clone(),
+            indexSets: index_sets,
         };
-        let redeem_calldata = redeem_call.abi_encode();
+        Ok(redeem_call.abi_encode())
+    }
+
+    /// Send `calls` (each a CTF redeemPositions calldata) from the configured wallet and wait for the receipt.
+    /// Send `(target, calldata)` calls from the configured wallet in one tx: Safe execTransaction (MultiSend
+    /// for several), Proxy Wallet Factory, or directly from the EOA (first call only). Waits for finality.
+    /// Returns the receipt, tx hash, and whether it went through a Safe.
+    async fn send_wallet_calls(
+        &self,
+        calls: &[(Address, Vec<u8>)],
+    ) -> Result<(alloy::rpc::types::TransactionReceipt, B256, bool)> {
+        let signer = self.signer()?;
+        
+        // One node for the whole flow: Safe nonce, signing, and the send must agree.
+        let rpc_url = self.healthy_rpc_url().await?;
+        let rpc_url = rpc_url.as_str();
+        let use_proxy = self.proxy_wallet_address.is_some();
+        let sig_type = self.signature_type.unwrap_or(1);
+        let n_calls = calls.len() as u64;
         
-        let (tx_to, tx_data, gas_limit, used_safe_redemption) = if use_proxy && sig_type == 2 {
-            // Gnosis Safe: create Safe tx (redeemPositions), sign with EOA, execute via Safe.execTransaction
+        // The per-path gas limit is only a fallback for when eth_estimateGas fails.
+        let (tx_to, tx_data, fallback_gas_limit, used_safe_redemption) = if use_proxy && sig_type == 2 {
+            // Gnosis Safe: create Safe tx (e.g. redeemPositions), sign with EOA, execute via Safe.execTransaction
             // Matches redeem.ts redeemPositionsViaSafe() using Safe SDK (createTransaction -> signTransaction -> executeTransaction)
             let safe_address_str = self.proxy_wallet_address.as_deref()
                 .ok_or_else(|| anyhow::anyhow!("proxy_wallet_address required for Safe redemption"))?;
-            let safe_address = parse_address_hex(safe_address_str)
+            let safe_address = Address::from_str(safe_address_str)
                 .context("Failed to parse proxy_wallet_address (Safe address)")?;
-            eprintln!("   Using Gnosis Safe (proxy): signing and executing redemption via Safe.execTransaction");
+            eprintln!("   Using Gnosis Safe (proxy): signing and executing via Safe.execTransaction");
+            // A single call goes straight to its target; several are packed into MultiSendCallOnly and delegatecalled.
+            let (inner_to, inner_data, operation) = if calls.len() == 1 {
+                (calls[0].0, calls[0].1.clone(), 0u8)
+            } else {
+                let mut packed = Vec::new();
+                for (to, call) in calls {
+                    packed.push(0u8); // operation = Call
+                    packed.extend_from_slice(to.as_slice());
+                    packed.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
+                    packed.extend_from_slice(&U256::from(call.len()).to_be_bytes::<32>());
+                    packed.extend_from_slice(call);
+                }
+                let multi_send = Address::from_str(&self.contracts.multi_send_call_only)
+                    .context("Failed to parse MultiSendCallOnly address")?;
+                let data = IMultiSend::multiSendCall { transactions: Bytes::from(packed) }.abi_encode();
+                (multi_send, data, 1u8)
+            };
             // 1) Get Safe nonce
             let nonce_selector = keccak256("nonce()".as_bytes());
             let nonce_calldata: Vec<u8> = nonce_selector.as_slice()[..4].to_vec();
-            let provider_read = ProviderBuilder::new()
-                .connect(rpc_url)
-                .await
+            let provider_read = connect_rpc(ProviderBuilder::new(), rpc_url).await
                 .context("Failed to connect to RPC for Safe read calls")?;
             let nonce_tx = TransactionRequest::default()
                 .to(safe_address)
@@ -933,34 +2525,27 @@ impl PolymarketApi {
             let nonce_result = provider_read.call(nonce_tx).await
                 .map_err(|e| anyhow::anyhow!("Failed to call Safe.nonce() on {}: {}. \
                     If you use MagicLink/email login, your proxy is a Polymarket custom proxy, not a Gnosis Safe; \
-                    redemption via Safe is only supported for MetaMask (Gnosis Safe) proxies.",
+                    sending via Safe is only supported for MetaMask (Gnosis Safe) proxies.",
                     safe_address_str, e))?;
             let nonce_bytes: [u8; 32] = nonce_result.as_ref().try_into()
                 .map_err(|_| anyhow::anyhow!("Safe.nonce() did not return 32 bytes"))?;
             let nonce = U256::from_be_slice(&nonce_bytes);
             // safeTxGas: use non-zero like new_redeem.py (REDEEM_GAS_LIMIT). 0 can cause inner call to fail.
-            const SAFE_TX_GAS: u64 = 300_000;
+            let safe_tx_gas = U256::from(SAFE_TX_GAS_PER_REDEEM * n_calls);
             // 2) Get transaction hash from Safe.getTransactionHash(to, value, data, operation, safeTxGas, baseGas, gasPrice, gasToken, refundReceiver, nonce)
-            let get_tx_hash_sig = "getTransactionHash(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,uint256)";
-            let get_tx_hash_selector = keccak256(get_tx_hash_sig.as_bytes()).as_slice()[..4].to_vec();
-            let zero_addr = [0u8; 32];
-            let mut to_enc = [0u8; 32];
-            to_enc[12..].copy_from_slice(ctf_address.as_slice());
-            let data_offset_get_hash = U256::from(32u32 * 10u32); // 320: data starts after 10 param words
-            let mut get_tx_hash_calldata = Vec::new();
-            get_tx_hash_calldata.extend_from_slice(&get_tx_hash_selector);
-            get_tx_hash_calldata.extend_from_slice(&to_enc);
-            get_tx_hash_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            get_tx_hash_calldata.extend_from_slice(&data_offset_get_hash.to_be_bytes::<32>());
-            get_tx_hash_calldata.push(0); get_tx_hash_calldata.extend_from_slice(&[0u8; 31]); // operation = 0 (Call)
-            get_tx_hash_calldata.extend_from_slice(&U256::from(SAFE_TX_GAS).to_be_bytes::<32>());
-            get_tx_hash_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            get_tx_hash_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            get_tx_hash_calldata.extend_from_slice(&zero_addr);
-            get_tx_hash_calldata.extend_from_slice(&zero_addr);
-            get_tx_hash_calldata.extend_from_slice(&nonce.to_be_bytes::<32>());
-            get_tx_hash_calldata.extend_from_slice(&U256::from(redeem_calldata.len()).to_be_bytes::<32>());
-            get_tx_hash_calldata.extend_from_slice(&redeem_calldata);
+            let get_tx_hash_calldata = IGnosisSafe::getTransactionHashCall {
+                to: inner_to,
+                value: U256::ZERO,
+                data: Bytes::from(inner_data.clone()),
+                operation,
+                safeTxGas: safe_tx_gas,
+                baseGas: U256::ZERO,
+                gasPrice: U256::ZERO,
+                gasToken: Address::ZERO,
+                refundReceiver: Address::ZERO,
+                _nonce: nonce,
+            }
+            .abi_encode();
             let get_tx_hash_tx = TransactionRequest::default()
                 .to(safe_address)
                 .input(Bytes::from(get_tx_hash_calldata).into());
@@ -1003,118 +2588,117 @@ impl PolymarketApi {
                 with_owner.extend_from_slice(&packed_sig);
                 packed_sig = with_owner;
             }
-            let safe_sig_bytes = packed_sig;
             // 4) Encode execTransaction(to, value, data, operation, safeTxGas, baseGas, gasPrice, gasToken, refundReceiver, signatures)
-            let exec_sig = "execTransaction(address,uint256,bytes,uint8,uint256,uint256,uint256,address,address,bytes)";
-            let exec_selector = keccak256(exec_sig.as_bytes()).as_slice()[..4].to_vec();
-            let data_offset = 32u32 * 10u32; // 320: first dynamic param starts after 10 words
-            let sigs_offset = data_offset + 32 + redeem_calldata.len() as u32; // offset to signatures bytes
-            let mut exec_calldata = Vec::new();
-            exec_calldata.extend_from_slice(&exec_selector);
-            exec_calldata.extend_from_slice(&to_enc);
-            exec_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&U256::from(data_offset).to_be_bytes::<32>());
-            exec_calldata.push(0); exec_calldata.extend_from_slice(&[0u8; 31]);
-            exec_calldata.extend_from_slice(&U256::from(SAFE_TX_GAS).to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&zero_addr);
-            exec_calldata.extend_from_slice(&zero_addr);
-            exec_calldata.extend_from_slice(&U256::from(sigs_offset).to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&U256::from(redeem_calldata.len()).to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&redeem_calldata);
-            exec_calldata.extend_from_slice(&U256::from(safe_sig_bytes.len()).to_be_bytes::<32>());
-            exec_calldata.extend_from_slice(&safe_sig_bytes);
-            (safe_address, exec_calldata, 400_000u64, true)
+            let exec_calldata = IGnosisSafe::execTransactionCall {
+                to: inner_to,
+                value: U256::ZERO,
+                data: Bytes::from(inner_data),
+                operation,
+                safeTxGas: safe_tx_gas,
+                baseGas: U256::ZERO,
+                gasPrice: U256::ZERO,
+                gasToken: Address::ZERO,
+                refundReceiver: Address::ZERO,
+                signatures: Bytes::from(packed_sig),
+            }
+            .abi_encode();
+            (safe_address, exec_calldata, REDEEM_GAS_BASE + REDEEM_GAS_PER_CALL * n_calls, true)
         } else if use_proxy && sig_type == 1 {
-            // Polymarket Proxy: execute via Proxy Wallet Factory – factory.proxy([(typeCode, to, value, data)])
+            // Polymarket Proxy: execute via Proxy Wallet Factory – factory.proxy([(typeCode, to, value, data), ...])
             // Refs: https://docs.polymarket.com/developers/proxy-wallet, Polymarket/examples examples/proxyWallet/redeem.ts
-            eprintln!("   Using proxy wallet: sending redemption via Proxy Wallet Factory");
-            let factory_address = parse_address_hex(PROXY_WALLET_FACTORY)
+            eprintln!("   Using proxy wallet: sending via Proxy Wallet Factory");
+            let factory = self
+                .contracts
+                .proxy_wallet_factory
+                .as_deref()
+                .ok_or_else(|| anyhow::anyhow!("No Proxy Wallet Factory configured for this network (set contracts.proxy_wallet_factory)"))?;
+            let factory_address = Address::from_str(factory)
                 .context("Failed to parse Proxy Wallet Factory address")?;
-            // ABI: proxy((uint8 typeCode, address to, uint256 value, bytes data)[] calls)
-            let selector = keccak256("proxy((uint8,address,uint256,bytes)[])".as_bytes());
-            let proxy_selector = &selector.as_slice()[..4];
-            // Encode one call: typeCode=1 (Call), to=CTF, value=0, data=redeem_calldata
-            let mut proxy_calldata = Vec::with_capacity(4 + 32 * 3 + 128 + 32 + redeem_calldata.len());
-            proxy_calldata.extend_from_slice(proxy_selector);
-            // offset to array (params start at byte 4) = 32
-            proxy_calldata.extend_from_slice(&U256::from(32u32).to_be_bytes::<32>());
-            // array length = 1
-            proxy_calldata.extend_from_slice(&U256::from(1u32).to_be_bytes::<32>());
-            // offset to first tuple from start of params = 96 (tuple at 4+96=100)
-            proxy_calldata.extend_from_slice(&U256::from(96u32).to_be_bytes::<32>());
-            // tuple: typeCode = 1 (32 bytes, right-padded)
-            let mut type_code = [0u8; 32];
-            type_code[31] = 1;
-            proxy_calldata.extend_from_slice(&type_code);
-            // to = ctf_address (32 bytes, left-padded)
-            let mut to_bytes = [0u8; 32];
-            to_bytes[12..].copy_from_slice(ctf_address.as_slice());
-            proxy_calldata.extend_from_slice(&to_bytes);
-            // value = 0
-            proxy_calldata.extend_from_slice(&U256::ZERO.to_be_bytes::<32>());
-            // offset to bytes (from start of tuple) = 128
-            proxy_calldata.extend_from_slice(&U256::from(128u32).to_be_bytes::<32>());
-            // bytes: length then data
-            let data_len = redeem_calldata.len();
-            proxy_calldata.extend_from_slice(&U256::from(data_len).to_be_bytes::<32>());
-            proxy_calldata.extend_from_slice(&redeem_calldata);
-            (factory_address, proxy_calldata, 400_000u64, false)
+            let proxy_calls = calls
+                .iter()
+                .map(|(to, call)| IProxyWalletFactory::ProxyCall {
+                    typeCode: 1, // Call
+                    to: *to,
+                    value: U256::ZERO,
+                    data: Bytes::from(call.clone()),
+                })
+                .collect();
+            let proxy_calldata = IProxyWalletFactory::proxyCall { calls: proxy_calls }.abi_encode();
+            (factory_address, proxy_calldata, REDEEM_GAS_BASE + REDEEM_GAS_PER_CALL * n_calls, false)
         } else {
-            // EOA or no proxy: send redeemPositions directly to CTF (tokens must be in EOA)
-            eprintln!("   Sending redemption from EOA to CTF contract");
-            (ctf_address, redeem_calldata, 300_000, false)
+            // EOA or no proxy: send the call directly (tokens must be in EOA); callers send one call per tx.
+            eprintln!("   Sending from EOA to {}", calls[0].0);
+            (calls[0].0, calls[0].1.clone(), 300_000, false)
         };
         
-        let provider = ProviderBuilder::new()
-            .wallet(signer.clone())
-            .connect(rpc_url)
-            .await
+        let provider = connect_rpc(ProviderBuilder::new().wallet(EthereumWallet::from(signer.clone())), rpc_url).await
             .context("Failed to connect to Polygon RPC")?;
         
-        let tx_request = TransactionRequest {
+        let mut tx_request = TransactionRequest {
+            from: Some(signer.address()),
             to: Some(alloy::primitives::TxKind::Call(tx_to)),
             input: Bytes::from(tx_data).into(),
             value: Some(U256::ZERO),
-            gas: Some(gas_limit),
             ..Default::default()
         };
-        
-        let pending_tx = match provider.send_transaction(tx_request).await {
-            Ok(tx) => tx,
+        let policy = self.gas.policy();
+        let gas_limit = match provider.estimate_gas(tx_request.clone()).await {
+            Ok(estimate) => gas_limit_with_headroom(estimate, policy.limit_multiplier),
             Err(e) => {
-                let err_msg = format!("Failed to send redeem transaction: {}", e);
-                eprintln!("   {}", err_msg);
-                anyhow::bail!("{}", err_msg);
+                eprintln!("   eth_estimateGas failed ({}); using fallback gas limit {}", e, fallback_gas_limit);
+                fallback_gas_limit
             }
         };
-
-        let tx_hash = *pending_tx.tx_hash();
-        eprintln!("   Transaction sent, waiting for confirmation...");
-        eprintln!("   Transaction hash: {:?}", tx_hash);
-        
-        let receipt = pending_tx.get_receipt().await
-            .context("Failed to get transaction receipt")?;
+        let (max_fee, priority_fee) = self.redemption_fees(&provider, &policy).await?;
+        eprintln!(
+            "   Gas: limit {}, max fee {:.2} gwei, priority fee {:.2} gwei",
+            gas_limit,
+            max_fee as f64 / 1e9,
+            priority_fee as f64 / 1e9
+        );
+        tx_request.gas = Some(gas_limit);
+        tx_request.max_fee_per_gas = Some(max_fee);
+        tx_request.max_priority_fee_per_gas = Some(priority_fee);
+        
+        // Pin the nonce so a stuck tx can be replaced rather than queued behind.
+        let nonce = provider
+            .get_transaction_count(signer.address())
+            .pending()
+            .await
+            .context("Failed to fetch account nonce")?;
+        tx_request.nonce = Some(nonce);
+        let mut broadcasts = Vec::new();
+        let (receipt, tx_hash) = self
+            .send_and_confirm(&provider, &mut tx_request, &policy, &mut broadcasts)
+            .await?;
+        let (receipt, tx_hash) = self
+            .await_finality(&provider, &mut tx_request, &policy, &mut broadcasts, receipt, tx_hash)
+            .await?;
         
         if !receipt.status() {
-            anyhow::bail!("Redemption transaction failed. Transaction hash: {:?}", tx_hash);
+            anyhow::bail!("Transaction failed. Transaction hash: {:?}", tx_hash);
         }
-        
+        Ok((receipt, tx_hash, used_safe_redemption))
+    }
+
+    /// Redeem via the configured wallet: one tx carrying every `redeemPositions` call (one per condition on EOA).
+    async fn send_redemption(&self, calls: &[Vec<u8>]) -> Result<RedeemResponse> {
+        let ctf_address = Address::from_str(&self.contracts.ctf)
+            .context("Failed to parse CTF contract address")?;
+        eprintln!("   Prepared redemption of {} condition(s) via CTF {}", calls.len(), ctf_address);
+        let targeted: Vec<(Address, Vec<u8>)> = calls.iter().map(|call| (ctf_address, call.clone())).collect();
+        let (receipt, tx_hash, used_safe_redemption) = self.send_wallet_calls(&targeted).await?;
+
         // When using Gnosis Safe, the outer tx can succeed while the inner CTF redeemPositions reverts.
-        // Detect inner failure by checking for CTF PayoutRedemption event in logs.
+        // Detect inner failure by counting CTF PayoutRedemption events in logs (one per redeemed condition).
         if used_safe_redemption {
-            let payout_redemption_topic = keccak256(
-                b"PayoutRedemption(address,address,bytes32,bytes32,uint256[],uint256)"
-            );
-            let logs = receipt.logs();
-            let ctf_has_payout = logs.iter().any(|log| {
-                log.address() == ctf_address && log.topics().first().map(|t| t.as_slice()) == Some(payout_redemption_topic.as_slice())
-            });
-            if !ctf_has_payout {
+            let payouts = payout_redemptions(&receipt, ctf_address);
+            if payouts < calls.len() {
                 anyhow::bail!(
-                    "Redemption tx was mined but the inner redeem reverted (no PayoutRedemption from CTF). \
+                    "Redemption tx was mined but {} of {} inner redeem(s) reverted (missing PayoutRedemption from CTF). \
                     Check that the Safe holds the winning tokens and conditionId/indexSet are correct. Tx: {:?}",
+                    calls.len() - payouts,
+                    calls.len(),
                     tx_hash
                 );
             }
@@ -1133,6 +2717,348 @@ impl PolymarketApi {
         }
         Ok(redeem_response)
     }
+
+    /// Broadcast and wait up to `tx_timeout_secs` for the receipt; if not mined, rebroadcast with the same
+    /// nonce and bumped fees, up to `max_replacements` times. Any earlier broadcast that lands meanwhile
+    /// counts. Errors (for manual intervention) once replacements are exhausted or would exceed the fee cap.
+    async fn send_and_confirm<P: Provider>(
+        &self,
+        provider: &P,
+        tx_request: &mut TransactionRequest,
+        policy: &GasPolicy,
+        broadcasts: &mut Vec<B256>,
+    ) -> Result<(alloy::rpc::types::TransactionReceipt, B256)> {
+        let timeout = std::time::Duration::from_secs(self.gas.tx_timeout_secs.max(1));
+        let nonce = tx_request.nonce.unwrap_or_default();
+        let mut replacements = 0u32;
+        loop {
+            let pending_tx = match provider.send_transaction(tx_request.clone()).await {
+                Ok(tx) => tx,
+                Err(e) => {
+                    // A replacement is refused once an earlier broadcast has been mined ("nonce too low").
+                    if let Some(found) = find_receipt(provider, broadcasts).await {
+                        return Ok(found);
+                    }
+                    let err_msg = format!("Failed to send redeem transaction: {}", e);
+                    eprintln!("   {}", err_msg);
+                    anyhow::bail!("{}", err_msg);
+                }
+            };
+            let tx_hash = *pending_tx.tx_hash();
+            broadcasts.push(tx_hash);
+            eprintln!("   Transaction sent (nonce {}), waiting up to {}s for confirmation...", nonce, timeout.as_secs());
+            eprintln!("   Transaction hash: {:?}", tx_hash);
+
+            match pending_tx.with_timeout(Some(timeout)).get_receipt().await {
+                Ok(receipt) => return Ok((receipt, tx_hash)),
+                Err(e) => {
+                    if let Some(found) = find_receipt(provider, broadcasts).await {
+                        return Ok(found);
+                    }
+                    if replacements >= self.gas.max_replacements {
+                        anyhow::bail!(
+                            "Redemption tx not mined after {} broadcast(s) (nonce {}, last error: {}). \
+                            Needs manual intervention: speed up or cancel nonce {} from the wallet. Hashes: {:?}",
+                            broadcasts.len(),
+                            nonce,
+                            e,
+                            nonce,
+                            broadcasts
+                        );
+                    }
+                    let (max_fee, priority_fee) = bump_fees(
+                        tx_request.max_fee_per_gas.unwrap_or_default(),
+                        tx_request.max_priority_fee_per_gas.unwrap_or_default(),
+                        self.gas.replacement_fee_bump,
+                    );
+                    if policy.max_fee_cap_wei.is_some_and(|cap| max_fee > cap) {
+                        anyhow::bail!(
+                            "Redemption tx {:?} stuck (nonce {}) and a replacement would exceed max_fee_gwei. \
+                            Needs manual intervention.",
+                            tx_hash,
+                            nonce
+                        );
+                    }
+                    replacements += 1;
+                    eprintln!(
+                        "   Not mined within {}s; rebroadcasting (replacement {}/{}) at max fee {:.2} gwei, priority {:.2} gwei",
+                        timeout.as_secs(),
+                        replacements,
+                        self.gas.max_replacements,
+                        max_fee as f64 / 1e9,
+                        priority_fee as f64 / 1e9
+                    );
+                    tx_request.max_fee_per_gas = Some(max_fee);
+                    tx_request.max_priority_fee_per_gas = Some(priority_fee);
+                }
+            }
+        }
+    }
+
+    /// Hold a mined redemption until it is `confirmations` deep (or under the `finalized` tag). A receipt that
+    /// disappears was reorged out: wait one tx timeout for re-inclusion, then resubmit the same nonce with
+    /// bumped fees. Returns the receipt that reached finality, which may belong to a different broadcast;
+    /// `UnconfirmedRedemption` with the mined hash when `finality_timeout_secs` runs out first.
+    async fn await_finality<P: Provider>(
+        &self,
+        provider: &P,
+        tx_request: &mut TransactionRequest,
+        policy: &GasPolicy,
+        broadcasts: &mut Vec<B256>,
+        mut receipt: alloy::rpc::types::TransactionReceipt,
+        mut tx_hash: B256,
+    ) -> Result<(alloy::rpc::types::TransactionReceipt, B256)> {
+        if self.gas.confirmations == 0 && !self.gas.wait_finalized {
+            return Ok((receipt, tx_hash));
+        }
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(self.gas.finality_timeout_secs);
+        let poll = std::time::Duration::from_secs(FINALITY_POLL_SECS);
+        let mut missing_since: Option<tokio::time::Instant> = None;
+        let mut resubmissions = 0u32;
+        loop {
+            if tokio::time::Instant::now() >= deadline {
+                return Err(UnconfirmedRedemption {
+                    tx_hash: format!("{:?}", tx_hash),
+                    message: format!(
+                        "Redemption tx {:?} not final after {}s; its receipt is re-checked before any retry",
+                        tx_hash, self.gas.finality_timeout_secs
+                    ),
+                }
+                .into());
+            }
+            tokio::time::sleep(poll).await;
+
+            let Some((current, hash)) = find_receipt(provider, broadcasts).await else {
+                let since = *missing_since.get_or_insert_with(|| {
+                    warn!("Redemption tx {:?} receipt disappeared (chain reorg); waiting for re-inclusion", tx_hash);
+                    tokio::time::Instant::now()
+                });
+                if since.elapsed().as_secs() < self.gas.tx_timeout_secs.max(1) {
+                    continue;
+                }
+                if resubmissions >= MAX_REORG_RESUBMISSIONS {
+                    anyhow::bail!(
+                        "Redemption tx {:?} dropped by reorg and not re-included after {} resubmission(s)",
+                        tx_hash,
+                        resubmissions
+                    );
+                }
+                resubmissions += 1;
+                warn!("Resubmitting redemption (nonce {:?}) after reorg", tx_request.nonce);
+                let (max_fee, priority_fee) = bump_fees(
+                    tx_request.max_fee_per_gas.unwrap_or_default(),
+                    tx_request.max_priority_fee_per_gas.unwrap_or_default(),
+                    self.gas.replacement_fee_bump,
+                );
+                tx_request.max_fee_per_gas = Some(max_fee);
+                tx_request.max_priority_fee_per_gas = Some(priority_fee);
+                (receipt, tx_hash) = self.send_and_confirm(provider, tx_request, policy, broadcasts).await?;
+                missing_since = None;
+                continue;
+            };
+            if missing_since.take().is_some() || current.block_hash != receipt.block_hash {
+                info!("Redemption tx {:?} re-included in block {:?}", hash, current.block_number);
+            }
+            (receipt, tx_hash) = (current, hash);
+            let Some(mined_at) = receipt.block_number else {
+                continue;
+            };
+            if self.is_final(provider, mined_at).await {
+                return Ok((receipt, tx_hash));
+            }
+        }
+    }
+
+    /// Whether a tx mined at `mined_at` is `confirmations` deep (or under the `finalized` tag).
+    async fn is_final<P: Provider>(&self, provider: &P, mined_at: u64) -> bool {
+        let final_block = if self.gas.wait_finalized {
+            provider
+                .get_block_by_number(BlockNumberOrTag::Finalized)
+                .await
+                .ok()
+                .flatten()
+                .map(|b| b.header.number)
+        } else {
+            provider
+                .get_block_number()
+                .await
+                .ok()
+                .map(|latest| latest.saturating_sub(self.gas.confirmations))
+        };
+        final_block.is_some_and(|b| b >= mined_at)
+    }
+
+    /// Where the redemption `tx_hash` left by an `UnconfirmedRedemption` stands.
+    pub async fn redemption_status(&self, tx_hash: &str) -> Result<RedemptionStatus> {
+        let hash = B256::from_str(tx_hash).context(format!("Failed to parse tx hash {}", tx_hash))?;
+        self.with_rpc_failover("Redemption receipt", |rpc_url| async move {
+            let provider = connect_rpc(ProviderBuilder::new(), &rpc_url).await
+                .context("Failed to connect to Polygon RPC")?;
+            self.redemption_status_via(&provider, hash).await
+        })
+        .await
+    }
+
+    /// A Safe redemption whose inner `redeemPositions` reverted counts as failed, as in `send_redemption`.
+    async fn redemption_status_via<P: Provider>(&self, provider: &P, hash: B256) -> Result<RedemptionStatus> {
+        let Some(receipt) = provider.get_transaction_receipt(hash).await? else {
+            return Ok(RedemptionStatus::Failed);
+        };
+        let ctf_address = Address::from_str(&self.contracts.ctf).context("Failed to parse CTF contract address")?;
+        let via_safe = self.proxy_wallet_address.is_some() && self.signature_type.unwrap_or(1) == 2;
+        if !receipt.status() || (via_safe && payout_redemptions(&receipt, ctf_address) == 0) {
+            return Ok(RedemptionStatus::Failed);
+        }
+        Ok(match receipt.block_number {
+            Some(mined_at) if self.is_final(provider, mined_at).await => RedemptionStatus::Redeemed,
+            _ => RedemptionStatus::Pending,
+        })
+    }
+
+    /// (max_fee_per_gas, max_priority_fee_per_gas) in wei: gas station quote when configured and reachable,
+    /// otherwise the node's fee-history estimate, then the configured multiplier / override / cap.
+    async fn redemption_fees<P: Provider>(&self, provider: &P, policy: &GasPolicy) -> Result<(u128, u128)> {
+        if let Some(url) = self.gas.gas_station_url.as_deref() {
+            match self.gas_station_fees(url).await {
+                Ok((max_fee, priority_fee)) => return Ok(eip1559_fees(max_fee, priority_fee, policy)),
+                Err(e) => warn!("Gas station {} unavailable ({}); using node fee estimate", url, e),
+            }
+        }
+        let estimate = provider
+            .estimate_eip1559_fees()
+            .await
+            .context("Failed to estimate EIP-1559 fees")?;
+        Ok(eip1559_fees(
+            estimate.max_fee_per_gas,
+            estimate.max_priority_fee_per_gas,
+            policy,
+        ))
+    }
+
+    /// Polygon gas station v2: {"fast": {"maxPriorityFee": gwei, "maxFee": gwei}, ...}.
+    async fn gas_station_fees(&self, url: &str) -> Result<(u128, u128)> {
+        let json: Value = self
+            .client
+            .get(url)
+            .timeout(self.http.rpc_timeout())
+            .send()
+            .await
+            .context("gas station request failed")?
+            .json()
+            .await
+            .context("gas station response is not JSON")?;
+        let tier = json
+            .get(&self.gas.gas_station_speed)
+            .ok_or_else(|| anyhow::anyhow!("no \"{}\" tier in gas station response", self.gas.gas_station_speed))?;
+        let gwei = |key: &str| tier.get(key).and_then(|v| v.as_f64());
+        match (gwei("maxFee"), gwei("maxPriorityFee")) {
+            (Some(max_fee), Some(priority_fee)) => Ok((gwei_to_wei(max_fee), gwei_to_wei(priority_fee))),
+            _ => anyhow::bail!("gas station tier missing maxFee/maxPriorityFee"),
+        }
+    }
+}
+
+/// CTF `PayoutRedemption` events in `receipt`: one per redeemed condition.
+/// Items from an offset-paged listing: `fetch(page_size, offset)` is called page by page until `limit` items
+/// (0 = all of them) or a short page.
+async fn paginate<T, F, Fut>(page_size: usize, limit: usize, mut fetch: F) -> Result<Vec<T>>
+where
+    F: FnMut(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>>>,
+{
+    let mut items = Vec::new();
+    loop {
+        let wanted = match limit {
+            0 => page_size,
+            limit => page_size.min(limit - items.len()),
+        };
+        let page = fetch(wanted, items.len()).await?;
+        let full = page.len() == wanted;
+        items.extend(page);
+        if !full || (limit > 0 && items.len() >= limit) {
+            return Ok(items);
+        }
+    }
+}
+
+fn payout_redemptions(receipt: &alloy::rpc::types::TransactionReceipt, ctf_address: Address) -> usize {
+    let topic = keccak256(b"PayoutRedemption(address,address,bytes32,bytes32,uint256[],uint256)");
+    receipt
+        .logs()
+        .iter()
+        .filter(|log| log.address() == ctf_address && log.topics().first() == Some(&topic))
+        .count()
+}
+
+/// First of `hashes` that has a receipt (i.e. was mined).
+async fn find_receipt<P: Provider>(
+    provider: &P,
+    hashes: &[B256],
+) -> Option<(alloy::rpc::types::TransactionReceipt, B256)> {
+    for hash in hashes {
+        if let Ok(Some(receipt)) = provider.get_transaction_receipt(*hash).await {
+            return Some((receipt, *hash));
+        }
+    }
+    None
+}
+
+/// The SDK's side for one of ours.
+/// Client with `http`'s connect and pool settings. Requests set their category's timeout; the order timeout is
+/// the fallback for any that don't.
+fn http_client(http: &HttpConfig) -> Client {
+    proxy::client_builder()
+        .timeout(http.order_timeout())
+        .connect_timeout(http.connect_timeout())
+        .pool_max_idle_per_host(http.pool_max_idle_per_host)
+        .pool_idle_timeout(std::time::Duration::from_secs(http.pool_idle_timeout_secs))
+        .build()
+        .expect("Failed to create HTTP client")
+}
+
+/// SDK client config: signatures carry CLOB server time (one extra round trip each), so local clock skew can't get
+/// them rejected.
+/// The L2 API secret as HMAC key: URL-safe base64 as Polymarket issues it, or standard base64.
+fn decode_api_secret(secret: &str) -> Result<Vec<u8>> {
+    use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+    use base64::Engine;
+    URL_SAFE
+        .decode(secret)
+        .or_else(|_| STANDARD.decode(secret))
+        .map_err(|_| anyhow::anyhow!("API secret is not base64; check api_secret or run create-api-key"))
+}
+
+/// `builder.connect(rpc_url)`, with HTTP(S) endpoints reached through the configured proxy.
+async fn connect_rpc<L, F>(builder: ProviderBuilder<L, F>, rpc_url: &str) -> Result<F::Provider>
+where
+    L: ProviderLayer<RootProvider>,
+    F: TxFiller + ProviderLayer<L::Provider>,
+{
+    if rpc_url.starts_with("http://") || rpc_url.starts_with("https://") {
+        let url = rpc_url.parse().context(format!("RPC URL {} is not a URL", redact_url(rpc_url)))?;
+        return Ok(builder.connect_reqwest(proxy::rpc_client()?, url));
+    }
+    Ok(builder.connect(rpc_url).await?)
+}
+
+fn clob_config() -> ClobConfig {
+    ClobConfig::builder().use_server_time(true).build()
+}
+
+fn clob_side(side: Side) -> ClobSide {
+    match side {
+        Side::Buy => ClobSide::Buy,
+        Side::Sell => ClobSide::Sell,
+    }
+}
+
+fn clob_order_type(order_type: OrderType) -> ClobOrderType {
+    match order_type {
+        OrderType::Gtc => ClobOrderType::GTC,
+        OrderType::Fok => ClobOrderType::FOK,
+        OrderType::Fak => ClobOrderType::FAK,
+        OrderType::Gtd => ClobOrderType::GTD,
+    }
 }
 
 // --- Chainlink BTC/USD price via Ethereum RPC (for price-to-beat) ---
@@ -1142,7 +3068,7 @@ fn chainlink_latest_round_selector() -> [u8; 4] {
     [h[0], h[1], h[2], h[3]]
 }
 
-/// Fetch current BTC/USD price from Chainlink data feed via eth_call.
+/// Fetch the latest answer of a Chainlink USD feed (8 decimals, e.g. BTC/USD or POL/USD) via eth_call.
 /// Returns (price_usd, updated_at_unix_secs) or error description for logging.
 pub async fn get_chainlink_btc_price_usd(
     client: &Client,
@@ -1197,3 +3123,161 @@ pub async fn get_chainlink_btc_price_usd(
     let updated_at = u64::from_be_bytes(updated_slice[24..32].try_into().map_err(|_| "updatedAt bytes")?);
     Ok((price, updated_at))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy::primitives::U64;
+    use alloy::transports::mock::Asserter;
+
+    #[tokio::test]
+    async fn trade_history_follows_the_offset_until_the_limit_or_a_short_page() {
+        // A listing of 12 trades, served 5 at a time at most; records each (page size, offset) asked for.
+        let listing: Vec<u32> = (0..12).collect();
+        let asked = std::sync::Mutex::new(Vec::new());
+        let fetch = |page_size: usize, offset: usize| {
+            asked.lock().unwrap().push((page_size, offset));
+            let page = listing.iter().skip(offset).take(page_size.min(5)).copied().collect();
+            async move { Ok::<Vec<u32>, anyhow::Error>(page) }
+        };
+        let pages = || std::mem::take(&mut *asked.lock().unwrap());
+
+        assert_eq!(paginate(5, 0, fetch).await.unwrap(), listing);
+        assert_eq!(pages(), [(5, 0), (5, 5), (5, 10)]);
+
+        // The last page only asks for what is left of the limit.
+        assert_eq!(paginate(5, 7, fetch).await.unwrap(), listing[..7]);
+        assert_eq!(pages(), [(5, 0), (2, 5)]);
+
+        // A listing that ends exactly on a page boundary takes one more, empty, page to notice.
+        assert_eq!(paginate(4, 0, fetch).await.unwrap(), listing);
+        assert_eq!(pages(), [(4, 0), (4, 4), (4, 8), (4, 12)]);
+
+        let failing = |_, _| async { Err::<Vec<u32>, _>(anyhow::anyhow!("Data API returned 503")) };
+        assert!(paginate(5, 0, failing).await.is_err());
+    }
+
+    #[test]
+    fn unauthorized_is_read_from_the_sdk_status() {
+        use polymarket_client_sdk::error::{Error, Method, StatusCode};
+        let status = |code| Error::status(code, Method::POST, "/order".to_string(), "Unauthorized/Invalid api key");
+        assert!(is_unauthorized(&status(StatusCode::UNAUTHORIZED)));
+        // The message alone doesn't count.
+        assert!(!is_unauthorized(&status(StatusCode::BAD_REQUEST)));
+        assert!(!is_unauthorized(&Error::validation("unauthorized")));
+    }
+
+    #[test]
+    fn decodes_url_safe_and_standard_api_secrets() {
+        // Bytes 0xfb 0xff 0xbf encode to "-_-_" URL-safe and "+/+/" standard.
+        assert_eq!(decode_api_secret("-_-_").unwrap(), vec![0xfb, 0xff, 0xbf]);
+        assert_eq!(decode_api_secret("+/+/").unwrap(), vec![0xfb, 0xff, 0xbf]);
+        assert!(decode_api_secret("not base64!").is_err());
+    }
+
+    fn finality_api(confirmations: u64) -> PolymarketApi {
+        let mut api = PolymarketApi::new(ApiSettings::default());
+        api.gas.confirmations = confirmations;
+        api.gas.tx_timeout_secs = 10;
+        api.gas.finality_timeout_secs = 60;
+        api
+    }
+
+    fn mocked_chain() -> (Asserter, impl Provider) {
+        let asserter = Asserter::new();
+        let provider = ProviderBuilder::new()
+            .disable_recommended_fillers()
+            .connect_mocked_client(asserter.clone());
+        (asserter, provider)
+    }
+
+    /// Successful receipt of `hash` mined at `block` (each block has its own hash).
+    fn receipt(hash: B256, block: u64) -> alloy::rpc::types::TransactionReceipt {
+        serde_json::from_value(serde_json::json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionHash": hash,
+            "transactionIndex": "0x0",
+            "blockHash": B256::with_last_byte(block as u8),
+            "blockNumber": format!("{:#x}", block),
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "from": Address::repeat_byte(0xaa),
+            "to": Address::repeat_byte(0xbb),
+            "contractAddress": null,
+        }))
+        .unwrap()
+    }
+
+    async fn wait_final(
+        api: &PolymarketApi,
+        provider: &impl Provider,
+        hash: B256,
+        block: u64,
+    ) -> Result<(alloy::rpc::types::TransactionReceipt, B256)> {
+        let (mut tx, mut broadcasts) = (TransactionRequest::default(), vec![hash]);
+        api.await_finality(provider, &mut tx, &api.gas.policy(), &mut broadcasts, receipt(hash, block), hash)
+            .await
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_redemption_is_held_until_it_is_confirmations_deep() {
+        let (api, (asserter, provider)) = (finality_api(3), mocked_chain());
+        let hash = B256::repeat_byte(1);
+        // Mined at 100: a head of 102 is short of 3 confirmations, 103 has them.
+        for head in [102u64, 103] {
+            asserter.push_success(&receipt(hash, 100));
+            asserter.push_success(&U64::from(head));
+        }
+        let (receipt, final_hash) = wait_final(&api, &provider, hash, 100).await.unwrap();
+        assert_eq!((receipt.block_number, final_hash), (Some(100), hash));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_redemption_reorged_out_counts_its_confirmations_from_the_block_it_lands_in_again() {
+        let (api, (asserter, provider)) = (finality_api(1), mocked_chain());
+        let hash = B256::repeat_byte(2);
+        // The receipt at 100 disappears, then the tx is re-included at 105: head 105 is no longer enough.
+        asserter.push_success(&serde_json::Value::Null);
+        asserter.push_success(&receipt(hash, 105));
+        asserter.push_success(&U64::from(105));
+        asserter.push_success(&receipt(hash, 105));
+        asserter.push_success(&U64::from(106));
+        let (receipt, final_hash) = wait_final(&api, &provider, hash, 100).await.unwrap();
+        assert_eq!((receipt.block_number, final_hash), (Some(105), hash));
+        assert_eq!(receipt.block_hash, Some(B256::with_last_byte(105)));
+        assert!(asserter.read_q().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_redemption_not_final_in_time_is_reported_unconfirmed_with_its_hash() {
+        let (mut api, (asserter, provider)) = (finality_api(3), mocked_chain());
+        api.gas.finality_timeout_secs = 2 * FINALITY_POLL_SECS;
+        let hash = B256::repeat_byte(3);
+        for _ in 0..2 {
+            asserter.push_success(&receipt(hash, 100));
+            asserter.push_success(&U64::from(101));
+        }
+        let err = wait_final(&api, &provider, hash, 100).await.unwrap_err();
+        let unconfirmed = err.downcast_ref::<UnconfirmedRedemption>().expect("UnconfirmedRedemption");
+        assert_eq!(unconfirmed.tx_hash, format!("{:?}", hash));
+    }
+
+    #[tokio::test]
+    async fn redemption_status_reads_the_receipt_and_its_depth() {
+        let (api, (asserter, provider)) = (finality_api(3), mocked_chain());
+        let hash = B256::repeat_byte(4);
+        asserter.push_success(&receipt(hash, 100));
+        asserter.push_success(&U64::from(101));
+        asserter.push_success(&receipt(hash, 100));
+        asserter.push_success(&U64::from(103));
+        asserter.push_success(&serde_json::Value::Null);
+        for expected in [RedemptionStatus::Pending, RedemptionStatus::Redeemed, RedemptionStatus::Failed] {
+            assert_eq!(api.redemption_status_via(&provider, hash).await.unwrap(), expected);
+        }
+    }
+}
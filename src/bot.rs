@@ -0,0 +1,400 @@
+//! Embedding entry point: build the overlap bot from a `Config`, optionally with a custom `Strategy`, then start
+//! and stop it and subscribe to its arb signals or its full event stream (`events`).
+//!
+//! ```no_run
+//! # async fn example(config: polymarket_arbitrage_bot::config::Config) -> anyhow::Result<()> {
+//! use futures_util::StreamExt;
+//! use polymarket_arbitrage_bot::ArbBot;
+//!
+//! let bot = ArbBot::builder().config(config).build()?;
+//! bot.connect().await?;
+//! let mut signals = Box::pin(bot.signals());
+//! let handle = bot.start();
+//! while let Some(signal) = signals.next().await {
+//!     println!("{} arb at {:.4}", signal.symbol, signal.effective_sum);
+//! }
+//! handle.stop().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::adapters::polymarket::proxy_wallet::verify_funder;
+use crate::adapters::polymarket::{PolymarketApi, PolymarketClient};
+use crate::adapters::polymarket::ws_recorder;
+use crate::config::Config;
+use crate::events::EventBus;
+use crate::models::ArbSignal;
+use crate::services::admin::spawn_admin_server;
+use crate::services::arbitrage_orchestrator::ArbStrategy;
+use crate::services::strategy::Strategy;
+use crate::services::webhook::spawn_webhook_sink;
+use crate::utils::clock::Clock;
+use crate::utils::proxy;
+use crate::utils::supervisor::install_panic_hook;
+use anyhow::Result;
+use futures_util::stream::{self, Stream};
+use log::{error, info, warn};
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// One or more `ArbStrategy` instances (see `Config::strategy_instances`) sharing an API client, or one client
+/// per trading wallet when `wallets` are configured.
+pub struct ArbBot {
+    api: Arc<PolymarketApi>,
+    config: Config,
+    /// Client and config of each wallet in `wallets`.
+    wallets: Vec<(Arc<PolymarketApi>, Config)>,
+    events: EventBus,
+    strategies: Vec<Arc<ArbStrategy>>,
+    install_panic_hook: bool,
+}
+
+#[derive(Default)]
+pub struct ArbBotBuilder {
+    config: Option<Config>,
+    api: Option<Arc<PolymarketApi>>,
+    strategy: Option<Arc<dyn Strategy>>,
+    clock: Option<Arc<dyn Clock>>,
+    allow_dangerous_settings: bool,
+    install_proxy: bool,
+    install_panic_hook: bool,
+}
+
+impl ArbBotBuilder {
+    pub fn config(mut self, config: Config) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Reuse an API client; by default one is created from `config.polymarket`.
+    pub fn api(mut self, api: Arc<PolymarketApi>) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    /// Trade with `strategy` in every instance instead of the one `strategy.kind` selects.
+    pub fn with_strategy(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.strategy = Some(Arc::new(strategy));
+        self
+    }
+
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Build even when `StrategyConfig::dangerous_settings` reports issues.
+    pub fn allow_dangerous_settings(mut self, allow: bool) -> Self {
+        self.allow_dangerous_settings = allow;
+        self
+    }
+
+    /// Also route this process's HTTP clients, RPC providers, and WebSockets through `config.proxy`
+    /// (`utils::proxy::install`). Off by default, as it applies to the whole process. The CLOB SDK's client only
+    /// follows `HTTPS_PROXY`/`HTTP_PROXY`: set those, or call `utils::proxy::export_env`, before starting the
+    /// runtime.
+    pub fn install_proxy(mut self, install: bool) -> Self {
+        self.install_proxy = install;
+        self
+    }
+
+    /// Also log panics of the whole process through `log` (`utils::supervisor::install_panic_hook`) from `start`.
+    /// Off by default, so the embedding program's own panic hook is left in place.
+    pub fn install_panic_hook(mut self, install: bool) -> Self {
+        self.install_panic_hook = install;
+        self
+    }
+
+    /// Fails without a config, on invalid `instances`, or on dangerous settings unless allowed.
+    pub fn build(self) -> Result<ArbBot> {
+        let config = self
+            .config
+            .ok_or_else(|| anyhow::anyhow!("ArbBot::builder() needs a config"))?;
+        if self.install_proxy {
+            proxy::install(&config.proxy)?;
+        }
+        let instances = config.strategy_instances()?;
+        let issues = dangerous_settings(&instances);
+        if !issues.is_empty() && !self.allow_dangerous_settings {
+            anyhow::bail!("{} dangerous setting(s): {}", issues.len(), issues.join("; "));
+        }
+        let api = self
+            .api
+            .unwrap_or_else(|| Arc::new(PolymarketApi::from_config(&config.polymarket)));
+        let mut wallets: Vec<(Arc<PolymarketApi>, Config)> = Vec::new();
+        let instances = instances
+            .into_iter()
+            .map(|(name, instance)| {
+                let client: Arc<dyn PolymarketClient> = match &instance.wallet {
+                    None => api.clone(),
+                    Some(slot) => match wallets.iter().find(|(_, c)| c.wallet.as_ref() == Some(slot)) {
+                        Some((wallet_api, _)) => wallet_api.clone(),
+                        None => {
+                            let wallet_api = Arc::new(PolymarketApi::from_config(&instance.polymarket));
+                            wallets.push((wallet_api.clone(), instance.clone()));
+                            wallet_api
+                        }
+                    },
+                };
+                (name, instance, client)
+            })
+            .collect();
+        let events = EventBus::new();
+        for api in std::iter::once(&api).chain(wallets.iter().map(|(api, _)| api)) {
+            api.publish_alerts(&events);
+        }
+        let strategies = ArbStrategy::new_instances_with_apis(instances, events.clone())
+            .into_iter()
+            .map(|s| match &self.strategy {
+                Some(strategy) => s.with_strategy(Arc::clone(strategy)),
+                None => s,
+            })
+            .map(|s| match &self.clock {
+                Some(clock) => s.with_clock(Arc::clone(clock)),
+                None => s,
+            })
+            .map(Arc::new)
+            .collect();
+        Ok(ArbBot {
+            api,
+            config,
+            wallets,
+            events,
+            strategies,
+            install_panic_hook: self.install_panic_hook,
+        })
+    }
+}
+
+impl ArbBot {
+    pub fn builder() -> ArbBotBuilder {
+        ArbBotBuilder::default()
+    }
+
+    pub fn api(&self) -> Arc<PolymarketApi> {
+        Arc::clone(&self.api)
+    }
+
+    pub fn strategies(&self) -> &[Arc<ArbStrategy>] {
+        &self.strategies
+    }
+
+    /// Sync the clock with the CLOB and, with a private key or signer, authenticate and check approvals.
+    pub async fn connect(&self) -> Result<()> {
+        connect(&self.api, &self.config).await?;
+        self.connect_wallets().await
+    }
+
+    /// `connect` for each wallet in `wallets`; nothing without any.
+    pub async fn connect_wallets(&self) -> Result<()> {
+        for (api, config) in &self.wallets {
+            if let Some(wallet) = &config.wallet {
+                info!("Connecting wallet {}", wallet.name);
+            }
+            connect(api, config).await?;
+        }
+        Ok(())
+    }
+
+    /// Quotes, arbs, orders, resolutions, and redemptions of every instance (see `events::ArbEvent`).
+    pub fn events(&self) -> EventBus {
+        self.events.clone()
+    }
+
+    /// Arb signals of every instance. Subscribe before `start` to see the first ones.
+    pub fn signals(&self) -> impl Stream<Item = ArbSignal> {
+        stream::select_all(self.strategies.iter().map(|s| Box::pin(s.signals())))
+    }
+
+    /// Run every instance on the current tokio runtime until stopped, with the webhook sink and admin API when
+    /// configured.
+    pub fn start(&self) -> BotHandle {
+        if self.install_panic_hook {
+            install_panic_hook();
+        }
+        if let Err(e) = ws_recorder::start(&self.config.polymarket.ws_record_dir) {
+            warn!("WS recording disabled: {}", e);
+        }
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        let webhook = spawn_webhook_sink(&self.config.webhook, &self.events, shutdown_rx.clone());
+        let admin = spawn_admin_server(&self.config.admin, self.strategies.clone(), shutdown_rx);
+        let strategies = self.strategies.clone();
+        let task = tokio::spawn(async move {
+            for strategy in &strategies {
+                strategy.sweep_stray_orders("startup").await;
+            }
+            futures_util::future::try_join_all(strategies.iter().map(|s| s.run())).await?;
+            Ok(())
+        });
+        BotHandle {
+            strategies: self.strategies.clone(),
+            task,
+            shutdown,
+            webhook,
+            admin,
+        }
+    }
+}
+
+/// A started `ArbBot`.
+pub struct BotHandle {
+    strategies: Vec<Arc<ArbStrategy>>,
+    task: tokio::task::JoinHandle<Result<()>>,
+    /// Turned true once trading has stopped, to close the webhook sink and the admin API.
+    shutdown: watch::Sender<bool>,
+    webhook: Option<tokio::task::JoinHandle<()>>,
+    admin: Option<tokio::task::JoinHandle<()>>,
+}
+
+impl BotHandle {
+    /// Stop trading and wait for the bot to wind down. Rounds in progress enter no new trades, cancel their
+    /// resting legs, and hand their trades to the pending-resolution store (see `ArbStrategy::stop`); then the
+    /// webhook delivers what it has queued and the admin API closes.
+    pub async fn stop(self) -> Result<()> {
+        self.request_stop();
+        self.wait().await
+    }
+
+    fn request_stop(&self) {
+        for strategy in &self.strategies {
+            strategy.stop();
+        }
+    }
+
+    /// Wait until the bot fails or is stopped; a stop counts as success.
+    pub async fn wait(self) -> Result<()> {
+        let result = joined(self.task.await);
+        close_sinks(self.shutdown, self.webhook, self.admin).await;
+        result
+    }
+
+    /// `wait`, except that Ctrl-C stops the bot and then sweeps the resting orders its loops leave behind
+    /// (see `ArbStrategy::sweep_stray_orders`).
+    pub async fn wait_until_ctrl_c(self) -> Result<()> {
+        let BotHandle {
+            strategies,
+            mut task,
+            shutdown,
+            webhook,
+            admin,
+        } = self;
+        let result = tokio::select! {
+            joined_task = &mut task => joined(joined_task),
+            signal = tokio::signal::ctrl_c() => {
+                signal?;
+                info!("Ctrl-C: stopping");
+                for strategy in &strategies {
+                    strategy.stop();
+                }
+                let result = joined(task.await);
+                for strategy in &strategies {
+                    strategy.sweep_stray_orders("shutdown").await;
+                }
+                result
+            }
+        };
+        close_sinks(shutdown, webhook, admin).await;
+        result
+    }
+}
+
+fn joined(joined: Result<Result<()>, tokio::task::JoinError>) -> Result<()> {
+    match joined {
+        Ok(result) => result,
+        Err(e) if e.is_cancelled() => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Signal `shutdown` and wait for the webhook sink and admin API to end.
+async fn close_sinks(
+    shutdown: watch::Sender<bool>,
+    webhook: Option<tokio::task::JoinHandle<()>>,
+    admin: Option<tokio::task::JoinHandle<()>>,
+) {
+    shutdown.send_replace(true);
+    for task in webhook.into_iter().chain(admin) {
+        if let Err(e) = task.await {
+            warn!("Webhook or admin task ended abnormally: {}", e);
+        }
+    }
+}
+
+/// Issues of every instance, prefixed with the instance name when there is one.
+pub fn dangerous_settings(instances: &[(String, Config)]) -> Vec<String> {
+    instances
+        .iter()
+        .flat_map(|(name, config)| {
+            config.strategy.dangerous_settings().into_iter().map(move |issue| {
+                if name.is_empty() {
+                    issue
+                } else {
+                    format!("[{}] {}", name, issue)
+                }
+            })
+        })
+        .collect()
+}
+
+/// Clock sync, then (with a private key or signer) proxy wallet check, CLOB authentication, and the approval
+/// check, which sends missing approvals when `auto_approve` is on outside simulation.
+pub async fn connect(api: &PolymarketApi, config: &Config) -> Result<()> {
+    // A broken signer must not leave the bot running on `private_key`, or monitor-only, unnoticed.
+    if let Some(e) = api.signer_error() {
+        anyhow::bail!("polymarket.signer is invalid: {}", e);
+    }
+    if let Err(e) = api.sync_clock().await {
+        warn!("Clock sync with CLOB failed, using local time: {}", e);
+    }
+
+    if !api.has_signer() {
+        warn!("⚠️ No private key or signer provided. Bot can only monitor (no orders).");
+        return Ok(());
+    }
+    // Checked against the network's factories; a wallet type without one isn't checked.
+    if config.polymarket.verify_proxy_wallet {
+        verify_funder(
+            api.signer_address()?,
+            &config.polymarket.contracts(),
+            config.polymarket.proxy_wallet_address.as_deref(),
+            config.polymarket.signature_type,
+        )?;
+    }
+    if let Err(e) = api.authenticate().await {
+        error!("Authentication failed: {}", e);
+        anyhow::bail!("Authentication failed. Please check your credentials.");
+    }
+    let polymarket = &config.polymarket;
+    if polymarket.api_key.is_some() && polymarket.api_secret.is_some() && polymarket.api_passphrase.is_some() {
+        if let Err(e) = api.check_credentials().await {
+            warn!("API credential check failed: {}", e);
+        }
+    }
+    let send = config.polymarket.auto_approve && !config.strategy.simulation_mode;
+    if let Err(e) = ensure_approvals(api, send).await {
+        warn!("Approval check failed: {}", e);
+    }
+    Ok(())
+}
+
+/// Report missing USDC allowances / CTF approvals for the exchanges; with `send`, set them.
+pub async fn ensure_approvals(api: &PolymarketApi, send: bool) -> Result<()> {
+    let missing = api.missing_approvals().await?;
+    if missing.is_empty() {
+        eprintln!("✅ USDC allowance and CTF approval are set for all exchange contracts");
+        return Ok(());
+    }
+    for (_, _, what) in &missing {
+        eprintln!("⚠️ Missing {}", what);
+    }
+    if !send {
+        eprintln!("Orders will be rejected for \"not enough balance / allowance\". Run the `approve` subcommand or set polymarket.auto_approve.");
+        return Ok(());
+    }
+    eprintln!("Sending {} approval(s)...", missing.len());
+    for hash in api.send_approvals(&missing).await? {
+        eprintln!("Approval tx: {}", hash);
+    }
+    Ok(())
+}
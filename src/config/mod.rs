@@ -1,6 +1,18 @@
-use clap::Parser;
-use serde::{Deserialize, Serialize};
+use crate::adapters::polymarket::ws_rtds::SPOT_HISTORY_SECS;
+use crate::domain::fees::LegFees;
+use crate::domain::gas::{gwei_to_wei, GasPolicy};
+use crate::domain::sizing::SizingPolicy;
+use crate::domain::window::PeriodPair;
+use crate::models::OrderType;
+use crate::utils::secret::{with_exposed, Secret};
+use crate::utils::time_windows::{et_minute_of_day, et_weekday, in_daily_window, parse_hhmm};
+use alloy::signers::local::PrivateKeySigner;
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -8,36 +20,347 @@ pub struct Args {
     #[arg(short, long, default_value = "config.json")]
     pub config: PathBuf,
 
+    /// Apply this entry of the config's `profiles` (e.g. sim, live) over the rest of the file.
+    #[arg(long)]
+    pub profile: Option<String>,
+
     #[arg(long)]
     pub redeem: bool,
 
     #[arg(long, requires = "redeem")]
     pub condition_id: Option<String>,
+
+    /// Replay a `polymarket.ws_record_dir` recording through the strategy in simulation mode.
+    #[arg(long, value_name = "DIR")]
+    pub replay: Option<PathBuf>,
+
+    /// Replay pacing: 1 keeps the recorded timing, N runs N times faster, 0 as fast as possible.
+    #[arg(long, requires = "replay", default_value_t = 0.0)]
+    pub replay_speed: f64,
+
+    /// Start even when the config fails the dangerous-settings checks.
+    #[arg(long = "i-know-what-im-doing")]
+    pub i_know_what_im_doing: bool,
+
+    /// `redeemer` skips trading and sweeps the wallet's redeemable positions every
+    /// `strategy.redeemer_interval_secs`.
+    #[arg(long, value_enum, default_value_t = RunMode::Trade)]
+    pub mode: RunMode,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RunMode {
+    Trade,
+    Redeemer,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Render a trade's lifecycle from the journal as a Mermaid sequence diagram.
+    Lifecycle {
+        /// Trade id from the journal, e.g. btc-1767726000-1.
+        trade_id: String,
+        /// Journal file (defaults to strategy.journal_path).
+        #[arg(long)]
+        journal: Option<PathBuf>,
+        /// Write the diagram to this file instead of stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Interactively create config.json: key, proxy wallet detection, symbols and sizes, then checks.
+    Init,
+    /// Check USDC allowance and CTF approval for the exchange contracts, and send any that are missing.
+    Approve {
+        /// Only report what is missing.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Create (or derive, when the key already has them) CLOB API credentials from the private key or signer,
+    /// and write them into the config (into the `--profile` entry when one is given).
+    CreateApiKey {
+        /// Nonce the credentials are tied to; the same key and nonce always derive the same credentials.
+        #[arg(long, default_value_t = 0)]
+        nonce: u64,
+        /// Print them as POLYMARKET_API_* environment variables instead of writing the config.
+        #[arg(long)]
+        print: bool,
+    },
+    /// List redemptions waiting for retry and those that permanently failed.
+    RedeemQueue {
+        /// Queue file (defaults to strategy.redeem_queue_path).
+        #[arg(long)]
+        queue: Option<PathBuf>,
+    },
+    /// Research mode, no trading: record Chainlink spot next to both markets' best bid/ask during overlaps.
+    Research {
+        /// CSV file rows are appended to.
+        #[arg(long, default_value = "research.csv")]
+        output: PathBuf,
+        /// Milliseconds between samples per symbol.
+        #[arg(long, default_value_t = 1000)]
+        interval_ms: u64,
+    },
+    /// Cross-venue arbitrage: Polymarket vs Kalshi 15m crypto up/down markets (see `strategy.cross_venue`).
+    CrossVenue,
+    /// Run the bot with `strategy.kind` complete_set (see `strategy::CompleteSet`).
+    CompleteSet,
+    /// Print the equity curve recorded by portfolio snapshots.
+    Equity {
+        /// Snapshot file (defaults to strategy.portfolio_snapshot_path).
+        #[arg(long)]
+        snapshots: Option<PathBuf>,
+        /// Print CSV (for charting) instead of a table.
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Save the price history of past up/down markets as OHLC candles (CSV) for offline analysis.
+    DownloadHistory {
+        /// CSV file to write (overwritten).
+        #[arg(long, default_value = "history.csv")]
+        output: PathBuf,
+        /// Symbols, comma-separated (defaults to strategy.symbols).
+        #[arg(long, value_delimiter = ',')]
+        symbols: Vec<String>,
+        /// Market periods in minutes, comma-separated (defaults to the strategy's long and short periods).
+        #[arg(long, value_delimiter = ',')]
+        periods: Vec<i64>,
+        /// How far back to go, in hours.
+        #[arg(long, default_value_t = 24)]
+        hours: i64,
+        /// Candle length in minutes.
+        #[arg(long, default_value_t = 1)]
+        candle_mins: u32,
+    },
+    /// Check clock offset, signer, CLOB authentication, USDC balance/allowance, and the account's resting orders
+    /// (marking the ones the journal doesn't show this bot placed).
+    Doctor,
+    /// Print the account's most recent fills from the data API.
+    Fills {
+        /// How many fills to show, newest first.
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Only fills in this condition id.
+        #[arg(long)]
+        market: Option<String>,
+    },
+    /// Manage the skip-list of markets (condition ids or slugs) the bot never trades.
+    Skip {
+        #[command(subcommand)]
+        action: SkipAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SkipAction {
+    /// Never trade this condition id or slug.
+    Add {
+        key: String,
+        #[arg(long, default_value = "manual")]
+        reason: String,
+    },
+    /// Trade this market again and reset its anomaly count.
+    Remove { key: String },
+    /// Show skipped markets and anomaly counts.
+    List,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
     pub polymarket: PolymarketConfig,
     pub strategy: StrategyConfig,
+    /// Kalshi credentials, only used by the `cross-venue` subcommand.
+    #[serde(default)]
+    pub kalshi: KalshiConfig,
+    /// Variants of `strategy` run side by side (see `StrategyInstance`); empty runs `strategy` alone.
+    #[serde(default)]
+    pub instances: Vec<StrategyInstance>,
+    /// Trading wallets the arbs are spread over (see `WalletConfig`); empty trades from the `polymarket` keys.
+    #[serde(default)]
+    pub wallets: Vec<WalletConfig>,
+    /// How arbs are spread over `wallets` (see `WalletAllocation`).
+    #[serde(default)]
+    pub wallet_allocation: WalletAllocation,
+    /// Set by `Config::wallet_configs` on each wallet's config; None without `wallets`.
+    #[serde(skip)]
+    pub wallet: Option<WalletSlot>,
+    /// Named overlays selected with `--profile`, e.g. {"sim": {"strategy": {"simulation_mode": true}}}. Top-level
+    /// keys replace the base ones, and nested objects merge key by key.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, serde_json::Value>,
+    /// The profile applied by `Config::load_profile`, if any.
+    #[serde(skip)]
+    pub profile: Option<String>,
+    /// POST bot events to an HTTP endpoint (see `WebhookConfig`).
+    #[serde(default)]
+    pub webhook: WebhookConfig,
+    /// HTTP admin API for pausing and tuning a running bot (see `AdminConfig`).
+    #[serde(default)]
+    pub admin: AdminConfig,
+    /// Outbound HTTP or SOCKS5 proxy for REST and WebSocket connections (see `ProxyConfig`).
+    #[serde(default)]
+    pub proxy: ProxyConfig,
+}
+
+/// A named variant of `strategy`. Any strategy keys given here replace the base ones, and nested objects merge
+/// key by key. e.g. {"name": "aggressive", "sum_threshold": 0.99, "symbols": ["btc"]}. Unless overridden, its
+/// journal, redeem queue, canary state, and portfolio snapshot files get the name as a suffix.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyInstance {
+    pub name: String,
+    #[serde(flatten)]
+    pub overrides: serde_json::Map<String, serde_json::Value>,
 }
 
-/// 15m vs 5m arbitrage: trade overlap window; per-symbol price-to-beat tolerance (USD).
+/// A trading wallet: any `polymarket` keys given here replace the base ones, except that the key, proxy wallet,
+/// and API credentials are never inherited. e.g. {"name": "second", "private_key": "0x..", "proxy_wallet_address":
+/// "0x..", "signature_type": 1, "symbols": ["eth"]}. Each wallet trades as its own instance, with the wallet name
+/// as a suffix on its journal, redeem queue, PnL, canary state, and portfolio snapshot files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WalletConfig {
+    pub name: String,
+    /// Symbols this wallet trades under `per_symbol` allocation; empty takes the symbols no other wallet lists.
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    #[serde(flatten)]
+    pub overrides: serde_json::Map<String, serde_json::Value>,
+}
+
+/// How arbs are spread over several `wallets`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WalletAllocation {
+    /// Every wallet watches every symbol and they take turns: each long period of a symbol goes to the next
+    /// wallet, so consecutive arbs land on different accounts.
+    #[default]
+    RoundRobin,
+    /// Each symbol is traded by the one wallet listing it in `symbols`.
+    PerSymbol,
+}
+
+/// Which of the configured wallets a config trades from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletSlot {
+    pub name: String,
+    pub index: usize,
+    pub count: usize,
+    pub allocation: WalletAllocation,
+}
+
+impl WalletSlot {
+    /// Whether this wallet trades the long period of `symbol` starting at `period_start`. Under round robin the
+    /// turn rotates every period, offset per symbol so the wallets share each period's symbols.
+    pub fn takes_period(&self, symbol: &str, period_start: i64, period_secs: i64) -> bool {
+        if self.allocation != WalletAllocation::RoundRobin || self.count < 2 {
+            return true;
+        }
+        let offset: u64 = symbol.to_lowercase().bytes().map(u64::from).sum();
+        let period = (period_start / period_secs.max(1)).max(0) as u64;
+        (period + offset) % self.count as u64 == self.index as u64
+    }
+}
+
+/// Long vs short period arbitrage (15m vs 5m by default): trade overlap window; per-symbol price-to-beat tolerance (USD).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategyConfig {
-    /// Symbols to arb (15m vs 5m overlap). e.g. ["btc", "eth", "sol", "xrp"].
+    /// Strategy to run (see `StrategyKind`); "overlap" by default.
+    #[serde(default)]
+    pub kind: StrategyKind,
+    /// Symbols to arb (long vs short overlap). e.g. ["btc", "eth", "sol", "xrp"].
     #[serde(default = "default_symbols")]
     pub symbols: Vec<String>,
+    /// Also trade any other asset Gamma lists up/down markets for (see `SymbolDiscoveryConfig`).
+    #[serde(default)]
+    pub symbol_discovery: SymbolDiscoveryConfig,
+    /// Period pair and overlap window, e.g. {"long_period_mins":60,"short_period_mins":15,"overlap_start_offset_mins":45}.
+    /// Fields named `_15` / `_5` elsewhere (journal, trades, signals) refer to the long / short leg.
+    #[serde(default)]
+    pub periods: PeriodPair,
     /// Max sum of (15m one side ask + 5m opposite side ask) to trigger arb (e.g. 0.99).
     #[serde(default = "default_sum_threshold")]
     pub sum_threshold: f64,
     /// Seconds to wait after placing an arb before placing the next one (cooldown).
     #[serde(default = "default_trade_interval_secs")]
     pub trade_interval_secs: u64,
+    /// Most arbs per symbol and long period, so one noisy window can't take the whole bankroll (0 = no limit).
+    #[serde(default)]
+    pub max_trades_per_period: u32,
+    /// Per-symbol overrides of the cooldown and trade limit, keyed by lowercase symbol, e.g.
+    /// {"sol": {"trade_interval_secs": 30, "max_trades_per_period": 2}}.
+    #[serde(default)]
+    pub symbol_limits: BTreeMap<String, SymbolLimits>,
+    /// No new trades in the last this-many seconds of the long period, when books are pulled and GTC
+    /// orders can't realistically fill (0 = trade until the close).
+    #[serde(default = "default_no_trade_before_close_secs")]
+    pub no_trade_before_close_secs: u64,
     #[serde(default)]
     pub simulation_mode: bool,
-    /// Size in shares per leg (15m and 5m).
+    /// Only publish detected arbs to signal subscribers; place no orders (execution handled downstream).
+    #[serde(default)]
+    pub signals_only: bool,
+    /// Size in shares per leg (15m and 5m); the base that `sizing` scales.
     #[serde(default = "default_arb_shares")]
     pub arb_shares: String,
+    /// Size arbs by available capital and edge, capped per period (see `SizingConfig`).
+    #[serde(default)]
+    pub sizing: SizingConfig,
+    /// Order type of both arb legs: "GTC" (rests until filled or cancelled), "FOK" (fills completely at
+    /// once or is cancelled), "FAK" (fills what it can at once, the rest is cancelled), or "GTD" (rests
+    /// for `leg_order_ttl_secs`).
+    #[serde(default = "default_leg_order_type")]
+    pub leg_order_type: String,
+    /// Lifetime of GTD legs in seconds, counted from CLOB server time.
+    #[serde(default = "default_leg_order_ttl_secs")]
+    pub leg_order_ttl_secs: u64,
+    /// GTC/GTD legs still resting this many seconds after placement are cancelled, keeping any partial
+    /// fill (0 = leave them resting).
+    #[serde(default)]
+    pub leg_cancel_after_secs: u64,
+    /// "both" (default) or "thinner_first" (see `LegSequencing`).
+    #[serde(default)]
+    pub leg_sequencing: LegSequencing,
+    /// Daily stop: no new positions after `stop_at_et`, residual exposure flattened, idle until `resume_at_et`.
+    #[serde(default)]
+    pub end_of_day: EndOfDayConfig,
+    /// Weekdays, ET hours, and blackout intervals for new trades (see `ScheduleConfig`).
+    #[serde(default)]
+    pub schedule: ScheduleConfig,
+    /// Randomize arb size and submission timing so orders don't form a fixed, fadeable pattern.
+    #[serde(default)]
+    pub jitter: JitterConfig,
+    /// Abort (or reprice) an arb whose legs no longer beat the threshold when it is about to be sent.
+    #[serde(default)]
+    pub slippage_guard: SlippageGuardConfig,
+    /// Trade only with the live spot within (or beyond) a band around the strikes (see `StrikeDistanceConfig`).
+    #[serde(default)]
+    pub strike_distance: StrikeDistanceConfig,
+    /// Require positive expected value under a binary option fair-value model (see `PricingModelConfig`).
+    #[serde(default)]
+    pub pricing_model: PricingModelConfig,
+    /// Sell both legs of an open arb before resolution on a profit target or stop (see `ExitRulesConfig`).
+    #[serde(default)]
+    pub exit_rules: ExitRulesConfig,
+    /// Offset the spot delta of open legs on a perp venue near the close (see `HedgeConfig`).
+    #[serde(default)]
+    pub hedge: HedgeConfig,
+    /// Polymarket vs Kalshi arbitrage run by the `cross-venue` subcommand (see `CrossVenueConfig`).
+    #[serde(default)]
+    pub cross_venue: CrossVenueConfig,
+    /// Skip arbs while the spot is moving fast (see `VolatilityFilterConfig`).
+    #[serde(default)]
+    pub volatility_filter: VolatilityFilterConfig,
+    /// Rest bids inside the spread when the sum is near the threshold (see `MakerModeConfig`).
+    #[serde(default)]
+    pub maker_mode: MakerModeConfig,
+    /// After a change to the execution settings (threshold, size, fees, periods, timing), place arbs at
+    /// this fraction of `arb_shares` until one goes through cleanly (0 = off).
+    #[serde(default)]
+    pub canary_fraction: f64,
+    /// Execution settings that last traded cleanly, compared at startup to decide on a canary.
+    #[serde(default = "default_canary_state_path")]
+    pub canary_state_path: String,
     /// Per-symbol max |15m price-to-beat − 5m price-to-beat| (USD) to allow arb.
     #[serde(default, alias = "price_to_beat_tolerance_usd")]
     pub btc_price_to_beat_tolerance_usd: f64,
@@ -47,50 +370,1211 @@ pub struct StrategyConfig {
     pub sol_price_to_beat_tolerance_usd: f64,
     #[serde(default = "default_xrp_tolerance")]
     pub xrp_price_to_beat_tolerance_usd: f64,
+    /// Seconds to wait after the period closes before the first resolution poll.
+    #[serde(default = "default_resolution_initial_delay_secs")]
+    pub resolution_initial_delay_secs: u64,
     /// Seconds between polls when checking if markets are closed/resolved (e.g. 30).
     #[serde(default = "default_resolution_poll_interval_secs")]
     pub resolution_poll_interval_secs: u64,
     /// Max seconds to wait for resolution before giving up (e.g. 600 = 10 min).
     #[serde(default = "default_resolution_max_wait_secs")]
     pub resolution_max_wait_secs: u64,
+    /// Resolution sources in trust order; the first one reporting a winner decides each market.
+    #[serde(default = "default_resolution_sources")]
+    pub resolution_sources: Vec<ResolutionSource>,
+    /// Check a `clob`/`gamma` winner against the CTF payout vector once the oracle has reported on-chain;
+    /// on a disagreement the on-chain winner is used.
+    #[serde(default = "default_resolution_onchain_cross_check")]
+    pub resolution_onchain_cross_check: bool,
     /// Automatically redeem winning tokens after resolution.
     #[serde(default = "default_auto_redeem")]
     pub auto_redeem: bool,
+    /// Defer a redemption while its estimated gas exceeds this fraction of the payout (0 = always redeem).
+    /// Deferred targets are folded into the next batch, where the wrapper overhead is shared.
+    #[serde(default)]
+    pub max_redeem_gas_to_payout_ratio: f64,
+    /// Failed redemptions are persisted here and retried with backoff. Empty keeps the queue in memory only.
+    #[serde(default = "default_redeem_queue_path")]
+    pub redeem_queue_path: String,
+    /// Periods still awaiting resolution are persisted here, so PnL and redemption survive restarts and
+    /// resolution timeouts. Empty keeps them in memory only.
+    #[serde(default = "default_pending_resolution_path")]
+    pub pending_resolution_path: String,
+    /// Realized PnL per symbol and in total, reloaded at startup. Empty keeps it in memory only.
+    #[serde(default = "default_pnl_state_path")]
+    pub pnl_state_path: String,
+    /// Seconds between polls of periods that outlived `resolution_max_wait_secs`.
+    #[serde(default = "default_pending_resolution_poll_secs")]
+    pub pending_resolution_poll_secs: u64,
+    /// Publish a `resolution_delayed` alert for a period still unresolved this long after close (0 = never).
+    #[serde(default = "default_resolution_delay_alert_secs")]
+    pub resolution_delay_alert_secs: u64,
+    /// Seconds between sweeps of redeemable positions in `--mode redeemer`.
+    #[serde(default = "default_redeemer_interval_secs")]
+    pub redeemer_interval_secs: u64,
+    /// Condition ids / slugs never traded (see the `skip` subcommand). Empty keeps the list in memory only.
+    #[serde(default = "default_skip_list_path")]
+    pub skip_list_path: String,
+    /// Skip a market automatically after this many anomalies, e.g. resolution timeouts (0 = never).
+    #[serde(default = "default_skip_after_anomalies")]
+    pub skip_after_anomalies: u32,
+    /// Attempts (including the first) before a redemption is marked permanently failed.
+    #[serde(default = "default_redeem_max_attempts")]
+    pub redeem_max_attempts: u32,
+    /// Hold trading while a USDC deposit to the funder landed within this many seconds (0 = off), so the
+    /// first orders after a top-up aren't rejected for balance that isn't usable yet.
+    #[serde(default = "default_funding_settle_secs")]
+    pub funding_settle_secs: u64,
+    /// Seconds between mark-to-market valuations of open legs at best bids (0 = off).
+    #[serde(default = "default_mark_to_market_interval_secs")]
+    pub mark_to_market_interval_secs: u64,
+    /// Seconds between polls of the data API activity feed for fills, applied to the inventory as they
+    /// happen instead of only after each period (0 = off).
+    #[serde(default = "default_activity_poll_secs")]
+    pub activity_poll_secs: u64,
+    /// Timestamp of the latest fill read from the activity feed, where polling resumes after a restart. Empty
+    /// starts an hour back every time.
+    #[serde(default = "default_activity_cursor_path")]
+    pub activity_cursor_path: String,
+    /// Seconds between checks of the L2 API credentials with an authenticated request, so revoked or expired
+    /// ones are re-derived (or alerted) before an order needs them (0 = only at startup).
+    #[serde(default = "default_credential_check_secs")]
+    pub credential_check_secs: u64,
+    /// Verify tracked inventory against on-chain CTF balances (one RPC call per token) after each period.
+    #[serde(default)]
+    pub verify_inventory_onchain: bool,
+    /// Cancel resting orders the bot isn't tracking: at startup (left over from an earlier run) and on Ctrl-C
+    /// (quotes the stopped loops can no longer manage). Off, they are only logged.
+    #[serde(default = "default_cancel_stray_orders")]
+    pub cancel_stray_orders: bool,
+    /// At the start of each overlap, reconcile the WS-built best bid/ask with one REST book snapshot per token.
+    #[serde(default = "default_book_cross_check")]
+    pub book_cross_check: bool,
+    /// JSONL trade journal (decision, orders, fills, resolution, redemption). Empty disables it.
+    #[serde(default = "default_journal_path")]
+    pub journal_path: String,
+    /// Minutes between portfolio snapshots (cash, open cost, marked value, realized PnL; 0 = off).
+    #[serde(default = "default_portfolio_snapshot_mins")]
+    pub portfolio_snapshot_mins: u64,
+    /// JSONL equity curve the snapshots are appended to (see the `equity` subcommand).
+    #[serde(default = "default_portfolio_snapshot_path")]
+    pub portfolio_snapshot_path: String,
+    /// Fee rates per market type, applied to the arb edge check and to PnL.
+    #[serde(default)]
+    pub fees: FeeConfig,
+}
+
+/// Daily trading stop in ET. Once idle, pending redemptions are pushed through, leftover positions are
+/// merged (both outcomes held) or sold at the best bid, and a daily report is logged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndOfDayConfig {
+    /// "HH:MM" ET after which no new positions are opened; empty disables the daily stop.
+    #[serde(default)]
+    pub stop_at_et: String,
+    /// "HH:MM" ET when trading resumes (the start of the next trading day).
+    #[serde(default = "default_resume_at_et")]
+    pub resume_at_et: String,
+    /// Sell or merge residual positions once idle; off leaves them to resolution and redemption.
+    #[serde(default = "default_flatten")]
+    pub flatten: bool,
+}
+
+impl Default for EndOfDayConfig {
+    fn default() -> Self {
+        Self {
+            stop_at_et: String::new(),
+            resume_at_et: default_resume_at_et(),
+            flatten: default_flatten(),
+        }
+    }
+}
+
+impl EndOfDayConfig {
+    pub fn enabled(&self) -> bool {
+        !self.stop_at_et.trim().is_empty()
+    }
+
+    /// Whether `ts_sec` falls between the daily stop and the resume time.
+    pub fn idle_at(&self, ts_sec: i64) -> bool {
+        match (parse_hhmm(&self.stop_at_et), parse_hhmm(&self.resume_at_et)) {
+            (Some(stop), Some(resume)) => in_daily_window(et_minute_of_day(ts_sec), stop, resume),
+            _ => false,
+        }
+    }
+}
+
+/// When new trades may open. Outside the schedule or inside a blackout no new rounds start, while earlier
+/// positions keep resolving and redeeming.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// ET weekdays to trade on ("mon".."sun"); empty trades every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+    /// "HH:MM" ET window to trade in, wrapping past midnight when end <= start; both empty = all day.
+    #[serde(default)]
+    pub start_et: String,
+    #[serde(default)]
+    pub end_et: String,
+    /// No-trade intervals, e.g. around FOMC or CPI releases.
+    #[serde(default)]
+    pub blackouts: Vec<BlackoutWindow>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlackoutWindow {
+    /// RFC 3339 timestamps, e.g. "2026-12-16T13:45:00-05:00".
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub label: String,
+}
+
+impl BlackoutWindow {
+    fn bounds(&self) -> Option<(i64, i64)> {
+        let parse = |s: &str| chrono::DateTime::parse_from_rfc3339(s.trim()).ok().map(|t| t.timestamp());
+        Some((parse(&self.start)?, parse(&self.end)?))
+    }
+}
+
+impl ScheduleConfig {
+    /// Why new trades are paused at `ts_sec`; None when the schedule allows trading.
+    pub fn paused_at(&self, ts_sec: i64) -> Option<String> {
+        if let Some(blackout) = self
+            .blackouts
+            .iter()
+            .find(|b| b.bounds().is_some_and(|(start, end)| ts_sec >= start && ts_sec < end))
+        {
+            let label = if blackout.label.is_empty() { "blackout" } else { blackout.label.as_str() };
+            return Some(format!("{} until {}", label, blackout.end));
+        }
+        let weekday = et_weekday(ts_sec);
+        if !self.days.is_empty()
+            && !self
+                .days
+                .iter()
+                .any(|d| d.trim().parse::<chrono::Weekday>().is_ok_and(|d| d == weekday))
+        {
+            return Some(format!("no trading on {}", weekday));
+        }
+        if let (Some(start), Some(end)) = (parse_hhmm(&self.start_et), parse_hhmm(&self.end_et)) {
+            if !in_daily_window(et_minute_of_day(ts_sec), start, end) {
+                return Some(format!("outside trading hours {}-{} ET", self.start_et, self.end_et));
+            }
+        }
+        None
+    }
+
+    fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        for day in &self.days {
+            if day.trim().parse::<chrono::Weekday>().is_err() {
+                issues.push(format!("schedule.days entry {:?} is not a weekday", day));
+            }
+        }
+        if !self.start_et.trim().is_empty() || !self.end_et.trim().is_empty() {
+            for (key, value) in [("start_et", &self.start_et), ("end_et", &self.end_et)] {
+                if parse_hhmm(value).is_none() {
+                    issues.push(format!("schedule.{} {:?} is not a HH:MM time", key, value));
+                }
+            }
+        }
+        for blackout in &self.blackouts {
+            match blackout.bounds() {
+                Some((start, end)) if start < end => {}
+                Some(_) => issues.push(format!("schedule blackout {} ends before it starts", blackout.start)),
+                None => issues.push(format!(
+                    "schedule blackout {:?}..{:?} needs RFC 3339 timestamps",
+                    blackout.start, blackout.end
+                )),
+            }
+        }
+        issues
+    }
+}
+
+fn default_resume_at_et() -> String {
+    "00:00".to_string()
+}
+fn default_flatten() -> bool {
+    true
+}
+
+/// Overrides for one symbol; unset fields fall back to the strategy-wide values.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SymbolLimits {
+    #[serde(default)]
+    pub trade_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub max_trades_per_period: Option<u32>,
+}
+
+/// Both legs of an arb always get the same size; only the per-arb size and the delay before submitting vary.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JitterConfig {
+    /// Vary each arb's size uniformly within ±this percent of `arb_shares` (0 = fixed size).
+    #[serde(default)]
+    pub size_pct: f64,
+    /// Wait a random 0..this many milliseconds before submitting an arb (0 = immediately).
+    #[serde(default)]
+    pub max_delay_ms: u64,
+}
+
+/// Passive execution: when the taker sum is just above the threshold, rest bids a few ticks below the ask
+/// on both legs (GTC), requoting as the book moves, so the arb is captured at maker prices.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MakerModeConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How far below each leg's ask to bid.
+    #[serde(default = "default_maker_ticks_below_ask")]
+    pub ticks_below_ask: u32,
+    #[serde(default = "default_maker_tick_size")]
+    pub tick_size: f64,
+    /// Quote only while the taker sum is less than this above `sum_threshold`.
+    #[serde(default = "default_maker_near_threshold")]
+    pub near_threshold: f64,
+    /// Seconds between fill checks of resting quotes.
+    #[serde(default = "default_maker_status_poll_secs")]
+    pub status_poll_secs: u64,
+    /// Never bid above a leg's midpoint: in a wide book the quote sits at the mid instead of just under the ask.
+    #[serde(default)]
+    pub cap_at_midpoint: bool,
+}
+
+impl Default for MakerModeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ticks_below_ask: default_maker_ticks_below_ask(),
+            tick_size: default_maker_tick_size(),
+            near_threshold: default_maker_near_threshold(),
+            status_poll_secs: default_maker_status_poll_secs(),
+            cap_at_midpoint: false,
+        }
+    }
+}
+
+fn default_maker_ticks_below_ask() -> u32 {
+    1
+}
+fn default_maker_tick_size() -> f64 {
+    0.01
+}
+fn default_maker_near_threshold() -> f64 {
+    0.03
+}
+fn default_maker_status_poll_secs() -> u64 {
+    2
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrikeBand {
+    /// Trade only while the spot is within the band of the nearer strike.
+    #[default]
+    Within,
+    /// Trade only while the spot is at least the band away from both strikes.
+    Beyond,
+}
+
+/// Gate arbs on where the live Chainlink spot sits relative to the period's price-to-beat. The tolerance check
+/// only compares the two strikes with each other; this looks at where the market actually is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrikeDistanceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub mode: StrikeBand,
+    /// Band around the nearer strike, in bps of the spot.
+    #[serde(default = "default_strike_band_bps")]
+    pub band_bps: f64,
+    /// Per-symbol band overrides, e.g. {"sol": 40}.
+    #[serde(default)]
+    pub symbol_band_bps: BTreeMap<String, f64>,
+}
+
+impl Default for StrikeDistanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: StrikeBand::default(),
+            band_bps: default_strike_band_bps(),
+            symbol_band_bps: BTreeMap::new(),
+        }
+    }
+}
+
+impl StrikeDistanceConfig {
+    pub fn band_bps_for(&self, symbol: &str) -> f64 {
+        self.symbol_band_bps
+            .get(&symbol.to_lowercase())
+            .copied()
+            .unwrap_or(self.band_bps)
+    }
+
+    /// Whether a spot `distance_bps` from the nearer strike may trade on `symbol`.
+    pub fn allows(&self, symbol: &str, distance_bps: f64) -> bool {
+        match self.mode {
+            StrikeBand::Within => distance_bps <= self.band_bps_for(symbol),
+            StrikeBand::Beyond => distance_bps >= self.band_bps_for(symbol),
+        }
+    }
+}
+
+fn default_strike_band_bps() -> f64 {
+    20.0
+}
+
+/// Fair value per leg from spot, strike, time to expiry, and the spot's realized volatility over
+/// `vol_window_secs`; an arb must also have an expected value of at least `min_expected_value` per share pair.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingModelConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_pricing_vol_window_secs")]
+    pub vol_window_secs: u64,
+    #[serde(default)]
+    pub min_expected_value: f64,
+}
+
+impl Default for PricingModelConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            vol_window_secs: default_pricing_vol_window_secs(),
+            min_expected_value: 0.0,
+        }
+    }
+}
+
+fn default_pricing_vol_window_secs() -> u64 {
+    300
+}
+
+/// Close an open arb before resolution by selling both legs at the bid (FOK) once their bid value, net of taker
+/// fees, is `take_profit_per_pair` above or `stop_loss_per_pair` below the entry cost per share pair.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ExitRulesConfig {
+    /// USDC per share pair (0 = off).
+    #[serde(default)]
+    pub take_profit_per_pair: f64,
+    /// USDC per share pair (0 = off).
+    #[serde(default)]
+    pub stop_loss_per_pair: f64,
+}
+
+impl ExitRulesConfig {
+    pub fn enabled(&self) -> bool {
+        self.take_profit_per_pair > 0.0 || self.stop_loss_per_pair > 0.0
+    }
+}
+
+/// Delta hedging on Binance USDⓈ-M futures: in the last `final_secs` of the long period, hold a perp position
+/// offsetting the open legs' net spot delta (from the pricing model), for symbols listed in `symbols`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgeConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Binance futures testnet instead of live.
+    #[serde(default = "default_hedge_testnet")]
+    pub testnet: bool,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub api_secret: Option<Secret>,
+    #[serde(default = "default_hedge_final_secs")]
+    pub final_secs: u64,
+    /// Only hedge once the round's open size reaches this many share pairs.
+    #[serde(default = "default_hedge_min_shares")]
+    pub min_shares: f64,
+    #[serde(default = "default_hedge_rebalance_secs")]
+    pub rebalance_secs: u64,
+    /// Per symbol, e.g. {"btc": {"perp_symbol": "BTCUSDT", "quantity_step": 0.001}}.
+    #[serde(default)]
+    pub symbols: BTreeMap<String, HedgeSymbol>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HedgeSymbol {
+    pub perp_symbol: String,
+    /// Order quantity increment of the perp.
+    pub quantity_step: f64,
+    /// Fraction of the net delta to offset.
+    #[serde(default = "default_hedge_ratio")]
+    pub hedge_ratio: f64,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            testnet: default_hedge_testnet(),
+            api_key: None,
+            api_secret: None,
+            final_secs: default_hedge_final_secs(),
+            min_shares: default_hedge_min_shares(),
+            rebalance_secs: default_hedge_rebalance_secs(),
+            symbols: BTreeMap::new(),
+        }
+    }
+}
+
+fn default_hedge_testnet() -> bool {
+    true
+}
+fn default_hedge_final_secs() -> u64 {
+    180
+}
+fn default_hedge_min_shares() -> f64 {
+    100.0
+}
+fn default_hedge_rebalance_secs() -> u64 {
+    5
+}
+fn default_hedge_ratio() -> f64 {
+    1.0
+}
+
+/// Kalshi trade API access. Market data is public; orders need an API key id and its RSA private key (PEM).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KalshiConfig {
+    #[serde(default = "default_kalshi_api_url")]
+    pub api_url: String,
+    #[serde(default)]
+    pub key_id: Option<String>,
+    #[serde(default)]
+    pub private_key_path: Option<String>,
+    /// Trading fee rate: rate * contracts * price * (1 - price), rounded up to the cent per order.
+    #[serde(default = "default_kalshi_fee_rate")]
+    pub fee_rate: f64,
+}
+
+impl Default for KalshiConfig {
+    fn default() -> Self {
+        Self {
+            api_url: default_kalshi_api_url(),
+            key_id: None,
+            private_key_path: None,
+            fee_rate: default_kalshi_fee_rate(),
+        }
+    }
+}
+
+fn default_kalshi_api_url() -> String {
+    "https://api.elections.kalshi.com/trade-api/v2".to_string()
+}
+fn default_kalshi_fee_rate() -> f64 {
+    0.07
+}
+
+/// Route every outbound connection (REST clients, the CLOB SDK, RPC, and the market and RTDS WebSockets) through
+/// a proxy, for hosts where Polymarket is blocked or routed poorly.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// `http://host:port` or `socks5://host:port` (`socks5h://` to resolve names at the proxy). Empty = direct.
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<Secret>,
+}
+
+/// POST each selected `events::ArbEvent` as JSON to `url`. With a `secret`, requests carry
+/// `X-Webhook-Timestamp` and `X-Webhook-Signature: sha256=<hex HMAC-SHA256 of "<timestamp>.<body>">`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// Empty = off.
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub secret: Option<Secret>,
+    /// Event types to send: "quote", "opportunity", or a journal event type (e.g. "order_acked", "resolved",
+    /// "redeemed", "order_failed"); "*" sends everything.
+    #[serde(default = "default_webhook_events")]
+    pub events: Vec<String>,
+    /// Retries after a failed delivery (network error, 429 or 5xx), with doubling backoff from 1s.
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_webhook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            secret: None,
+            events: default_webhook_events(),
+            max_retries: default_webhook_max_retries(),
+            timeout_secs: default_webhook_timeout_secs(),
+        }
+    }
+}
+
+fn default_webhook_events() -> Vec<String> {
+    ["order_acked", "order_failed", "position_closed", "resolved", "redeemed", "redeem_failed", "alert"]
+        .map(String::from)
+        .to_vec()
+}
+fn default_webhook_max_retries() -> u32 {
+    3
+}
+fn default_webhook_timeout_secs() -> u64 {
+    10
+}
+
+/// Admin API (`services::admin`) on `bind`, e.g. "127.0.0.1:8787". Every request must send
+/// `Authorization: Bearer <token>`; without a token the API stays off.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AdminConfig {
+    /// Empty = off.
+    #[serde(default)]
+    pub bind: String,
+    #[serde(default)]
+    pub token: Option<Secret>,
+}
+
+/// Buy Polymarket Up + Kalshi No (or Down + Yes) on the same 15m window when the pair costs less than
+/// 1 - `min_edge` with fees. The venues settle against different references (Chainlink vs CF Benchmarks), so
+/// pairs whose strikes leave more than `max_dead_zone_bps` where both legs lose are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossVenueConfig {
+    /// Kalshi series per symbol, e.g. {"btc": "KXBTC15M"}.
+    #[serde(default = "default_cross_venue_series")]
+    pub series: BTreeMap<String, String>,
+    /// Whole contracts per leg (Kalshi doesn't trade fractions).
+    #[serde(default = "default_cross_venue_contracts")]
+    pub contracts: f64,
+    #[serde(default = "default_cross_venue_min_edge")]
+    pub min_edge: f64,
+    #[serde(default = "default_cross_venue_max_dead_zone_bps")]
+    pub max_dead_zone_bps: f64,
+    /// 0 = no limit.
+    #[serde(default = "default_cross_venue_max_trades_per_period")]
+    pub max_trades_per_period: u32,
+    #[serde(default = "default_cross_venue_poll_ms")]
+    pub poll_ms: u64,
+    /// Windows with trades are saved here until both venues resolved and they were settled, so a restart
+    /// picks them up again. Empty keeps them in memory only.
+    #[serde(default = "default_cross_venue_pending_path")]
+    pub pending_path: String,
+}
+
+impl Default for CrossVenueConfig {
+    fn default() -> Self {
+        Self {
+            series: default_cross_venue_series(),
+            contracts: default_cross_venue_contracts(),
+            min_edge: default_cross_venue_min_edge(),
+            max_dead_zone_bps: default_cross_venue_max_dead_zone_bps(),
+            max_trades_per_period: default_cross_venue_max_trades_per_period(),
+            poll_ms: default_cross_venue_poll_ms(),
+            pending_path: default_cross_venue_pending_path(),
+        }
+    }
+}
+
+fn default_cross_venue_series() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("btc".to_string(), "KXBTC15M".to_string()),
+        ("eth".to_string(), "KXETH15M".to_string()),
+    ])
+}
+fn default_cross_venue_contracts() -> f64 {
+    10.0
+}
+fn default_cross_venue_min_edge() -> f64 {
+    0.02
+}
+fn default_cross_venue_max_dead_zone_bps() -> f64 {
+    1.0
+}
+fn default_cross_venue_max_trades_per_period() -> u32 {
+    1
+}
+fn default_cross_venue_poll_ms() -> u64 {
+    1000
+}
+fn default_cross_venue_pending_path() -> String {
+    "cross_venue_pending.json".to_string()
+}
+
+/// No arbs while the Chainlink spot's realized volatility over the last `window_secs` exceeds
+/// `max_realized_vol_bps`: a spot whipping around the strike makes both legs losing far more likely.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolatilityFilterConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_volatility_window_secs")]
+    pub window_secs: u64,
+    /// Square root of the summed squared tick log returns over the window, in bps.
+    #[serde(default = "default_max_realized_vol_bps")]
+    pub max_realized_vol_bps: f64,
+}
+
+impl Default for VolatilityFilterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: default_volatility_window_secs(),
+            max_realized_vol_bps: default_max_realized_vol_bps(),
+        }
+    }
+}
+
+fn default_volatility_window_secs() -> u64 {
+    120
+}
+fn default_max_realized_vol_bps() -> f64 {
+    25.0
+}
+
+/// Per-arb sizing on top of `arb_shares`. All off by default, which keeps the fixed size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SizingConfig {
+    /// Spend this fraction of the wallet's USDC per arb instead of `arb_shares` (0 = fixed size).
+    #[serde(default)]
+    pub capital_fraction: f64,
+    /// Most USDC to put into one symbol's long period across all its arbs (0 = no cap).
+    #[serde(default)]
+    pub max_notional_per_period: f64,
+    /// Scale the size with the edge below the threshold: 1x at `edge_reference`, up to `max_edge_multiplier`.
+    #[serde(default)]
+    pub edge_scaling: bool,
+    #[serde(default = "default_sizing_edge_reference")]
+    pub edge_reference: f64,
+    #[serde(default = "default_sizing_max_edge_multiplier")]
+    pub max_edge_multiplier: f64,
+}
+
+impl Default for SizingConfig {
+    fn default() -> Self {
+        Self {
+            capital_fraction: 0.0,
+            max_notional_per_period: 0.0,
+            edge_scaling: false,
+            edge_reference: default_sizing_edge_reference(),
+            max_edge_multiplier: default_sizing_max_edge_multiplier(),
+        }
+    }
+}
+
+impl SizingConfig {
+    pub fn policy(&self) -> SizingPolicy {
+        SizingPolicy {
+            capital_fraction: self.capital_fraction,
+            max_notional_per_period: self.max_notional_per_period,
+            edge_reference: self.edge_scaling.then_some(self.edge_reference),
+            max_edge_multiplier: self.max_edge_multiplier,
+        }
+    }
+}
+
+fn default_sizing_edge_reference() -> f64 {
+    0.02
+}
+fn default_sizing_max_edge_multiplier() -> f64 {
+    3.0
+}
+
+/// Re-check the selected legs against the latest quotes right before orders are signed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlippageGuardConfig {
+    #[serde(default = "default_slippage_guard_enabled")]
+    pub enabled: bool,
+    /// If the fee-inclusive sum at the latest asks got worse than at detection, it must still be below
+    /// `sum_threshold` minus this.
+    #[serde(default)]
+    pub buffer: f64,
+    /// Send at the latest asks instead of the ones the arb was detected at, when they still pass.
+    #[serde(default)]
+    pub reprice: bool,
+}
+
+impl Default for SlippageGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_slippage_guard_enabled(),
+            buffer: 0.0,
+            reprice: false,
+        }
+    }
+}
+
+fn default_slippage_guard_enabled() -> bool {
+    true
+}
+
+/// Periodically list open up/down markets on Gamma and start a symbol loop for every asset that has both
+/// periods above the liquidity / volume floors, in addition to `symbols`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolDiscoveryConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_discovery_min_liquidity_usd")]
+    pub min_liquidity_usd: f64,
+    #[serde(default)]
+    pub min_volume_usd: f64,
+    #[serde(default = "default_discovery_refresh_secs")]
+    pub refresh_secs: u64,
+    /// Price-to-beat tolerance for symbols without a `<symbol>_price_to_beat_tolerance_usd`, in bps of price.
+    #[serde(default = "default_discovery_tolerance_bps")]
+    pub price_to_beat_tolerance_bps: f64,
+}
+
+impl Default for SymbolDiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_liquidity_usd: default_discovery_min_liquidity_usd(),
+            min_volume_usd: 0.0,
+            refresh_secs: default_discovery_refresh_secs(),
+            price_to_beat_tolerance_bps: default_discovery_tolerance_bps(),
+        }
+    }
+}
+
+fn default_discovery_min_liquidity_usd() -> f64 {
+    1000.0
+}
+fn default_discovery_refresh_secs() -> u64 {
+    300
+}
+fn default_discovery_tolerance_bps() -> f64 {
+    1.0
+}
+
+/// Which `services::strategy` the orchestrator runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyKind {
+    /// Long vs short period arb across the overlap.
+    #[default]
+    Overlap,
+    /// Up + Down of the long market against 1 (see `strategy::CompleteSet`).
+    CompleteSet,
+}
+
+/// Order in which the two arb legs are submitted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegSequencing {
+    /// Submit both legs back to back.
+    #[default]
+    Both,
+    /// Submit the leg with less displayed size at the best ask first, as FOK; the other leg follows only
+    /// once it filled, and the arb is abandoned when it misses.
+    ThinnerFirst,
+}
+
+/// Where a market's winning outcome is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResolutionSource {
+    /// CLOB market `winner` flag on the tokens.
+    Clob,
+    /// Gamma market `outcomePrices` once the market is closed.
+    Gamma,
+    /// CTF payout numerators reported on-chain by the oracle.
+    Onchain,
+    /// Captured Chainlink close vs the price to beat; simulation mode only, never read from the config.
+    #[serde(skip)]
+    Chainlink,
+}
+
+impl ResolutionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResolutionSource::Clob => "clob",
+            ResolutionSource::Gamma => "gamma",
+            ResolutionSource::Onchain => "onchain",
+            ResolutionSource::Chainlink => "chainlink",
+        }
+    }
+}
+
+/// Fee rates in basis points per market type. Polymarket charges rate * min(price, 1 - price) per share.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeeConfig {
+    /// Taker fee (bps) on 15m markets; arb legs lift the ask, so this is what applies today.
+    #[serde(default)]
+    pub taker_bps_15m: f64,
+    #[serde(default)]
+    pub taker_bps_5m: f64,
+    /// Maker fee (bps) on 15m markets, for resting orders.
+    #[serde(default)]
+    pub maker_bps_15m: f64,
+    #[serde(default)]
+    pub maker_bps_5m: f64,
+}
+
+impl FeeConfig {
+    /// Taker rates for the (15m, 5m) legs.
+    pub fn taker(&self) -> LegFees {
+        LegFees {
+            bps_15: self.taker_bps_15m,
+            bps_5: self.taker_bps_5m,
+        }
+    }
+
+    /// Maker rates for the (15m, 5m) legs.
+    pub fn maker(&self) -> LegFees {
+        LegFees {
+            bps_15: self.maker_bps_15m,
+            bps_5: self.maker_bps_5m,
+        }
+    }
+}
+
+fn default_cancel_stray_orders() -> bool {
+    true
+}
+fn default_book_cross_check() -> bool {
+    true
 }
 
 fn default_symbols() -> Vec<String> {
     vec!["btc".into(), "eth".into(), "sol".into(), "xrp".into()]
 }
-fn default_sum_threshold() -> f64 {
-    0.99
+fn default_sum_threshold() -> f64 {
+    0.99
+}
+fn default_trade_interval_secs() -> u64 {
+    60
+}
+fn default_no_trade_before_close_secs() -> u64 {
+    20
+}
+fn default_arb_shares() -> String {
+    "10".to_string()
+}
+fn default_leg_order_type() -> String {
+    "GTC".to_string()
+}
+fn default_leg_order_ttl_secs() -> u64 {
+    60
+}
+fn default_canary_state_path() -> String {
+    "canary_state.json".to_string()
+}
+fn default_eth_tolerance() -> f64 {
+    1.0
+}
+fn default_sol_tolerance() -> f64 {
+    0.05
+}
+fn default_xrp_tolerance() -> f64 {
+    0.0003
+}
+fn default_resolution_initial_delay_secs() -> u64 {
+    60
+}
+fn default_resolution_poll_interval_secs() -> u64 {
+    30
+}
+fn default_resolution_max_wait_secs() -> u64 {
+    600
+}
+fn default_resolution_sources() -> Vec<ResolutionSource> {
+    vec![
+        ResolutionSource::Clob,
+        ResolutionSource::Gamma,
+        ResolutionSource::Onchain,
+    ]
+}
+fn default_resolution_onchain_cross_check() -> bool {
+    true
+}
+fn default_auto_redeem() -> bool {
+    true
+}
+fn default_redeem_queue_path() -> String {
+    "redeem_queue.json".to_string()
+}
+fn default_pending_resolution_path() -> String {
+    "pending_resolutions.json".to_string()
+}
+fn default_pnl_state_path() -> String {
+    "pnl_state.json".to_string()
+}
+fn default_pending_resolution_poll_secs() -> u64 {
+    60
+}
+fn default_resolution_delay_alert_secs() -> u64 {
+    1800
+}
+fn default_redeemer_interval_secs() -> u64 {
+    300
+}
+fn default_skip_list_path() -> String {
+    "skip_list.json".to_string()
+}
+fn default_skip_after_anomalies() -> u32 {
+    3
 }
-fn default_trade_interval_secs() -> u64 {
-    60
+fn default_redeem_max_attempts() -> u32 {
+    8
 }
-fn default_arb_shares() -> String {
-    "10".to_string()
+fn default_funding_settle_secs() -> u64 {
+    90
 }
-fn default_eth_tolerance() -> f64 {
-    1.0
+fn default_activity_poll_secs() -> u64 {
+    15
 }
-fn default_sol_tolerance() -> f64 {
-    0.05
+fn default_credential_check_secs() -> u64 {
+    300
 }
-fn default_xrp_tolerance() -> f64 {
-    0.0003
+fn default_activity_cursor_path() -> String {
+    "activity_cursor.json".to_string()
 }
-fn default_resolution_poll_interval_secs() -> u64 {
+fn default_mark_to_market_interval_secs() -> u64 {
     30
 }
-fn default_resolution_max_wait_secs() -> u64 {
-    600
+fn default_journal_path() -> String {
+    "journal.jsonl".to_string()
 }
-fn default_auto_redeem() -> bool {
-    true
+fn default_portfolio_snapshot_mins() -> u64 {
+    5
+}
+fn default_portfolio_snapshot_path() -> String {
+    "portfolio.jsonl".to_string()
 }
 
+/// Share size above which a zero cooldown is treated as dangerous.
+const LARGE_ARB_SHARES: f64 = 100.0;
+
 impl StrategyConfig {
+    /// `leg_order_type` parsed; GTC when it isn't a valid type (which `dangerous_settings` reports).
+    pub fn leg_type(&self) -> OrderType {
+        self.leg_order_type.parse().unwrap_or_default()
+    }
+
+    /// (key, path) of the state files each instance and wallet keeps to itself.
+    pub fn instance_paths(&mut self) -> [(&'static str, &mut String); 7] {
+        [
+            ("journal_path", &mut self.journal_path),
+            ("redeem_queue_path", &mut self.redeem_queue_path),
+            ("pending_resolution_path", &mut self.pending_resolution_path),
+            ("pnl_state_path", &mut self.pnl_state_path),
+            ("canary_state_path", &mut self.canary_state_path),
+            ("portfolio_snapshot_path", &mut self.portfolio_snapshot_path),
+            ("activity_cursor_path", &mut self.activity_cursor_path),
+        ]
+    }
+
+    /// In `simulation_mode`, give the state files a `-sim` suffix so paper trades never share PnL, pending
+    /// resolutions, the journal, or the redeem queue with live trading.
+    pub fn separate_sim_state(&mut self) {
+        if self.simulation_mode {
+            for (_, path) in self.instance_paths() {
+                *path = instance_path(path, "sim");
+            }
+        }
+    }
+
+    /// Settings that are valid JSON but almost certainly a mistake (guaranteed-loss threshold,
+    /// unthrottled large size, no strike matching). Empty when the config looks sane.
+    pub fn dangerous_settings(&self) -> Vec<String> {
+        let mut issues = self.periods.issues();
+        let overlap_secs = self.periods.long_secs() - self.periods.overlap_start_offset_mins * 60;
+        if self.no_trade_before_close_secs as i64 >= overlap_secs {
+            issues.push(format!(
+                "no_trade_before_close_secs {} covers the whole {}s overlap window: no trade would ever be placed",
+                self.no_trade_before_close_secs, overlap_secs
+            ));
+        }
+        if self.sum_threshold >= 1.0 {
+            issues.push(format!(
+                "sum_threshold {} >= 1.0: every arb would lock in a loss",
+                self.sum_threshold
+            ));
+        }
+        if self.end_of_day.enabled() {
+            for (key, value) in [
+                ("stop_at_et", &self.end_of_day.stop_at_et),
+                ("resume_at_et", &self.end_of_day.resume_at_et),
+            ] {
+                if parse_hhmm(value).is_none() {
+                    issues.push(format!("end_of_day.{} {:?} is not a HH:MM time", key, value));
+                }
+            }
+            if self.end_of_day.stop_at_et.trim() == self.end_of_day.resume_at_et.trim() {
+                issues.push("end_of_day.stop_at_et equals resume_at_et: the bot would never trade".to_string());
+            }
+        }
+        issues.extend(self.schedule.issues());
+        if !(0.0..100.0).contains(&self.jitter.size_pct) {
+            issues.push(format!(
+                "jitter.size_pct {} must be in [0, 100): sizes could reach zero",
+                self.jitter.size_pct
+            ));
+        }
+        match self.leg_order_type.parse::<OrderType>() {
+            Err(_) => issues.push(format!(
+                "leg_order_type {:?} must be one of GTC, FOK, FAK, GTD",
+                self.leg_order_type
+            )),
+            Ok(OrderType::Gtd) if self.leg_order_ttl_secs == 0 => {
+                issues.push("leg_order_type GTD with leg_order_ttl_secs 0: legs would expire on arrival".to_string())
+            }
+            Ok(_) => {}
+        }
+        if self.maker_mode.enabled && (self.maker_mode.tick_size <= 0.0 || self.maker_mode.ticks_below_ask == 0) {
+            issues.push(format!(
+                "maker_mode needs a positive tick_size and ticks_below_ask (got {} and {}): bids would cross the ask",
+                self.maker_mode.tick_size, self.maker_mode.ticks_below_ask
+            ));
+        }
+        if self.strike_distance.enabled
+            && std::iter::once(&self.strike_distance.band_bps)
+                .chain(self.strike_distance.symbol_band_bps.values())
+                .any(|b| *b < 0.0)
+        {
+            issues.push("strike_distance band_bps must not be negative".to_string());
+        }
+        if self.pricing_model.enabled
+            && (self.pricing_model.vol_window_secs == 0 || self.pricing_model.vol_window_secs as i64 > SPOT_HISTORY_SECS)
+        {
+            issues.push(format!(
+                "pricing_model.vol_window_secs {} must be in 1..={}",
+                self.pricing_model.vol_window_secs, SPOT_HISTORY_SECS
+            ));
+        }
+        if self.exit_rules.take_profit_per_pair < 0.0 || self.exit_rules.stop_loss_per_pair < 0.0 {
+            issues.push(format!(
+                "exit_rules take_profit_per_pair {} / stop_loss_per_pair {} must not be negative",
+                self.exit_rules.take_profit_per_pair, self.exit_rules.stop_loss_per_pair
+            ));
+        }
+        if self.hedge.enabled {
+            if self.hedge.api_key.is_none() || self.hedge.api_secret.is_none() {
+                issues.push("hedge is enabled without api_key and api_secret: no hedges will be placed".to_string());
+            }
+            for (symbol, perp) in &self.hedge.symbols {
+                if perp.quantity_step <= 0.0 || !(0.0..=1.0).contains(&perp.hedge_ratio) {
+                    issues.push(format!(
+                        "hedge.symbols.{} needs a positive quantity_step and hedge_ratio in [0, 1]",
+                        symbol
+                    ));
+                }
+            }
+        }
+        let cross = &self.cross_venue;
+        if cross.contracts < 1.0 || cross.contracts.fract() != 0.0 || cross.min_edge < 0.0 || cross.poll_ms == 0 {
+            issues.push(format!(
+                "cross_venue needs whole contracts >= 1, min_edge >= 0 and a positive poll_ms (got {}, {}, {})",
+                cross.contracts, cross.min_edge, cross.poll_ms
+            ));
+        }
+        if self.kind == StrategyKind::CompleteSet && self.maker_mode.enabled {
+            issues.push(
+                "maker_mode quotes overlap pairs and can't run with the complete_set strategy".to_string(),
+            );
+        }
+        if self.volatility_filter.enabled
+            && (self.volatility_filter.window_secs == 0
+                || self.volatility_filter.window_secs as i64 > SPOT_HISTORY_SECS
+                || self.volatility_filter.max_realized_vol_bps <= 0.0)
+        {
+            issues.push(format!(
+                "volatility_filter needs window_secs in 1..={} and a positive max_realized_vol_bps (got {} and {})",
+                SPOT_HISTORY_SECS, self.volatility_filter.window_secs, self.volatility_filter.max_realized_vol_bps
+            ));
+        }
+        if self.slippage_guard.buffer < 0.0 {
+            issues.push(format!(
+                "slippage_guard.buffer {} is negative: arbs past the threshold would be sent",
+                self.slippage_guard.buffer
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.sizing.capital_fraction) {
+            issues.push(format!(
+                "sizing.capital_fraction {} must be in [0, 1]: it is a fraction of the wallet's USDC",
+                self.sizing.capital_fraction
+            ));
+        }
+        if self.sizing.edge_scaling && (self.sizing.edge_reference <= 0.0 || self.sizing.max_edge_multiplier < 1.0) {
+            issues.push(format!(
+                "sizing.edge_scaling needs a positive edge_reference and max_edge_multiplier >= 1 (got {} and {})",
+                self.sizing.edge_reference, self.sizing.max_edge_multiplier
+            ));
+        }
+        if !(0.0..1.0).contains(&self.canary_fraction) {
+            issues.push(format!(
+                "canary_fraction {} must be in [0, 1): it is a fraction of arb_shares",
+                self.canary_fraction
+            ));
+        }
+        match self.arb_shares.trim().parse::<f64>() {
+            Ok(shares) if shares > 0.0 => {
+                if self.trade_interval_secs == 0 && shares >= LARGE_ARB_SHARES {
+                    issues.push(format!(
+                        "trade_interval_secs 0 with arb_shares {}: no cooldown between large arbs",
+                        shares
+                    ));
+                }
+            }
+            _ => issues.push(format!("arb_shares {:?} is not a positive number", self.arb_shares)),
+        }
+        if !self.symbols.is_empty()
+            && self
+                .symbols
+                .iter()
+                .all(|s| self.price_to_beat_tolerance_for(s) <= 0.0)
+        {
+            issues.push(
+                "price-to-beat tolerance is 0 for every symbol: 15m and 5m strikes are never compared meaningfully"
+                    .to_string(),
+            );
+        }
+        issues
+    }
+
+    /// Settings that change how orders are placed; a change triggers a canary arb (see `canary_fraction`).
+    pub fn execution_settings(&self) -> serde_json::Value {
+        serde_json::json!({
+            "sum_threshold": self.sum_threshold,
+            "arb_shares": self.arb_shares,
+            "sizing": self.sizing,
+            "trade_interval_secs": self.trade_interval_secs,
+            "max_trades_per_period": self.max_trades_per_period,
+            "symbol_limits": self.symbol_limits,
+            "no_trade_before_close_secs": self.no_trade_before_close_secs,
+            "periods": self.periods,
+            "fees": self.fees,
+            "jitter": self.jitter,
+            "leg_order_type": self.leg_order_type,
+            "leg_order_ttl_secs": self.leg_order_ttl_secs,
+            "leg_cancel_after_secs": self.leg_cancel_after_secs,
+            "leg_sequencing": self.leg_sequencing,
+            "kind": self.kind,
+            "slippage_guard": self.slippage_guard,
+            "strike_distance": self.strike_distance,
+            "volatility_filter": self.volatility_filter,
+            "pricing_model": self.pricing_model,
+            "exit_rules": self.exit_rules,
+            "maker_mode": self.maker_mode,
+        })
+    }
+
     /// Price-to-beat tolerance (USD) for the given symbol.
+    /// Cooldown between arbs on `symbol`.
+    pub fn trade_interval_secs_for(&self, symbol: &str) -> u64 {
+        self.symbol_limits
+            .get(&symbol.to_lowercase())
+            .and_then(|l| l.trade_interval_secs)
+            .unwrap_or(self.trade_interval_secs)
+    }
+
+    /// Most arbs per long period on `symbol` (0 = no limit).
+    pub fn max_trades_per_period_for(&self, symbol: &str) -> u32 {
+        self.symbol_limits
+            .get(&symbol.to_lowercase())
+            .and_then(|l| l.max_trades_per_period)
+            .unwrap_or(self.max_trades_per_period)
+    }
+
     pub fn price_to_beat_tolerance_for(&self, symbol: &str) -> f64 {
         match symbol.to_lowercase().as_str() {
             "btc" => self.btc_price_to_beat_tolerance_usd,
@@ -100,27 +1584,491 @@ impl StrategyConfig {
             _ => 0.0,
         }
     }
+
+    /// Price-to-beat tolerance (USD) at `price`: the configured USD tolerance for the four built-in symbols,
+    /// otherwise `symbol_discovery.price_to_beat_tolerance_bps` of the price.
+    pub fn price_to_beat_tolerance_at(&self, symbol: &str, price: f64) -> f64 {
+        match symbol.to_lowercase().as_str() {
+            "btc" | "eth" | "sol" | "xrp" => self.price_to_beat_tolerance_for(symbol),
+            _ => price.abs() * self.symbol_discovery.price_to_beat_tolerance_bps / 10_000.0,
+        }
+    }
 }
 
+pub const ENV_API_KEY: &str = "POLYMARKET_API_KEY";
+pub const ENV_API_SECRET: &str = "POLYMARKET_API_SECRET";
+pub const ENV_API_PASSPHRASE: &str = "POLYMARKET_API_PASSPHRASE";
+pub const ENV_API_KEY_NONCE: &str = "POLYMARKET_API_KEY_NONCE";
+pub const ENV_PRIVATE_KEY: &str = "POLYMARKET_PRIVATE_KEY";
+pub const ENV_KEYSTORE_PASSWORD: &str = "POLYMARKET_KEYSTORE_PASSWORD";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PolymarketConfig {
     pub gamma_api_url: String,
     pub clob_api_url: String,
     pub api_key: Option<String>,
-    pub api_secret: Option<String>,
-    pub api_passphrase: Option<String>,
-    pub private_key: Option<String>,
+    /// Credentials print and serialize redacted (see `utils::secret`).
+    pub api_secret: Option<Secret>,
+    pub api_passphrase: Option<Secret>,
+    /// Nonce the API credentials were created with (`create-api-key --nonce`); used to re-derive them after a 401.
+    #[serde(default)]
+    pub api_key_nonce: u64,
+    pub private_key: Option<Secret>,
+    /// Encrypted JSON keystore holding the private key, read at startup when `private_key` is unset. Its password
+    /// comes from POLYMARKET_KEYSTORE_PASSWORD, or is typed in on the terminal.
+    #[serde(default)]
+    pub keystore_path: Option<String>,
     pub proxy_wallet_address: Option<String>,
     pub signature_type: Option<u8>,
-    /// Polygon RPC URL for redemption (Safe reads + sendTransaction). Defaults to polygon-rpc.com if unset.
-    #[serde(default)]
-    pub rpc_url: Option<String>,
+    /// Polygon RPC URL, or a list tried in order with failover, for redemption and on-chain reads.
+    /// Defaults to polygon-rpc.com if unset.
+    #[serde(default, deserialize_with = "string_or_list", alias = "rpc_urls")]
+    pub rpc_url: Vec<String>,
     /// WebSocket base URL for market channel (e.g. wss://ws-subscriptions-clob.polymarket.com).
     #[serde(default = "default_ws_url")]
     pub ws_url: String,
     /// RTDS WebSocket URL for Chainlink BTC price (price-to-beat). Topic: crypto_prices_chainlink, symbol: btc/usd.
     #[serde(default = "default_rtds_ws_url")]
     pub rtds_ws_url: String,
+    /// Record raw market WS and RTDS messages here for `--replay` (empty = off).
+    #[serde(default)]
+    pub ws_record_dir: String,
+    /// Check at startup that proxy_wallet_address matches the proxy / Safe derived from private_key.
+    #[serde(default = "default_verify_proxy_wallet")]
+    pub verify_proxy_wallet: bool,
+    /// Gas limit and EIP-1559 fee settings for redemption transactions.
+    #[serde(default)]
+    pub gas: GasConfig,
+    /// Request timeouts per endpoint category and connection pool settings (see `HttpConfig`).
+    #[serde(default)]
+    pub http: HttpConfig,
+    /// Chain to target: "polygon" (mainnet) or "amoy" (testnet) for end-to-end testing.
+    #[serde(default)]
+    pub network: Network,
+    /// Per-contract address overrides; unset entries use the network's defaults.
+    #[serde(default)]
+    pub contracts: ContractOverrides,
+    /// At startup, send any missing USDC allowance / CTF approval for the exchanges instead of only warning.
+    #[serde(default)]
+    pub auto_approve: bool,
+    /// Sign through a remote service instead of with `private_key` (see `SignerConfig`).
+    #[serde(default)]
+    pub signer: SignerConfig,
+}
+
+/// Where orders, CLOB auth, and Safe / redemption transactions are signed. e.g. {"kind": "remote", "url":
+/// "https://signer.internal/sign", "token": "..", "address": "0x.."} for a signing service, or {"kind": "aws_kms",
+/// "key_id": "..", "region": "us-east-1", "address": "0x.."} for a KMS key, so `private_key` can stay out of the
+/// config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SignerConfig {
+    #[serde(default)]
+    pub kind: SignerKind,
+    /// Remote signer endpoint (see `adapters::signer::RemoteSigner` for the request format); for the KMS kinds,
+    /// an endpoint overriding the provider's default.
+    #[serde(default)]
+    pub url: String,
+    /// Sent as a bearer token to the remote signer, or to GCP KMS (default: a token from the metadata server).
+    #[serde(default)]
+    pub token: Option<Secret>,
+    /// EOA the signer signs for; every signature is checked against it.
+    #[serde(default)]
+    pub address: String,
+    /// AWS KMS key id or ARN, or GCP KMS key version name ("projects/../cryptoKeyVersions/1").
+    #[serde(default)]
+    pub key_id: String,
+    /// AWS region of the KMS key.
+    #[serde(default)]
+    pub region: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SignerKind {
+    /// Sign with `private_key`.
+    #[default]
+    Local,
+    /// Sign through the HTTP service at `url`.
+    Remote,
+    /// Sign with the AWS KMS key `key_id`, with credentials from the AWS_* environment variables.
+    AwsKms,
+    /// Sign with the GCP Cloud KMS key version `key_id`.
+    GcpKms,
+}
+
+impl PolymarketConfig {
+    /// API credentials from POLYMARKET_API_KEY, POLYMARKET_API_SECRET, and POLYMARKET_API_PASSPHRASE override
+    /// the file (see the `create-api-key` subcommand), and so does a key in POLYMARKET_PRIVATE_KEY.
+    pub fn apply_env(&mut self) {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        if let Some(key) = var(ENV_PRIVATE_KEY) {
+            self.private_key = Some(Secret::new(key.trim().to_string()));
+        }
+        if let Some(key) = var(ENV_API_KEY) {
+            self.api_key = Some(key);
+        }
+        if let Some(secret) = var(ENV_API_SECRET) {
+            self.api_secret = Some(Secret::new(secret));
+        }
+        if let Some(passphrase) = var(ENV_API_PASSPHRASE) {
+            self.api_passphrase = Some(Secret::new(passphrase));
+        }
+        if let Some(nonce) = var(ENV_API_KEY_NONCE).and_then(|n| n.trim().parse().ok()) {
+            self.api_key_nonce = nonce;
+        }
+    }
+
+    /// Without a `private_key`, decrypt the one in `keystore_path`, with the password from
+    /// POLYMARKET_KEYSTORE_PASSWORD or typed in without echo when there is a terminal.
+    pub fn unlock_keystore(&mut self) -> anyhow::Result<()> {
+        let Some(path) = self.keystore_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) else {
+            return Ok(());
+        };
+        if self.private_key.is_some() {
+            return Ok(());
+        }
+        let password = match std::env::var(ENV_KEYSTORE_PASSWORD) {
+            Ok(password) => password,
+            Err(_) if std::io::stdin().is_terminal() => rpassword::prompt_password(format!("Password for {}: ", path))?,
+            Err(_) => anyhow::bail!(
+                "keystore_path {} needs its password in {} (no terminal to ask on)",
+                path,
+                ENV_KEYSTORE_PASSWORD
+            ),
+        };
+        let signer = PrivateKeySigner::decrypt_keystore(path, password)
+            .map_err(|e| anyhow::anyhow!("Failed to unlock keystore {}: {}", path, e))?;
+        self.private_key = Some(Secret::new(signer.to_bytes().to_string()));
+        Ok(())
+    }
+
+    /// The network's contract addresses with any configured overrides applied.
+    pub fn contracts(&self) -> ContractAddresses {
+        let defaults = self.network.default_contracts();
+        let o = &self.contracts;
+        let pick = |over: &Option<String>, default: String| over.clone().unwrap_or(default);
+        ContractAddresses {
+            ctf: pick(&o.ctf, defaults.ctf),
+            usdc: pick(&o.usdc, defaults.usdc),
+            ctf_exchange: pick(&o.ctf_exchange, defaults.ctf_exchange),
+            neg_risk_ctf_exchange: pick(&o.neg_risk_ctf_exchange, defaults.neg_risk_ctf_exchange),
+            neg_risk_adapter: pick(&o.neg_risk_adapter, defaults.neg_risk_adapter),
+            proxy_wallet_factory: o.proxy_wallet_factory.clone().or(defaults.proxy_wallet_factory),
+            safe_factory: o.safe_factory.clone().or(defaults.safe_factory),
+            multi_send_call_only: pick(&o.multi_send_call_only, defaults.multi_send_call_only),
+            pol_usd_feed: o.pol_usd_feed.clone().or(defaults.pol_usd_feed),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Network {
+    #[default]
+    Polygon,
+    Amoy,
+}
+
+impl Network {
+    pub fn chain_id(self) -> u64 {
+        match self {
+            Network::Polygon => 137,
+            Network::Amoy => 80002,
+        }
+    }
+
+    /// CLOB base URL used when `clob_api_url` is left at the mainnet default.
+    fn clob_api_url(self) -> &'static str {
+        match self {
+            Network::Polygon => MAINNET_CLOB_API_URL,
+            Network::Amoy => "https://clob-staging.polymarket.com",
+        }
+    }
+
+    pub fn default_contracts(self) -> ContractAddresses {
+        match self {
+            Network::Polygon => ContractAddresses {
+                ctf: "0x4d97dcd97ec945f40cf65f87097ace5ea0476045".to_string(),
+                usdc: "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174".to_string(),
+                ctf_exchange: "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E".to_string(),
+                neg_risk_ctf_exchange: "0xC5d563A36AE78145C45a50134d48A1215220f80a".to_string(),
+                neg_risk_adapter: NEG_RISK_ADAPTER.to_string(),
+                proxy_wallet_factory: Some("0xaB45c5A4B0c941a2F231C04C3f49182e1A254052".to_string()),
+                safe_factory: Some("0xaacFeEa03eb1561C4e67d661e40682Bd20E3541b".to_string()),
+                multi_send_call_only: SAFE_MULTI_SEND_CALL_ONLY.to_string(),
+                pol_usd_feed: Some("0xAB594600376Ec9fD91F8e885dADF0CE036862dE0".to_string()),
+            },
+            // No proxy wallet factory or POL/USD feed on Amoy: use an EOA or Safe wallet there; the
+            // redemption gas check is skipped without a price feed. Without a Safe factory the Safe address
+            // isn't derived, only taken from the config.
+            Network::Amoy => ContractAddresses {
+                ctf: "0x69308FB512518e39F9b16112fA8d994F4e2Bf8bB".to_string(),
+                usdc: "0x9c4e1703476e875070ee25b56a58b008cfb8fa78".to_string(),
+                ctf_exchange: "0xdFE02Eb6733538f8Ea35D585af8DE5958AD99E40".to_string(),
+                neg_risk_ctf_exchange: "0xC5d563A36AE78145C45a50134d48A1215220f80a".to_string(),
+                neg_risk_adapter: NEG_RISK_ADAPTER.to_string(),
+                proxy_wallet_factory: None,
+                safe_factory: None,
+                multi_send_call_only: SAFE_MULTI_SEND_CALL_ONLY.to_string(),
+                pol_usd_feed: None,
+            },
+        }
+    }
+}
+
+/// Optional per-contract overrides in config.json.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ContractOverrides {
+    #[serde(default)]
+    pub ctf: Option<String>,
+    /// Collateral token (USDC.e on mainnet).
+    #[serde(default)]
+    pub usdc: Option<String>,
+    #[serde(default)]
+    pub ctf_exchange: Option<String>,
+    #[serde(default)]
+    pub neg_risk_ctf_exchange: Option<String>,
+    #[serde(default)]
+    pub neg_risk_adapter: Option<String>,
+    #[serde(default)]
+    pub proxy_wallet_factory: Option<String>,
+    /// Gnosis Safe proxy factory that deployed Polymarket's Safe wallets.
+    #[serde(default)]
+    pub safe_factory: Option<String>,
+    #[serde(default)]
+    pub multi_send_call_only: Option<String>,
+    /// Chainlink POL/USD feed for the redemption gas check.
+    #[serde(default)]
+    pub pol_usd_feed: Option<String>,
+}
+
+/// Resolved contract addresses for the configured network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractAddresses {
+    pub ctf: String,
+    pub usdc: String,
+    pub ctf_exchange: String,
+    pub neg_risk_ctf_exchange: String,
+    pub neg_risk_adapter: String,
+    pub proxy_wallet_factory: Option<String>,
+    pub safe_factory: Option<String>,
+    pub multi_send_call_only: String,
+    pub pol_usd_feed: Option<String>,
+}
+
+/// Same deployment address on Polygon and Amoy.
+const NEG_RISK_ADAPTER: &str = "0xd91E80cF2E7be2e162c6513ceD06f1dD0dA35296";
+const MAINNET_CLOB_API_URL: &str = "https://clob.polymarket.com";
+/// Safe MultiSendCallOnly v1.3.0 (same deterministic address on every chain).
+const SAFE_MULTI_SEND_CALL_ONLY: &str = "0x40A2aCCbd92BCA938b02010E17A5b8929b49130D";
+
+/// Redemption gas: limit from eth_estimateGas with headroom, fees from the node or a Polygon gas station.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasConfig {
+    /// Headroom on eth_estimateGas (1.2 = +20%).
+    #[serde(default = "default_gas_limit_multiplier")]
+    pub gas_limit_multiplier: f64,
+    /// Multiplier on the estimated max fee and priority fee.
+    #[serde(default = "default_gas_fee_multiplier")]
+    pub fee_multiplier: f64,
+    /// Cap on max fee per gas (gwei); a redemption waits out a spike rather than overpay.
+    #[serde(default)]
+    pub max_fee_gwei: Option<f64>,
+    /// Fixed priority fee (gwei) instead of the estimate.
+    #[serde(default)]
+    pub priority_fee_gwei: Option<f64>,
+    /// Polygon gas station (e.g. https://gasstation.polygon.technology/v2), preferred over the node's estimate when set.
+    #[serde(default)]
+    pub gas_station_url: Option<String>,
+    /// Gas station tier: "safeLow", "standard" or "fast".
+    #[serde(default = "default_gas_station_speed")]
+    pub gas_station_speed: String,
+    /// Seconds to wait for a redemption to be mined before rebroadcasting it with bumped fees.
+    #[serde(default = "default_tx_timeout_secs")]
+    pub tx_timeout_secs: u64,
+    /// Same-nonce rebroadcasts before the tx is left for manual intervention.
+    #[serde(default = "default_max_replacements")]
+    pub max_replacements: u32,
+    /// Fee multiplier per rebroadcast (at least 1.1, the minimum nodes accept).
+    #[serde(default = "default_replacement_fee_bump")]
+    pub replacement_fee_bump: f64,
+    /// Blocks on top of the redemption's block before it counts as final (0 = first receipt).
+    #[serde(default = "default_confirmations")]
+    pub confirmations: u64,
+    /// Wait for the `finalized` block tag to pass the redemption's block instead of counting confirmations.
+    #[serde(default)]
+    pub wait_finalized: bool,
+    /// Give up waiting for finality after this long; the redemption is then retried from the queue.
+    #[serde(default = "default_finality_timeout_secs")]
+    pub finality_timeout_secs: u64,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            gas_limit_multiplier: default_gas_limit_multiplier(),
+            fee_multiplier: default_gas_fee_multiplier(),
+            max_fee_gwei: None,
+            priority_fee_gwei: None,
+            gas_station_url: None,
+            gas_station_speed: default_gas_station_speed(),
+            tx_timeout_secs: default_tx_timeout_secs(),
+            max_replacements: default_max_replacements(),
+            replacement_fee_bump: default_replacement_fee_bump(),
+            confirmations: default_confirmations(),
+            wait_finalized: false,
+            finality_timeout_secs: default_finality_timeout_secs(),
+        }
+    }
+}
+
+impl GasConfig {
+    pub fn policy(&self) -> GasPolicy {
+        GasPolicy {
+            limit_multiplier: self.gas_limit_multiplier,
+            fee_multiplier: self.fee_multiplier,
+            priority_fee_wei: self.priority_fee_gwei.map(gwei_to_wei),
+            max_fee_cap_wei: self.max_fee_gwei.map(gwei_to_wei),
+        }
+    }
+}
+
+fn default_gas_limit_multiplier() -> f64 {
+    1.2
+}
+
+fn default_gas_fee_multiplier() -> f64 {
+    1.0
+}
+
+fn default_gas_station_speed() -> String {
+    "fast".to_string()
+}
+
+fn default_tx_timeout_secs() -> u64 {
+    120
+}
+
+fn default_max_replacements() -> u32 {
+    3
+}
+
+fn default_replacement_fee_bump() -> f64 {
+    1.25
+}
+
+fn default_confirmations() -> u64 {
+    5
+}
+
+fn default_finality_timeout_secs() -> u64 {
+    600
+}
+
+/// HTTP client settings for the Polymarket APIs and Polygon RPC. Order posts and cancels through the CLOB SDK
+/// use the SDK's own client and timeouts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpConfig {
+    /// Books, prices, Gamma markets, price-to-beat, and server time; quotes are stale long before 10s.
+    #[serde(default = "default_market_data_timeout_ms")]
+    pub market_data_timeout_ms: u64,
+    /// Authenticated CLOB requests: open orders, cancels, balances, and API keys.
+    #[serde(default = "default_order_timeout_ms")]
+    pub order_timeout_ms: u64,
+    /// data-api queries (fills, activity, order status, redeemable positions), which can be slow on busy wallets.
+    #[serde(default = "default_data_api_timeout_ms")]
+    pub data_api_timeout_ms: u64,
+    /// Budget of one RPC operation on one endpoint before failing over to the next.
+    #[serde(default = "default_rpc_timeout_ms")]
+    pub rpc_timeout_ms: u64,
+    /// TCP (and TLS) connect timeout for every request.
+    #[serde(default = "default_connect_timeout_ms")]
+    pub connect_timeout_ms: u64,
+    /// Idle keep-alive connections kept per host.
+    #[serde(default = "default_pool_max_idle_per_host")]
+    pub pool_max_idle_per_host: usize,
+    /// Seconds an idle pooled connection is kept before closing it.
+    #[serde(default = "default_pool_idle_timeout_secs")]
+    pub pool_idle_timeout_secs: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            market_data_timeout_ms: default_market_data_timeout_ms(),
+            order_timeout_ms: default_order_timeout_ms(),
+            data_api_timeout_ms: default_data_api_timeout_ms(),
+            rpc_timeout_ms: default_rpc_timeout_ms(),
+            connect_timeout_ms: default_connect_timeout_ms(),
+            pool_max_idle_per_host: default_pool_max_idle_per_host(),
+            pool_idle_timeout_secs: default_pool_idle_timeout_secs(),
+        }
+    }
+}
+
+impl HttpConfig {
+    pub fn market_data_timeout(&self) -> Duration {
+        Duration::from_millis(self.market_data_timeout_ms.max(1))
+    }
+
+    pub fn order_timeout(&self) -> Duration {
+        Duration::from_millis(self.order_timeout_ms.max(1))
+    }
+
+    pub fn data_api_timeout(&self) -> Duration {
+        Duration::from_millis(self.data_api_timeout_ms.max(1))
+    }
+
+    pub fn rpc_timeout(&self) -> Duration {
+        Duration::from_millis(self.rpc_timeout_ms.max(1))
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_millis(self.connect_timeout_ms.max(1))
+    }
+}
+
+fn default_market_data_timeout_ms() -> u64 {
+    5_000
+}
+
+fn default_order_timeout_ms() -> u64 {
+    10_000
+}
+
+fn default_data_api_timeout_ms() -> u64 {
+    30_000
+}
+
+fn default_rpc_timeout_ms() -> u64 {
+    15_000
+}
+
+fn default_connect_timeout_ms() -> u64 {
+    3_000
+}
+
+fn default_pool_max_idle_per_host() -> usize {
+    16
+}
+
+fn default_pool_idle_timeout_secs() -> u64 {
+    90
+}
+
+/// Accepts a single string or a list of strings.
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match Option::<OneOrMany>::deserialize(deserializer)? {
+        None => Vec::new(),
+        Some(OneOrMany::One(url)) => vec![url],
+        Some(OneOrMany::Many(urls)) => urls,
+    })
 }
 
 fn default_ws_url() -> String {
@@ -131,50 +2079,579 @@ fn default_rtds_ws_url() -> String {
     "wss://ws-live-data.polymarket.com".to_string()
 }
 
+fn default_verify_proxy_wallet() -> bool {
+    true
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             polymarket: PolymarketConfig {
                 gamma_api_url: "https://gamma-api.polymarket.com".to_string(),
-                clob_api_url: "https://clob.polymarket.com".to_string(),
+                clob_api_url: MAINNET_CLOB_API_URL.to_string(),
                 api_key: None,
                 api_secret: None,
                 api_passphrase: None,
+                api_key_nonce: 0,
                 private_key: None,
+                keystore_path: None,
                 proxy_wallet_address: None,
                 signature_type: None,
-                rpc_url: None,
+                rpc_url: Vec::new(),
                 ws_url: default_ws_url(),
                 rtds_ws_url: default_rtds_ws_url(),
+                ws_record_dir: String::new(),
+                verify_proxy_wallet: default_verify_proxy_wallet(),
+                gas: GasConfig::default(),
+                http: HttpConfig::default(),
+                network: Network::default(),
+                contracts: ContractOverrides::default(),
+                auto_approve: false,
+                signer: SignerConfig::default(),
             },
             strategy: StrategyConfig {
+                kind: StrategyKind::default(),
                 symbols: default_symbols(),
+                symbol_discovery: SymbolDiscoveryConfig::default(),
+                periods: PeriodPair::default(),
                 sum_threshold: 0.99,
                 trade_interval_secs: default_trade_interval_secs(),
+                max_trades_per_period: 0,
+                symbol_limits: BTreeMap::new(),
+                no_trade_before_close_secs: default_no_trade_before_close_secs(),
                 simulation_mode: false,
+                signals_only: false,
                 arb_shares: default_arb_shares(),
+                sizing: SizingConfig::default(),
+                leg_order_type: default_leg_order_type(),
+                leg_order_ttl_secs: default_leg_order_ttl_secs(),
+                leg_cancel_after_secs: 0,
+                leg_sequencing: LegSequencing::default(),
+                end_of_day: EndOfDayConfig::default(),
+                schedule: ScheduleConfig::default(),
+                jitter: JitterConfig::default(),
+                slippage_guard: SlippageGuardConfig::default(),
+                strike_distance: StrikeDistanceConfig::default(),
+                volatility_filter: VolatilityFilterConfig::default(),
+                pricing_model: PricingModelConfig::default(),
+                exit_rules: ExitRulesConfig::default(),
+                hedge: HedgeConfig::default(),
+                cross_venue: CrossVenueConfig::default(),
+                maker_mode: MakerModeConfig::default(),
+                canary_fraction: 0.0,
+                canary_state_path: default_canary_state_path(),
                 btc_price_to_beat_tolerance_usd: 10.0,
                 eth_price_to_beat_tolerance_usd: default_eth_tolerance(),
                 sol_price_to_beat_tolerance_usd: default_sol_tolerance(),
                 xrp_price_to_beat_tolerance_usd: default_xrp_tolerance(),
+                resolution_initial_delay_secs: default_resolution_initial_delay_secs(),
                 resolution_poll_interval_secs: default_resolution_poll_interval_secs(),
                 resolution_max_wait_secs: default_resolution_max_wait_secs(),
+                resolution_sources: default_resolution_sources(),
+                resolution_onchain_cross_check: default_resolution_onchain_cross_check(),
                 auto_redeem: default_auto_redeem(),
+                max_redeem_gas_to_payout_ratio: 0.0,
+                redeem_queue_path: default_redeem_queue_path(),
+                pending_resolution_path: default_pending_resolution_path(),
+                pnl_state_path: default_pnl_state_path(),
+                pending_resolution_poll_secs: default_pending_resolution_poll_secs(),
+                resolution_delay_alert_secs: default_resolution_delay_alert_secs(),
+                redeemer_interval_secs: default_redeemer_interval_secs(),
+                skip_list_path: default_skip_list_path(),
+                skip_after_anomalies: default_skip_after_anomalies(),
+                redeem_max_attempts: default_redeem_max_attempts(),
+                funding_settle_secs: default_funding_settle_secs(),
+                mark_to_market_interval_secs: default_mark_to_market_interval_secs(),
+                activity_poll_secs: default_activity_poll_secs(),
+                credential_check_secs: default_credential_check_secs(),
+                activity_cursor_path: default_activity_cursor_path(),
+                verify_inventory_onchain: false,
+                cancel_stray_orders: default_cancel_stray_orders(),
+                book_cross_check: default_book_cross_check(),
+                journal_path: default_journal_path(),
+                portfolio_snapshot_mins: default_portfolio_snapshot_mins(),
+                portfolio_snapshot_path: default_portfolio_snapshot_path(),
+                fees: FeeConfig::default(),
             },
+            kalshi: KalshiConfig::default(),
+            instances: Vec::new(),
+            wallets: Vec::new(),
+            wallet_allocation: WalletAllocation::default(),
+            wallet: None,
+            profiles: BTreeMap::new(),
+            profile: None,
+            webhook: WebhookConfig::default(),
+            admin: AdminConfig::default(),
+            proxy: ProxyConfig::default(),
         }
     }
 }
 
 impl Config {
+    /// (name, config) per entry of `instances` and wallet (see `wallet_configs`), each with its merged strategy
+    /// and keys; just ("", self) without either. Names join as "instance-wallet".
+    pub fn strategy_instances(&self) -> anyhow::Result<Vec<(String, Config)>> {
+        let mut instances = Vec::new();
+        for (name, config) in self.named_instances()? {
+            for (wallet, config) in config.wallet_configs()? {
+                let name = match (name.is_empty(), wallet.is_empty()) {
+                    (_, true) => name.clone(),
+                    (true, false) => wallet,
+                    (false, false) => format!("{}-{}", name, wallet),
+                };
+                instances.push((name, config));
+            }
+        }
+        Ok(instances)
+    }
+
+    /// (wallet name, config) per entry of `wallets`, with that wallet's `polymarket` keys, its share of the
+    /// symbols under `per_symbol` allocation, and its name as a suffix on the per-instance state files. Just
+    /// ("", self) without wallets. Wallets left without symbols are dropped.
+    pub fn wallet_configs(&self) -> anyhow::Result<Vec<(String, Config)>> {
+        if self.wallets.is_empty() {
+            return Ok(vec![(String::new(), self.clone())]);
+        }
+        // Keys and credentials belong to one account; a wallet without its own trades monitor-only.
+        let mut shared = self.polymarket.clone();
+        shared.private_key = None;
+        shared.keystore_path = None;
+        shared.proxy_wallet_address = None;
+        shared.api_key = None;
+        shared.api_secret = None;
+        shared.api_passphrase = None;
+        shared.signer = SignerConfig::default();
+        let base = with_exposed(|| serde_json::to_value(&shared))?;
+        let lower = |symbols: &[String]| symbols.iter().map(|s| s.to_lowercase()).collect::<Vec<_>>();
+        let mut claimed: Vec<String> = Vec::new();
+        if self.wallet_allocation == WalletAllocation::PerSymbol {
+            for wallet in &self.wallets {
+                for symbol in lower(&wallet.symbols) {
+                    if claimed.contains(&symbol) {
+                        anyhow::bail!("wallets: {} is listed by more than one wallet", symbol);
+                    }
+                    claimed.push(symbol);
+                }
+            }
+        }
+        let mut names = std::collections::HashSet::new();
+        let mut wallets = Vec::with_capacity(self.wallets.len());
+        for (index, wallet) in self.wallets.iter().enumerate() {
+            let name = wallet.name.trim();
+            if name.is_empty() || !names.insert(name) {
+                anyhow::bail!("wallets need unique, non-empty names (got {:?})", wallet.name);
+            }
+            if let Some(key) = wallet.overrides.keys().find(|k| base.get(k.as_str()).is_none()) {
+                anyhow::bail!("wallet {}: unknown polymarket key {:?}", name, key);
+            }
+            let mut merged = base.clone();
+            merge_json(&mut merged, &serde_json::Value::Object(wallet.overrides.clone()));
+            let polymarket: PolymarketConfig = serde_json::from_value(merged)
+                .map_err(|e| anyhow::anyhow!("wallet {}: {}", name, e))?;
+            let mut strategy = self.strategy.clone();
+            if self.wallet_allocation == WalletAllocation::PerSymbol {
+                let own = lower(&wallet.symbols);
+                if own.is_empty() {
+                    strategy.symbols.retain(|s| !claimed.contains(&s.to_lowercase()));
+                } else {
+                    strategy.symbols.retain(|s| own.contains(&s.to_lowercase()));
+                    // Discovered symbols go to the wallet without a list.
+                    strategy.symbol_discovery.enabled = false;
+                }
+                if strategy.symbols.is_empty() && !strategy.symbol_discovery.enabled {
+                    continue;
+                }
+            }
+            for (_, path) in strategy.instance_paths() {
+                *path = instance_path(path, name);
+            }
+            wallets.push((
+                name.to_string(),
+                Config {
+                    polymarket,
+                    strategy,
+                    wallets: Vec::new(),
+                    wallet: Some(WalletSlot {
+                        name: name.to_string(),
+                        index,
+                        count: self.wallets.len(),
+                        allocation: self.wallet_allocation,
+                    }),
+                    ..self.clone()
+                },
+            ));
+        }
+        Ok(wallets)
+    }
+
+    fn named_instances(&self) -> anyhow::Result<Vec<(String, Config)>> {
+        if self.instances.is_empty() {
+            return Ok(vec![(String::new(), self.clone())]);
+        }
+        let base = with_exposed(|| serde_json::to_value(&self.strategy))?;
+        let mut names = std::collections::HashSet::new();
+        let mut instances = Vec::with_capacity(self.instances.len());
+        for instance in &self.instances {
+            let name = instance.name.trim();
+            if name.is_empty() || !names.insert(name) {
+                anyhow::bail!("instances need unique, non-empty names (got {:?})", instance.name);
+            }
+            if let Some(key) = instance.overrides.keys().find(|k| base.get(k.as_str()).is_none()) {
+                anyhow::bail!("instance {}: unknown strategy key {:?}", name, key);
+            }
+            let mut merged = base.clone();
+            merge_json(&mut merged, &serde_json::Value::Object(instance.overrides.clone()));
+            let mut strategy: StrategyConfig = serde_json::from_value(merged)
+                .map_err(|e| anyhow::anyhow!("instance {}: {}", name, e))?;
+            for (key, path) in strategy.instance_paths() {
+                if !instance.overrides.contains_key(key) {
+                    *path = instance_path(path, name);
+                }
+            }
+            instances.push((
+                name.to_string(),
+                Config {
+                    strategy,
+                    instances: Vec::new(),
+                    ..self.clone()
+                },
+            ));
+        }
+        Ok(instances)
+    }
+
     pub fn load(path: &PathBuf) -> anyhow::Result<Self> {
+        Self::load_profile(path, None)
+    }
+
+    /// `load`, then `profile` from the file's `profiles` applied over the rest. Fails on an unknown profile or
+    /// one that sets keys the config doesn't have.
+    pub fn load_profile(path: &PathBuf, profile: Option<&str>) -> anyhow::Result<Self> {
         if path.exists() {
             let content = std::fs::read_to_string(path)?;
-            Ok(serde_json::from_str(&content)?)
+            let mut value: serde_json::Value = serde_json::from_str(&content)?;
+            let overridden: Vec<String> = match profile {
+                Some(name) => apply_profile(&mut value, name)?,
+                None => Vec::new(),
+            };
+            let mut config: Config = serde_json::from_value(value)?;
+            config.profile = profile.map(str::to_string);
+            match profile {
+                // A profile's state is its own already, sim or live.
+                Some(name) => {
+                    for (key, path) in config.strategy.instance_paths() {
+                        if !overridden.iter().any(|k| k == key) {
+                            *path = instance_path(path, name);
+                        }
+                    }
+                }
+                None => config.strategy.separate_sim_state(),
+            }
+            config.polymarket.apply_env();
+            config.polymarket.unlock_keystore()?;
+            let polymarket = &mut config.polymarket;
+            if polymarket.clob_api_url == MAINNET_CLOB_API_URL {
+                polymarket.clob_api_url = polymarket.network.clob_api_url().to_string();
+            }
+            Ok(config)
         } else {
-            let config = Config::default();
+            if let Some(name) = profile {
+                anyhow::bail!("profile {:?} not found: {} does not exist", name, path.display());
+            }
+            let mut config = Config::default();
             let content = serde_json::to_string_pretty(&config)?;
             std::fs::write(path, content)?;
+            config.strategy.separate_sim_state();
             Ok(config)
         }
     }
 }
+
+/// Overlay `profiles.<name>` onto the rest of the raw config. Returns the `strategy` keys the profile sets.
+fn apply_profile(config: &mut serde_json::Value, name: &str) -> anyhow::Result<Vec<String>> {
+    let profiles = config.get("profiles").and_then(|p| p.as_object());
+    let Some(profile) = profiles.and_then(|p| p.get(name)).cloned() else {
+        let known: Vec<&String> = profiles.map(|p| p.keys().collect()).unwrap_or_default();
+        anyhow::bail!("profile {:?} not found in the config (profiles: {:?})", name, known);
+    };
+    let Some(keys) = profile.as_object() else {
+        anyhow::bail!("profile {:?} must be an object", name);
+    };
+    let base = serde_json::to_value(Config::default())?;
+    if let Some(key) = keys.keys().find(|k| k.as_str() == "profiles" || base.get(k.as_str()).is_none()) {
+        anyhow::bail!("profile {}: unknown config key {:?}", name, key);
+    }
+    let strategy_keys = profile
+        .get("strategy")
+        .and_then(|s| s.as_object())
+        .map(|s| s.keys().cloned().collect())
+        .unwrap_or_default();
+    merge_json(config, &profile);
+    Ok(strategy_keys)
+}
+
+/// Overlay `over` onto `base`: objects merge key by key, anything else is replaced.
+fn merge_json(base: &mut serde_json::Value, over: &serde_json::Value) {
+    match (base, over) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(over)) => {
+            for (key, value) in over {
+                match base.get_mut(key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, over) => *base = over.clone(),
+    }
+}
+
+/// "journal.jsonl" -> "journal-aggressive.jsonl"; an empty path (feature off) stays empty.
+fn instance_path(path: &str, name: &str) -> String {
+    let path = path.trim();
+    if path.is_empty() {
+        return String::new();
+    }
+    let p = std::path::Path::new(path);
+    let stem = p.file_stem().and_then(|s| s.to_str()).unwrap_or(path);
+    let file = match p.extension().and_then(|e| e.to_str()) {
+        Some(ext) => format!("{}-{}.{}", stem, name, ext),
+        None => format!("{}-{}", stem, name),
+    };
+    p.with_file_name(file).to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unlocks_the_keystore_when_no_key_is_set() {
+        let dir = std::env::temp_dir().join(format!("config-keystore-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key = PrivateKeySigner::random();
+        PrivateKeySigner::encrypt_keystore(&dir, &mut rand::thread_rng(), key.to_bytes(), "hunter2", Some("key.json"))
+            .unwrap();
+        let mut polymarket = Config::default().polymarket;
+        polymarket.keystore_path = Some(dir.join("key.json").display().to_string());
+        std::env::set_var(ENV_KEYSTORE_PASSWORD, "hunter2");
+        let unlocked = polymarket.unlock_keystore();
+        std::env::remove_var(ENV_KEYSTORE_PASSWORD);
+        unlocked.unwrap();
+        let signer: PrivateKeySigner = polymarket.private_key.as_ref().unwrap().expose().parse().unwrap();
+        assert_eq!(signer.address(), key.address());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn default_config_is_not_dangerous() {
+        assert!(Config::default().strategy.dangerous_settings().is_empty());
+    }
+
+    #[test]
+    fn flags_dangerous_settings() {
+        let mut strategy = Config::default().strategy;
+        strategy.sum_threshold = 1.01;
+        strategy.trade_interval_secs = 0;
+        strategy.arb_shares = "500".to_string();
+        strategy.btc_price_to_beat_tolerance_usd = 0.0;
+        strategy.eth_price_to_beat_tolerance_usd = 0.0;
+        strategy.sol_price_to_beat_tolerance_usd = 0.0;
+        strategy.xrp_price_to_beat_tolerance_usd = 0.0;
+        assert_eq!(strategy.dangerous_settings().len(), 3);
+    }
+
+    #[test]
+    fn flags_a_close_cutoff_covering_the_overlap() {
+        let mut strategy = Config::default().strategy;
+        let overlap_secs = strategy.periods.long_secs() - strategy.periods.overlap_start_offset_mins * 60;
+        strategy.no_trade_before_close_secs = overlap_secs as u64 - 1;
+        assert!(strategy.dangerous_settings().is_empty());
+        strategy.no_trade_before_close_secs = overlap_secs as u64;
+        assert_eq!(strategy.dangerous_settings().len(), 1);
+    }
+
+    #[test]
+    fn complete_set_cant_run_in_maker_mode() {
+        let mut strategy = Config::default().strategy;
+        strategy.kind = StrategyKind::CompleteSet;
+        assert!(strategy.dangerous_settings().is_empty());
+        strategy.maker_mode.enabled = true;
+        assert_eq!(strategy.dangerous_settings().len(), 1);
+    }
+
+    #[test]
+    fn validates_leg_order_type() {
+        let mut strategy = Config::default().strategy;
+        strategy.leg_order_type = "IOC".to_string();
+        assert_eq!(strategy.dangerous_settings().len(), 1);
+        strategy.leg_order_type = "GTD".to_string();
+        assert!(strategy.dangerous_settings().is_empty());
+        strategy.leg_order_ttl_secs = 0;
+        assert_eq!(strategy.dangerous_settings().len(), 1);
+    }
+
+    #[test]
+    fn symbol_limits_override_strategy_defaults() {
+        let mut strategy = Config::default().strategy;
+        strategy.max_trades_per_period = 3;
+        strategy.symbol_limits =
+            serde_json::from_str(r#"{"sol": {"trade_interval_secs": 30, "max_trades_per_period": 1}, "eth": {}}"#)
+                .unwrap();
+        assert_eq!(strategy.trade_interval_secs_for("SOL"), 30);
+        assert_eq!(strategy.max_trades_per_period_for("sol"), 1);
+        assert_eq!(strategy.trade_interval_secs_for("eth"), strategy.trade_interval_secs);
+        assert_eq!(strategy.max_trades_per_period_for("btc"), 3);
+    }
+
+    #[test]
+    fn schedule_pauses_outside_days_hours_and_blackouts() {
+        let schedule: ScheduleConfig = serde_json::from_str(
+            r#"{"days": ["mon", "tue", "wed", "thu", "fri"], "start_et": "09:00", "end_et": "17:00",
+                "blackouts": [{"start": "2026-12-16T13:45:00-05:00", "end": "2026-12-16T15:00:00-05:00", "label": "FOMC"}]}"#,
+        )
+        .unwrap();
+        assert!(schedule.issues().is_empty());
+        // Wednesday 2026-12-16 ET.
+        assert_eq!(schedule.paused_at(1_797_440_400), None); // 12:00
+        assert!(schedule.paused_at(1_797_450_000).unwrap().starts_with("FOMC")); // 14:40
+        assert!(schedule.paused_at(1_797_458_400).is_some()); // 17:00
+        assert!(schedule.paused_at(1_797_699_600).is_some()); // Saturday 12:00
+    }
+
+    #[test]
+    fn parses_resolution_source_priority() {
+        let sources: Vec<ResolutionSource> = serde_json::from_str(r#"["onchain", "clob"]"#).unwrap();
+        assert_eq!(sources, vec![ResolutionSource::Onchain, ResolutionSource::Clob]);
+    }
+
+    #[test]
+    fn rpc_url_accepts_string_or_list() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        value["polymarket"]["rpc_url"] = serde_json::json!("https://a.example");
+        let config: Config = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(config.polymarket.rpc_url, vec!["https://a.example"]);
+
+        value["polymarket"]["rpc_url"] = serde_json::json!(["https://a.example", "https://b.example"]);
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.polymarket.rpc_url.len(), 2);
+    }
+
+    #[test]
+    fn contract_overrides_apply_on_top_of_network_defaults() {
+        let mut polymarket = Config::default().polymarket;
+        polymarket.network = Network::Amoy;
+        polymarket.contracts.usdc = Some("0xusdc".to_string());
+        let contracts = polymarket.contracts();
+        assert_eq!(contracts.usdc, "0xusdc");
+        assert_eq!(contracts.ctf, Network::Amoy.default_contracts().ctf);
+        assert_eq!(Network::Amoy.chain_id(), 80002);
+    }
+
+    #[test]
+    fn instances_merge_over_the_base_strategy() {
+        let mut config = Config {
+            instances: serde_json::from_value(serde_json::json!([
+                {"name": "aggressive", "sum_threshold": 0.995, "maker_mode": {"enabled": true}},
+                {"name": "conservative", "symbols": ["btc"], "journal_path": "safe.jsonl"}
+            ]))
+            .unwrap(),
+            ..Config::default()
+        };
+        let instances = config.strategy_instances().unwrap();
+        let (name, aggressive) = &instances[0];
+        assert_eq!(name, "aggressive");
+        assert_eq!(aggressive.strategy.sum_threshold, 0.995);
+        assert!(aggressive.strategy.maker_mode.enabled);
+        assert_eq!(aggressive.strategy.maker_mode.near_threshold, config.strategy.maker_mode.near_threshold);
+        assert_eq!(aggressive.strategy.journal_path, "journal-aggressive.jsonl");
+        let conservative = &instances[1].1.strategy;
+        assert_eq!(conservative.symbols, vec!["btc"]);
+        assert_eq!(conservative.journal_path, "safe.jsonl");
+
+        config.instances[1].overrides.insert("sum_treshold".to_string(), serde_json::json!(0.9));
+        assert!(config.strategy_instances().is_err());
+    }
+
+    #[test]
+    fn wallets_get_their_own_keys_symbols_and_state_files() {
+        let mut config = Config {
+            wallets: serde_json::from_value(serde_json::json!([
+                {"name": "main", "private_key": "0x01", "symbols": ["btc", "eth"]},
+                {"name": "spare", "private_key": "0x02", "proxy_wallet_address": "0xproxy"}
+            ]))
+            .unwrap(),
+            wallet_allocation: WalletAllocation::PerSymbol,
+            ..Config::default()
+        };
+        config.polymarket.api_key = Some("base-key".to_string());
+        config.strategy.symbols = ["btc", "eth", "sol"].iter().map(|s| s.to_string()).collect();
+        let instances = config.strategy_instances().unwrap();
+        assert_eq!(instances.len(), 2);
+        let (name, main) = &instances[0];
+        assert_eq!(name, "main");
+        assert_eq!(main.polymarket.private_key.as_ref().map(Secret::expose), Some("0x01"));
+        assert_eq!(main.polymarket.api_key, None);
+        assert_eq!(main.strategy.symbols, vec!["btc", "eth"]);
+        assert_eq!(main.strategy.pnl_state_path, "pnl_state-main.json");
+        let spare = &instances[1].1;
+        assert_eq!(spare.strategy.symbols, vec!["sol"]);
+        assert_eq!(spare.polymarket.proxy_wallet_address.as_deref(), Some("0xproxy"));
+
+        config.wallets[1].symbols = vec!["ETH".to_string()];
+        assert!(config.strategy_instances().is_err());
+        config.wallets[1].symbols.clear();
+        config.wallets[1].overrides.insert("privat_key".to_string(), serde_json::json!("0x02"));
+        assert!(config.strategy_instances().is_err());
+    }
+
+    #[test]
+    fn profiles_overlay_the_rest_of_the_file() {
+        let path = std::env::temp_dir().join(format!("config-profiles-{}.json", std::process::id()));
+        let file = serde_json::json!({
+            "polymarket": {"gamma_api_url": "https://gamma", "clob_api_url": "https://clob", "private_key": "0xlive"},
+            "strategy": {"simulation_mode": false, "arb_shares": "50"},
+            "profiles": {
+                "sim": {"strategy": {"simulation_mode": true}, "polymarket": {"private_key": null}},
+                "paper": {"strategy": {"simulation_mode": true, "journal_path": "paper.jsonl"}},
+                "typo": {"stratgy": {}}
+            }
+        });
+        std::fs::write(&path, file.to_string()).unwrap();
+        let base = Config::load_profile(&path, None).unwrap();
+        assert!(!base.strategy.simulation_mode);
+        let sim = Config::load_profile(&path, Some("sim")).unwrap();
+        assert_eq!(sim.profile.as_deref(), Some("sim"));
+        assert!(sim.strategy.simulation_mode);
+        assert_eq!(sim.strategy.arb_shares, "50");
+        assert_eq!(sim.polymarket.private_key, None);
+        assert_eq!(
+            (base.strategy.pnl_state_path.as_str(), sim.strategy.pnl_state_path.as_str()),
+            ("pnl_state.json", "pnl_state-sim.json")
+        );
+        assert_eq!(sim.strategy.redeem_queue_path, instance_path(&base.strategy.redeem_queue_path, "sim"));
+        let paper = Config::load_profile(&path, Some("paper")).unwrap();
+        assert_eq!(paper.strategy.journal_path, "paper.jsonl");
+        assert_eq!(paper.strategy.pnl_state_path, "pnl_state-paper.json");
+        assert!(Config::load_profile(&path, Some("typo")).is_err());
+        assert!(Config::load_profile(&path, Some("live")).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn round_robin_wallets_take_turns_each_period() {
+        let slot = |index| WalletSlot {
+            name: format!("w{}", index),
+            index,
+            count: 3,
+            allocation: WalletAllocation::RoundRobin,
+        };
+        for period in 0..6 {
+            let start = 1_767_726_000 + period * 900;
+            let takers: Vec<usize> = (0..3).filter(|&i| slot(i).takes_period("btc", start, 900)).collect();
+            assert_eq!(takers.len(), 1);
+            assert!(!slot(takers[0]).takes_period("btc", start + 900, 900));
+        }
+    }
+}
@@ -1,67 +1,175 @@
+use crate::domain::fees::{effective_price, fee_per_share, LegFees};
+use crate::models::Outcome;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy)]
 pub struct ArbSelection<'a> {
     pub leg1_token: &'a str,
-    pub leg1_price: f64,
+    pub leg1_price: Decimal,
     pub leg2_token: &'a str,
-    pub leg2_price: f64,
-    pub leg1_outcome: &'a str,
-    pub leg2_outcome: &'a str,
+    pub leg2_price: Decimal,
+    pub leg1_outcome: Outcome,
+    pub leg2_outcome: Outcome,
+    /// Fee per share (USDC) for each leg at the selected prices.
+    pub leg1_fee: Decimal,
+    pub leg2_fee: Decimal,
+    /// Leg 2 is on the long market like leg 1 (a complete set), not on the short one.
+    pub leg2_long: bool,
+}
+
+impl<'a> ArbSelection<'a> {
+    /// Cost per share pair including fees; this is what must beat the threshold.
+    pub fn effective_sum(&self) -> Decimal {
+        self.leg1_price + self.leg1_fee + self.leg2_price + self.leg2_fee
+    }
+
+    /// The same legs at new asks, with fees recomputed.
+    pub fn repriced(&self, ask1: Decimal, ask2: Decimal, fees: &LegFees) -> ArbSelection<'a> {
+        ArbSelection {
+            leg1_price: ask1,
+            leg2_price: ask2,
+            leg1_fee: fee_per_share(ask1, fees.bps_15),
+            leg2_fee: fee_per_share(ask2, if self.leg2_long { fees.bps_15 } else { fees.bps_5 }),
+            ..*self
+        }
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn select_arb_legs<'a>(
-    ask_15_up: Option<f64>,
-    ask_15_down: Option<f64>,
-    ask_5_up: Option<f64>,
-    ask_5_down: Option<f64>,
-    threshold: f64,
+    ask_15_up: Option<Decimal>,
+    ask_15_down: Option<Decimal>,
+    ask_5_up: Option<Decimal>,
+    ask_5_down: Option<Decimal>,
+    threshold: Decimal,
+    fees: &LegFees,
     t15_up: &'a str,
     t15_down: &'a str,
     t5_up: &'a str,
     t5_down: &'a str,
 ) -> Option<ArbSelection<'a>> {
     let sum_up_down = match (ask_15_up, ask_5_down) {
-        (Some(a), Some(b)) => Some(a + b),
+        (Some(a), Some(b)) => Some(effective_price(a, fees.bps_15) + effective_price(b, fees.bps_5)),
         _ => None,
     };
     let sum_down_up = match (ask_15_down, ask_5_up) {
-        (Some(a), Some(b)) => Some(a + b),
+        (Some(a), Some(b)) => Some(effective_price(a, fees.bps_15) + effective_price(b, fees.bps_5)),
         _ => None,
     };
 
     if sum_up_down.map(|s| s < threshold).unwrap_or(false) {
+        let leg1_price = ask_15_up.expect("ask_15_up checked");
+        let leg2_price = ask_5_down.expect("ask_5_down checked");
         return Some(ArbSelection {
             leg1_token: t15_up,
-            leg1_price: ask_15_up.expect("ask_15_up checked"),
+            leg1_price,
             leg2_token: t5_down,
-            leg2_price: ask_5_down.expect("ask_5_down checked"),
-            leg1_outcome: "Up",
-            leg2_outcome: "Down",
+            leg2_price,
+            leg1_outcome: Outcome::Up,
+            leg2_outcome: Outcome::Down,
+            leg1_fee: fee_per_share(leg1_price, fees.bps_15),
+            leg2_fee: fee_per_share(leg2_price, fees.bps_5),
+            leg2_long: false,
         });
     }
     if sum_down_up.map(|s| s < threshold).unwrap_or(false) {
+        let leg1_price = ask_15_down.expect("ask_15_down checked");
+        let leg2_price = ask_5_up.expect("ask_5_up checked");
         return Some(ArbSelection {
             leg1_token: t15_down,
-            leg1_price: ask_15_down.expect("ask_15_down checked"),
+            leg1_price,
             leg2_token: t5_up,
-            leg2_price: ask_5_up.expect("ask_5_up checked"),
-            leg1_outcome: "Down",
-            leg2_outcome: "Up",
+            leg2_price,
+            leg1_outcome: Outcome::Down,
+            leg2_outcome: Outcome::Up,
+            leg1_fee: fee_per_share(leg1_price, fees.bps_15),
+            leg2_fee: fee_per_share(leg2_price, fees.bps_5),
+            leg2_long: false,
         });
     }
     None
 }
 
+/// Maker prices for the arb pair whose taker sum is just above `threshold` (within `near`): each leg bids
+/// `ticks` ticks below its ask, or at its midpoint on the tick grid when `mids` (by token id) has a lower one.
+/// Returns the pair with the lowest maker sum when it beats the threshold at maker fees, or None when a taker
+/// arb exists already or no pair is close enough.
+#[allow(clippy::too_many_arguments)]
+pub fn select_maker_legs<'a>(
+    ask_15_up: Option<Decimal>,
+    ask_15_down: Option<Decimal>,
+    ask_5_up: Option<Decimal>,
+    ask_5_down: Option<Decimal>,
+    threshold: Decimal,
+    near: Decimal,
+    ticks: u32,
+    tick_size: Decimal,
+    taker_fees: &LegFees,
+    maker_fees: &LegFees,
+    mids: &HashMap<&str, Decimal>,
+    t15_up: &'a str,
+    t15_down: &'a str,
+    t5_up: &'a str,
+    t5_down: &'a str,
+) -> Option<ArbSelection<'a>> {
+    let bid_below = |token: &str, ask: Decimal| {
+        let below_ask = ((ask / tick_size).round() - Decimal::from(ticks)) * tick_size;
+        let price = match mids.get(token) {
+            Some(mid) => below_ask.min((mid / tick_size).floor() * tick_size),
+            None => below_ask,
+        };
+        (price >= tick_size).then_some(price)
+    };
+    let pairs = [
+        (ask_15_up, ask_5_down, t15_up, t5_down, Outcome::Up, Outcome::Down),
+        (ask_15_down, ask_5_up, t15_down, t5_up, Outcome::Down, Outcome::Up),
+    ];
+    pairs
+        .into_iter()
+        .filter_map(|(ask1, ask2, token1, token2, outcome1, outcome2)| {
+            let (ask1, ask2) = (ask1?, ask2?);
+            let taker_sum = effective_price(ask1, taker_fees.bps_15) + effective_price(ask2, taker_fees.bps_5);
+            if taker_sum < threshold || taker_sum >= threshold + near {
+                return None;
+            }
+            let (price1, price2) = (bid_below(token1, ask1)?, bid_below(token2, ask2)?);
+            let selection = ArbSelection {
+                leg1_token: token1,
+                leg1_price: price1,
+                leg2_token: token2,
+                leg2_price: price2,
+                leg1_outcome: outcome1,
+                leg2_outcome: outcome2,
+                leg1_fee: fee_per_share(price1, maker_fees.bps_15),
+                leg2_fee: fee_per_share(price2, maker_fees.bps_5),
+                leg2_long: false,
+            };
+            (selection.effective_sum() < threshold).then_some(selection)
+        })
+        .min_by_key(|selection| selection.effective_sum())
+}
+
+/// Whether leg 2 should go first under thinner-first sequencing: it shows fewer shares at its best ask.
+/// An unknown size counts as thinnest; ties keep leg 1 first.
+pub fn leg2_is_thinner(ask_size_1: Option<f64>, ask_size_2: Option<f64>) -> bool {
+    ask_size_2.unwrap_or(0.0) < ask_size_1.unwrap_or(0.0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
 
     #[test]
     fn selects_up_down_when_threshold_hit() {
         let sel = select_arb_legs(
-            Some(0.48),
-            Some(0.6),
-            Some(0.7),
-            Some(0.49),
-            0.99,
+            Some(dec!(0.48)),
+            Some(dec!(0.6)),
+            Some(dec!(0.7)),
+            Some(dec!(0.49)),
+            dec!(0.99),
+            &LegFees::default(),
             "t15u",
             "t15d",
             "t5u",
@@ -75,11 +183,12 @@ mod tests {
     #[test]
     fn returns_none_when_no_edge() {
         let sel = select_arb_legs(
-            Some(0.6),
-            Some(0.6),
-            Some(0.5),
-            Some(0.5),
-            0.99,
+            Some(dec!(0.6)),
+            Some(dec!(0.6)),
+            Some(dec!(0.5)),
+            Some(dec!(0.5)),
+            dec!(0.99),
+            &LegFees::default(),
             "t15u",
             "t15d",
             "t5u",
@@ -87,4 +196,87 @@ mod tests {
         );
         assert!(sel.is_none());
     }
+
+    #[test]
+    fn fees_can_remove_edge() {
+        let fees = LegFees {
+            bps_15: 300.0,
+            bps_5: 300.0,
+        };
+        let sel = select_arb_legs(
+            Some(dec!(0.48)),
+            Some(dec!(0.6)),
+            Some(dec!(0.7)),
+            Some(dec!(0.50)),
+            dec!(0.99),
+            &fees,
+            "t15u",
+            "t15d",
+            "t5u",
+            "t5d",
+        );
+        assert!(sel.is_none());
+    }
+
+    #[test]
+    fn thinner_leg_goes_first() {
+        assert!(leg2_is_thinner(Some(120.0), Some(15.0)));
+        assert!(!leg2_is_thinner(Some(15.0), Some(120.0)));
+        assert!(!leg2_is_thinner(Some(15.0), Some(15.0)));
+        assert!(leg2_is_thinner(Some(15.0), None));
+    }
+
+    #[test]
+    fn maker_quotes_below_ask_when_near_threshold() {
+        let fees = LegFees::default();
+        let no_mids = HashMap::new();
+        let quote = |ask_15_up, ask_5_down| {
+            select_maker_legs(
+                Some(ask_15_up),
+                Some(dec!(0.9)),
+                Some(dec!(0.9)),
+                Some(ask_5_down),
+                dec!(0.99),
+                dec!(0.03),
+                1,
+                dec!(0.01),
+                &fees,
+                &fees,
+                &no_mids,
+                "t15u",
+                "t15d",
+                "t5u",
+                "t5d",
+            )
+        };
+        let sel = quote(dec!(0.5), dec!(0.5)).expect("sum 1.00 is within reach at maker prices");
+        assert_eq!(sel.leg1_token, "t15u");
+        assert_eq!(sel.leg1_price, dec!(0.49));
+        assert_eq!(sel.leg2_price, dec!(0.49));
+        assert!(quote(dec!(0.48), dec!(0.5)).is_none(), "taker arb already there");
+        assert!(quote(dec!(0.52), dec!(0.5)).is_none(), "too far above the threshold");
+
+        // A wide 15m book: its bid drops to the midpoint; the 5m leg has no mid and stays a tick under.
+        let mids = HashMap::from([("t15u", dec!(0.455))]);
+        let sel = select_maker_legs(
+            Some(dec!(0.5)),
+            Some(dec!(0.9)),
+            Some(dec!(0.9)),
+            Some(dec!(0.5)),
+            dec!(0.99),
+            dec!(0.03),
+            1,
+            dec!(0.01),
+            &fees,
+            &fees,
+            &mids,
+            "t15u",
+            "t15d",
+            "t5u",
+            "t5d",
+        )
+        .expect("capped bids still beat the threshold");
+        assert_eq!(sel.leg1_price, dec!(0.45));
+        assert_eq!(sel.leg2_price, dec!(0.49));
+    }
 }
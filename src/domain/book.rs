@@ -0,0 +1,25 @@
+//! Midpoint and spread of a token's top of book, the same figures the CLOB `/midpoint` and `/spread` endpoints
+//! report, computed from the WS snapshot so the hot path makes no REST calls.
+
+/// Halfway between the best bid and ask; None unless both sides are quoted.
+pub fn midpoint(bid: Option<f64>, ask: Option<f64>) -> Option<f64> {
+    Some((bid? + ask?) / 2.0)
+}
+
+/// Best ask minus best bid; None unless both sides are quoted.
+pub fn spread(bid: Option<f64>, ask: Option<f64>) -> Option<f64> {
+    Some(ask? - bid?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn midpoint_and_spread_need_both_sides() {
+        assert!((midpoint(Some(0.44), Some(0.46)).unwrap() - 0.45).abs() < 1e-9);
+        assert!((spread(Some(0.44), Some(0.46)).unwrap() - 0.02).abs() < 1e-9);
+        assert_eq!(midpoint(None, Some(0.46)), None);
+        assert_eq!(spread(Some(0.44), None), None);
+    }
+}
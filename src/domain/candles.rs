@@ -0,0 +1,66 @@
+//! OHLC candles from the CLOB price history, which reports one price per point.
+
+use crate::models::PricePoint;
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Candle {
+    /// Unix seconds, a multiple of the candle length.
+    pub start: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    /// Price points in the candle.
+    pub points: usize,
+}
+
+/// Bucket `points` into `secs`-long candles aligned to multiples of `secs`, oldest first. Candles without
+/// points are left out.
+pub fn candles(points: &[PricePoint], secs: i64) -> Vec<Candle> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by_key(|p| p.t);
+    let mut out: Vec<Candle> = Vec::new();
+    for point in sorted {
+        let start = point.t - point.t.rem_euclid(secs.max(1));
+        match out.last_mut() {
+            Some(candle) if candle.start == start => {
+                candle.high = candle.high.max(point.p);
+                candle.low = candle.low.min(point.p);
+                candle.close = point.p;
+                candle.points += 1;
+            }
+            _ => out.push(Candle {
+                start,
+                open: point.p,
+                high: point.p,
+                low: point.p,
+                close: point.p,
+                points: 1,
+            }),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_bucket_into_aligned_candles() {
+        let points: Vec<PricePoint> = [(120, 0.50), (60, 0.40), (90, 0.55), (100, 0.45), (300, 0.60)]
+            .into_iter()
+            .map(|(t, p)| PricePoint { t, p })
+            .collect();
+        let got = candles(&points, 120);
+        assert_eq!(
+            got,
+            vec![
+                Candle { start: 0, open: 0.40, high: 0.55, low: 0.40, close: 0.45, points: 3 },
+                Candle { start: 120, open: 0.50, high: 0.50, low: 0.50, close: 0.50, points: 1 },
+                Candle { start: 240, open: 0.60, high: 0.60, low: 0.60, close: 0.60, points: 1 },
+            ]
+        );
+    }
+}
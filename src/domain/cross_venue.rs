@@ -0,0 +1,98 @@
+use crate::domain::fees::effective_price;
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+/// Kalshi trading fee in USD for `contracts` at `price`: rate * C * P * (1 - P), rounded up to the cent.
+pub fn kalshi_fee(price: Decimal, contracts: Decimal, fee_rate: f64) -> Decimal {
+    let rate = Decimal::from_f64(fee_rate).unwrap_or_default();
+    let fee = rate * contracts * price * (Decimal::ONE - price);
+    (fee * Decimal::ONE_HUNDRED).ceil().max(Decimal::ZERO) / Decimal::ONE_HUNDRED
+}
+
+/// Polymarket and Kalshi settle against different references, so a pair can lose on both venues when the spot
+/// ends between the two strikes. Width of that range in bps of the Polymarket strike: holding Polymarket Up
+/// (wins at or above `polymarket_strike`) with Kalshi No (wins below `kalshi_strike`) loses both when the
+/// Polymarket strike is the higher one, and the reverse pair when it is the lower one.
+pub fn dead_zone_bps(polymarket_strike: f64, kalshi_strike: f64, polymarket_up: bool) -> f64 {
+    if polymarket_strike <= 0.0 {
+        return f64::INFINITY;
+    }
+    let gap = if polymarket_up {
+        polymarket_strike - kalshi_strike
+    } else {
+        kalshi_strike - polymarket_strike
+    };
+    gap.max(0.0) / polymarket_strike * 10_000.0
+}
+
+/// Both outcomes held across the two venues.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrossPair {
+    /// Polymarket Up with Kalshi No; otherwise Polymarket Down with Kalshi Yes.
+    pub polymarket_up: bool,
+    pub polymarket_ask: Decimal,
+    pub kalshi_ask: Decimal,
+    /// Per share pair, both venues' fees included.
+    pub cost: Decimal,
+}
+
+/// Cheaper of (Polymarket Up + Kalshi No) and (Polymarket Down + Kalshi Yes) at the asks, for `contracts` pairs.
+/// `polymarket` is (Up ask, Down ask), `kalshi` is (Yes ask, No ask).
+pub fn cheapest_pair(
+    polymarket: (Option<Decimal>, Option<Decimal>),
+    kalshi: (Option<Decimal>, Option<Decimal>),
+    polymarket_fee_bps: f64,
+    kalshi_fee_rate: f64,
+    contracts: Decimal,
+) -> Option<CrossPair> {
+    let pair = |polymarket_up: bool, polymarket_ask: Option<Decimal>, kalshi_ask: Option<Decimal>| {
+        let (polymarket_ask, kalshi_ask) = (polymarket_ask?, kalshi_ask?);
+        let kalshi_fee = if contracts > Decimal::ZERO {
+            kalshi_fee(kalshi_ask, contracts, kalshi_fee_rate) / contracts
+        } else {
+            Decimal::ZERO
+        };
+        Some(CrossPair {
+            polymarket_up,
+            polymarket_ask,
+            kalshi_ask,
+            cost: effective_price(polymarket_ask, polymarket_fee_bps) + kalshi_ask + kalshi_fee,
+        })
+    };
+    [pair(true, polymarket.0, kalshi.1), pair(false, polymarket.1, kalshi.0)]
+        .into_iter()
+        .flatten()
+        .min_by_key(|pair| pair.cost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn kalshi_fee_rounds_up_to_the_cent() {
+        // 0.07 * 10 * 0.5 * 0.5 = 0.175 -> 0.18
+        assert_eq!(kalshi_fee(dec!(0.5), dec!(10), 0.07), dec!(0.18));
+        assert_eq!(kalshi_fee(dec!(0.5), dec!(100), 0.07), dec!(1.75));
+        assert_eq!(kalshi_fee(dec!(0.5), dec!(10), 0.0), Decimal::ZERO);
+    }
+
+    #[test]
+    fn dead_zone_depends_on_strike_order() {
+        assert!((dead_zone_bps(100_010.0, 100_000.0, true) - 1.0).abs() < 0.01);
+        assert_eq!(dead_zone_bps(100_010.0, 100_000.0, false), 0.0);
+        assert!((dead_zone_bps(100_000.0, 100_020.0, false) - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn picks_the_cheaper_pair() {
+        let polymarket = (Some(dec!(0.55)), Some(dec!(0.47)));
+        let pair = cheapest_pair(polymarket, (Some(dec!(0.53)), Some(dec!(0.44))), 0.0, 0.0, dec!(10)).unwrap();
+        assert!(pair.polymarket_up);
+        assert_eq!(pair.cost, dec!(0.99));
+        let pair = cheapest_pair(polymarket, (Some(dec!(0.50)), None), 0.0, 0.0, dec!(10)).unwrap();
+        assert!(!pair.polymarket_up);
+        assert!(cheapest_pair((None, Some(dec!(0.47))), (None, Some(dec!(0.5))), 0.0, 0.0, dec!(10)).is_none());
+    }
+}
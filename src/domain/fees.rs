@@ -0,0 +1,37 @@
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+
+/// Taker/maker fee rates (bps) for the two legs of an arb.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LegFees {
+    pub bps_15: f64,
+    pub bps_5: f64,
+}
+
+/// Fee in USDC per share, Polymarket formula: rate * min(price, 1 - price).
+pub fn fee_per_share(price: Decimal, fee_bps: f64) -> Decimal {
+    let rate = Decimal::from_f64(fee_bps).unwrap_or_default();
+    if rate <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    rate / Decimal::from(10_000) * price.min(Decimal::ONE - price).max(Decimal::ZERO)
+}
+
+/// Price per share including fees.
+pub fn effective_price(price: Decimal, fee_bps: f64) -> Decimal {
+    price + fee_per_share(price, fee_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn fee_scales_with_distance_from_bounds() {
+        assert_eq!(fee_per_share(dec!(0.5), 0.0), Decimal::ZERO);
+        assert_eq!(fee_per_share(dec!(0.5), 100.0), dec!(0.005));
+        assert_eq!(fee_per_share(dec!(0.9), 100.0), dec!(0.001));
+        assert_eq!(effective_price(dec!(0.2), 100.0), dec!(0.202));
+    }
+}
@@ -0,0 +1,90 @@
+/// EIP-1559 policy applied on top of the node's (or gas station's) estimates.
+#[derive(Debug, Clone, Copy)]
+pub struct GasPolicy {
+    /// Headroom on eth_estimateGas (1.2 = +20%).
+    pub limit_multiplier: f64,
+    /// Multiplier on the estimated max fee and priority fee.
+    pub fee_multiplier: f64,
+    /// Fixed priority fee (wei) instead of the estimate.
+    pub priority_fee_wei: Option<u128>,
+    /// Upper bound on max fee per gas (wei).
+    pub max_fee_cap_wei: Option<u128>,
+}
+
+impl Default for GasPolicy {
+    fn default() -> Self {
+        Self {
+            limit_multiplier: 1.2,
+            fee_multiplier: 1.0,
+            priority_fee_wei: None,
+            max_fee_cap_wei: None,
+        }
+    }
+}
+
+pub fn gwei_to_wei(gwei: f64) -> u128 {
+    (gwei.max(0.0) * 1e9).round() as u128
+}
+
+pub fn gas_limit_with_headroom(estimate: u64, multiplier: f64) -> u64 {
+    (estimate as f64 * multiplier.max(1.0)).ceil() as u64
+}
+
+/// (max_fee_per_gas, max_priority_fee_per_gas) in wei. The cap wins over the multiplier, and the
+/// priority fee never exceeds the max fee.
+pub fn eip1559_fees(estimated_max_fee: u128, estimated_priority_fee: u128, policy: &GasPolicy) -> (u128, u128) {
+    let scale = |v: u128| (v as f64 * policy.fee_multiplier.max(0.0)).round() as u128;
+    let mut priority = policy
+        .priority_fee_wei
+        .unwrap_or_else(|| scale(estimated_priority_fee));
+    let mut max_fee = scale(estimated_max_fee).max(priority);
+    if let Some(cap) = policy.max_fee_cap_wei {
+        max_fee = max_fee.min(cap);
+    }
+    priority = priority.min(max_fee);
+    (max_fee, priority)
+}
+
+/// Fees for a same-nonce replacement. Nodes reject replacements that don't raise both fees by at least
+/// 10%, so smaller factors are lifted to that minimum.
+pub fn bump_fees(max_fee: u128, priority_fee: u128, factor: f64) -> (u128, u128) {
+    const MIN_REPLACEMENT_BUMP: f64 = 1.1;
+    let factor = factor.max(MIN_REPLACEMENT_BUMP);
+    let bump = |v: u128| ((v as f64) * factor).ceil() as u128 + 1;
+    (bump(max_fee), bump(priority_fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_multiplier_override_and_cap() {
+        let gwei = gwei_to_wei(1.0);
+        let default = GasPolicy::default();
+        assert_eq!(eip1559_fees(100 * gwei, 30 * gwei, &default), (100 * gwei, 30 * gwei));
+        assert_eq!(gas_limit_with_headroom(100_000, default.limit_multiplier), 120_000);
+
+        let policy = GasPolicy {
+            fee_multiplier: 1.5,
+            priority_fee_wei: Some(40 * gwei),
+            max_fee_cap_wei: Some(120 * gwei),
+            ..default
+        };
+        assert_eq!(eip1559_fees(100 * gwei, 30 * gwei, &policy), (120 * gwei, 40 * gwei));
+
+        let tight_cap = GasPolicy {
+            max_fee_cap_wei: Some(20 * gwei),
+            ..default
+        };
+        assert_eq!(eip1559_fees(100 * gwei, 30 * gwei, &tight_cap), (20 * gwei, 20 * gwei));
+    }
+
+    #[test]
+    fn replacement_bump_is_at_least_ten_percent() {
+        let (max_fee, priority) = bump_fees(100, 30, 1.01);
+        assert!(max_fee >= 110 && priority >= 33);
+        let (max_fee, _) = bump_fees(1_000, 30, 1.5);
+        assert!(max_fee >= 1_500);
+    }
+}
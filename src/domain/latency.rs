@@ -0,0 +1,58 @@
+use crate::models::LegLatency;
+
+/// "quote→decision 2 ms; leg 1 sign 14 ms, post 83 ms, quote→ack 99 ms; leg 2 ...": how long each step of a
+/// placed arb took. Quote-relative spans are left out when the triggering WS update is unknown.
+pub fn describe_latency(quote_at_ms: Option<i64>, decided_at_ms: i64, legs: &[LegLatency]) -> String {
+    let mut parts = Vec::with_capacity(legs.len() + 1);
+    if let Some(quote) = quote_at_ms {
+        parts.push(format!("quote→decision {} ms", decided_at_ms - quote));
+    }
+    for l in legs {
+        let mut part = format!(
+            "leg {} sign {} ms, post {} ms",
+            l.leg,
+            l.order.signed_at_ms - l.order.started_at_ms,
+            l.order.acked_at_ms - l.order.signed_at_ms
+        );
+        match quote_at_ms {
+            Some(quote) => part.push_str(&format!(", quote→ack {} ms", l.order.acked_at_ms - quote)),
+            None => part.push_str(&format!(", decision→ack {} ms", l.order.acked_at_ms - decided_at_ms)),
+        }
+        parts.push(part);
+    }
+    parts.join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderLatency;
+
+    #[test]
+    fn describes_each_step() {
+        let legs = [
+            LegLatency {
+                leg: 1,
+                order: OrderLatency {
+                    started_at_ms: 1_005,
+                    signed_at_ms: 1_019,
+                    acked_at_ms: 1_102,
+                },
+            },
+            LegLatency {
+                leg: 2,
+                order: OrderLatency {
+                    started_at_ms: 1_102,
+                    signed_at_ms: 1_110,
+                    acked_at_ms: 1_190,
+                },
+            },
+        ];
+        assert_eq!(
+            describe_latency(Some(1_000), 1_003, &legs),
+            "quote→decision 3 ms; leg 1 sign 14 ms, post 83 ms, quote→ack 102 ms; \
+             leg 2 sign 8 ms, post 80 ms, quote→ack 190 ms"
+        );
+        assert_eq!(describe_latency(None, 1_003, &legs[..1]), "leg 1 sign 14 ms, post 83 ms, decision→ack 99 ms");
+    }
+}
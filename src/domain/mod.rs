@@ -1,3 +1,15 @@
 pub mod arbitrage;
+pub mod book;
+pub mod candles;
+pub mod cross_venue;
+pub mod fees;
+pub mod gas;
+pub mod latency;
 pub mod pnl;
+pub mod pricing;
+pub mod sizing;
+pub mod strike;
+pub mod ticks;
+pub mod trade_ids;
+pub mod volatility;
 pub mod window;
@@ -1,21 +1,33 @@
-use crate::models::TradeRecord;
+use crate::domain::fees::{fee_per_share, LegFees};
+use crate::models::{Fill, Outcome, Side, TradeRecord};
+use rust_decimal::prelude::FromPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
 
 pub struct TradePnl {
-    pub cost: f64,
-    pub payout: f64,
-    pub pnl: f64,
+    /// Share cost plus fees.
+    pub cost: Decimal,
+    pub fees: Decimal,
+    pub payout: Decimal,
+    pub pnl: Decimal,
     pub won_15m: bool,
     pub won_5m: bool,
 }
 
 pub fn compute_trade_pnl(trade: &TradeRecord, win_token_15: &str, win_token_5: &str) -> TradePnl {
-    let cost = (trade.leg1_price + trade.leg2_price) * trade.size;
-    let won_15m = win_token_15 == trade.leg1_token || win_token_15 == trade.leg2_token;
-    let won_5m = win_token_5 == trade.leg1_token || win_token_5 == trade.leg2_token;
-    let payout = trade.size * ((won_15m as i32 + won_5m as i32) as f64);
+    let fees = held(trade, trade.leg1_fee, trade.leg2_fee) * trade.size;
+    let cost = cost_per_pair(trade) * trade.size;
+    let holds_winner = |token: &str| {
+        (trade.holds_leg(1) && token == trade.leg1_token) || (trade.holds_leg(2) && token == trade.leg2_token)
+    };
+    let won_15m = holds_winner(win_token_15);
+    let won_5m = holds_winner(win_token_5);
+    let payout = trade.size * Decimal::from(won_15m as i32 + won_5m as i32);
     let pnl = payout - cost;
     TradePnl {
         cost,
+        fees,
         payout,
         pnl,
         won_15m,
@@ -23,34 +35,259 @@ pub fn compute_trade_pnl(trade: &TradeRecord, win_token_15: &str, win_token_5: &
     }
 }
 
+/// `leg1 + leg2`, counting only the legs `trade` holds.
+fn held(trade: &TradeRecord, leg1: Decimal, leg2: Decimal) -> Decimal {
+    let leg = |n: u8, value: Decimal| if trade.holds_leg(n) { value } else { Decimal::ZERO };
+    leg(1, leg1) + leg(2, leg2)
+}
+
+/// Unrealized PnL of an open trade marked at the current best bids (missing bid = worthless).
+pub fn unrealized_pnl(trade: &TradeRecord, bid_leg1: Option<Decimal>, bid_leg2: Option<Decimal>) -> Decimal {
+    let value = held(trade, bid_leg1.unwrap_or_default(), bid_leg2.unwrap_or_default()) * trade.size;
+    value - cost_per_pair(trade) * trade.size
+}
+
+/// Cost per share pair of `trade` (per share of the one leg it holds, when one-sided), fees included.
+pub fn cost_per_pair(trade: &TradeRecord) -> Decimal {
+    held(trade, trade.leg1_price + trade.leg1_fee, trade.leg2_price + trade.leg2_fee)
+}
+
+/// Why an open arb should be closed early at a per-pair PnL of `pnl_per_pair`: at or above `take_profit`, or at
+/// or below minus `stop_loss` (0 disables either).
+pub fn exit_trigger(pnl_per_pair: Decimal, take_profit: Decimal, stop_loss: Decimal) -> Option<&'static str> {
+    if take_profit > Decimal::ZERO && pnl_per_pair >= take_profit {
+        Some("take-profit")
+    } else if stop_loss > Decimal::ZERO && pnl_per_pair <= -stop_loss {
+        Some("stop-loss")
+    } else {
+        None
+    }
+}
+
+/// Outcome implied by the Chainlink close relative to the period's price-to-beat.
+/// Up/down markets resolve "Up" when the close is greater than or equal to the open.
+pub fn infer_outcome(price_to_beat: f64, close_price: f64) -> Outcome {
+    if close_price >= price_to_beat {
+        Outcome::Up
+    } else {
+        Outcome::Down
+    }
+}
+
+/// PnL for a trade given the winning outcome of each market (leg1 is the 15m leg, leg2 the 5m leg unless the
+/// trade is a complete set of the 15m market).
+pub fn compute_trade_pnl_by_outcome(trade: &TradeRecord, outcome_15: Outcome, outcome_5: Outcome) -> TradePnl {
+    let legs = [
+        (trade.leg1_token.as_str(), trade.leg1_outcome.as_str(), true),
+        (trade.leg2_token.as_str(), trade.leg2_outcome.as_str(), trade.leg2_long()),
+    ];
+    let winner = |long: bool, outcome: Outcome| {
+        legs.iter()
+            .find(|(_, leg_outcome, on_long)| *on_long == long && outcome.is(leg_outcome))
+            .map_or("", |(token, _, _)| *token)
+    };
+    compute_trade_pnl(trade, winner(true, outcome_15), winner(false, outcome_5))
+}
+
+/// Period PnL recomputed from actual fills, next to what the intended order prices implied.
+pub struct FillReconciliation {
+    pub intended_pnl: Decimal,
+    pub actual_pnl: Decimal,
+    pub actual_cost: Decimal,
+    pub actual_payout: Decimal,
+    /// (token_id, intended size, net filled size) for tokens where they differ.
+    pub size_mismatches: Vec<(String, Decimal, Decimal)>,
+}
+
+/// Fill sizes come off the REST API as floats; differences below this are parse noise, not a short fill.
+const SIZE_TOLERANCE: Decimal = dec!(0.000001);
+
+/// Recompute PnL from the wallet's fills on the two markets. BUY fills add cost, SELL fills (unwinds)
+/// add proceeds; winning tokens still held pay 1 each. Fees use the configured rates on fill prices.
+pub fn reconcile_with_fills(
+    trades: &[TradeRecord],
+    fills: &[Fill],
+    win_token_15: &str,
+    win_token_5: &str,
+    fees: &LegFees,
+) -> FillReconciliation {
+    let cid_15 = trades.first().map(|t| t.cid_15.as_str()).unwrap_or("");
+    let mut intended_pnl = Decimal::ZERO;
+    let mut intended_size: HashMap<&str, Decimal> = HashMap::new();
+    for trade in trades {
+        intended_pnl += compute_trade_pnl(trade, win_token_15, win_token_5).pnl;
+        for (leg, token) in [(1, trade.leg1_token.as_str()), (2, trade.leg2_token.as_str())] {
+            if trade.holds_leg(leg) {
+                *intended_size.entry(token).or_default() += trade.size;
+            }
+        }
+    }
+
+    let mut held: HashMap<&str, Decimal> = HashMap::new();
+    let mut actual_cost = Decimal::ZERO;
+    for fill in fills {
+        let Some(token) = fill.token_id.as_deref() else {
+            continue;
+        };
+        let fee_bps = if fill.condition_id.as_deref() == Some(cid_15) {
+            fees.bps_15
+        } else {
+            fees.bps_5
+        };
+        let price = Decimal::from_f64(fill.price).unwrap_or_default();
+        let size = Decimal::from_f64(fill.size).unwrap_or_default();
+        let fee = fee_per_share(price, fee_bps) * size;
+        if fill.side == Side::Buy {
+            *held.entry(token).or_default() += size;
+            actual_cost += price * size + fee;
+        } else {
+            *held.entry(token).or_default() -= size;
+            actual_cost -= price * size - fee;
+        }
+    }
+
+    let actual_payout = [win_token_15, win_token_5]
+        .iter()
+        .map(|t| held.get(t).copied().unwrap_or_default().max(Decimal::ZERO))
+        .sum::<Decimal>();
+
+    let mut size_mismatches: Vec<(String, Decimal, Decimal)> = intended_size
+        .iter()
+        .filter_map(|(token, intended)| {
+            let filled = held.get(token).copied().unwrap_or_default();
+            ((intended - filled).abs() > SIZE_TOLERANCE).then(|| (token.to_string(), *intended, filled))
+        })
+        .collect();
+    size_mismatches.sort_by(|a, b| a.0.cmp(&b.0));
+
+    FillReconciliation {
+        intended_pnl,
+        actual_pnl: actual_payout - actual_cost,
+        actual_cost,
+        actual_payout,
+        size_mismatches,
+    }
+}
+
+/// A 10-share pair (15m Up "a" at 0.45, 5m Down "b" at 0.47, no fees) that tests across the crate build on.
+#[cfg(test)]
+pub(crate) fn sample_trade() -> TradeRecord {
+    TradeRecord {
+        trade_id: "btc-1-1".to_string(),
+        symbol: "btc".to_string(),
+        period_15: 1,
+        period_5: 1,
+        cid_15: "c15".to_string(),
+        cid_5: "c5".to_string(),
+        leg1_token: "a".to_string(),
+        leg1_price: dec!(0.45),
+        leg1_cid: "c15".to_string(),
+        leg1_outcome: "Up".to_string(),
+        leg2_token: "b".to_string(),
+        leg2_price: dec!(0.47),
+        leg2_cid: "c5".to_string(),
+        leg2_outcome: "Down".to_string(),
+        size: dec!(10),
+        leg1_fee: Decimal::ZERO,
+        leg2_fee: Decimal::ZERO,
+        only_leg: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn sample_trade() -> TradeRecord {
-        TradeRecord {
-            symbol: "btc".to_string(),
-            period_15: 1,
-            period_5: 1,
-            cid_15: "c15".to_string(),
-            cid_5: "c5".to_string(),
-            leg1_token: "a".to_string(),
-            leg1_price: 0.45,
-            leg1_cid: "c15".to_string(),
-            leg1_outcome: "Up".to_string(),
-            leg2_token: "b".to_string(),
-            leg2_price: 0.47,
-            leg2_cid: "c5".to_string(),
-            leg2_outcome: "Down".to_string(),
-            size: 10.0,
+    #[test]
+    fn computes_two_leg_win_pnl() {
+        let result = compute_trade_pnl(&sample_trade(), "a", "b");
+        assert_eq!(result.cost, dec!(9.2));
+        assert_eq!(result.payout, dec!(20));
+        assert_eq!(result.pnl, dec!(10.8));
+    }
+
+    #[test]
+    fn costs_add_up_without_float_drift() {
+        // 0.1 + 0.2 is not 0.3 in f64; ten such pairs must still cost exactly 3.
+        let mut trade = sample_trade();
+        trade.leg1_price = dec!(0.1);
+        trade.leg2_price = dec!(0.2);
+        trade.size = Decimal::ONE;
+        let total: Decimal = (0..10).map(|_| compute_trade_pnl(&trade, "a", "x").cost).sum();
+        assert_eq!(total, dec!(3));
+    }
+
+    #[test]
+    fn exit_triggers_on_target_or_stop() {
+        assert_eq!(cost_per_pair(&sample_trade()), dec!(0.92));
+        assert_eq!(exit_trigger(dec!(0.05), dec!(0.05), dec!(0.1)), Some("take-profit"));
+        assert_eq!(exit_trigger(dec!(-0.1), dec!(0.05), dec!(0.1)), Some("stop-loss"));
+        assert_eq!(exit_trigger(dec!(0.04), dec!(0.05), dec!(0.1)), None);
+        assert_eq!(exit_trigger(dec!(-0.5), dec!(0.05), Decimal::ZERO), None);
+    }
+
+    #[test]
+    fn fees_reduce_pnl() {
+        let mut trade = sample_trade();
+        trade.leg1_fee = dec!(0.01);
+        trade.leg2_fee = dec!(0.01);
+        let result = compute_trade_pnl(&trade, "a", "x");
+        assert_eq!(result.fees, dec!(0.2));
+        assert_eq!(result.pnl, dec!(0.6));
+    }
+
+    fn fill(token: &str, cid: &str, side: Side, size: f64, price: f64) -> Fill {
+        Fill {
+            token_id: Some(token.to_string()),
+            side,
+            size,
+            price,
+            timestamp: 0,
+            condition_id: Some(cid.to_string()),
+            outcome: None,
+            transaction_hash: None,
         }
     }
 
     #[test]
-    fn computes_two_leg_win_pnl() {
-        let result = compute_trade_pnl(&sample_trade(), "a", "b");
-        assert_eq!(result.cost, 9.2);
-        assert_eq!(result.payout, 20.0);
-        assert_eq!(result.pnl, 10.8);
+    fn reconciles_pnl_from_actual_fills() {
+        let fills = vec![fill("a", "c15", Side::Buy, 10.0, 0.44), fill("b", "c5", Side::Buy, 6.0, 0.47)];
+        let rec = reconcile_with_fills(&[sample_trade()], &fills, "a", "b", &LegFees::default());
+        assert_eq!(rec.intended_pnl, dec!(10.8));
+        assert_eq!(rec.actual_cost, dec!(7.22));
+        assert_eq!(rec.actual_payout, dec!(16));
+        assert_eq!(rec.size_mismatches, vec![("b".to_string(), dec!(10), dec!(6))]);
+    }
+
+    #[test]
+    fn one_sided_trade_counts_only_its_leg() {
+        let trade = sample_trade().one_sided("btc-1-1-leg1", 1, dec!(4));
+        assert_eq!(cost_per_pair(&trade), dec!(0.45));
+        let result = compute_trade_pnl(&trade, "x", "b");
+        assert_eq!((result.cost, result.payout), (dec!(1.8), Decimal::ZERO));
+        assert_eq!(compute_trade_pnl(&trade, "a", "b").payout, dec!(4));
+        assert_eq!(unrealized_pnl(&trade, Some(dec!(0.5)), Some(dec!(0.9))), dec!(0.2));
+    }
+
+    #[test]
+    fn marks_open_trade_at_bids() {
+        let trade = sample_trade();
+        assert_eq!(unrealized_pnl(&trade, Some(dec!(0.5)), Some(dec!(0.5))), dec!(0.8));
+        assert_eq!(unrealized_pnl(&trade, Some(dec!(0.5)), None), dec!(-4.2));
+    }
+
+    #[test]
+    fn infers_outcome_from_close() {
+        assert_eq!(infer_outcome(100.0, 100.5), Outcome::Up);
+        assert_eq!(infer_outcome(100.0, 100.0), Outcome::Up);
+        assert_eq!(infer_outcome(100.0, 99.9), Outcome::Down);
+    }
+
+    #[test]
+    fn computes_pnl_from_outcomes() {
+        let result = compute_trade_pnl_by_outcome(&sample_trade(), Outcome::Up, Outcome::Up);
+        assert!(result.won_15m);
+        assert!(!result.won_5m);
+        assert_eq!(result.payout, dec!(10));
     }
 }
@@ -0,0 +1,118 @@
+//! Binary option fair value for up/down markets: the chance the spot finishes at or above the strike, under
+//! a driftless lognormal spot with volatility taken from recent Chainlink ticks.
+
+use crate::models::Outcome;
+use serde::{Deserialize, Serialize};
+
+/// Model inputs and outputs for one arb, logged and attached to the signal and decision.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelEstimate {
+    pub spot: f64,
+    pub strike_15: f64,
+    pub strike_5: f64,
+    pub secs_to_expiry_15: f64,
+    pub secs_to_expiry_5: f64,
+    /// Volatility per square root of a second (log-price units).
+    pub vol_per_sqrt_sec: f64,
+    pub prob_up_15: f64,
+    pub prob_up_5: f64,
+    /// Model probability that each leg pays out.
+    pub leg1_prob: f64,
+    pub leg2_prob: f64,
+    /// Expected payout per share pair minus its cost (with fees).
+    pub expected_value: f64,
+}
+
+/// Probability that the spot ends at or above `strike` after `secs` seconds: N(d2) with zero drift.
+pub fn prob_up(spot: f64, strike: f64, secs: f64, vol_per_sqrt_sec: f64) -> f64 {
+    let spread = vol_per_sqrt_sec * secs.max(0.0).sqrt();
+    if spread <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        return if spot >= strike { 1.0 } else { 0.0 };
+    }
+    normal_cdf(((spot / strike).ln() - spread * spread / 2.0) / spread)
+}
+
+/// Sensitivity of `prob_up` to the spot: the change in the chance of Up per unit of spot. 0 at or past expiry.
+pub fn up_delta(spot: f64, strike: f64, secs: f64, vol_per_sqrt_sec: f64) -> f64 {
+    let spread = vol_per_sqrt_sec * secs.max(0.0).sqrt();
+    if spread <= 0.0 || spot <= 0.0 || strike <= 0.0 {
+        return 0.0;
+    }
+    let d2 = ((spot / strike).ln() - spread * spread / 2.0) / spread;
+    (-d2 * d2 / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt() / (spot * spread)
+}
+
+/// Per-√second volatility from a realized volatility (bps) measured over `window_secs`.
+pub fn vol_per_sqrt_sec(realized_vol_bps: f64, window_secs: f64) -> f64 {
+    if window_secs <= 0.0 {
+        return 0.0;
+    }
+    realized_vol_bps / 10_000.0 / window_secs.sqrt()
+}
+
+/// Model estimate for an arb buying `leg1_outcome` on the long market and `leg2_outcome` on the short one
+/// for `cost` per share pair.
+pub fn estimate_arb(
+    spot: f64,
+    strikes: (f64, f64),
+    secs_to_expiry: (f64, f64),
+    vol_per_sqrt_sec: f64,
+    leg1_outcome: Outcome,
+    leg2_outcome: Outcome,
+    cost: f64,
+) -> ModelEstimate {
+    let prob_up_15 = prob_up(spot, strikes.0, secs_to_expiry.0, vol_per_sqrt_sec);
+    let prob_up_5 = prob_up(spot, strikes.1, secs_to_expiry.1, vol_per_sqrt_sec);
+    let leg_prob = |outcome: Outcome, up: f64| if outcome == Outcome::Up { up } else { 1.0 - up };
+    let leg1_prob = leg_prob(leg1_outcome, prob_up_15);
+    let leg2_prob = leg_prob(leg2_outcome, prob_up_5);
+    ModelEstimate {
+        spot,
+        strike_15: strikes.0,
+        strike_5: strikes.1,
+        secs_to_expiry_15: secs_to_expiry.0,
+        secs_to_expiry_5: secs_to_expiry.1,
+        vol_per_sqrt_sec,
+        prob_up_15,
+        prob_up_5,
+        leg1_prob,
+        leg2_prob,
+        expected_value: leg1_prob + leg2_prob - cost,
+    }
+}
+
+/// Standard normal CDF (Abramowitz-Stegun 7.1.26 erf, error below 1.5e-7).
+fn normal_cdf(x: f64) -> f64 {
+    let z = x.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let poly = t * (0.254_829_592 + t * (-0.284_496_736 + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-z * z).exp();
+    if x >= 0.0 {
+        (1.0 + erf) / 2.0
+    } else {
+        (1.0 - erf) / 2.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fair_value_of_up_and_arb_ev() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-7);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-4);
+        // At the money the chance is just under a half; far in the money it is near certain.
+        let atm = prob_up(100.0, 100.0, 300.0, 0.0005);
+        assert!(atm < 0.5 && atm > 0.49, "{}", atm);
+        assert!(prob_up(103.0, 100.0, 300.0, 0.0005) > 0.99);
+        assert_eq!(prob_up(99.0, 100.0, 0.0, 0.0005), 0.0);
+        let bump = (prob_up(100.01, 100.0, 300.0, 0.0005) - prob_up(99.99, 100.0, 300.0, 0.0005)) / 0.02;
+        assert!((up_delta(100.0, 100.0, 300.0, 0.0005) - bump).abs() < 1e-3);
+
+        // Up on the long market, Down on the short one, spot between the strikes: both legs likely pay.
+        let estimate = estimate_arb(100.0, (99.9, 100.1), (600.0, 120.0), 0.0001, Outcome::Up, Outcome::Down, 0.97);
+        assert!(estimate.leg1_prob > 0.5 && estimate.leg2_prob > 0.5);
+        assert!(estimate.expected_value > 0.0);
+    }
+}
@@ -0,0 +1,74 @@
+/// How large each arb is, beyond the fixed `arb_shares`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SizingPolicy {
+    /// Fraction of available USDC to spend per arb; 0 keeps the fixed base size.
+    pub capital_fraction: f64,
+    /// Cap on the USDC spent per symbol and long period, across all its arbs (0 = no cap).
+    pub max_notional_per_period: f64,
+    /// Edge at which the size is 1x; None = no edge scaling.
+    pub edge_reference: Option<f64>,
+    /// Largest multiple of the size that a wide edge can scale up to.
+    pub max_edge_multiplier: f64,
+}
+
+/// Shares per leg for an arb costing `pair_cost` USDC per share (both legs, with fees) at `edge` below the
+/// threshold, floored to 2 decimals. 0 means the period's notional cap is used up.
+pub fn arb_size(
+    policy: &SizingPolicy,
+    base_shares: f64,
+    available_usdc: Option<f64>,
+    pair_cost: f64,
+    edge: f64,
+    period_notional: f64,
+) -> f64 {
+    if pair_cost <= 0.0 {
+        return 0.0;
+    }
+    let mut shares = match available_usdc {
+        Some(cash) if policy.capital_fraction > 0.0 => cash.max(0.0) * policy.capital_fraction / pair_cost,
+        _ => base_shares,
+    };
+    if let Some(reference) = policy.edge_reference.filter(|r| *r > 0.0) {
+        shares *= (edge / reference).clamp(0.0, policy.max_edge_multiplier.max(1.0));
+    }
+    if policy.max_notional_per_period > 0.0 {
+        let remaining = (policy.max_notional_per_period - period_notional).max(0.0);
+        shares = shares.min(remaining / pair_cost);
+    }
+    (shares * 100.0).floor() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_by_capital_edge_and_period_cap() {
+        let fixed = SizingPolicy::default();
+        assert_eq!(arb_size(&fixed, 10.0, Some(1000.0), 0.95, 0.03, 0.0), 10.0);
+
+        let capital = SizingPolicy {
+            capital_fraction: 0.05,
+            ..fixed
+        };
+        assert_eq!(arb_size(&capital, 10.0, Some(950.0), 0.95, 0.03, 0.0), 50.0);
+        // Unknown balance falls back to the base size.
+        assert_eq!(arb_size(&capital, 10.0, None, 0.95, 0.03, 0.0), 10.0);
+
+        let by_edge = SizingPolicy {
+            edge_reference: Some(0.02),
+            max_edge_multiplier: 2.0,
+            ..fixed
+        };
+        assert_eq!(arb_size(&by_edge, 10.0, None, 0.95, 0.01, 0.0), 5.0);
+        assert_eq!(arb_size(&by_edge, 10.0, None, 0.95, 0.10, 0.0), 20.0);
+
+        let capped = SizingPolicy {
+            max_notional_per_period: 20.0,
+            ..fixed
+        };
+        assert_eq!(arb_size(&capped, 10.0, None, 0.5, 0.03, 15.0), 10.0);
+        assert_eq!(arb_size(&capped, 10.0, None, 0.5, 0.03, 18.0), 4.0);
+        assert_eq!(arb_size(&capped, 10.0, None, 0.5, 0.03, 25.0), 0.0);
+    }
+}
@@ -0,0 +1,18 @@
+/// Distance from `spot` to the nearer of the long and short period strikes (price-to-beat), in bps of the spot.
+pub fn distance_to_strike_bps(spot: f64, strike_long: f64, strike_short: f64) -> f64 {
+    if spot <= 0.0 {
+        return f64::INFINITY;
+    }
+    (spot - strike_long).abs().min((spot - strike_short).abs()) / spot * 10_000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_uses_nearer_strike() {
+        assert!((distance_to_strike_bps(100_000.0, 100_050.0, 99_980.0) - 2.0).abs() < 1e-9);
+        assert_eq!(distance_to_strike_bps(0.0, 1.0, 1.0), f64::INFINITY);
+    }
+}
@@ -0,0 +1,77 @@
+//! Per-market order rules from the CLOB book: prices must sit on the tick grid strictly inside (0, 1) and sizes
+//! must reach the minimum order size, otherwise the order is rejected.
+
+use anyhow::Result;
+use rust_decimal::{Decimal, RoundingStrategy};
+
+/// Share sizes are accepted to 2 decimals.
+const SIZE_DECIMALS: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderRules {
+    pub tick_size: Decimal,
+    pub min_size: Decimal,
+}
+
+impl Default for OrderRules {
+    /// The common case: a cent tick and no minimum size.
+    fn default() -> Self {
+        Self {
+            tick_size: Decimal::new(1, 2),
+            min_size: Decimal::ZERO,
+        }
+    }
+}
+
+impl OrderRules {
+    /// `price` on the tick grid: down for buys and up for sells, so rounding never makes the order worse for us.
+    pub fn round_price(&self, price: Decimal, buy: bool) -> Result<Decimal> {
+        let ticks = price / self.tick_size;
+        let ticks = if buy { ticks.floor() } else { ticks.ceil() };
+        let rounded = (ticks * self.tick_size).normalize();
+        if rounded < self.tick_size || rounded > Decimal::ONE - self.tick_size {
+            anyhow::bail!(
+                "Price {} is outside {}..={} for tick size {}",
+                price,
+                self.tick_size,
+                Decimal::ONE - self.tick_size,
+                self.tick_size
+            );
+        }
+        Ok(rounded)
+    }
+
+    /// `size` floored to 2 decimals; an error below the market's minimum order size.
+    pub fn round_size(&self, size: Decimal) -> Result<Decimal> {
+        let rounded = size.round_dp_with_strategy(SIZE_DECIMALS, RoundingStrategy::ToZero);
+        if rounded <= Decimal::ZERO || rounded < self.min_size {
+            anyhow::bail!("Size {} is below the minimum order size {}", size, self.min_size);
+        }
+        Ok(rounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(s: &str) -> Decimal {
+        Decimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn rounds_to_tick_and_checks_min_size() {
+        let rules = OrderRules {
+            tick_size: dec("0.001"),
+            min_size: dec("5"),
+        };
+        assert_eq!(rules.round_price(dec("0.4567"), true).unwrap(), dec("0.456"));
+        assert_eq!(rules.round_price(dec("0.4567"), false).unwrap(), dec("0.457"));
+        assert_eq!(rules.round_price(dec("0.45"), true).unwrap(), dec("0.45"));
+        assert!(rules.round_price(dec("0.9995"), false).is_err());
+        assert!(rules.round_price(dec("0.0005"), true).is_err());
+        assert_eq!(rules.round_size(dec("5.019")).unwrap(), dec("5.01"));
+        assert!(rules.round_size(dec("4.999")).is_err());
+    }
+}
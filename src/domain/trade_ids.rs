@@ -0,0 +1,53 @@
+/// Trade ids of one round, "<prefix>-<period start>-<sequence>". The sequence is the milliseconds since the
+/// period started, bumped past the last one handed out, so it only ever increases: an aborted or failed attempt
+/// never frees its id for the next one, and a round restarted within the same period can't reuse an id already in
+/// the instance's journal.
+#[derive(Debug, Clone)]
+pub struct TradeIds {
+    prefix: String,
+    period_start: i64,
+    last: Option<i64>,
+}
+
+impl TradeIds {
+    pub fn new(prefix: &str, period_start: i64) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            period_start,
+            last: None,
+        }
+    }
+
+    /// A fresh id at `now_ms` (Unix milliseconds).
+    pub fn next(&mut self, now_ms: i64) -> String {
+        let elapsed = (now_ms - self.period_start * 1000).max(0);
+        let seq = self.last.map_or(elapsed, |last| elapsed.max(last + 1));
+        self.last = Some(seq);
+        format!("{}-{}-{}", self.prefix, self.period_start, seq)
+    }
+}
+
+/// The Unix milliseconds an id from `TradeIds` was handed out at (a few ms late for an id bumped past the last).
+pub fn issued_at_ms(trade_id: &str) -> Option<i64> {
+    let mut parts = trade_id.rsplitn(3, '-');
+    let seq: i64 = parts.next()?.parse().ok()?;
+    let period_start: i64 = parts.next()?.parse().ok()?;
+    Some(period_start * 1000 + seq)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_never_repeat_even_within_a_millisecond() {
+        let mut ids = TradeIds::new("btc", 1_700_000_000);
+        let at = 1_700_000_000_000 + 1_500;
+        assert_eq!(ids.next(at), "btc-1700000000-1500");
+        assert_eq!(ids.next(at), "btc-1700000000-1501");
+        // A restarted round starts from the clock again, past anything the old one could have used so far.
+        assert_eq!(TradeIds::new("btc", 1_700_000_000).next(at + 2_000), "btc-1700000000-3500");
+        assert_eq!(issued_at_ms("btc-1700000000-3500"), Some(at + 2_000));
+        assert_eq!(issued_at_ms("btc-7"), None);
+    }
+}
@@ -0,0 +1,31 @@
+/// Realized volatility of spot `ticks` (feed timestamp ms, price; oldest first) from `since_ms` on: the square
+/// root of the summed squared log returns, in bps. None with fewer than two ticks in the window.
+pub fn realized_vol_bps(ticks: impl IntoIterator<Item = (i64, f64)>, since_ms: i64) -> Option<f64> {
+    let mut prev: Option<f64> = None;
+    let mut sum_sq = 0.0;
+    let mut returns = 0;
+    for (_, price) in ticks.into_iter().filter(|(ts, p)| *ts >= since_ms && *p > 0.0) {
+        if let Some(prev) = prev {
+            let r = (price / prev).ln();
+            sum_sq += r * r;
+            returns += 1;
+        }
+        prev = Some(price);
+    }
+    (returns > 0).then(|| sum_sq.sqrt() * 10_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn realized_vol_over_window() {
+        let ticks = vec![(0, 100.0), (1_000, 200.0), (2_000, 100.0), (3_000, 100.5), (4_000, 100.0)];
+        assert_eq!(realized_vol_bps(ticks.clone(), 5_000), None);
+        assert_eq!(realized_vol_bps(ticks.clone(), 4_000), None);
+        let calm = realized_vol_bps(ticks.clone(), 2_000).unwrap();
+        assert!((calm - 70.5).abs() < 0.1, "{}", calm);
+        assert!(realized_vol_bps(ticks, 0).unwrap() > 9_000.0);
+    }
+}
@@ -1,3 +1,161 @@
-pub use crate::utils::time_windows::{
-    current_15m_period_start, current_5m_period_start, is_last_5min_of_15m, period_start_et_unix_at,
-};
+pub use crate::utils::time_windows::{is_in_overlap, period_start_et_unix, period_start_et_unix_at};
+use crate::utils::clock::Clock;
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// The two up/down market periods traded against each other (e.g. 15m vs 5m, 1h vs 15m) and when the
+/// overlap window opens inside the long period. The short market traded is the one ending with the long one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeriodPair {
+    #[serde(default = "default_long_period_mins")]
+    pub long_period_mins: i64,
+    #[serde(default = "default_short_period_mins")]
+    pub short_period_mins: i64,
+    /// Minutes after the long period start when the overlap opens (defaults to the last short period).
+    #[serde(default = "default_overlap_start_offset_mins")]
+    pub overlap_start_offset_mins: i64,
+}
+
+impl Default for PeriodPair {
+    fn default() -> Self {
+        Self {
+            long_period_mins: default_long_period_mins(),
+            short_period_mins: default_short_period_mins(),
+            overlap_start_offset_mins: default_overlap_start_offset_mins(),
+        }
+    }
+}
+
+fn default_long_period_mins() -> i64 {
+    15
+}
+
+fn default_short_period_mins() -> i64 {
+    5
+}
+
+fn default_overlap_start_offset_mins() -> i64 {
+    10
+}
+
+impl PeriodPair {
+    pub fn long_secs(&self) -> i64 {
+        self.long_period_mins * 60
+    }
+
+    pub fn short_secs(&self) -> i64 {
+        self.short_period_mins * 60
+    }
+
+    /// (long, short) ET-aligned period starts at `clock`'s current time.
+    pub fn current_starts(&self, clock: &dyn Clock) -> (i64, i64) {
+        (
+            period_start_et_unix(clock, self.long_period_mins),
+            period_start_et_unix(clock, self.short_period_mins),
+        )
+    }
+
+    pub fn in_overlap(&self, now_ts: i64, long_start: i64) -> bool {
+        is_in_overlap(now_ts, long_start, self.overlap_start_offset_mins * 60, self.long_secs())
+    }
+
+    /// "15m", "1h", "4h", ... as used in logs and market slugs.
+    pub fn label(period_mins: i64) -> String {
+        if period_mins % 60 == 0 {
+            format!("{}h", period_mins / 60)
+        } else {
+            format!("{}m", period_mins)
+        }
+    }
+
+    pub fn long_label(&self) -> String {
+        Self::label(self.long_period_mins)
+    }
+
+    pub fn short_label(&self) -> String {
+        Self::label(self.short_period_mins)
+    }
+
+    /// Configuration errors: the short market must divide the long one and the overlap must fall in
+    /// the short market that closes with the long one, so both legs resolve at the same time.
+    pub fn issues(&self) -> Vec<String> {
+        let mut issues = Vec::new();
+        if self.short_period_mins <= 0 || self.long_period_mins <= self.short_period_mins {
+            issues.push(format!(
+                "periods: short_period_mins {} must be positive and below long_period_mins {}",
+                self.short_period_mins, self.long_period_mins
+            ));
+        } else if self.long_period_mins % self.short_period_mins != 0 {
+            issues.push(format!(
+                "periods: long_period_mins {} is not a multiple of short_period_mins {}",
+                self.long_period_mins, self.short_period_mins
+            ));
+        } else if MINUTES_PER_DAY % self.long_period_mins != 0 {
+            // Periods are counted from ET midnight, so they have to tile the day.
+            issues.push(format!(
+                "periods: long_period_mins {} does not divide the day into whole periods",
+                self.long_period_mins
+            ));
+        }
+        let last_short_start = self.long_period_mins - self.short_period_mins;
+        if self.overlap_start_offset_mins < last_short_start || self.overlap_start_offset_mins >= self.long_period_mins {
+            issues.push(format!(
+                "periods: overlap_start_offset_mins {} must be in [{}, {}) so both legs close together",
+                self.overlap_start_offset_mins, last_short_start, self.long_period_mins
+            ));
+        }
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hourly_vs_quarter_hour_overlap() {
+        let pair = PeriodPair {
+            long_period_mins: 60,
+            short_period_mins: 15,
+            overlap_start_offset_mins: 45,
+        };
+        assert!(pair.issues().is_empty());
+        assert_eq!(pair.long_label(), "1h");
+        assert_eq!(pair.short_label(), "15m");
+        let start = 1_700_000_000;
+        assert!(!pair.in_overlap(start + 45 * 60 - 1, start));
+        assert!(pair.in_overlap(start + 45 * 60, start));
+        assert!(!pair.in_overlap(start + 3600, start));
+    }
+
+    #[test]
+    fn rejects_overlap_outside_last_short_period() {
+        assert!(PeriodPair::default().issues().is_empty());
+        let pair = PeriodPair {
+            overlap_start_offset_mins: 5,
+            ..PeriodPair::default()
+        };
+        assert_eq!(pair.issues().len(), 1);
+    }
+
+    #[test]
+    fn four_hour_periods_start_on_the_et_four_hour_grid() {
+        let pair = PeriodPair {
+            long_period_mins: 240,
+            short_period_mins: 60,
+            overlap_start_offset_mins: 180,
+        };
+        assert!(pair.issues().is_empty());
+        assert_eq!(pair.long_label(), "4h");
+        // 2024-01-15 13:37 EST: in the 12:00-16:00 EST market and its 13:00 hourly one.
+        let clock = crate::utils::clock::ManualClock::new(1_705_343_820_000);
+        assert_eq!(pair.current_starts(&clock), (1_705_338_000, 1_705_341_600));
+        let uneven = PeriodPair {
+            long_period_mins: 420,
+            short_period_mins: 60,
+            overlap_start_offset_mins: 360,
+        };
+        assert_eq!(uneven.issues().len(), 1);
+    }
+}
@@ -0,0 +1,126 @@
+//! In-process event bus. The market WebSocket publishes quotes, the orchestrator publishes detected arbs, and
+//! every journaled lifecycle event (orders, resolutions, redemptions) is published as it is recorded, so
+//! dashboards, notifiers, and recorders subscribe here instead of being threaded through the services.
+
+use crate::models::{ArbSignal, JournalEntry};
+use futures_util::stream::{self, Stream};
+use log::warn;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+const EVENT_CHANNEL_CAPACITY: usize = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArbEvent {
+    /// Best bid/ask of a token changed on the market WebSocket.
+    Quote {
+        token_id: String,
+        bid: Option<f64>,
+        ask: Option<f64>,
+        ts_ms: i64,
+    },
+    /// An arb beat the threshold (published whether or not it is executed).
+    Opportunity(ArbSignal),
+    /// A trade lifecycle event, exactly as journaled: decisions, orders, fills, resolutions, redemptions.
+    Trade(JournalEntry),
+    /// Something an operator should look at, e.g. `loop_restart` when a symbol loop failed and is restarted.
+    Alert {
+        kind: String,
+        /// What it concerns, e.g. the symbol loop ("btc loop").
+        subject: String,
+        message: String,
+        ts_ms: i64,
+    },
+}
+
+/// Cloneable handle to one broadcast channel. Publishing never blocks; a subscriber that falls more than the
+/// channel capacity behind skips the oldest events.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<ArbEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self {
+            tx: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+        }
+    }
+
+    pub fn publish(&self, event: ArbEvent) {
+        // No subscribers is the normal case, not an error.
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> impl Stream<Item = ArbEvent> {
+        stream::unfold(self.tx.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(event) => return Some((event, rx)),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Event subscriber lagged; skipped {} event(s)", n)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::ws_market::{process_message, PricesSnapshot};
+    use crate::models::JournalEvent;
+    use crate::services::journal::Journal;
+    use dashmap::DashMap;
+    use futures_util::StreamExt;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn quotes_and_journaled_events_reach_every_subscriber_in_order() {
+        let events = EventBus::new();
+        events.publish(ArbEvent::Alert {
+            kind: "unheard".into(),
+            subject: String::new(),
+            message: String::new(),
+            ts_ms: 0,
+        });
+        let (mut first, mut second) = (Box::pin(events.subscribe()), Box::pin(events.subscribe()));
+
+        let prices: PricesSnapshot = Arc::new(DashMap::new());
+        let book = r#"{"event_type":"book","asset_id":"15up","bids":[{"price":"0.44","size":"10"}],
+            "asks":[{"price":"0.46","size":"20"}]}"#;
+        process_message(book, &prices, Some(&events)).unwrap();
+        // A disabled journal still publishes what it is given.
+        let journal = Journal::new("").with_events(events.clone());
+        let failed = JournalEvent::OrderFailed {
+            leg: 2,
+            error: "no match".into(),
+        };
+        journal.record(Some("btc-1767726000-1"), failed);
+
+        for subscriber in [&mut first, &mut second] {
+            match subscriber.next().await {
+                Some(ArbEvent::Quote { token_id, bid, ask, .. }) => {
+                    assert_eq!((token_id.as_str(), bid, ask), ("15up", Some(0.44), Some(0.46)))
+                }
+                other => panic!("expected the quote, got {:?}", other),
+            }
+            match subscriber.next().await {
+                Some(ArbEvent::Trade(entry)) => {
+                    assert_eq!(entry.trade_id.as_deref(), Some("btc-1767726000-1"));
+                    assert!(matches!(entry.event, JournalEvent::OrderFailed { leg: 2, .. }));
+                }
+                other => panic!("expected the journaled event, got {:?}", other),
+            }
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! Polymarket up/down arbitrage bot as a library. `ArbBot` embeds the overlap strategy; the modules below are
+//! what the `polymarket-arbitrage-bot` binary is built from.
+
+pub mod adapters;
+pub mod api;
+pub mod bot;
+pub mod config;
+pub mod domain;
+pub mod events;
+pub mod models;
+pub mod services;
+pub mod utils;
+
+pub use bot::{ArbBot, ArbBotBuilder, BotHandle};
@@ -1,62 +1,197 @@
-mod adapters;
-mod domain;
-mod services;
-mod utils;
-mod models;
-mod config;
-mod api;
-
 use anyhow::Result;
 use clap::Parser;
-use config::{Args, Config};
+use futures_util::StreamExt;
+use polymarket_arbitrage_bot::bot::{connect, dangerous_settings, ensure_approvals};
+use polymarket_arbitrage_bot::config::{self, Args, Command, Config, RunMode, SkipAction, StrategyKind};
+use polymarket_arbitrage_bot::{services, utils, ArbBot};
 use std::io::Write;
 use std::sync::Arc;
-use adapters::polymarket::PolymarketApi;
-use services::arbitrage_orchestrator::ArbStrategy;
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::models::{BalanceAsset, TradeHistoryParams};
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     env_logger::Builder::from_default_env()
         .filter_level(log::LevelFilter::Info)
         .format(|buf, record| writeln!(buf, "{}", record.args()))
         .init();
+    utils::supervisor::install_panic_hook();
 
     let args = Args::parse();
-    let config = Config::load(&args.config)?;
+    // Before Config::load, which writes a default config when the file is missing.
+    if let Some(Command::Init) = &args.command {
+        return runtime()?.block_on(services::setup_wizard::run(&args.config));
+    }
+    // A silently written default config has no key and fails authentication; point new users at the wizard.
+    if !args.config.exists() {
+        anyhow::bail!(
+            "{} not found. Create it with `polymarket-arbitrage-bot init`, or pass -c <path>.",
+            args.config.display()
+        );
+    }
+    let mut config = Config::load_profile(&args.config, args.profile.as_deref())?;
+    if let Some(Command::CompleteSet) = &args.command {
+        config.strategy.kind = StrategyKind::CompleteSet;
+    }
+    utils::proxy::install(&config.proxy)?;
+    // Still single-threaded: nothing else can be reading the environment yet.
+    utils::proxy::export_env();
+    runtime()?.block_on(run(args, config))
+}
+
+fn runtime() -> Result<tokio::runtime::Runtime> {
+    Ok(tokio::runtime::Builder::new_multi_thread().enable_all().build()?)
+}
+
+async fn run(args: Args, config: Config) -> Result<()> {
+    if let Some(Command::Lifecycle {
+        trade_id,
+        journal,
+        output,
+    }) = &args.command
+    {
+        let journal_path = journal
+            .clone()
+            .unwrap_or_else(|| config.strategy.journal_path.clone().into());
+        return export_lifecycle(&journal_path, trade_id, output.as_deref());
+    }
+    if let Some(Command::RedeemQueue { queue }) = &args.command {
+        let queue_path = queue
+            .clone()
+            .unwrap_or_else(|| config.strategy.redeem_queue_path.clone().into());
+        return print_redeem_queue(&queue_path);
+    }
+    if let Some(Command::Skip { action }) = &args.command {
+        return manage_skip_list(&config, action);
+    }
+    if let Some(Command::Equity { snapshots, csv }) = &args.command {
+        let path = snapshots
+            .clone()
+            .unwrap_or_else(|| config.strategy.portfolio_snapshot_path.clone().into());
+        return print_equity_curve(&path, *csv);
+    }
+    if let Some(dir) = &args.replay {
+        return replay_recording(config, dir, args.replay_speed).await;
+    }
 
     eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    eprintln!("📋 15m vs 5m arbitrage (BTC, ETH, SOL, XRP) — overlap, per-symbol tolerance");
+    let periods = config.strategy.periods;
+    eprintln!(
+        "📋 {} vs {} arbitrage ({}) — overlap, per-symbol tolerance",
+        periods.long_label(),
+        periods.short_label(),
+        config.strategy.symbols.join(", ").to_uppercase()
+    );
     eprintln!("   All symbols via parallel WebSocket; arb when sum < threshold & |P15−P5| ≤ tolerance");
+    if let Some(profile) = &config.profile {
+        eprintln!(
+            "   Profile: {} ({})",
+            profile,
+            if config.strategy.simulation_mode { "simulation" } else { "LIVE trading" }
+        );
+    }
     eprintln!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-    let api = Arc::new(PolymarketApi::new(
-        config.polymarket.gamma_api_url.clone(),
-        config.polymarket.clob_api_url.clone(),
-        config.polymarket.api_key.clone(),
-        config.polymarket.api_secret.clone(),
-        config.polymarket.api_passphrase.clone(),
-        config.polymarket.private_key.clone(),
-        config.polymarket.proxy_wallet_address.clone(),
-        config.polymarket.signature_type,
-        config.polymarket.rpc_url.clone(),
-    ));
+    let api = Arc::new(PolymarketApi::from_config(&config.polymarket));
+
+    if let Some(Command::Approve { dry_run }) = &args.command {
+        return ensure_approvals(api.as_ref(), !dry_run).await;
+    }
+    if let Some(Command::CreateApiKey { nonce, print }) = &args.command {
+        return create_api_key(api.as_ref(), &args.config, args.profile.as_deref(), *nonce, *print).await;
+    }
+    if let Some(Command::Doctor) = &args.command {
+        return run_doctor(api.as_ref(), &config).await;
+    }
+    if let Some(Command::Fills { limit, market }) = &args.command {
+        return print_fills(api.as_ref(), *limit, market.as_deref()).await;
+    }
+    if let Some(Command::DownloadHistory {
+        output,
+        symbols,
+        periods,
+        hours,
+        candle_mins,
+    }) = &args.command
+    {
+        let symbols = if symbols.is_empty() { &config.strategy.symbols } else { symbols };
+        let strategy_periods = config.strategy.periods;
+        let periods = if periods.is_empty() {
+            vec![strategy_periods.long_period_mins, strategy_periods.short_period_mins]
+        } else {
+            periods.clone()
+        };
+        let candles = services::history_downloader::download_history(
+            api.as_ref(),
+            symbols,
+            &periods,
+            *hours,
+            *candle_mins,
+            output,
+        )
+        .await?;
+        eprintln!("Wrote {} candle(s) to {}", candles, output.display());
+        return Ok(());
+    }
+    if let Some(Command::Research { output, interval_ms }) = &args.command {
+        return services::research_recorder::run_research(api, config, output, *interval_ms).await;
+    }
 
     if args.redeem {
         run_redeem_only(api.as_ref(), &config, args.condition_id.as_deref()).await?;
         return Ok(());
     }
+    if args.mode == RunMode::Redeemer {
+        if config.wallets.is_empty() {
+            return services::redemption_service::run_redeemer(api, config).await;
+        }
+        let redeemers = config.wallet_configs()?.into_iter().map(|(_, wallet)| {
+            let api = Arc::new(PolymarketApi::from_config(&wallet.polymarket));
+            services::redemption_service::run_redeemer(api, wallet)
+        });
+        futures_util::future::try_join_all(redeemers).await?;
+        return Ok(());
+    }
 
-    if config.polymarket.private_key.is_some() {
-        if let Err(e) = api.authenticate().await {
-            log::error!("Authentication failed: {}", e);
-            anyhow::bail!("Authentication failed. Please check your credentials.");
+    let issues = dangerous_settings(&config.strategy_instances()?);
+    if !issues.is_empty() {
+        for issue in &issues {
+            eprintln!("⚠️ Config check: {}", issue);
         }
-    } else {
-        log::warn!("⚠️ No private key provided. Bot can only monitor (no orders).");
+        if !args.i_know_what_im_doing {
+            anyhow::bail!(
+                "Refusing to start with {} dangerous setting(s) in {}. Fix the config or pass --i-know-what-im-doing.",
+                issues.len(),
+                args.config.display()
+            );
+        }
+        log::warn!("Starting anyway (--i-know-what-im-doing).");
+    }
+
+    connect(api.as_ref(), &config).await?;
+
+    if let Some(Command::CrossVenue) = &args.command {
+        return services::cross_venue::run_cross_venue(api, config).await;
     }
 
-    let strategy = ArbStrategy::new(api, config);
-    strategy.run().await
+    let bot = ArbBot::builder()
+        .config(config)
+        .api(api)
+        // Checked above, where --i-know-what-im-doing can override it.
+        .allow_dangerous_settings(true)
+        .build()?;
+    bot.connect_wallets().await?;
+    for strategy in bot.strategies().iter().filter(|s| s.config().strategy.signals_only) {
+        // One JSON object per line on stdout, for piping into an external executor.
+        let mut signals = Box::pin(strategy.signals());
+        tokio::spawn(async move {
+            while let Some(signal) = signals.next().await {
+                if let Ok(line) = serde_json::to_string(&signal) {
+                    println!("{}", line);
+                }
+            }
+        });
+    }
+    bot.start().wait_until_ctrl_c().await
 }
 
 async fn run_redeem_only(
@@ -71,7 +206,7 @@ async fn run_redeem_only(
         .ok_or_else(|| anyhow::anyhow!("--redeem requires proxy_wallet_address in config.json"))?;
 
     eprintln!("Redeem-only mode (proxy: {})", proxy);
-    let cids: Vec<String> = if let Some(cid) = condition_id {
+    let mut cids: Vec<String> = if let Some(cid) = condition_id {
         let cid = if cid.starts_with("0x") {
             cid.to_string()
         } else {
@@ -92,6 +227,27 @@ async fn run_redeem_only(
 
     let mut ok_count = 0u32;
     let mut fail_count = 0u32;
+    if cids.len() > 1 {
+        let targets: Vec<(String, String)> =
+            cids.iter().map(|cid| (cid.clone(), "Up".to_string())).collect();
+        eprintln!("\n--- Redeeming {} conditions in one transaction ---", cids.len());
+        match api.redeem_tokens_batch(&targets).await {
+            Ok(results) => {
+                let mut failed = Vec::new();
+                for (cid, result) in cids.iter().zip(results) {
+                    match result {
+                        Ok(_) => ok_count += 1,
+                        Err(e) => {
+                            eprintln!("Batch redeem of {} failed: {} (retrying it alone)", cid, e);
+                            failed.push(cid.clone());
+                        }
+                    }
+                }
+                cids = failed;
+            }
+            Err(e) => eprintln!("Batch redeem failed: {} (redeeming one by one)", e),
+        }
+    }
     for cid in &cids {
         eprintln!("\n--- Redeeming condition {} ---", &cid[..cid.len().min(18)]);
         match api.redeem_tokens(cid, "", "Up").await {
@@ -111,3 +267,291 @@ async fn run_redeem_only(
     );
     Ok(())
 }
+
+fn print_redeem_queue(queue_path: &std::path::Path) -> Result<()> {
+    if !queue_path.exists() {
+        println!("No redemptions queued ({} does not exist).", queue_path.display());
+        return Ok(());
+    }
+    let items = services::redeem_queue::read_queue(queue_path)?;
+    if items.is_empty() {
+        println!("No redemptions queued.");
+        return Ok(());
+    }
+    for item in &items {
+        let status = if item.permanently_failed {
+            "FAILED".to_string()
+        } else if item.deferred {
+            "deferred to next batch".to_string()
+        } else {
+            let next = chrono::DateTime::from_timestamp(item.next_attempt_unix, 0)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default();
+            format!("retry at {}", next)
+        };
+        println!(
+            "{} {} | attempts {} | {} | last error: {}",
+            item.condition_id, item.outcome, item.attempts, status, item.last_error
+        );
+    }
+    let failed = items.iter().filter(|i| i.permanently_failed).count();
+    if failed > 0 {
+        println!(
+            "{} permanently failed; redeem with --redeem --condition-id <id>.",
+            failed
+        );
+    }
+    Ok(())
+}
+
+async fn print_fills(api: &PolymarketApi, limit: usize, market: Option<&str>) -> Result<()> {
+    let wallet = api
+        .wallet_address()
+        .ok_or_else(|| anyhow::anyhow!("fills needs proxy_wallet_address or private_key in config.json"))?;
+    let params = TradeHistoryParams {
+        user: wallet.clone(),
+        markets: market.map(|m| vec![m.to_string()]).unwrap_or_default(),
+        taker_only: false,
+        limit,
+    };
+    let fills = api.get_trade_history(&params).await?;
+    if fills.is_empty() {
+        println!("No fills for {}.", wallet);
+        return Ok(());
+    }
+    for f in &fills {
+        let at = chrono::DateTime::from_timestamp(f.timestamp as i64, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        let token = f.token_id.as_deref().unwrap_or("");
+        println!(
+            "{} | {} {:.2} {} @ {:.4} | {:.2} USDC | token {} | market {}",
+            at,
+            f.side,
+            f.size,
+            f.outcome.as_deref().unwrap_or("?"),
+            f.price,
+            f.size * f.price,
+            &token[..token.len().min(16)],
+            f.condition_id.as_deref().unwrap_or("?")
+        );
+    }
+    Ok(())
+}
+
+/// Print each connectivity check and the account's resting orders; an order the journal has no ack for was
+/// placed by hand or by another process.
+async fn run_doctor(api: &PolymarketApi, config: &Config) -> Result<()> {
+    match api.sync_clock().await {
+        Ok(offset_ms) => println!("✅ Clock offset from CLOB: {} ms", offset_ms),
+        Err(e) => println!("⚠️  Clock sync failed: {}", e),
+    }
+    if !api.has_signer() {
+        println!("⚠️  No private key or signer: monitoring only");
+        return Ok(());
+    }
+    println!("✅ Signer {}", api.signer_address()?);
+    if let Err(e) = api.authenticate().await {
+        println!("❌ CLOB authentication failed: {}", e);
+        return Ok(());
+    }
+    println!("✅ CLOB authentication");
+    match api.get_balance_allowance(BalanceAsset::Collateral, None).await {
+        Ok(b) => println!("✅ USDC balance {:.2}, allowance {:.2}", b.balance, b.allowance),
+        Err(e) => println!("⚠️  USDC balance/allowance: {}", e),
+    }
+    let orders = match api.get_open_orders(None).await {
+        Ok(orders) => orders,
+        Err(e) => {
+            println!("⚠️  Resting orders: {}", e);
+            return Ok(());
+        }
+    };
+    let journal = std::path::Path::new(config.strategy.journal_path.trim());
+    let placed = if journal.is_file() {
+        services::journal::read_placed_order_ids(journal)?
+    } else {
+        Default::default()
+    };
+    println!("{} resting order(s)", orders.len());
+    for o in &orders {
+        println!(
+            "  {} {} {} @ {} | market {} | {} | {}",
+            o.side,
+            o.original_size,
+            o.outcome,
+            o.price,
+            o.market,
+            o.id,
+            if placed.contains(&o.id) { "placed by this bot" } else { "NOT in the journal" }
+        );
+    }
+    Ok(())
+}
+
+/// Create or derive the CLOB API credentials and print them as environment variables, or write them into
+/// `polymarket` of the config file (of profile `profile` when given), leaving the rest of the file as is.
+async fn create_api_key(
+    api: &PolymarketApi,
+    config_path: &std::path::Path,
+    profile: Option<&str>,
+    nonce: u64,
+    print: bool,
+) -> Result<()> {
+    if let Err(e) = api.sync_clock().await {
+        eprintln!("⚠️  Clock sync failed ({}); signing with local time", e);
+    }
+    let credentials = api.create_or_derive_api_key(nonce).await?;
+    eprintln!("API key {} for {} (nonce {})", credentials.api_key, api.signer_address()?, nonce);
+    if print {
+        println!("{}={}", config::ENV_API_KEY, credentials.api_key);
+        println!("{}={}", config::ENV_API_SECRET, credentials.secret.expose());
+        println!("{}={}", config::ENV_API_PASSPHRASE, credentials.passphrase.expose());
+        println!("{}={}", config::ENV_API_KEY_NONCE, nonce);
+        return Ok(());
+    }
+    let mut raw: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(config_path)?)?;
+    let target = match profile {
+        Some(name) => &mut raw["profiles"][name]["polymarket"],
+        None => &mut raw["polymarket"],
+    };
+    if target.is_null() {
+        *target = serde_json::json!({});
+    }
+    let polymarket = target
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("polymarket in {} is not an object", config_path.display()))?;
+    polymarket.insert("api_key".to_string(), credentials.api_key.into());
+    polymarket.insert("api_secret".to_string(), credentials.secret.expose().into());
+    polymarket.insert("api_passphrase".to_string(), credentials.passphrase.expose().into());
+    polymarket.insert("api_key_nonce".to_string(), nonce.into());
+    std::fs::write(config_path, serde_json::to_string_pretty(&raw)? + "\n")?;
+    eprintln!("Wrote api_key, api_secret, api_passphrase, and api_key_nonce to {}", config_path.display());
+    Ok(())
+}
+
+fn print_equity_curve(path: &std::path::Path, csv: bool) -> Result<()> {
+    if !path.exists() {
+        println!("No portfolio snapshots yet ({} does not exist).", path.display());
+        return Ok(());
+    }
+    let curve = services::portfolio::read_equity_curve(path)?;
+    let opt = |v: Option<f64>| v.map(|v| format!("{:.2}", v)).unwrap_or_default();
+    if csv {
+        println!("ts,cash_usdc,open_cost,marked_value,realized_pnl,equity");
+    }
+    for s in &curve {
+        if csv {
+            println!(
+                "{},{},{:.2},{:.2},{:.2},{}",
+                s.ts, opt(s.cash_usdc), s.open_cost, s.marked_value, s.realized_pnl, opt(s.equity())
+            );
+            continue;
+        }
+        let at = chrono::DateTime::from_timestamp(s.ts, 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        println!(
+            "{} | equity {} | cash {} | open cost {:.2} | marked {:.2} | realized {:.2}",
+            at, opt(s.equity()), opt(s.cash_usdc), s.open_cost, s.marked_value, s.realized_pnl
+        );
+    }
+    Ok(())
+}
+
+async fn replay_recording(mut config: Config, dir: &std::path::Path, speed: f64) -> Result<()> {
+    if !config.strategy.simulation_mode {
+        config.strategy.simulation_mode = true;
+        config.strategy.separate_sim_state();
+    }
+    let messages = polymarket_arbitrage_bot::adapters::polymarket::ws_recorder::read_recordings(dir)?;
+    let strategy = services::strategy::build(config.strategy.kind);
+    let report = services::replay::run_replay(&config, strategy.as_ref(), &messages, speed).await?;
+    for t in &report.trades {
+        let at = chrono::DateTime::from_timestamp_millis(t.decided_at_ms)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default();
+        let result = match (&t.outcomes, t.pnl) {
+            (Some((outcome_15, outcome_5)), Some(pnl)) => format!("{}/{} -> PnL {:.2}", outcome_15, outcome_5, pnl),
+            _ => "unresolved in the recording".to_string(),
+        };
+        println!(
+            "{} | {} | {} @ {:.4} + {} @ {:.4} x {} | {}",
+            at,
+            t.trade.trade_id,
+            t.trade.leg1_outcome,
+            t.trade.leg1_price,
+            t.trade.leg2_outcome,
+            t.trade.leg2_price,
+            t.trade.size,
+            result
+        );
+    }
+    println!(
+        "Replayed {} message(s), {} overlap(s): {} trade(s), PnL {:.2}",
+        report.messages,
+        report.rounds,
+        report.trades.len(),
+        report.pnl
+    );
+    Ok(())
+}
+
+fn manage_skip_list(config: &Config, action: &SkipAction) -> Result<()> {
+    let skip_list = services::skip_list::SkipList::load(
+        &config.strategy.skip_list_path,
+        config.strategy.skip_after_anomalies,
+    );
+    match action {
+        SkipAction::Add { key, reason } => {
+            if skip_list.add(key, reason) {
+                println!("Skipping {} ({}).", key, reason);
+            } else {
+                println!("{} is already skipped.", key);
+            }
+        }
+        SkipAction::Remove { key } => {
+            if skip_list.remove(key) {
+                println!("{} will be traded again.", key);
+            } else {
+                println!("{} was not skipped.", key);
+            }
+        }
+        SkipAction::List => {
+            let state = skip_list.snapshot();
+            if state.skipped.is_empty() {
+                println!("No markets skipped.");
+            }
+            for market in &state.skipped {
+                let added = chrono::DateTime::from_timestamp(market.added_unix, 0)
+                    .map(|t| t.to_rfc3339())
+                    .unwrap_or_default();
+                let how = if market.automatic { "auto" } else { "manual" };
+                println!("{} | {} | {} | {}", market.key, how, added, market.reason);
+            }
+            for (key, count) in &state.anomalies {
+                println!("{} | {} anomal{} (not skipped yet)", key, count, if *count == 1 { "y" } else { "ies" });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn export_lifecycle(
+    journal_path: &std::path::Path,
+    trade_id: &str,
+    output: Option<&std::path::Path>,
+) -> Result<()> {
+    let entries = services::journal::read_journal(journal_path)?;
+    let diagram = utils::lifecycle::render_lifecycle_mermaid(&entries, trade_id).ok_or_else(|| {
+        anyhow::anyhow!("Trade {} not found in {}", trade_id, journal_path.display())
+    })?;
+    match output {
+        Some(path) => {
+            std::fs::write(path, &diagram)?;
+            eprintln!("Wrote lifecycle of {} to {}", trade_id, path.display());
+        }
+        None => print!("{}", diagram),
+    }
+    Ok(())
+}
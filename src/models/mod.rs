@@ -1,5 +1,9 @@
+use crate::domain::pricing::ModelEstimate;
+use crate::utils::secret::Secret;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Market {
@@ -39,6 +43,10 @@ pub struct MarketToken {
 pub struct OrderBook {
     pub bids: Vec<OrderBookEntry>,
     pub asks: Vec<OrderBookEntry>,
+    #[serde(default)]
+    pub tick_size: Option<Decimal>,
+    #[serde(default)]
+    pub min_order_size: Option<Decimal>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,14 +55,171 @@ pub struct OrderBookEntry {
     pub size: Decimal,
 }
 
+/// Order side, serialized the way the CLOB spells it; any case parses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Side {
+    #[default]
+    #[serde(alias = "buy", alias = "Buy")]
+    Buy,
+    #[serde(alias = "sell", alias = "Sell")]
+    Sell,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Side {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "BUY" => Ok(Side::Buy),
+            "SELL" => Ok(Side::Sell),
+            _ => anyhow::bail!("Invalid order side: {}. Must be BUY or SELL", s),
+        }
+    }
+}
+
+/// CLOB time in force: GTC rests, GTD rests until its expiration, FOK fills in full or not at all, FAK fills
+/// what it can at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrderType {
+    #[default]
+    #[serde(alias = "gtc")]
+    Gtc,
+    #[serde(alias = "gtd")]
+    Gtd,
+    #[serde(alias = "fok")]
+    Fok,
+    #[serde(alias = "fak")]
+    Fak,
+}
+
+impl OrderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Gtc => "GTC",
+            OrderType::Gtd => "GTD",
+            OrderType::Fok => "FOK",
+            OrderType::Fak => "FAK",
+        }
+    }
+
+    /// GTC and GTD orders can rest on the book; FOK and FAK never do.
+    pub fn rests(&self) -> bool {
+        matches!(self, OrderType::Gtc | OrderType::Gtd)
+    }
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OrderType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "GTC" => Ok(OrderType::Gtc),
+            "GTD" => Ok(OrderType::Gtd),
+            "FOK" => Ok(OrderType::Fok),
+            "FAK" => Ok(OrderType::Fak),
+            _ => anyhow::bail!("Invalid order type: {}. Must be GTC, FOK, FAK, or GTD", s),
+        }
+    }
+}
+
+/// Side of an up/down market, as Polymarket names its outcomes; any case parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Outcome {
+    #[serde(alias = "UP", alias = "up")]
+    Up,
+    #[serde(alias = "DOWN", alias = "down")]
+    Down,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Up => "Up",
+            Outcome::Down => "Down",
+        }
+    }
+
+    pub fn opposite(&self) -> Outcome {
+        match self {
+            Outcome::Up => Outcome::Down,
+            Outcome::Down => Outcome::Up,
+        }
+    }
+
+    /// Whether a venue's outcome name (e.g. a trade's leg outcome) names this outcome.
+    pub fn is(&self, name: &str) -> bool {
+        name.eq_ignore_ascii_case(self.as_str())
+    }
+
+    /// Outcome of a CLOB token label: anything mentioning up or down, or the "1" / "0" some markets use.
+    pub fn from_token_label(label: &str) -> Option<Outcome> {
+        let label = label.to_uppercase();
+        if label.contains("UP") || label == "1" {
+            Some(Outcome::Up)
+        } else if label.contains("DOWN") || label == "0" {
+            Some(Outcome::Down)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "up" => Ok(Outcome::Up),
+            "down" => Ok(Outcome::Down),
+            _ => anyhow::bail!("Invalid outcome: {}. Must be Up or Down", s),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderRequest {
     pub token_id: String,
-    pub side: String,
+    pub side: Side,
     pub size: String,
     pub price: String,
     #[serde(rename = "type")]
-    pub order_type: String,
+    pub order_type: OrderType,
+    /// Unix seconds after which a GTD order expires; None for other order types.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration: Option<String>,
+    /// Locally generated id tying the order to its journal entries. Never sent: the CLOB has no client id, so
+    /// an order whose post failed ambiguously is looked up by its fields instead.
+    #[serde(default, skip_serializing)]
+    pub client_order_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +227,131 @@ pub struct OrderResponse {
     pub order_id: Option<String>,
     pub status: String,
     pub message: Option<String>,
+    /// When the order was started, signed, and acknowledged; None from venues that don't measure it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<OrderLatency>,
+}
+
+/// Unix milliseconds at each step of placing one order.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrderLatency {
+    /// Entered `place_order`: rounding, order building, and signing start here.
+    pub started_at_ms: i64,
+    /// Signed and about to be posted.
+    pub signed_at_ms: i64,
+    /// The CLOB's acknowledgement arrived.
+    pub acked_at_ms: i64,
+}
+
+/// `OrderLatency` of one arb leg.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LegLatency {
+    pub leg: u8,
+    #[serde(flatten)]
+    pub order: OrderLatency,
+}
+
+/// A resting order from the CLOB `/data/orders` listing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OpenOrder {
+    pub id: String,
+    #[serde(default)]
+    pub status: String,
+    /// Condition id.
+    #[serde(default)]
+    pub market: String,
+    #[serde(default)]
+    pub asset_id: String,
+    #[serde(default)]
+    pub side: Side,
+    #[serde(default)]
+    pub outcome: String,
+    #[serde(default)]
+    pub price: String,
+    #[serde(default)]
+    pub original_size: String,
+    #[serde(default)]
+    pub size_matched: String,
+    #[serde(default)]
+    pub order_type: OrderType,
+    /// Unix seconds.
+    #[serde(default)]
+    pub created_at: Option<i64>,
+}
+
+/// A trade of this account from the CLOB `/data/trades` listing. Listed once matched, well before the data API
+/// sees it settle on chain. `side`, `size`, and `price` are the taker order's.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ClobTrade {
+    pub id: String,
+    #[serde(default)]
+    pub taker_order_id: String,
+    #[serde(default)]
+    pub asset_id: String,
+    #[serde(default)]
+    pub side: Side,
+    #[serde(default)]
+    pub size: String,
+    #[serde(default)]
+    pub price: String,
+    /// MATCHED, MINED, CONFIRMED, RETRYING, or FAILED.
+    #[serde(default)]
+    pub status: String,
+    /// Unix seconds, as a string.
+    #[serde(default)]
+    pub match_time: String,
+    /// TAKER when the order of this account was `taker_order_id`, MAKER when one of its resting orders was hit.
+    #[serde(default)]
+    pub trader_side: String,
+}
+
+/// Result of a batch cancel: the ids cancelled, and the reason for each one that wasn't.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CancelOutcome {
+    #[serde(default)]
+    pub canceled: Vec<String>,
+    #[serde(default)]
+    pub not_canceled: std::collections::HashMap<String, String>,
+}
+
+/// CLOB L2 API credentials, as returned by `/auth/api-key` and `/auth/derive-api-key`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiCredentials {
+    #[serde(rename = "apiKey")]
+    pub api_key: String,
+    pub secret: Secret,
+    pub passphrase: Secret,
+}
+
+/// Asset of a CLOB `/balance-allowance` query: USDC collateral or one outcome token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceAsset {
+    Collateral,
+    Conditional,
+}
+
+impl BalanceAsset {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BalanceAsset::Collateral => "COLLATERAL",
+            BalanceAsset::Conditional => "CONDITIONAL",
+        }
+    }
+}
+
+/// Balance of the trading wallet (proxy wallet if configured) as the CLOB sees it, with the smallest
+/// allowance it has granted the exchange contracts. USDC for collateral, shares for outcome tokens.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BalanceAllowance {
+    pub balance: f64,
+    pub allowance: f64,
+}
+
+impl BalanceAllowance {
+    /// What an order can actually spend: the balance, capped by the allowance.
+    pub fn spendable(&self) -> f64 {
+        self.balance.min(self.allowance)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +372,26 @@ pub struct RedeemResponse {
     pub amount_redeemed: Option<String>,
 }
 
+/// One point of the CLOB `/prices-history` series.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PricePoint {
+    /// Unix seconds.
+    pub t: i64,
+    pub p: f64,
+}
+
+/// CLOB `/midpoint` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Midpoint {
+    pub mid: Decimal,
+}
+
+/// CLOB `/spread` response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spread {
+    pub spread: Decimal,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenPrice {
     pub token_id: String,
@@ -89,9 +399,47 @@ pub struct TokenPrice {
     pub ask: Option<Decimal>,
 }
 
+/// Detected arb opportunity, published to signal subscribers before (or instead of) execution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArbSignal {
+    pub symbol: String,
+    pub period_15: i64,
+    pub period_5: i64,
+    pub cid_15: String,
+    pub cid_5: String,
+    /// 15m leg.
+    pub leg1: SignalLeg,
+    /// 5m leg (opposite side).
+    pub leg2: SignalLeg,
+    /// Cost per share pair including fees.
+    pub effective_sum: f64,
+    /// Threshold minus effective sum: expected profit per share pair before slippage.
+    pub edge: f64,
+    pub detected_at_ms: i64,
+    /// Fair-value model inputs and outputs, when `pricing_model` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<ModelEstimate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalLeg {
+    pub token_id: String,
+    pub outcome: String,
+    pub ask: f64,
+    /// Fee per share (USDC) at `ask`.
+    pub fee: f64,
+    /// Midpoint and bid/ask spread of the token's book when the signal fired; None while its bid is unknown.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mid: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spread: Option<f64>,
+}
+
 /// Record of an arb trade for PnL tracking and redeem.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeRecord {
+    /// Local id, e.g. "btc-1700000000-61250" (symbol, 15m period, and a sequence from `domain::trade_ids::TradeIds`).
+    pub trade_id: String,
     pub symbol: String,
     pub period_15: i64,
     pub period_5: i64,
@@ -99,25 +447,228 @@ pub struct TradeRecord {
     pub cid_5: String,
     /// Leg 1: token_id, price, condition_id (15m or 5m), outcome "Up" or "Down"
     pub leg1_token: String,
-    pub leg1_price: f64,
+    pub leg1_price: Decimal,
     pub leg1_cid: String,
     pub leg1_outcome: String,
     /// Leg 2
     pub leg2_token: String,
-    pub leg2_price: f64,
+    pub leg2_price: Decimal,
     pub leg2_cid: String,
     pub leg2_outcome: String,
-    pub size: f64,
+    pub size: Decimal,
+    /// Fee per share (USDC) paid on each leg.
+    pub leg1_fee: Decimal,
+    pub leg2_fee: Decimal,
+    /// The one leg (1 or 2) held when the other never filled or was sold early; None for a full pair.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub only_leg: Option<u8>,
+}
+
+impl TradeRecord {
+    /// Leg 2 was bought on the long market next to leg 1 (a complete set) rather than on the short market.
+    pub fn leg2_long(&self) -> bool {
+        self.leg2_cid == self.cid_15
+    }
+
+    /// Whether `size` shares of leg `leg` (1 or 2) are held.
+    pub fn holds_leg(&self, leg: u8) -> bool {
+        self.only_leg.is_none_or(|only| only == leg)
+    }
+
+    /// This trade holding leg `leg` alone, `size` shares of it.
+    pub fn one_sided(&self, trade_id: &str, leg: u8, size: Decimal) -> Self {
+        Self {
+            trade_id: trade_id.to_string(),
+            size,
+            only_leg: Some(leg),
+            ..self.clone()
+        }
+    }
+
+    /// What this pair leaves held when its legs matched only `filled` shares each: as many pairs as both legs
+    /// filled under this trade's id, and the fuller leg's excess on its own, under "<trade id>-leg<n>" next to a
+    /// pair and under this trade's id otherwise. Empty when neither leg filled.
+    pub fn matched(&self, filled: [Decimal; 2]) -> Vec<Self> {
+        let [filled1, filled2] = filled;
+        let mut trades = Vec::new();
+        let pairs = filled1.min(filled2);
+        if pairs > Decimal::ZERO {
+            trades.push(Self {
+                size: pairs,
+                only_leg: None,
+                ..self.clone()
+            });
+        }
+        let (leg, excess) = if filled1 > filled2 { (1, filled1 - filled2) } else { (2, filled2 - filled1) };
+        if excess > Decimal::ZERO {
+            let trade_id = match trades.is_empty() {
+                true => self.trade_id.clone(),
+                false => format!("{}-leg{}", self.trade_id, leg),
+            };
+            trades.push(self.one_sided(&trade_id, leg, excess));
+        }
+        trades
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fill {
-    #[serde(rename = "tokenID")]
+    #[serde(rename = "tokenID", alias = "asset")]
     pub token_id: Option<String>,
-    pub side: String,
+    pub side: Side,
     pub size: f64,
     pub price: f64,
     pub timestamp: u64,
     #[serde(rename = "conditionId")]
     pub condition_id: Option<String>,
+    /// Outcome name ("Up"/"Down"); reported by the activity feed, not by /trades.
+    #[serde(default)]
+    pub outcome: Option<String>,
+    /// Settlement transaction; tells apart fills that match in every other field.
+    #[serde(rename = "transactionHash", default)]
+    pub transaction_hash: Option<String>,
+}
+
+/// Filters for `PolymarketApi::get_trade_history`.
+#[derive(Debug, Clone, Default)]
+pub struct TradeHistoryParams {
+    /// Wallet whose trades to list.
+    pub user: String,
+    /// Condition ids; empty for every market.
+    pub markets: Vec<String>,
+    /// Only trades where the wallet was the taker.
+    pub taker_only: bool,
+    /// Stop after this many fills, newest first (0 = all of them).
+    pub limit: usize,
+}
+
+/// One line of the trade journal (JSONL).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// Unix milliseconds.
+    pub ts_ms: i64,
+    /// Trade the event belongs to; None for market-level events (e.g. redemption of a condition).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_id: Option<String>,
+    pub event: JournalEvent,
+}
+
+/// Lifecycle events: decision -> orders -> fills -> resolution -> redemption.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalEvent {
+    Decision {
+        symbol: String,
+        period_15: i64,
+        period_5: i64,
+        cid_15: String,
+        cid_5: String,
+        leg1_outcome: String,
+        leg1_price: f64,
+        leg2_outcome: String,
+        leg2_price: f64,
+        effective_sum: f64,
+        threshold: f64,
+        simulated: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<ModelEstimate>,
+    },
+    OrderSubmitted {
+        leg: u8,
+        token_id: String,
+        side: Side,
+        price: String,
+        size: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        client_order_id: Option<String>,
+    },
+    OrderAcked {
+        leg: u8,
+        order_id: Option<String>,
+        status: String,
+    },
+    OrderFailed {
+        leg: u8,
+        error: String,
+    },
+    /// The post of `client_order_id` failed without saying whether the CLOB took it, so it was looked up among
+    /// the resting orders and fills before any retry. `order_id` None: not found, posted again.
+    OrderLookedUp {
+        leg: u8,
+        client_order_id: String,
+        found: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        order_id: Option<String>,
+    },
+    /// A resting leg was cancelled because it hadn't fully matched in time.
+    OrderCancelled {
+        leg: u8,
+        order_id: String,
+        size_matched: Option<String>,
+    },
+    /// A resting maker quote on `leg` matched `size` shares at `price`, as its order status reported.
+    LegFilled {
+        leg: u8,
+        size: f64,
+        price: f64,
+    },
+    /// The decision was dropped before any order went out (e.g. quotes moved past the slippage guard).
+    Aborted {
+        reason: String,
+    },
+    /// Both legs were sold before resolution by stop-loss or take-profit.
+    PositionClosed {
+        reason: String,
+        proceeds: f64,
+        pnl: f64,
+    },
+    FillsReconciled {
+        actual_pnl: f64,
+        intended_pnl: f64,
+        size_mismatches: usize,
+    },
+    Resolved {
+        outcome_15: String,
+        outcome_5: String,
+        pnl: f64,
+        /// Resolution source that decided each market (e.g. "clob", "onchain").
+        #[serde(default)]
+        source_15: Option<String>,
+        #[serde(default)]
+        source_5: Option<String>,
+    },
+    Redeemed {
+        condition_id: String,
+        outcome: String,
+        transaction_hash: Option<String>,
+    },
+    RedeemFailed {
+        condition_id: String,
+        outcome: String,
+        error: String,
+    },
+    /// Timing of a placed arb: the WS update that completed its quotes, the decision, and each leg's order.
+    Latency {
+        quote_at_ms: Option<i64>,
+        decided_at_ms: i64,
+        legs: Vec<LegLatency>,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enums_parse_any_case_and_serialize_as_the_clob_spells_them() {
+        assert_eq!("buy".parse::<Side>().unwrap(), Side::Buy);
+        assert_eq!(serde_json::from_str::<Side>("\"Sell\"").unwrap(), Side::Sell);
+        assert_eq!(serde_json::to_string(&Side::Buy).unwrap(), "\"BUY\"");
+        assert!("IOC".parse::<OrderType>().is_err());
+        assert_eq!(serde_json::to_string(&OrderType::Fok).unwrap(), "\"FOK\"");
+        assert_eq!("UP".parse::<Outcome>().unwrap(), Outcome::Up);
+        assert_eq!(serde_json::to_string(&Outcome::Down).unwrap(), "\"Down\"");
+        assert_eq!(Outcome::from_token_label("1"), Some(Outcome::Up));
+        assert!(Outcome::Down.is("down"));
+    }
 }
@@ -0,0 +1,370 @@
+//! Admin API: a minimal HTTP/1.1 server for operating a running bot without a restart. Every request needs
+//! `Authorization: Bearer <admin.token>`. Parameters go in the query string; `instance` limits a request to one
+//! named instance (default: all of them) and every response is JSON. It speaks plain HTTP, so the token crosses
+//! the wire in the clear: bind it to loopback and reach it through an SSH tunnel or a TLS proxy.
+//!
+//! - `GET /state`: controls, PnL, active periods, positions, redeem queue, and task restarts of each instance
+//! - `GET /equity?since=<unix secs>`: each instance's equity curve from its portfolio snapshots (all of it
+//!   without `since`)
+//! - `POST /pause?symbol=btc`, `POST /resume?symbol=btc`: stop or restart new trades (no symbol = all symbols)
+//! - `POST /threshold?value=0.97`, `POST /shares?value=20`: override `sum_threshold` / `arb_shares` (no value
+//!   = back to the config). An override that would add a `dangerous_settings` issue is refused with a 400
+//! - `POST /redeem`: retry every queued redemption now
+
+use crate::config::{AdminConfig, StrategyConfig};
+use crate::services::arbitrage_orchestrator::ArbStrategy;
+use crate::services::controls::RuntimeControls;
+use log::{info, warn};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::watch;
+use tokio::time::{timeout, Duration};
+
+const MAX_REQUEST_BYTES: usize = 8192;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+#[derive(Debug, PartialEq)]
+pub struct AdminRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub token: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AdminAction {
+    State,
+    Equity(Option<i64>),
+    Pause(Option<String>),
+    Resume(Option<String>),
+    SetThreshold(Option<f64>),
+    SetShares(Option<String>),
+    RedeemSweep,
+}
+
+impl AdminAction {
+    /// GET endpoints, which change nothing.
+    fn is_read(&self) -> bool {
+        matches!(self, AdminAction::State | AdminAction::Equity(_))
+    }
+}
+
+/// Serve the admin API for `strategies` until `shutdown` turns true; None when `admin.bind` is empty or no token
+/// is set.
+pub fn spawn_admin_server(
+    config: &AdminConfig,
+    strategies: Vec<Arc<ArbStrategy>>,
+    mut shutdown: watch::Receiver<bool>,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let bind = config.bind.trim().to_string();
+    if bind.is_empty() {
+        return None;
+    }
+    let Some(token) = config.token.clone().filter(|t| !t.is_empty()) else {
+        warn!("Admin API: admin.bind is set but admin.token is empty; not starting it");
+        return None;
+    };
+    Some(tokio::spawn(async move {
+        let listener = match TcpListener::bind(&bind).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Admin API: bind {} failed: {}", bind, e);
+                return;
+            }
+        };
+        info!("Admin API listening on {}", bind);
+        if listener.local_addr().is_ok_and(|addr| !addr.ip().is_loopback()) {
+            warn!(
+                "Admin API: {} is not a loopback address and the API is plain HTTP; the token and every request \
+                 can be read on the network",
+                bind
+            );
+        }
+        let strategies = Arc::new(strategies);
+        let token = Arc::new(token);
+        loop {
+            let accepted = tokio::select! {
+                accepted = listener.accept() => accepted,
+                _ = shutdown.wait_for(|stop| *stop) => break,
+            };
+            let (stream, _) = match accepted {
+                Ok(conn) => conn,
+                Err(e) => {
+                    warn!("Admin API: accept failed: {}", e);
+                    continue;
+                }
+            };
+            let strategies = Arc::clone(&strategies);
+            let token = Arc::clone(&token);
+            tokio::spawn(async move {
+                if let Err(e) = serve(stream, token.expose(), &strategies).await {
+                    warn!("Admin API: {}", e);
+                }
+            });
+        }
+    }))
+}
+
+async fn serve(mut stream: TcpStream, token: &str, strategies: &[Arc<ArbStrategy>]) -> anyhow::Result<()> {
+    let head = timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), read_head(&mut stream)).await??;
+    let (status, body) = match parse_request(&head) {
+        None => (400, json!({ "error": "malformed request" })),
+        Some(req) if !token_matches(req.token.as_deref(), token) => (401, json!({ "error": "unauthorized" })),
+        Some(req) => {
+            let instance = req.query.get("instance").map(String::as_str);
+            match parse_action(&req).and_then(|action| check_overrides(&action, instance, strategies).map(|_| action))
+            {
+                Ok(action) => (200, apply(action, instance, strategies).await),
+                Err((status, error)) => (status, json!({ "error": error })),
+            }
+        }
+    };
+    let body = body.to_string();
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        _ => "Method Not Allowed",
+    };
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Compares digests of the two tokens byte by byte without stopping early, so the time taken says nothing about
+/// how much of the token was right.
+fn token_matches(given: Option<&str>, token: &str) -> bool {
+    let Some(given) = given else {
+        return false;
+    };
+    let (given, token) = (Sha256::digest(given.as_bytes()), Sha256::digest(token.as_bytes()));
+    given.iter().zip(token.iter()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Refuse a threshold or shares override that would give any targeted instance a new `dangerous_settings` issue.
+fn check_overrides(
+    action: &AdminAction,
+    instance: Option<&str>,
+    strategies: &[Arc<ArbStrategy>],
+) -> Result<(), (u16, String)> {
+    for strategy in strategies.iter().filter(|s| instance.is_none_or(|name| s.name() == name)) {
+        let issues = override_issues(&strategy.config().strategy, &strategy.controls(), action);
+        if !issues.is_empty() {
+            return Err((400, format!("{}: {}", strategy.name(), issues.join("; "))));
+        }
+    }
+    Ok(())
+}
+
+/// `dangerous_settings` issues `action` would add to an instance configured with `configured` and currently
+/// overridden by `controls`. Issues the running settings already have (allowed at startup) don't count.
+pub fn override_issues(configured: &StrategyConfig, controls: &RuntimeControls, action: &AdminAction) -> Vec<String> {
+    let running = StrategyConfig {
+        sum_threshold: controls.sum_threshold().unwrap_or(configured.sum_threshold),
+        arb_shares: controls.arb_shares().unwrap_or_else(|| configured.arb_shares.clone()),
+        ..configured.clone()
+    };
+    let next = match action {
+        AdminAction::SetThreshold(threshold) => StrategyConfig {
+            sum_threshold: threshold.unwrap_or(configured.sum_threshold),
+            ..running.clone()
+        },
+        AdminAction::SetShares(shares) => StrategyConfig {
+            arb_shares: shares.clone().unwrap_or_else(|| configured.arb_shares.clone()),
+            ..running.clone()
+        },
+        _ => return Vec::new(),
+    };
+    let known = running.dangerous_settings();
+    next.dangerous_settings()
+        .into_iter()
+        .filter(|issue| !known.contains(issue))
+        .collect()
+}
+
+/// Request line and headers; any body is ignored.
+async fn read_head(stream: &mut TcpStream) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+        if buf.len() >= MAX_REQUEST_BYTES {
+            anyhow::bail!("request headers over {} bytes", MAX_REQUEST_BYTES);
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+pub fn parse_request(head: &str) -> Option<AdminRequest> {
+    let mut lines = head.lines();
+    let mut parts = lines.next()?.split_whitespace();
+    let (method, target) = (parts.next()?, parts.next()?);
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let token = lines
+        .take_while(|l| !l.is_empty())
+        .filter_map(|l| l.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer ").map(|t| t.trim().to_string()));
+    Some(AdminRequest {
+        method: method.to_uppercase(),
+        path: path.trim_end_matches('/').to_string(),
+        query: url::form_urlencoded::parse(query.as_bytes()).into_owned().collect(),
+        token,
+    })
+}
+
+/// The action of an authorized request, or the HTTP status and error to answer with.
+pub fn parse_action(req: &AdminRequest) -> Result<AdminAction, (u16, String)> {
+    let param = |key: &str| {
+        req.query
+            .get(key)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let action = match req.path.as_str() {
+        "/state" => AdminAction::State,
+        "/equity" => match param("since") {
+            None => AdminAction::Equity(None),
+            Some(v) => match v.parse::<i64>() {
+                Ok(since) => AdminAction::Equity(Some(since)),
+                Err(_) => return Err((400, format!("since {:?} must be a unix timestamp in seconds", v))),
+            },
+        },
+        "/pause" => AdminAction::Pause(param("symbol")),
+        "/resume" => AdminAction::Resume(param("symbol")),
+        "/threshold" => match param("value") {
+            None => AdminAction::SetThreshold(None),
+            Some(v) => match v.parse::<f64>() {
+                Ok(t) if t > 0.0 && t < 1.0 => AdminAction::SetThreshold(Some(t)),
+                _ => return Err((400, format!("threshold {:?} must be a number in (0, 1)", v))),
+            },
+        },
+        "/shares" => match param("value") {
+            None => AdminAction::SetShares(None),
+            Some(v) => match v.parse::<f64>() {
+                Ok(s) if s > 0.0 => AdminAction::SetShares(Some(v)),
+                _ => return Err((400, format!("shares {:?} must be a positive number", v))),
+            },
+        },
+        "/redeem" => AdminAction::RedeemSweep,
+        path => return Err((404, format!("no endpoint {}", path))),
+    };
+    let method = if action.is_read() { "GET" } else { "POST" };
+    if req.method != method {
+        return Err((405, format!("{} needs {}", req.path, method)));
+    }
+    Ok(action)
+}
+
+async fn apply(action: AdminAction, instance: Option<&str>, strategies: &[Arc<ArbStrategy>]) -> Value {
+    let mut results = Vec::new();
+    for strategy in strategies.iter().filter(|s| instance.is_none_or(|name| s.name() == name)) {
+        let controls = strategy.controls();
+        let result = match &action {
+            AdminAction::State => json!(strategy.state().await),
+            AdminAction::Equity(since) => json!(strategy.equity_curve(since.unwrap_or(i64::MIN)).await),
+            AdminAction::Pause(symbol) => {
+                controls.pause(symbol.as_deref());
+                json!(controls.state())
+            }
+            AdminAction::Resume(symbol) => {
+                controls.resume(symbol.as_deref());
+                json!(controls.state())
+            }
+            AdminAction::SetThreshold(threshold) => {
+                controls.set_sum_threshold(*threshold);
+                json!(controls.state())
+            }
+            AdminAction::SetShares(shares) => {
+                controls.set_arb_shares(shares.clone());
+                json!(controls.state())
+            }
+            AdminAction::RedeemSweep => {
+                let (pending, failed) = strategy.redeem_sweep().await;
+                json!({ "pending": pending, "permanently_failed": failed })
+            }
+        };
+        results.push(json!({ "instance": strategy.name(), "result": result }));
+    }
+    if !action.is_read() {
+        info!("Admin API: {:?} applied to {} instance(s)", action, results.len());
+    }
+    Value::Array(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_authorized_actions() {
+        let req = parse_request(
+            "POST /pause?symbol=btc&instance=fast HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer s3cret\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(req.token.as_deref(), Some("s3cret"));
+        assert_eq!(req.query.get("instance").map(String::as_str), Some("fast"));
+        assert_eq!(parse_action(&req), Ok(AdminAction::Pause(Some("btc".to_string()))));
+
+        let get = |target: &str| parse_request(&format!("GET {} HTTP/1.1\r\n\r\n", target)).unwrap();
+        assert_eq!(get("/state").token, None);
+        assert_eq!(parse_action(&get("/state/")), Ok(AdminAction::State));
+        assert_eq!(parse_action(&get("/equity?since=1767225600")), Ok(AdminAction::Equity(Some(1767225600))));
+        assert_eq!(parse_action(&get("/equity")), Ok(AdminAction::Equity(None)));
+        assert_eq!(parse_action(&get("/equity?since=yesterday")).unwrap_err().0, 400);
+        assert_eq!(parse_action(&get("/pause")).unwrap_err().0, 405);
+        assert_eq!(parse_action(&get("/nope")).unwrap_err().0, 404);
+
+        let post = |target: &str| parse_request(&format!("POST {} HTTP/1.1\r\n\r\n", target)).unwrap();
+        assert_eq!(parse_action(&post("/threshold?value=0.97")), Ok(AdminAction::SetThreshold(Some(0.97))));
+        assert_eq!(parse_action(&post("/threshold")), Ok(AdminAction::SetThreshold(None)));
+        assert_eq!(parse_action(&post("/threshold?value=1.02")).unwrap_err().0, 400);
+        assert_eq!(parse_action(&post("/shares?value=abc")).unwrap_err().0, 400);
+    }
+
+    #[test]
+    fn compares_the_whole_token() {
+        assert!(token_matches(Some("s3cret"), "s3cret"));
+        assert!(!token_matches(Some("s3cre"), "s3cret"));
+        assert!(!token_matches(Some("s3cret!"), "s3cret"));
+        assert!(!token_matches(None, "s3cret"));
+    }
+
+    #[test]
+    fn refuses_overrides_that_add_a_dangerous_setting() {
+        let configured = StrategyConfig {
+            trade_interval_secs: 0,
+            ..crate::config::Config::default().strategy
+        };
+        let controls = RuntimeControls::new();
+        let shares = |v: &str| AdminAction::SetShares(Some(v.to_string()));
+        assert!(override_issues(&configured, &controls, &shares("20")).is_empty());
+        // No cooldown between large arbs.
+        assert_eq!(override_issues(&configured, &controls, &shares("500")).len(), 1);
+        assert!(override_issues(&configured, &controls, &AdminAction::SetThreshold(Some(0.97))).is_empty());
+
+        // An issue the bot was started with anyway doesn't block other overrides.
+        let started_large = StrategyConfig {
+            arb_shares: "500".to_string(),
+            ..configured.clone()
+        };
+        assert!(override_issues(&started_large, &controls, &AdminAction::SetThreshold(Some(0.97))).is_empty());
+        assert!(override_issues(&started_large, &controls, &AdminAction::SetShares(None)).is_empty());
+    }
+}
@@ -1,77 +1,501 @@
-use crate::adapters::polymarket::ws_rtds::{run_chainlink_multi_poller, PriceCacheMulti};
-use crate::adapters::polymarket::PolymarketApi;
+use crate::adapters::hedge::Hedger;
+use crate::adapters::polymarket::{ws_market, ws_recorder};
+use crate::adapters::polymarket::ws_rtds::{run_chainlink_multi_poller, PriceCacheMulti, SpotHistory};
+use crate::adapters::polymarket::PolymarketClient;
 use crate::config::Config;
-use crate::domain::window::{current_15m_period_start, current_5m_period_start, is_last_5min_of_15m};
+use crate::events::{ArbEvent, EventBus};
 use crate::models::TradeRecord;
+use crate::services::canary::Canary;
+use crate::services::controls::{ControlsState, RuntimeControls};
 use crate::services::discovery_service::MarketDiscovery;
-use crate::services::execution_service::run_overlap_round;
+use crate::services::end_of_day::run_end_of_day;
+use crate::services::hedging::hedger_from_config;
+use crate::models::ArbSignal;
+use crate::services::execution_service::{
+    run_overlap_round, MarketFeed, RoundContext, SignalSender, UnrealizedPnl,
+};
+use crate::services::inventory::{Inventory, Position};
+use crate::services::journal::Journal;
+use crate::services::pending_resolution::{run_pending_resolutions, PendingResolutions};
+use crate::services::pnl_ledger::PnlLedger;
+use crate::services::portfolio::{read_equity_curve, run_portfolio_snapshots, PortfolioSnapshot};
+use crate::services::redeem_queue::RedeemQueue;
 use crate::services::redemption_service::auto_redeem_winners;
-use crate::services::resolution_service::resolve_and_compute_pnl;
+use crate::services::resolution_service::{log_provisional_pnl, resolve_and_compute_pnl, resolve_simulated};
+use crate::services::skip_list::SkipList;
+use crate::services::strategy::{self, Strategy};
+use crate::utils::clock::{Clock, SystemClock};
+use crate::utils::json_store::store_path;
+use crate::utils::slug_builder::{build_updown_slug, updown_series};
+use crate::utils::supervisor::Supervisor;
 use anyhow::Result;
-use chrono::Utc;
 use log::{error, info, warn};
-use std::collections::HashMap;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use futures_util::stream::{self, Stream, StreamExt};
+use futures_util::FutureExt;
+use tokio::sync::{broadcast, RwLock};
 use tokio::time::{sleep, Duration};
 
 const OVERLAP_POLL_SECS: u64 = 5;
+/// Seconds before the overlap opens to look up its markets and tokens.
+const PREFETCH_LEAD_SECS: i64 = 60;
 const WAIT_FOR_PRICE_POLL_SECS: u64 = 10;
+const REDEEM_RETRY_POLL_SECS: u64 = 30;
+const SIGNAL_CHANNEL_CAPACITY: usize = 256;
+const FUNDING_RECHECK_SECS: u64 = 10;
+const POLYGON_BLOCK_SECS: u64 = 2;
+const CLOCK_SYNC_INTERVAL_SECS: u64 = 600;
+
+/// One long period being traded or awaiting resolution for a symbol. Several can be live per symbol: the
+/// next overlap is discovered and traded while earlier periods are still resolving.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeriodContext {
+    pub symbol: String,
+    pub period_15: i64,
+    pub period_5: i64,
+    pub cid_15: String,
+    pub cid_5: String,
+    pub t15_up: String,
+    pub t15_down: String,
+    pub t5_up: String,
+    pub t5_down: String,
+    /// Price-to-beat of the long and short periods.
+    pub strike_15: f64,
+    pub strike_5: f64,
+}
+
+impl PeriodContext {
+    /// "btc-1767726000": the trade id prefix, also used to key per-period unrealized PnL.
+    pub fn key(&self) -> String {
+        format!("{}-{}", self.symbol.to_lowercase(), self.period_15)
+    }
+}
+
+/// Periods currently trading or resolving, by `PeriodContext::key`.
+type ActivePeriods = Arc<std::sync::Mutex<std::collections::HashSet<String>>>;
+
+/// Snapshot of one instance for the admin API's state dump.
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyState {
+    pub name: String,
+    pub symbols: Vec<String>,
+    /// Effective values: the admin override when set, else the config.
+    pub sum_threshold: f64,
+    pub arb_shares: String,
+    pub controls: ControlsState,
+    pub cumulative_pnl: f64,
+    /// Realized PnL per symbol, carried over between sessions.
+    pub pnl_by_symbol: BTreeMap<String, f64>,
+    pub unrealized_pnl: HashMap<String, f64>,
+    pub active_periods: Vec<String>,
+    /// Held positions by token id.
+    pub positions: HashMap<String, Position>,
+    pub redeem_pending: usize,
+    pub redeem_failed: usize,
+    /// Supervisor restarts per symbol loop or background task since startup.
+    pub loop_restarts: HashMap<String, u32>,
+    /// Out-of-sequence market WS messages since startup, across all instances.
+    pub ws_sequence_gaps: u64,
+}
 
 pub struct ArbStrategy {
-    api: Arc<PolymarketApi>,
+    api: Arc<dyn PolymarketClient>,
     config: Config,
+    /// Instance name from `instances`; empty when the config has none.
+    name: String,
+    strategy: Arc<dyn Strategy>,
     discovery: MarketDiscovery,
-    price_cache_15: PriceCacheMulti,
-    price_cache_5: PriceCacheMulti,
+    price_cache_long: PriceCacheMulti,
+    price_cache_short: PriceCacheMulti,
+    spot_history: SpotHistory,
+    /// Symbols this instance's Chainlink feed captures (empty = every symbol); None when it reads the feed
+    /// of another instance with the same periods.
+    feed_symbols: Option<Vec<String>>,
+    hedger: Option<Arc<dyn Hedger>>,
+    unrealized_pnl: UnrealizedPnl,
+    journal: Arc<Journal>,
+    inventory: Arc<Inventory>,
+    redeem_queue: Arc<RedeemQueue>,
+    pending_resolutions: Arc<PendingResolutions>,
+    skip_list: Arc<SkipList>,
+    canary: Arc<Canary>,
+    active_periods: ActivePeriods,
+    signals: SignalSender,
+    events: EventBus,
+    controls: Arc<RuntimeControls>,
+    cumulative_pnl: Arc<PnlLedger>,
+    clock: Arc<dyn Clock>,
+    /// Restarts symbol loops and background tasks that fail or panic.
+    supervisor: Supervisor,
+    /// Background tasks and symbol loops started by `run`, aborted once the symbol loops have returned.
+    tasks: std::sync::Mutex<Vec<tokio::task::AbortHandle>>,
 }
 
 impl ArbStrategy {
-    pub fn new(api: Arc<PolymarketApi>, config: Config) -> Self {
+    pub fn new(api: Arc<dyn PolymarketClient>, config: Config) -> Self {
+        Self::with_events(api, config, EventBus::new())
+    }
+
+    /// Like `new`, publishing quotes, arbs, and journaled events to `events`.
+    pub fn with_events(api: Arc<dyn PolymarketClient>, config: Config, events: EventBus) -> Self {
         Self {
             discovery: MarketDiscovery::new(api.clone()),
+            journal: Arc::new(Journal::new(&config.strategy.journal_path).with_events(events.clone())),
+            inventory: Arc::new(Inventory::new()),
+            redeem_queue: Arc::new(RedeemQueue::load(
+                &config.strategy.redeem_queue_path,
+                config.strategy.redeem_max_attempts,
+            )),
+            pending_resolutions: Arc::new(PendingResolutions::load(&config.strategy.pending_resolution_path)),
+            skip_list: Arc::new(SkipList::load(
+                &config.strategy.skip_list_path,
+                config.strategy.skip_after_anomalies,
+            )),
+            canary: Arc::new(Canary::load(&config.strategy)),
+            hedger: hedger_from_config(&config.strategy.hedge, config.strategy.simulation_mode),
+            strategy: strategy::build(config.strategy.kind),
+            // With discovery the symbol set grows at runtime, so capture every symbol the feed publishes.
+            feed_symbols: Some(if config.strategy.symbol_discovery.enabled {
+                Vec::new()
+            } else {
+                config.strategy.symbols.clone()
+            }),
+            name: String::new(),
+            active_periods: Arc::new(std::sync::Mutex::new(std::collections::HashSet::new())),
+            signals: broadcast::channel(SIGNAL_CHANNEL_CAPACITY).0,
+            supervisor: Supervisor::new(Some(events.clone())),
+            tasks: std::sync::Mutex::new(Vec::new()),
+            events,
+            controls: Arc::new(RuntimeControls::new()),
+            cumulative_pnl: Arc::new(PnlLedger::load(&config.strategy.pnl_state_path)),
+            clock: Arc::new(SystemClock),
             api,
             config,
-            price_cache_15: Arc::new(RwLock::new(HashMap::new())),
-            price_cache_5: Arc::new(RwLock::new(HashMap::new())),
+            price_cache_long: Arc::new(RwLock::new(HashMap::new())),
+            price_cache_short: Arc::new(RwLock::new(HashMap::new())),
+            spot_history: Arc::new(RwLock::new(HashMap::new())),
+            unrealized_pnl: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// One strategy per (name, config) from `Config::strategy_instances`, each with its own PnL, journal, and
+    /// limits, all publishing to `events`. Instances with the same periods share one Chainlink feed covering
+    /// all their symbols.
+    pub fn new_instances(api: Arc<dyn PolymarketClient>, instances: Vec<(String, Config)>, events: EventBus) -> Vec<Self> {
+        let instances = instances.into_iter().map(|(name, config)| (name, config, Arc::clone(&api))).collect();
+        Self::new_instances_with_apis(instances, events)
+    }
+
+    /// `new_instances` with a client per instance, e.g. one per trading wallet.
+    pub fn new_instances_with_apis(
+        instances: Vec<(String, Config, Arc<dyn PolymarketClient>)>,
+        events: EventBus,
+    ) -> Vec<Self> {
+        let mut strategies: Vec<Self> = Vec::with_capacity(instances.len());
+        for (name, config, api) in instances {
+            let mut strategy = Self::with_events(api, config, events.clone());
+            strategy.name = name;
+            let periods = strategy.config.strategy.periods;
+            if let Some(owner) = strategies
+                .iter_mut()
+                .find(|s| s.feed_symbols.is_some() && s.config.strategy.periods == periods)
+            {
+                let theirs = strategy.feed_symbols.take().unwrap_or_default();
+                if let Some(symbols) = owner.feed_symbols.as_mut() {
+                    if symbols.is_empty() || theirs.is_empty() {
+                        symbols.clear();
+                    } else {
+                        symbols.extend(theirs.into_iter().filter(|s| !symbols.contains(s)).collect::<Vec<_>>());
+                    }
+                }
+                strategy.price_cache_long = Arc::clone(&owner.price_cache_long);
+                strategy.price_cache_short = Arc::clone(&owner.price_cache_short);
+                strategy.spot_history = Arc::clone(&owner.spot_history);
+            }
+            strategies.push(strategy);
+        }
+        strategies
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Trade with `strategy` instead of the one `strategy.kind` selects.
+    pub fn with_strategy(mut self, strategy: Arc<dyn Strategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Read the time from `clock` instead of the system clock (tests, replays).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Ask `run` to return: the overlap round in progress stops entering trades and winds up (resting legs are
+    /// cancelled, the hedge flattened), its trades go to the pending-resolution store, and no new round starts.
+    /// Resolutions already under way and the Chainlink feed keep going until the runtime shuts down.
+    pub fn stop(&self) {
+        self.controls.stop();
+    }
+
+    /// End the background tasks started by `run`, once the symbol loops are done.
+    fn abort_tasks(&self) {
+        for task in self.tasks.lock().unwrap_or_else(|e| e.into_inner()).drain(..) {
+            task.abort();
+        }
+    }
+
+    fn track<T>(&self, handle: tokio::task::JoinHandle<T>) -> tokio::task::JoinHandle<T> {
+        self.tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(handle.abort_handle());
+        handle
+    }
+
+    pub fn events(&self) -> EventBus {
+        self.events.clone()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Pause and override switches read by the symbol loops (see `services::admin`).
+    pub fn controls(&self) -> Arc<RuntimeControls> {
+        Arc::clone(&self.controls)
+    }
+
+    /// Retry every queued redemption now, as end of day does; returns (pending, permanently failed) after.
+    pub async fn redeem_sweep(&self) -> (usize, usize) {
+        if !self.config.strategy.simulation_mode {
+            self.redeem_queue.expedite();
+            self.redeem_queue
+                .retry_due(self.api.as_ref(), &self.journal, &self.inventory)
+                .await;
+        }
+        self.redeem_queue.counts()
+    }
+
+    /// List the account's resting orders, none of which a symbol loop manages at startup or after `stop`. They
+    /// are logged and raised as a `stray_orders` alert; with `cancel_stray_orders` the ones the journal shows this
+    /// bot placed are cancelled, while manual orders and other processes' orders are left alone. `when` labels
+    /// both. Returns how many were cancelled.
+    pub async fn sweep_stray_orders(&self, when: &str) -> usize {
+        let strategy = &self.config.strategy;
+        if strategy.simulation_mode || strategy.signals_only || self.api.wallet_address().is_none() {
+            return 0;
+        }
+        let orders = match self.api.get_open_orders(None).await {
+            Ok(orders) => orders,
+            Err(e) => {
+                warn!("Resting orders at {}: listing failed: {}", when, e);
+                return 0;
+            }
+        };
+        if orders.is_empty() {
+            return 0;
+        }
+        let listed: Vec<String> = orders
+            .iter()
+            .map(|o| format!("{} {} {} @ {} ({})", o.side, o.original_size, o.outcome, o.price, o.id))
+            .collect();
+        let message = format!("{} resting order(s) at {}: {}", orders.len(), when, listed.join(", "));
+        warn!("{}", message);
+        self.events.publish(ArbEvent::Alert {
+            kind: "stray_orders".to_string(),
+            subject: when.to_string(),
+            message,
+            ts_ms: self.clock.now_ms(),
+        });
+        if !strategy.cancel_stray_orders {
+            return 0;
+        }
+        let placed = self.journal.placed_order_ids();
+        let (ours, others): (Vec<_>, Vec<_>) = orders.iter().partition(|o| placed.contains(&o.id));
+        for o in &others {
+            info!("Resting order {} at {} was not placed by this bot, leaving it", o.id, when);
+        }
+        if ours.is_empty() {
+            return 0;
+        }
+        let ids: Vec<&str> = ours.iter().map(|o| o.id.as_str()).collect();
+        let cancelled = match self.api.cancel_orders(&ids).await {
+            Ok(outcome) => {
+                for (id, reason) in &outcome.not_canceled {
+                    warn!("Cancel of resting order {} failed: {}", id, reason);
+                }
+                outcome.canceled.len()
+            }
+            Err(e) => {
+                warn!("Cancel of resting orders failed: {}", e);
+                0
+            }
+        };
+        info!("Cancelled {} of {} resting order(s) placed by this bot at {}", cancelled, ours.len(), when);
+        cancelled
+    }
+
+    pub async fn state(&self) -> StrategyState {
+        let mut active_periods: Vec<String> = self
+            .active_periods
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect();
+        active_periods.sort();
+        let (redeem_pending, redeem_failed) = self.redeem_queue.counts();
+        let pnl = self.cumulative_pnl.snapshot();
+        StrategyState {
+            name: self.name.clone(),
+            symbols: self.config.strategy.symbols.clone(),
+            sum_threshold: self
+                .controls
+                .sum_threshold()
+                .unwrap_or(self.config.strategy.sum_threshold),
+            arb_shares: self
+                .controls
+                .arb_shares()
+                .unwrap_or_else(|| self.config.strategy.arb_shares.clone()),
+            controls: self.controls.state(),
+            cumulative_pnl: pnl.total.to_f64().unwrap_or(0.0),
+            pnl_by_symbol: pnl
+                .by_symbol
+                .iter()
+                .map(|(symbol, p)| (symbol.clone(), p.to_f64().unwrap_or(0.0)))
+                .collect(),
+            unrealized_pnl: self.unrealized_pnl.read().await.clone(),
+            active_periods,
+            positions: self.inventory.snapshot().await,
+            redeem_pending,
+            redeem_failed,
+            loop_restarts: self.supervisor.restart_counts(),
+            ws_sequence_gaps: ws_market::sequence_gap_count(),
+        }
+    }
+
+    /// Portfolio snapshots taken at or after `since` (unix seconds), oldest first; empty when snapshots are off
+    /// or none were written yet.
+    pub async fn equity_curve(&self, since: i64) -> Vec<PortfolioSnapshot> {
+        let Some(path) = store_path(&self.config.strategy.portfolio_snapshot_path).filter(|p| p.exists()) else {
+            return Vec::new();
+        };
+        match tokio::task::spawn_blocking(move || read_equity_curve(&path)).await {
+            Ok(Ok(curve)) => curve.into_iter().filter(|s| s.ts >= since).collect(),
+            Ok(Err(e)) => {
+                warn!("[{}] Equity curve unavailable: {}", self.name, e);
+                Vec::new()
+            }
+            Err(e) => {
+                warn!("[{}] Equity curve read failed: {}", self.name, e);
+                Vec::new()
+            }
         }
     }
 
+    /// Latest unrealized PnL of open legs per symbol and period (cleared once the period resolves).
+    pub fn unrealized_pnl(&self) -> UnrealizedPnl {
+        Arc::clone(&self.unrealized_pnl)
+    }
+
+    /// Stream of detected arbs across all symbols, for embedding: subscribe before `run` and execute
+    /// downstream (set `signals_only` to keep the bot from placing orders itself). A subscriber that falls
+    /// more than the channel capacity behind skips the oldest signals.
+    pub fn signals(&self) -> impl Stream<Item = ArbSignal> {
+        stream::unfold(self.signals.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(signal) => return Some((signal, rx)),
+                    Err(broadcast::error::RecvError::Lagged(n)) => {
+                        warn!("Arb signal subscriber lagged; skipped {} signal(s)", n)
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Wait for the next tradable overlap of `symbol`. A minute before it opens, its markets are looked up and
+    /// the market WS for its tokens is opened into `prefetched_feed`.
     async fn wait_for_overlap_and_prices(
         &self,
         symbol: &str,
-    ) -> Result<(
-        String,
-        String,
-        String,
-        String,
-        String,
-        String,
-        i64,
-        i64,
-        f64,
-        f64,
-    )> {
+        prefetched_feed: &mut Option<MarketFeed>,
+    ) -> Result<PeriodContext> {
+        let periods = self.config.strategy.periods;
+        let (long_label, short_label) = (periods.long_label(), periods.short_label());
+        let mut prefetched_for = None;
+        let mut paused_for: Option<String> = None;
         loop {
-            let now = Utc::now().timestamp();
-            let period_15 = current_15m_period_start();
-            let period_5 = current_5m_period_start();
+            let now = self.clock.now();
+            let (period_15, period_5) = periods.current_starts(self.clock.as_ref());
 
-            if !is_last_5min_of_15m(now, period_15) {
+            if self.config.strategy.end_of_day.idle_at(now) {
+                sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
+                continue;
+            }
+            let paused = self.config.strategy.schedule.paused_at(now);
+            if paused != paused_for {
+                match &paused {
+                    Some(reason) => info!("{}: new trades paused ({})", symbol.to_uppercase(), reason),
+                    None => info!("{}: trading schedule open again", symbol.to_uppercase()),
+                }
+                paused_for = paused;
+            }
+            if paused_for.is_some() {
+                sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
+                continue;
+            }
+
+            if let Some(wallet) = &self.config.wallet {
+                if !wallet.takes_period(symbol, period_15, periods.long_secs()) {
+                    info!(
+                        "{}: {} period {} goes to another wallet; {} sits it out.",
+                        symbol.to_uppercase(),
+                        long_label,
+                        period_15,
+                        wallet.name
+                    );
+                    let until_close = (period_15 + periods.long_secs() - now).max(OVERLAP_POLL_SECS as i64);
+                    sleep(Duration::from_secs(until_close as u64)).await;
+                    continue;
+                }
+            }
+
+            if !periods.in_overlap(now, period_15) {
+                let overlap_start = period_15 + periods.overlap_start_offset_mins * 60;
+                if now >= overlap_start - PREFETCH_LEAD_SECS && prefetched_for != Some(period_15) {
+                    prefetched_for = Some(period_15);
+                    if let Some(asset_ids) = self.discovery.prefetch_overlap(symbol, periods, period_15).await {
+                        *prefetched_feed = Some(MarketFeed::spawn(
+                            &self.config.polymarket.ws_url,
+                            asset_ids,
+                            symbol,
+                            Some(self.events.clone()),
+                        ));
+                    }
+                }
                 sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
                 continue;
             }
 
             let (cid_15, cid_5) = {
-                let m15 = self.discovery.get_15m_market(symbol, period_15);
-                let m5 = self.discovery.get_5m_market(symbol, period_5);
+                let m15 = self.discovery.get_updown_market(symbol, periods.long_period_mins, period_15);
+                let m5 = self.discovery.get_updown_market(symbol, periods.short_period_mins, period_5);
                 let (r15, r5) = tokio::try_join!(m15, m5)?;
                 let cid_15 = match r15 {
                     Some((cid, _)) => cid,
                     None => {
                         warn!(
-                            "15m {} market not found for period {}. Retrying.",
-                            symbol, period_15
+                            "{} {} market not found for period {}. Retrying.",
+                            long_label, symbol, period_15
                         );
                         sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
                         continue;
@@ -81,8 +505,8 @@ impl ArbStrategy {
                     Some((cid, _)) => cid,
                     None => {
                         warn!(
-                            "5m {} market not found for period {}. Retrying.",
-                            symbol, period_5
+                            "{} {} market not found for period {}. Retrying.",
+                            short_label, symbol, period_5
                         );
                         sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
                         continue;
@@ -91,9 +515,27 @@ impl ArbStrategy {
                 (cid_15, cid_5)
             };
 
+            let slug_15 = build_updown_slug(symbol, periods.long_period_mins, period_15);
+            let slug_5 = build_updown_slug(symbol, periods.short_period_mins, period_5);
+            let series_15 = updown_series(symbol, periods.long_period_mins);
+            let series_5 = updown_series(symbol, periods.short_period_mins);
+            let keys = [&cid_15, &cid_5, &slug_15, &slug_5, &series_15, &series_5].map(String::as_str);
+            if let Some(skipped) = self.skip_list.find(&keys) {
+                warn!(
+                    "{}: {} is on the skip-list ({}); sitting out this {} period.",
+                    symbol.to_uppercase(),
+                    skipped.key,
+                    skipped.reason,
+                    long_label
+                );
+                let until_close = (period_15 + periods.long_secs() - now).max(OVERLAP_POLL_SECS as i64);
+                sleep(Duration::from_secs(until_close as u64)).await;
+                continue;
+            }
+
             let (price_15, price_5) = {
-                let c15 = self.price_cache_15.read().await;
-                let c5 = self.price_cache_5.read().await;
+                let c15 = self.price_cache_long.read().await;
+                let c5 = self.price_cache_short.read().await;
                 let p15 = c15.get(symbol).and_then(|m| m.get(&period_15).copied());
                 let p5 = c5.get(symbol).and_then(|m| m.get(&period_5).copied());
                 (p15, p5)
@@ -103,9 +545,11 @@ impl ArbStrategy {
                 (Some(a), Some(b)) => (a, b),
                 _ => {
                     info!(
-                        "{}: waiting for price-to-beat 15m={:?}, 5m={:?}",
+                        "{}: waiting for price-to-beat {}={:?}, {}={:?}",
                         symbol.to_uppercase(),
+                        long_label,
                         price_15,
+                        short_label,
                         price_5
                     );
                     sleep(Duration::from_secs(WAIT_FOR_PRICE_POLL_SECS)).await;
@@ -113,18 +557,6 @@ impl ArbStrategy {
                 }
             };
 
-            let tolerance = self.config.strategy.price_to_beat_tolerance_for(symbol);
-            if (price_15 - price_5).abs() > tolerance {
-                info!(
-                    "{}: |15m - 5m| price-to-beat = {:.6} > tolerance {:.6} USD; skipping.",
-                    symbol.to_uppercase(),
-                    (price_15 - price_5).abs(),
-                    tolerance
-                );
-                sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
-                continue;
-            }
-
             let (t15_up, t15_down, t5_up, t5_down) = {
                 let tok15 = self.discovery.get_market_tokens(&cid_15);
                 let tok5 = self.discovery.get_market_tokens(&cid_5);
@@ -132,95 +564,353 @@ impl ArbStrategy {
                 (u15, d15, u5, d5)
             };
 
+            let ctx = PeriodContext {
+                symbol: symbol.to_string(),
+                period_15,
+                period_5,
+                cid_15,
+                cid_5,
+                t15_up,
+                t15_down,
+                t5_up,
+                t5_down,
+                strike_15: price_15,
+                strike_5: price_5,
+            };
+            if let Err(reason) = self.strategy.setup(&self.config, &ctx) {
+                info!("{}: {}; skipping.", symbol.to_uppercase(), reason);
+                sleep(Duration::from_secs(OVERLAP_POLL_SECS)).await;
+                continue;
+            }
+
+            if let Ok(json) = serde_json::to_string(&ctx) {
+                ws_recorder::record(ws_recorder::SOURCE_ROUND, &json);
+            }
             info!(
-                "{} overlap active: 15m period {} (P2B {:.4}), 5m period {} (P2B {:.4}), tolerance {:.6}",
+                "{} overlap active: {} period {} (P2B {:.4}), {} period {} (P2B {:.4})",
                 symbol.to_uppercase(),
+                long_label,
                 period_15,
                 price_15,
+                short_label,
                 period_5,
-                price_5,
-                tolerance
+                price_5
             );
-            return Ok((
-                cid_15, cid_5, t15_up, t15_down, t5_up, t5_down, period_15, period_5, price_15,
-                price_5,
-            ));
+            return Ok(ctx);
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn run_symbol_loop(
-        api: Arc<PolymarketApi>,
+        api: Arc<dyn PolymarketClient>,
         config: Config,
-        price_cache_15: PriceCacheMulti,
-        price_cache_5: PriceCacheMulti,
-        cumulative_pnl: Arc<RwLock<f64>>,
+        strategy: Arc<dyn Strategy>,
+        price_cache_long: PriceCacheMulti,
+        price_cache_short: PriceCacheMulti,
+        spot_history: SpotHistory,
+        hedger: Option<Arc<dyn Hedger>>,
+        unrealized_pnl: UnrealizedPnl,
+        journal: Arc<Journal>,
+        inventory: Arc<Inventory>,
+        redeem_queue: Arc<RedeemQueue>,
+        pending_resolutions: Arc<PendingResolutions>,
+        skip_list: Arc<SkipList>,
+        canary: Arc<Canary>,
+        active_periods: ActivePeriods,
+        signals: SignalSender,
+        events: EventBus,
+        controls: Arc<RuntimeControls>,
+        cumulative_pnl: Arc<PnlLedger>,
+        clock: Arc<dyn Clock>,
         symbol: String,
     ) -> Result<()> {
         let discovery = MarketDiscovery::new(api.clone());
-        let strategy = Self {
+        let strategy = Arc::new(Self {
             api: api.clone(),
             config: config.clone(),
+            name: String::new(),
+            strategy,
             discovery,
-            price_cache_15,
-            price_cache_5,
-        };
-        loop {
-            let (cid_15, cid_5, t15_up, t15_down, t5_up, t5_down, period_15, period_5, _p15, _p5) =
-                strategy.wait_for_overlap_and_prices(&symbol).await?;
-
-            match run_overlap_round(
-                strategy.api.clone(),
-                &strategy.config,
-                &symbol,
-                &cid_15,
-                &cid_5,
-                &t15_up,
-                &t15_down,
-                &t5_up,
-                &t5_down,
-                period_15,
-                period_5,
-            )
-            .await
+            price_cache_long,
+            price_cache_short,
+            spot_history,
+            feed_symbols: None,
+            hedger,
+            unrealized_pnl,
+            journal,
+            inventory,
+            redeem_queue,
+            pending_resolutions,
+            skip_list,
+            canary,
+            active_periods,
+            signals,
+            supervisor: Supervisor::default(),
+            tasks: std::sync::Mutex::new(Vec::new()),
+            events,
+            controls,
+            cumulative_pnl: Arc::clone(&cumulative_pnl),
+            clock,
+        });
+        let mut prefetched_feed = None;
+        while !strategy.controls.is_stopping() {
+            let ctx = tokio::select! {
+                ctx = strategy.wait_for_overlap_and_prices(&symbol, &mut prefetched_feed) => ctx?,
+                _ = strategy.controls.stopped() => break,
+            };
+
+            if strategy.deposit_settling(&symbol).await {
+                sleep(Duration::from_secs(FUNDING_RECHECK_SECS)).await;
+                continue;
+            }
+
+            let resolving = strategy.begin_period(&ctx);
+            if resolving > 0 {
+                info!(
+                    "{}: trading period {} while {} earlier period(s) resolve",
+                    symbol.to_uppercase(),
+                    ctx.period_15,
+                    resolving
+                );
+            }
+
+            let round = RoundContext {
+                api: strategy.api.clone(),
+                config: &strategy.config,
+                unrealized: strategy.unrealized_pnl(),
+                journal: Arc::clone(&strategy.journal),
+                signals: &strategy.signals,
+                canary: &strategy.canary,
+                spot_history: &strategy.spot_history,
+                cumulative_pnl: &cumulative_pnl,
+                hedger: strategy.hedger.clone(),
+                strategy: strategy.strategy.as_ref(),
+                controls: &strategy.controls,
+                clock: strategy.clock.as_ref(),
+            };
+            match run_overlap_round(round, &ctx, prefetched_feed.take()).await
             {
-                Ok(trades) => {
-                    if !trades.is_empty() {
-                        strategy
-                            .resolve_redeem_and_track(trades, cumulative_pnl.clone())
-                            .await?;
-                    }
+                Ok(trades) if !trades.is_empty() && strategy.controls.is_stopping() => {
+                    strategy.track_inventory(&trades).await;
+                    // Stopping: the period is persisted for the pending-resolution worker of the next start.
+                    strategy.track_pending(&trades);
+                    strategy.pending_resolutions.release(&ctx.cid_15, &ctx.cid_5);
+                    info!(
+                        "{} period {}: {} trade(s) handed to the pending-resolution store",
+                        symbol.to_uppercase(),
+                        ctx.period_15,
+                        trades.len()
+                    );
+                    strategy.finish_period(&ctx).await;
                 }
+                Ok(trades) if !trades.is_empty() => {
+                    strategy.track_inventory(&trades).await;
+                    // Resolution takes minutes; run it beside the loop so the next overlap isn't missed.
+                    let strategy = Arc::clone(&strategy);
+                    let cumulative_pnl = Arc::clone(&cumulative_pnl);
+                    tokio::spawn(async move {
+                        match strategy.resolve_redeem_and_track(&trades, cumulative_pnl).await {
+                            Ok(pnl) => strategy.strategy.on_resolution(&ctx, &trades, pnl),
+                            Err(e) => error!(
+                                "{} period {} resolution error: {}",
+                                ctx.symbol.to_uppercase(),
+                                ctx.period_15,
+                                e
+                            ),
+                        }
+                        strategy.finish_period(&ctx).await;
+                    });
+                }
+                Ok(_) => strategy.finish_period(&ctx).await,
                 Err(e) => {
                     error!("{} overlap round error: {}", symbol.to_uppercase(), e);
+                    strategy.finish_period(&ctx).await;
+                }
+            }
+            tokio::select! {
+                _ = sleep(Duration::from_secs(5)) => {}
+                _ = strategy.controls.stopped() => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// True while a USDC deposit to the funder is younger than `funding_settle_secs`. RPC errors don't
+    /// block trading.
+    async fn deposit_settling(&self, symbol: &str) -> bool {
+        let settle_secs = self.config.strategy.funding_settle_secs;
+        if settle_secs == 0 || self.config.strategy.simulation_mode {
+            return false;
+        }
+        let Some(wallet) = self.api.wallet_address() else {
+            return false;
+        };
+        match self
+            .api
+            .recent_usdc_inbound(&wallet, settle_secs.div_ceil(POLYGON_BLOCK_SECS))
+            .await
+        {
+            Ok(Some((block, amount))) => {
+                info!(
+                    "{}: USDC deposit of {:.2} in block {} is still settling; holding trades (re-check in {}s)",
+                    symbol.to_uppercase(),
+                    amount,
+                    block,
+                    FUNDING_RECHECK_SECS
+                );
+                true
+            }
+            Ok(None) => false,
+            Err(e) => {
+                warn!("Deposit check failed ({}); trading anyway", e);
+                false
+            }
+        }
+    }
+
+    /// Simulated trades count as filled; live positions come from the account's fills.
+    async fn track_inventory(&self, trades: &[TradeRecord]) {
+        if self.config.strategy.simulation_mode {
+            self.inventory.record_trades(trades).await;
+        } else {
+            match self.inventory.sync_fills(self.api.as_ref(), trades).await {
+                Ok(n) => info!("Inventory: applied {} new fill(s)", n),
+                Err(e) => warn!("Inventory fill sync failed: {}", e),
+            }
+            if self.config.strategy.verify_inventory_onchain {
+                if let Err(e) = self.inventory.verify_onchain(self.api.as_ref()).await {
+                    warn!("Inventory on-chain verification failed: {}", e);
                 }
             }
-            sleep(Duration::from_secs(5)).await;
         }
+        self.inventory.log_summary().await;
     }
 
+    /// Paper positions are never redeemed; they leave the inventory once their markets have settled.
+    async fn settle_simulated_inventory(&self, cid_15: &str, cid_5: &str) {
+        self.inventory.record_redeemed(cid_15).await;
+        self.inventory.record_redeemed(cid_5).await;
+    }
+
+    /// Add the period to the active set; returns how many earlier periods of its symbol are still in it.
+    fn begin_period(&self, ctx: &PeriodContext) -> usize {
+        let mut active = self.active_periods.lock().unwrap_or_else(|e| e.into_inner());
+        let prefix = format!("{}-", ctx.symbol.to_lowercase());
+        let earlier = active.iter().filter(|k| k.starts_with(&prefix)).count();
+        active.insert(ctx.key());
+        earlier
+    }
+
+    /// Drop a period that traded nothing or has resolved from the active set and the unrealized PnL map.
+    async fn finish_period(&self, ctx: &PeriodContext) {
+        let key = ctx.key();
+        self.unrealized_pnl.write().await.remove(&key);
+        self.active_periods
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&key);
+    }
+
+    /// Resolve the period's trades and redeem the winners; returns the period's PnL. A period still unresolved
+    /// after `resolution_max_wait_secs` is left to the pending-resolution worker.
     async fn resolve_redeem_and_track(
         &self,
-        trades: Vec<TradeRecord>,
-        cumulative_pnl: Arc<RwLock<f64>>,
-    ) -> Result<()> {
-        let (redeem_targets, _) = resolve_and_compute_pnl(
+        trades: &[TradeRecord],
+        cumulative_pnl: Arc<PnlLedger>,
+    ) -> Result<Decimal> {
+        let Some(first) = trades.first() else {
+            return Ok(Decimal::ZERO);
+        };
+        let (cid_15, cid_5) = (first.cid_15.clone(), first.cid_5.clone());
+        let strategy = &self.config.strategy;
+        self.track_pending(trades);
+        if strategy.simulation_mode {
+            // Paper trades settle on the captured Chainlink closes; the markets' own resolution is the fallback.
+            let simulated = resolve_simulated(
+                self.api.as_ref(),
+                &self.config,
+                trades,
+                &self.price_cache_long,
+                &self.price_cache_short,
+                Arc::clone(&cumulative_pnl),
+                &self.journal,
+            )
+            .await;
+            if let Some((_, period_pnl)) = simulated {
+                self.pending_resolutions.remove(&cid_15, &cid_5);
+                self.settle_simulated_inventory(&cid_15, &cid_5).await;
+                return Ok(period_pnl);
+            }
+            info!("{} simulation: Chainlink closes not captured; using market resolution", first.symbol.to_uppercase());
+        }
+        let provisional = log_provisional_pnl(
+            trades,
+            self.config.strategy.periods,
+            Arc::clone(&self.price_cache_long),
+            Arc::clone(&self.price_cache_short),
+        );
+        let official = resolve_and_compute_pnl(
             self.api.clone(),
             &self.config,
-            &trades,
+            trades,
             cumulative_pnl,
+            &self.journal,
+            &self.skip_list,
+        );
+        let (_, resolved) = tokio::join!(provisional, official);
+        let resolved = resolved.inspect_err(|_| self.pending_resolutions.release(&cid_15, &cid_5))?;
+        let Some((redeem_targets, period_pnl)) = resolved else {
+            info!("{} / {} still unresolved; will keep polling in the background", cid_15, cid_5);
+            self.pending_resolutions.release(&cid_15, &cid_5);
+            return Ok(Decimal::ZERO);
+        };
+        self.pending_resolutions.remove(&cid_15, &cid_5);
+        if strategy.simulation_mode {
+            self.settle_simulated_inventory(&cid_15, &cid_5).await;
+        }
+        auto_redeem_winners(
+            self.api.clone(),
+            &self.config,
+            &redeem_targets,
+            &self.journal,
+            &self.inventory,
+            &self.redeem_queue,
         )
         .await?;
-        auto_redeem_winners(self.api.clone(), &self.config, &redeem_targets).await?;
-        Ok(())
+        Ok(period_pnl)
+    }
+
+    /// Store the period of `trades` in the pending-resolution store until it resolves.
+    fn track_pending(&self, trades: &[TradeRecord]) {
+        let Some(first) = trades.first() else {
+            return;
+        };
+        let strategy = &self.config.strategy;
+        let periods = strategy.periods;
+        let slugs = vec![
+            build_updown_slug(&first.symbol, periods.long_period_mins, first.period_15),
+            build_updown_slug(&first.symbol, periods.short_period_mins, first.period_5),
+        ];
+        let end_unix =
+            (first.period_15 + periods.long_period_mins * 60).max(first.period_5 + periods.short_period_mins * 60);
+        let wait_secs = strategy.resolution_initial_delay_secs + strategy.resolution_max_wait_secs;
+        self.pending_resolutions
+            .track(trades, slugs, end_unix, end_unix.max(self.clock.now()) + wait_secs as i64);
     }
 
     pub async fn run(&self) -> Result<()> {
         let symbols = &self.config.strategy.symbols;
         info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        let periods = self.config.strategy.periods;
         info!(
-            "15m vs 5m arbitrage (symbols: {:?}) — overlap window, parallel WS",
-            symbols
+            "{}{} vs {} arbitrage (symbols: {:?}) — overlap from minute {}, parallel WS",
+            if self.name.is_empty() { String::new() } else { format!("[{}] ", self.name) },
+            periods.long_label(),
+            periods.short_label(),
+            symbols,
+            periods.overlap_start_offset_mins
         );
+        info!("   Strategy: {}", self.strategy.name());
         info!(
             "   Price-to-beat: RTDS Chainlink (all symbols in one WS); per-symbol tolerance"
         );
@@ -234,39 +924,387 @@ impl ArbStrategy {
         );
         info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
-        let cumulative_pnl: Arc<RwLock<f64>> = Arc::new(RwLock::new(0.0));
-        let rtds_url = self.config.polymarket.rtds_ws_url.clone();
-        let cache_15 = Arc::clone(&self.price_cache_15);
-        let cache_5 = Arc::clone(&self.price_cache_5);
-        let symbols_rtds = symbols.clone();
-        if let Err(e) = run_chainlink_multi_poller(rtds_url, symbols_rtds, cache_15, cache_5).await {
-            warn!("RTDS Chainlink poller start: {}", e);
+        let cumulative_pnl = Arc::clone(&self.cumulative_pnl);
+        let discovery = &self.config.strategy.symbol_discovery;
+        if let Some(symbols_rtds) = self.feed_symbols.clone() {
+            let rtds_url = self.config.polymarket.rtds_ws_url.clone();
+            let cache_long = Arc::clone(&self.price_cache_long);
+            let cache_short = Arc::clone(&self.price_cache_short);
+            let spot_history = Arc::clone(&self.spot_history);
+            if let Err(e) = run_chainlink_multi_poller(
+                rtds_url,
+                symbols_rtds,
+                periods,
+                cache_long,
+                cache_short,
+                None,
+                Some(spot_history),
+                &self.supervisor,
+            )
+            .await
+            {
+                warn!("RTDS Chainlink poller start: {}", e);
+            }
         }
         sleep(Duration::from_secs(2)).await;
 
-        let mut handles = Vec::new();
-        for symbol in symbols.clone() {
+        if self.config.strategy.auto_redeem && !self.config.strategy.simulation_mode {
             let api = Arc::clone(&self.api);
-            let config = self.config.clone();
-            let price_cache_15 = Arc::clone(&self.price_cache_15);
-            let price_cache_5 = Arc::clone(&self.price_cache_5);
+            let journal = Arc::clone(&self.journal);
+            let inventory = Arc::clone(&self.inventory);
+            let redeem_queue = Arc::clone(&self.redeem_queue);
+            self.track(self.supervisor.spawn("redeem retries", move || {
+                let (api, journal, inventory) = (Arc::clone(&api), Arc::clone(&journal), Arc::clone(&inventory));
+                let redeem_queue = Arc::clone(&redeem_queue);
+                async move {
+                    loop {
+                        redeem_queue.retry_due(api.as_ref(), &journal, &inventory).await;
+                        sleep(Duration::from_secs(REDEEM_RETRY_POLL_SECS)).await;
+                    }
+                }
+            }));
+        }
+
+        {
+            let (api, config) = (Arc::clone(&self.api), self.config.clone());
+            let pending = Arc::clone(&self.pending_resolutions);
+            let (journal, inventory) = (Arc::clone(&self.journal), Arc::clone(&self.inventory));
+            let (redeem_queue, events) = (Arc::clone(&self.redeem_queue), self.events.clone());
             let cumulative_pnl = Arc::clone(&cumulative_pnl);
-            handles.push(tokio::spawn(async move {
-                if let Err(e) = Self::run_symbol_loop(
-                    api,
-                    config,
-                    price_cache_15,
-                    price_cache_5,
-                    cumulative_pnl,
-                    symbol.clone(),
+            let clock = Arc::clone(&self.clock);
+            self.track(self.supervisor.spawn("pending resolutions", move || {
+                run_pending_resolutions(
+                    Arc::clone(&api),
+                    Arc::clone(&clock),
+                    config.clone(),
+                    Arc::clone(&pending),
+                    Arc::clone(&cumulative_pnl),
+                    Arc::clone(&journal),
+                    Arc::clone(&inventory),
+                    Arc::clone(&redeem_queue),
+                    events.clone(),
                 )
-                .await
-                {
-                    error!("Symbol loop {} failed: {}", symbol, e);
+                .map(Ok)
+            }));
+        }
+
+        let mut signals = Box::pin(self.signals());
+        let events = self.events.clone();
+        self.track(tokio::spawn(async move {
+            while let Some(signal) = signals.next().await {
+                events.publish(ArbEvent::Opportunity(signal));
+            }
+        }));
+
+        let api = Arc::clone(&self.api);
+        self.track(self.supervisor.spawn("clock sync", move || {
+            let api = Arc::clone(&api);
+            async move {
+                loop {
+                    sleep(Duration::from_secs(CLOCK_SYNC_INTERVAL_SECS)).await;
+                    if let Err(e) = api.sync_clock().await {
+                        warn!("Clock resync failed, keeping previous offset: {}", e);
+                    }
                 }
+            }
+        }));
+
+        let credential_secs = self.config.strategy.credential_check_secs;
+        if credential_secs > 0 && !self.config.strategy.simulation_mode {
+            let api = Arc::clone(&self.api);
+            self.track(self.supervisor.spawn("credential check", move || {
+                let api = Arc::clone(&api);
+                async move {
+                    loop {
+                        sleep(Duration::from_secs(credential_secs)).await;
+                        if let Err(e) = api.check_credentials().await {
+                            warn!("API credential check failed: {}", e);
+                        }
+                    }
+                }
+            }));
+        }
+
+        let activity_secs = self.config.strategy.activity_poll_secs;
+        if activity_secs > 0 && !self.config.strategy.simulation_mode && !self.config.strategy.signals_only {
+            let api = Arc::clone(&self.api);
+            let inventory = Arc::clone(&self.inventory);
+            let cursor_path = store_path(&self.config.strategy.activity_cursor_path);
+            self.track(self.supervisor.spawn("activity poll", move || {
+                let (api, inventory, cursor_path) = (Arc::clone(&api), Arc::clone(&inventory), cursor_path.clone());
+                async move {
+                    inventory.poll_activity(api.as_ref(), activity_secs, cursor_path.as_deref()).await;
+                    Ok(())
+                }
+            }));
+        }
+
+        let snapshot_mins = self.config.strategy.portfolio_snapshot_mins;
+        let snapshot_path = self.config.strategy.portfolio_snapshot_path.trim();
+        if snapshot_mins > 0 && !snapshot_path.is_empty() {
+            let api = Arc::clone(&self.api);
+            let inventory = Arc::clone(&self.inventory);
+            let unrealized = self.unrealized_pnl();
+            let cumulative_pnl = Arc::clone(&cumulative_pnl);
+            let snapshot_path = std::path::PathBuf::from(snapshot_path);
+            self.track(self.supervisor.spawn("portfolio snapshots", move || {
+                run_portfolio_snapshots(
+                    Arc::clone(&api),
+                    Arc::clone(&inventory),
+                    Arc::clone(&unrealized),
+                    Arc::clone(&cumulative_pnl),
+                    snapshot_path.clone(),
+                    snapshot_mins,
+                )
+                .map(Ok)
             }));
         }
+
+        if self.config.strategy.end_of_day.enabled() {
+            let (api, config, inventory) = (Arc::clone(&self.api), self.config.clone(), Arc::clone(&self.inventory));
+            let (redeem_queue, journal) = (Arc::clone(&self.redeem_queue), Arc::clone(&self.journal));
+            let cumulative_pnl = Arc::clone(&cumulative_pnl);
+            self.track(self.supervisor.spawn("end of day", move || {
+                run_end_of_day(
+                    Arc::clone(&api),
+                    config.clone(),
+                    Arc::clone(&inventory),
+                    Arc::clone(&redeem_queue),
+                    Arc::clone(&journal),
+                    Arc::clone(&cumulative_pnl),
+                )
+                .map(Ok)
+            }));
+        }
+
+        let mut handles = Vec::new();
+        for symbol in symbols.clone() {
+            handles.push(self.spawn_symbol_loop(symbol, &cumulative_pnl));
+        }
+        if discovery.enabled {
+            self.discover_symbols(&mut handles, &cumulative_pnl).await;
+        }
         futures_util::future::try_join_all(handles).await?;
+        // The symbol loops are done; what the background tasks hold is persisted, so they can end mid-wait.
+        self.abort_tasks();
+        let journal = Arc::clone(&self.journal);
+        if let Err(e) = tokio::task::spawn_blocking(move || journal.close()).await {
+            warn!("Journal close failed: {}", e);
+        }
         Ok(())
     }
+
+    /// Start a loop for every symbol discovery finds, until the strategy is stopped.
+    async fn discover_symbols(
+        &self,
+        handles: &mut Vec<tokio::task::JoinHandle<()>>,
+        cumulative_pnl: &Arc<PnlLedger>,
+    ) {
+        let discovery = &self.config.strategy.symbol_discovery;
+        let periods = self.config.strategy.periods;
+        let mut running: std::collections::HashSet<String> =
+            self.config.strategy.symbols.iter().map(|s| s.to_lowercase()).collect();
+        while !self.controls.is_stopping() {
+            match self
+                .discovery
+                .discover_updown_symbols(periods, discovery.min_liquidity_usd, discovery.min_volume_usd)
+                .await
+            {
+                Ok(found) => {
+                    for symbol in found {
+                        if running.insert(symbol.clone()) {
+                            info!("Symbol discovery: starting {} loop", symbol.to_uppercase());
+                            handles.push(self.spawn_symbol_loop(symbol, cumulative_pnl));
+                        }
+                    }
+                }
+                Err(e) => warn!("Symbol discovery failed: {}", e),
+            }
+            tokio::select! {
+                _ = sleep(Duration::from_secs(discovery.refresh_secs.max(OVERLAP_POLL_SECS))) => {}
+                _ = self.controls.stopped() => {}
+            }
+        }
+    }
+
+    fn spawn_symbol_loop(&self, symbol: String, cumulative_pnl: &Arc<PnlLedger>) -> tokio::task::JoinHandle<()> {
+        let api = Arc::clone(&self.api);
+        let config = self.config.clone();
+        let strategy = Arc::clone(&self.strategy);
+        let price_cache_long = Arc::clone(&self.price_cache_long);
+        let price_cache_short = Arc::clone(&self.price_cache_short);
+        let spot_history = Arc::clone(&self.spot_history);
+        let hedger = self.hedger.clone();
+        let unrealized_pnl = self.unrealized_pnl();
+        let journal = Arc::clone(&self.journal);
+        let inventory = Arc::clone(&self.inventory);
+        let redeem_queue = Arc::clone(&self.redeem_queue);
+        let pending_resolutions = Arc::clone(&self.pending_resolutions);
+        let skip_list = Arc::clone(&self.skip_list);
+        let canary = Arc::clone(&self.canary);
+        let active_periods = Arc::clone(&self.active_periods);
+        let signals = self.signals.clone();
+        let events = self.events.clone();
+        let controls = Arc::clone(&self.controls);
+        let cumulative_pnl = Arc::clone(cumulative_pnl);
+        let clock = Arc::clone(&self.clock);
+        let name = format!("{} loop", symbol.to_lowercase());
+        self.track(self.supervisor.spawn(name, move || {
+            Self::run_symbol_loop(
+                Arc::clone(&api),
+                config.clone(),
+                Arc::clone(&strategy),
+                Arc::clone(&price_cache_long),
+                Arc::clone(&price_cache_short),
+                Arc::clone(&spot_history),
+                hedger.clone(),
+                Arc::clone(&unrealized_pnl),
+                Arc::clone(&journal),
+                Arc::clone(&inventory),
+                Arc::clone(&redeem_queue),
+                Arc::clone(&pending_resolutions),
+                Arc::clone(&skip_list),
+                Arc::clone(&canary),
+                Arc::clone(&active_periods),
+                signals.clone(),
+                events.clone(),
+                Arc::clone(&controls),
+                Arc::clone(&cumulative_pnl),
+                Arc::clone(&clock),
+                symbol.clone(),
+            )
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::models::{JournalEvent, OpenOrder, Side, SignalLeg};
+
+    #[test]
+    fn sweep_cancels_only_the_bots_resting_orders_and_alerts() {
+        let api = Arc::new(MockPolymarketApi::new().with_wallet("0xwallet", 100.0));
+        for id in ["0xleftover", "0xmanual"] {
+            api.add_open_order(OpenOrder {
+                id: id.to_string(),
+                market: "0xc15".to_string(),
+                side: Side::Buy,
+                ..OpenOrder::default()
+            });
+        }
+        let mut config = Config::default();
+        config.strategy.simulation_mode = false;
+        config.strategy.journal_path = String::new();
+        config.strategy.redeem_queue_path = String::new();
+        config.strategy.pending_resolution_path = String::new();
+        config.strategy.pnl_state_path = String::new();
+        config.strategy.skip_list_path = String::new();
+        config.strategy.canary_state_path = String::new();
+        let strategy = ArbStrategy::new(api.clone(), config);
+        strategy.journal.record(
+            Some("btc-1"),
+            JournalEvent::OrderAcked { leg: 1, order_id: Some("0xleftover".to_string()), status: "live".to_string() },
+        );
+        let mut events = Box::pin(strategy.events().subscribe());
+
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        assert_eq!(rt.block_on(strategy.sweep_stray_orders("startup")), 1);
+        assert_eq!(api.cancelled(), vec!["0xleftover".to_string()]);
+        assert!(matches!(rt.block_on(events.next()), Some(ArbEvent::Alert { kind, .. }) if kind == "stray_orders"));
+        assert_eq!(rt.block_on(strategy.sweep_stray_orders("shutdown")), 0, "nothing left to cancel");
+    }
+
+    #[tokio::test]
+    async fn a_symbols_next_period_trades_while_its_earlier_ones_resolve() {
+        let strategy = ArbStrategy::new(Arc::new(MockPolymarketApi::new()), Config::default());
+        let period = |symbol: &str, period_15: i64| PeriodContext {
+            symbol: symbol.to_string(),
+            period_15,
+            period_5: period_15 + 600,
+            cid_15: String::new(),
+            cid_5: String::new(),
+            t15_up: String::new(),
+            t15_down: String::new(),
+            t5_up: String::new(),
+            t5_down: String::new(),
+            strike_15: 0.0,
+            strike_5: 0.0,
+        };
+        let (first, second) = (period("BTC", 1767726000), period("btc", 1767726900));
+        assert_eq!(strategy.begin_period(&first), 0);
+        assert_eq!(strategy.begin_period(&period("eth", 1767726000)), 0, "other symbols aren't counted");
+        assert_eq!(strategy.begin_period(&second), 1);
+        strategy.unrealized_pnl.write().await.extend([(first.key(), -1.5), (second.key(), 2.0)]);
+
+        strategy.finish_period(&first).await;
+        assert_eq!(strategy.state().await.active_periods, ["btc-1767726900", "eth-1767726000"]);
+        assert_eq!(*strategy.unrealized_pnl.read().await, HashMap::from([(second.key(), 2.0)]));
+        assert_eq!(strategy.begin_period(&period("btc", 1767727800)), 1);
+    }
+
+    #[tokio::test]
+    async fn a_lagging_signal_subscriber_skips_the_oldest_signals_and_the_stream_ends_with_the_bot() {
+        let strategy = ArbStrategy::new(Arc::new(MockPolymarketApi::new()), Config::default());
+        let signals = strategy.signals();
+        let leg = |outcome: &str| SignalLeg {
+            token_id: outcome.to_lowercase(),
+            outcome: outcome.to_string(),
+            ask: 0.45,
+            fee: 0.0,
+            mid: None,
+            spread: None,
+        };
+        let sent = SIGNAL_CHANNEL_CAPACITY as i64 + 2;
+        for detected_at_ms in 0..sent {
+            let signal = ArbSignal {
+                symbol: "btc".to_string(),
+                period_15: 0,
+                period_5: 0,
+                cid_15: "0x15".to_string(),
+                cid_5: "0x5".to_string(),
+                leg1: leg("Up"),
+                leg2: leg("Down"),
+                effective_sum: 0.9,
+                edge: 0.05,
+                detected_at_ms,
+                model: None,
+            };
+            strategy.signals.send(signal).unwrap();
+        }
+        drop(strategy);
+        let received: Vec<i64> = signals.map(|s| s.detected_at_ms).collect().await;
+        assert_eq!(received, (2..sent).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn trading_holds_while_a_deposit_is_younger_than_the_settle_time() {
+        let api = Arc::new(MockPolymarketApi::new().with_wallet("0xwallet", 100.0));
+        let mut config = Config::default();
+        config.strategy.simulation_mode = false;
+        config.strategy.funding_settle_secs = 60;
+        let strategy = ArbStrategy::new(api.clone(), config.clone());
+        assert!(!strategy.deposit_settling("btc").await, "no deposit");
+
+        // 60s is 30 Polygon blocks: a deposit 20 blocks back still settles, 31 back no longer does.
+        api.deposit_usdc(1000, 25.0);
+        api.set_head_block(1020);
+        assert!(strategy.deposit_settling("btc").await);
+        api.set_head_block(1031);
+        assert!(!strategy.deposit_settling("btc").await);
+
+        // Near genesis the lookback reaches back to block 0.
+        let api = Arc::new(MockPolymarketApi::new().with_wallet("0xwallet", 100.0));
+        api.deposit_usdc(0, 5.0);
+        let strategy = ArbStrategy::new(api.clone(), config.clone());
+        assert!(strategy.deposit_settling("btc").await);
+
+        // Off, or paper trading: never held.
+        for (settle_secs, simulation_mode) in [(0, false), (60, true)] {
+            config.strategy.funding_settle_secs = settle_secs;
+            config.strategy.simulation_mode = simulation_mode;
+            assert!(!ArbStrategy::new(api.clone(), config.clone()).deposit_settling("btc").await);
+        }
+    }
 }
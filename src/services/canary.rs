@@ -0,0 +1,108 @@
+//! Canary sizing after execution config changes. The execution settings last traded cleanly are stored
+//! in a JSON file; when the current ones differ, arbs go out at `canary_fraction` of `arb_shares` until both
+//! legs of one are confirmed filled, after which the new settings are recorded and full size resumes.
+
+use crate::config::StrategyConfig;
+use crate::utils::json_store::{persist, read_json, store_path};
+use log::{info, warn};
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_json::Value;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub struct Canary {
+    path: Option<PathBuf>,
+    fraction: f64,
+    settings: Value,
+    pending: AtomicBool,
+}
+
+impl Canary {
+    /// Canary state for `strategy`, compared against the settings stored at `canary_state_path`.
+    pub fn load(strategy: &StrategyConfig) -> Self {
+        let path = store_path(&strategy.canary_state_path);
+        let fraction = strategy.canary_fraction;
+        let settings = strategy.execution_settings();
+        let validated = path
+            .as_ref()
+            .filter(|p| p.exists())
+            .and_then(|p| match read_json::<Value>(p, "canary state") {
+                Ok(settings) => Some(settings),
+                Err(e) => {
+                    warn!("Canary state {} unreadable: {:#}", p.display(), e);
+                    None
+                }
+            });
+        let pending = fraction > 0.0 && fraction < 1.0 && validated.as_ref() != Some(&settings);
+        if pending {
+            info!(
+                "Execution settings changed since the last clean trade; next arb at {:.0}% size (canary)",
+                fraction * 100.0
+            );
+        }
+        Self {
+            path,
+            fraction,
+            settings,
+            pending: AtomicBool::new(pending),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    /// Canary size for `shares` per leg, floored to 2 decimals but never below the market's `min_size` (nor above
+    /// `shares`), so the canary isn't rejected for being too small.
+    pub fn scaled(&self, shares: &str, min_size: Decimal) -> String {
+        let full: Decimal = shares.trim().parse().unwrap_or_default();
+        let fraction = Decimal::try_from(self.fraction).unwrap_or_default();
+        let scaled = (full * fraction).round_dp_with_strategy(2, RoundingStrategy::ToZero);
+        scaled.max(min_size).min(full).normalize().to_string()
+    }
+
+    /// Both legs of the canary filled: record the settings as validated and return to full size.
+    pub fn passed(&self) {
+        if !self.pending.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        info!("Canary arb filled on both legs; trading full size from now on");
+        persist(self.path.as_deref(), &self.settings, "Canary state");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn canary(path: &str) -> Canary {
+        Canary::load(&StrategyConfig {
+            canary_fraction: 0.1,
+            canary_state_path: path.to_string(),
+            ..crate::config::Config::default().strategy
+        })
+    }
+
+    #[test]
+    fn scaled_is_clamped_to_the_minimum_order_size() {
+        let canary = canary("");
+        assert_eq!(canary.scaled("55", Decimal::ZERO), "5.5");
+        assert_eq!(canary.scaled("55", dec!(5)), "5.5");
+        assert_eq!(canary.scaled("20", dec!(5)), "5");
+        assert_eq!(canary.scaled("3", dec!(5)), "3");
+    }
+
+    #[test]
+    fn passing_records_the_settings_once() {
+        let path = std::env::temp_dir().join(format!("canary-state-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let first = canary(&path.display().to_string());
+        assert!(first.is_pending());
+        first.passed();
+        assert!(!first.is_pending());
+        assert!(path.exists());
+        assert!(!canary(&path.display().to_string()).is_pending());
+        let _ = std::fs::remove_file(&path);
+    }
+}
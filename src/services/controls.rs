@@ -0,0 +1,148 @@
+//! Settings an operator can change while the bot runs (see `services::admin`): pausing new trades globally or
+//! per symbol, and overriding `sum_threshold` and `arb_shares`. The overlap loop reads them on every tick, along
+//! with the stop request that ends the round early when the bot shuts down.
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+#[derive(Debug, Default)]
+pub struct RuntimeControls {
+    stopping: AtomicBool,
+    stop_requested: Notify,
+    paused: AtomicBool,
+    paused_symbols: Mutex<BTreeSet<String>>,
+    sum_threshold: Mutex<Option<f64>>,
+    arb_shares: Mutex<Option<String>>,
+}
+
+/// Current controls, as reported by the admin API.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ControlsState {
+    pub paused: bool,
+    pub paused_symbols: Vec<String>,
+    pub sum_threshold: Option<f64>,
+    pub arb_shares: Option<String>,
+}
+
+impl RuntimeControls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop new trades for `symbol`, or for every symbol when None. Open positions still resolve and redeem.
+    pub fn pause(&self, symbol: Option<&str>) {
+        match symbol {
+            Some(symbol) => {
+                lock(&self.paused_symbols).insert(symbol.to_lowercase());
+            }
+            None => self.paused.store(true, Ordering::Relaxed),
+        }
+    }
+
+    /// Resume `symbol`, or lift the global pause and every symbol pause when None.
+    pub fn resume(&self, symbol: Option<&str>) {
+        match symbol {
+            Some(symbol) => {
+                lock(&self.paused_symbols).remove(&symbol.to_lowercase());
+            }
+            None => {
+                self.paused.store(false, Ordering::Relaxed);
+                lock(&self.paused_symbols).clear();
+            }
+        }
+    }
+
+    pub fn is_paused(&self, symbol: &str) -> bool {
+        self.paused.load(Ordering::Relaxed) || lock(&self.paused_symbols).contains(&symbol.to_lowercase())
+    }
+
+    /// Shut down: the overlap round in progress enters no new trades and winds up, and no new round starts.
+    pub fn stop(&self) {
+        self.stopping.store(true, Ordering::Relaxed);
+        self.stop_requested.notify_waiters();
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.stopping.load(Ordering::Relaxed)
+    }
+
+    /// Resolves once `stop` is called.
+    pub async fn stopped(&self) {
+        loop {
+            let requested = self.stop_requested.notified();
+            if self.is_stopping() {
+                return;
+            }
+            requested.await;
+        }
+    }
+
+    /// Override `strategy.sum_threshold`; None restores the configured value.
+    pub fn set_sum_threshold(&self, threshold: Option<f64>) {
+        *lock(&self.sum_threshold) = threshold;
+    }
+
+    pub fn sum_threshold(&self) -> Option<f64> {
+        *lock(&self.sum_threshold)
+    }
+
+    /// Override `strategy.arb_shares`; None restores the configured value.
+    pub fn set_arb_shares(&self, shares: Option<String>) {
+        *lock(&self.arb_shares) = shares;
+    }
+
+    pub fn arb_shares(&self) -> Option<String> {
+        lock(&self.arb_shares).clone()
+    }
+
+    pub fn state(&self) -> ControlsState {
+        ControlsState {
+            paused: self.paused.load(Ordering::Relaxed),
+            paused_symbols: lock(&self.paused_symbols).iter().cloned().collect(),
+            sum_threshold: self.sum_threshold(),
+            arb_shares: self.arb_shares(),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_resume_clears_symbol_pauses() {
+        let controls = RuntimeControls::new();
+        controls.pause(Some("BTC"));
+        assert!(controls.is_paused("btc"));
+        assert!(!controls.is_paused("eth"));
+        controls.pause(None);
+        assert!(controls.is_paused("eth"));
+        controls.resume(None);
+        assert!(!controls.is_paused("btc"));
+        assert!(!controls.is_paused("eth"));
+    }
+
+    #[test]
+    fn stopped_wakes_on_stop() {
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let controls = std::sync::Arc::new(RuntimeControls::new());
+        rt.block_on(async {
+            let waiting = tokio::spawn({
+                let controls = std::sync::Arc::clone(&controls);
+                async move { controls.stopped().await }
+            });
+            tokio::task::yield_now().await;
+            assert!(!waiting.is_finished());
+            controls.stop();
+            waiting.await.unwrap();
+        });
+        assert!(controls.is_stopping());
+    }
+}
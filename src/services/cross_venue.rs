@@ -0,0 +1,767 @@
+//! Cross-venue arbitrage (`cross-venue` subcommand): Polymarket and Kalshi both list 15-minute crypto up/down
+//! markets for the same windows. When Polymarket Up plus Kalshi No (or Polymarket Down plus Kalshi Yes) costs
+//! less than 1 minus both venues' fees and `min_edge`, buy both. Each leg is placed, resolved and settled
+//! through its own venue. A window with trades is saved until both venues resolved it; one still open after
+//! `resolution_max_wait_secs` is reported as a `resolution_delayed` alert and polled until it resolves, also
+//! after a restart.
+
+use crate::adapters::kalshi::KalshiApi;
+use crate::adapters::polymarket::PolymarketApi;
+use crate::adapters::venue::{PredictionMarketVenue, VenueMarket};
+use crate::config::Config;
+use crate::domain::cross_venue::{cheapest_pair, dead_zone_bps, kalshi_fee, CrossPair};
+use crate::domain::fees::effective_price;
+use crate::domain::trade_ids::TradeIds;
+use crate::events::{ArbEvent, EventBus};
+use crate::models::{JournalEvent, OrderBook, OrderRequest, OrderType, Side};
+use crate::services::execution_service::{fully_matched, place_leg};
+use crate::services::journal::Journal;
+use crate::services::webhook::spawn_webhook_sink;
+use crate::utils::clock::SystemClock;
+use crate::utils::json_store::{load_or_default, persist, store_path};
+use crate::utils::slug_builder::updown_series;
+use crate::utils::time_windows::period_start_et_unix;
+use anyhow::Result;
+use chrono::{SecondsFormat, TimeZone, Utc};
+use log::{debug, info, warn};
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+use tokio::time::{sleep, Duration, Instant};
+
+const PERIOD_MINS: i64 = 15;
+/// Seconds between lookups while either venue hasn't listed the current window yet.
+const MARKET_RETRY_SECS: u64 = 10;
+/// Seconds between Polymarket price-to-beat lookups until it is published (about 2 minutes into the window).
+const STRIKE_RETRY_SECS: u64 = 10;
+
+/// The current window on both venues.
+struct PeriodMarkets {
+    period_start: i64,
+    polymarket: VenueMarket,
+    kalshi: VenueMarket,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CrossTrade {
+    trade_id: String,
+    polymarket_token: String,
+    kalshi_token: String,
+    /// Whether the (Polymarket, Kalshi) leg went through.
+    filled: (bool, bool),
+    contracts: Decimal,
+    /// USD paid for the filled legs, fees included.
+    cost: Decimal,
+}
+
+/// A window's trades waiting for both venues to resolve it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UnsettledWindow {
+    /// Upper-case symbol, as logged.
+    symbol: String,
+    period_start: i64,
+    polymarket_market: String,
+    kalshi_market: String,
+    trades: Vec<CrossTrade>,
+}
+
+/// Unsettled windows, persisted at `cross_venue.pending_path`.
+struct UnsettledWindows {
+    path: Option<PathBuf>,
+    items: Mutex<Vec<UnsettledWindow>>,
+}
+
+impl UnsettledWindows {
+    fn load(path: &str) -> Self {
+        let path = store_path(path);
+        let items: Vec<UnsettledWindow> = load_or_default(path.as_deref(), "Cross-venue pending windows");
+        if !items.is_empty() {
+            info!("Cross-venue: restored {} unsettled window(s)", items.len());
+        }
+        Self {
+            path,
+            items: Mutex::new(items),
+        }
+    }
+
+    fn all(&self) -> Vec<UnsettledWindow> {
+        self.items.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn add(&self, window: &UnsettledWindow) {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if !items.iter().any(|w| same_window(w, window)) {
+            items.push(window.clone());
+            persist(self.path.as_deref(), &*items, "Cross-venue pending windows");
+        }
+    }
+
+    fn remove(&self, window: &UnsettledWindow) {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items.retain(|w| !same_window(w, window));
+        persist(self.path.as_deref(), &*items, "Cross-venue pending windows");
+    }
+}
+
+fn same_window(a: &UnsettledWindow, b: &UnsettledWindow) -> bool {
+    (&a.polymarket_market, &a.kalshi_market) == (&b.polymarket_market, &b.kalshi_market)
+}
+
+/// Trade every symbol in `cross_venue.series` until stopped.
+pub async fn run_cross_venue(api: Arc<PolymarketApi>, config: Config) -> Result<()> {
+    let kalshi = Arc::new(KalshiApi::from_config(&config.kalshi)?);
+    if !config.strategy.simulation_mode && !kalshi.can_trade() {
+        anyhow::bail!("cross-venue needs kalshi.key_id and kalshi.private_key_path unless simulation_mode is on");
+    }
+    let cross = &config.strategy.cross_venue;
+    info!(
+        "Cross-venue: Polymarket vs Kalshi 15m up/down for {:?}, {} contracts per leg, min edge {}{}",
+        cross.series.keys().collect::<Vec<_>>(),
+        cross.contracts,
+        cross.min_edge,
+        if config.strategy.simulation_mode { " (simulation)" } else { "" }
+    );
+    let events = EventBus::new();
+    api.publish_alerts(&events);
+    // Never stopped: the sink runs as long as the process.
+    let (_shutdown, shutdown_rx) = watch::channel(false);
+    let _webhook = spawn_webhook_sink(&config.webhook, &events, shutdown_rx);
+    let journal = Arc::new(Journal::new(&config.strategy.journal_path).with_events(events));
+    let unsettled = Arc::new(UnsettledWindows::load(&cross.pending_path));
+    let config = Arc::new(config);
+    for window in unsettled.all() {
+        tokio::spawn(settle(
+            api.clone(),
+            kalshi.clone(),
+            Arc::clone(&journal),
+            Arc::clone(&config),
+            Arc::clone(&unsettled),
+            window,
+        ));
+    }
+    let loops: Vec<_> = config
+        .strategy
+        .cross_venue
+        .series
+        .iter()
+        .map(|(symbol, series)| {
+            tokio::spawn(run_symbol(
+                Arc::clone(&api),
+                Arc::clone(&kalshi),
+                Arc::clone(&journal),
+                Arc::clone(&config),
+                Arc::clone(&unsettled),
+                symbol.clone(),
+                series.clone(),
+            ))
+        })
+        .collect();
+    for handle in loops {
+        handle.await?;
+    }
+    Ok(())
+}
+
+async fn run_symbol(
+    api: Arc<PolymarketApi>,
+    kalshi: Arc<KalshiApi>,
+    journal: Arc<Journal>,
+    config: Arc<Config>,
+    unsettled: Arc<UnsettledWindows>,
+    symbol: String,
+    series: String,
+) {
+    let sym = symbol.to_uppercase();
+    loop {
+        let period_start = period_start_et_unix(&SystemClock, PERIOD_MINS);
+        let period_end = period_start + PERIOD_MINS * 60;
+        let markets = match find_markets(api.as_ref(), kalshi.as_ref(), &symbol, &series, period_start).await {
+            Ok(Some(m)) => m,
+            Ok(None) => {
+                debug!("{} cross-venue: window {} not listed on both venues yet", sym, period_start);
+                sleep(Duration::from_secs(MARKET_RETRY_SECS)).await;
+                continue;
+            }
+            Err(e) => {
+                warn!("{} cross-venue market lookup: {}", sym, e);
+                sleep(Duration::from_secs(MARKET_RETRY_SECS)).await;
+                continue;
+            }
+        };
+        info!(
+            "{} cross-venue window {}: Polymarket {} vs Kalshi {}",
+            sym, period_start, markets.polymarket.id, markets.kalshi.id
+        );
+        let trades = trade_window(&api, &kalshi, &journal, &config, &symbol, &markets).await;
+
+        let remaining = period_end - Utc::now().timestamp();
+        if remaining > 0 {
+            sleep(Duration::from_secs(remaining as u64)).await;
+        }
+        if !trades.is_empty() {
+            let window = UnsettledWindow {
+                symbol: sym.clone(),
+                period_start: markets.period_start,
+                polymarket_market: markets.polymarket.id,
+                kalshi_market: markets.kalshi.id,
+                trades,
+            };
+            tokio::spawn(settle(
+                api.clone(),
+                kalshi.clone(),
+                Arc::clone(&journal),
+                Arc::clone(&config),
+                Arc::clone(&unsettled),
+                window,
+            ));
+        }
+    }
+}
+
+/// Both venues' markets for the window starting at `period_start`; None until both are listed. The Kalshi
+/// market is the one of `series` closing when the Polymarket window ends.
+async fn find_markets(
+    polymarket: &dyn PredictionMarketVenue,
+    kalshi: &dyn PredictionMarketVenue,
+    symbol: &str,
+    series: &str,
+    period_start: i64,
+) -> Result<Option<PeriodMarkets>> {
+    let period_end = period_start + PERIOD_MINS * 60;
+    let Some(polymarket) = polymarket
+        .find_market(&updown_series(symbol, PERIOD_MINS), period_end)
+        .await?
+    else {
+        return Ok(None);
+    };
+    let Some(kalshi) = kalshi.find_market(series, period_end).await? else {
+        return Ok(None);
+    };
+    Ok(Some(PeriodMarkets {
+        period_start,
+        polymarket,
+        kalshi,
+    }))
+}
+
+/// Poll both books until `no_trade_before_close_secs` before the close, taking pairs that clear the edge.
+async fn trade_window(
+    api: &PolymarketApi,
+    kalshi: &KalshiApi,
+    journal: &Journal,
+    config: &Config,
+    symbol: &str,
+    markets: &PeriodMarkets,
+) -> Vec<CrossTrade> {
+    let cross = &config.strategy.cross_venue;
+    let period_start = markets.period_start;
+    let period_end = period_start + PERIOD_MINS * 60;
+    let deadline = period_end - config.strategy.no_trade_before_close_secs as i64;
+    let mut polymarket_strike = markets.polymarket.strike;
+    let mut last_strike_lookup: Option<std::time::Instant> = None;
+    let mut trades = Vec::new();
+    let mut trade_ids = TradeIds::new(&format!("{}-kalshi", symbol), period_start);
+    while Utc::now().timestamp() < deadline
+        && (cross.max_trades_per_period == 0 || trades.len() < cross.max_trades_per_period as usize)
+    {
+        if polymarket_strike.is_none()
+            && last_strike_lookup.is_none_or(|t| t.elapsed().as_secs() >= STRIKE_RETRY_SECS)
+        {
+            last_strike_lookup = Some(std::time::Instant::now());
+            polymarket_strike = polymarket_price_to_beat(api, symbol, period_start).await;
+        }
+        // Without both strikes the range where both legs lose is unknown, so nothing is traded.
+        if let (Some(pm_strike), Some(kalshi_strike)) = (polymarket_strike, markets.kalshi.strike) {
+            let trade_id = trade_ids.next(Utc::now().timestamp_millis());
+            if let Some(trade) =
+                try_pair(api, kalshi, journal, config, symbol, markets, (pm_strike, kalshi_strike), trade_id).await
+            {
+                trades.push(trade);
+            }
+        }
+        sleep(Duration::from_millis(cross.poll_ms)).await;
+    }
+    trades
+}
+
+async fn polymarket_price_to_beat(api: &PolymarketApi, symbol: &str, period_start: i64) -> Option<f64> {
+    let iso = |ts: i64| {
+        Utc.timestamp_opt(ts, 0)
+            .single()
+            .map(|t| t.to_rfc3339_opts(SecondsFormat::Secs, true))
+    };
+    let (start, end) = (iso(period_start)?, iso(period_start + PERIOD_MINS * 60)?);
+    match api
+        .get_crypto_price_to_beat(&symbol.to_uppercase(), &start, "fifteen", &end)
+        .await
+    {
+        Ok(strike) => strike,
+        Err(e) => {
+            debug!("{} price-to-beat lookup: {}", symbol.to_uppercase(), e);
+            None
+        }
+    }
+}
+
+/// Best ask with at least `size` behind it.
+fn best_ask(book: &OrderBook, size: Decimal) -> Option<Decimal> {
+    book.asks.iter().filter(|l| l.size >= size).map(|l| l.price).min()
+}
+
+/// Check the cheapest pair at the current asks and buy it if it clears the edge and the strikes' dead zone.
+/// The Kalshi leg goes first, fill-or-kill, since its books are thinner; the Polymarket leg follows only once
+/// it has matched.
+#[allow(clippy::too_many_arguments)]
+async fn try_pair(
+    api: &PolymarketApi,
+    kalshi: &KalshiApi,
+    journal: &Journal,
+    config: &Config,
+    symbol: &str,
+    markets: &PeriodMarkets,
+    strikes: (f64, f64),
+    trade_id: String,
+) -> Option<CrossTrade> {
+    let cross = &config.strategy.cross_venue;
+    // Kalshi only trades whole contracts; the Polymarket leg buys the same number of shares.
+    let contracts = Decimal::from_f64(cross.contracts).unwrap_or_default().floor();
+    let books: [(&dyn PredictionMarketVenue, &str); 4] = [
+        (api, &markets.polymarket.yes_token),
+        (api, &markets.polymarket.no_token),
+        (kalshi, &markets.kalshi.yes_token),
+        (kalshi, &markets.kalshi.no_token),
+    ];
+    let asks: Vec<Option<Decimal>> =
+        futures_util::future::join_all(books.iter().map(|(venue, token)| venue.get_orderbook(token)))
+            .await
+            .into_iter()
+            .map(|book| book.ok().and_then(|b| best_ask(&b, contracts)))
+            .collect();
+    let polymarket_fee_bps = config.strategy.fees.taker_bps_15m;
+    let pair = cheapest_pair(
+        (asks[0], asks[1]),
+        (asks[2], asks[3]),
+        polymarket_fee_bps,
+        config.kalshi.fee_rate,
+        contracts,
+    )?;
+    let threshold = Decimal::ONE - Decimal::from_f64(cross.min_edge).unwrap_or_default();
+    if pair.cost >= threshold {
+        return None;
+    }
+    let dead_zone = dead_zone_bps(strikes.0, strikes.1, pair.polymarket_up);
+    if dead_zone > cross.max_dead_zone_bps {
+        debug!(
+            "{} cross-venue: pair at {:.4} skipped, strikes {} / {} leave a {:.1} bps dead zone",
+            symbol.to_uppercase(),
+            pair.cost,
+            strikes.0,
+            strikes.1,
+            dead_zone
+        );
+        return None;
+    }
+
+    let (pm_outcome, kalshi_outcome) = if pair.polymarket_up { ("Up", "No") } else { ("Down", "Yes") };
+    let (polymarket_token, kalshi_token) = if pair.polymarket_up {
+        (markets.polymarket.yes_token.clone(), markets.kalshi.no_token.clone())
+    } else {
+        (markets.polymarket.no_token.clone(), markets.kalshi.yes_token.clone())
+    };
+    let simulation = config.strategy.simulation_mode;
+    journal.record(
+        Some(&trade_id),
+        JournalEvent::Decision {
+            symbol: symbol.to_string(),
+            period_15: markets.period_start,
+            period_5: markets.period_start,
+            cid_15: markets.polymarket.id.clone(),
+            cid_5: markets.kalshi.id.clone(),
+            leg1_outcome: pm_outcome.to_string(),
+            leg1_price: pair.polymarket_ask.to_f64().unwrap_or(0.0),
+            leg2_outcome: kalshi_outcome.to_string(),
+            leg2_price: pair.kalshi_ask.to_f64().unwrap_or(0.0),
+            effective_sum: pair.cost.to_f64().unwrap_or(0.0),
+            threshold: threshold.to_f64().unwrap_or(0.0),
+            simulated: simulation,
+            model: None,
+        },
+    );
+    info!(
+        "{} cross-venue arb {}: Polymarket {} @ {:.4} + Kalshi {} @ {:.2} = {:.4} with fees (< {}){}",
+        symbol.to_uppercase(),
+        trade_id,
+        pm_outcome,
+        pair.polymarket_ask,
+        kalshi_outcome,
+        pair.kalshi_ask,
+        pair.cost,
+        threshold,
+        if simulation { " [simulated]" } else { "" }
+    );
+
+    let mut trade = CrossTrade {
+        trade_id,
+        polymarket_token,
+        kalshi_token,
+        filled: (true, true),
+        contracts,
+        cost: Decimal::ZERO,
+    };
+    if !simulation {
+        trade.filled = place_pair(api, kalshi, journal, &trade, &pair).await?;
+    }
+    trade.cost = leg_costs(&pair, polymarket_fee_bps, config.kalshi.fee_rate, contracts, trade.filled);
+    Some(trade)
+}
+
+/// Kalshi leg, then the Polymarket leg, both fill-or-kill for `trade.contracts`. None when the Kalshi leg didn't
+/// match, so nothing is held; otherwise which legs filled.
+async fn place_pair(
+    polymarket: &dyn PredictionMarketVenue,
+    kalshi: &dyn PredictionMarketVenue,
+    journal: &Journal,
+    trade: &CrossTrade,
+    pair: &CrossPair,
+) -> Option<(bool, bool)> {
+    let buy = |token: &str, size: String, price: String| OrderRequest {
+        token_id: token.to_string(),
+        side: Side::Buy,
+        size,
+        price,
+        order_type: OrderType::Fok,
+        expiration: None,
+        client_order_id: None,
+    };
+    let kalshi_order = buy(&trade.kalshi_token, trade.contracts.to_string(), format!("{:.2}", pair.kalshi_ask));
+    match place_leg(kalshi, journal, &trade.trade_id, 2, &kalshi_order).await {
+        Ok(res) if res.status.eq_ignore_ascii_case("matched") => {}
+        Ok(res) => {
+            if let Some(order_id) = res.order_id.as_deref() {
+                if let Err(e) = kalshi.cancel_order(order_id).await {
+                    warn!("Cancel of unconfirmed Kalshi leg {} failed: {}", order_id, e);
+                }
+            }
+            warn!("Cross arb {}: Kalshi leg not filled (status {})", trade.trade_id, res.status);
+            return None;
+        }
+        Err(e) => {
+            warn!("Cross arb {}: Kalshi leg failed: {}", trade.trade_id, e);
+            return None;
+        }
+    }
+    let polymarket_order = buy(
+        &trade.polymarket_token,
+        trade.contracts.to_string(),
+        format!("{:.4}", pair.polymarket_ask),
+    );
+    match place_leg(polymarket, journal, &trade.trade_id, 1, &polymarket_order).await {
+        Ok(res) if res.status.eq_ignore_ascii_case("matched") => Some((true, true)),
+        Ok(res) => {
+            // Not confirmed filled: cancel whatever is left, and count the leg only if the CLOB reports it whole.
+            let filled = match res.order_id.as_deref() {
+                Some(order_id) => {
+                    if let Err(e) = polymarket.cancel_order(order_id).await {
+                        warn!("Cancel of unconfirmed Polymarket leg {} failed: {}", order_id, e);
+                    }
+                    polymarket.get_order_status(order_id).await.is_ok_and(|s| fully_matched(&s))
+                }
+                None => false,
+            };
+            if !filled {
+                warn!(
+                    "Cross arb {}: Polymarket leg not filled (status {}), holding Kalshi only",
+                    trade.trade_id, res.status
+                );
+            }
+            Some((filled, true))
+        }
+        Err(e) => {
+            warn!(
+                "Cross arb {}: Polymarket leg failed after the Kalshi leg filled, holding Kalshi only: {}",
+                trade.trade_id, e
+            );
+            Some((false, true))
+        }
+    }
+}
+
+/// USD paid for the filled legs at the pair's asks, fees included.
+fn leg_costs(
+    pair: &CrossPair,
+    polymarket_fee_bps: f64,
+    kalshi_fee_rate: f64,
+    contracts: Decimal,
+    filled: (bool, bool),
+) -> Decimal {
+    let mut cost = Decimal::ZERO;
+    if filled.0 {
+        cost += contracts * effective_price(pair.polymarket_ask, polymarket_fee_bps);
+    }
+    if filled.1 {
+        cost += contracts * pair.kalshi_ask + kalshi_fee(pair.kalshi_ask, contracts, kalshi_fee_rate);
+    }
+    cost
+}
+
+/// Wait for both venues to resolve the window, journal each trade's PnL, then settle the winning legs on
+/// their own venue (a Polymarket redemption; Kalshi credits the balance itself). The window stays in
+/// `unsettled` until then; past `resolution_max_wait_secs` it is alerted once and polled every
+/// `pending_resolution_poll_secs`.
+async fn settle(
+    api: Arc<dyn PredictionMarketVenue>,
+    kalshi: Arc<dyn PredictionMarketVenue>,
+    journal: Arc<Journal>,
+    config: Arc<Config>,
+    unsettled: Arc<UnsettledWindows>,
+    window: UnsettledWindow,
+) {
+    unsettled.add(&window);
+    let UnsettledWindow {
+        symbol: sym,
+        period_start,
+        trades,
+        ..
+    } = &window;
+    let poll = Duration::from_secs(config.strategy.resolution_poll_interval_secs.max(1));
+    let slow_poll = Duration::from_secs(config.strategy.pending_resolution_poll_secs.max(1));
+    let max_wait = config.strategy.resolution_max_wait_secs;
+    let started = Instant::now();
+    let mut delayed = false;
+    let venues: [(&dyn PredictionMarketVenue, &str); 2] =
+        [(api.as_ref(), &window.polymarket_market), (kalshi.as_ref(), &window.kalshi_market)];
+    let mut winners: [Option<(String, String)>; 2] = [None, None];
+    loop {
+        for ((venue, market), winner) in venues.iter().zip(winners.iter_mut()) {
+            if winner.is_none() {
+                match venue.resolved_winner(market).await {
+                    Ok(w) => *winner = w,
+                    Err(e) => debug!("{} resolution of {}: {}", sym, market, e),
+                }
+            }
+        }
+        if winners.iter().all(|w| w.is_some()) {
+            break;
+        }
+        if !delayed && started.elapsed().as_secs() > max_wait {
+            delayed = true;
+            let message = format!(
+                "{} cross-venue window {}: not resolved on both venues after {}s; {} trade(s) wait for it",
+                sym,
+                period_start,
+                max_wait,
+                trades.len()
+            );
+            warn!("{}", message);
+            if let Some(events) = journal.events() {
+                events.publish(ArbEvent::Alert {
+                    kind: "resolution_delayed".to_string(),
+                    subject: format!("{} / {}", window.polymarket_market, window.kalshi_market),
+                    message,
+                    ts_ms: Utc::now().timestamp_millis(),
+                });
+            }
+        }
+        sleep(if delayed { slow_poll } else { poll }).await;
+    }
+    let [Some(polymarket), Some(kalshi_winner)] = winners else {
+        return;
+    };
+
+    let mut period_pnl = Decimal::ZERO;
+    for trade in trades {
+        let won = (
+            trade.filled.0 && trade.polymarket_token == polymarket.0,
+            trade.filled.1 && trade.kalshi_token == kalshi_winner.0,
+        );
+        let payout = trade.contracts * Decimal::from(won.0 as u8 + won.1 as u8);
+        let pnl = payout - trade.cost;
+        period_pnl += pnl;
+        journal.record(
+            Some(&trade.trade_id),
+            JournalEvent::Resolved {
+                outcome_15: polymarket.1.clone(),
+                outcome_5: kalshi_winner.1.clone(),
+                pnl: pnl.to_f64().unwrap_or(0.0),
+                source_15: Some("polymarket".to_string()),
+                source_5: Some("kalshi".to_string()),
+            },
+        );
+        if trade.filled == (true, true) && !won.0 && !won.1 {
+            warn!(
+                "{} cross arb {} lost on both venues: the settlement prices fell between the strikes",
+                sym, trade.trade_id
+            );
+        }
+        info!(
+            "{} cross arb {} resolved: Polymarket {}, Kalshi {} | cost={:.2}, payout={:.2}, PnL={:.2}",
+            sym, trade.trade_id, polymarket.1, kalshi_winner.1, trade.cost, payout, pnl
+        );
+    }
+    info!("{} cross-venue window {} PnL={:.2}", sym, period_start, period_pnl);
+    unsettled.remove(&window);
+
+    if config.strategy.simulation_mode || !config.strategy.auto_redeem {
+        return;
+    }
+    for ((venue, market), (token, outcome)) in venues.iter().zip([&polymarket, &kalshi_winner]) {
+        let held = trades
+            .iter()
+            .any(|t| (t.filled.0 && &t.polymarket_token == token) || (t.filled.1 && &t.kalshi_token == token));
+        if !held {
+            continue;
+        }
+        let event = match venue.redeem(market, token, outcome).await {
+            Ok(res) => JournalEvent::Redeemed {
+                condition_id: market.to_string(),
+                outcome: outcome.clone(),
+                transaction_hash: res.transaction_hash,
+            },
+            Err(e) => {
+                warn!("{} redeem of {} {} failed: {}", sym, market, outcome, e);
+                JournalEvent::RedeemFailed {
+                    condition_id: market.to_string(),
+                    outcome: outcome.clone(),
+                    error: e.to_string(),
+                }
+            }
+        };
+        journal.record(None, event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use futures_util::StreamExt;
+
+    fn trade() -> CrossTrade {
+        CrossTrade {
+            trade_id: "btc-kalshi-1767726000-1000".to_string(),
+            polymarket_token: "up".to_string(),
+            kalshi_token: "KXBTC15M-26JAN061515-15:no".to_string(),
+            filled: (false, false),
+            contracts: Decimal::from(10),
+            cost: Decimal::ZERO,
+        }
+    }
+
+    fn pair() -> CrossPair {
+        CrossPair {
+            polymarket_up: true,
+            polymarket_ask: Decimal::new(45, 2),
+            kalshi_ask: Decimal::new(50, 2),
+            cost: Decimal::new(97, 2),
+        }
+    }
+
+    #[tokio::test]
+    async fn both_legs_buy_the_same_whole_number_of_contracts() {
+        let (polymarket, kalshi) = (MockPolymarketApi::new(), MockPolymarketApi::new());
+        let filled = place_pair(&polymarket, &kalshi, &Journal::new(""), &trade(), &pair()).await;
+        assert_eq!(filled, Some((true, true)));
+        let (pm_order, kalshi_order) = (&polymarket.orders()[0], &kalshi.orders()[0]);
+        assert_eq!((pm_order.size.as_str(), kalshi_order.size.as_str()), ("10", "10"));
+        assert_eq!(pm_order.order_type, OrderType::Fok);
+    }
+
+    #[tokio::test]
+    async fn an_unmatched_polymarket_leg_leaves_the_kalshi_leg_held_alone() {
+        let (polymarket, kalshi) = (MockPolymarketApi::new(), MockPolymarketApi::new());
+        polymarket.reject_orders(true);
+        let filled = place_pair(&polymarket, &kalshi, &Journal::new(""), &trade(), &pair()).await;
+        assert_eq!(filled, Some((false, true)));
+
+        // Nothing is sent to Polymarket once the Kalshi leg misses.
+        let (polymarket, kalshi) = (MockPolymarketApi::new(), MockPolymarketApi::new());
+        kalshi.reject_orders(true);
+        assert_eq!(place_pair(&polymarket, &kalshi, &Journal::new(""), &trade(), &pair()).await, None);
+        assert!(polymarket.orders().is_empty());
+    }
+
+    #[tokio::test]
+    async fn finds_the_polymarket_window_through_the_venue() {
+        let polymarket = MockPolymarketApi::new();
+        // 2026-01-06 14:00-14:15 ET.
+        let (start, end) = (1767726000, 1767726900);
+        polymarket.add_market(&format!("btc-updown-15m-{}", start), "c15", &[("up", "Up"), ("down", "Down")]);
+        let kalshi = MockPolymarketApi::new();
+        kalshi.add_market(&format!("btc-updown-15m-{}", start), "KXBTC15M", &[("yes", "Up"), ("no", "Down")]);
+        let markets = find_markets(&polymarket, &kalshi, "btc", "btc-updown-15m", start).await.unwrap().unwrap();
+        assert_eq!(markets.polymarket.id, "c15");
+        assert_eq!((markets.polymarket.yes_token.as_str(), markets.polymarket.no_token.as_str()), ("up", "down"));
+        assert_eq!(markets.polymarket.close_unix, end);
+        assert_eq!(markets.kalshi.yes_token, "yes");
+        assert!(find_markets(&polymarket, &kalshi, "btc", "btc-updown-15m", end).await.unwrap().is_none());
+        assert!(polymarket.find_market("KXBTC15M", end).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn a_window_unresolved_past_the_wait_is_kept_and_alerted_until_it_resolves() {
+        let path = std::env::temp_dir().join(format!("cross-venue-pending-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let path_str = path.display().to_string();
+        let polymarket = Arc::new(MockPolymarketApi::new());
+        polymarket.add_market("btc-updown-15m-1767726000", "c15", &[("up", "Up"), ("down", "Down")]);
+        let kalshi = Arc::new(MockPolymarketApi::new());
+        kalshi.add_market("KXBTC15M-26JAN061415", "KXBTC15M-26JAN061415", &[("yes", "Up"), ("no", "Down")]);
+        let mut config = Config::default();
+        config.strategy.resolution_poll_interval_secs = 1;
+        config.strategy.resolution_max_wait_secs = 0;
+        config.strategy.pending_resolution_poll_secs = 1;
+        let events = EventBus::new();
+        let mut alerts = Box::pin(events.subscribe().filter_map(|e| async move {
+            match e {
+                ArbEvent::Alert { kind, .. } => Some(kind),
+                _ => None,
+            }
+        }));
+        let journal = Arc::new(Journal::new("").with_events(events.clone()));
+        let window = UnsettledWindow {
+            symbol: "BTC".to_string(),
+            period_start: 1767726000,
+            polymarket_market: "c15".to_string(),
+            kalshi_market: "KXBTC15M-26JAN061415".to_string(),
+            trades: vec![CrossTrade {
+                filled: (true, true),
+                cost: Decimal::new(97, 1),
+                ..trade()
+            }],
+        };
+        let unsettled = Arc::new(UnsettledWindows::load(&path_str));
+        let settling = tokio::spawn(settle(
+            polymarket.clone(),
+            kalshi.clone(),
+            Arc::clone(&journal),
+            Arc::new(config),
+            Arc::clone(&unsettled),
+            window,
+        ));
+
+        assert_eq!(alerts.next().await.as_deref(), Some("resolution_delayed"));
+        assert!(!settling.is_finished());
+        // A restart now would pick the window up again.
+        assert_eq!(UnsettledWindows::load(&path_str).all().len(), 1);
+
+        let mut resolved = Box::pin(events.subscribe().filter_map(|e| async move {
+            match e {
+                ArbEvent::Trade(entry) => match entry.event {
+                    JournalEvent::Resolved { pnl, .. } => Some(pnl),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }));
+        polymarket.resolve("c15", "Up");
+        kalshi.resolve("KXBTC15M-26JAN061415", "Up");
+        settling.await.unwrap();
+        let pnl = resolved.next().await.unwrap();
+        assert!((pnl - 0.3).abs() < 1e-9, "{}", pnl);
+        assert!(UnsettledWindows::load(&path_str).all().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+}
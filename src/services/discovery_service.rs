@@ -1,42 +1,98 @@
-use crate::adapters::polymarket::PolymarketApi;
-use crate::utils::slug_builder::{build_15m_slug, build_5m_slug, parse_price_to_beat_from_question};
+use crate::adapters::polymarket::PolymarketClient;
+use crate::domain::window::{period_start_et_unix_at, PeriodPair};
+use crate::models::Outcome;
+use crate::utils::slug_builder::{build_updown_slug, parse_price_to_beat_from_question, parse_updown_slug};
 use anyhow::Result;
-use std::sync::Arc;
+use chrono::{Duration, SecondsFormat, Utc};
+use serde_json::Value;
+use log::{debug, warn};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// How long market lookups (slug -> condition id, condition id -> tokens) are reused.
+const METADATA_TTL_SECS: u64 = 600;
+
+/// Key of an up/down market: (symbol, period_mins, period start).
+type UpdownKey = (String, i64, i64);
+
+/// Entries expire after `METADATA_TTL_SECS`; misses are not cached so a market that isn't listed yet
+/// is looked up again on the next retry.
+struct TtlCache<K, V> {
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &K) -> Option<V> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.retain(|_, (at, _)| at.elapsed().as_secs() < METADATA_TTL_SECS);
+        entries.get(key).map(|(_, v)| v.clone())
+    }
+
+    fn insert(&self, key: K, value: V) {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(key, (Instant::now(), value));
+    }
+}
 
 pub struct MarketDiscovery {
-    api: Arc<PolymarketApi>,
+    api: Arc<dyn PolymarketClient>,
+    markets: TtlCache<UpdownKey, (String, Option<f64>)>,
+    tokens: TtlCache<String, (String, String)>,
 }
 
 impl MarketDiscovery {
-    pub fn new(api: Arc<PolymarketApi>) -> Self {
-        Self { api }
+    pub fn new(api: Arc<dyn PolymarketClient>) -> Self {
+        Self {
+            api,
+            markets: TtlCache::new(),
+            tokens: TtlCache::new(),
+        }
     }
 
+    /// (Up, Down) token ids of a market, cached by condition id.
     pub async fn get_market_tokens(&self, condition_id: &str) -> Result<(String, String)> {
+        if let Some(tokens) = self.tokens.get(&condition_id.to_string()) {
+            return Ok(tokens);
+        }
         let details = self.api.get_market(condition_id).await?;
         let mut up_token = None;
         let mut down_token = None;
 
         for token in details.tokens {
-            let outcome = token.outcome.to_uppercase();
-            if outcome.contains("UP") || outcome == "1" {
-                up_token = Some(token.token_id);
-            } else if outcome.contains("DOWN") || outcome == "0" {
-                down_token = Some(token.token_id);
+            match Outcome::from_token_label(&token.outcome) {
+                Some(Outcome::Up) => up_token = Some(token.token_id),
+                Some(Outcome::Down) => down_token = Some(token.token_id),
+                None => {}
             }
         }
 
         let up = up_token.ok_or_else(|| anyhow::anyhow!("Up token not found"))?;
         let down = down_token.ok_or_else(|| anyhow::anyhow!("Down token not found"))?;
+        self.tokens.insert(condition_id.to_string(), (up.clone(), down.clone()));
         Ok((up, down))
     }
 
-    pub async fn get_15m_market(
+    /// Active `period_mins` market for `symbol` starting at `period_start`: (condition id, price-to-beat
+    /// parsed from the question). Found markets are cached.
+    pub async fn get_updown_market(
         &self,
         symbol: &str,
+        period_mins: i64,
         period_start: i64,
     ) -> Result<Option<(String, Option<f64>)>> {
-        let slug = build_15m_slug(symbol, period_start);
+        let key = (symbol.to_lowercase(), period_mins, period_start);
+        if let Some(market) = self.markets.get(&key) {
+            return Ok(Some(market));
+        }
+        let slug = build_updown_slug(symbol, period_mins, period_start);
         let market = match self.api.get_market_by_slug(&slug).await {
             Ok(m) => m,
             Err(_) => return Ok(None),
@@ -45,23 +101,160 @@ impl MarketDiscovery {
             return Ok(None);
         }
         let price_to_beat = parse_price_to_beat_from_question(&market.question);
+        self.markets.insert(key, (market.condition_id.clone(), price_to_beat));
         Ok(Some((market.condition_id, price_to_beat)))
     }
 
-    pub async fn get_5m_market(
+    /// Warm the cache with both markets (and their tokens) of the overlap in the long period starting at
+    /// `long_start`, so the round can begin without lookups. Returns the token ids (long Up, long Down,
+    /// short Up, short Down) once all are known; failures are only logged.
+    pub async fn prefetch_overlap(&self, symbol: &str, periods: PeriodPair, long_start: i64) -> Option<Vec<String>> {
+        let overlap_start = long_start + periods.overlap_start_offset_mins * 60;
+        let short_start = period_start_et_unix_at(overlap_start, periods.short_period_mins);
+        let mut asset_ids = Vec::with_capacity(4);
+        for (period_mins, start) in [
+            (periods.long_period_mins, long_start),
+            (periods.short_period_mins, short_start),
+        ] {
+            let condition_id = match self.get_updown_market(symbol, period_mins, start).await {
+                Ok(Some((condition_id, _))) => condition_id,
+                Ok(None) => {
+                    debug!(
+                        "Prefetch: {} {} market for {} not listed yet",
+                        symbol.to_uppercase(),
+                        PeriodPair::label(period_mins),
+                        start
+                    );
+                    return None;
+                }
+                Err(e) => {
+                    warn!("Prefetch {} market lookup: {}", symbol.to_uppercase(), e);
+                    return None;
+                }
+            };
+            match self.get_market_tokens(&condition_id).await {
+                Ok((up, down)) => asset_ids.extend([up, down]),
+                Err(e) => {
+                    warn!("Prefetch {} tokens for {}: {}", symbol.to_uppercase(), condition_id, e);
+                    return None;
+                }
+            }
+        }
+        Some(asset_ids)
+    }
+
+    /// Symbols with open "<symbol>-updown-<period>-<start>" markets for both periods of the pair, each at or
+    /// above the liquidity and volume floors (USD). Dated hourly slugs aren't matched.
+    pub async fn discover_updown_symbols(
         &self,
-        symbol: &str,
-        period_start: i64,
-    ) -> Result<Option<(String, Option<f64>)>> {
-        let slug = build_5m_slug(symbol, period_start);
-        let market = match self.api.get_market_by_slug(&slug).await {
-            Ok(m) => m,
-            Err(_) => return Ok(None),
-        };
-        if !market.active || market.closed {
-            return Ok(None);
+        periods: PeriodPair,
+        min_liquidity: f64,
+        min_volume: f64,
+    ) -> Result<Vec<String>> {
+        let now = Utc::now();
+        let end_max = now + Duration::seconds(periods.long_secs());
+        let markets = self
+            .api
+            .get_gamma_markets_ending_between(
+                &now.to_rfc3339_opts(SecondsFormat::Secs, true),
+                &end_max.to_rfc3339_opts(SecondsFormat::Secs, true),
+            )
+            .await?;
+        let mut long = BTreeSet::new();
+        let mut short = HashSet::new();
+        for market in &markets {
+            let Some((symbol, period_mins, _)) =
+                market.get("slug").and_then(|s| s.as_str()).and_then(parse_updown_slug)
+            else {
+                continue;
+            };
+            let liquidity = gamma_number(market, &["liquidityNum", "liquidity"]);
+            let volume = gamma_number(market, &["volumeNum", "volume"]);
+            if liquidity < min_liquidity || volume < min_volume {
+                continue;
+            }
+            if period_mins == periods.long_period_mins {
+                long.insert(symbol);
+            } else if period_mins == periods.short_period_mins {
+                short.insert(symbol);
+            }
         }
-        let price_to_beat = parse_price_to_beat_from_question(&market.question);
-        Ok(Some((market.condition_id, price_to_beat)))
+        Ok(long.into_iter().filter(|s| short.contains(s)).collect())
+    }
+}
+
+/// First of `keys` present as a number or numeric string (Gamma uses both); 0 when missing.
+fn gamma_number(market: &Value, keys: &[&str]) -> f64 {
+    keys.iter()
+        .find_map(|k| match market.get(*k)? {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        })
+        .unwrap_or(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+
+    #[test]
+    fn found_markets_are_reused_and_misses_looked_up_again() {
+        let api = Arc::new(MockPolymarketApi::new());
+        let slug = build_updown_slug("btc", 15, 1767726000);
+        let discovery = MarketDiscovery::new(api.clone());
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        assert_eq!(rt.block_on(discovery.get_updown_market("btc", 15, 1767726000)).unwrap(), None);
+
+        api.add_market(&slug, "0xfirst", &[("1", "Up"), ("2", "Down")]);
+        let found = rt.block_on(discovery.get_updown_market("BTC", 15, 1767726000)).unwrap();
+        assert_eq!(found.map(|(cid, _)| cid).as_deref(), Some("0xfirst"));
+        assert_eq!(rt.block_on(discovery.get_market_tokens("0xfirst")).unwrap(), ("1".into(), "2".into()));
+
+        // Relisted under the same slug with other tokens: both lookups still come from the cache.
+        api.add_market(&slug, "0xsecond", &[("3", "Up"), ("4", "Down")]);
+        api.add_market("other", "0xfirst", &[("5", "Up"), ("6", "Down")]);
+        let cached = rt.block_on(discovery.get_updown_market("btc", 15, 1767726000)).unwrap();
+        assert_eq!(cached.map(|(cid, _)| cid).as_deref(), Some("0xfirst"));
+        assert_eq!(rt.block_on(discovery.get_market_tokens("0xfirst")).unwrap(), ("1".into(), "2".into()));
+    }
+
+    #[test]
+    fn cache_entries_expire_after_the_ttl() {
+        let cache = TtlCache::new();
+        cache.insert("fresh", 1);
+        let expired_at = Instant::now().checked_sub(std::time::Duration::from_secs(METADATA_TTL_SECS)).unwrap();
+        cache.entries.lock().unwrap().insert("stale", (expired_at, 2));
+        assert_eq!((cache.get(&"fresh"), cache.get(&"stale")), (Some(1), None));
+        assert!(!cache.entries.lock().unwrap().contains_key("stale"));
+    }
+
+    #[test]
+    fn prefetches_overlap_tokens_through_the_client() {
+        let api = Arc::new(MockPolymarketApi::new());
+        let periods = PeriodPair::default();
+        let long_start = 1767726000;
+        let short_start = period_start_et_unix_at(
+            long_start + periods.overlap_start_offset_mins * 60,
+            periods.short_period_mins,
+        );
+        api.add_market(
+            &build_updown_slug("btc", periods.long_period_mins, long_start),
+            "0xlong",
+            &[("1", "Up"), ("2", "Down")],
+        );
+        api.add_market(
+            &build_updown_slug("btc", periods.short_period_mins, short_start),
+            "0xshort",
+            &[("4", "Down"), ("3", "Up")],
+        );
+        let discovery = MarketDiscovery::new(api.clone());
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        assert_eq!(
+            rt.block_on(discovery.prefetch_overlap("BTC", periods, long_start)),
+            Some(["1", "2", "3", "4"].map(String::from).to_vec())
+        );
+        assert_eq!(rt.block_on(discovery.prefetch_overlap("eth", periods, long_start)), None);
     }
 }
@@ -0,0 +1,218 @@
+//! End-of-day wind-down: after `end_of_day.stop_at_et` no new rounds start; once the last round has had time
+//! to resolve, pending redemptions are pushed through, residual positions are merged or sold, and a daily
+//! report is logged. Trading resumes at `resume_at_et`.
+
+use crate::adapters::polymarket::PolymarketClient;
+use crate::config::Config;
+use crate::models::{OrderRequest, OrderType, Side};
+use crate::services::inventory::{Inventory, Position};
+use crate::services::journal::Journal;
+use crate::services::pnl_ledger::PnlLedger;
+use crate::services::portfolio::{equity_change_since, read_equity_curve};
+use crate::services::redeem_queue::RedeemQueue;
+use chrono::Utc;
+use log::{info, warn};
+use rust_decimal::prelude::ToPrimitive;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+const EOD_POLL_SECS: u64 = 30;
+/// Positions smaller than this are dust and not worth an order.
+const MIN_FLATTEN_SHARES: f64 = 0.01;
+
+pub async fn run_end_of_day(
+    api: Arc<dyn PolymarketClient>,
+    config: Config,
+    inventory: Arc<Inventory>,
+    redeem_queue: Arc<RedeemQueue>,
+    journal: Arc<Journal>,
+    cumulative_pnl: Arc<PnlLedger>,
+) {
+    let eod = &config.strategy.end_of_day;
+    // In-flight rounds finish their period, then resolution may take up to resolution_max_wait_secs.
+    let settle_secs =
+        config.strategy.periods.long_secs() as u64 + config.strategy.resolution_max_wait_secs;
+    let mut pnl_at_day_start = cumulative_pnl.snapshot();
+    let mut day_start = Utc::now().timestamp();
+    let mut was_idle = eod.idle_at(Utc::now().timestamp());
+    if was_idle {
+        info!("Past the daily stop ({} ET); idle until {} ET", eod.stop_at_et, eod.resume_at_et);
+    }
+    loop {
+        sleep(Duration::from_secs(EOD_POLL_SECS)).await;
+        let idle = eod.idle_at(Utc::now().timestamp());
+        if idle == was_idle {
+            continue;
+        }
+        was_idle = idle;
+        if !idle {
+            info!("Start of trading day ({} ET): opening new positions again", eod.resume_at_et);
+            continue;
+        }
+        info!(
+            "End of day ({} ET): no new positions; flattening in {}s once open rounds have resolved",
+            eod.stop_at_et, settle_secs
+        );
+        sleep(Duration::from_secs(settle_secs)).await;
+        if config.strategy.auto_redeem && !config.strategy.simulation_mode {
+            redeem_queue.expedite();
+            redeem_queue.retry_due(api.as_ref(), &journal, &inventory).await;
+        }
+        if eod.flatten {
+            flatten_positions(api.as_ref(), &inventory, config.strategy.simulation_mode).await;
+        }
+        let cumulative = cumulative_pnl.snapshot();
+        let positions = inventory.snapshot().await;
+        let (pending, failed) = redeem_queue.counts();
+        info!(
+            "📊 Daily report: PnL today {:.2} USDC (all time {:.2}) | {} position(s) left, cost basis {:.2} | redemptions pending {}, failed {}",
+            cumulative.total - pnl_at_day_start.total,
+            cumulative.total,
+            positions.len(),
+            positions.values().map(|p| p.cost).sum::<f64>(),
+            pending,
+            failed
+        );
+        for (symbol, total) in &cumulative.by_symbol {
+            let today = *total - pnl_at_day_start.by_symbol.get(symbol).copied().unwrap_or_default();
+            info!("   {}: today {:.2} USDC, all time {:.2}", symbol.to_uppercase(), today, total);
+        }
+        let snapshots = config.strategy.portfolio_snapshot_path.trim();
+        if !snapshots.is_empty() && std::path::Path::new(snapshots).exists() {
+            match read_equity_curve(std::path::Path::new(snapshots)) {
+                Ok(curve) => {
+                    if let Some(change) = equity_change_since(&curve, day_start) {
+                        info!("📈 Equity change today: {:+.2} USDC", change);
+                    }
+                }
+                Err(e) => warn!("Daily report: equity curve unreadable: {}", e),
+            }
+        }
+        pnl_at_day_start = cumulative;
+        day_start = Utc::now().timestamp();
+        info!("Idle until {} ET", eod.resume_at_et);
+    }
+}
+
+/// Shares order `order_id` matched, from its status; None when there is no id or the status is unavailable.
+async fn matched_size(api: &dyn PolymarketClient, order_id: Option<&str>) -> Option<f64> {
+    let status = api.get_order_status(order_id?).await.ok()?;
+    status.size_matched?.trim().parse().ok()
+}
+
+/// Merge positions holding both outcomes of a market into USDC, then sell what is left into the best bid (FAK,
+/// so nothing rests overnight). Only the size the order reports matched leaves the inventory. Tokens without a
+/// bid (resolved or empty book) are left to resolution and redemption.
+async fn flatten_positions(api: &dyn PolymarketClient, inventory: &Inventory, simulation: bool) {
+    let positions = inventory.snapshot().await;
+    if positions.is_empty() {
+        return;
+    }
+    let mut by_condition: HashMap<&str, Vec<(&String, &Position)>> = HashMap::new();
+    for (token, pos) in &positions {
+        by_condition.entry(pos.condition_id.as_str()).or_default().push((token, pos));
+    }
+    let mut remaining: HashMap<String, f64> = positions.iter().map(|(t, p)| (t.clone(), p.size)).collect();
+
+    for (condition_id, held) in &by_condition {
+        if held.len() != 2 || condition_id.is_empty() {
+            continue;
+        }
+        let sets = held[0].1.size.min(held[1].1.size);
+        if sets < MIN_FLATTEN_SHARES {
+            continue;
+        }
+        if simulation {
+            info!("[SIM] End of day: would merge {:.2} complete set(s) of {}", sets, condition_id);
+            continue;
+        }
+        match api.merge_positions(condition_id, sets).await {
+            Ok(tx) => {
+                info!("End of day: merged {:.2} complete set(s) of {} ({})", sets, condition_id, tx);
+                for (token, _) in held {
+                    inventory.record_sell(token, sets).await;
+                    *remaining.entry((*token).clone()).or_default() -= sets;
+                }
+            }
+            Err(e) => warn!("End of day: merge of {} failed: {}", condition_id, e),
+        }
+    }
+
+    let to_sell: Vec<(String, f64)> = remaining
+        .into_iter()
+        .map(|(token, size)| (token, (size * 100.0).floor() / 100.0))
+        .filter(|(_, size)| *size >= MIN_FLATTEN_SHARES)
+        .collect();
+    if to_sell.is_empty() {
+        return;
+    }
+    // Every bid in one request.
+    let tokens: Vec<&str> = to_sell.iter().map(|(token, _)| token.as_str()).collect();
+    let bids = api.get_prices(&tokens, "BUY").await.unwrap_or_else(|e| {
+        warn!("End of day: bid lookup failed: {}", e);
+        HashMap::new()
+    });
+    for (token, size) in to_sell {
+        let bid = bids.get(&token).and_then(|b| b.to_f64());
+        let Some(bid) = bid.filter(|b| *b > 0.0) else {
+            info!(
+                "End of day: no bid for token {}; leaving {:.2} to resolution",
+                &token[..token.len().min(16)],
+                size
+            );
+            continue;
+        };
+        if simulation {
+            info!("[SIM] End of day: would sell {:.2} of token {} @ {:.4}", size, &token[..token.len().min(16)], bid);
+            continue;
+        }
+        let order = OrderRequest {
+            token_id: token.clone(),
+            side: Side::Sell,
+            size: format!("{:.2}", size),
+            price: format!("{:.4}", bid),
+            order_type: OrderType::Fak,
+            expiration: None,
+            client_order_id: None,
+        };
+        let short = &token[..token.len().min(16)];
+        let order_id = match api.place_order(&order).await {
+            Ok(res) => res.order_id,
+            Err(e) => {
+                warn!("End of day: sell of token {} failed: {}", short, e);
+                continue;
+            }
+        };
+        match matched_size(api, order_id.as_deref()).await {
+            Some(sold) if sold > 0.0 => {
+                info!("End of day: sold {:.2} of {:.2} of token {} @ {:.4}", sold, size, short, bid);
+                inventory.record_sell(&token, sold).await;
+            }
+            Some(_) => info!("End of day: nothing of token {} sold at {:.4}; leaving it to resolution", short, bid),
+            None => warn!("End of day: sell of token {} has no fill status; left to the next fill sync", short),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+
+    #[test]
+    fn flatten_only_books_what_the_bid_took() {
+        let api = MockPolymarketApi::new();
+        api.add_market("btc-up-or-down", "0xcid", &[("up", "Up"), ("down", "Down")]);
+        api.set_orderbook("up", &[(0.6, 4.0)], &[(0.62, 50.0)]);
+        api.limit_fak_depth(4.0);
+        let inventory = Inventory::new();
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        rt.block_on(inventory.record_buy("up", "0xcid", "Up", 10.0, 0.5));
+
+        rt.block_on(flatten_positions(&api, &inventory, false));
+        let orders = api.orders();
+        assert_eq!((orders.len(), orders[0].order_type), (1, OrderType::Fak));
+        assert_eq!(rt.block_on(inventory.outcome_size("0xcid", "Up")), Some(6.0), "4 of 10 sold");
+    }
+}
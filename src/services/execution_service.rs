@@ -1,185 +1,826 @@
-use crate::adapters::polymarket::ws_market::{run_market_ws, PricesSnapshot};
-use crate::adapters::polymarket::PolymarketApi;
-use crate::config::Config;
-use crate::domain::arbitrage::select_arb_legs;
-use crate::models::{OrderRequest, TradeRecord};
+use crate::adapters::polymarket::ws_market::{
+    book_check_counts, out_of_sequence_assets, reconcile_with_snapshot, run_market_ws, PricesSnapshot,
+};
+use crate::adapters::hedge::Hedger;
+use crate::adapters::polymarket::ws_rtds::SpotHistory;
+use crate::adapters::polymarket::PolymarketClient;
+use crate::adapters::venue::{AmbiguousPost, PredictionMarketVenue};
+use crate::config::{Config, ExitRulesConfig, LegSequencing, SlippageGuardConfig, StrategyConfig};
+use crate::domain::arbitrage::{leg2_is_thinner, select_maker_legs, ArbSelection};
+use crate::domain::book::{midpoint, spread};
+use crate::domain::fees::{fee_per_share, LegFees};
+use crate::domain::latency::describe_latency;
+use crate::domain::pnl::{cost_per_pair, exit_trigger, unrealized_pnl};
+use crate::domain::pricing::{estimate_arb, vol_per_sqrt_sec, ModelEstimate};
+use crate::domain::sizing::arb_size;
+use crate::domain::strike::distance_to_strike_bps;
+use crate::domain::trade_ids::TradeIds;
+use crate::domain::volatility::realized_vol_bps;
+use crate::events::EventBus;
+use crate::models::{
+    ArbSignal, BalanceAsset, JournalEvent, LegLatency, OrderRequest, OrderResponse, OrderStatus, OrderType, Side,
+    SignalLeg, TradeRecord,
+};
+use crate::services::canary::Canary;
+use crate::services::controls::RuntimeControls;
+use crate::services::journal::Journal;
+use crate::services::hedging::DeltaHedge;
+use crate::services::maker_quoter::{MakerFill, MakerQuoter};
+use crate::services::arbitrage_orchestrator::PeriodContext;
+use crate::services::pnl_ledger::PnlLedger;
+use crate::services::strategy::{Strategy, WindowQuotes};
+use crate::utils::clock::Clock;
+use crate::utils::supervisor::Supervisor;
 use anyhow::Result;
-use chrono::Utc;
+use dashmap::DashMap;
 use log::{info, warn};
+use rand::Rng;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::hash_map::Entry as MapEntry;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tokio::time::{sleep, Duration};
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Duration};
 
-const MARKET_15M_DURATION_SECS: i64 = 15 * 60;
-const LIVE_PRICE_POLL_MS: u64 = 10;
+/// Longest the overlap loop waits for a quote update, so time-based checks (marks, exit retries, the cutoff,
+/// schedules) still run on a quiet book.
+const IDLE_WAKEUP_MS: i64 = 250;
+/// How long the overlap WS gets to deliver initial books before the REST cross-check.
+const BOOK_CHECK_WARMUP_SECS: u64 = 3;
+/// The CLOB only accepts GTD expirations at least this far past its own clock.
+const GTD_SAFETY_MARGIN_SECS: i64 = 60;
+/// Wait between attempts to sell the legs of an arb being closed early.
+const EXIT_RETRY_SECS: u64 = 2;
+/// How far before its post an ambiguously posted order is looked for, covering skew against the CLOB's clock. Kept
+/// short so an older fill of the same token and size isn't taken for it.
+const AMBIGUOUS_POST_SLACK_SECS: i64 = 5;
+/// How long, in CLOB server time, an ambiguously posted order is looked for before it is posted again: the
+/// CLOB's listings can trail the post that reached it.
+const AMBIGUOUS_LOOKUP_SECS: i64 = 3;
+const AMBIGUOUS_LOOKUP_POLL_MS: u64 = 500;
+/// How long a canary arb's legs get to report filled before the canary is left pending.
+const CANARY_CONFIRM_SECS: u64 = 30;
+/// A Chainlink tick older than this is no reading of where the spot is.
+pub const SPOT_STALE_SECS: i64 = 10;
 
-#[allow(clippy::too_many_arguments)]
+/// Latest mark-to-market unrealized PnL of open legs, per symbol and long period ("btc-1767726000").
+pub type UnrealizedPnl = Arc<RwLock<HashMap<String, f64>>>;
+/// Every detected arb is broadcast here; sending with no subscribers is a no-op.
+pub type SignalSender = broadcast::Sender<ArbSignal>;
+
+/// Market WS subscription for one overlap's four tokens, feeding a best bid/ask snapshot. Opened ahead of
+/// the overlap by the orchestrator so the round starts with books already in place; dropping it closes the WS.
+pub struct MarketFeed {
+    asset_ids: Vec<String>,
+    prices: PricesSnapshot,
+    /// Signalled after each WS message applied to `prices`.
+    updates: watch::Receiver<()>,
+    /// None for a stepped feed, which has no WS task.
+    handle: Option<tokio::task::JoinHandle<()>>,
+    /// Stepped feeds only: where the round reports the clock time it would next wake at.
+    waits: Option<mpsc::UnboundedSender<i64>>,
+}
+
+impl MarketFeed {
+    pub fn spawn(ws_url: &str, asset_ids: Vec<String>, symbol: &str, events: Option<EventBus>) -> Self {
+        let prices: PricesSnapshot = Arc::new(DashMap::new());
+        let ws_url = ws_url.to_string();
+        let prices_ws = Arc::clone(&prices);
+        let ws_assets = asset_ids.clone();
+        let (updates_tx, updates) = watch::channel(());
+        let supervisor = Supervisor::new(events.clone());
+        let handle = supervisor.spawn(format!("{} market WS", symbol.to_lowercase()), move || {
+            let (ws_url, ws_assets, prices_ws) = (ws_url.clone(), ws_assets.clone(), Arc::clone(&prices_ws));
+            let (events, updates_tx) = (events.clone(), updates_tx.clone());
+            async move { run_market_ws(&ws_url, ws_assets, prices_ws, events, updates_tx).await }
+        });
+        Self {
+            asset_ids,
+            prices,
+            updates,
+            handle: Some(handle),
+            waits: None,
+        }
+    }
+
+    /// A feed written by the caller rather than a WS, for a round run on a `ManualClock`: the caller applies
+    /// messages to `prices` and signals `updates`. Instead of timing its waits, the round sends on `waits` the
+    /// clock time it would next wake at (a cooldown's end, or `IDLE_WAKEUP_MS` on) and blocks until the next
+    /// update, so the caller can step it in lockstep with the clock.
+    pub fn stepped(
+        asset_ids: Vec<String>,
+        prices: PricesSnapshot,
+        updates: watch::Receiver<()>,
+        waits: mpsc::UnboundedSender<i64>,
+    ) -> Self {
+        Self {
+            asset_ids,
+            prices,
+            updates,
+            handle: None,
+            waits: Some(waits),
+        }
+    }
+
+    /// The feed for an overlap's `asset_ids`: `prefetched` when it was opened for those tokens, else a new one.
+    /// A prefetched feed for other tokens is dropped, closing its WS.
+    fn for_overlap(
+        prefetched: Option<MarketFeed>,
+        asset_ids: &[String],
+        ws_url: &str,
+        symbol: &str,
+        events: Option<EventBus>,
+    ) -> Self {
+        match prefetched {
+            Some(feed) if feed.asset_ids == asset_ids => feed,
+            _ => MarketFeed::spawn(ws_url, asset_ids.to_vec(), symbol, events),
+        }
+    }
+
+    pub fn prices(&self) -> PricesSnapshot {
+        Arc::clone(&self.prices)
+    }
+}
+
+impl Drop for MarketFeed {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.handle {
+            handle.abort();
+        }
+    }
+}
+
+/// What an overlap round runs with besides its period: the venue, the state it shares with the rest of the bot,
+/// and the strategy, controls, and clock it trades under.
+pub struct RoundContext<'a> {
+    pub api: Arc<dyn PolymarketClient>,
+    pub config: &'a Config,
+    pub unrealized: UnrealizedPnl,
+    pub journal: Arc<Journal>,
+    pub signals: &'a SignalSender,
+    pub canary: &'a Arc<Canary>,
+    pub spot_history: &'a SpotHistory,
+    pub cumulative_pnl: &'a Arc<PnlLedger>,
+    pub hedger: Option<Arc<dyn Hedger>>,
+    pub strategy: &'a dyn Strategy,
+    pub controls: &'a RuntimeControls,
+    pub clock: &'a dyn Clock,
+}
+
+/// Trade the overlap window of `period` until the long market closes or the bot stops. Returns the arbs still
+/// held, to resolve and redeem.
 pub async fn run_overlap_round(
-    api: Arc<PolymarketApi>,
-    config: &Config,
-    symbol: &str,
-    cid_15: &str,
-    cid_5: &str,
-    t15_up: &str,
-    t15_down: &str,
-    t5_up: &str,
-    t5_down: &str,
-    period_15: i64,
-    period_5: i64,
+    round: RoundContext<'_>,
+    period: &PeriodContext,
+    prefetched_feed: Option<MarketFeed>,
 ) -> Result<Vec<TradeRecord>> {
-    let prices: PricesSnapshot = Arc::new(RwLock::new(HashMap::new()));
+    let RoundContext {
+        api,
+        config,
+        unrealized,
+        journal,
+        signals,
+        canary,
+        spot_history,
+        cumulative_pnl,
+        hedger,
+        strategy,
+        controls,
+        clock,
+    } = round;
+    let symbol = period.symbol.as_str();
+    let (period_15, period_5) = (period.period_15, period.period_5);
+    let (cid_15, cid_5) = (period.cid_15.as_str(), period.cid_5.as_str());
+    let (t15_up, t15_down) = (period.t15_up.as_str(), period.t15_down.as_str());
+    let (t5_up, t5_down) = (period.t5_up.as_str(), period.t5_down.as_str());
+    let strikes = (period.strike_15, period.strike_5);
     let asset_ids = vec![
         t15_up.to_string(),
         t15_down.to_string(),
         t5_up.to_string(),
         t5_down.to_string(),
     ];
-    let ws_url = config.polymarket.ws_url.clone();
-    let prices_clone = Arc::clone(&prices);
-    let symbol_ws = symbol.to_string();
-    let ws_handle = tokio::spawn(async move {
-        if let Err(e) = run_market_ws(&ws_url, asset_ids, prices_clone).await {
-            warn!("{} overlap WebSocket exited: {}", symbol_ws.to_uppercase(), e);
+    let feed = MarketFeed::for_overlap(
+        prefetched_feed,
+        &asset_ids,
+        &config.polymarket.ws_url,
+        symbol,
+        journal.events().cloned(),
+    );
+    let prices = Arc::clone(&feed.prices);
+    if config.strategy.book_cross_check {
+        cross_check_books(api.as_ref(), &prices, &asset_ids, symbol).await;
+    }
+    // Authenticate and warm the order builders now so a trigger only has to fill in price and size.
+    if !config.strategy.simulation_mode && !config.strategy.signals_only {
+        if let Err(e) = api.prepare_orders(&[t15_up, t15_down, t5_up, t5_down]).await {
+            warn!("{} order preparation failed, orders will prepare on demand: {}", symbol.to_uppercase(), e);
         }
-    });
+    }
 
-    let threshold = config.strategy.sum_threshold;
-    let shares = config.strategy.arb_shares.clone();
-    let interval_secs = config.strategy.trade_interval_secs;
+    let fees = config.strategy.fees.taker();
+    let interval_secs = config.strategy.trade_interval_secs_for(symbol);
+    let max_trades = config.strategy.max_trades_per_period_for(symbol) as usize;
+    let mut max_trades_logged = false;
     let simulation = config.strategy.simulation_mode;
+    let signals_only = config.strategy.signals_only;
     let sym_upper = symbol.to_uppercase();
+    let periods = config.strategy.periods;
+    let (long_label, short_label) = (periods.long_label(), periods.short_label());
+
+    let mtm_interval_secs = config.strategy.mark_to_market_interval_secs;
+    let period_end = period_15 + periods.long_secs();
+    let trade_cutoff = period_end - config.strategy.no_trade_before_close_secs as i64;
+    // No new trades this close to the end, past the daily stop, or outside the schedule.
+    let past_cutoff = |now: i64| {
+        now >= trade_cutoff
+            || config.strategy.end_of_day.idle_at(now)
+            || config.strategy.schedule.paused_at(now).is_some()
+    };
+    let mut cutoff_logged = false;
+    let mut admin_paused = false;
+
+    let maker_cfg = &config.strategy.maker_mode;
+    let maker_enabled = maker_cfg.enabled && !simulation && !signals_only;
+    let maker_fees = config.strategy.fees.maker();
+    // Quote on the coarser of the configured tick and the markets' own tick grids.
+    let mut maker_tick = Decimal::from_f64(maker_cfg.tick_size).unwrap_or_default();
+    if maker_enabled {
+        for token in [t15_up, t5_up] {
+            match api.order_rules(token).await {
+                Ok(rules) => maker_tick = maker_tick.max(rules.tick_size),
+                Err(e) => warn!("{} tick size unavailable, quoting on {}: {}", sym_upper, maker_tick, e),
+            }
+        }
+    }
+    let mut maker = MakerQuoter::new();
+
+    let sizing = config.strategy.sizing.policy();
+    // Spendable USDC when the round starts (CLOB balance capped by the exchange allowance, or the on-chain
+    // balance when the CLOB can't say), less what this round's arbs spend.
+    let mut available_usdc = match api.wallet_address() {
+        Some(wallet) if sizing.capital_fraction > 0.0 && !simulation && !signals_only => {
+            match api.get_balance_allowance(BalanceAsset::Collateral, None).await {
+                Ok(collateral) => Some(collateral.spendable()),
+                Err(e) => {
+                    warn!("{} CLOB balance/allowance unavailable, reading USDC on-chain: {}", sym_upper, e);
+                    match api.get_usdc_balance(&wallet).await {
+                        Ok(balance) => Some(balance),
+                        Err(e) => {
+                            warn!("{} USDC balance unavailable, sizing from arb_shares: {}", sym_upper, e);
+                            None
+                        }
+                    }
+                }
+            }
+        }
+        _ => None,
+    };
+    let mut size_exhausted_logged = false;
+    let pricing = &config.strategy.pricing_model;
+    let short_end = period_5 + periods.short_secs();
+    let mut entry_filters = EntryFilters::new(&config.strategy, symbol, strikes, (period_end, short_end));
 
-    let mut last_trade_at: Option<std::time::Instant> = None;
+    let mut last_trade_ms: Option<i64> = None;
+    let mut last_mark_ms = clock.now_ms();
     let mut trades: Vec<TradeRecord> = Vec::new();
+    let exit_rules = &config.strategy.exit_rules;
+    let mut exits: HashMap<String, Exit> = HashMap::new();
+    let hedge_cfg = &config.strategy.hedge;
+    let mut hedge = DeltaHedge::new(hedger, hedge_cfg, symbol);
+    // Arbs closed early leave `trades`; counted so the per-period limit still sees them.
+    let mut closed = 0usize;
+    let mut trade_ids = TradeIds::new(&symbol.to_lowercase(), period_15);
+    // Timed cancels of resting legs; what a cancelled leg matched resizes its trade once the cancel is done.
+    let mut leg_cancels: Vec<(String, u8, JoinHandle<Option<Decimal>>)> = Vec::new();
 
-    while Utc::now().timestamp() < period_15 + MARKET_15M_DURATION_SECS {
-        let snap = prices.read().await;
-        let ask_15_up = snap.get(t15_up).and_then(|p| p.ask);
-        let ask_15_down = snap.get(t15_down).and_then(|p| p.ask);
-        let ask_5_up = snap.get(t5_up).and_then(|p| p.ask);
-        let ask_5_down = snap.get(t5_down).and_then(|p| p.ask);
-        drop(snap);
-
-        if let Some(t) = last_trade_at {
-            if t.elapsed().as_secs() < interval_secs {
-                sleep(Duration::from_millis(LIVE_PRICE_POLL_MS)).await;
+    // Each pass runs when the WS applies a quote update, a cooldown runs out, or after IDLE_WAKEUP_MS.
+    let mut updates = feed.updates.clone();
+    let mut first_pass = true;
+    while clock.now() < period_end && !controls.is_stopping() {
+        if !first_pass {
+            let cooldown_ends_ms = last_trade_ms.map(|t| t + interval_secs as i64 * 1000);
+            wait_for_quotes(&mut updates, clock, cooldown_ends_ms, feed.waits.as_ref()).await;
+        }
+        first_pass = false;
+        let (done, waiting): (Vec<_>, Vec<_>) = leg_cancels.drain(..).partition(|(_, _, c)| c.is_finished());
+        leg_cancels = waiting;
+        for (trade_id, leg, cancel) in done {
+            if let Ok(Some(matched)) = cancel.await {
+                apply_cancelled_leg(&mut trades, &trade_id, leg, matched);
+            }
+        }
+        // Books the WS flagged out of sequence are re-read over REST before anything is priced off them.
+        let suspect = out_of_sequence_assets(&prices, &asset_ids);
+        if !suspect.is_empty() && !reconcile_books(api.as_ref(), &prices, &suspect, symbol, "sequence gap").await {
+            continue;
+        }
+        // Read each tick so admin API overrides apply mid-period.
+        let threshold = controls.sum_threshold().unwrap_or(config.strategy.sum_threshold);
+        let threshold = Decimal::from_f64(threshold).unwrap_or_default();
+        let shares = controls
+            .arb_shares()
+            .unwrap_or_else(|| config.strategy.arb_shares.clone());
+        let base_shares: f64 = shares.trim().parse().unwrap_or(0.0);
+        // The WS book keeps f64 quotes; everything priced off them from here on is Decimal.
+        let ask = |token: &str| prices.get(token).and_then(|p| p.ask).and_then(Decimal::from_f64);
+        let bid = |token: &str| prices.get(token).and_then(|p| p.bid).and_then(Decimal::from_f64);
+        let (ask_15_up, ask_15_down, ask_5_up, ask_5_down) = (ask(t15_up), ask(t15_down), ask(t5_up), ask(t5_down));
+        let exit_bids: Vec<(Option<Decimal>, Option<Decimal>)> = if exit_rules.enabled() {
+            trades.iter().map(|t| (bid(&t.leg1_token), bid(&t.leg2_token))).collect()
+        } else {
+            Vec::new()
+        };
+        let marked = if mtm_interval_secs > 0
+            && !trades.is_empty()
+            && clock.now_ms() - last_mark_ms >= mtm_interval_secs as i64 * 1000
+        {
+            let value: Decimal = trades
+                .iter()
+                .map(|t| unrealized_pnl(t, bid(&t.leg1_token), bid(&t.leg2_token)))
+                .sum();
+            Some(value.to_f64().unwrap_or(0.0))
+        } else {
+            None
+        };
+
+        if let Some(value) = marked {
+            last_mark_ms = clock.now_ms();
+            unrealized
+                .write()
+                .await
+                .insert(format!("{}-{}", symbol.to_lowercase(), period_15), value);
+            info!(
+                "{} mark-to-market: {} open trade(s), unrealized PnL={:.2}",
+                sym_upper,
+                trades.len(),
+                value
+            );
+        }
+
+        // Newest first, so closing a trade doesn't shift the ones still to check.
+        for (idx, (bid1, bid2)) in exit_bids.into_iter().enumerate().rev() {
+            let trade = &trades[idx];
+            let exit = match exits.entry(trade.trade_id.clone()) {
+                MapEntry::Occupied(exit) => exit.into_mut(),
+                MapEntry::Vacant(slot) => {
+                    let Some((exit, pnl_per_pair)) = start_exit(trade, [bid1, bid2], &fees, exit_rules) else {
+                        continue;
+                    };
+                    info!(
+                        "{} arb {} hit its {} at {:.4} per pair; selling its legs",
+                        sym_upper, trade.trade_id, exit.reason, pnl_per_pair
+                    );
+                    slot.insert(exit)
+                }
+            };
+            if !exit.attempt(clock.now_ms()) {
+                continue;
+            }
+            let exiting = ExitingTrade { trade, symbol, simulation };
+            let held = sell_out(api.as_ref(), &journal, cumulative_pnl, &exiting, exit, [bid1, bid2], &fees).await;
+            match held {
+                Some(rest) => trades[idx] = rest,
+                None => {
+                    let trade = trades.remove(idx);
+                    exits.remove(&trade.trade_id);
+                    closed += 1;
+                }
+            }
+        }
+
+        if let Some(hedge) = hedge.as_mut() {
+            if !trades.is_empty() && clock.now() >= period_end - hedge_cfg.final_secs as i64 {
+                let now_ms = clock.now_ms();
+                if let Some((spot, vol)) = spot_and_vol(spot_history, symbol, pricing.vol_window_secs, now_ms).await {
+                    let secs_to = |end: i64| (end - clock.now()).max(0) as f64;
+                    hedge
+                        .rebalance(&trades, spot, strikes, (secs_to(period_end), secs_to(short_end)), vol)
+                        .await;
+                }
+            }
+        }
+
+        let now = clock.now();
+        if controls.is_paused(symbol) != admin_paused {
+            admin_paused = !admin_paused;
+            info!(
+                "{}: new trades {} via the admin API",
+                sym_upper,
+                if admin_paused { "paused" } else { "resumed" }
+            );
+        }
+        let stopped = past_cutoff(now);
+        if stopped || admin_paused {
+            if stopped && !cutoff_logged {
+                info!(
+                    "{}: {}s to the {} close, past the daily stop, or outside the schedule; no new trades this period.",
+                    sym_upper,
+                    period_end - now,
+                    long_label
+                );
+                cutoff_logged = true;
+            }
+            if maker.is_quoting() {
+                if let Some(fill) = maker.cancel(api.as_ref()).await {
+                    trades.extend(maker_trades(&fill, &journal, period));
+                }
+            }
+            continue;
+        }
+
+        if max_trades > 0 && trades.len() + closed >= max_trades {
+            if !max_trades_logged {
+                info!(
+                    "{}: {} arb(s) placed, the per-period limit; no new trades this period.",
+                    sym_upper,
+                    trades.len() + closed
+                );
+                max_trades_logged = true;
+            }
+            continue;
+        }
+
+        if let Some(t) = last_trade_ms {
+            if clock.now_ms() - t < interval_secs as i64 * 1000 {
                 continue;
             }
         }
 
-        let Some(selection) = select_arb_legs(
+        let quotes = WindowQuotes {
             ask_15_up,
             ask_15_down,
             ask_5_up,
             ask_5_down,
-            threshold,
             t15_up,
             t15_down,
             t5_up,
             t5_down,
-        ) else {
-            sleep(Duration::from_millis(LIVE_PRICE_POLL_MS)).await;
+        };
+        let Some(mut selection) = strategy.on_quote(&quotes, threshold, &fees) else {
+            if maker_enabled {
+                let mids: HashMap<&str, Decimal> = if maker_cfg.cap_at_midpoint {
+                    [t15_up, t15_down, t5_up, t5_down]
+                        .into_iter()
+                        .filter_map(|token| {
+                            let p = prices.get(token)?;
+                            Some((token, Decimal::from_f64(midpoint(p.bid, p.ask)?)?))
+                        })
+                        .collect()
+                } else {
+                    HashMap::new()
+                };
+                let wanted = select_maker_legs(
+                    ask_15_up,
+                    ask_15_down,
+                    ask_5_up,
+                    ask_5_down,
+                    threshold,
+                    Decimal::from_f64(maker_cfg.near_threshold).unwrap_or_default(),
+                    maker_cfg.ticks_below_ask,
+                    maker_tick,
+                    &fees,
+                    &maker_fees,
+                    &mids,
+                    t15_up,
+                    t15_down,
+                    t5_up,
+                    t5_down,
+                );
+                let asks = HashMap::from([
+                    (t15_up, ask_15_up),
+                    (t15_down, ask_15_down),
+                    (t5_up, ask_5_up),
+                    (t5_down, ask_5_down),
+                ]);
+                let next_trade_id = trade_ids.next(clock.now_ms());
+                let filled = maker
+                    .step(
+                        api.as_ref(),
+                        &journal,
+                        wanted,
+                        &next_trade_id,
+                        &shares,
+                        threshold,
+                        &fees,
+                        &asks,
+                        maker_cfg.status_poll_secs,
+                    )
+                    .await;
+                if let Some(fill) = filled {
+                    last_trade_ms = Some(clock.now_ms());
+                    trades.extend(maker_trades(&fill, &journal, period));
+                }
+            }
             continue;
         };
 
-        if simulation {
+        let model = match entry_filters.check(spot_history, &selection, clock.now_ms()).await {
+            Entry::Take(model) => model,
+            Entry::Skip => continue,
+        };
+
+        // The later of the two quotes that made the arb: the start of the quote-to-ack span.
+        let quote_at_ms = {
+            let received = |token: &str| prices.get(token).and_then(|p| p.received_at_ms);
+            received(selection.leg1_token).max(received(selection.leg2_token))
+        };
+        let decided_at_ms = clock.now_ms();
+        let book = |token: &str| {
+            let (bid, ask) = prices.get(token).map(|p| (p.bid, p.ask)).unwrap_or_default();
+            (midpoint(bid, ask), spread(bid, ask))
+        };
+        let ((mid1, spread1), (mid2, spread2)) = (book(selection.leg1_token), book(selection.leg2_token));
+        let _ = signals.send(ArbSignal {
+            symbol: symbol.to_string(),
+            period_15,
+            period_5,
+            cid_15: cid_15.to_string(),
+            cid_5: cid_5.to_string(),
+            leg1: SignalLeg {
+                token_id: selection.leg1_token.to_string(),
+                outcome: selection.leg1_outcome.to_string(),
+                ask: selection.leg1_price.to_f64().unwrap_or(0.0),
+                fee: selection.leg1_fee.to_f64().unwrap_or(0.0),
+                mid: mid1,
+                spread: spread1,
+            },
+            leg2: SignalLeg {
+                token_id: selection.leg2_token.to_string(),
+                outcome: selection.leg2_outcome.to_string(),
+                ask: selection.leg2_price.to_f64().unwrap_or(0.0),
+                fee: selection.leg2_fee.to_f64().unwrap_or(0.0),
+                mid: mid2,
+                spread: spread2,
+            },
+            effective_sum: selection.effective_sum().to_f64().unwrap_or(0.0),
+            edge: (threshold - selection.effective_sum()).to_f64().unwrap_or(0.0),
+            detected_at_ms: decided_at_ms,
+            model,
+        });
+        if signals_only {
+            let show = |v: Option<f64>| v.map_or_else(|| "?".to_string(), |v| format!("{:.4}", v));
             info!(
-                "[SIM] {} arb would place: 15m {} @ {:.4} + 5m {} @ {:.4} (sum {:.4} < {})",
+                "{} arb signal: {} {} @ {:.4} + {} {} @ {:.4} (with fees {:.4} < {}; spreads {}/{}); execution left to \
+                 subscribers",
                 sym_upper,
+                long_label,
                 selection.leg1_outcome,
                 selection.leg1_price,
+                short_label,
                 selection.leg2_outcome,
                 selection.leg2_price,
-                selection.leg1_price + selection.leg2_price,
-                threshold
+                selection.effective_sum(),
+                threshold,
+                show(spread1),
+                show(spread2)
             );
-            last_trade_at = Some(std::time::Instant::now());
-            let size_f64: f64 = shares.parse().unwrap_or(0.0);
-            trades.push(TradeRecord {
+            last_trade_ms = Some(clock.now_ms());
+            continue;
+        }
+
+        let pair_cost = selection.effective_sum();
+        let period_notional: Decimal = trades.iter().map(|t| cost_per_pair(t) * t.size).sum();
+        let sized = arb_size(
+            &sizing,
+            base_shares,
+            available_usdc,
+            pair_cost.to_f64().unwrap_or(0.0),
+            (threshold - pair_cost).to_f64().unwrap_or(0.0),
+            period_notional.to_f64().unwrap_or(0.0),
+        );
+        if sized <= 0.0 {
+            if !size_exhausted_logged {
+                info!(
+                    "{}: arb size is 0 ({:.2} USDC in this period, cap {}); skipping arbs",
+                    sym_upper, period_notional, sizing.max_notional_per_period
+                );
+                size_exhausted_logged = true;
+            }
+            continue;
+        }
+        let shares = sized.to_string();
+
+        // Submission jitter: the arb is only decided if it still holds, and is still allowed, after the delay.
+        let max_delay_ms = config.strategy.jitter.max_delay_ms;
+        if max_delay_ms > 0 && !simulation {
+            let delay_ms = rand::thread_rng().gen_range(0..=max_delay_ms);
+            sleep(Duration::from_millis(delay_ms)).await;
+            if past_cutoff(clock.now()) || controls.is_paused(symbol) || controls.is_stopping() {
+                info!("{} arb dropped: trading stopped during the {}ms submission delay", sym_upper, delay_ms);
+                continue;
+            }
+            let (ask1, ask2) = (ask(selection.leg1_token), ask(selection.leg2_token));
+            let current = ask1.zip(ask2).map(|(a1, a2)| selection.repriced(a1, a2, &fees));
+            if current.is_none_or(|c| c.effective_sum() >= threshold) {
+                info!(
+                    "{} arb dropped: gone after the {}ms submission delay (sum {})",
+                    sym_upper,
+                    delay_ms,
+                    current.map(|c| format!("{:.4}", c.effective_sum())).unwrap_or_else(|| "unknown".to_string())
+                );
+                continue;
+            }
+        }
+
+        let trade_id = trade_ids.next(clock.now_ms());
+        journal.record(
+            Some(&trade_id),
+            JournalEvent::Decision {
                 symbol: symbol.to_string(),
                 period_15,
                 period_5,
                 cid_15: cid_15.to_string(),
                 cid_5: cid_5.to_string(),
-                leg1_token: selection.leg1_token.to_string(),
-                leg1_price: selection.leg1_price,
-                leg1_cid: cid_15.to_string(),
                 leg1_outcome: selection.leg1_outcome.to_string(),
-                leg2_token: selection.leg2_token.to_string(),
-                leg2_price: selection.leg2_price,
-                leg2_cid: cid_5.to_string(),
+                leg1_price: selection.leg1_price.to_f64().unwrap_or(0.0),
                 leg2_outcome: selection.leg2_outcome.to_string(),
-                size: size_f64,
-            });
-            sleep(Duration::from_millis(LIVE_PRICE_POLL_MS)).await;
+                leg2_price: selection.leg2_price.to_f64().unwrap_or(0.0),
+                effective_sum: selection.effective_sum().to_f64().unwrap_or(0.0),
+                threshold: threshold.to_f64().unwrap_or(0.0),
+                simulated: simulation,
+                model,
+            },
+        );
+
+        let shares = jittered_shares(&shares, config.strategy.jitter.size_pct);
+        if simulation {
+            info!(
+                "[SIM] {} arb would place: {} {} @ {:.4} + {} {} @ {:.4} (sum {:.4}, with fees {:.4} < {})",
+                sym_upper,
+                long_label,
+                selection.leg1_outcome,
+                selection.leg1_price,
+                short_label,
+                selection.leg2_outcome,
+                selection.leg2_price,
+                selection.leg1_price + selection.leg2_price,
+                selection.effective_sum(),
+                threshold
+            );
+            last_trade_ms = Some(clock.now_ms());
+            trades.push(trade_record(&trade_id, period, &selection, &shares));
             continue;
         }
 
-        let order1 = OrderRequest {
-            token_id: selection.leg1_token.to_string(),
-            side: "BUY".to_string(),
-            size: shares.clone(),
-            price: format!("{:.4}", selection.leg1_price),
-            order_type: "GTC".to_string(),
+        let guard = &config.strategy.slippage_guard;
+        if guard.enabled {
+            let (ask1, ask2) = (ask(selection.leg1_token), ask(selection.leg2_token));
+            let latest = ask1.zip(ask2).map(|(a1, a2)| selection.repriced(a1, a2, &fees));
+            match slippage_checked(guard, selection, latest, threshold) {
+                Ok(checked) => selection = checked,
+                Err(reason) => {
+                    warn!("{} arb aborted, {}", sym_upper, reason);
+                    journal.record(Some(&trade_id), JournalEvent::Aborted { reason });
+                    continue;
+                }
+            }
+        }
+        // The taker arb supersedes any resting maker quotes.
+        if maker.is_quoting() {
+            if let Some(fill) = maker.cancel(api.as_ref()).await {
+                trades.extend(maker_trades(&fill, &journal, period));
+            }
+        }
+        // Read once: another symbol's canary may complete while these legs are in flight.
+        let is_canary = canary.is_pending();
+        let shares = if is_canary {
+            let mut min_size = Decimal::ZERO;
+            for token in [selection.leg1_token, selection.leg2_token] {
+                if let Ok(rules) = api.order_rules(token).await {
+                    min_size = min_size.max(rules.min_size);
+                }
+            }
+            canary.scaled(&shares, min_size)
+        } else {
+            shares.clone()
         };
-        let order2 = OrderRequest {
-            token_id: selection.leg2_token.to_string(),
-            side: "BUY".to_string(),
-            size: shares.clone(),
-            price: format!("{:.4}", selection.leg2_price),
-            order_type: "GTC".to_string(),
+        if is_canary {
+            info!("{} canary arb: {} shares per leg until the new settings trade cleanly", sym_upper, shares);
+        }
+        let order1 = leg_order(api.as_ref(), config, selection.leg1_token, selection.leg1_price, &shares);
+        let order2 = leg_order(api.as_ref(), config, selection.leg2_token, selection.leg2_price, &shares);
+
+        let (r1, r2) = match config.strategy.leg_sequencing {
+            LegSequencing::Both => {
+                let r1 = place_leg(api.as_ref(), &journal, &trade_id, 1, &order1).await;
+                let r2 = place_leg(api.as_ref(), &journal, &trade_id, 2, &order2).await;
+                (r1, r2)
+            }
+            LegSequencing::ThinnerFirst => {
+                let size = |token: &str| prices.get(token).and_then(|p| p.ask_size);
+                let (size1, size2) = (size(selection.leg1_token), size(selection.leg2_token));
+                place_thinner_first(api.as_ref(), &journal, &trade_id, &order1, &order2, leg2_is_thinner(size1, size2))
+                    .await
+            }
         };
+        let legs: Vec<LegLatency> = [(1u8, &r1), (2u8, &r2)]
+            .into_iter()
+            .filter_map(|(leg, res)| Some(LegLatency { leg, order: res.as_ref().ok()?.latency? }))
+            .collect();
+        if !legs.is_empty() {
+            info!("{} arb latency: {}", sym_upper, describe_latency(quote_at_ms, decided_at_ms, &legs));
+            journal.record(
+                Some(&trade_id),
+                JournalEvent::Latency {
+                    quote_at_ms,
+                    decided_at_ms,
+                    legs,
+                },
+            );
+        }
 
-        let r1 = api.place_order(&order1).await;
-        let r2 = api.place_order(&order2).await;
+        let cancel_after_secs = config.strategy.leg_cancel_after_secs;
+        if cancel_after_secs > 0 && config.strategy.leg_type().rests() {
+            for (leg, res) in [(1u8, &r1), (2u8, &r2)] {
+                if let Some(order_id) = res.as_ref().ok().and_then(|r| r.order_id.clone()) {
+                    let cancel = tokio::spawn(cancel_if_unfilled(
+                        api.clone(),
+                        Arc::clone(&journal),
+                        trade_id.clone(),
+                        leg,
+                        order_id,
+                        cancel_after_secs,
+                    ));
+                    leg_cancels.push((trade_id.clone(), leg, cancel));
+                }
+            }
+        }
 
         match (&r1, &r2) {
             (Ok(res1), Ok(res2)) => {
                 let id1 = res1.order_id.as_deref().unwrap_or("");
                 let id2 = res2.order_id.as_deref().unwrap_or("");
                 info!(
-                    "{} arb placed: 15m {} @ {:.4} ({}), 5m {} @ {:.4} ({}), next in {}s",
+                    "{} arb placed: {} {} @ {:.4} ({}), {} {} @ {:.4} ({}), next in {}s",
                     sym_upper,
+                    long_label,
                     selection.leg1_outcome,
                     selection.leg1_price,
                     id1,
+                    short_label,
                     selection.leg2_outcome,
                     selection.leg2_price,
                     id2,
                     interval_secs
                 );
-                last_trade_at = Some(std::time::Instant::now());
-                let size_f64: f64 = shares.parse().unwrap_or(0.0);
-                trades.push(TradeRecord {
-                    symbol: symbol.to_string(),
-                    period_15,
-                    period_5,
-                    cid_15: cid_15.to_string(),
-                    cid_5: cid_5.to_string(),
-                    leg1_token: selection.leg1_token.to_string(),
-                    leg1_price: selection.leg1_price,
-                    leg1_cid: cid_15.to_string(),
-                    leg1_outcome: selection.leg1_outcome.to_string(),
-                    leg2_token: selection.leg2_token.to_string(),
-                    leg2_price: selection.leg2_price,
-                    leg2_cid: cid_5.to_string(),
-                    leg2_outcome: selection.leg2_outcome.to_string(),
-                    size: size_f64,
-                });
-            }
-            (Err(e), _) => {
-                warn!("{} arb leg1 place failed: {}", sym_upper, e);
+                last_trade_ms = Some(clock.now_ms());
+                trades.push(trade_record(&trade_id, period, &selection, &shares));
+                if let Some(cash) = available_usdc.as_mut() {
+                    let spent = selection.effective_sum() * shares.parse::<Decimal>().unwrap_or_default();
+                    *cash -= spent.to_f64().unwrap_or(0.0);
+                }
+                if is_canary {
+                    let order_ids = [res1.order_id.clone(), res2.order_id.clone()];
+                    tokio::spawn(confirm_canary(api.clone(), Arc::clone(canary), trade_id.clone(), order_ids));
+                }
             }
-            (_, Err(e)) => {
-                warn!("{} arb leg2 place failed: {}", sym_upper, e);
+            (Ok(_), Err(e)) | (Err(e), Ok(_)) => {
+                // One leg is on the book or filled without its pair: hold it as a one-sided trade so it is
+                // resolved, redeemed, and counted, and cool down before trying this symbol again.
+                let (held_leg, failed_leg) = if r1.is_ok() { (1, 2) } else { (2, 1) };
+                warn!(
+                    "{} arb leg{} place failed: {}; holding leg{} alone as a one-sided trade",
+                    sym_upper, failed_leg, e, held_leg
+                );
+                last_trade_ms = Some(clock.now_ms());
+                let pair = trade_record(&trade_id, period, &selection, &shares);
+                if let Some(held) = held_after_placing(pair, [r1.is_ok(), r2.is_ok()]) {
+                    if let Some(cash) = available_usdc.as_mut() {
+                        *cash -= (cost_per_pair(&held) * held.size).to_f64().unwrap_or(0.0);
+                    }
+                    trades.push(held);
+                }
+            }
+            (Err(e), Err(_)) => {
+                warn!("{} arb leg1 place failed: {}", sym_upper, e);
             }
         }
-
-        sleep(Duration::from_millis(LIVE_PRICE_POLL_MS)).await;
     }
 
-    ws_handle.abort();
+    if let Some(fill) = maker.cancel(api.as_ref()).await {
+        trades.extend(maker_trades(&fill, &journal, period));
+    }
+    for (trade_id, leg, cancel) in leg_cancels {
+        if let Ok(Some(matched)) = cancel.await {
+            apply_cancelled_leg(&mut trades, &trade_id, leg, matched);
+        }
+    }
+    if let Some(hedge) = hedge.as_mut() {
+        hedge.flatten().await;
+    }
+    for (trade_id, exit) in &exits {
+        warn!(
+            "{} arb {}: {} exit incomplete (legs sold {:?}); the rest is left to resolution",
+            sym_upper, trade_id, exit.reason, exit.sold
+        );
+    }
+    drop(feed);
+    if controls.is_stopping() {
+        info!("{}: stopping; overlap round for period {} wound up early", sym_upper, period_15);
+    }
     info!(
         "{} overlap window ended (period {}), {} trade(s) placed.",
         sym_upper,
@@ -188,3 +829,1167 @@ pub async fn run_overlap_round(
     );
     Ok(trades)
 }
+
+/// An open arb being closed early: which legs are sold so far, what they brought in (net of fees), and the PnL
+/// booked for them.
+struct Exit {
+    reason: &'static str,
+    sold: [bool; 2],
+    proceeds: Decimal,
+    pnl: Decimal,
+    last_attempt_ms: Option<i64>,
+}
+
+impl Exit {
+    /// Whether a sell may be tried at `now_ms`: the first time, then every `EXIT_RETRY_SECS`. Records the attempt.
+    fn attempt(&mut self, now_ms: i64) -> bool {
+        if self.last_attempt_ms.is_some_and(|t| now_ms - t < EXIT_RETRY_SECS as i64 * 1000) {
+            return false;
+        }
+        self.last_attempt_ms = Some(now_ms);
+        true
+    }
+}
+
+/// PnL per share pair of selling `trade`'s held legs at `bids` now, net of taker fees; None while a held leg has
+/// no bid. A leg the trade doesn't hold neither needs a bid nor brings anything in.
+fn exit_pnl_per_pair(trade: &TradeRecord, bids: [Option<Decimal>; 2], fees: &LegFees) -> Option<Decimal> {
+    let net = |leg: u8, bid: Option<Decimal>, bps: f64| match trade.holds_leg(leg) {
+        true => bid.map(|b| b - fee_per_share(b, bps)),
+        false => Some(Decimal::ZERO),
+    };
+    let (v1, v2) = net(1, bids[0], fees.bps_15).zip(net(2, bids[1], fees.bps_5))?;
+    Some(v1 + v2 - cost_per_pair(trade))
+}
+
+/// The exit to start for `trade` when selling at `bids` hits its take-profit or stop-loss, with the PnL per pair
+/// that triggered it.
+fn start_exit(
+    trade: &TradeRecord,
+    bids: [Option<Decimal>; 2],
+    fees: &LegFees,
+    rules: &ExitRulesConfig,
+) -> Option<(Exit, Decimal)> {
+    let pnl_per_pair = exit_pnl_per_pair(trade, bids, fees)?;
+    let take_profit = Decimal::from_f64(rules.take_profit_per_pair).unwrap_or_default();
+    let stop_loss = Decimal::from_f64(rules.stop_loss_per_pair).unwrap_or_default();
+    let reason = exit_trigger(pnl_per_pair, take_profit, stop_loss)?;
+    let exit = Exit {
+        reason,
+        sold: [!trade.holds_leg(1), !trade.holds_leg(2)],
+        proceeds: Decimal::ZERO,
+        pnl: Decimal::ZERO,
+        last_attempt_ms: None,
+    };
+    Some((exit, pnl_per_pair))
+}
+
+/// The trade an exit is selling, and what its logs need.
+struct ExitingTrade<'a> {
+    trade: &'a TradeRecord,
+    symbol: &'a str,
+    simulation: bool,
+}
+
+/// Try to sell each unsold leg of `exiting.trade` at its bid, booking each sale's PnL in the ledger as it goes.
+/// Returns what is still held: None once every leg is sold, the unsold leg as a one-sided trade after a partial
+/// exit, or the trade unchanged when nothing sold.
+async fn sell_out(
+    api: &dyn PredictionMarketVenue,
+    journal: &Journal,
+    ledger: &PnlLedger,
+    exiting: &ExitingTrade<'_>,
+    exit: &mut Exit,
+    bids: [Option<Decimal>; 2],
+    fees: &LegFees,
+) -> Option<TradeRecord> {
+    let ExitingTrade { trade, symbol, simulation } = *exiting;
+    let sim = if simulation { "[SIM] " } else { "" };
+    let legs = [
+        (trade.leg1_token.as_str(), trade.leg1_price + trade.leg1_fee, fees.bps_15),
+        (trade.leg2_token.as_str(), trade.leg2_price + trade.leg2_fee, fees.bps_5),
+    ];
+    for (i, (token, cost, bps)) in legs.into_iter().enumerate() {
+        let Some(bid) = bids[i].filter(|_| !exit.sold[i]) else {
+            continue;
+        };
+        let leg = i as u8 + 1;
+        if simulation || sell_leg(api, journal, &trade.trade_id, leg, token, trade.size, bid).await {
+            let proceeds = (bid - fee_per_share(bid, bps)) * trade.size;
+            let pnl = proceeds - cost * trade.size;
+            exit.sold[i] = true;
+            exit.proceeds += proceeds;
+            exit.pnl += pnl;
+            ledger.record(symbol, pnl);
+            info!(
+                "{}{} arb {} leg {} sold by {}: proceeds {:.2}, PnL {:.2}",
+                sim,
+                symbol.to_uppercase(),
+                trade.trade_id,
+                leg,
+                exit.reason,
+                proceeds,
+                pnl
+            );
+        }
+    }
+    match exit.sold {
+        [true, true] => {
+            info!(
+                "{}{} arb {} closed by {}: proceeds {:.2}, PnL {:.2}",
+                sim,
+                symbol.to_uppercase(),
+                trade.trade_id,
+                exit.reason,
+                exit.proceeds,
+                exit.pnl
+            );
+            journal.record(
+                Some(&trade.trade_id),
+                JournalEvent::PositionClosed {
+                    reason: exit.reason.to_string(),
+                    proceeds: exit.proceeds.to_f64().unwrap_or(0.0),
+                    pnl: exit.pnl.to_f64().unwrap_or(0.0),
+                },
+            );
+            None
+        }
+        // One leg is sold and booked; only the other is left to sell or to resolve.
+        [true, false] if trade.holds_leg(1) => Some(trade.one_sided(&trade.trade_id, 2, trade.size)),
+        [false, true] if trade.holds_leg(2) => Some(trade.one_sided(&trade.trade_id, 1, trade.size)),
+        _ => Some(trade.clone()),
+    }
+}
+
+/// Sell `size` shares of `token` at `bid`, all or nothing. True once sold.
+async fn sell_leg(
+    api: &dyn PredictionMarketVenue,
+    journal: &Journal,
+    trade_id: &str,
+    leg: u8,
+    token: &str,
+    size: Decimal,
+    bid: Decimal,
+) -> bool {
+    let order = OrderRequest {
+        token_id: token.to_string(),
+        side: Side::Sell,
+        size: format!("{:.2}", size),
+        price: format!("{:.4}", bid),
+        order_type: OrderType::Fok,
+        expiration: None,
+        client_order_id: None,
+    };
+    match place_leg(api, journal, trade_id, leg, &order).await {
+        Ok(_) => true,
+        Err(e) => {
+            warn!("Arb {} leg {} exit sell failed: {}", trade_id, leg, e);
+            false
+        }
+    }
+}
+
+/// Whether an arb passes the spot-based entry filters: the strike-distance band, the realized-vol limit, and the
+/// pricing model's minimum expected value.
+enum Entry {
+    Skip,
+    /// Taken, with the model's estimate when the pricing model is on.
+    Take(Option<ModelEstimate>),
+}
+
+/// The spot-based entry filters of one round, remembering which of them last blocked so each logs only when it
+/// starts or stops blocking.
+struct EntryFilters<'a> {
+    strategy: &'a StrategyConfig,
+    symbol: &'a str,
+    sym_upper: String,
+    strikes: (f64, f64),
+    /// Ends of the long and short periods.
+    ends: (i64, i64),
+    strike_blocked: bool,
+    vol_blocked: bool,
+    model_blocked: bool,
+}
+
+impl<'a> EntryFilters<'a> {
+    fn new(strategy: &'a StrategyConfig, symbol: &'a str, strikes: (f64, f64), ends: (i64, i64)) -> Self {
+        EntryFilters {
+            strategy,
+            symbol,
+            sym_upper: symbol.to_uppercase(),
+            strikes,
+            ends,
+            strike_blocked: false,
+            vol_blocked: false,
+            model_blocked: false,
+        }
+    }
+
+    async fn check(&mut self, spot_history: &SpotHistory, selection: &ArbSelection<'_>, now_ms: i64) -> Entry {
+        let (symbol, sym_upper, strikes) = (self.symbol, self.sym_upper.as_str(), self.strikes);
+        let strike_distance = &self.strategy.strike_distance;
+        if strike_distance.enabled {
+            let spot = spot_history
+                .read()
+                .await
+                .get(&symbol.to_lowercase())
+                .and_then(|ticks| ticks.back().copied())
+                .filter(|(ts, _)| now_ms - ts <= SPOT_STALE_SECS * 1000)
+                .map(|(_, price)| price);
+            let distance = spot.map(|s| distance_to_strike_bps(s, strikes.0, strikes.1));
+            let blocked = !distance.is_some_and(|d| strike_distance.allows(symbol, d));
+            if blocked != self.strike_blocked {
+                self.strike_blocked = blocked;
+                match distance {
+                    Some(d) => info!(
+                        "{}: spot {:.4} is {:.1} bps from the nearer strike ({:.4} / {:.4}), band {:?} {} bps; arbs {}",
+                        sym_upper,
+                        spot.unwrap_or(0.0),
+                        d,
+                        strikes.0,
+                        strikes.1,
+                        strike_distance.mode,
+                        strike_distance.band_bps_for(symbol),
+                        if blocked { "skipped" } else { "resume" }
+                    ),
+                    None => info!("{}: no fresh spot tick; arbs skipped until one arrives", sym_upper),
+                }
+            }
+            if blocked {
+                return Entry::Skip;
+            }
+        }
+
+        let vol_filter = &self.strategy.volatility_filter;
+        if vol_filter.enabled {
+            let since_ms = now_ms - vol_filter.window_secs as i64 * 1000;
+            let vol = spot_history
+                .read()
+                .await
+                .get(&symbol.to_lowercase())
+                .and_then(|ticks| realized_vol_bps(ticks.iter().copied(), since_ms));
+            let too_volatile = vol.is_some_and(|v| v > vol_filter.max_realized_vol_bps);
+            if too_volatile != self.vol_blocked {
+                self.vol_blocked = too_volatile;
+                info!(
+                    "{}: spot realized vol {:.1} bps over {}s {} the {} bps limit; arbs {}",
+                    sym_upper,
+                    vol.unwrap_or(0.0),
+                    vol_filter.window_secs,
+                    if too_volatile { "above" } else { "back under" },
+                    vol_filter.max_realized_vol_bps,
+                    if too_volatile { "skipped" } else { "resume" }
+                );
+            }
+            if too_volatile {
+                return Entry::Skip;
+            }
+        }
+
+        let pricing = &self.strategy.pricing_model;
+        if !pricing.enabled {
+            return Entry::Take(None);
+        }
+        let model =
+            model_estimate(spot_history, symbol, strikes, self.ends, pricing.vol_window_secs, selection, now_ms).await;
+        let blocked = !model.is_some_and(|m| m.expected_value >= pricing.min_expected_value);
+        if blocked != self.model_blocked {
+            self.model_blocked = blocked;
+            match &model {
+                Some(m) => info!(
+                    "{}: model EV {:.4} (P {} {:.3}, P {} {:.3}, spot {:.4}, vol {:.6}/√s) vs min {}; arbs {}",
+                    sym_upper,
+                    m.expected_value,
+                    selection.leg1_outcome,
+                    m.leg1_prob,
+                    selection.leg2_outcome,
+                    m.leg2_prob,
+                    m.spot,
+                    m.vol_per_sqrt_sec,
+                    pricing.min_expected_value,
+                    if blocked { "skipped" } else { "resume" }
+                ),
+                None => info!("{}: no fresh spot or volatility for the pricing model; arbs skipped", sym_upper),
+            }
+        }
+        match blocked {
+            true => Entry::Skip,
+            false => Entry::Take(model),
+        }
+    }
+}
+
+/// Fair-value estimate for `selection` at `now_ms` from the latest spot tick and the realized volatility over
+/// `vol_window_secs`; None without a fresh tick or enough ticks for a volatility.
+pub async fn model_estimate(
+    spot_history: &SpotHistory,
+    symbol: &str,
+    strikes: (f64, f64),
+    expiries: (i64, i64),
+    vol_window_secs: u64,
+    selection: &ArbSelection<'_>,
+    now_ms: i64,
+) -> Option<ModelEstimate> {
+    let (spot, vol) = spot_and_vol(spot_history, symbol, vol_window_secs, now_ms).await?;
+    let secs_to = |end: i64| (end * 1000 - now_ms).max(0) as f64 / 1000.0;
+    // Both legs of a complete set settle on the long market.
+    let (strikes, expiries) = if selection.leg2_long {
+        ((strikes.0, strikes.0), (expiries.0, expiries.0))
+    } else {
+        (strikes, expiries)
+    };
+    Some(estimate_arb(
+        spot,
+        strikes,
+        (secs_to(expiries.0), secs_to(expiries.1)),
+        vol,
+        selection.leg1_outcome,
+        selection.leg2_outcome,
+        selection.effective_sum().to_f64().unwrap_or(0.0),
+    ))
+}
+
+/// Latest spot and its volatility per √second over `vol_window_secs`, as of `now_ms`; None without a fresh tick
+/// or enough ticks for a volatility.
+pub async fn spot_and_vol(
+    spot_history: &SpotHistory,
+    symbol: &str,
+    vol_window_secs: u64,
+    now_ms: i64,
+) -> Option<(f64, f64)> {
+    let history = spot_history.read().await;
+    let ticks = history.get(&symbol.to_lowercase())?;
+    let (spot_ts, spot) = *ticks.back()?;
+    if now_ms - spot_ts > SPOT_STALE_SECS * 1000 {
+        return None;
+    }
+    let vol_bps = realized_vol_bps(ticks.iter().copied(), now_ms - vol_window_secs as i64 * 1000)?;
+    Some((spot, vol_per_sqrt_sec(vol_bps, vol_window_secs as f64)))
+}
+
+/// Pass `canary` once both of its legs report fully matched, polling for up to `CANARY_CONFIRM_SECS`; an ack
+/// alone proves nothing about fills, so a canary that rests or gets cancelled leaves the next arb at canary size.
+async fn confirm_canary(
+    api: Arc<dyn PredictionMarketVenue>,
+    canary: Arc<Canary>,
+    trade_id: String,
+    order_ids: [Option<String>; 2],
+) {
+    let [Some(id1), Some(id2)] = order_ids else {
+        warn!("Canary arb {} has a leg without an order id; staying at canary size", trade_id);
+        return;
+    };
+    for attempt in 0..CANARY_CONFIRM_SECS {
+        if attempt > 0 {
+            sleep(Duration::from_secs(1)).await;
+        }
+        let (s1, s2) = tokio::join!(api.get_order_status(&id1), api.get_order_status(&id2));
+        if [&s1, &s2].iter().all(|s| s.as_ref().is_ok_and(fully_matched)) {
+            canary.passed();
+            return;
+        }
+    }
+    warn!(
+        "Canary arb {} not filled on both legs within {}s; staying at canary size",
+        trade_id, CANARY_CONFIRM_SECS
+    );
+}
+
+/// The order matched its whole size.
+pub fn fully_matched(status: &OrderStatus) -> bool {
+    if status.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("matched")) {
+        return true;
+    }
+    let parse = |v: &Option<String>| v.as_deref().and_then(|v| v.trim().parse::<Decimal>().ok());
+    parse(&status.size_matched)
+        .zip(parse(&status.original_size))
+        .is_some_and(|(matched, original)| original > Decimal::ZERO && matched >= original)
+}
+
+/// Cancel `order_id` if it is still resting `after_secs` after placement, so a stale leg can't fill long after
+/// the edge is gone. A partially matched leg keeps what it got: returns the shares it matched once cancelled,
+/// None when it wasn't cancelled or its matched size is unknown.
+async fn cancel_if_unfilled(
+    api: Arc<dyn PredictionMarketVenue>,
+    journal: Arc<Journal>,
+    trade_id: String,
+    leg: u8,
+    order_id: String,
+    after_secs: u64,
+) -> Option<Decimal> {
+    sleep(Duration::from_secs(after_secs)).await;
+    let mut size_matched = match api.get_order_status(&order_id).await {
+        Ok(status) => {
+            if !status.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("live")) {
+                return None;
+            }
+            status.size_matched
+        }
+        Err(e) => {
+            warn!("Arb {} leg {} status unavailable, cancelling anyway: {}", trade_id, leg, e);
+            None
+        }
+    };
+    match api.cancel_order(&order_id).await {
+        Ok(()) => {
+            if size_matched.is_none() {
+                size_matched = api.get_order_status(&order_id).await.ok().and_then(|s| s.size_matched);
+            }
+            info!(
+                "Arb {} leg {} unfilled after {}s (matched {}), cancelled",
+                trade_id,
+                leg,
+                after_secs,
+                size_matched.as_deref().unwrap_or("?")
+            );
+            journal.record(
+                Some(&trade_id),
+                JournalEvent::OrderCancelled {
+                    leg,
+                    order_id,
+                    size_matched: size_matched.clone(),
+                },
+            );
+            size_matched.and_then(|m| m.trim().parse().ok())
+        }
+        Err(e) => {
+            warn!("Arb {} leg {} cancel failed: {}", trade_id, leg, e);
+            None
+        }
+    }
+}
+
+/// Cut trade `trade_id` (with any leg split off it) down to the `matched` shares leg `leg` got before the rest of
+/// its order was cancelled, so resolution, PnL, and redemption only count what was bought.
+fn apply_cancelled_leg(trades: &mut Vec<TradeRecord>, trade_id: &str, leg: u8, matched: Decimal) {
+    let split_off = format!("{}-leg", trade_id);
+    let (parts, rest): (Vec<_>, Vec<_>) = trades
+        .drain(..)
+        .partition(|t| t.trade_id == trade_id || t.trade_id.starts_with(&split_off));
+    *trades = rest;
+    let Some(first) = parts.first() else {
+        return;
+    };
+    let mut held = [1u8, 2].map(|l| parts.iter().filter(|t| t.holds_leg(l)).map(|t| t.size).sum::<Decimal>());
+    let i = leg as usize - 1;
+    held[i] = held[i].min(matched);
+    let pair = TradeRecord {
+        trade_id: trade_id.to_string(),
+        only_leg: None,
+        ..first.clone()
+    };
+    trades.extend(pair.matched(held));
+}
+
+/// Trades for what resting maker quotes filled (see `MakerFill::trades`).
+fn maker_trades(fill: &MakerFill, journal: &Journal, period: &PeriodContext) -> Vec<TradeRecord> {
+    let pair = trade_record(&fill.trade_id, period, &fill.selection, "0");
+    fill.trades(&pair, journal)
+}
+
+/// The arb `selection` bought in `period`, `shares` per leg.
+pub fn trade_record(trade_id: &str, period: &PeriodContext, selection: &ArbSelection, shares: &str) -> TradeRecord {
+    TradeRecord {
+        trade_id: trade_id.to_string(),
+        symbol: period.symbol.clone(),
+        period_15: period.period_15,
+        period_5: period.period_5,
+        cid_15: period.cid_15.clone(),
+        cid_5: period.cid_5.clone(),
+        leg1_token: selection.leg1_token.to_string(),
+        leg1_price: selection.leg1_price,
+        leg1_cid: period.cid_15.clone(),
+        leg1_outcome: selection.leg1_outcome.to_string(),
+        leg2_token: selection.leg2_token.to_string(),
+        leg2_price: selection.leg2_price,
+        leg2_cid: if selection.leg2_long { &period.cid_15 } else { &period.cid_5 }.clone(),
+        leg2_outcome: selection.leg2_outcome.to_string(),
+        size: shares.parse().unwrap_or_default(),
+        leg1_fee: selection.leg1_fee,
+        leg2_fee: selection.leg2_fee,
+        only_leg: None,
+    }
+}
+
+/// What placing an arb's legs leaves held: the pair when both were placed, the one leg that was when the other
+/// failed, or nothing.
+fn held_after_placing(pair: TradeRecord, placed: [bool; 2]) -> Option<TradeRecord> {
+    match placed {
+        [true, true] => Some(pair),
+        [true, false] => Some(pair.one_sided(&pair.trade_id, 1, pair.size)),
+        [false, true] => Some(pair.one_sided(&pair.trade_id, 2, pair.size)),
+        [false, false] => None,
+    }
+}
+
+/// Buy order for one arb leg with the configured `leg_order_type`; GTD legs expire `leg_order_ttl_secs`
+/// after CLOB server time, on top of the minute the CLOB requires as a safety margin.
+fn leg_order(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    token_id: &str,
+    price: Decimal,
+    shares: &str,
+) -> OrderRequest {
+    let order_type = config.strategy.leg_type();
+    let expiration = (order_type == OrderType::Gtd).then(|| {
+        (api.server_now_unix() + GTD_SAFETY_MARGIN_SECS + config.strategy.leg_order_ttl_secs as i64).to_string()
+    });
+    OrderRequest {
+        token_id: token_id.to_string(),
+        side: Side::Buy,
+        size: shares.to_string(),
+        price: format!("{:.4}", price),
+        order_type,
+        expiration,
+        client_order_id: None,
+    }
+}
+
+/// `shares` varied uniformly within ±`pct` percent, floored to 2 decimals; unchanged when `pct` is 0.
+fn jittered_shares(shares: &str, pct: f64) -> String {
+    if pct <= 0.0 {
+        return shares.to_string();
+    }
+    let base: f64 = shares.trim().parse().unwrap_or(0.0);
+    let factor = 1.0 + rand::thread_rng().gen_range(-pct..=pct) / 100.0;
+    format!("{:.2}", (base * factor * 100.0).floor() / 100.0)
+}
+
+/// Place the thinner leg first as FOK and the other one only once it filled. Results are returned in
+/// leg order; a leg not placed because the thinner one missed is an error.
+async fn place_thinner_first(
+    api: &dyn PredictionMarketVenue,
+    journal: &Journal,
+    trade_id: &str,
+    order1: &OrderRequest,
+    order2: &OrderRequest,
+    leg2_first: bool,
+) -> (Result<OrderResponse>, Result<OrderResponse>) {
+    let (first_leg, first, second_leg, second) = if leg2_first {
+        (2, order2, 1, order1)
+    } else {
+        (1, order1, 2, order2)
+    };
+    let fok = OrderRequest {
+        order_type: OrderType::Fok,
+        expiration: None,
+        ..first.clone()
+    };
+    let first_result = match place_leg(api, journal, trade_id, first_leg, &fok).await {
+        Ok(res) if res.status.eq_ignore_ascii_case("matched") => Ok(res),
+        Ok(res) => {
+            // Not confirmed filled: make sure nothing is left resting before giving up on the arb.
+            if let Some(order_id) = res.order_id.as_deref() {
+                if let Err(e) = api.cancel_order(order_id).await {
+                    warn!("Cancel of unconfirmed FOK leg {} ({}) failed: {}", first_leg, order_id, e);
+                }
+            }
+            Err(anyhow::anyhow!("FOK leg {} not confirmed filled (status {})", first_leg, res.status))
+        }
+        Err(e) => Err(e),
+    };
+    let second_result = match &first_result {
+        Ok(_) => place_leg(api, journal, trade_id, second_leg, second).await,
+        Err(e) => Err(anyhow::anyhow!("not placed, thinner leg {} missed: {}", first_leg, e)),
+    };
+    if leg2_first {
+        (second_result, first_result)
+    } else {
+        (first_result, second_result)
+    }
+}
+
+/// Place one arb leg, journaling submission under a client order id and the CLOB acknowledgement or error.
+/// A post that fails ambiguously is looked up for `AMBIGUOUS_LOOKUP_SECS` before it is posted once more, so a
+/// lost answer can't double the leg; when the lookup itself fails the leg is given up rather than risk a second
+/// order.
+pub async fn place_leg(
+    api: &dyn PredictionMarketVenue,
+    journal: &Journal,
+    trade_id: &str,
+    leg: u8,
+    order: &OrderRequest,
+) -> Result<OrderResponse> {
+    let client_order_id = order
+        .client_order_id
+        .clone()
+        .unwrap_or_else(|| format!("{}-{}-{}", trade_id, leg, chrono::Utc::now().timestamp_millis()));
+    journal.record(
+        Some(trade_id),
+        JournalEvent::OrderSubmitted {
+            leg,
+            token_id: order.token_id.clone(),
+            side: order.side,
+            price: order.price.clone(),
+            size: order.size.clone(),
+            client_order_id: Some(client_order_id.clone()),
+        },
+    );
+    let submitted_at = api.server_now_unix() - AMBIGUOUS_POST_SLACK_SECS;
+    let mut result = api.place_order(order).await;
+    if result.as_ref().is_err_and(|e| e.is::<AmbiguousPost>()) {
+        warn!("Arb {} leg {} ({}): post outcome unknown, looking it up", trade_id, leg, client_order_id);
+        let deadline = api.server_now_unix() + AMBIGUOUS_LOOKUP_SECS;
+        let lookup = loop {
+            match api.find_submitted_order(order, submitted_at).await {
+                Ok(None) if api.server_now_unix() < deadline => {
+                    sleep(Duration::from_millis(AMBIGUOUS_LOOKUP_POLL_MS)).await
+                }
+                other => break other,
+            }
+        };
+        match lookup {
+            Ok(found) => {
+                journal.record(
+                    Some(trade_id),
+                    JournalEvent::OrderLookedUp {
+                        leg,
+                        client_order_id: client_order_id.clone(),
+                        found: found.is_some(),
+                        order_id: found.as_ref().and_then(|f| f.order_id.clone()),
+                    },
+                );
+                result = match found {
+                    Some(res) => {
+                        info!("Arb {} leg {} ({}) was taken: {}", trade_id, leg, client_order_id, res.status);
+                        Ok(res)
+                    }
+                    None => {
+                        info!("Arb {} leg {} ({}) not on the book, posting again", trade_id, leg, client_order_id);
+                        api.place_order(order).await
+                    }
+                };
+            }
+            Err(e) => warn!("Arb {} leg {} ({}) lookup failed, not retrying: {}", trade_id, leg, client_order_id, e),
+        }
+    }
+    let event = match &result {
+        Ok(res) => JournalEvent::OrderAcked {
+            leg,
+            order_id: res.order_id.clone(),
+            status: res.status.clone(),
+        },
+        Err(e) => JournalEvent::OrderFailed {
+            leg,
+            error: e.to_string(),
+        },
+    };
+    journal.record(Some(trade_id), event);
+    result
+}
+
+/// Wait for the feed's next quote update, at most until `wake_at_ms` when that is still ahead and never longer
+/// than IDLE_WAKEUP_MS.
+async fn wait_for_quotes(
+    updates: &mut watch::Receiver<()>,
+    clock: &dyn Clock,
+    wake_at_ms: Option<i64>,
+    waits: Option<&mpsc::UnboundedSender<i64>>,
+) {
+    let now_ms = clock.now_ms();
+    let wait_ms = wake_at_ms
+        .map(|t| t - now_ms)
+        .filter(|ms| *ms > 0)
+        .map_or(IDLE_WAKEUP_MS, |ms| ms.min(IDLE_WAKEUP_MS)) as u64;
+    // A stepped feed: whoever moves the clock wakes the round.
+    if let Some(waits) = waits {
+        if waits.send(now_ms + wait_ms as i64).is_ok() {
+            let _ = updates.changed().await;
+        }
+        return;
+    }
+    let wait = Duration::from_millis(wait_ms);
+    if let Ok(Err(_)) = timeout(wait, updates.changed()).await {
+        // The WS task is gone and no update will come.
+        sleep(wait).await;
+    }
+}
+
+/// Once the WS has delivered its initial books, fetch a REST snapshot of every token in one batch request and
+/// correct any best bid/ask the WS state disagrees with, so a WS processing bug can't silently drive trades.
+async fn cross_check_books(api: &dyn PredictionMarketVenue, prices: &PricesSnapshot, asset_ids: &[String], symbol: &str) {
+    // A wait on the WS, not market time: on tokio's timer like the polling sleeps.
+    let warmup = tokio::time::Instant::now();
+    while warmup.elapsed().as_secs() < BOOK_CHECK_WARMUP_SECS {
+        if asset_ids.iter().all(|id| prices.contains_key(id)) {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    reconcile_books(api, prices, asset_ids, symbol, "book cross-check").await;
+}
+
+/// Overwrite the WS best bid/ask of `asset_ids` with a REST snapshot where they disagree; `why` labels the logs.
+/// False when the snapshot couldn't be fetched for all of them.
+async fn reconcile_books(
+    api: &dyn PredictionMarketVenue,
+    prices: &PricesSnapshot,
+    asset_ids: &[String],
+    symbol: &str,
+    why: &str,
+) -> bool {
+    let ids: Vec<&str> = asset_ids.iter().map(String::as_str).collect();
+    let books = match api.get_orderbooks(&ids).await {
+        Ok(books) => books,
+        Err(e) => {
+            warn!("{} {}: REST snapshot failed: {}", symbol.to_uppercase(), why, e);
+            return false;
+        }
+    };
+    let mut complete = true;
+    for asset_id in asset_ids {
+        let Some(book) = books.get(asset_id) else {
+            warn!("{} {}: no REST snapshot for {}", symbol.to_uppercase(), why, asset_id);
+            complete = false;
+            continue;
+        };
+        if let Some(stale) = reconcile_with_snapshot(prices, asset_id, book) {
+            let (checked, mismatched) = book_check_counts();
+            warn!(
+                "{} {}: WS bid/ask {:?}/{:?} for {} disagreed with REST; corrected ({} of {} books mismatched so far)",
+                symbol.to_uppercase(),
+                why,
+                stale.bid,
+                stale.ask,
+                asset_id,
+                mismatched,
+                checked
+            );
+        }
+    }
+    complete
+}
+
+/// The legs to send once the slippage guard has compared `selection` with the `latest` quotes (None without an
+/// ask on either leg), or why the arb is aborted.
+fn slippage_checked<'a>(
+    guard: &SlippageGuardConfig,
+    selection: ArbSelection<'a>,
+    latest: Option<ArbSelection<'a>>,
+    threshold: Decimal,
+) -> Result<ArbSelection<'a>, String> {
+    let limit = threshold - Decimal::from_f64(guard.buffer).unwrap_or_default();
+    // Quotes that haven't worsened since detection are never slippage, whatever the buffer.
+    match latest {
+        Some(latest) if latest.effective_sum() <= selection.effective_sum() || latest.effective_sum() < limit => {
+            Ok(if guard.reprice { latest } else { selection })
+        }
+        _ => Err(format!(
+            "slippage guard: sum {:.4} at detection, {} now (limit {:.4})",
+            selection.effective_sum(),
+            latest.map(|l| format!("{:.4}", l.effective_sum())).unwrap_or_else(|| "no ask".to_string()),
+            limit
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::domain::pnl::sample_trade;
+    use crate::models::Outcome;
+
+    fn order() -> OrderRequest {
+        OrderRequest {
+            token_id: "15up".to_string(),
+            side: Side::Buy,
+            size: "5".to_string(),
+            price: "0.4500".to_string(),
+            order_type: OrderType::Fok,
+            expiration: None,
+            client_order_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn a_prefetched_feed_is_used_only_for_its_own_tokens() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let stepped = |asset_ids: Vec<String>| {
+            let (waits_tx, waits_rx) = mpsc::unbounded_channel();
+            let feed = MarketFeed::stepped(asset_ids, Arc::new(DashMap::new()), watch::channel(()).1, waits_tx);
+            (feed, waits_rx)
+        };
+        let overlap = ids(&["15up", "15down", "5up", "5down"]);
+
+        let (prefetched, _waits) = stepped(overlap.clone());
+        let prefetched_prices = prefetched.prices();
+        let feed = MarketFeed::for_overlap(Some(prefetched), &overlap, "ws://127.0.0.1:1", "btc", None);
+        assert!(Arc::ptr_eq(&feed.prices(), &prefetched_prices));
+        assert!(feed.handle.is_none(), "no second WS is opened");
+
+        // Prefetched for another overlap: a new WS is opened and the stale feed closed.
+        let (stale, mut stale_waits) = stepped(ids(&["a", "b", "c", "d"]));
+        let feed = MarketFeed::for_overlap(Some(stale), &overlap, "ws://127.0.0.1:1", "btc", None);
+        assert_eq!((feed.asset_ids.as_slice(), feed.handle.is_some()), (overlap.as_slice(), true));
+        assert_eq!(stale_waits.try_recv(), Err(mpsc::error::TryRecvError::Disconnected));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn a_round_wakes_on_a_quote_update_a_cooldown_end_or_the_idle_wakeup() {
+        let clock = crate::utils::clock::ManualClock::new(1_000_000);
+        let (updates_tx, mut updates) = watch::channel(());
+        let waited = |since: tokio::time::Instant| since.elapsed().as_millis() as i64;
+
+        let since = tokio::time::Instant::now();
+        updates_tx.send_replace(());
+        wait_for_quotes(&mut updates, &clock, None, None).await;
+        assert_eq!(waited(since), 0, "an update wakes it at once");
+
+        // A cooldown ending before the idle wakeup shortens the wait; one already over doesn't.
+        let waits = [(None, IDLE_WAKEUP_MS), (Some(1_000_100), 100), (Some(999_000), IDLE_WAKEUP_MS)];
+        for (wake_at_ms, expected) in waits {
+            let since = tokio::time::Instant::now();
+            wait_for_quotes(&mut updates, &clock, wake_at_ms, None).await;
+            assert_eq!(waited(since), expected, "waking at {:?}", wake_at_ms);
+        }
+
+        // A stepped feed reports when it would wake and blocks until the next update instead.
+        let (waits_tx, mut waits_rx) = mpsc::unbounded_channel();
+        let stepped = tokio::spawn(async move {
+            wait_for_quotes(&mut updates, &clock, Some(1_000_040), Some(&waits_tx)).await;
+            updates
+        });
+        assert_eq!(waits_rx.recv().await, Some(1_000_040));
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert!(!stepped.is_finished());
+        updates_tx.send_replace(());
+        let mut updates = stepped.await.unwrap();
+
+        // With the WS gone it still paces itself rather than spinning.
+        drop(updates_tx);
+        let since = tokio::time::Instant::now();
+        wait_for_quotes(&mut updates, &crate::utils::clock::ManualClock::new(0), None, None).await;
+        assert_eq!(waited(since), IDLE_WAKEUP_MS);
+    }
+
+    #[test]
+    fn jittered_sizes_stay_within_the_percentage_and_floor_to_cents() {
+        assert_eq!(jittered_shares("12.34", 0.0), "12.34");
+        for _ in 0..200 {
+            let shares: f64 = jittered_shares("100", 5.0).parse().unwrap();
+            assert!((95.0..=105.0).contains(&shares), "{} outside ±5%", shares);
+            assert_eq!(shares, (shares * 100.0).round() / 100.0);
+        }
+    }
+
+    #[test]
+    fn the_slippage_guard_skips_an_arb_repriced_past_its_limit() {
+        let selection = ArbSelection {
+            leg1_token: "15up",
+            leg1_price: Decimal::new(45, 2),
+            leg2_token: "5down",
+            leg2_price: Decimal::new(47, 2),
+            leg1_outcome: Outcome::Up,
+            leg2_outcome: Outcome::Down,
+            leg1_fee: Decimal::ZERO,
+            leg2_fee: Decimal::ZERO,
+            leg2_long: false,
+        };
+        let at = |leg2_ask: i64| {
+            Some(ArbSelection {
+                leg2_price: Decimal::new(leg2_ask, 2),
+                ..selection
+            })
+        };
+        let guard = SlippageGuardConfig {
+            enabled: true,
+            buffer: 0.01,
+            reprice: true,
+        };
+        let threshold = Decimal::new(97, 2);
+        // 0.45 + 0.52 = 0.97 is past the 0.96 limit: no order goes out.
+        let reason = slippage_checked(&guard, selection, at(52), threshold).unwrap_err();
+        assert!(reason.contains("0.9200 at detection, 0.9700 now"), "{}", reason);
+        assert!(slippage_checked(&guard, selection, None, threshold).is_err());
+        // Worse but under the limit: sent at the latest asks.
+        let sent = slippage_checked(&guard, selection, at(50), threshold).unwrap();
+        assert_eq!(sent.leg2_price, Decimal::new(50, 2));
+        // Without repricing the detected prices are kept.
+        let guard = SlippageGuardConfig { reprice: false, ..guard };
+        assert_eq!(slippage_checked(&guard, selection, at(50), threshold).unwrap().leg2_price, Decimal::new(47, 2));
+    }
+
+    #[test]
+    fn a_canary_passes_only_once_both_legs_fill() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let api = Arc::new(MockPolymarketApi::new());
+        api.rest_orders(true);
+        let order = OrderRequest {
+            order_type: OrderType::Gtc,
+            ..order()
+        };
+        let (id1, id2) = rt.block_on(async {
+            let r1 = api.place_order(&order).await.unwrap();
+            let r2 = api.place_order(&order).await.unwrap();
+            (r1.order_id.unwrap(), r2.order_id.unwrap())
+        });
+        let canary = Arc::new(Canary::load(&crate::config::StrategyConfig {
+            canary_fraction: 0.1,
+            canary_state_path: String::new(),
+            ..crate::config::Config::default().strategy
+        }));
+        let confirm = confirm_canary(api.clone(), Arc::clone(&canary), "btc-1-1".to_string(), [
+            Some(id1.clone()),
+            Some(id2.clone()),
+        ]);
+        rt.block_on(async {
+            let confirm = tokio::spawn(confirm);
+            // Acked and resting, or filled on one leg only, is no fill.
+            api.fill_resting(&id1, 5.0);
+            sleep(Duration::from_millis(1500)).await;
+            assert!(canary.is_pending());
+            api.fill_resting(&id2, 5.0);
+            confirm.await.unwrap();
+        });
+        assert!(!canary.is_pending());
+    }
+
+    #[test]
+    fn a_leg_placed_without_its_pair_is_held_one_sided() {
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let journal = Journal::new("");
+        let api = MockPolymarketApi::new();
+        // The thinner leg 2 fills, then the CLOB fails the post of leg 1.
+        api.fail_orders_after(1);
+        let order2 = OrderRequest {
+            token_id: "5down".to_string(),
+            ..order()
+        };
+        let (r1, r2) = rt.block_on(place_thinner_first(&api, &journal, "btc-1-1", &order(), &order2, true));
+        assert!(r1.is_err() && r2.is_ok());
+        assert_eq!(api.orders().len(), 1);
+
+        let pair = sample_trade();
+        let held = held_after_placing(pair.clone(), [r1.is_ok(), r2.is_ok()]).unwrap();
+        assert_eq!((held.trade_id.as_str(), held.only_leg, held.size), ("btc-1-1", Some(2), Decimal::TEN));
+        assert_eq!(cost_per_pair(&held), Decimal::new(47, 2));
+        assert!(held_after_placing(pair.clone(), [true, true]).is_some_and(|t| t.only_leg.is_none()));
+        assert!(held_after_placing(pair, [false, false]).is_none());
+    }
+
+    #[tokio::test]
+    async fn a_leg_cancelled_after_a_partial_fill_shrinks_its_trade() {
+        let journal = Arc::new(Journal::new(""));
+        let api = Arc::new(MockPolymarketApi::new());
+        api.rest_orders(true);
+        let gtc = |token: &str| OrderRequest {
+            token_id: token.to_string(),
+            size: "10".to_string(),
+            order_type: OrderType::Gtc,
+            ..order()
+        };
+        let leg1 = place_leg(api.as_ref(), &journal, "btc-1-1", 1, &gtc("a")).await.unwrap();
+        let leg2 = place_leg(api.as_ref(), &journal, "btc-1-1", 2, &gtc("b")).await.unwrap();
+        api.fill_resting("mock-1", 10.0);
+        api.fill_resting("mock-2", 4.0);
+
+        let cancel = |order_id: Option<String>, leg| {
+            cancel_if_unfilled(api.clone(), Arc::clone(&journal), "btc-1-1".to_string(), leg, order_id.unwrap(), 0)
+        };
+        // Leg 1 filled in full and is left alone; leg 2 rested with 4 of 10 and is cancelled.
+        assert_eq!(cancel(leg1.order_id, 1).await, None);
+        assert_eq!(cancel(leg2.order_id, 2).await, Some(Decimal::from(4)));
+        assert_eq!(api.cancelled(), vec!["mock-2"]);
+
+        let mut trades = vec![sample_trade()];
+        apply_cancelled_leg(&mut trades, "btc-1-1", 2, Decimal::from(4));
+        assert_eq!(trades.len(), 2);
+        let held = |t: &TradeRecord| (t.trade_id.clone(), t.size, t.only_leg);
+        assert_eq!(held(&trades[0]), ("btc-1-1".to_string(), Decimal::from(4), None));
+        assert_eq!((trades[1].trade_id.as_str(), trades[1].size), ("btc-1-1-leg1", Decimal::from(6)));
+        assert_eq!(trades[1].only_leg, Some(1));
+
+        // Leg 1 turns out cut short too: the pairs shrink to it and leg 2's 4 shares stay held.
+        apply_cancelled_leg(&mut trades, "btc-1-1", 1, Decimal::from(3));
+        assert_eq!(trades.len(), 2);
+        assert_eq!((trades[0].size, trades[0].only_leg), (Decimal::from(3), None));
+        assert_eq!((trades[1].trade_id.as_str(), trades[1].size), ("btc-1-1-leg2", Decimal::ONE));
+        // Nothing matched on leg 1 after all: leg 2 alone is left, under the trade's own id.
+        apply_cancelled_leg(&mut trades, "btc-1-1", 1, Decimal::ZERO);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(held(&trades[0]), ("btc-1-1".to_string(), Decimal::from(4), Some(2)));
+    }
+
+    #[tokio::test]
+    async fn a_partial_exit_books_the_sold_leg_and_holds_the_other_alone() {
+        let journal = Journal::new("");
+        let ledger = PnlLedger::load("");
+        let api = MockPolymarketApi::new();
+        // Leg 1 sells, then the CLOB fails the sell of leg 2.
+        api.fail_orders_after(1);
+        let trade = sample_trade();
+        let mut exit = Exit {
+            reason: "take-profit",
+            sold: [false, false],
+            proceeds: Decimal::ZERO,
+            pnl: Decimal::ZERO,
+            last_attempt_ms: None,
+        };
+        let bids = [Some(Decimal::new(60, 2)), Some(Decimal::new(50, 2))];
+        let exiting = ExitingTrade { trade: &trade, symbol: "btc", simulation: false };
+        let held = sell_out(&api, &journal, &ledger, &exiting, &mut exit, bids, &LegFees::default()).await;
+        let held = held.expect("leg 2 still held");
+        assert_eq!((held.trade_id.as_str(), held.only_leg, held.size), ("btc-1-1", Some(2), Decimal::TEN));
+        // 10 x 0.60 in for 10 x 0.45 out.
+        assert_eq!(ledger.total(), Decimal::new(15, 1));
+        assert_eq!(exit.sold, [true, false]);
+
+        // The retry sells leg 2 from the one-sided trade: 10 x 0.50 against 10 x 0.47.
+        api.fail_orders_after(1);
+        let exiting = ExitingTrade { trade: &held, ..exiting };
+        assert!(sell_out(&api, &journal, &ledger, &exiting, &mut exit, bids, &LegFees::default()).await.is_none());
+        assert_eq!(ledger.total(), Decimal::new(18, 1));
+        assert_eq!(exit.pnl, Decimal::new(18, 1));
+        let sells: Vec<String> = api.orders().into_iter().map(|o| o.token_id).collect();
+        assert_eq!(sells, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn ambiguous_posts_are_looked_up_before_a_retry() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let journal = Journal::new("");
+
+        // The answer got lost but the order went through: adopted, not posted again.
+        let api = MockPolymarketApi::new();
+        api.fail_posts_ambiguously(1, true);
+        let res = rt.block_on(place_leg(&api, &journal, "btc-1-1", 1, &order())).unwrap();
+        assert_eq!(res.order_id.as_deref(), Some("mock-1"));
+        assert_eq!(api.orders().len(), 1);
+
+        // It went through but only shows up on the second lookup: still adopted.
+        let api = MockPolymarketApi::new();
+        api.fail_posts_ambiguously(1, true);
+        api.lag_lookups(1);
+        let res = rt.block_on(place_leg(&api, &journal, "btc-1-1", 1, &order())).unwrap();
+        assert_eq!(res.order_id.as_deref(), Some("mock-1"));
+        assert_eq!(api.orders().len(), 1);
+
+        // The post never arrived: posted once more.
+        let api = MockPolymarketApi::new();
+        api.fail_posts_ambiguously(1, false);
+        let res = rt.block_on(place_leg(&api, &journal, "btc-1-1", 1, &order())).unwrap();
+        assert_eq!(res.status, "matched");
+        assert_eq!(api.orders().len(), 1);
+    }
+
+    const NOW_MS: i64 = 1_000_000_000;
+
+    fn spot(ticks: &[(i64, f64)]) -> SpotHistory {
+        let history = HashMap::from([("btc".to_string(), ticks.iter().copied().collect())]);
+        Arc::new(RwLock::new(history))
+    }
+
+    fn filters(strategy: &StrategyConfig) -> EntryFilters<'_> {
+        let now = NOW_MS / 1000;
+        EntryFilters::new(strategy, "btc", (100_000.0, 100_000.0), (now + 600, now + 300))
+    }
+
+    fn arb() -> ArbSelection<'static> {
+        ArbSelection {
+            leg1_token: "15up",
+            leg1_price: Decimal::new(45, 2),
+            leg2_token: "5down",
+            leg2_price: Decimal::new(47, 2),
+            leg1_outcome: Outcome::Up,
+            leg2_outcome: Outcome::Down,
+            leg1_fee: Decimal::ZERO,
+            leg2_fee: Decimal::ZERO,
+            leg2_long: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn the_strike_band_takes_arbs_only_near_a_fresh_spot() {
+        let mut strategy = Config::default().strategy;
+        strategy.strike_distance.enabled = true;
+        strategy.strike_distance.band_bps = 20.0;
+        let mut filters = filters(&strategy);
+
+        // 1 bps from the strike is inside the band; 50 bps isn't.
+        let near = spot(&[(NOW_MS - 1_000, 100_010.0)]);
+        assert!(matches!(filters.check(&near, &arb(), NOW_MS).await, Entry::Take(None)));
+        let far = spot(&[(NOW_MS - 1_000, 100_500.0)]);
+        assert!(matches!(filters.check(&far, &arb(), NOW_MS).await, Entry::Skip));
+        assert!(filters.strike_blocked);
+        // A near spot that has gone stale says nothing about where it is now.
+        let stale = spot(&[(NOW_MS - (SPOT_STALE_SECS + 1) * 1000, 100_010.0)]);
+        assert!(matches!(filters.check(&stale, &arb(), NOW_MS).await, Entry::Skip));
+        assert!(matches!(filters.check(&near, &arb(), NOW_MS).await, Entry::Take(None)));
+        assert!(!filters.strike_blocked);
+    }
+
+    #[tokio::test]
+    async fn a_spot_whipping_past_the_vol_limit_skips_arbs() {
+        let mut strategy = Config::default().strategy;
+        strategy.volatility_filter.enabled = true;
+        strategy.volatility_filter.max_realized_vol_bps = 25.0;
+        let mut filters = filters(&strategy);
+
+        let calm = spot(&[(NOW_MS - 2_000, 100_000.0), (NOW_MS - 1_000, 100_010.0)]);
+        assert!(matches!(filters.check(&calm, &arb(), NOW_MS).await, Entry::Take(None)));
+        let whipping = spot(&[(NOW_MS - 2_000, 100_000.0), (NOW_MS - 1_000, 100_500.0), (NOW_MS, 100_000.0)]);
+        assert!(matches!(filters.check(&whipping, &arb(), NOW_MS).await, Entry::Skip));
+        assert!(filters.vol_blocked);
+    }
+
+    #[tokio::test]
+    async fn the_pricing_model_takes_arbs_at_its_minimum_expected_value() {
+        let mut strategy = Config::default().strategy;
+        strategy.pricing_model.enabled = true;
+        // Spot on both strikes: each leg is a coin flip, so the 0.92 pair is worth 0.08 per share pair.
+        let history = spot(&[(NOW_MS - 2_000, 100_000.0), (NOW_MS - 1_000, 100_010.0), (NOW_MS, 100_000.0)]);
+
+        strategy.pricing_model.min_expected_value = 0.05;
+        match filters(&strategy).check(&history, &arb(), NOW_MS).await {
+            Entry::Take(Some(model)) => assert!((model.expected_value - 0.08).abs() < 1e-4, "{:?}", model),
+            _ => panic!("an EV of 0.08 clears a 0.05 minimum"),
+        }
+        strategy.pricing_model.min_expected_value = 0.1;
+        assert!(matches!(filters(&strategy).check(&history, &arb(), NOW_MS).await, Entry::Skip));
+        // No spot, no estimate: skipped rather than taken blind.
+        strategy.pricing_model.min_expected_value = 0.0;
+        assert!(matches!(filters(&strategy).check(&spot(&[]), &arb(), NOW_MS).await, Entry::Skip));
+    }
+
+    #[test]
+    fn exits_start_at_the_take_profit_or_stop_loss_of_the_held_legs() {
+        let rules = ExitRulesConfig {
+            take_profit_per_pair: 0.05,
+            stop_loss_per_pair: 0.05,
+        };
+        let fees = LegFees::default();
+        let bids = |b1: Option<i64>, b2: Option<i64>| [b1.map(|b| Decimal::new(b, 2)), b2.map(|b| Decimal::new(b, 2))];
+        let trade = sample_trade();
+
+        // Paid 0.92 per pair: 0.98 at the bids is a 0.06 profit, 0.85 a 0.07 loss, 0.93 neither.
+        let (exit, pnl) = start_exit(&trade, bids(Some(50), Some(48)), &fees, &rules).unwrap();
+        assert_eq!((exit.reason, exit.sold, pnl), ("take-profit", [false, false], Decimal::new(6, 2)));
+        let (exit, pnl) = start_exit(&trade, bids(Some(40), Some(45)), &fees, &rules).unwrap();
+        assert_eq!((exit.reason, pnl), ("stop-loss", Decimal::new(-7, 2)));
+        assert!(start_exit(&trade, bids(Some(46), Some(47)), &fees, &rules).is_none());
+        // A held leg without a bid can't be valued.
+        assert!(start_exit(&trade, bids(Some(90), None), &fees, &rules).is_none());
+
+        // Holding leg 1 alone, bought at 0.45: leg 2 needs no bid and is already "sold".
+        let one_sided = TradeRecord { only_leg: Some(1), ..sample_trade() };
+        let (exit, pnl) = start_exit(&one_sided, bids(Some(52), None), &fees, &rules).unwrap();
+        assert_eq!((exit.reason, exit.sold, pnl), ("take-profit", [false, true], Decimal::new(7, 2)));
+    }
+
+    #[test]
+    fn an_exit_retries_its_sells_every_few_seconds() {
+        let rules = ExitRulesConfig {
+            take_profit_per_pair: 0.05,
+            stop_loss_per_pair: 0.0,
+        };
+        let bids = [Some(Decimal::new(50, 2)), Some(Decimal::new(48, 2))];
+        let (mut exit, _) = start_exit(&sample_trade(), bids, &LegFees::default(), &rules).unwrap();
+        let retry_ms = EXIT_RETRY_SECS as i64 * 1000;
+        assert!(exit.attempt(NOW_MS));
+        assert!(!exit.attempt(NOW_MS + retry_ms - 1));
+        assert!(exit.attempt(NOW_MS + retry_ms));
+        assert_eq!(exit.last_attempt_ms, Some(NOW_MS + retry_ms));
+    }
+}
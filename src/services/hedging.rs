@@ -0,0 +1,294 @@
+//! Delta hedge of a round's open arbs on a perp venue: in the last minutes of the long period, hold a perp
+//! position offsetting the net spot delta of the legs, and close it when the round ends.
+//!
+//! The round only ever trades its own hedge: a perp position already open when it starts is read once as a
+//! baseline and left in place, and the hedge is re-read from the venue (minus that baseline) before each move,
+//! so an order that failed after all is still accounted for.
+
+use crate::adapters::hedge::{BinanceFuturesHedger, Hedger};
+use crate::config::{HedgeConfig, HedgeSymbol};
+use crate::domain::pricing::up_delta;
+use crate::models::{Outcome, TradeRecord};
+use log::{error, info, warn};
+use rust_decimal::prelude::ToPrimitive;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+/// Attempts to close the round's hedge before it is left to the operator.
+const FLATTEN_ATTEMPTS: u32 = 5;
+/// Wait before the first retry of a failed close; doubled after each further miss.
+const FLATTEN_RETRY: Duration = Duration::from_secs(2);
+
+/// The configured venue; None when hedging is off, simulating, or missing credentials.
+pub fn hedger_from_config(config: &HedgeConfig, simulation: bool) -> Option<Arc<dyn Hedger>> {
+    if !config.enabled || simulation {
+        return None;
+    }
+    let (Some(key), Some(secret)) = (config.api_key.clone(), config.api_secret.clone()) else {
+        warn!("Hedge: enabled without api_key/api_secret; not hedging");
+        return None;
+    };
+    Some(Arc::new(BinanceFuturesHedger::new(key, secret, config.testnet)))
+}
+
+pub struct DeltaHedge<'a> {
+    hedger: Arc<dyn Hedger>,
+    config: &'a HedgeConfig,
+    perp: &'a HedgeSymbol,
+    /// Perp position this round has put on (base units).
+    position: f64,
+    /// The venue's position before this round first hedged; None until read.
+    baseline: Option<f64>,
+    last_rebalance: Option<std::time::Instant>,
+    retry_delay: Duration,
+}
+
+impl<'a> DeltaHedge<'a> {
+    /// None when hedging is off or `symbol` has no perp configured.
+    pub fn new(hedger: Option<Arc<dyn Hedger>>, config: &'a HedgeConfig, symbol: &str) -> Option<Self> {
+        let perp = config.symbols.get(&symbol.to_lowercase())?;
+        Some(Self {
+            hedger: hedger?,
+            config,
+            perp,
+            position: 0.0,
+            baseline: None,
+            last_rebalance: None,
+            retry_delay: FLATTEN_RETRY,
+        })
+    }
+
+    /// Move the perp position towards offsetting the net delta of `trades` at `spot`. Throttled to
+    /// `rebalance_secs`; skipped while the open size is below `min_shares`.
+    pub async fn rebalance(
+        &mut self,
+        trades: &[TradeRecord],
+        spot: f64,
+        strikes: (f64, f64),
+        secs_to_expiry: (f64, f64),
+        vol_per_sqrt_sec: f64,
+    ) {
+        if self
+            .last_rebalance
+            .is_some_and(|t| t.elapsed().as_secs() < self.config.rebalance_secs)
+        {
+            return;
+        }
+        self.last_rebalance = Some(std::time::Instant::now());
+        // Without knowing what the venue holds, a move could close or double a position that isn't ours.
+        if !self.sync_position().await {
+            return;
+        }
+
+        let size = |t: &TradeRecord| t.size.to_f64().unwrap_or(0.0);
+        let open_size: f64 = trades.iter().map(size).sum();
+        let target = if open_size < self.config.min_shares {
+            0.0
+        } else {
+            let delta_15 = up_delta(spot, strikes.0, secs_to_expiry.0, vol_per_sqrt_sec);
+            let delta_5 = up_delta(spot, strikes.1, secs_to_expiry.1, vol_per_sqrt_sec);
+            let sign = |outcome: &str| if Outcome::Up.is(outcome) { 1.0 } else { -1.0 };
+            let net_delta: f64 = trades
+                .iter()
+                .map(|t| {
+                    let leg = |n: u8, outcome: &str, delta: f64| {
+                        if t.holds_leg(n) { sign(outcome) * delta } else { 0.0 }
+                    };
+                    let delta_2 = if t.leg2_long() { delta_15 } else { delta_5 };
+                    size(t) * (leg(1, &t.leg1_outcome, delta_15) + leg(2, &t.leg2_outcome, delta_2))
+                })
+                .sum();
+            -net_delta * self.perp.hedge_ratio
+        };
+        self.move_to(round_to_step(target, self.perp.quantity_step)).await;
+    }
+
+    /// Close what this round put on, leaving any position held before it. A failed close is retried with
+    /// backoff, re-reading the venue's position first, up to `FLATTEN_ATTEMPTS` times.
+    pub async fn flatten(&mut self) {
+        // Never hedged: there is nothing of ours to close.
+        if self.baseline.is_none() {
+            return;
+        }
+        let mut delay = self.retry_delay;
+        for attempt in 1..=FLATTEN_ATTEMPTS {
+            if self.sync_position().await && self.move_to(0.0).await {
+                return;
+            }
+            if attempt < FLATTEN_ATTEMPTS {
+                warn!(
+                    "Hedge: closing {} on {} failed (attempt {}/{}); retrying in {:?}",
+                    self.perp.perp_symbol,
+                    self.hedger.name(),
+                    attempt,
+                    FLATTEN_ATTEMPTS,
+                    delay
+                );
+                sleep(delay).await;
+                delay *= 2;
+            }
+        }
+        error!(
+            "Hedge: {} of {} this round put on is still open on {} after {} attempts; close it by hand",
+            self.position,
+            self.perp.perp_symbol,
+            self.hedger.name(),
+            FLATTEN_ATTEMPTS
+        );
+    }
+
+    /// Read the venue's position: the first read is the baseline, later ones set what this round holds on top
+    /// of it. False when the venue can't say.
+    async fn sync_position(&mut self) -> bool {
+        let held = match self.hedger.position(&self.perp.perp_symbol).await {
+            Ok(held) => held,
+            Err(e) => {
+                warn!("Hedge: {} position unavailable: {}", self.hedger.name(), e);
+                return false;
+            }
+        };
+        match self.baseline {
+            Some(baseline) => self.position = held - baseline,
+            None => {
+                if held != 0.0 {
+                    info!(
+                        "Hedge: {} already holds {} {}; hedging on top of it and leaving it open",
+                        self.hedger.name(),
+                        held,
+                        self.perp.perp_symbol
+                    );
+                }
+                self.baseline = Some(held);
+            }
+        }
+        true
+    }
+
+    /// Trade towards `target`; true once the position is there.
+    async fn move_to(&mut self, target: f64) -> bool {
+        let qty = round_to_step(target - self.position, self.perp.quantity_step);
+        if qty == 0.0 {
+            return true;
+        }
+        match self.hedger.trade(&self.perp.perp_symbol, qty).await {
+            Ok(()) => {
+                self.position += qty;
+                info!(
+                    "Hedge: {} {} {} on {} (position {})",
+                    if qty > 0.0 { "bought" } else { "sold" },
+                    qty.abs(),
+                    self.perp.perp_symbol,
+                    self.hedger.name(),
+                    self.position
+                );
+                true
+            }
+            Err(e) => {
+                warn!("Hedge: {} {} order failed: {}", self.hedger.name(), self.perp.perp_symbol, e);
+                false
+            }
+        }
+    }
+}
+
+/// `qty` rounded to the nearest multiple of `step`; unchanged when `step` is 0.
+fn round_to_step(qty: f64, step: f64) -> f64 {
+    if step <= 0.0 {
+        return qty;
+    }
+    (qty / step).round() * step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::pnl::sample_trade;
+    use anyhow::Result;
+    use futures_util::future::BoxFuture;
+    use rust_decimal::Decimal;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    /// A perp venue holding one position; trades fail while `failures` lasts.
+    #[derive(Default)]
+    struct MockHedger {
+        position: Mutex<f64>,
+        failures: Mutex<usize>,
+    }
+
+    impl Hedger for MockHedger {
+        fn name(&self) -> &str {
+            "mock"
+        }
+
+        fn position<'a>(&'a self, _perp_symbol: &'a str) -> BoxFuture<'a, Result<f64>> {
+            let position = *self.position.lock().unwrap();
+            Box::pin(async move { Ok(position) })
+        }
+
+        fn trade<'a>(&'a self, _perp_symbol: &'a str, qty: f64) -> BoxFuture<'a, Result<()>> {
+            let mut failures = self.failures.lock().unwrap();
+            let result = if *failures > 0 {
+                *failures -= 1;
+                Err(anyhow::anyhow!("mock order rejected"))
+            } else {
+                *self.position.lock().unwrap() += qty;
+                Ok(())
+            };
+            Box::pin(async move { result })
+        }
+    }
+
+    fn config() -> HedgeConfig {
+        let perp = HedgeSymbol {
+            perp_symbol: "BTCUSDT".to_string(),
+            quantity_step: 0.001,
+            hedge_ratio: 1.0,
+        };
+        HedgeConfig {
+            enabled: true,
+            min_shares: 0.0,
+            symbols: BTreeMap::from([("btc".to_string(), perp)]),
+            ..HedgeConfig::default()
+        }
+    }
+
+    /// 1000 shares of the long Up leg alone, 10 minutes out: about 1.6 BTC of delta.
+    async fn hedge_up_leg(hedge: &mut DeltaHedge<'_>) {
+        let trade = TradeRecord {
+            size: Decimal::from(1000),
+            only_leg: Some(1),
+            ..sample_trade()
+        };
+        hedge.rebalance(&[trade], 100_000.0, (100_000.0, 100_000.0), (600.0, 600.0), 0.0001).await;
+    }
+
+    #[tokio::test]
+    async fn a_position_held_before_the_round_is_left_open() {
+        let hedger = Arc::new(MockHedger::default());
+        *hedger.position.lock().unwrap() = 2.0;
+        let config = config();
+        let mut hedge = DeltaHedge::new(Some(hedger.clone()), &config, "btc").unwrap();
+        hedge_up_leg(&mut hedge).await;
+        let hedged = *hedger.position.lock().unwrap();
+        assert!(hedged < 1.0, "the hedge shorts about 1.6 on top of the 2 held: {}", hedged);
+
+        hedge.flatten().await;
+        assert!((*hedger.position.lock().unwrap() - 2.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn a_failed_flatten_is_retried() {
+        let hedger = Arc::new(MockHedger::default());
+        let config = config();
+        let mut hedge = DeltaHedge::new(Some(hedger.clone()), &config, "btc").unwrap();
+        hedge.retry_delay = Duration::from_millis(1);
+        hedge_up_leg(&mut hedge).await;
+        assert!(*hedger.position.lock().unwrap() < 0.0);
+
+        *hedger.failures.lock().unwrap() = 2;
+        hedge.flatten().await;
+        assert!(hedger.position.lock().unwrap().abs() < 1e-9);
+        assert_eq!(*hedger.failures.lock().unwrap(), 0);
+    }
+}
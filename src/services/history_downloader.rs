@@ -0,0 +1,107 @@
+//! `download-history` subcommand: the CLOB price history of past up/down markets, bucketed into OHLC candles
+//! and written to a CSV, one row per (token, candle), as a dataset for backtests and threshold tuning.
+//!
+//! Every period of each symbol and period length that closed within the lookback is fetched; markets the Gamma
+//! API doesn't list (skipped slots, symbols without that period) are skipped.
+
+use crate::adapters::polymarket::PolymarketApi;
+use crate::domain::candles::{candles, Candle};
+use crate::domain::window::period_start_et_unix_at;
+use crate::utils::slug_builder::build_updown_slug;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const CSV_HEADER: &str =
+    "symbol,period_mins,period_start,slug,condition_id,outcome,token_id,candle_start,open,high,low,close,points";
+
+/// Download `candle_mins` candles of every `symbols` × `period_mins` market that closed in the last `hours`
+/// to `output` (overwritten). Returns the number of candles written.
+pub async fn download_history(
+    api: &PolymarketApi,
+    symbols: &[String],
+    period_mins: &[i64],
+    hours: i64,
+    candle_mins: u32,
+    output: &Path,
+) -> Result<usize> {
+    let file = File::create(output).context(format!("Failed to create {}", output.display()))?;
+    let mut out = BufWriter::new(file);
+    writeln!(out, "{}", CSV_HEADER)?;
+    let now = Utc::now().timestamp();
+    let candle_secs = candle_mins.max(1) as i64 * 60;
+    let mut written = 0usize;
+    for symbol in symbols {
+        for &minutes in period_mins {
+            let len = minutes * 60;
+            let mut start = period_start_et_unix_at(now - hours * 3600, minutes);
+            let (mut markets, first_row) = (0usize, written);
+            while start + len <= now {
+                let slug = build_updown_slug(symbol, minutes, start);
+                match market_candles(api, &slug, (start, start + len), candle_secs).await {
+                    Ok(Some((condition_id, rows))) => {
+                        markets += 1;
+                        for (outcome, token_id, candle) in rows {
+                            writeln!(
+                                out,
+                                "{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                                symbol.to_lowercase(),
+                                minutes,
+                                start,
+                                slug,
+                                condition_id,
+                                outcome,
+                                token_id,
+                                candle.start,
+                                candle.open,
+                                candle.high,
+                                candle.low,
+                                candle.close,
+                                candle.points
+                            )?;
+                            written += 1;
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("History {}: {}", slug, e),
+                }
+                start += len;
+            }
+            info!(
+                "History {} {}m: {} market(s), {} candle(s)",
+                symbol.to_uppercase(),
+                minutes,
+                markets,
+                written - first_row
+            );
+        }
+    }
+    out.flush()?;
+    Ok(written)
+}
+
+/// Condition id and (outcome, token id, candle) of every token of the market `slug`; None when Gamma doesn't
+/// list it.
+async fn market_candles(
+    api: &PolymarketApi,
+    slug: &str,
+    range: (i64, i64),
+    candle_secs: i64,
+) -> Result<Option<(String, Vec<(String, String, Candle)>)>> {
+    let Ok(market) = api.get_market_by_slug(slug).await else {
+        return Ok(None);
+    };
+    let details = api.get_market(&market.condition_id).await?;
+    let mut rows = Vec::new();
+    for token in details.tokens {
+        // Minute points, whatever the candle length.
+        let points = api.get_price_history(&token.token_id, 1, range).await?;
+        for candle in candles(&points, candle_secs) {
+            rows.push((token.outcome.clone(), token.token_id.clone(), candle));
+        }
+    }
+    Ok(Some((market.condition_id, rows)))
+}
@@ -0,0 +1,331 @@
+//! Token inventory built from fills, sells, and redemptions, optionally verified against on-chain
+//! ERC-1155 balances, so SELL-side and hedging logic know what the wallet actually holds.
+
+use crate::adapters::polymarket::PolymarketClient;
+use crate::models::{Fill, Side, TradeRecord};
+use crate::utils::json_store::{load_or_default, persist};
+use anyhow::Result;
+use chrono::Utc;
+use log::{info, warn};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+const BALANCE_TOLERANCE: f64 = 1e-6;
+/// How far back the activity feed is read at startup when no cursor was saved.
+const ACTIVITY_LOOKBACK_SECS: u64 = 3600;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Position {
+    pub condition_id: String,
+    pub outcome: String,
+    /// Shares held.
+    pub size: f64,
+    /// USDC paid for the shares still held (average cost basis).
+    pub cost: f64,
+}
+
+#[derive(Default)]
+pub struct Inventory {
+    positions: RwLock<HashMap<String, Position>>,
+    /// Fills already applied, so repeated syncs over the same markets don't double count.
+    seen_fills: RwLock<HashSet<String>>,
+}
+
+/// A fill's identity across /trades and the activity feed: its settlement transaction plus what it traded, since
+/// one transaction can settle several fills. Without a hash the timestamp stands in for it.
+fn fill_key(fill: &Fill) -> String {
+    let tx = match &fill.transaction_hash {
+        Some(hash) => hash.to_ascii_lowercase(),
+        None => fill.timestamp.to_string(),
+    };
+    format!(
+        "{}|{}|{}|{}|{}",
+        tx,
+        fill.token_id.as_deref().unwrap_or(""),
+        fill.side,
+        fill.size,
+        fill.price
+    )
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn record_buy(&self, token_id: &str, condition_id: &str, outcome: &str, size: f64, price: f64) {
+        let mut positions = self.positions.write().await;
+        let pos = positions.entry(token_id.to_string()).or_default();
+        if pos.condition_id.is_empty() {
+            pos.condition_id = condition_id.to_string();
+        }
+        if pos.outcome.is_empty() {
+            pos.outcome = outcome.to_string();
+        }
+        pos.size += size;
+        pos.cost += size * price;
+    }
+
+    pub async fn record_sell(&self, token_id: &str, size: f64) {
+        let mut positions = self.positions.write().await;
+        if let Some(pos) = positions.get_mut(token_id) {
+            let sold = size.min(pos.size);
+            if pos.size > 0.0 {
+                pos.cost -= pos.cost * sold / pos.size;
+            }
+            pos.size -= sold;
+            if pos.size <= BALANCE_TOLERANCE {
+                positions.remove(token_id);
+            }
+        }
+    }
+
+    /// Shares held of a condition's outcome (case-insensitive), if the position is tracked.
+    pub async fn outcome_size(&self, condition_id: &str, outcome: &str) -> Option<f64> {
+        self.positions
+            .read()
+            .await
+            .values()
+            .find(|p| {
+                p.condition_id.eq_ignore_ascii_case(condition_id)
+                    && p.outcome.eq_ignore_ascii_case(outcome)
+            })
+            .map(|p| p.size)
+    }
+
+    /// Redemption burns the condition's outcome tokens, as does the settlement of a simulated period.
+    pub async fn record_redeemed(&self, condition_id: &str) {
+        self.positions
+            .write()
+            .await
+            .retain(|_, p| !p.condition_id.eq_ignore_ascii_case(condition_id));
+    }
+
+    /// Simulated trades fill in full at the decision prices.
+    pub async fn record_trades(&self, trades: &[TradeRecord]) {
+        let f64_of = |d: Decimal| d.to_f64().unwrap_or(0.0);
+        for t in trades {
+            if t.holds_leg(1) {
+                self.record_buy(&t.leg1_token, &t.leg1_cid, &t.leg1_outcome, f64_of(t.size), f64_of(t.leg1_price))
+                    .await;
+            }
+            if t.holds_leg(2) {
+                self.record_buy(&t.leg2_token, &t.leg2_cid, &t.leg2_outcome, f64_of(t.size), f64_of(t.leg2_price))
+                    .await;
+            }
+        }
+    }
+
+    /// Apply fills not seen before; returns how many were new.
+    pub async fn apply_fills(&self, fills: &[Fill], outcomes: &HashMap<String, String>) -> usize {
+        let mut applied = 0;
+        for fill in fills {
+            let Some(token) = fill.token_id.as_deref() else {
+                continue;
+            };
+            if !self.seen_fills.write().await.insert(fill_key(fill)) {
+                continue;
+            }
+            if fill.side == Side::Buy {
+                let outcome = outcomes
+                    .get(token)
+                    .map(String::as_str)
+                    .or(fill.outcome.as_deref())
+                    .unwrap_or("");
+                let cid = fill.condition_id.as_deref().unwrap_or("");
+                self.record_buy(token, cid, outcome, fill.size, fill.price).await;
+            } else {
+                self.record_sell(token, fill.size).await;
+            }
+            applied += 1;
+        }
+        applied
+    }
+
+    /// Pull the wallet's fills for the markets of `trades` from the data API and apply new ones.
+    pub async fn sync_fills(&self, api: &dyn PolymarketClient, trades: &[TradeRecord]) -> Result<usize> {
+        let Some(wallet) = api.wallet_address() else {
+            return Ok(0);
+        };
+        let mut cids: Vec<&str> = trades
+            .iter()
+            .flat_map(|t| [t.cid_15.as_str(), t.cid_5.as_str()])
+            .collect();
+        cids.sort_unstable();
+        cids.dedup();
+        if cids.is_empty() {
+            return Ok(0);
+        }
+        let outcomes: HashMap<String, String> = trades
+            .iter()
+            .flat_map(|t| {
+                [
+                    (t.leg1_token.clone(), t.leg1_outcome.clone()),
+                    (t.leg2_token.clone(), t.leg2_outcome.clone()),
+                ]
+            })
+            .collect();
+        let fills = api.get_fills(&wallet, &cids).await?;
+        Ok(self.apply_fills(&fills, &outcomes).await)
+    }
+
+    /// Poll the data API activity feed every `interval_secs` and apply trades not seen yet. This catches
+    /// fills as they happen, ahead of the per-period `sync_fills`; both share the same dedup keys, so a
+    /// fill is only counted once whichever source reports it first. The latest fill timestamp is saved to
+    /// `cursor_path`, and polling resumes from it after a restart.
+    pub async fn poll_activity(&self, api: &dyn PolymarketClient, interval_secs: u64, cursor_path: Option<&Path>) {
+        let Some(wallet) = api.wallet_address() else {
+            return;
+        };
+        let mut cursor = activity_start(cursor_path, Utc::now().timestamp().max(0) as u64);
+        loop {
+            match api.get_activity_trades(&wallet, cursor).await {
+                Ok(fills) => {
+                    let applied = self.apply_fills(&fills, &HashMap::new()).await;
+                    if applied > 0 {
+                        info!("Inventory: activity feed reported {} new fill(s)", applied);
+                    }
+                    // Re-read the last second: more trades may land with the same timestamp.
+                    if let Some(latest) = fills.iter().map(|f| f.timestamp).max().filter(|t| *t > cursor) {
+                        cursor = latest;
+                        persist(cursor_path, &cursor, "activity cursor");
+                    }
+                }
+                Err(e) => warn!("Activity feed poll failed: {}", e),
+            }
+            sleep(Duration::from_secs(interval_secs)).await;
+        }
+    }
+
+    /// Compare tracked sizes with on-chain CTF balances and adopt the on-chain value on mismatch.
+    /// Returns (token_id, tracked, on-chain) for every corrected token.
+    pub async fn verify_onchain(&self, api: &dyn PolymarketClient) -> Result<Vec<(String, f64, f64)>> {
+        let Some(wallet) = api.wallet_address() else {
+            return Ok(Vec::new());
+        };
+        let tokens: Vec<(String, f64)> = self
+            .positions
+            .read()
+            .await
+            .iter()
+            .map(|(t, p)| (t.clone(), p.size))
+            .collect();
+        let mut corrected = Vec::new();
+        for (token, tracked) in tokens {
+            let onchain = api.get_ctf_balance(&wallet, &token).await?;
+            if (onchain - tracked).abs() > BALANCE_TOLERANCE {
+                warn!(
+                    "Inventory: token {} tracked {:.4} but on-chain {:.4}; using on-chain balance",
+                    &token[..token.len().min(16)],
+                    tracked,
+                    onchain
+                );
+                let mut positions = self.positions.write().await;
+                if let Some(pos) = positions.get_mut(&token) {
+                    if pos.size > 0.0 {
+                        pos.cost *= onchain / pos.size;
+                    }
+                    pos.size = onchain;
+                }
+                positions.retain(|_, p| p.size > BALANCE_TOLERANCE);
+                corrected.push((token, tracked, onchain));
+            }
+        }
+        Ok(corrected)
+    }
+
+    pub async fn snapshot(&self) -> HashMap<String, Position> {
+        self.positions.read().await.clone()
+    }
+
+    pub async fn log_summary(&self) {
+        let positions = self.snapshot().await;
+        let cost: f64 = positions.values().map(|p| p.cost).sum();
+        info!(
+            "Inventory: {} token position(s), cost basis {:.2} USDC",
+            positions.len(),
+            cost
+        );
+    }
+}
+
+/// Where the activity feed is read from: the saved cursor, or `ACTIVITY_LOOKBACK_SECS` before `now`.
+fn activity_start(cursor_path: Option<&Path>, now: u64) -> u64 {
+    match load_or_default::<u64>(cursor_path, "activity cursor") {
+        0 => now.saturating_sub(ACTIVITY_LOOKBACK_SECS),
+        saved => saved,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::pnl::sample_trade;
+
+    fn fill(tx: Option<&str>, side: Side, size: f64, timestamp: u64) -> Fill {
+        Fill {
+            token_id: Some("up".to_string()),
+            side,
+            size,
+            price: 0.4,
+            timestamp,
+            condition_id: Some("0xcid".to_string()),
+            outcome: Some("Up".to_string()),
+            transaction_hash: tx.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn applies_buys_and_sells_at_average_cost() {
+        let inventory = Inventory::new();
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let fills = [fill(Some("0x1"), Side::Buy, 10.0, 100), fill(Some("0x2"), Side::Sell, 4.0, 200)];
+        assert_eq!(rt.block_on(inventory.apply_fills(&fills, &HashMap::new())), 2);
+        let held = &rt.block_on(inventory.snapshot())["up"];
+        assert_eq!((held.outcome.as_str(), held.size), ("Up", 6.0));
+        assert!((held.cost - 2.4).abs() < 1e-9);
+        assert_eq!(rt.block_on(inventory.outcome_size("0xCID", "up")), Some(6.0));
+    }
+
+    #[test]
+    fn dedups_by_transaction_but_keeps_lookalike_fills() {
+        let inventory = Inventory::new();
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        // The same fill from /trades and from the activity feed, which stamps it a second later.
+        let trades = [fill(Some("0xABC"), Side::Buy, 5.0, 100)];
+        let activity = [fill(Some("0xabc"), Side::Buy, 5.0, 101)];
+        assert_eq!(rt.block_on(inventory.apply_fills(&trades, &HashMap::new())), 1);
+        assert_eq!(rt.block_on(inventory.apply_fills(&activity, &HashMap::new())), 0);
+        // Two identical fills in the same second, settled in different transactions.
+        let twins = [fill(Some("0xdef"), Side::Buy, 5.0, 100), fill(Some("0x123"), Side::Buy, 5.0, 100)];
+        assert_eq!(rt.block_on(inventory.apply_fills(&twins, &HashMap::new())), 2);
+        assert_eq!(rt.block_on(inventory.outcome_size("0xcid", "Up")), Some(15.0));
+    }
+
+    #[test]
+    fn activity_resumes_from_the_saved_cursor() {
+        let path = std::env::temp_dir().join(format!("activity-cursor-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(activity_start(Some(&path), 10_000), 10_000 - ACTIVITY_LOOKBACK_SECS);
+        persist(Some(&path), &1_234u64, "activity cursor");
+        assert_eq!(activity_start(Some(&path), 10_000), 1_234);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn settled_simulated_trades_leave_the_inventory() {
+        let inventory = Inventory::new();
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let trade = sample_trade();
+        rt.block_on(inventory.record_trades(std::slice::from_ref(&trade)));
+        assert_eq!(rt.block_on(inventory.snapshot()).len(), 2);
+        rt.block_on(inventory.record_redeemed(&trade.cid_15));
+        rt.block_on(inventory.record_redeemed(&trade.cid_5));
+        assert!(rt.block_on(inventory.snapshot()).is_empty());
+    }
+}
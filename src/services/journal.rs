@@ -0,0 +1,290 @@
+//! Append-only JSONL journal of trade lifecycle events (decision, orders, fills, resolution, redemption).
+//!
+//! `record` never blocks: entries go through a bounded channel to a writer thread that appends them in
+//! batches. If the disk can't keep up and the channel fills, the journal drops to sampling one entry in
+//! `DEGRADED_SAMPLE_EVERY` (with an error log) until the writer catches up, so trading is never stalled.
+//! `close` flushes what is queued and joins the writer, for a clean shutdown.
+
+use crate::events::{ArbEvent, EventBus};
+use crate::models::{JournalEntry, JournalEvent};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{error, info, warn};
+use std::collections::HashSet;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+const CHANNEL_CAPACITY: usize = 4096;
+const MAX_BATCH: usize = 256;
+/// While degraded, keep one entry in this many.
+const DEGRADED_SAMPLE_EVERY: u64 = 10;
+/// Leave degraded mode once the queue is back under this depth.
+const RECOVERED_QUEUE_DEPTH: usize = CHANNEL_CAPACITY / 4;
+const METRICS_LOG_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Backpressure counters shared between `record` and the writer thread.
+#[derive(Debug, Default)]
+struct JournalStats {
+    written: AtomicU64,
+    dropped: AtomicU64,
+    batches: AtomicU64,
+    queued: AtomicUsize,
+    max_queue_depth: AtomicUsize,
+    degraded: AtomicBool,
+}
+
+pub struct Journal {
+    /// Taken by `close`; None when journaling is disabled or closed.
+    tx: Mutex<Option<mpsc::Sender<JournalEntry>>>,
+    writer: Mutex<Option<std::thread::JoinHandle<()>>>,
+    stats: Arc<JournalStats>,
+    seq: AtomicU64,
+    events: Option<EventBus>,
+    path: Option<PathBuf>,
+    /// Order ids acked since start, so they're known before the writer has appended them.
+    placed: Mutex<HashSet<String>>,
+}
+
+impl Journal {
+    /// Journal appending to `path`; an empty path disables journaling.
+    pub fn new(path: &str) -> Self {
+        let path = path.trim();
+        let path = (!path.is_empty()).then(|| PathBuf::from(path));
+        let stats = Arc::new(JournalStats::default());
+        let (tx, writer) = path
+            .clone()
+            .map(|path| {
+                let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+                let writer_stats = Arc::clone(&stats);
+                let writer = std::thread::Builder::new()
+                    .name("journal-writer".to_string())
+                    .spawn(move || run_writer(path, rx, writer_stats))
+                    .expect("Failed to spawn journal writer thread");
+                (tx, writer)
+            })
+            .unzip();
+        Self {
+            tx: Mutex::new(tx),
+            writer: Mutex::new(writer),
+            stats,
+            seq: AtomicU64::new(0),
+            events: None,
+            path,
+            placed: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Also publish every recorded entry to `events`, even with journaling disabled.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn events(&self) -> Option<&EventBus> {
+        self.events.as_ref()
+    }
+
+    /// Ids of every order this bot got acked, from this run and from the journal file of earlier runs.
+    pub fn placed_order_ids(&self) -> HashSet<String> {
+        let mut ids = self.placed.lock().unwrap().clone();
+        if let Some(path) = self.path.as_deref().filter(|p| p.exists()) {
+            match read_placed_order_ids(path) {
+                Ok(journaled) => ids.extend(journaled),
+                Err(e) => warn!("Could not read placed orders from {}: {}", path.display(), e),
+            }
+        }
+        ids
+    }
+
+    pub fn record(&self, trade_id: Option<&str>, event: JournalEvent) {
+        if let JournalEvent::OrderAcked { order_id: Some(id), .. } = &event {
+            self.placed.lock().unwrap().insert(id.clone());
+        }
+        let tx = self.tx.lock().unwrap_or_else(|e| e.into_inner());
+        if tx.is_none() && self.events.is_none() {
+            return;
+        }
+        let entry = JournalEntry {
+            ts_ms: Utc::now().timestamp_millis(),
+            trade_id: trade_id.map(str::to_string),
+            event,
+        };
+        if let Some(events) = &self.events {
+            events.publish(ArbEvent::Trade(entry.clone()));
+        }
+        let Some(tx) = tx.as_ref() else {
+            return;
+        };
+        let stats = &self.stats;
+        if stats.degraded.load(Ordering::Relaxed)
+            && !self
+                .seq
+                .fetch_add(1, Ordering::Relaxed)
+                .is_multiple_of(DEGRADED_SAMPLE_EVERY)
+        {
+            stats.dropped.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        // Count before sending so the writer never decrements an entry that isn't counted yet.
+        let depth = stats.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        match tx.try_send(entry) {
+            Ok(()) => {
+                stats.max_queue_depth.fetch_max(depth, Ordering::Relaxed);
+            }
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                stats.queued.fetch_sub(1, Ordering::Relaxed);
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+                if !stats.degraded.swap(true, Ordering::Relaxed) {
+                    error!(
+                        "Journal writer is falling behind (queue of {} full); sampling 1 in {} entries until it catches up",
+                        CHANNEL_CAPACITY, DEGRADED_SAMPLE_EVERY
+                    );
+                }
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                stats.queued.fetch_sub(1, Ordering::Relaxed);
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Journal {
+    /// Stop journaling to the file: the entries already queued are appended and the writer thread is joined.
+    /// Later entries are still published to the event bus. Blocks until the writer is done.
+    pub fn close(&self) {
+        drop(self.tx.lock().unwrap_or_else(|e| e.into_inner()).take());
+        let writer = self.writer.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(writer) = writer {
+            if writer.join().is_err() {
+                error!("Journal writer thread panicked");
+            }
+            log_metrics(&self.stats);
+        }
+    }
+}
+
+/// Writer thread: block for one entry, drain up to `MAX_BATCH` more, append them with a single open/flush.
+fn run_writer(path: PathBuf, mut rx: mpsc::Receiver<JournalEntry>, stats: Arc<JournalStats>) {
+    let mut last_metrics = Instant::now();
+    while let Some(first) = rx.blocking_recv() {
+        let mut batch = vec![first];
+        while batch.len() < MAX_BATCH {
+            match rx.try_recv() {
+                Ok(entry) => batch.push(entry),
+                Err(_) => break,
+            }
+        }
+        let n = batch.len();
+        match append_batch(&path, &batch) {
+            Ok(()) => {
+                stats.written.fetch_add(n as u64, Ordering::Relaxed);
+            }
+            Err(e) => {
+                stats.dropped.fetch_add(n as u64, Ordering::Relaxed);
+                warn!("Journal write to {} failed: {}", path.display(), e);
+            }
+        }
+        stats.batches.fetch_add(1, Ordering::Relaxed);
+        let depth = stats.queued.fetch_sub(n, Ordering::Relaxed).saturating_sub(n);
+
+        if depth < RECOVERED_QUEUE_DEPTH && stats.degraded.swap(false, Ordering::Relaxed) {
+            info!(
+                "Journal writer caught up; full journaling resumed ({} entries dropped so far)",
+                stats.dropped.load(Ordering::Relaxed)
+            );
+        }
+        if last_metrics.elapsed() >= METRICS_LOG_INTERVAL {
+            last_metrics = Instant::now();
+            log_metrics(&stats);
+        }
+    }
+}
+
+fn log_metrics(stats: &JournalStats) {
+    let dropped = stats.dropped.load(Ordering::Relaxed);
+    let msg = format!(
+        "Journal: {} written in {} batches, {} dropped, max queue depth {}/{}",
+        stats.written.load(Ordering::Relaxed),
+        stats.batches.load(Ordering::Relaxed),
+        dropped,
+        stats.max_queue_depth.load(Ordering::Relaxed),
+        CHANNEL_CAPACITY
+    );
+    if dropped > 0 {
+        warn!("{}", msg);
+    } else {
+        info!("{}", msg);
+    }
+}
+
+fn append_batch(path: &Path, batch: &[JournalEntry]) -> Result<()> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context("open journal")?;
+    let mut out = BufWriter::new(file);
+    for entry in batch {
+        serde_json::to_writer(&mut out, entry)?;
+        out.write_all(b"\n").context("append journal line")?;
+    }
+    out.flush().context("flush journal")?;
+    Ok(())
+}
+
+/// Read all journal entries, skipping lines that fail to parse.
+pub fn read_journal(path: &Path) -> Result<Vec<JournalEntry>> {
+    let file = std::fs::File::open(path)
+        .context(format!("Failed to open journal {}", path.display()))?;
+    let mut entries = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<JournalEntry>(&line) {
+            Ok(e) => entries.push(e),
+            Err(e) => warn!("Skipping journal line {}: {}", i + 1, e),
+        }
+    }
+    Ok(entries)
+}
+
+/// Order ids the journal at `path` saw acked.
+pub fn read_placed_order_ids(path: &Path) -> Result<HashSet<String>> {
+    Ok(read_journal(path)?
+        .into_iter()
+        .filter_map(|e| match e.event {
+            JournalEvent::OrderAcked { order_id, .. } => order_id,
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close_flushes_every_queued_entry() {
+        let path = std::env::temp_dir().join(format!("journal-close-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let journal = Journal::new(&path.display().to_string());
+        for i in 0..500 {
+            journal.record(Some(&format!("btc-1-{}", i)), JournalEvent::Aborted { reason: "test".to_string() });
+        }
+        journal.close();
+        assert_eq!(read_journal(&path).unwrap().len(), 500);
+        // Closed: nothing more reaches the file.
+        journal.record(Some("btc-1-500"), JournalEvent::Aborted { reason: "test".to_string() });
+        journal.close();
+        assert_eq!(read_journal(&path).unwrap().len(), 500);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,440 @@
+//! Maker-mode quoting for one overlap round: rests a bid on both legs a few ticks below the ask while the
+//! sum is near the threshold, requotes when the wanted prices change, and reports the arb once both legs
+//! have filled. When only one leg fills, the rest of the other is lifted at the ask if the pair still beats the
+//! threshold. Quotes are only cancelled after reading their fills, and whatever filled is handed back as a
+//! `MakerFill`, so partial and one-sided fills become trades too.
+
+use crate::adapters::venue::PredictionMarketVenue;
+use crate::domain::arbitrage::ArbSelection;
+use crate::domain::fees::{effective_price, fee_per_share, LegFees};
+use crate::models::{JournalEvent, OrderRequest, OrderType, Side, TradeRecord};
+use crate::services::execution_service::place_leg;
+use crate::services::journal::Journal;
+use log::{info, warn};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+struct RestingLeg {
+    token: String,
+    price: Decimal,
+    order_id: Option<String>,
+    /// Shares matched so far, quote and completion order together.
+    filled: Decimal,
+}
+
+struct LiveQuotes<'a> {
+    selection: ArbSelection<'a>,
+    trade_id: String,
+    /// Shares quoted on each leg.
+    shares: Decimal,
+    legs: [RestingLeg; 2],
+}
+
+/// What a maker arb ended up holding: `filled` shares of each leg, at the prices (and fees) in `selection`.
+pub struct MakerFill<'a> {
+    pub trade_id: String,
+    pub selection: ArbSelection<'a>,
+    pub filled: [Decimal; 2],
+}
+
+impl MakerFill<'_> {
+    /// The fills as trades shaped like `pair` (a trade record of this arb), split by `TradeRecord::matched`. Each
+    /// leg's fill is journaled.
+    pub fn trades(&self, pair: &TradeRecord, journal: &Journal) -> Vec<TradeRecord> {
+        let prices = [self.selection.leg1_price, self.selection.leg2_price];
+        for (i, size) in self.filled.iter().enumerate().filter(|(_, size)| !size.is_zero()) {
+            journal.record(
+                Some(&self.trade_id),
+                JournalEvent::LegFilled {
+                    leg: i as u8 + 1,
+                    size: size.to_f64().unwrap_or(0.0),
+                    price: prices[i].to_f64().unwrap_or(0.0),
+                },
+            );
+        }
+        let pair = TradeRecord {
+            trade_id: self.trade_id.clone(),
+            ..pair.clone()
+        };
+        pair.matched(self.filled)
+    }
+}
+
+#[derive(Default)]
+pub struct MakerQuoter<'a> {
+    live: Option<LiveQuotes<'a>>,
+    last_status_check: Option<std::time::Instant>,
+}
+
+impl<'a> MakerQuoter<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_quoting(&self) -> bool {
+        self.live.is_some()
+    }
+
+    /// Bring the resting quotes in line with `wanted` (None = nothing worth quoting) and check fills.
+    /// Returns what filled once both legs have filled in full, or when quotes that had partly filled were
+    /// cancelled to requote.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn step(
+        &mut self,
+        api: &dyn PredictionMarketVenue,
+        journal: &Journal,
+        wanted: Option<ArbSelection<'a>>,
+        next_trade_id: &str,
+        shares: &str,
+        threshold: Decimal,
+        taker_fees: &LegFees,
+        asks: &HashMap<&str, Option<Decimal>>,
+        status_poll_secs: u64,
+    ) -> Option<MakerFill<'a>> {
+        if self.live.is_some()
+            && self
+                .last_status_check
+                .is_none_or(|t| t.elapsed().as_secs() >= status_poll_secs)
+        {
+            self.refresh_fills(api).await;
+        }
+
+        let mut requoted = None;
+        if let Some(live) = &mut self.live {
+            match (live.legs[0].filled >= live.shares, live.legs[1].filled >= live.shares) {
+                (true, true) => {
+                    let live = self.live.take().expect("checked above");
+                    info!("Maker arb {} filled on both legs", live.trade_id);
+                    return Some(live.into_fill());
+                }
+                (true, false) | (false, true) => {
+                    complete_at_ask(api, journal, live, threshold, taker_fees, asks).await;
+                    return None;
+                }
+                (false, false) => {
+                    let unchanged = wanted.is_some_and(|w| {
+                        w.leg1_token == live.legs[0].token
+                            && w.leg2_token == live.legs[1].token
+                            && w.leg1_price == live.legs[0].price
+                            && w.leg2_price == live.legs[1].price
+                    });
+                    if unchanged {
+                        return None;
+                    }
+                    requoted = self.cancel(api).await;
+                }
+            }
+        }
+
+        let Some(wanted) = wanted else {
+            return requoted;
+        };
+        let mut legs = [
+            RestingLeg {
+                token: wanted.leg1_token.to_string(),
+                price: wanted.leg1_price,
+                order_id: None,
+                filled: Decimal::ZERO,
+            },
+            RestingLeg {
+                token: wanted.leg2_token.to_string(),
+                price: wanted.leg2_price,
+                order_id: None,
+                filled: Decimal::ZERO,
+            },
+        ];
+        for (i, leg) in legs.iter_mut().enumerate() {
+            let order = OrderRequest {
+                token_id: leg.token.clone(),
+                side: Side::Buy,
+                size: shares.to_string(),
+                price: format!("{:.4}", leg.price),
+                order_type: OrderType::Gtc,
+                expiration: None,
+                client_order_id: None,
+            };
+            match place_leg(api, journal, next_trade_id, i as u8 + 1, &order).await {
+                Ok(res) => leg.order_id = res.order_id,
+                Err(e) => warn!("Maker quote leg {} failed: {}", i + 1, e),
+            }
+        }
+        info!(
+            "Maker quotes {}: {} @ {:.4} + {} @ {:.4} (with maker fees {:.4} < {})",
+            next_trade_id,
+            wanted.leg1_outcome,
+            wanted.leg1_price,
+            wanted.leg2_outcome,
+            wanted.leg2_price,
+            wanted.effective_sum(),
+            threshold
+        );
+        self.live = Some(LiveQuotes {
+            selection: wanted,
+            trade_id: next_trade_id.to_string(),
+            shares: Decimal::from_str(shares.trim()).unwrap_or_default(),
+            legs,
+        });
+        self.last_status_check = Some(std::time::Instant::now());
+        requoted
+    }
+
+    /// Cancel whatever is still resting and return what the quotes filled (None when nothing did). Fills are read
+    /// before the cancel and again after it, once the cancelled orders' matched sizes are final.
+    pub async fn cancel(&mut self, api: &dyn PredictionMarketVenue) -> Option<MakerFill<'a>> {
+        self.refresh_fills(api).await;
+        let mut live = self.live.take()?;
+        let resting: Vec<&str> = live
+            .legs
+            .iter()
+            .filter(|leg| leg.filled < live.shares)
+            .filter_map(|leg| leg.order_id.as_deref())
+            .collect();
+        if !resting.is_empty() {
+            match api.cancel_orders(&resting).await {
+                Ok(outcome) => {
+                    for (order_id, reason) in outcome.not_canceled {
+                        warn!("Maker quote {} cancel failed: {}", order_id, reason);
+                    }
+                }
+                Err(e) => warn!("Maker quote cancel of {} failed: {}", resting.join(", "), e),
+            }
+            live.refresh(api).await;
+        }
+        let fill = live.into_fill();
+        match fill.filled {
+            [f1, f2] if f1.is_zero() && f2.is_zero() => None,
+            [f1, f2] => {
+                if f1 != f2 {
+                    warn!("Maker arb {}: legs filled {} / {}, the excess is held on its own", fill.trade_id, f1, f2);
+                }
+                Some(fill)
+            }
+        }
+    }
+
+    async fn refresh_fills(&mut self, api: &dyn PredictionMarketVenue) {
+        self.last_status_check = Some(std::time::Instant::now());
+        if let Some(live) = &mut self.live {
+            live.refresh(api).await;
+        }
+    }
+}
+
+impl<'a> LiveQuotes<'a> {
+    /// Read the matched size of every leg still short of `shares`.
+    async fn refresh(&mut self, api: &dyn PredictionMarketVenue) {
+        let shares = self.shares;
+        for leg in self.legs.iter_mut().filter(|l| l.filled < shares) {
+            let Some(order_id) = leg.order_id.as_deref() else {
+                continue;
+            };
+            match api.get_order_status(order_id).await {
+                Ok(status) => {
+                    let size = |v: &Option<String>| v.as_deref().and_then(|s| Decimal::from_str(s.trim()).ok());
+                    let matched = status.status.as_deref().is_some_and(|s| s.eq_ignore_ascii_case("matched"));
+                    let quoted = size(&status.original_size).unwrap_or(shares);
+                    let size_matched = size(&status.size_matched).or(matched.then_some(quoted));
+                    // A completion order counts on top of what the quote itself filled.
+                    leg.filled = leg.filled.max(size_matched.unwrap_or_default());
+                }
+                Err(e) => warn!("Maker quote {} status check failed: {}", order_id, e),
+            }
+        }
+    }
+
+    fn into_fill(self) -> MakerFill<'a> {
+        MakerFill {
+            trade_id: self.trade_id,
+            selection: self.selection,
+            filled: [self.legs[0].filled, self.legs[1].filled],
+        }
+    }
+}
+
+/// One leg filled as maker: cancel the other quote and lift the ask for what it is short of the filled leg, if
+/// the pair still beats the threshold.
+async fn complete_at_ask(
+    api: &dyn PredictionMarketVenue,
+    journal: &Journal,
+    live: &mut LiveQuotes<'_>,
+    threshold: Decimal,
+    taker_fees: &LegFees,
+    asks: &HashMap<&str, Option<Decimal>>,
+) {
+    let (filled, open) = if live.legs[0].filled >= live.shares { (0, 1) } else { (1, 0) };
+    let ask = asks.get(live.legs[open].token.as_str()).copied().flatten();
+    let filled_cost = if filled == 0 {
+        live.selection.leg1_price + live.selection.leg1_fee
+    } else {
+        live.selection.leg2_price + live.selection.leg2_fee
+    };
+    let open_bps = if open == 0 { taker_fees.bps_15 } else { taker_fees.bps_5 };
+    let Some(ask) = ask.filter(|a| filled_cost + effective_price(*a, open_bps) < threshold) else {
+        return;
+    };
+    if let Some(order_id) = live.legs[open].order_id.clone() {
+        live.refresh(api).await;
+        if let Err(e) = api.cancel_order(&order_id).await {
+            warn!("Maker quote {} cancel failed, not lifting the ask: {}", order_id, e);
+            return;
+        }
+        // Matched up to the cancel: the quote's final size.
+        live.refresh(api).await;
+        live.legs[open].order_id = None;
+    }
+    let missing = live.legs[filled].filled - live.legs[open].filled;
+    if missing <= Decimal::ZERO {
+        return;
+    }
+    let order = OrderRequest {
+        token_id: live.legs[open].token.clone(),
+        side: Side::Buy,
+        size: missing.to_string(),
+        price: format!("{:.4}", ask),
+        order_type: OrderType::Fok,
+        expiration: None,
+        client_order_id: None,
+    };
+    match place_leg(api, journal, &live.trade_id, open as u8 + 1, &order).await {
+        Ok(res) => {
+            info!("Maker arb {}: leg {} completed with {} at the ask {:.4}", live.trade_id, open + 1, missing, ask);
+            let leg = &mut live.legs[open];
+            // The leg's price and fee become the average over the quote's fill and the completion.
+            let (quoted_shares, total) = (leg.filled, leg.filled + missing);
+            let blend = |quoted: Decimal, lifted: Decimal| (quoted * quoted_shares + lifted * missing) / total;
+            let (price, fee) = if open == 0 {
+                (&mut live.selection.leg1_price, &mut live.selection.leg1_fee)
+            } else {
+                (&mut live.selection.leg2_price, &mut live.selection.leg2_fee)
+            };
+            *fee = blend(*fee, fee_per_share(ask, open_bps));
+            *price = blend(*price, ask);
+            leg.price = *price;
+            leg.order_id = res.order_id;
+            leg.filled = total;
+        }
+        Err(e) => {
+            // The quote is cancelled; the next step retries at the ask while the pair still beats the threshold.
+            warn!("Maker arb {}: completing leg {} at the ask failed: {}", live.trade_id, open + 1, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::models::Outcome;
+    use crate::services::arbitrage_orchestrator::PeriodContext;
+    use crate::services::execution_service::trade_record;
+    use rust_decimal_macros::dec;
+
+    fn selection() -> ArbSelection<'static> {
+        ArbSelection {
+            leg1_token: "15up",
+            leg1_outcome: Outcome::Up,
+            leg1_price: dec!(0.44),
+            leg1_fee: Decimal::ZERO,
+            leg2_token: "5down",
+            leg2_outcome: Outcome::Down,
+            leg2_price: dec!(0.50),
+            leg2_fee: Decimal::ZERO,
+            leg2_long: false,
+        }
+    }
+
+    /// One step quoting 10 shares under a threshold of 1, fills polled every `poll_secs`.
+    async fn step(
+        quoter: &mut MakerQuoter<'static>,
+        api: &MockPolymarketApi,
+        wanted: Option<ArbSelection<'static>>,
+        trade_id: &str,
+        asks: &HashMap<&str, Option<Decimal>>,
+        poll_secs: u64,
+    ) -> Option<MakerFill<'static>> {
+        let journal = Journal::new("");
+        quoter
+            .step(api, &journal, wanted, trade_id, "10", dec!(1), &LegFees::default(), asks, poll_secs)
+            .await
+    }
+
+    fn trades(fill: &MakerFill) -> Vec<TradeRecord> {
+        let period = PeriodContext {
+            symbol: "btc".to_string(),
+            period_15: 1,
+            period_5: 1,
+            cid_15: "c15".to_string(),
+            cid_5: "c5".to_string(),
+            t15_up: String::new(),
+            t15_down: String::new(),
+            t5_up: String::new(),
+            t5_down: String::new(),
+            strike_15: 0.0,
+            strike_5: 0.0,
+        };
+        let pair = trade_record(&fill.trade_id, &period, &fill.selection, "0");
+        fill.trades(&pair, &Journal::new(""))
+    }
+
+    #[tokio::test]
+    async fn partial_fills_are_read_before_a_requote_and_become_trades() {
+        let api = MockPolymarketApi::new();
+        api.rest_orders(true);
+        let mut quoter = MakerQuoter::new();
+        assert!(step(&mut quoter, &api, Some(selection()), "btc-1-1", &HashMap::new(), 3600).await.is_none());
+        api.fill_resting("mock-1", 4.0);
+        api.fill_resting("mock-2", 6.0);
+
+        // The poll isn't due, but the fills are read before the quotes are cancelled.
+        let moved = ArbSelection { leg1_price: dec!(0.43), ..selection() };
+        let fill = step(&mut quoter, &api, Some(moved), "btc-1-2", &HashMap::new(), 3600).await.expect("fills");
+        assert_eq!((fill.trade_id.as_str(), fill.filled), ("btc-1-1", [dec!(4), dec!(6)]));
+        assert_eq!(api.cancelled(), vec!["mock-1", "mock-2"]);
+        assert!(quoter.is_quoting());
+
+        let trades = trades(&fill);
+        assert_eq!(trades.len(), 2);
+        assert_eq!((trades[0].trade_id.as_str(), trades[0].size, trades[0].only_leg), ("btc-1-1", dec!(4), None));
+        assert_eq!((trades[1].trade_id.as_str(), trades[1].size), ("btc-1-1-leg2", dec!(2)));
+        assert_eq!(trades[1].only_leg, Some(2));
+    }
+
+    #[tokio::test]
+    async fn one_sided_fill_before_a_requote_is_held_as_a_one_sided_trade() {
+        let api = MockPolymarketApi::new();
+        api.rest_orders(true);
+        let mut quoter = MakerQuoter::new();
+        step(&mut quoter, &api, Some(selection()), "btc-1-1", &HashMap::new(), 3600).await;
+        api.fill_resting("mock-1", 3.0);
+
+        let fill = step(&mut quoter, &api, None, "btc-1-2", &HashMap::new(), 3600).await.expect("fills");
+        assert_eq!(fill.filled, [dec!(3), Decimal::ZERO]);
+        assert!(!quoter.is_quoting());
+        let trades = trades(&fill);
+        assert_eq!(trades.len(), 1);
+        assert_eq!((trades[0].only_leg, trades[0].size, trades[0].leg1_price), (Some(1), dec!(3), dec!(0.44)));
+    }
+
+    #[tokio::test]
+    async fn completion_lifts_only_what_the_other_leg_is_short() {
+        let api = MockPolymarketApi::new();
+        api.rest_orders(true);
+        let mut quoter = MakerQuoter::new();
+        let asks = HashMap::from([("5down", Some(dec!(0.52)))]);
+        step(&mut quoter, &api, Some(selection()), "btc-1-1", &asks, 0).await;
+        api.fill_resting("mock-1", 10.0);
+        api.fill_resting("mock-2", 4.0);
+
+        assert!(step(&mut quoter, &api, Some(selection()), "btc-1-2", &asks, 0).await.is_none());
+        let completion = api.orders().pop().expect("completion order");
+        assert_eq!((completion.size.as_str(), completion.order_type), ("6", OrderType::Fok));
+        assert_eq!(api.cancelled(), vec!["mock-2"]);
+
+        let fill = step(&mut quoter, &api, Some(selection()), "btc-1-2", &asks, 0).await.expect("both legs");
+        assert_eq!(fill.filled, [dec!(10), dec!(10)]);
+        // 4 at the quote and 6 at the ask.
+        assert_eq!(fill.selection.leg2_price, dec!(0.512));
+    }
+}
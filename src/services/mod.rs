@@ -1,5 +1,25 @@
+pub mod admin;
 pub mod arbitrage_orchestrator;
+pub mod canary;
+pub mod controls;
+pub mod cross_venue;
 pub mod discovery_service;
+pub mod end_of_day;
 pub mod execution_service;
+pub mod hedging;
+pub mod history_downloader;
+pub mod inventory;
+pub mod journal;
+pub mod maker_quoter;
+pub mod pending_resolution;
+pub mod pnl_ledger;
+pub mod portfolio;
+pub mod redeem_queue;
 pub mod redemption_service;
+pub mod replay;
+pub mod research_recorder;
 pub mod resolution_service;
+pub mod setup_wizard;
+pub mod skip_list;
+pub mod strategy;
+pub mod webhook;
@@ -0,0 +1,312 @@
+//! Persistent store of periods whose markets haven't resolved yet. A period is stored (with its trades) as
+//! soon as resolution starts, so a restart mid-wait doesn't lose it; if it is still unresolved after
+//! `resolution_max_wait_secs` it is handed to a background worker that keeps polling until it resolves.
+//! A period still open `resolution_delay_alert_secs` after its markets closed (usually a UMA dispute) is
+//! reported once as a `resolution_delayed` alert.
+
+use crate::adapters::polymarket::PolymarketClient;
+use crate::config::Config;
+use crate::events::{ArbEvent, EventBus};
+use crate::models::TradeRecord;
+use crate::services::inventory::Inventory;
+use crate::services::journal::Journal;
+use crate::services::pnl_ledger::PnlLedger;
+use crate::services::redeem_queue::RedeemQueue;
+use crate::services::redemption_service::auto_redeem_winners;
+use crate::services::resolution_service::resolve_pending_once;
+use crate::utils::clock::Clock;
+use crate::utils::json_store::{load_or_default, persist, store_path};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, Duration};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingResolution {
+    pub cid_15: String,
+    pub cid_5: String,
+    pub trade_ids: Vec<String>,
+    /// When the regular resolution wait gives up (`resolution_max_wait_secs` after the period closed).
+    pub deadline_unix: i64,
+    /// Kept whole so PnL can be computed after a restart.
+    pub trades: Vec<TradeRecord>,
+    /// Close of the later of the two markets; 0 in records stored before it was kept (see `closed_unix`).
+    #[serde(default)]
+    pub end_unix: i64,
+    /// Long then short market slug, for alerts.
+    #[serde(default)]
+    pub slugs: Vec<String>,
+    /// The `resolution_delayed` alert went out already.
+    #[serde(default)]
+    pub delay_alerted: bool,
+}
+
+impl PendingResolution {
+    /// `end_unix`, or for older records without it the resolution deadline, which is later: a delay alert may
+    /// come late for those but never right after a restart.
+    pub fn closed_unix(&self) -> i64 {
+        if self.end_unix > 0 {
+            self.end_unix
+        } else {
+            self.deadline_unix
+        }
+    }
+}
+
+pub struct PendingResolutions {
+    path: Option<PathBuf>,
+    items: Mutex<Vec<PendingResolution>>,
+    /// Periods whose regular resolution wait is still running in this process; the worker leaves them alone.
+    in_flight: Mutex<HashSet<(String, String)>>,
+}
+
+impl PendingResolutions {
+    /// Store persisted at `path` (empty keeps it in memory only), restoring any periods already there.
+    pub fn load(path: &str) -> Self {
+        let path = store_path(path);
+        let items: Vec<PendingResolution> = load_or_default(path.as_deref(), "Pending resolutions");
+        if !items.is_empty() {
+            info!("Pending resolutions: restored {} unresolved period(s)", items.len());
+        }
+        Self {
+            path,
+            items: Mutex::new(items),
+            in_flight: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Store the period of `trades` while its regular resolution wait runs.
+    pub fn track(&self, trades: &[TradeRecord], slugs: Vec<String>, end_unix: i64, deadline_unix: i64) {
+        let Some(first) = trades.first() else {
+            return;
+        };
+        let key = (first.cid_15.clone(), first.cid_5.clone());
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key.clone());
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if items.iter().any(|i| (&i.cid_15, &i.cid_5) == (&key.0, &key.1)) {
+            return;
+        }
+        items.push(PendingResolution {
+            cid_15: key.0,
+            cid_5: key.1,
+            trade_ids: trades.iter().map(|t| t.trade_id.clone()).collect(),
+            deadline_unix,
+            trades: trades.to_vec(),
+            end_unix,
+            slugs,
+            delay_alerted: false,
+        });
+        self.persist(&items);
+    }
+
+    /// The regular wait timed out: leave the period to the worker.
+    pub fn release(&self, cid_15: &str, cid_5: &str) {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&(cid_15.to_string(), cid_5.to_string()));
+    }
+
+    /// The period resolved and was settled.
+    pub fn remove(&self, cid_15: &str, cid_5: &str) {
+        self.release(cid_15, cid_5);
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let before = items.len();
+        items.retain(|i| (i.cid_15.as_str(), i.cid_5.as_str()) != (cid_15, cid_5));
+        if items.len() != before {
+            self.persist(&items);
+        }
+    }
+
+    /// Periods the worker should poll: everything stored that no regular wait is handling.
+    pub fn waiting(&self) -> Vec<PendingResolution> {
+        let in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        items
+            .iter()
+            .filter(|i| !in_flight.contains(&(i.cid_15.clone(), i.cid_5.clone())))
+            .cloned()
+            .collect()
+    }
+
+    /// Periods (in flight or not) open for `alert_after_secs` past their close that haven't been alerted yet;
+    /// each is returned once. 0 disables.
+    pub fn take_newly_delayed(&self, now_unix: i64, alert_after_secs: u64) -> Vec<PendingResolution> {
+        if alert_after_secs == 0 {
+            return Vec::new();
+        }
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let mut delayed = Vec::new();
+        for item in items.iter_mut() {
+            if !item.delay_alerted && now_unix - item.closed_unix() >= alert_after_secs as i64 {
+                item.delay_alerted = true;
+                delayed.push(item.clone());
+            }
+        }
+        if !delayed.is_empty() {
+            self.persist(&items);
+        }
+        delayed
+    }
+
+    fn persist(&self, items: &[PendingResolution]) {
+        persist(self.path.as_deref(), items, "Pending resolutions");
+    }
+}
+
+/// Worker: every `pending_resolution_poll_secs`, alert on newly delayed periods, then try each waiting period
+/// once; settle and redeem the ones that have resolved and drop them from the store. Delays are measured on
+/// `clock`.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_pending_resolutions(
+    api: Arc<dyn PolymarketClient>,
+    clock: Arc<dyn Clock>,
+    config: Config,
+    pending: Arc<PendingResolutions>,
+    cumulative_pnl: Arc<PnlLedger>,
+    journal: Arc<Journal>,
+    inventory: Arc<Inventory>,
+    redeem_queue: Arc<RedeemQueue>,
+    events: EventBus,
+) {
+    let poll = Duration::from_secs(config.strategy.pending_resolution_poll_secs.max(1));
+    loop {
+        let now_ms = clock.now_ms();
+        let now = clock.now();
+        for item in pending.take_newly_delayed(now, config.strategy.resolution_delay_alert_secs) {
+            let markets = if item.slugs.is_empty() {
+                format!("{} / {}", item.cid_15, item.cid_5)
+            } else {
+                item.slugs.join(" / ")
+            };
+            let message = format!(
+                "{} still unresolved {} min after close (possible UMA dispute); {} trade(s) waiting",
+                markets,
+                (now - item.closed_unix()) / 60,
+                item.trade_ids.len()
+            );
+            warn!("{}", message);
+            events.publish(ArbEvent::Alert {
+                kind: "resolution_delayed".to_string(),
+                subject: item.slugs.first().cloned().unwrap_or(item.cid_15),
+                message,
+                ts_ms: now_ms,
+            });
+        }
+        for item in pending.waiting() {
+            let settled =
+                resolve_pending_once(api.as_ref(), &config, &item.trades, Arc::clone(&cumulative_pnl), &journal).await;
+            let Some((redeem_targets, period_pnl)) = settled else {
+                continue;
+            };
+            info!(
+                "Late resolution of {} / {}: {} trade(s), PnL {:.2}, {}s past the resolution deadline",
+                item.cid_15,
+                item.cid_5,
+                item.trade_ids.len(),
+                period_pnl,
+                (clock.now() - item.deadline_unix).max(0)
+            );
+            if let Err(e) = auto_redeem_winners(
+                Arc::clone(&api),
+                &config,
+                &redeem_targets,
+                &journal,
+                &inventory,
+                &redeem_queue,
+            )
+            .await
+            {
+                warn!("Redeem after late resolution of {} failed: {}", item.cid_15, e);
+            }
+            pending.remove(&item.cid_15, &item.cid_5);
+        }
+        sleep(poll).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::domain::pnl::sample_trade;
+    use crate::utils::clock::ManualClock;
+    use futures_util::StreamExt;
+    use rust_decimal::Decimal;
+
+    fn trade(trade_id: &str) -> TradeRecord {
+        TradeRecord {
+            trade_id: trade_id.to_string(),
+            ..sample_trade()
+        }
+    }
+
+    #[test]
+    fn timed_out_periods_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("pending-resolutions-{}.json", std::process::id()));
+        let path_str = path.display().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let pending = PendingResolutions::load(&path_str);
+        let slugs = vec!["btc-updown-15m-1767726000".to_string(), "btc-updown-5m-1767726600".to_string()];
+        pending.track(&[trade("btc-1767726000-1"), trade("btc-1767726000-2")], slugs, 1767726900, 1767727560);
+        assert!(pending.waiting().is_empty(), "the regular wait still owns the period");
+        pending.release("c15", "c5");
+        assert_eq!(pending.waiting().len(), 1);
+
+        let restarted = PendingResolutions::load(&path_str);
+        let waiting = restarted.waiting();
+        assert_eq!(waiting.len(), 1);
+        assert_eq!(waiting[0].trade_ids, vec!["btc-1767726000-1", "btc-1767726000-2"]);
+        assert_eq!(waiting[0].trades[1].size, Decimal::TEN);
+
+        assert!(restarted.take_newly_delayed(1767726900 + 1799, 1800).is_empty());
+        let delayed = restarted.take_newly_delayed(1767726900 + 1800, 1800);
+        assert_eq!(delayed[0].slugs[0], "btc-updown-15m-1767726000");
+        assert!(restarted.take_newly_delayed(1767726900 + 3600, 1800).is_empty(), "alerted once");
+        assert!(PendingResolutions::load(&path_str).take_newly_delayed(i64::MAX, 1800).is_empty());
+        restarted.remove("c15", "c5");
+        assert!(PendingResolutions::load(&path_str).waiting().is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn records_without_a_close_time_are_timed_from_the_deadline() {
+        let pending = PendingResolutions::load("");
+        pending.track(&[trade("btc-1767726000-1")], Vec::new(), 0, 1767727560);
+        assert!(pending.take_newly_delayed(1767727560 + 1799, 1800).is_empty(), "not alerted on load");
+        assert_eq!(pending.take_newly_delayed(1767727560 + 1800, 1800).len(), 1);
+    }
+
+    #[tokio::test]
+    async fn delay_alerts_read_the_injected_clock() {
+        let pending = Arc::new(PendingResolutions::load(""));
+        pending.track(&[trade("btc-1767726000-1")], vec!["btc-updown-15m-1767726000".to_string()], 1767726900, 0);
+        pending.release("c15", "c5");
+        let now_ms = (1767726900 + 3600) * 1000;
+        let events = EventBus::new();
+        let mut alerts = Box::pin(events.subscribe());
+        let worker = tokio::spawn(run_pending_resolutions(
+            Arc::new(MockPolymarketApi::new()),
+            Arc::new(ManualClock::new(now_ms)),
+            Config::default(),
+            Arc::clone(&pending),
+            Arc::new(PnlLedger::load("")),
+            Arc::new(Journal::new("")),
+            Arc::new(Inventory::new()),
+            Arc::new(RedeemQueue::load("", 3)),
+            events.clone(),
+        ));
+        let Some(ArbEvent::Alert { kind, message, ts_ms, .. }) = alerts.next().await else {
+            panic!("no alert");
+        };
+        worker.abort();
+        assert_eq!((kind.as_str(), ts_ms), ("resolution_delayed", now_ms));
+        assert!(message.contains("60 min after close"), "{}", message);
+    }
+}
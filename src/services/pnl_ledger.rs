@@ -0,0 +1,88 @@
+//! Realized PnL per symbol and in total, persisted to a small JSON state file so it carries over between
+//! sessions instead of starting from zero on every restart.
+
+use crate::utils::json_store::{load_or_default, persist, store_path};
+use chrono::Utc;
+use log::info;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlState {
+    /// Keyed by lowercase symbol.
+    #[serde(default)]
+    pub by_symbol: BTreeMap<String, Decimal>,
+    #[serde(default)]
+    pub total: Decimal,
+    #[serde(default)]
+    pub updated_unix: i64,
+}
+
+pub struct PnlLedger {
+    path: Option<PathBuf>,
+    state: Mutex<PnlState>,
+}
+
+impl PnlLedger {
+    /// Ledger persisted at `path` (empty keeps it in memory only), restoring the totals already there.
+    pub fn load(path: &str) -> Self {
+        let path = store_path(path);
+        let state: PnlState = load_or_default(path.as_deref(), "PnL state");
+        if !state.by_symbol.is_empty() {
+            info!(
+                "PnL: restored cumulative {:.2} USDC across {} symbol(s)",
+                state.total,
+                state.by_symbol.len()
+            );
+        }
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Add realized `pnl` for `symbol`; returns the new overall total.
+    pub fn record(&self, symbol: &str, pnl: Decimal) -> Decimal {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        *state.by_symbol.entry(symbol.to_lowercase()).or_default() += pnl;
+        state.total += pnl;
+        state.updated_unix = Utc::now().timestamp();
+        persist(self.path.as_deref(), &*state, "PnL state");
+        state.total
+    }
+
+    pub fn total(&self) -> Decimal {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).total
+    }
+
+    pub fn snapshot(&self) -> PnlState {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn totals_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!("pnl-state-{}.json", std::process::id()));
+        let path_str = path.display().to_string();
+        let _ = std::fs::remove_file(&path);
+
+        let ledger = PnlLedger::load(&path_str);
+        ledger.record("BTC", dec!(0.50));
+        ledger.record("eth", dec!(-0.20));
+        assert_eq!(ledger.record("btc", dec!(0.25)), dec!(0.55));
+
+        let restarted = PnlLedger::load(&path_str).snapshot();
+        assert_eq!(restarted.total, dec!(0.55));
+        assert_eq!(restarted.by_symbol["btc"], dec!(0.75));
+        assert_eq!(restarted.by_symbol["eth"], dec!(-0.20));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,130 @@
+//! Periodic portfolio snapshots (cash, open position cost, marked value, realized PnL) appended as JSON
+//! lines, giving an equity curve for the daily report and the `equity` subcommand.
+
+use crate::adapters::polymarket::PolymarketClient;
+use crate::services::execution_service::UnrealizedPnl;
+use crate::services::inventory::Inventory;
+use crate::services::pnl_ledger::PnlLedger;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::warn;
+use rust_decimal::prelude::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    pub ts: i64,
+    /// Wallet USDC balance; None when it couldn't be read (no wallet configured, RPC down).
+    pub cash_usdc: Option<f64>,
+    /// Cost basis of positions still held.
+    pub open_cost: f64,
+    /// Open positions at cost plus the latest mark-to-market of the periods still trading.
+    pub marked_value: f64,
+    /// Cumulative realized PnL since the bot started.
+    pub realized_pnl: f64,
+}
+
+impl PortfolioSnapshot {
+    /// Cash plus marked positions; None without a cash reading.
+    pub fn equity(&self) -> Option<f64> {
+        self.cash_usdc.map(|cash| cash + self.marked_value)
+    }
+}
+
+/// Append a snapshot to `path` every `interval_mins` minutes.
+pub async fn run_portfolio_snapshots(
+    api: Arc<dyn PolymarketClient>,
+    inventory: Arc<Inventory>,
+    unrealized: UnrealizedPnl,
+    cumulative_pnl: Arc<PnlLedger>,
+    path: PathBuf,
+    interval_mins: u64,
+) {
+    let wallet = api.wallet_address();
+    loop {
+        let cash_usdc = match &wallet {
+            Some(wallet) => match api.get_usdc_balance(wallet).await {
+                Ok(balance) => Some(balance),
+                Err(e) => {
+                    warn!("Portfolio snapshot: USDC balance unavailable: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let open_cost: f64 = inventory.snapshot().await.values().map(|p| p.cost).sum();
+        let marked: f64 = unrealized.read().await.values().sum();
+        let snapshot = PortfolioSnapshot {
+            ts: Utc::now().timestamp(),
+            cash_usdc,
+            open_cost,
+            marked_value: open_cost + marked,
+            realized_pnl: cumulative_pnl.total().to_f64().unwrap_or(0.0),
+        };
+        if let Err(e) = append_snapshot(&path, &snapshot) {
+            warn!("Portfolio snapshot write to {} failed: {}", path.display(), e);
+        }
+        sleep(Duration::from_secs(interval_mins * 60)).await;
+    }
+}
+
+fn append_snapshot(path: &Path, snapshot: &PortfolioSnapshot) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .context(format!("Failed to open {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(snapshot)?)?;
+    Ok(())
+}
+
+/// All snapshots in `path`, oldest first. Unparseable lines (e.g. a torn last write) are skipped.
+pub fn read_equity_curve(path: &Path) -> Result<Vec<PortfolioSnapshot>> {
+    let data = std::fs::read_to_string(path)
+        .context(format!("Failed to read portfolio snapshots {}", path.display()))?;
+    Ok(data
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Equity change from the first snapshot at or after `since` to the latest one.
+pub fn equity_change_since(curve: &[PortfolioSnapshot], since: i64) -> Option<f64> {
+    let mut with_equity = curve.iter().filter(|s| s.ts >= since).filter_map(|s| s.equity());
+    let first = with_equity.next()?;
+    Some(with_equity.next_back().unwrap_or(first) - first)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(ts: i64, cash_usdc: Option<f64>, marked_value: f64) -> PortfolioSnapshot {
+        PortfolioSnapshot {
+            ts,
+            cash_usdc,
+            open_cost: marked_value,
+            marked_value,
+            realized_pnl: 0.0,
+        }
+    }
+
+    #[test]
+    fn equity_change_uses_snapshots_since() {
+        let curve = vec![
+            snapshot(100, Some(500.0), 0.0),
+            snapshot(200, Some(480.0), 30.0),
+            snapshot(300, None, 0.0),
+            snapshot(400, Some(490.0), 25.0),
+        ];
+        assert_eq!(equity_change_since(&curve, 0), Some(15.0));
+        assert_eq!(equity_change_since(&curve, 150), Some(5.0));
+        assert_eq!(equity_change_since(&curve, 400), Some(0.0));
+        assert_eq!(equity_change_since(&curve, 500), None);
+    }
+}
@@ -0,0 +1,401 @@
+//! Persistent retry queue for failed redemptions. Failed (condition, outcome) targets are stored in a JSON
+//! file, retried with exponential backoff, and marked permanently failed after `redeem_max_attempts`.
+
+use crate::adapters::venue::{PredictionMarketVenue, RedemptionStatus, UnconfirmedRedemption};
+use crate::events::ArbEvent;
+use crate::models::{JournalEvent, RedeemResponse};
+use crate::services::inventory::Inventory;
+use crate::services::journal::Journal;
+use crate::utils::json_store::{load_or_default, persist, read_json, store_path};
+use anyhow::Result;
+use chrono::Utc;
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const BASE_BACKOFF_SECS: i64 = 60;
+const MAX_BACKOFF_SECS: i64 = 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRedemption {
+    pub condition_id: String,
+    pub outcome: String,
+    pub attempts: u32,
+    pub first_failed_unix: i64,
+    pub next_attempt_unix: i64,
+    pub last_error: String,
+    /// Gave up after the max attempts; kept for the `redeem-queue` listing until redeemed manually.
+    #[serde(default)]
+    pub permanently_failed: bool,
+    /// Not failed: held back because gas was too high relative to the payout; retried with the next batch.
+    #[serde(default)]
+    pub deferred: bool,
+    /// Redemption tx sent but not final at the last attempt; its receipt is checked before sending another.
+    #[serde(default)]
+    pub pending_tx: Option<String>,
+}
+
+pub struct RedeemQueue {
+    path: Option<PathBuf>,
+    max_attempts: u32,
+    items: Mutex<Vec<PendingRedemption>>,
+}
+
+impl RedeemQueue {
+    /// Queue persisted at `path` (empty keeps it in memory only), restoring any entries already there.
+    pub fn load(path: &str, max_attempts: u32) -> Self {
+        let path = store_path(path);
+        let items: Vec<PendingRedemption> = load_or_default(path.as_deref(), "Redeem queue");
+        let pending = items.iter().filter(|i| !i.permanently_failed && !i.deferred).count();
+        if pending > 0 {
+            info!("Redeem queue: restored {} pending redemption(s)", pending);
+        }
+        Self {
+            path,
+            max_attempts: max_attempts.max(1),
+            items: Mutex::new(items),
+        }
+    }
+
+    /// Record a failed redemption; a target already queued keeps its attempt count. One that had permanently
+    /// failed is still held, so it gets a fresh set of attempts. An `UnconfirmedRedemption` keeps its tx hash.
+    pub fn enqueue(&self, condition_id: &str, outcome: &str, error: &anyhow::Error) {
+        let now = Utc::now().timestamp();
+        let pending_tx = unconfirmed_tx(error);
+        let error = error.to_string();
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(item) = items
+            .iter_mut()
+            .find(|i| i.condition_id == condition_id && i.outcome == outcome)
+        {
+            if pending_tx.is_some() {
+                item.pending_tx = pending_tx;
+                self.persist(&items);
+            } else if item.permanently_failed {
+                warn!(
+                    "Redemption of {} {} failed again after being given up ({} attempts); retrying it from scratch",
+                    condition_id, outcome, item.attempts
+                );
+                item.attempts = 1;
+                item.next_attempt_unix = now + backoff_secs(1);
+                item.last_error = error;
+                item.permanently_failed = false;
+                self.persist(&items);
+            }
+            return;
+        }
+        items.push(PendingRedemption {
+            condition_id: condition_id.to_string(),
+            outcome: outcome.to_string(),
+            attempts: 1,
+            first_failed_unix: now,
+            next_attempt_unix: now + backoff_secs(1),
+            last_error: error,
+            permanently_failed: false,
+            deferred: false,
+            pending_tx,
+        });
+        self.persist(&items);
+    }
+
+    /// Hold a target back until the next redemption batch.
+    pub fn defer(&self, condition_id: &str, outcome: &str, reason: &str) {
+        let now = Utc::now().timestamp();
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if items
+            .iter()
+            .any(|i| i.condition_id == condition_id && i.outcome == outcome)
+        {
+            return;
+        }
+        items.push(PendingRedemption {
+            condition_id: condition_id.to_string(),
+            outcome: outcome.to_string(),
+            attempts: 0,
+            first_failed_unix: now,
+            next_attempt_unix: now,
+            last_error: reason.to_string(),
+            permanently_failed: false,
+            deferred: true,
+            pending_tx: None,
+        });
+        self.persist(&items);
+    }
+
+    /// Remove and return deferred targets so they can join the current batch.
+    pub fn take_deferred(&self) -> Vec<(String, String)> {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let (deferred, rest): (Vec<_>, Vec<_>) = items.drain(..).partition(|i| i.deferred);
+        *items = rest;
+        if !deferred.is_empty() {
+            self.persist(&items);
+        }
+        deferred
+            .into_iter()
+            .map(|i| (i.condition_id, i.outcome))
+            .collect()
+    }
+
+    /// Make every pending and deferred entry due now (end of day: settle everything before going idle).
+    pub fn expedite(&self) {
+        let now = Utc::now().timestamp();
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        for item in items.iter_mut().filter(|i| !i.permanently_failed) {
+            item.deferred = false;
+            item.next_attempt_unix = now;
+        }
+        self.persist(&items);
+    }
+
+    /// (pending, permanently failed) entry counts.
+    pub fn counts(&self) -> (usize, usize) {
+        let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        let failed = items.iter().filter(|i| i.permanently_failed).count();
+        (items.len() - failed, failed)
+    }
+
+    /// Retry every entry whose backoff has elapsed. Successes leave the queue; failures back off, and
+    /// entries reaching the max attempts are flagged permanently failed and reported. An entry with a pending
+    /// tx is only sent again once that tx has reverted or dropped off the chain.
+    pub async fn retry_due(&self, api: &dyn PredictionMarketVenue, journal: &Journal, inventory: &Inventory) {
+        let now = Utc::now().timestamp();
+        let due: Vec<PendingRedemption> = {
+            let items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+            items
+                .iter()
+                .filter(|i| !i.permanently_failed && !i.deferred && i.next_attempt_unix <= now)
+                .cloned()
+                .collect()
+        };
+        for target in due {
+            let Some(result) = self.retry(api, &target, now).await else {
+                continue;
+            };
+            let redeemed = result.is_ok();
+            {
+                let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+                let Some(pos) = items.iter().position(|i| {
+                    i.condition_id == target.condition_id && i.outcome == target.outcome
+                }) else {
+                    continue;
+                };
+                self.apply_retry_result(&mut items, pos, result, now, journal);
+            }
+            if redeemed {
+                inventory.record_redeemed(&target.condition_id).await;
+            }
+        }
+    }
+
+    /// Redeem `target` again, or take its pending tx once final; None while that tx is still pending.
+    async fn retry(
+        &self,
+        api: &dyn PredictionMarketVenue,
+        target: &PendingRedemption,
+        now: i64,
+    ) -> Option<Result<RedeemResponse>> {
+        if let Some(tx_hash) = &target.pending_tx {
+            match api.redemption_status(tx_hash).await {
+                Ok(RedemptionStatus::Redeemed) => {
+                    return Some(Ok(RedeemResponse {
+                        success: true,
+                        message: Some(format!("Redemption tx {} is final", tx_hash)),
+                        transaction_hash: Some(tx_hash.clone()),
+                        amount_redeemed: None,
+                    }));
+                }
+                Ok(RedemptionStatus::Failed) => {
+                    warn!(
+                        "Redemption tx {} of {} reverted or was dropped; sending it again",
+                        tx_hash, target.condition_id
+                    );
+                }
+                Ok(RedemptionStatus::Pending) => {
+                    info!("Redemption tx {} of {} not final yet; checking again later", tx_hash, target.condition_id);
+                    self.check_later(target, now);
+                    return None;
+                }
+                Err(e) => {
+                    warn!(
+                        "Could not check redemption tx {} of {} ({}); checking again later",
+                        tx_hash, target.condition_id, e
+                    );
+                    self.check_later(target, now);
+                    return None;
+                }
+            }
+        }
+        Some(api.redeem(&target.condition_id, "", &target.outcome).await)
+    }
+
+    /// Back `target` off without counting an attempt: its pending tx may still land.
+    fn check_later(&self, target: &PendingRedemption, now: i64) {
+        let mut items = self.items.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(item) = items
+            .iter_mut()
+            .find(|i| i.condition_id == target.condition_id && i.outcome == target.outcome)
+        {
+            item.next_attempt_unix = now + backoff_secs(item.attempts);
+            self.persist(&items);
+        }
+    }
+
+    fn apply_retry_result(
+        &self,
+        items: &mut Vec<PendingRedemption>,
+        pos: usize,
+        result: Result<RedeemResponse>,
+        now: i64,
+        journal: &Journal,
+    ) {
+        match result {
+            Ok(res) => {
+                let target = items.remove(pos);
+                info!(
+                    "Redeem retry succeeded for {} {} after {} failed attempt(s)",
+                    target.condition_id, target.outcome, target.attempts
+                );
+                journal.record(
+                    None,
+                    JournalEvent::Redeemed {
+                        condition_id: target.condition_id,
+                        outcome: target.outcome,
+                        transaction_hash: res.transaction_hash,
+                    },
+                );
+                self.persist(items);
+            }
+            Err(e) => {
+                let item = &mut items[pos];
+                item.attempts += 1;
+                item.last_error = e.to_string();
+                item.pending_tx = unconfirmed_tx(&e);
+                item.next_attempt_unix = now + backoff_secs(item.attempts);
+                if item.attempts >= self.max_attempts {
+                    item.permanently_failed = true;
+                    let message = format!(
+                        "Redemption of {} {} permanently failed after {} attempts: {}. Redeem manually with --redeem --condition-id {}",
+                        item.condition_id, item.outcome, item.attempts, e, item.condition_id
+                    );
+                    error!("{}", message);
+                    if let Some(events) = journal.events() {
+                        events.publish(ArbEvent::Alert {
+                            kind: "redemption_failed".to_string(),
+                            subject: item.condition_id.clone(),
+                            message,
+                            ts_ms: Utc::now().timestamp_millis(),
+                        });
+                    }
+                } else {
+                    warn!(
+                        "Redeem retry {}/{} failed for {} {}: {}",
+                        item.attempts, self.max_attempts, item.condition_id, item.outcome, e
+                    );
+                }
+                journal.record(
+                    None,
+                    JournalEvent::RedeemFailed {
+                        condition_id: item.condition_id.clone(),
+                        outcome: item.outcome.clone(),
+                        error: item.last_error.clone(),
+                    },
+                );
+                self.persist(items);
+            }
+        }
+    }
+
+    fn persist(&self, items: &[PendingRedemption]) {
+        persist(self.path.as_deref(), items, "Redeem queue");
+    }
+}
+
+/// Hash of the redemption tx `error` left unconfirmed, if it did.
+fn unconfirmed_tx(error: &anyhow::Error) -> Option<String> {
+    error.downcast_ref::<UnconfirmedRedemption>().map(|u| u.tx_hash.clone())
+}
+
+/// 60s, 120s, 240s, ... capped at one hour.
+fn backoff_secs(attempts: u32) -> i64 {
+    BASE_BACKOFF_SECS
+        .saturating_mul(1i64 << attempts.saturating_sub(1).min(16))
+        .min(MAX_BACKOFF_SECS)
+}
+
+pub fn read_queue(path: &Path) -> Result<Vec<PendingRedemption>> {
+    read_json(path, "redeem queue")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::events::EventBus;
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn a_permanently_failed_target_failing_again_is_retried_from_scratch() {
+        let events = EventBus::new();
+        let is_alert = |e: &ArbEvent| std::future::ready(matches!(e, ArbEvent::Alert { .. }));
+        let mut alerts = Box::pin(events.subscribe().filter(is_alert));
+        let (api, inventory) = (MockPolymarketApi::new(), Inventory::new());
+        let journal = Journal::new("").with_events(events.clone());
+        let queue = RedeemQueue::load("", 2);
+        queue.enqueue("c15", "Up", &anyhow::anyhow!("reverted"));
+        // Queued already: the attempt count is kept.
+        queue.enqueue("c15", "Up", &anyhow::anyhow!("reverted"));
+        assert_eq!(queue.counts(), (1, 0));
+
+        queue.expedite();
+        api.fail_next_redeem("c15");
+        queue.retry_due(&api, &journal, &inventory).await;
+        assert_eq!(queue.counts(), (0, 1));
+        let Some(ArbEvent::Alert { kind, subject, message, .. }) = alerts.next().await else {
+            panic!("no alert");
+        };
+        assert_eq!((kind.as_str(), subject.as_str()), ("redemption_failed", "c15"));
+        assert!(message.contains("--redeem --condition-id c15"), "{}", message);
+
+        queue.enqueue("c15", "Up", &anyhow::anyhow!("reverted again"));
+        assert_eq!(queue.counts(), (1, 0));
+        let item = queue.items.lock().unwrap()[0].clone();
+        assert_eq!((item.attempts, item.last_error.as_str()), (1, "reverted again"));
+
+        queue.expedite();
+        queue.retry_due(&api, &journal, &inventory).await;
+        assert_eq!(queue.counts(), (0, 0));
+        assert_eq!(api.redeemed(), vec![("c15".to_string(), "Up".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn an_unconfirmed_redemption_is_rechecked_instead_of_sent_again() {
+        let (api, journal, inventory) = (MockPolymarketApi::new(), Journal::new(""), Inventory::new());
+        let queue = RedeemQueue::load("", 3);
+        for (condition_id, tx_hash) in [("c7", "0x77"), ("c8", "0x88")] {
+            api.leave_next_redeem_unconfirmed(condition_id, tx_hash);
+            let err = api.redeem(condition_id, "", "Up").await.unwrap_err();
+            queue.enqueue(condition_id, "Up", &err);
+        }
+        let pending_txs = |queue: &RedeemQueue| {
+            let items = queue.items.lock().unwrap();
+            items.iter().map(|i| (i.attempts, i.pending_tx.clone())).collect::<Vec<_>>()
+        };
+        assert_eq!(pending_txs(&queue), vec![(1, Some("0x77".into())), (1, Some("0x88".into()))]);
+
+        // Both still pending: nothing is sent and no attempt is counted.
+        queue.expedite();
+        queue.retry_due(&api, &journal, &inventory).await;
+        assert_eq!(api.redeemed().len(), 2);
+        assert_eq!(pending_txs(&queue), vec![(1, Some("0x77".into())), (1, Some("0x88".into()))]);
+
+        // c7's tx went final; c8's was reorged out and is sent again.
+        api.set_redemption_status("0x77", RedemptionStatus::Redeemed);
+        api.set_redemption_status("0x88", RedemptionStatus::Failed);
+        queue.expedite();
+        queue.retry_due(&api, &journal, &inventory).await;
+        assert_eq!(queue.counts(), (0, 0));
+        let sent: Vec<String> = api.redeemed().into_iter().map(|(cid, _)| cid).collect();
+        assert_eq!(sent, vec!["c7", "c8", "c8"]);
+    }
+}
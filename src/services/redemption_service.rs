@@ -1,13 +1,23 @@
-use crate::adapters::polymarket::PolymarketApi;
+use crate::adapters::polymarket::PolymarketClient;
+use crate::adapters::venue::UnconfirmedRedemption;
 use crate::config::Config;
+use crate::models::{JournalEvent, RedeemResponse};
+use crate::services::inventory::Inventory;
+use crate::services::journal::Journal;
+use crate::services::redeem_queue::RedeemQueue;
 use anyhow::Result;
 use log::{info, warn};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
 
 pub async fn auto_redeem_winners(
-    api: Arc<PolymarketApi>,
+    api: Arc<dyn PolymarketClient>,
     config: &Config,
     redeem_targets: &[(String, String)],
+    journal: &Journal,
+    inventory: &Inventory,
+    queue: &RedeemQueue,
 ) -> Result<()> {
     if !config.strategy.auto_redeem || config.strategy.simulation_mode {
         return Ok(());
@@ -15,13 +25,301 @@ pub async fn auto_redeem_winners(
     if config.polymarket.proxy_wallet_address.is_none() {
         return Ok(());
     }
+    let redeem_targets = &gas_worthy_targets(api.as_ref(), config, redeem_targets, inventory, queue).await[..];
+    if redeem_targets.is_empty() {
+        return Ok(());
+    }
 
-    for (condition_id, outcome) in redeem_targets {
-        if let Err(e) = api.redeem_tokens(condition_id, "", outcome).await {
-            warn!("Redeem failed for {} {}: {}", condition_id, outcome, e);
-        } else {
-            info!("Redeemed {} outcome {} tokens", condition_id, outcome);
-        }
+    for (condition_id, outcome, error) in redeem_all(api.as_ref(), redeem_targets, journal, inventory).await {
+        queue.enqueue(&condition_id, &outcome, &error);
     }
     Ok(())
 }
+
+/// Redeemer run mode: no trading, just sweep the wallet's redeemable positions every
+/// `strategy.redeemer_interval_secs` until stopped. Failures are retried by the next sweep.
+pub async fn run_redeemer(api: Arc<dyn PolymarketClient>, config: Config) -> Result<()> {
+    let wallet = api
+        .wallet_address()
+        .ok_or_else(|| anyhow::anyhow!("--mode redeemer needs private_key or proxy_wallet_address in config.json"))?;
+    let interval = Duration::from_secs(config.strategy.redeemer_interval_secs.max(1));
+    info!("Redeemer mode for {}: sweeping every {}s", wallet, interval.as_secs());
+    let (journal, inventory) = (Journal::new(&config.strategy.journal_path), Inventory::new());
+    loop {
+        if let Err(e) = sweep_redeemable(api.as_ref(), &config, &wallet, &journal, &inventory).await {
+            warn!("Redeem sweep failed: {}", e);
+        }
+        sleep(interval).await;
+    }
+}
+
+/// One redeemer pass: redeem everything `wallet` can claim; returns the targets that failed.
+pub async fn sweep_redeemable(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    wallet: &str,
+    journal: &Journal,
+    inventory: &Inventory,
+) -> Result<Vec<(String, String)>> {
+    let targets = api.get_redeemable_targets(wallet).await?;
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+    if config.strategy.simulation_mode {
+        info!("Simulation mode: would redeem {} condition(s): {:?}", targets.len(), targets);
+        return Ok(Vec::new());
+    }
+    info!("Found {} redeemable condition(s)", targets.len());
+    let failed = redeem_all(api, &targets, journal, inventory).await;
+    Ok(failed.into_iter().map(|(condition_id, outcome, _)| (condition_id, outcome)).collect())
+}
+
+/// One batch for every target; the targets that failed in it (all of them when the batch itself failed) are
+/// retried one by one, so a single bad target doesn't block the others. Targets left unconfirmed are not sent
+/// again: the queue re-checks their tx first. Returns (condition_id, outcome, error) of those that still failed.
+async fn redeem_all(
+    api: &dyn PolymarketClient,
+    redeem_targets: &[(String, String)],
+    journal: &Journal,
+    inventory: &Inventory,
+) -> Vec<(String, String, anyhow::Error)> {
+    let mut retry = redeem_targets.to_vec();
+    let mut failed = Vec::new();
+    if redeem_targets.len() > 1 {
+        match api.redeem_tokens_batch(redeem_targets).await {
+            Ok(results) => {
+                retry.clear();
+                for ((condition_id, outcome), result) in redeem_targets.iter().zip(results) {
+                    match result {
+                        Ok(res) => record_redeemed(journal, inventory, condition_id, outcome, &res).await,
+                        Err(e) if e.is::<UnconfirmedRedemption>() => {
+                            warn!("Batch redeem of {} {} not confirmed: {}", condition_id, outcome, e);
+                            failed.push((condition_id.clone(), outcome.clone(), e));
+                        }
+                        Err(e) => {
+                            warn!("Batch redeem of {} {} failed ({}); retrying it alone", condition_id, outcome, e);
+                            retry.push((condition_id.clone(), outcome.clone()));
+                        }
+                    }
+                }
+                info!(
+                    "Redeemed {} of {} conditions in one batch",
+                    redeem_targets.len() - retry.len() - failed.len(),
+                    redeem_targets.len()
+                );
+            }
+            Err(e) => match e.downcast::<UnconfirmedRedemption>() {
+                Ok(unconfirmed) => {
+                    warn!("Batch redeem not confirmed: {}", unconfirmed);
+                    for (condition_id, outcome) in retry.drain(..) {
+                        failed.push((condition_id, outcome, unconfirmed.clone().into()));
+                    }
+                }
+                Err(e) => warn!("Batch redeem failed ({}); redeeming individually", e),
+            },
+        }
+    }
+
+    for (condition_id, outcome) in &retry {
+        match api.redeem(condition_id, "", outcome).await {
+            Ok(res) => {
+                info!("Redeemed {} outcome {} tokens", condition_id, outcome);
+                record_redeemed(journal, inventory, condition_id, outcome, &res).await;
+            }
+            Err(e) => {
+                warn!("Redeem failed for {} {}: {}", condition_id, outcome, e);
+                journal.record(
+                    None,
+                    JournalEvent::RedeemFailed {
+                        condition_id: condition_id.clone(),
+                        outcome: outcome.clone(),
+                        error: e.to_string(),
+                    },
+                );
+                failed.push((condition_id.clone(), outcome.clone(), e));
+            }
+        }
+    }
+    failed
+}
+
+/// Current targets plus previously deferred ones, minus those whose share of the batch gas cost exceeds
+/// `max_redeem_gas_to_payout_ratio` of their payout; those are deferred again to share the next batch.
+/// Targets with an unknown payout, or when gas can't be estimated, are always redeemed.
+async fn gas_worthy_targets(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    redeem_targets: &[(String, String)],
+    inventory: &Inventory,
+    queue: &RedeemQueue,
+) -> Vec<(String, String)> {
+    let mut targets = redeem_targets.to_vec();
+    for target in queue.take_deferred() {
+        if !targets.contains(&target) {
+            targets.push(target);
+        }
+    }
+    let ratio = config.strategy.max_redeem_gas_to_payout_ratio;
+    if ratio <= 0.0 || targets.is_empty() {
+        return targets;
+    }
+    let cost = match api.estimate_redemption_cost_usd(&targets).await {
+        Ok(c) => c,
+        Err(e) => {
+            warn!("Redemption gas estimate failed ({}); redeeming without the gas check", e);
+            return targets;
+        }
+    };
+    let per_target = cost / targets.len() as f64;
+    let mut worthy = Vec::with_capacity(targets.len());
+    for (condition_id, outcome) in targets {
+        match inventory.outcome_size(&condition_id, &outcome).await {
+            Some(payout) if per_target > ratio * payout => {
+                let reason = format!(
+                    "gas ~${:.4} exceeds {} x payout ${:.2}",
+                    per_target, ratio, payout
+                );
+                info!("Deferring redeem of {} {}: {}", condition_id, outcome, reason);
+                queue.defer(&condition_id, &outcome, &reason);
+            }
+            _ => worthy.push((condition_id, outcome)),
+        }
+    }
+    worthy
+}
+
+async fn record_redeemed(
+    journal: &Journal,
+    inventory: &Inventory,
+    condition_id: &str,
+    outcome: &str,
+    res: &RedeemResponse,
+) {
+    inventory.record_redeemed(condition_id).await;
+    journal.record(
+        None,
+        JournalEvent::Redeemed {
+            condition_id: condition_id.to_string(),
+            outcome: outcome.to_string(),
+            transaction_hash: res.transaction_hash.clone(),
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+
+    #[test]
+    fn redeems_winners_in_one_batch() {
+        let api = Arc::new(MockPolymarketApi::new());
+        let mut config = Config::default();
+        config.strategy.auto_redeem = true;
+        config.strategy.simulation_mode = false;
+        config.polymarket.proxy_wallet_address = Some("0xproxy".to_string());
+        let targets = vec![
+            ("0xlong".to_string(), "Up".to_string()),
+            ("0xshort".to_string(), "Down".to_string()),
+        ];
+        let (journal, inventory, queue) = (Journal::new(""), Inventory::new(), RedeemQueue::load("", 3));
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        rt.block_on(auto_redeem_winners(api.clone(), &config, &targets, &journal, &inventory, &queue))
+            .unwrap();
+        assert_eq!(api.redeemed(), targets);
+        assert_eq!(queue.counts(), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn defers_a_target_whose_gas_share_outweighs_its_payout_to_the_next_batch() {
+        let api = Arc::new(MockPolymarketApi::new());
+        let mut config = Config::default();
+        config.strategy.auto_redeem = true;
+        config.strategy.simulation_mode = false;
+        config.strategy.max_redeem_gas_to_payout_ratio = 0.1;
+        config.polymarket.proxy_wallet_address = Some("0xproxy".to_string());
+        let (journal, inventory, queue) = (Journal::new(""), Inventory::new(), RedeemQueue::load("", 3));
+        inventory.record_buy("small-up", "0xsmall", "Up", 1.0, 0.5).await;
+        inventory.record_buy("large-up", "0xlarge", "Up", 100.0, 0.5).await;
+        let targets = vec![
+            ("0xsmall".to_string(), "Up".to_string()),
+            ("0xlarge".to_string(), "Up".to_string()),
+        ];
+
+        // $0.50 of gas each: over 0.1 x the $1 payout, well under 0.1 x $100.
+        api.set_redemption_cost(1.0);
+        auto_redeem_winners(api.clone(), &config, &targets, &journal, &inventory, &queue)
+            .await
+            .unwrap();
+        assert_eq!(api.redeemed(), vec![("0xlarge".to_string(), "Up".to_string())]);
+        assert_eq!(queue.counts(), (1, 0));
+
+        // Cheap enough now: the deferred target joins the next batch without being passed in again.
+        api.set_redemption_cost(0.05);
+        auto_redeem_winners(api.clone(), &config, &[], &journal, &inventory, &queue)
+            .await
+            .unwrap();
+        assert_eq!(api.redeemed().last(), Some(&("0xsmall".to_string(), "Up".to_string())));
+        assert_eq!(queue.counts(), (0, 0));
+    }
+
+    #[test]
+    fn retries_only_the_targets_that_failed_in_the_batch() {
+        let api = MockPolymarketApi::new();
+        let targets: Vec<(String, String)> = ["0xa", "0xb", "0xc"]
+            .iter()
+            .map(|cid| (cid.to_string(), "Up".to_string()))
+            .collect();
+        api.fail_next_redeem("0xb");
+        let (journal, inventory) = (Journal::new(""), Inventory::new());
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let failed = rt.block_on(redeem_all(&api, &targets, &journal, &inventory));
+        assert!(failed.is_empty());
+        let order: Vec<String> = api.redeemed().into_iter().map(|(cid, _)| cid).collect();
+        assert_eq!(order, vec!["0xa", "0xc", "0xb"], "0xa and 0xc are not redeemed twice");
+    }
+
+    #[tokio::test]
+    async fn a_target_left_unconfirmed_in_the_batch_is_queued_with_its_tx_not_sent_again() {
+        let api = Arc::new(MockPolymarketApi::new());
+        let mut config = Config::default();
+        config.strategy.auto_redeem = true;
+        config.strategy.simulation_mode = false;
+        config.polymarket.proxy_wallet_address = Some("0xproxy".to_string());
+        let targets: Vec<(String, String)> = ["0xa", "0xb"]
+            .iter()
+            .map(|cid| (cid.to_string(), "Up".to_string()))
+            .collect();
+        api.leave_next_redeem_unconfirmed("0xb", "0xtx");
+        let (journal, inventory, queue) = (Journal::new(""), Inventory::new(), RedeemQueue::load("", 3));
+        auto_redeem_winners(api.clone(), &config, &targets, &journal, &inventory, &queue)
+            .await
+            .unwrap();
+        assert_eq!(api.redeemed(), targets);
+        assert_eq!(queue.counts(), (1, 0));
+    }
+
+    #[test]
+    fn sweep_redeems_held_winners_once() {
+        let api = MockPolymarketApi::new().with_wallet("0xwallet", 0.0);
+        api.add_market("btc-up-or-down", "0xwon", &[("up", "Up"), ("down", "Down")]);
+        api.add_market("eth-up-or-down", "0xlost", &[("eth-up", "Up"), ("eth-down", "Down")]);
+        api.add_market("sol-up-or-down", "0xopen", &[("sol-up", "Up"), ("sol-down", "Down")]);
+        api.hold("down", 5.0);
+        api.hold("eth-up", 5.0);
+        api.hold("sol-up", 5.0);
+        api.resolve("0xwon", "Down");
+        api.resolve("0xlost", "Down");
+        let mut config = Config::default();
+        config.strategy.simulation_mode = false;
+        let (journal, inventory) = (Journal::new(""), Inventory::new());
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        for _ in 0..2 {
+            let failed = rt
+                .block_on(sweep_redeemable(&api, &config, "0xwallet", &journal, &inventory))
+                .unwrap();
+            assert!(failed.is_empty());
+        }
+        assert_eq!(api.redeemed(), vec![("0xwon".to_string(), "Down".to_string())]);
+    }
+}
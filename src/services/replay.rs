@@ -0,0 +1,343 @@
+//! `--replay <dir>`: run the strategy over a `polymarket.ws_record_dir` recording instead of live feeds, to
+//! answer "why did it trade here?" after the fact.
+//!
+//! Messages are applied in recorded order through the same parsers as the live feeds. Each recorded overlap goes
+//! through `Strategy::setup` and is then run by the live `run_overlap_round`, in simulation mode, on a
+//! `ManualClock` set to the recording's time and a stepped `MarketFeed`: every market message wakes the rounds,
+//! and between messages the clock stops at each time a round asked to wake at (a cooldown running out, the idle
+//! wakeup), so each pass happens at the recorded time it would have live and a replay gives the same trades
+//! whatever its speed. Maker quoting and hedging don't run in simulation, so they are not replayed. Outcomes come
+//! from the recorded Chainlink ticks: the first tick at or after each market's close against its price-to-beat,
+//! as the markets resolve.
+
+use crate::adapters::polymarket::mock::MockPolymarketApi;
+use crate::adapters::polymarket::ws_market::{process_message, PricesSnapshot};
+use crate::adapters::polymarket::ws_recorder::{RecordedMessage, SOURCE_MARKET, SOURCE_ROUND, SOURCE_RTDS};
+use crate::adapters::polymarket::ws_rtds::{parse_chainlink_tick, push_tick, SpotHistory};
+use crate::adapters::polymarket::PolymarketClient;
+use crate::config::Config;
+use crate::domain::pnl::{compute_trade_pnl_by_outcome, infer_outcome};
+use crate::domain::trade_ids::issued_at_ms;
+use crate::models::{Outcome, TradeRecord};
+use crate::services::arbitrage_orchestrator::PeriodContext;
+use crate::services::canary::Canary;
+use crate::services::controls::RuntimeControls;
+use crate::services::execution_service::{run_overlap_round, MarketFeed, RoundContext, UnrealizedPnl};
+use crate::services::journal::Journal;
+use crate::services::pnl_ledger::PnlLedger;
+use crate::services::strategy::Strategy;
+use crate::utils::clock::ManualClock;
+use anyhow::Result;
+use dashmap::DashMap;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use log::info;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc, watch, RwLock};
+use tokio::time::{sleep, Duration};
+
+/// One replayed arb: when it was decided (recording time) and, once both markets closed in the recording,
+/// their outcomes and the arb's PnL.
+#[derive(Debug, Clone)]
+pub struct ReplayedTrade {
+    pub trade: TradeRecord,
+    pub decided_at_ms: i64,
+    pub outcomes: Option<(Outcome, Outcome)>,
+    pub pnl: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReplayReport {
+    pub messages: usize,
+    pub rounds: usize,
+    pub trades: Vec<ReplayedTrade>,
+    /// PnL of the trades whose markets closed within the recording, and of the arbs closed early.
+    pub pnl: Decimal,
+}
+
+/// A replayed overlap round: how to wake it, when it asked to be woken, and the Chainlink closes once seen.
+struct Round {
+    ctx: PeriodContext,
+    period_end: i64,
+    short_end: i64,
+    updates: watch::Sender<()>,
+    waits: mpsc::UnboundedReceiver<i64>,
+    /// Set while the round waits; None while it runs a pass.
+    wake_at_ms: Option<i64>,
+    /// What the round returned, once it ended.
+    trades: Option<Vec<TradeRecord>>,
+    close_15: Option<f64>,
+    close_5: Option<f64>,
+}
+
+impl Round {
+    fn wake(&mut self) {
+        if self.wake_at_ms.take().is_some() {
+            let _ = self.updates.send(());
+        }
+    }
+
+    fn running_pass(&self) -> bool {
+        self.trades.is_none() && self.wake_at_ms.is_none()
+    }
+}
+
+type RoundFuture<'a> = Pin<Box<dyn Future<Output = (usize, Result<Vec<TradeRecord>>)> + 'a>>;
+
+/// Replay `messages` (see `ws_recorder::read_recordings`) through `strategy` with `config`. `speed` paces the
+/// replay: 1 keeps the original timing, 10 runs ten times faster, 0 as fast as possible.
+pub async fn run_replay(
+    config: &Config,
+    strategy: &dyn Strategy,
+    messages: &[RecordedMessage],
+    speed: f64,
+) -> Result<ReplayReport> {
+    let mut config = config.clone();
+    config.strategy.simulation_mode = true;
+    // Nothing to check the recorded books against, and no live arb to size as a canary.
+    config.strategy.book_cross_check = false;
+    config.strategy.canary_fraction = 0.0;
+    let config = &config;
+
+    // A simulated round places nothing; the API is only there to satisfy the round.
+    let api: Arc<dyn PolymarketClient> = Arc::new(MockPolymarketApi::new());
+    let journal = Arc::new(Journal::new(""));
+    let (signals, _) = broadcast::channel(16);
+    let canary = Arc::new(Canary::load(&config.strategy));
+    let ledger = Arc::new(PnlLedger::load(""));
+    let unrealized: UnrealizedPnl = Arc::new(RwLock::new(HashMap::new()));
+    let controls = RuntimeControls::new();
+    let prices: PricesSnapshot = Arc::new(DashMap::new());
+    let spot_history: SpotHistory = Arc::new(RwLock::new(HashMap::new()));
+    // Recording time: the replay's clock, moved message by message and to each wakeup in between.
+    let clock = ManualClock::new(messages.first().map_or(0, |m| m.ts_ms));
+    let (signals, canary, ledger, controls, spot_history, clock) =
+        (&signals, &canary, &ledger, &controls, &spot_history, &clock);
+
+    let mut rounds: Vec<Round> = Vec::new();
+    let mut running: FuturesUnordered<RoundFuture<'_>> = FuturesUnordered::new();
+    let mut prev_ts: Option<i64> = None;
+
+    for message in messages {
+        let now_ms = message.ts_ms;
+        if let Some(prev) = prev_ts {
+            if speed > 0.0 && now_ms > prev {
+                sleep(Duration::from_secs_f64((now_ms - prev) as f64 / 1000.0 / speed)).await;
+            }
+        }
+        prev_ts = Some(now_ms);
+        // The passes the live rounds would have run before this message.
+        while let Some(due) = rounds.iter().filter_map(|r| r.wake_at_ms).filter(|t| *t < now_ms).min() {
+            clock.set(due);
+            for round in rounds.iter_mut().filter(|r| r.wake_at_ms.is_some_and(|t| t <= due)) {
+                round.wake();
+            }
+            settle(&mut rounds, &mut running).await?;
+        }
+        clock.set(now_ms);
+
+        match message.source.as_str() {
+            SOURCE_MARKET => {
+                let _ = process_message(&message.data, &prices, None);
+                rounds.iter_mut().for_each(Round::wake);
+            }
+            SOURCE_RTDS => {
+                if let Some((symbol, ts_ms, value)) = parse_chainlink_tick(&message.data) {
+                    push_tick(spot_history.write().await.entry(symbol.clone()).or_default(), ts_ms, value);
+                    for round in rounds.iter_mut().filter(|r| r.ctx.symbol.eq_ignore_ascii_case(&symbol)) {
+                        if round.close_15.is_none() && ts_ms >= round.period_end * 1000 {
+                            round.close_15 = Some(value);
+                        }
+                        if round.close_5.is_none() && ts_ms >= round.short_end * 1000 {
+                            round.close_5 = Some(value);
+                        }
+                    }
+                }
+            }
+            SOURCE_ROUND => {
+                let Ok(ctx) = serde_json::from_str::<PeriodContext>(&message.data) else {
+                    continue;
+                };
+                // Every instance of a multi-instance run records its own copy.
+                if rounds.iter().any(|r| r.ctx.key() == ctx.key()) {
+                    continue;
+                }
+                if let Err(reason) = strategy.setup(config, &ctx) {
+                    info!("[REPLAY] {}: {}; skipping.", ctx.symbol.to_uppercase(), reason);
+                    continue;
+                }
+                let (updates, updates_rx) = watch::channel(());
+                let (waits_tx, waits) = mpsc::unbounded_channel();
+                let asset_ids = vec![ctx.t15_up.clone(), ctx.t15_down.clone(), ctx.t5_up.clone(), ctx.t5_down.clone()];
+                let feed = MarketFeed::stepped(asset_ids, Arc::clone(&prices), updates_rx, waits_tx);
+                let (index, round_ctx, api) = (rounds.len(), ctx.clone(), Arc::clone(&api));
+                let (unrealized, journal) = (Arc::clone(&unrealized), Arc::clone(&journal));
+                running.push(Box::pin(async move {
+                    let ctx = round_ctx;
+                    let round = RoundContext {
+                        api,
+                        config,
+                        unrealized,
+                        journal,
+                        signals,
+                        canary,
+                        spot_history,
+                        cumulative_pnl: ledger,
+                        hedger: None,
+                        strategy,
+                        controls,
+                        clock,
+                    };
+                    let trades = run_overlap_round(round, &ctx, Some(feed)).await;
+                    (index, trades)
+                }));
+                let periods = config.strategy.periods;
+                rounds.push(Round {
+                    period_end: ctx.period_15 + periods.long_secs(),
+                    short_end: ctx.period_5 + periods.short_secs(),
+                    ctx,
+                    updates,
+                    waits,
+                    wake_at_ms: None,
+                    trades: None,
+                    close_15: None,
+                    close_5: None,
+                });
+            }
+            _ => {}
+        }
+        settle(&mut rounds, &mut running).await?;
+    }
+    // The recording ends: wind up the rounds still open with what they traded so far.
+    controls.stop();
+    rounds.iter_mut().for_each(Round::wake);
+    settle(&mut rounds, &mut running).await?;
+
+    let mut report = ReplayReport {
+        messages: messages.len(),
+        rounds: rounds.len(),
+        pnl: ledger.total(),
+        ..Default::default()
+    };
+    for round in rounds {
+        let outcomes = round.close_15.zip(round.close_5).map(|(close_15, close_5)| {
+            (
+                infer_outcome(round.ctx.strike_15, close_15),
+                infer_outcome(round.ctx.strike_5, close_5),
+            )
+        });
+        for trade in round.trades.unwrap_or_default() {
+            let pnl = outcomes.map(|(outcome_15, outcome_5)| {
+                compute_trade_pnl_by_outcome(&trade, outcome_15, outcome_5).pnl
+            });
+            report.pnl += pnl.unwrap_or_default();
+            report.trades.push(ReplayedTrade {
+                decided_at_ms: issued_at_ms(&trade.trade_id).unwrap_or_default(),
+                trade,
+                outcomes,
+                pnl,
+            });
+        }
+    }
+    report.trades.sort_by_key(|t| t.decided_at_ms);
+    Ok(report)
+}
+
+/// Poll the rounds until each one running a pass is waiting again or has ended.
+async fn settle(rounds: &mut [Round], running: &mut FuturesUnordered<RoundFuture<'_>>) -> Result<()> {
+    for i in 0..rounds.len() {
+        while rounds[i].running_pass() {
+            let waits = &mut rounds[i].waits;
+            let ended = tokio::select! {
+                Some(wake_at_ms) = waits.recv() => {
+                    rounds[i].wake_at_ms = Some(wake_at_ms);
+                    continue;
+                }
+                Some(ended) = running.next() => ended,
+                else => anyhow::bail!("replayed round {} stalled", rounds[i].ctx.key()),
+            };
+            let (index, trades) = ended;
+            rounds[index].trades = Some(trades?);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::strategy::OverlapArb;
+    use serde_json::json;
+
+    fn message(ts_ms: i64, source: &str, data: serde_json::Value) -> RecordedMessage {
+        RecordedMessage {
+            ts_ms,
+            source: source.to_string(),
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn replays_a_recorded_overlap() {
+        let period_15 = 1_767_726_000;
+        let start_ms = (period_15 + 600) * 1000;
+        let ctx = PeriodContext {
+            symbol: "btc".to_string(),
+            period_15,
+            period_5: period_15 + 600,
+            cid_15: "0xc15".to_string(),
+            cid_5: "0xc5".to_string(),
+            t15_up: "15up".to_string(),
+            t15_down: "15down".to_string(),
+            t5_up: "5up".to_string(),
+            t5_down: "5down".to_string(),
+            strike_15: 100_000.0,
+            strike_5: 100_000.0,
+        };
+        let book = |token: &str, ask: f64| {
+            json!({
+                "event_type": "book",
+                "asset_id": token,
+                "bids": [],
+                "asks": [{ "price": ask.to_string(), "size": "100" }],
+            })
+        };
+        let tick = |ts_ms: i64, value: f64| {
+            json!({
+                "topic": "crypto_prices_chainlink",
+                "payload": { "symbol": "btc/usd", "timestamp": ts_ms, "value": value },
+            })
+        };
+        let mut messages = vec![message(start_ms, SOURCE_ROUND, serde_json::to_value(&ctx).unwrap())];
+        // 15m Up + 5m Down costs 0.95; the other pair 1.07.
+        let asks = [("15up", 0.45), ("15down", 0.56), ("5up", 0.51), ("5down", 0.50)];
+        for (i, (token, ask)) in asks.into_iter().enumerate() {
+            messages.push(message(start_ms + 1000 + i as i64, SOURCE_MARKET, book(token, ask)));
+        }
+        // Quiet until the close: the round wakes for the second arb when the 5s cooldown runs out.
+        messages.push(message(start_ms + 60_000, SOURCE_RTDS, tick(start_ms + 59_000, 99_990.0)));
+        let close_ms = (period_15 + 900) * 1000;
+        messages.push(message(close_ms + 1500, SOURCE_RTDS, tick(close_ms + 1000, 100_010.0)));
+
+        let mut config = Config::default();
+        config.strategy.arb_shares = "10".to_string();
+        config.strategy.max_trades_per_period = 2;
+        config.strategy.trade_interval_secs = 5;
+        let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let report = rt.block_on(run_replay(&config, &OverlapArb, &messages, 0.0)).unwrap();
+
+        assert_eq!(report.rounds, 1);
+        assert_eq!(report.trades.len(), 2);
+        let first = &report.trades[0];
+        assert_eq!(first.trade.trade_id, "btc-1767726000-601003");
+        assert_eq!((first.trade.leg1_token.as_str(), first.trade.leg2_token.as_str()), ("15up", "5down"));
+        assert_eq!(first.decided_at_ms, start_ms + 1003);
+        assert_eq!(report.trades[1].decided_at_ms, start_ms + 6003);
+        // Both markets close Up: each arb's 15m leg pays 10 against 9.50 paid.
+        assert_eq!(first.outcomes, Some((Outcome::Up, Outcome::Up)));
+        assert_eq!(report.pnl, Decimal::ONE, "replay PnL {}", report.pnl);
+    }
+}
@@ -0,0 +1,248 @@
+//! Research mode (`research` subcommand): no trading. During every overlap window it samples the live
+//! Chainlink spot (RTDS) next to the long and short markets' best bid/ask (market WS) and appends one CSV
+//! row per symbol per sample, building datasets on how binary quotes follow spot moves.
+
+use crate::adapters::polymarket::ws_rtds::{run_chainlink_multi_poller, PriceCacheMulti, SpotCache};
+use crate::adapters::polymarket::PolymarketApi;
+use crate::config::Config;
+use crate::services::discovery_service::MarketDiscovery;
+use crate::services::execution_service::MarketFeed;
+use crate::utils::clock::SystemClock;
+use crate::utils::supervisor::Supervisor;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use log::{info, warn};
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tokio::sync::RwLock;
+use tokio::time::{sleep, Duration};
+
+const WINDOW_POLL_SECS: u64 = 5;
+const CSV_HEADER: &str = "ts_ms,symbol,period_long,secs_to_close,spot,spot_ts_ms,ptb_long,ptb_short,\
+long_up_bid,long_up_ask,long_down_bid,long_down_ask,short_up_bid,short_up_ask,short_down_bid,short_down_ask";
+
+type CsvOut = Arc<Mutex<BufWriter<std::fs::File>>>;
+
+/// Record every configured symbol's overlap windows to `output` (CSV, appended) every `interval_ms`.
+pub async fn run_research(api: Arc<PolymarketApi>, config: Config, output: &Path, interval_ms: u64) -> Result<()> {
+    let is_new = !output.exists() || std::fs::metadata(output)?.len() == 0;
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(output)
+        .context(format!("Failed to open {}", output.display()))?;
+    let mut writer = BufWriter::new(file);
+    if is_new {
+        writeln!(writer, "{}", CSV_HEADER)?;
+    }
+    let out: CsvOut = Arc::new(Mutex::new(writer));
+
+    let periods = config.strategy.periods;
+    let cache_long: PriceCacheMulti = Arc::new(RwLock::new(HashMap::new()));
+    let cache_short: PriceCacheMulti = Arc::new(RwLock::new(HashMap::new()));
+    let spot: SpotCache = Arc::new(RwLock::new(HashMap::new()));
+    run_chainlink_multi_poller(
+        config.polymarket.rtds_ws_url.clone(),
+        config.strategy.symbols.clone(),
+        periods,
+        Arc::clone(&cache_long),
+        Arc::clone(&cache_short),
+        Some(Arc::clone(&spot)),
+        None,
+        &Supervisor::default(),
+    )
+    .await?;
+    info!(
+        "Research mode: recording {} vs {} overlaps for {:?} to {} every {} ms (no trading)",
+        periods.long_label(),
+        periods.short_label(),
+        config.strategy.symbols,
+        output.display(),
+        interval_ms
+    );
+
+    let mut handles = Vec::new();
+    for symbol in config.strategy.symbols.clone() {
+        let recorder = SymbolRecorder {
+            discovery: MarketDiscovery::new(api.clone()),
+            config: config.clone(),
+            cache_long: Arc::clone(&cache_long),
+            cache_short: Arc::clone(&cache_short),
+            spot: Arc::clone(&spot),
+            out: Arc::clone(&out),
+            interval_ms: interval_ms.max(1),
+        };
+        handles.push(tokio::spawn(async move { recorder.run(&symbol).await }));
+    }
+    futures_util::future::try_join_all(handles).await?;
+    Ok(())
+}
+
+struct SymbolRecorder {
+    discovery: MarketDiscovery,
+    config: Config,
+    cache_long: PriceCacheMulti,
+    cache_short: PriceCacheMulti,
+    spot: SpotCache,
+    out: CsvOut,
+    interval_ms: u64,
+}
+
+impl SymbolRecorder {
+    async fn run(&self, symbol: &str) {
+        let periods = self.config.strategy.periods;
+        loop {
+            let now = Utc::now().timestamp();
+            let (long_start, short_start) = periods.current_starts(&SystemClock);
+            if !periods.in_overlap(now, long_start) {
+                sleep(Duration::from_secs(WINDOW_POLL_SECS)).await;
+                continue;
+            }
+            let asset_ids = match self.lookup_tokens(symbol, long_start, short_start).await {
+                Some(ids) => ids,
+                None => {
+                    sleep(Duration::from_secs(WINDOW_POLL_SECS)).await;
+                    continue;
+                }
+            };
+            let feed = MarketFeed::spawn(&self.config.polymarket.ws_url, asset_ids.clone(), symbol, None);
+            let close = long_start + periods.long_secs();
+            let mut rows = 0usize;
+            while Utc::now().timestamp() < close {
+                if let Err(e) = self.sample(symbol, long_start, short_start, close, &feed, &asset_ids).await {
+                    warn!("Research {}: write failed: {}", symbol.to_uppercase(), e);
+                }
+                rows += 1;
+                sleep(Duration::from_millis(self.interval_ms)).await;
+            }
+            drop(feed);
+            if let Err(e) = self.out.lock().unwrap_or_else(|e| e.into_inner()).flush() {
+                warn!("Research: flush failed: {}", e);
+            }
+            info!("Research {}: recorded {} sample(s) for period {}", symbol.to_uppercase(), rows, long_start);
+        }
+    }
+
+    /// Token ids (long Up, long Down, short Up, short Down) of the current overlap.
+    async fn lookup_tokens(&self, symbol: &str, long_start: i64, short_start: i64) -> Option<Vec<String>> {
+        let periods = self.config.strategy.periods;
+        let mut asset_ids = Vec::with_capacity(4);
+        for (period_mins, start) in [
+            (periods.long_period_mins, long_start),
+            (periods.short_period_mins, short_start),
+        ] {
+            let (condition_id, _) = self.discovery.get_updown_market(symbol, period_mins, start).await.ok()??;
+            let (up, down) = self.discovery.get_market_tokens(&condition_id).await.ok()?;
+            asset_ids.extend([up, down]);
+        }
+        Some(asset_ids)
+    }
+
+    async fn sample(
+        &self,
+        symbol: &str,
+        long_start: i64,
+        short_start: i64,
+        close: i64,
+        feed: &MarketFeed,
+        asset_ids: &[String],
+    ) -> Result<()> {
+        let key = symbol.to_lowercase();
+        let spot = self.spot.read().await.get(&key).copied();
+        let ptb_long = self.cache_long.read().await.get(&key).and_then(|m| m.get(&long_start).copied());
+        let ptb_short = self.cache_short.read().await.get(&key).and_then(|m| m.get(&short_start).copied());
+        let quotes: Vec<String> = {
+            let prices = feed.prices();
+            asset_ids
+                .iter()
+                .flat_map(|id| {
+                    let (bid, ask) = prices.get(id).map(|p| (p.bid, p.ask)).unwrap_or_default();
+                    [bid, ask]
+                })
+                .map(opt)
+                .collect()
+        };
+        let now_ms = Utc::now().timestamp_millis();
+        let row = format!(
+            "{},{},{},{},{},{},{},{},{}",
+            now_ms,
+            key,
+            long_start,
+            close - now_ms / 1000,
+            opt(spot.map(|(_, v)| v)),
+            spot.map(|(ts, _)| ts.to_string()).unwrap_or_default(),
+            opt(ptb_long),
+            opt(ptb_short),
+            quotes.join(",")
+        );
+        let mut out = self.out.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(out, "{}", row)?;
+        Ok(())
+    }
+}
+
+fn opt(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::adapters::polymarket::ws_market::BestPrices;
+    use dashmap::DashMap;
+    use tokio::sync::{mpsc, watch};
+
+    #[tokio::test]
+    async fn a_sample_row_puts_spot_and_strikes_next_to_each_tokens_quote() {
+        let path = std::env::temp_dir().join(format!("research-sample-{}.csv", std::process::id()));
+        let file = OpenOptions::new().create(true).write(true).truncate(true).open(&path).unwrap();
+        let (long_start, short_start) = (1767726000, 1767726600);
+        let recorder = SymbolRecorder {
+            discovery: MarketDiscovery::new(Arc::new(MockPolymarketApi::new())),
+            config: Config::default(),
+            cache_long: Arc::new(RwLock::new(HashMap::from([(
+                "btc".to_string(),
+                HashMap::from([(long_start, 97000.5)]),
+            )]))),
+            cache_short: Arc::new(RwLock::new(HashMap::new())),
+            spot: Arc::new(RwLock::new(HashMap::from([("btc".to_string(), (1767726700123, 97010.25))]))),
+            out: Arc::new(Mutex::new(BufWriter::new(file))),
+            interval_ms: 1,
+        };
+        let asset_ids: Vec<String> = ["15up", "15down", "5up", "5down"].map(String::from).to_vec();
+        let prices = Arc::new(DashMap::new());
+        prices.insert(
+            "15up".to_string(),
+            BestPrices {
+                bid: Some(0.55),
+                ask: Some(0.57),
+                ..Default::default()
+            },
+        );
+        prices.insert(
+            "5down".to_string(),
+            BestPrices {
+                bid: None,
+                ask: Some(0.41),
+                ..Default::default()
+            },
+        );
+        let feed = MarketFeed::stepped(asset_ids.clone(), prices, watch::channel(()).1, mpsc::unbounded_channel().0);
+
+        let close = long_start + 900;
+        recorder.sample("BTC", long_start, short_start, close, &feed, &asset_ids).await.unwrap();
+        recorder.out.lock().unwrap().flush().unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let row: Vec<&str> = csv.trim_end().split(',').collect();
+        assert_eq!(row.len(), CSV_HEADER.split(',').count());
+        assert_eq!(row[1..3], ["btc", "1767726000"]);
+        assert_eq!(row[4..8], ["97010.25", "1767726700123", "97000.5", ""]);
+        assert_eq!(row[8..], ["0.55", "0.57", "", "", "", "", "", "0.41"]);
+    }
+}
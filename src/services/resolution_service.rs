@@ -1,25 +1,173 @@
-use crate::adapters::polymarket::PolymarketApi;
-use crate::config::Config;
-use crate::domain::pnl::compute_trade_pnl;
-use crate::models::TradeRecord;
+use crate::adapters::polymarket::ws_rtds::PriceCacheMulti;
+use crate::adapters::polymarket::PolymarketClient;
+use crate::config::{Config, ResolutionSource};
+use crate::domain::window::PeriodPair;
+use crate::domain::pnl::{
+    compute_trade_pnl, compute_trade_pnl_by_outcome, infer_outcome, reconcile_with_fills,
+};
+use crate::models::{JournalEvent, OpenOrder, Outcome, TradeHistoryParams, TradeRecord};
+use crate::services::journal::Journal;
+use crate::services::pnl_ledger::PnlLedger;
+use crate::services::skip_list::SkipList;
+use crate::utils::slug_builder::updown_series;
 use anyhow::Result;
-use log::{info, warn};
+use log::{debug, info, warn};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
 use std::sync::Arc;
-use tokio::sync::RwLock;
 use tokio::time::{sleep, Duration};
 
-const RESOLUTION_INITIAL_DELAY_SECS: u64 = 60;
+const PROVISIONAL_CLOSE_WAIT_SECS: u64 = 15;
 
+/// Provisional PnL preview right after the close: winner inferred from the captured price-to-beat
+/// vs the Chainlink close tick (the next period's open in the RTDS caches). Official resolution
+/// still drives cumulative PnL and redemption.
+pub async fn log_provisional_pnl(
+    trades: &[TradeRecord],
+    periods: PeriodPair,
+    price_cache_15: PriceCacheMulti,
+    price_cache_5: PriceCacheMulti,
+) -> Option<Decimal> {
+    let first = trades.first()?;
+    let symbol = first.symbol.to_lowercase();
+    let Some((open_15, close_15, open_5, close_5)) =
+        captured_open_close(first, periods, &price_cache_15, &price_cache_5, PROVISIONAL_CLOSE_WAIT_SECS).await
+    else {
+        info!(
+            "{} provisional: Chainlink open/close not captured; waiting for official resolution.",
+            symbol.to_uppercase()
+        );
+        return None;
+    };
+
+    let outcome_15 = infer_outcome(open_15, close_15);
+    let outcome_5 = infer_outcome(open_5, close_5);
+    let mut provisional_pnl = Decimal::ZERO;
+    for trade in trades {
+        provisional_pnl += compute_trade_pnl_by_outcome(trade, outcome_15, outcome_5).pnl;
+    }
+    info!(
+        "{} provisional: {} {} ({:.4} -> {:.4}), {} {} ({:.4} -> {:.4}) | {} trade(s), provisional PnL={:.2}",
+        symbol.to_uppercase(),
+        periods.long_label(),
+        outcome_15,
+        open_15,
+        close_15,
+        periods.short_label(),
+        outcome_5,
+        open_5,
+        close_5,
+        trades.len(),
+        provisional_pnl
+    );
+    Some(provisional_pnl)
+}
+
+/// Chainlink price to beat and close of both markets from the RTDS caches (the close is the next period's
+/// open), waiting up to `wait_secs` for the close ticks: (open_15, close_15, open_5, close_5).
+async fn captured_open_close(
+    first: &TradeRecord,
+    periods: PeriodPair,
+    price_cache_15: &PriceCacheMulti,
+    price_cache_5: &PriceCacheMulti,
+    wait_secs: u64,
+) -> Option<(f64, f64, f64, f64)> {
+    let symbol = first.symbol.to_lowercase();
+    let close_15_period = first.period_15 + periods.long_secs();
+    let close_5_period = first.period_5 + periods.short_secs();
+    let started = std::time::Instant::now();
+    loop {
+        let (o15, c15) = {
+            let c = price_cache_15.read().await;
+            let m = c.get(&symbol);
+            (
+                m.and_then(|m| m.get(&first.period_15).copied()),
+                m.and_then(|m| m.get(&close_15_period).copied()),
+            )
+        };
+        let (o5, c5) = {
+            let c = price_cache_5.read().await;
+            let m = c.get(&symbol);
+            (
+                m.and_then(|m| m.get(&first.period_5).copied()),
+                m.and_then(|m| m.get(&close_5_period).copied()),
+            )
+        };
+        match (o15, c15, o5, c5) {
+            (Some(a), Some(b), Some(c), Some(d)) => return Some((a, b, c, d)),
+            _ if started.elapsed().as_secs() >= wait_secs => {
+                debug!(
+                    "{} Chainlink open/close not captured ({} {:?}/{:?}, {} {:?}/{:?})",
+                    symbol.to_uppercase(),
+                    periods.long_label(),
+                    o15,
+                    c15,
+                    periods.short_label(),
+                    o5,
+                    c5
+                );
+                return None;
+            }
+            _ => sleep(Duration::from_secs(1)).await,
+        }
+    }
+}
+
+/// Simulation mode: settle the period's paper trades against the winners implied by the captured Chainlink
+/// closes, through the same PnL pipeline as a real resolution. None when the closes weren't captured
+/// within `resolution_max_wait_secs`.
+pub async fn resolve_simulated(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    trades: &[TradeRecord],
+    price_cache_15: &PriceCacheMulti,
+    price_cache_5: &PriceCacheMulti,
+    cumulative_pnl: Arc<PnlLedger>,
+    journal: &Journal,
+) -> Option<(Vec<(String, String)>, Decimal)> {
+    let first = trades.first()?;
+    let periods = config.strategy.periods;
+    let wait_secs = config.strategy.resolution_max_wait_secs;
+    let (open_15, close_15, open_5, close_5) =
+        captured_open_close(first, periods, price_cache_15, price_cache_5, wait_secs).await?;
+    let outcome_15 = infer_outcome(open_15, close_15);
+    let outcome_5 = infer_outcome(open_5, close_5);
+    let resolved_15 = simulated_resolution(trades, outcome_15, true);
+    let resolved_5 = simulated_resolution(trades, outcome_5, false);
+    Some(settle_resolved(api, config, trades, &resolved_15, &resolved_5, cumulative_pnl, journal).await)
+}
+
+/// The winning token of the long (or short) market is whichever leg token some trade bought there on the
+/// winning side; empty when no trade holds the winner, which pays nothing either way.
+fn simulated_resolution(trades: &[TradeRecord], outcome: Outcome, long: bool) -> MarketResolution {
+    let token_id = trades
+        .iter()
+        .flat_map(|t| [(&t.leg1_token, &t.leg1_outcome, true), (&t.leg2_token, &t.leg2_outcome, t.leg2_long())])
+        .find(|(_, leg_outcome, on_long)| *on_long == long && outcome.is(leg_outcome))
+        .map(|(token, _, _)| token.to_string())
+        .unwrap_or_default();
+    MarketResolution {
+        token_id,
+        outcome: outcome.as_str().to_string(),
+        source: ResolutionSource::Chainlink,
+    }
+}
+
+/// Poll the period's two markets until both resolve, then settle its trades. None when either is still
+/// unresolved after `resolution_max_wait_secs`.
 pub async fn resolve_and_compute_pnl(
-    api: Arc<PolymarketApi>,
+    api: Arc<dyn PolymarketClient>,
     config: &Config,
     trades: &[TradeRecord],
-    cumulative_pnl: Arc<RwLock<f64>>,
-) -> Result<(Vec<(String, String)>, f64)> {
+    cumulative_pnl: Arc<PnlLedger>,
+    journal: &Journal,
+    skip_list: &SkipList,
+) -> Result<Option<(Vec<(String, String)>, Decimal)>> {
     if trades.is_empty() {
-        return Ok((Vec::new(), 0.0));
+        return Ok(Some((Vec::new(), Decimal::ZERO)));
     }
 
+    let initial_delay = config.strategy.resolution_initial_delay_secs;
     let poll_interval = config.strategy.resolution_poll_interval_secs;
     let max_wait = config.strategy.resolution_max_wait_secs;
     let first = trades.first().expect("non-empty trades");
@@ -27,100 +175,131 @@ pub async fn resolve_and_compute_pnl(
     let cid_5 = &first.cid_5;
     info!(
         "Resolution: waiting {}s, then polling every {}s (max {}s) for {} trade(s).",
-        RESOLUTION_INITIAL_DELAY_SECS,
+        initial_delay,
         poll_interval,
         max_wait,
         trades.len()
     );
-    sleep(Duration::from_secs(RESOLUTION_INITIAL_DELAY_SECS)).await;
+    sleep(Duration::from_secs(initial_delay)).await;
 
+    let sources = resolution_sources(config);
+    let cross_check = config.strategy.resolution_onchain_cross_check;
     let started = std::time::Instant::now();
-    let mut m15_resolved = None;
-    let mut m5_resolved = None;
+    let mut resolved_15 = None;
+    let mut resolved_5 = None;
     while started.elapsed().as_secs() < max_wait {
-        let m15 = api.get_market(cid_15).await.ok();
-        let m5 = api.get_market(cid_5).await.ok();
-        let (closed_15, winner_15) = m15
-            .as_ref()
-            .map(|m| {
-                (
-                    m.closed,
-                    m.tokens
-                        .iter()
-                        .find(|t| t.winner)
-                        .map(|t| (t.token_id.as_str(), t.outcome.as_str())),
-                )
-            })
-            .unwrap_or((false, None));
-        let (closed_5, winner_5) = m5
-            .as_ref()
-            .map(|m| {
-                (
-                    m.closed,
-                    m.tokens
-                        .iter()
-                        .find(|t| t.winner)
-                        .map(|t| (t.token_id.as_str(), t.outcome.as_str())),
-                )
-            })
-            .unwrap_or((false, None));
-
-        if closed_15 && closed_5 && winner_15.is_some() && winner_5.is_some() {
-            m15_resolved = m15;
-            m5_resolved = m5;
+        if resolved_15.is_none() {
+            resolved_15 = resolve_market(api.as_ref(), &sources, cross_check, cid_15).await;
+        }
+        if resolved_5.is_none() {
+            resolved_5 = resolve_market(api.as_ref(), &sources, cross_check, cid_5).await;
+        }
+        if resolved_15.is_some() && resolved_5.is_some() {
             break;
         }
         sleep(Duration::from_secs(poll_interval)).await;
     }
 
-    let (winner_15, winner_5) = match (m15_resolved.as_ref(), m5_resolved.as_ref()) {
-        (Some(m15), Some(m5)) => (
-            m15.tokens
-                .iter()
-                .find(|t| t.winner)
-                .map(|t| (t.token_id.as_str(), t.outcome.as_str())),
-            m5.tokens
-                .iter()
-                .find(|t| t.winner)
-                .map(|t| (t.token_id.as_str(), t.outcome.as_str())),
-        ),
-        _ => {
-            warn!(
-                "Resolution timeout for {} trades (cid_15={}, cid_5={}).",
-                trades.len(),
-                cid_15,
-                cid_5
-            );
-            return Ok((Vec::new(), 0.0));
+    let unresolved = [(cid_15, resolved_15.is_none()), (cid_5, resolved_5.is_none())];
+    let (Some(resolved_15), Some(resolved_5)) = (resolved_15, resolved_5) else {
+        warn!(
+            "Resolution timeout for {} trades (cid_15={}, cid_5={}).",
+            trades.len(),
+            cid_15,
+            cid_5
+        );
+        // Markets stuck unresolved (usually disputed) count towards their series on the skip-list.
+        let periods = config.strategy.periods;
+        let series = [periods.long_period_mins, periods.short_period_mins].map(|m| updown_series(&first.symbol, m));
+        for ((cid, stuck), series) in unresolved.into_iter().zip(series) {
+            if stuck {
+                skip_list.record_anomaly(&series, &format!("{} unresolved after {}s", cid, max_wait));
+            }
         }
+        return Ok(None);
     };
+    Ok(Some(
+        settle_resolved(api.as_ref(), config, trades, &resolved_15, &resolved_5, cumulative_pnl, journal).await,
+    ))
+}
 
-    let (win_token_15, win_token_5, outcome_15, outcome_5) = match (winner_15, winner_5) {
-        (Some((t15, o15)), Some((t5, o5))) => (t15, t5, o15, o5),
-        _ => return Ok((Vec::new(), 0.0)),
-    };
+/// One resolution attempt for trades whose market outlived `resolution_max_wait_secs` (see
+/// `pending_resolution`): None while either market is still unresolved, else the same settlement as
+/// `resolve_and_compute_pnl`.
+pub async fn resolve_pending_once(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    trades: &[TradeRecord],
+    cumulative_pnl: Arc<PnlLedger>,
+    journal: &Journal,
+) -> Option<(Vec<(String, String)>, Decimal)> {
+    let first = trades.first()?;
+    let sources = resolution_sources(config);
+    let cross_check = config.strategy.resolution_onchain_cross_check;
+    let resolved_15 = resolve_market(api, &sources, cross_check, &first.cid_15).await?;
+    let resolved_5 = resolve_market(api, &sources, cross_check, &first.cid_5).await?;
+    Some(settle_resolved(api, config, trades, &resolved_15, &resolved_5, cumulative_pnl, journal).await)
+}
 
-    let mut period_pnl = 0.0f64;
+/// Journal each trade's PnL against the winners, reconcile with fills, and add the period to the cumulative
+/// PnL; returns the redeem targets and the period PnL.
+async fn settle_resolved(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    trades: &[TradeRecord],
+    resolved_15: &MarketResolution,
+    resolved_5: &MarketResolution,
+    cumulative_pnl: Arc<PnlLedger>,
+    journal: &Journal,
+) -> (Vec<(String, String)>, Decimal) {
+    let periods = config.strategy.periods;
+    let (long_label, short_label) = (periods.long_label(), periods.short_label());
+    info!(
+        "Resolution: {} {} via {}, {} {} via {}",
+        long_label,
+        resolved_15.outcome,
+        resolved_15.source.as_str(),
+        short_label,
+        resolved_5.outcome,
+        resolved_5.source.as_str()
+    );
+    let (win_token_15, win_token_5) = (resolved_15.token_id.as_str(), resolved_5.token_id.as_str());
+    let (outcome_15, outcome_5) = (resolved_15.outcome.as_str(), resolved_5.outcome.as_str());
+
+    let mut period_pnl = Decimal::ZERO;
     let mut redeem_targets: Vec<(String, String)> = Vec::new();
 
     for trade in trades {
         let sym = trade.symbol.to_uppercase();
         let pnl_result = compute_trade_pnl(trade, win_token_15, win_token_5);
         period_pnl += pnl_result.pnl;
+        journal.record(
+            Some(&trade.trade_id),
+            JournalEvent::Resolved {
+                outcome_15: outcome_15.to_string(),
+                outcome_5: outcome_5.to_string(),
+                pnl: pnl_result.pnl.to_f64().unwrap_or(0.0),
+                source_15: Some(resolved_15.source.as_str().to_string()),
+                source_5: Some(resolved_5.source.as_str().to_string()),
+            },
+        );
 
         let result_msg = match (pnl_result.won_15m, pnl_result.won_5m) {
-            (true, true) => "Won both legs",
-            (true, false) => "Won 15m leg",
-            (false, true) => "Won 5m leg",
-            (false, false) => "Lost both legs",
+            (true, true) => "Won both legs".to_string(),
+            (true, false) => format!("Won {} leg", long_label),
+            (false, true) => format!("Won {} leg", short_label),
+            (false, false) => "Lost both legs".to_string(),
         };
         info!(
-            "{} resolved: Won 15m {} 5m {} | {} | cost={:.2}, payout={:.2}, PnL={:.2} | period PnL={:.2}",
+            "{} resolved: Won {} {} {} {} | {} | cost={:.2} (fees {:.2}), payout={:.2}, PnL={:.2} | period PnL={:.2}",
             sym,
+            long_label,
             outcome_15,
+            short_label,
             outcome_5,
             result_msg,
             pnl_result.cost,
+            pnl_result.fees,
             pnl_result.payout,
             pnl_result.pnl,
             period_pnl
@@ -144,11 +323,340 @@ pub async fn resolve_and_compute_pnl(
         }
     }
 
-    if period_pnl != 0.0 {
-        let mut cum = cumulative_pnl.write().await;
-        *cum += period_pnl;
-        info!("Period PnL: {:.2} | Cumulative PnL: {:.2}", period_pnl, *cum);
+    if !config.strategy.simulation_mode {
+        if let Some(actual) = reconcile_period_fills(
+            api,
+            config,
+            trades,
+            win_token_15,
+            win_token_5,
+            journal,
+        )
+        .await
+        {
+            period_pnl = actual;
+        }
+    }
+
+    if let (false, Some(first)) = (period_pnl.is_zero(), trades.first()) {
+        let total = cumulative_pnl.record(&first.symbol, period_pnl);
+        info!("Period PnL: {:.2} | Cumulative PnL: {:.2}", period_pnl, total);
+    }
+
+    (redeem_targets, period_pnl)
+}
+
+/// Winning (token_id, outcome) of `condition_id` from the configured resolution sources, None while no
+/// source reports one. For loops that settle a single market, e.g. complete-set windows.
+pub async fn resolved_winner(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    condition_id: &str,
+) -> Option<(String, String)> {
+    let sources = resolution_sources(config);
+    let cross_check = config.strategy.resolution_onchain_cross_check;
+    let resolution = resolve_market(api, &sources, cross_check, condition_id).await?;
+    debug!("{} resolved {} via {}", condition_id, resolution.outcome, resolution.source.as_str());
+    Some((resolution.token_id, resolution.outcome))
+}
+
+/// Winning token of one market, and which source reported it.
+struct MarketResolution {
+    token_id: String,
+    outcome: String,
+    source: ResolutionSource,
+}
+
+/// Configured priority list; an empty list falls back to the default order rather than never resolving.
+fn resolution_sources(config: &Config) -> Vec<ResolutionSource> {
+    if config.strategy.resolution_sources.is_empty() {
+        Config::default().strategy.resolution_sources
+    } else {
+        config.strategy.resolution_sources.clone()
     }
+}
 
-    Ok((redeem_targets, period_pnl))
+/// Walk `sources` in priority order and take the first winner reported. Source errors are logged and
+/// the next source is tried. With `cross_check`, an off-chain winner is checked against the CTF payouts.
+async fn resolve_market(
+    api: &dyn PolymarketClient,
+    sources: &[ResolutionSource],
+    cross_check: bool,
+    condition_id: &str,
+) -> Option<MarketResolution> {
+    let resolution = first_reported_winner(api, sources, condition_id).await?;
+    if !cross_check || resolution.source == ResolutionSource::Onchain {
+        return Some(resolution);
+    }
+    // The payout vector is what redemption pays out on, so it overrides a lagging or wrong API flag.
+    // Before the oracle reports (or if the RPC is down) the off-chain winner stands.
+    match onchain_winner(api, condition_id).await {
+        Ok(Some((token_id, outcome))) if token_id != resolution.token_id => {
+            warn!(
+                "Resolution of {}: {} reports {} but on-chain payouts say {}; using on-chain",
+                condition_id,
+                resolution.source.as_str(),
+                resolution.outcome,
+                outcome
+            );
+            Some(MarketResolution {
+                token_id,
+                outcome,
+                source: ResolutionSource::Onchain,
+            })
+        }
+        Ok(_) => Some(resolution),
+        Err(e) => {
+            debug!("On-chain cross-check failed for {}: {}", condition_id, e);
+            Some(resolution)
+        }
+    }
+}
+
+async fn first_reported_winner(
+    api: &dyn PolymarketClient,
+    sources: &[ResolutionSource],
+    condition_id: &str,
+) -> Option<MarketResolution> {
+    for &source in sources {
+        let winner = match source {
+            ResolutionSource::Clob => api.resolved_winner(condition_id).await,
+            ResolutionSource::Gamma => api.get_gamma_winner(condition_id).await,
+            ResolutionSource::Onchain => onchain_winner(api, condition_id).await,
+            // Never configured; simulation mode resolves from the Chainlink caches in `resolve_simulated`.
+            ResolutionSource::Chainlink => Ok(None),
+        };
+        match winner {
+            Ok(Some((token_id, outcome))) => {
+                return Some(MarketResolution {
+                    token_id,
+                    outcome,
+                    source,
+                })
+            }
+            Ok(None) => {}
+            Err(e) => debug!(
+                "Resolution source {} failed for {}: {}",
+                source.as_str(),
+                condition_id,
+                e
+            ),
+        }
+    }
+    None
+}
+
+/// CTF outcome slots follow the market's token order, so the winning index maps onto the CLOB tokens.
+async fn onchain_winner(api: &dyn PolymarketClient, condition_id: &str) -> Result<Option<(String, String)>> {
+    let Some(index) = api.get_onchain_winner_index(condition_id).await? else {
+        return Ok(None);
+    };
+    let market = api.get_market(condition_id).await?;
+    Ok(market
+        .tokens
+        .get(index)
+        .map(|t| (t.token_id.clone(), t.outcome.clone())))
+}
+
+/// Recompute period PnL from the wallet's actual fills on both markets and flag discrepancies
+/// against the intended order prices/sizes. Returns None when fills are unavailable.
+async fn reconcile_period_fills(
+    api: &dyn PolymarketClient,
+    config: &Config,
+    trades: &[TradeRecord],
+    win_token_15: &str,
+    win_token_5: &str,
+    journal: &Journal,
+) -> Option<Decimal> {
+    let first = trades.first()?;
+    let wallet = api.wallet_address()?;
+    warn_unknown_orders(api, &[&first.cid_15, &first.cid_5], journal, &first.symbol).await;
+    let params = TradeHistoryParams {
+        user: wallet,
+        markets: vec![first.cid_15.clone(), first.cid_5.clone()],
+        ..TradeHistoryParams::default()
+    };
+    let fills = match api.get_trade_history(&params).await {
+        Ok(f) if !f.is_empty() => f,
+        Ok(_) => {
+            warn!(
+                "{} reconciliation: no fills found for cid_15={}, cid_5={}; keeping intended PnL.",
+                first.symbol.to_uppercase(),
+                first.cid_15,
+                first.cid_5
+            );
+            return None;
+        }
+        Err(e) => {
+            warn!("{} reconciliation: fetching fills failed: {}", first.symbol.to_uppercase(), e);
+            return None;
+        }
+    };
+
+    let rec = reconcile_with_fills(
+        trades,
+        &fills,
+        win_token_15,
+        win_token_5,
+        &config.strategy.fees.taker(),
+    );
+    let sym = first.symbol.to_uppercase();
+    for (token, intended, filled) in &rec.size_mismatches {
+        warn!(
+            "{} reconciliation: token {} intended size {:.2} but net filled {:.2}",
+            sym, token, intended, filled
+        );
+    }
+    if (rec.actual_pnl - rec.intended_pnl).abs() >= Decimal::new(1, 2) {
+        warn!(
+            "{} reconciliation: actual PnL {:.2} (cost {:.2}, payout {:.2}) differs from intended {:.2}",
+            sym, rec.actual_pnl, rec.actual_cost, rec.actual_payout, rec.intended_pnl
+        );
+    } else {
+        info!("{} reconciliation: fills match intended PnL {:.2}", sym, rec.actual_pnl);
+    }
+    for trade in trades {
+        journal.record(
+            Some(&trade.trade_id),
+            JournalEvent::FillsReconciled {
+                actual_pnl: rec.actual_pnl.to_f64().unwrap_or(0.0),
+                intended_pnl: rec.intended_pnl.to_f64().unwrap_or(0.0),
+                size_mismatches: rec.size_mismatches.len(),
+            },
+        );
+    }
+    Some(rec.actual_pnl)
+}
+
+/// Resting orders in `markets` the journal has no record of placing (manual orders, another process, or an ack
+/// that was lost), each logged with a warning; they are left alone.
+pub async fn warn_unknown_orders(
+    api: &dyn PolymarketClient,
+    markets: &[&str],
+    journal: &Journal,
+    symbol: &str,
+) -> Vec<OpenOrder> {
+    let placed = journal.placed_order_ids();
+    let mut unknown = Vec::new();
+    for market in markets {
+        match api.get_open_orders(Some(market)).await {
+            Ok(orders) => unknown.extend(orders.into_iter().filter(|o| !placed.contains(&o.id))),
+            Err(e) => warn!(
+                "{} reconciliation: listing resting orders in {} failed: {}",
+                symbol.to_uppercase(),
+                market,
+                e
+            ),
+        }
+    }
+    for o in &unknown {
+        warn!(
+            "{} reconciliation: resting order {} ({} {} {} @ {}) in {} was not placed by this bot",
+            symbol.to_uppercase(),
+            o.id,
+            o.side,
+            o.original_size,
+            o.outcome,
+            o.price,
+            o.market
+        );
+    }
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::polymarket::mock::MockPolymarketApi;
+    use crate::domain::pnl::sample_trade;
+
+    #[test]
+    fn flags_resting_orders_the_journal_does_not_know() {
+        let api = MockPolymarketApi::new();
+        for (id, market) in [("0xours", "0xc15"), ("0xmanual", "0xc5"), ("0xelsewhere", "0xother")] {
+            api.add_open_order(OpenOrder {
+                id: id.to_string(),
+                market: market.to_string(),
+                ..OpenOrder::default()
+            });
+        }
+        let journal = Journal::new("");
+        journal.record(
+            Some("btc-1"),
+            JournalEvent::OrderAcked { leg: 1, order_id: Some("0xours".to_string()), status: "live".to_string() },
+        );
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let unknown = rt.block_on(warn_unknown_orders(&api, &["0xc15", "0xc5"], &journal, "btc"));
+        assert_eq!(unknown.iter().map(|o| o.id.as_str()).collect::<Vec<_>>(), vec!["0xmanual"]);
+    }
+
+    fn resolved_market() -> MockPolymarketApi {
+        let api = MockPolymarketApi::new();
+        api.add_market("btc-up-or-down", "0xcid", &[("up", "Up"), ("down", "Down")]);
+        api.resolve("0xcid", "Up");
+        api
+    }
+
+    #[test]
+    fn onchain_payouts_override_a_disagreeing_clob_winner() {
+        let api = resolved_market();
+        api.set_onchain_winner("0xcid", Some(1));
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let sources = [ResolutionSource::Clob];
+
+        let checked = rt.block_on(resolve_market(&api, &sources, true, "0xcid")).unwrap();
+        assert_eq!((checked.token_id.as_str(), checked.source), ("down", ResolutionSource::Onchain));
+        let unchecked = rt.block_on(resolve_market(&api, &sources, false, "0xcid")).unwrap();
+        assert_eq!((unchecked.token_id.as_str(), unchecked.source), ("up", ResolutionSource::Clob));
+    }
+
+    #[test]
+    fn clob_winner_stands_until_the_oracle_reports() {
+        let api = resolved_market();
+        api.set_onchain_winner("0xcid", None);
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let resolution = rt
+            .block_on(resolve_market(&api, &[ResolutionSource::Clob], true, "0xcid"))
+            .unwrap();
+        assert_eq!((resolution.token_id.as_str(), resolution.source), ("up", ResolutionSource::Clob));
+    }
+
+    #[test]
+    fn simulated_trades_settle_on_captured_chainlink_closes() {
+        use rust_decimal_macros::dec;
+        use std::collections::HashMap;
+        use tokio::sync::RwLock;
+
+        let mut config = Config::default();
+        config.strategy.simulation_mode = true;
+        let periods = config.strategy.periods;
+        let (period_15, period_5) = (1767726000, 1767726000 + periods.long_secs() - periods.short_secs());
+        let trade = TradeRecord {
+            period_15,
+            period_5,
+            ..sample_trade()
+        };
+        // Both markets close above their price to beat: the 15m Up leg pays 10, the 5m Down leg nothing.
+        let cache = |open_at: i64, close_at: i64, open: f64| -> PriceCacheMulti {
+            let ticks = HashMap::from([(open_at, open), (close_at, 101.0)]);
+            Arc::new(RwLock::new(HashMap::from([("btc".to_string(), ticks)])))
+        };
+        let cache_15 = cache(period_15, period_15 + periods.long_secs(), 100.0);
+        let cache_5 = cache(period_5, period_5 + periods.short_secs(), 100.5);
+        let ledger = Arc::new(PnlLedger::load(""));
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let (_, pnl) = rt
+            .block_on(resolve_simulated(
+                &MockPolymarketApi::new(),
+                &config,
+                &[trade],
+                &cache_15,
+                &cache_5,
+                Arc::clone(&ledger),
+                &Journal::new(""),
+            ))
+            .unwrap();
+        assert_eq!(pnl, dec!(0.8));
+        assert_eq!(ledger.total(), dec!(0.8));
+    }
 }
@@ -0,0 +1,337 @@
+//! `init` subcommand: walks a new user through creating config.json on the terminal (wallet type and key, which
+//! is typed in without echo and can be kept in an encrypted keystore or an environment variable instead; symbols,
+//! sizes and risk limits, simulation), runs the startup checks (settings, proxy wallet, CLOB auth, approvals,
+//! balance) before writing it, then offers a simulated $1 order.
+
+use crate::adapters::polymarket::proxy_wallet::{expected_funder, verify_proxy_wallet};
+use crate::adapters::polymarket::PolymarketApi;
+use crate::config::{Config, Network, ENV_KEYSTORE_PASSWORD, ENV_PRIVATE_KEY};
+use crate::models::BalanceAsset;
+use crate::services::discovery_service::MarketDiscovery;
+use crate::utils::clock::SystemClock;
+use crate::utils::secret::{with_exposed, Secret};
+use crate::utils::time_windows::period_start_et_unix;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{Context, Result};
+use rust_decimal::prelude::ToPrimitive;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+const KNOWN_SYMBOLS: [&str; 4] = ["btc", "eth", "sol", "xrp"];
+const TEST_ORDER_USD: f64 = 1.0;
+
+pub async fn run(path: &Path) -> Result<()> {
+    println!("Polymarket arbitrage bot setup. Press Enter to accept the [default].\n");
+    let mut config = if path.exists() {
+        if !confirm(&format!("{} exists. Edit it (no = start from defaults)?", path.display()), true)? {
+            Config::default()
+        } else {
+            let content = std::fs::read_to_string(path)?;
+            serde_json::from_str(&content).context(format!("Failed to parse {}", path.display()))?
+        }
+    } else {
+        Config::default()
+    };
+
+    let current = if config.polymarket.network == Network::Amoy { "amoy" } else { "polygon" };
+    config.polymarket.network = loop {
+        match prompt("Network (polygon / amoy)", current)?.to_lowercase().as_str() {
+            "polygon" => break Network::Polygon,
+            "amoy" => break Network::Amoy,
+            other => println!("Unknown network {:?}", other),
+        }
+    };
+
+    let key = setup_wallet(&mut config).await?;
+    setup_strategy(&mut config)?;
+
+    // The checks sign with the key however it is stored; the file only gets what was chosen for it.
+    let mut checked = config.clone();
+    checked.polymarket.private_key = key;
+    if !run_checks(&checked).await && !confirm("\nSome checks failed. Write the config anyway?", false)? {
+        anyhow::bail!("Nothing written; fix the failing checks and run `init` again.");
+    }
+    std::fs::write(path, with_exposed(|| serde_json::to_string_pretty(&config))?)
+        .context(format!("Failed to write {}", path.display()))?;
+    println!("\nWrote {}", path.display());
+
+    if confirm("Simulate a $1 test order on the current BTC 15m market?", true)? {
+        if let Err(e) = simulate_test_order(&checked).await {
+            println!("❌ Test order: {}", e);
+        }
+    }
+    println!("\nDone. Start the bot with: polymarket-arbitrage-bot -c {}", path.display());
+    Ok(())
+}
+
+/// Private key (pasted, from a file, an encrypted keystore, or the environment), wallet type, and the matching
+/// proxy / Safe wallet. Returns the key, which is only written to the config when pasted or read from a file.
+async fn setup_wallet(config: &mut Config) -> Result<Option<Secret>> {
+    println!("Private key: paste it (not echoed), read it from a file, keep it in an encrypted keystore,");
+    println!("or set {} when starting the bot. none = monitor only.", ENV_PRIVATE_KEY);
+    let default_source = if std::env::var(ENV_PRIVATE_KEY).is_ok() { "env" } else { "paste" };
+    config.polymarket.private_key = None;
+    config.polymarket.keystore_path = None;
+    let key = loop {
+        match prompt("Private key (paste / file / keystore / env / none)", default_source)?.to_lowercase().as_str() {
+            "paste" => {
+                let key = rpassword::prompt_password("Private key (hex): ")?.trim().to_string();
+                config.polymarket.private_key = Some(Secret::new(key.clone()));
+                break key;
+            }
+            "file" => {
+                let path = prompt("Key file", "")?;
+                let key = std::fs::read_to_string(&path)
+                    .context(format!("Failed to read {}", path))?
+                    .trim()
+                    .to_string();
+                config.polymarket.private_key = Some(Secret::new(key.clone()));
+                break key;
+            }
+            "keystore" => break setup_keystore(config)?,
+            "env" => match std::env::var(ENV_PRIVATE_KEY) {
+                Ok(key) => break key.trim().to_string(),
+                Err(_) => println!("{} is not set", ENV_PRIVATE_KEY),
+            },
+            "none" => return Ok(None),
+            other => println!("Unknown key source {:?}", other),
+        }
+    };
+    let signer = PrivateKeySigner::from_str(&key).context("Not a valid private key")?;
+    let eoa = signer.address();
+    println!("EOA: {}", eoa);
+    let key = Some(Secret::new(key));
+
+    // Derived from the network's factories; a wallet type without one is entered by hand.
+    let api = PolymarketApi::from_config(&config.polymarket);
+    let contracts = config.polymarket.contracts();
+    let proxy = expected_funder(eoa, &contracts, Some(1))?.map(|a| a.to_string());
+    let safe = expected_funder(eoa, &contracts, Some(2))?.map(|a| a.to_string());
+    let mut detected = None;
+    for (wallet, wallet_type) in [(&safe, 2u8), (&proxy, 1)] {
+        let Some(wallet) = wallet else {
+            continue;
+        };
+        if api.is_contract(wallet).await.unwrap_or(false) {
+            detected = Some((wallet.clone(), wallet_type));
+            break;
+        }
+    }
+    let default_type = match &detected {
+        Some((addr, sig_type)) => {
+            let kind = if *sig_type == 2 { "Gnosis Safe (browser wallet)" } else { "Proxy (email / MagicLink)" };
+            println!("Detected {} wallet {}", kind, addr);
+            if *sig_type == 2 { "safe" } else { "proxy" }
+        }
+        None => {
+            println!("No deployed Polymarket proxy or Safe found for this key.");
+            "eoa"
+        }
+    };
+    let sig_type = loop {
+        match prompt("Wallet type (eoa / proxy / safe)", default_type)?.to_lowercase().as_str() {
+            "eoa" => break 0u8,
+            "proxy" => break 1,
+            "safe" => break 2,
+            other => println!("Unknown wallet type {:?}", other),
+        }
+    };
+    config.polymarket.signature_type = Some(sig_type);
+    if sig_type == 0 {
+        config.polymarket.proxy_wallet_address = None;
+        return Ok(key);
+    }
+    let default_wallet = match &detected {
+        Some((addr, detected_type)) if *detected_type == sig_type => addr.clone(),
+        _ if sig_type == 2 => safe.unwrap_or_default(),
+        _ => proxy.unwrap_or_default(),
+    };
+    let wallet = prompt("Funder wallet (proxy / Safe address)", &default_wallet)?;
+    if wallet.is_empty() {
+        anyhow::bail!("A proxy or Safe wallet type needs the wallet address");
+    }
+    config.polymarket.proxy_wallet_address = Some(wallet);
+    Ok(key)
+}
+
+/// Unlock an existing keystore, or encrypt a pasted key into a new one; sets `keystore_path` and returns the key.
+fn setup_keystore(config: &mut Config) -> Result<String> {
+    let path = prompt("Keystore file", "keystore.json")?;
+    let file = Path::new(&path);
+    let signer = if file.exists() {
+        let password = rpassword::prompt_password("Keystore password: ")?;
+        PrivateKeySigner::decrypt_keystore(file, password).context(format!("Failed to unlock {}", path))?
+    } else {
+        let key = rpassword::prompt_password("Private key (hex): ")?;
+        let signer = PrivateKeySigner::from_str(key.trim()).context("Not a valid private key")?;
+        let password = rpassword::prompt_password("New keystore password: ")?;
+        if rpassword::prompt_password("Repeat the password: ")? != password {
+            anyhow::bail!("The passwords don't match");
+        }
+        let dir = file.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let name = file.file_name().and_then(|n| n.to_str()).context("The keystore path needs a file name")?;
+        PrivateKeySigner::encrypt_keystore(dir, &mut rand::thread_rng(), signer.to_bytes(), password, Some(name))
+            .context(format!("Failed to write {}", path))?;
+        println!("Wrote {}; start the bot with its password in {} or type it in.", path, ENV_KEYSTORE_PASSWORD);
+        signer
+    };
+    config.polymarket.keystore_path = Some(path);
+    Ok(signer.to_bytes().to_string())
+}
+
+fn setup_strategy(config: &mut Config) -> Result<()> {
+    let strategy = &mut config.strategy;
+    let current = strategy.symbols.join(",");
+    let symbols = prompt(&format!("Symbols ({})", KNOWN_SYMBOLS.join(", ")), &current)?;
+    let symbols: Vec<String> = symbols
+        .split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| KNOWN_SYMBOLS.contains(&s.as_str()))
+        .collect();
+    if !symbols.is_empty() {
+        strategy.symbols = symbols;
+    }
+    strategy.arb_shares = prompt("Shares per leg", &strategy.arb_shares)?;
+    let threshold = prompt("sum_threshold (max combined ask)", &strategy.sum_threshold.to_string())?;
+    strategy.sum_threshold = threshold.parse().context("sum_threshold must be a number")?;
+    let cooldown = prompt("Seconds between arbs per symbol", &strategy.trade_interval_secs.to_string())?;
+    strategy.trade_interval_secs = cooldown.parse().context("the cooldown must be a whole number of seconds")?;
+    let max_trades = prompt(
+        "Most arbs per symbol and period (0 = no limit)",
+        &strategy.max_trades_per_period.to_string(),
+    )?;
+    strategy.max_trades_per_period = max_trades.parse().context("the arb limit must be a whole number")?;
+    let notional = prompt(
+        "Most USDC per symbol and period (0 = no limit)",
+        &strategy.sizing.max_notional_per_period.to_string(),
+    )?;
+    strategy.sizing.max_notional_per_period = notional.parse().context("the USDC limit must be a number")?;
+    strategy.simulation_mode = confirm("Start in simulation mode (no real orders)?", true)?;
+    Ok(())
+}
+
+/// The checks `main` runs at startup, reported one per line instead of aborting. True when all passed.
+async fn run_checks(config: &Config) -> bool {
+    println!("\nChecks:");
+    let mut ok = true;
+    let mut fail = |what: String| {
+        println!("❌ {}", what);
+        ok = false;
+    };
+    let issues = config.strategy.dangerous_settings();
+    if issues.is_empty() {
+        println!("✅ Settings");
+    }
+    for issue in issues {
+        fail(format!("Settings: {}", issue));
+    }
+    let Some(private_key) = config.polymarket.private_key.as_ref().map(Secret::expose) else {
+        println!("⚠️ No private key: monitor only");
+        return ok;
+    };
+    match verify_proxy_wallet(
+        private_key,
+        &config.polymarket.contracts(),
+        config.polymarket.proxy_wallet_address.as_deref(),
+        config.polymarket.signature_type,
+    ) {
+        Ok(()) => println!("✅ Proxy wallet matches the key"),
+        Err(e) => fail(format!("Proxy wallet: {}", e)),
+    }
+    let api = PolymarketApi::from_config(&config.polymarket);
+    match api.authenticate().await {
+        Ok(()) => println!("✅ CLOB authentication"),
+        Err(e) => fail(format!("CLOB authentication: {}", e)),
+    }
+    match api.missing_approvals().await {
+        Ok(missing) if missing.is_empty() => println!("✅ USDC allowance and CTF approval"),
+        Ok(missing) => {
+            for (_, _, what) in missing {
+                fail(format!("Missing {} (run the `approve` subcommand)", what));
+            }
+        }
+        Err(e) => fail(format!("Approval check: {}", e)),
+    }
+    match api.get_balance_allowance(BalanceAsset::Collateral, None).await {
+        Ok(c) if c.spendable() > 0.0 => {
+            println!("✅ Collateral: {:.2} USDC, {:.2} spendable", c.balance, c.spendable())
+        }
+        // Paper trading needs no funds.
+        Ok(c) if config.strategy.simulation_mode => {
+            println!("⚠️ Collateral: {:.2} USDC, nothing spendable", c.balance)
+        }
+        Ok(c) => fail(format!("Collateral: {:.2} USDC, nothing spendable (deposit or run `approve`)", c.balance)),
+        Err(e) => fail(format!("Collateral check: {}", e)),
+    }
+    ok
+}
+
+/// Price a $1 buy of the current BTC 15m Up token at the best ask and print it; nothing is sent.
+async fn simulate_test_order(config: &Config) -> Result<()> {
+    let api = Arc::new(PolymarketApi::from_config(&config.polymarket));
+    let discovery = MarketDiscovery::new(api.clone());
+    let (condition_id, _) = discovery
+        .get_updown_market("btc", 15, period_start_et_unix(&SystemClock, 15))
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no active BTC 15m market right now"))?;
+    let (up_token, _) = discovery.get_market_tokens(&condition_id).await?;
+    let ask = api
+        .get_best_price(&up_token)
+        .await?
+        .and_then(|p| p.ask)
+        .and_then(|a| a.to_f64())
+        .ok_or_else(|| anyhow::anyhow!("no ask on the Up token"))?;
+    let size = (TEST_ORDER_USD / ask * 100.0).floor() / 100.0;
+    println!(
+        "✅ [SIM] BUY {:.2} Up @ {:.2} (${:.2}) on {} — not sent",
+        size,
+        ask,
+        size * ask,
+        condition_id
+    );
+    Ok(())
+}
+
+fn prompt(label: &str, default: &str) -> Result<String> {
+    if default.is_empty() {
+        print!("{}: ", label);
+    } else {
+        print!("{} [{}]: ", label, default);
+    }
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    std::io::stdin().lock().read_line(&mut line)?;
+    let line = line.trim();
+    Ok(if line.is_empty() { default.to_string() } else { line.to_string() })
+}
+
+fn confirm(label: &str, default: bool) -> Result<bool> {
+    let answer = prompt(&format!("{} (y/n)", label), if default { "y" } else { "n" })?;
+    Ok(answer.to_lowercase().starts_with('y'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_failing_check_holds_the_config_back() {
+        assert!(run_checks(&Config::default()).await, "monitor only with safe settings passes");
+
+        let mut losing = Config::default();
+        losing.strategy.sum_threshold = 1.0;
+        assert!(!run_checks(&losing).await);
+
+        // A funder that isn't the key's proxy wallet, on a network that can't be reached.
+        let mut config = Config::default();
+        config.polymarket.private_key =
+            Some(Secret::new("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318"));
+        config.polymarket.proxy_wallet_address = Some("0x0000000000000000000000000000000000000abc".into());
+        config.polymarket.signature_type = Some(1);
+        config.polymarket.clob_api_url = "http://127.0.0.1:9".into();
+        config.polymarket.rpc_url = vec!["http://127.0.0.1:9".into()];
+        assert!(!run_checks(&config).await);
+    }
+}
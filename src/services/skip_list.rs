@@ -0,0 +1,189 @@
+//! Persistent skip-list of markets the bot must not trade, keyed by condition id, slug, or series
+//! ("btc-updown-15m", every market of that symbol and period). Entries are added by hand (`skip add`) or, for a
+//! series, automatically once its markets have caused `skip_after_anomalies` anomalies (e.g. periods that never
+//! resolved within `resolution_max_wait_secs`, as happens during disputes). The file is re-read before every
+//! lookup and change, so edits by the `skip` subcommand reach a running bot and aren't overwritten by it.
+
+use crate::utils::json_store::{load_or_default, persist, read_json, store_path};
+use chrono::Utc;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedMarket {
+    /// Condition id, market slug, or series.
+    pub key: String,
+    pub reason: String,
+    pub added_unix: i64,
+    /// Added after repeated anomalies rather than by hand.
+    #[serde(default)]
+    pub automatic: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SkipListFile {
+    #[serde(default)]
+    pub skipped: Vec<SkippedMarket>,
+    /// Anomalies seen per series that isn't skipped yet.
+    #[serde(default)]
+    pub anomalies: HashMap<String, u32>,
+}
+
+pub struct SkipList {
+    path: Option<PathBuf>,
+    anomaly_limit: u32,
+    state: Mutex<SkipListFile>,
+}
+
+impl SkipList {
+    /// Skip-list persisted at `path` (empty keeps it in memory only); `anomaly_limit` 0 disables auto-skipping.
+    pub fn load(path: &str, anomaly_limit: u32) -> Self {
+        let path = store_path(path);
+        let state: SkipListFile = load_or_default(path.as_deref(), "Skip-list");
+        if !state.skipped.is_empty() {
+            info!("Skip-list: {} market(s) will not be traded", state.skipped.len());
+        }
+        Self {
+            path,
+            anomaly_limit,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// The skip entry matching any of `keys` (condition ids, slugs, or series).
+    pub fn find(&self, keys: &[&str]) -> Option<SkippedMarket> {
+        let state = self.current();
+        state
+            .skipped
+            .iter()
+            .find(|m| keys.iter().any(|k| m.key.eq_ignore_ascii_case(k)))
+            .cloned()
+    }
+
+    /// Skip `key` by hand; returns false when it was already listed.
+    pub fn add(&self, key: &str, reason: &str) -> bool {
+        let mut state = self.current();
+        if state.skipped.iter().any(|m| m.key.eq_ignore_ascii_case(key)) {
+            return false;
+        }
+        state.anomalies.remove(key);
+        state.skipped.push(SkippedMarket {
+            key: key.to_string(),
+            reason: reason.to_string(),
+            added_unix: Utc::now().timestamp(),
+            automatic: false,
+        });
+        self.persist(&state);
+        true
+    }
+
+    /// Trade `key` again and forget its anomalies; returns false when it wasn't listed.
+    pub fn remove(&self, key: &str) -> bool {
+        let mut state = self.current();
+        let before = state.skipped.len();
+        state.skipped.retain(|m| !m.key.eq_ignore_ascii_case(key));
+        let removed = state.skipped.len() != before;
+        state.anomalies.remove(key);
+        self.persist(&state);
+        removed
+    }
+
+    pub fn snapshot(&self) -> SkipListFile {
+        self.current().clone()
+    }
+
+    /// Count an anomaly against series `key` (see `updown_series`); skips the series once the limit is reached.
+    pub fn record_anomaly(&self, key: &str, what: &str) {
+        if self.anomaly_limit == 0 {
+            return;
+        }
+        let mut state = self.current();
+        if state.skipped.iter().any(|m| m.key.eq_ignore_ascii_case(key)) {
+            return;
+        }
+        let count = state.anomalies.entry(key.to_string()).or_insert(0);
+        *count += 1;
+        let count = *count;
+        if count >= self.anomaly_limit {
+            warn!(
+                "Skip-list: {} had {} anomalies (latest: {}); no longer trading it",
+                key, count, what
+            );
+            state.anomalies.remove(key);
+            state.skipped.push(SkippedMarket {
+                key: key.to_string(),
+                reason: format!("{} anomalies, latest: {}", count, what),
+                added_unix: Utc::now().timestamp(),
+                automatic: true,
+            });
+        } else {
+            info!("Skip-list: anomaly {}/{} for {}: {}", count, self.anomaly_limit, key, what);
+        }
+        self.persist(&state);
+    }
+
+    /// The state, refreshed from the file when there is one so changes made by another process are kept.
+    fn current(&self) -> MutexGuard<'_, SkipListFile> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(path) = self.path.as_deref().filter(|p| p.exists()) {
+            match read_json(path, "skip-list") {
+                Ok(on_disk) => *state = on_disk,
+                Err(e) => warn!("Skip-list {} unreadable, keeping the loaded list: {:#}", path.display(), e),
+            }
+        }
+        state
+    }
+
+    fn persist(&self, state: &SkipListFile) {
+        persist(self.path.as_deref(), state, "Skip-list");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path.display().to_string()
+    }
+
+    #[test]
+    fn a_series_is_skipped_after_repeated_anomalies_across_periods() {
+        let list = SkipList::load("", 2);
+        // Each period has its own condition ids; the anomalies add up on the series.
+        list.record_anomaly("btc-updown-15m", "0xc15-a unresolved after 600s");
+        assert!(list.find(&["0xc15-b", "btc-updown-15m-1767726900", "btc-updown-15m"]).is_none());
+        list.record_anomaly("btc-updown-15m", "0xc15-b unresolved after 600s");
+        let skipped = list.find(&["0xc15-c", "btc-updown-15m-1767727800", "btc-updown-15m"]).unwrap();
+        assert!(skipped.automatic);
+        assert!(list.find(&["eth-updown-15m"]).is_none());
+        assert!(list.snapshot().anomalies.is_empty());
+    }
+
+    #[test]
+    fn edits_from_another_process_reach_the_running_list() {
+        let path = temp_path("skip-list");
+        let running = SkipList::load(&path, 3);
+        running.record_anomaly("btc-updown-5m", "unresolved");
+
+        // The `skip` subcommand works on its own copy of the file.
+        let cli = SkipList::load(&path, 3);
+        assert!(cli.add("eth-updown-15m-1767726000", "disputed"));
+        assert!(running.find(&["eth-updown-15m-1767726000"]).is_some());
+
+        // The running bot's next write keeps the hand-added entry.
+        running.record_anomaly("btc-updown-5m", "unresolved");
+        let state = SkipList::load(&path, 3).snapshot();
+        assert_eq!(state.skipped.len(), 1);
+        assert_eq!(state.anomalies["btc-updown-5m"], 2);
+
+        assert!(cli.remove("eth-updown-15m-1767726000"));
+        assert!(running.find(&["eth-updown-15m-1767726000"]).is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,179 @@
+//! What the orchestrator trades in each window, behind the `Strategy` trait. The orchestrator owns discovery,
+//! market feeds, order placement, and resolution; a strategy accepts or sits out each window (`setup`), picks
+//! the legs to buy from the live asks (`on_quote`), and sees the outcome (`on_resolution`). `build` is the
+//! registry, keyed by `strategy.kind`.
+
+use crate::config::{Config, StrategyKind};
+use crate::domain::arbitrage::{select_arb_legs, ArbSelection};
+use crate::domain::fees::{effective_price, fee_per_share, LegFees};
+use crate::models::{Outcome, TradeRecord};
+use crate::services::arbitrage_orchestrator::PeriodContext;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+/// Best asks of the window's four tokens, next to the tokens themselves.
+#[derive(Debug, Clone, Copy)]
+pub struct WindowQuotes<'a> {
+    pub ask_15_up: Option<Decimal>,
+    pub ask_15_down: Option<Decimal>,
+    pub ask_5_up: Option<Decimal>,
+    pub ask_5_down: Option<Decimal>,
+    pub t15_up: &'a str,
+    pub t15_down: &'a str,
+    pub t5_up: &'a str,
+    pub t5_down: &'a str,
+}
+
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Called once a window's markets and strikes are known; Err(reason) sits the window out.
+    fn setup(&self, config: &Config, ctx: &PeriodContext) -> Result<(), String>;
+
+    /// Legs to buy at the current asks, if any pair beats `threshold` with `fees`.
+    fn on_quote<'a>(&self, quotes: &WindowQuotes<'a>, threshold: Decimal, fees: &LegFees) -> Option<ArbSelection<'a>>;
+
+    /// The window's trades resolved for `pnl` USDC in total.
+    fn on_resolution(&self, _ctx: &PeriodContext, _trades: &[TradeRecord], _pnl: Decimal) {}
+}
+
+/// The configured strategy.
+pub fn build(kind: StrategyKind) -> Arc<dyn Strategy> {
+    match kind {
+        StrategyKind::Overlap => Arc::new(OverlapArb),
+        StrategyKind::CompleteSet => Arc::new(CompleteSet),
+    }
+}
+
+/// Long vs short period arb: one side of the long market plus the opposite side of the short market that
+/// closes with it, when both share (nearly) the same price-to-beat.
+pub struct OverlapArb;
+
+impl Strategy for OverlapArb {
+    fn name(&self) -> &'static str {
+        "overlap"
+    }
+
+    /// The two strikes must agree within the symbol's price-to-beat tolerance, or the legs don't hedge.
+    fn setup(&self, config: &Config, ctx: &PeriodContext) -> Result<(), String> {
+        let tolerance = config.strategy.price_to_beat_tolerance_at(&ctx.symbol, ctx.strike_15);
+        let gap = (ctx.strike_15 - ctx.strike_5).abs();
+        if gap > tolerance {
+            let periods = config.strategy.periods;
+            return Err(format!(
+                "|{} - {}| price-to-beat = {:.6} > tolerance {:.6} USD",
+                periods.long_label(),
+                periods.short_label(),
+                gap,
+                tolerance
+            ));
+        }
+        Ok(())
+    }
+
+    fn on_quote<'a>(&self, quotes: &WindowQuotes<'a>, threshold: Decimal, fees: &LegFees) -> Option<ArbSelection<'a>> {
+        select_arb_legs(
+            quotes.ask_15_up,
+            quotes.ask_15_down,
+            quotes.ask_5_up,
+            quotes.ask_5_down,
+            threshold,
+            fees,
+            quotes.t15_up,
+            quotes.t15_down,
+            quotes.t5_up,
+            quotes.t5_down,
+        )
+    }
+}
+
+/// One Up plus one Down share of the long market pay exactly 1 at its resolution, whatever the spot does: buy
+/// both when their asks, taker fees included, sum below the threshold. The short market isn't traded.
+pub struct CompleteSet;
+
+impl Strategy for CompleteSet {
+    fn name(&self) -> &'static str {
+        "complete_set"
+    }
+
+    /// Both legs settle on the same market, so the strikes don't have to agree.
+    fn setup(&self, _config: &Config, _ctx: &PeriodContext) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn on_quote<'a>(&self, quotes: &WindowQuotes<'a>, threshold: Decimal, fees: &LegFees) -> Option<ArbSelection<'a>> {
+        let (up, down) = (quotes.ask_15_up?, quotes.ask_15_down?);
+        if effective_price(up, fees.bps_15) + effective_price(down, fees.bps_15) >= threshold {
+            return None;
+        }
+        Some(ArbSelection {
+            leg1_token: quotes.t15_up,
+            leg1_price: up,
+            leg2_token: quotes.t15_down,
+            leg2_price: down,
+            leg1_outcome: Outcome::Up,
+            leg2_outcome: Outcome::Down,
+            leg1_fee: fee_per_share(up, fees.bps_15),
+            leg2_fee: fee_per_share(down, fees.bps_15),
+            leg2_long: true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(strike_15: f64, strike_5: f64) -> PeriodContext {
+        PeriodContext {
+            symbol: "btc".to_string(),
+            period_15: 0,
+            period_5: 0,
+            cid_15: String::new(),
+            cid_5: String::new(),
+            t15_up: String::new(),
+            t15_down: String::new(),
+            t5_up: String::new(),
+            t5_down: String::new(),
+            strike_15,
+            strike_5,
+        }
+    }
+
+    #[test]
+    fn overlap_sits_out_windows_with_diverging_strikes() {
+        let config = Config::default();
+        let overlap = build(StrategyKind::Overlap);
+        assert_eq!(overlap.name(), "overlap");
+        assert!(overlap.setup(&config, &ctx(100_000.0, 100_005.0)).is_ok());
+        assert!(overlap.setup(&config, &ctx(100_000.0, 100_050.0)).is_err());
+    }
+
+    #[test]
+    fn complete_set_buys_both_sides_of_the_long_market() {
+        use rust_decimal_macros::dec;
+        let quotes = |up, down| WindowQuotes {
+            ask_15_up: Some(up),
+            ask_15_down: Some(down),
+            ask_5_up: Some(dec!(0.01)),
+            ask_5_down: Some(dec!(0.01)),
+            t15_up: "15up",
+            t15_down: "15down",
+            t5_up: "5up",
+            t5_down: "5down",
+        };
+        let set = build(StrategyKind::CompleteSet);
+        // Strikes far apart don't matter, and the cheap short market is never touched.
+        assert!(set.setup(&Config::default(), &ctx(100_000.0, 101_000.0)).is_ok());
+        let no_fees = LegFees { bps_15: 0.0, bps_5: 0.0 };
+        let selection = set.on_quote(&quotes(dec!(0.52), dec!(0.46)), dec!(0.99), &no_fees).unwrap();
+        assert_eq!((selection.leg1_token, selection.leg2_token), ("15up", "15down"));
+        assert!(selection.leg2_long);
+        assert_eq!(selection.effective_sum(), dec!(0.98));
+        assert!(set.on_quote(&quotes(dec!(0.53), dec!(0.46)), dec!(0.99), &no_fees).is_none());
+        // 100 bps on both legs uses the long market's rate: 0.9894 clears 0.99 but not 0.985.
+        let fees = LegFees { bps_15: 100.0, bps_5: 1_000.0 };
+        assert!(set.on_quote(&quotes(dec!(0.52), dec!(0.46)), dec!(0.99), &fees).is_some());
+        assert!(set.on_quote(&quotes(dec!(0.52), dec!(0.46)), dec!(0.985), &fees).is_none());
+    }
+}
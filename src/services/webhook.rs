@@ -0,0 +1,205 @@
+//! Webhook sink: POSTs bot events from the `EventBus` to `webhook.url` as JSON, one request per event in order,
+//! retrying transient failures and signing the body when a secret is set.
+//!
+//! Events are filtered as they arrive and only the selected ones are queued for delivery, so a slow endpoint
+//! never makes the bus subscriber lag behind the quote stream. When the queue is full the newest event is
+//! dropped with a warning.
+
+use crate::config::WebhookConfig;
+use crate::events::EventBus;
+use crate::utils::proxy;
+use crate::utils::secret::{redact_url, Secret};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::StreamExt;
+use hmac::{Hmac, Mac};
+use log::{info, warn};
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::sync::{mpsc, watch};
+use tokio::time::{sleep, Duration};
+
+const RETRY_BASE_SECS: u64 = 1;
+/// Selected events waiting for delivery.
+const QUEUE_CAPACITY: usize = 256;
+
+/// Start delivering `events` to the configured webhook; None when no URL is set. Once `shutdown` turns true the
+/// sink stops taking events, delivers the ones queued, and ends.
+pub fn spawn_webhook_sink(
+    config: &WebhookConfig,
+    events: &EventBus,
+    mut shutdown: watch::Receiver<bool>,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let url = config.url.trim();
+    if url.is_empty() {
+        return None;
+    }
+    info!("Webhook: sending {:?} events to {}", config.events, redact_url(url));
+    let config = WebhookConfig {
+        url: url.to_string(),
+        ..config.clone()
+    };
+    let mut stream = Box::pin(events.subscribe());
+    Some(tokio::spawn(async move {
+        let client = match proxy::client_builder()
+            .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+            .build()
+        {
+            Ok(c) => c,
+            Err(e) => {
+                warn!("Webhook disabled, HTTP client failed: {}", e);
+                return;
+            }
+        };
+        let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+        let events = config.events.clone();
+        // Ends once the queue's sender is dropped, with this task or at shutdown, after the events already
+        // queued are delivered.
+        let delivery = tokio::spawn(run_delivery(client, config, rx));
+        loop {
+            let event = tokio::select! {
+                // Events published before the shutdown still go out.
+                biased;
+                event = stream.next() => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                _ = shutdown.wait_for(|stop| *stop) => break,
+            };
+            let body = match serde_json::to_value(&event) {
+                Ok(body) => body,
+                Err(e) => {
+                    warn!("Webhook: event not serializable: {}", e);
+                    continue;
+                }
+            };
+            if !selected(&events, &body) {
+                continue;
+            }
+            if let Err(e) = tx.try_send(body) {
+                let full = matches!(e, mpsc::error::TrySendError::Full(_));
+                let body = e.into_inner();
+                if !full {
+                    break;
+                }
+                warn!("Webhook: queue of {} full, dropped {} event", QUEUE_CAPACITY, event_type(&body));
+            }
+        }
+        drop(tx);
+        let _ = delivery.await;
+    }))
+}
+
+/// Deliver queued events in order until the queue closes.
+async fn run_delivery(client: reqwest::Client, config: WebhookConfig, mut queue: mpsc::Receiver<Value>) {
+    while let Some(body) = queue.recv().await {
+        if let Err(e) = deliver(&client, &config, &body).await {
+            warn!("Webhook: dropped {} event: {}", event_type(&body), e);
+        }
+    }
+}
+
+/// "quote", "opportunity", or the journal event type of a trade event.
+fn event_type(body: &Value) -> &str {
+    let ty = body.get("type").and_then(Value::as_str).unwrap_or_default();
+    if ty == "trade" {
+        return body
+            .pointer("/event/type")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+    }
+    ty
+}
+
+fn selected(events: &[String], body: &Value) -> bool {
+    let ty = event_type(body);
+    events.iter().any(|e| e == "*" || e == ty)
+}
+
+/// `sha256=<hex>` HMAC-SHA256 of "<timestamp>.<body>" under `secret`.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| anyhow::anyhow!("Failed to create HMAC: {}", e))?;
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    Ok(format!("sha256={}", hex::encode(mac.finalize().into_bytes())))
+}
+
+/// POST `body`, retrying network errors, 429, and 5xx up to `max_retries` times with doubling backoff. Other
+/// statuses fail at once.
+async fn deliver(client: &reqwest::Client, config: &WebhookConfig, body: &Value) -> Result<()> {
+    let body = serde_json::to_string(body).context("Serialize webhook body")?;
+    let mut attempt = 0u32;
+    loop {
+        let timestamp = Utc::now().timestamp();
+        let mut request = client
+            .post(&config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(secret) = config.secret.as_ref().map(Secret::expose).filter(|s| !s.is_empty()) {
+            request = request
+                .header("X-Webhook-Timestamp", timestamp.to_string())
+                .header("X-Webhook-Signature", signature(secret, timestamp, &body)?);
+        }
+        let error = match request.send().await {
+            Ok(res) if res.status().is_success() => return Ok(()),
+            Ok(res) => {
+                let status = res.status();
+                if !(status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS) {
+                    anyhow::bail!("webhook returned {}", status);
+                }
+                format!("webhook returned {}", status)
+            }
+            Err(e) => e.to_string(),
+        };
+        if attempt >= config.max_retries {
+            anyhow::bail!("{} (gave up after {} attempt(s))", error, attempt + 1);
+        }
+        let delay = RETRY_BASE_SECS << attempt.min(6);
+        warn!("Webhook delivery failed: {}; retrying in {}s", error, delay);
+        sleep(Duration::from_secs(delay)).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ArbEvent;
+    use crate::models::{JournalEntry, JournalEvent};
+
+    #[test]
+    fn filters_by_journal_event_type() {
+        let resolved = serde_json::to_value(ArbEvent::Trade(JournalEntry {
+            ts_ms: 0,
+            trade_id: Some("btc-1-1".to_string()),
+            event: JournalEvent::Resolved {
+                outcome_15: "Up".to_string(),
+                outcome_5: "Down".to_string(),
+                pnl: 0.5,
+                source_15: None,
+                source_5: None,
+            },
+        }))
+        .unwrap();
+        let quote = serde_json::to_value(ArbEvent::Quote {
+            token_id: "1".to_string(),
+            bid: Some(0.4),
+            ask: Some(0.41),
+            ts_ms: 0,
+        })
+        .unwrap();
+        let defaults = WebhookConfig::default().events;
+        assert_eq!(event_type(&resolved), "resolved");
+        assert!(selected(&defaults, &resolved));
+        assert!(!selected(&defaults, &quote));
+        assert!(selected(&["*".to_string()], &quote));
+    }
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        let sig = signature("secret", 1700000000, "{}").unwrap();
+        assert!(sig.starts_with("sha256="));
+        assert_eq!(sig.len(), "sha256=".len() + 64);
+        assert_ne!(sig, signature("secret", 1700000001, "{}").unwrap());
+    }
+}
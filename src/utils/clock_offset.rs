@@ -0,0 +1,28 @@
+/// Server time from an HTTP `Date` header (RFC 2822, whole seconds), in Unix milliseconds.
+pub fn parse_http_date_ms(date: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc2822(date.trim())
+        .ok()
+        .map(|dt| dt.timestamp_millis())
+}
+
+/// Offset (server − local, ms) from one round trip: the server stamped `server_ms` (truncated to the
+/// second, so its midpoint is used) roughly halfway between `sent_ms` and `received_ms` local time.
+pub fn offset_from_sample(sent_ms: i64, received_ms: i64, server_ms: i64) -> i64 {
+    let local_mid = sent_ms + (received_ms - sent_ms) / 2;
+    server_ms + 500 - local_mid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_uses_round_trip_midpoint() {
+        let server = parse_http_date_ms("Tue, 14 Nov 2023 22:13:20 GMT").unwrap();
+        assert_eq!(server, 1_700_000_000_000);
+        // Local clock 3s behind: request sent at server time .2s, answered at .8s.
+        assert_eq!(offset_from_sample(1_699_999_997_200, 1_699_999_997_800, server), 3_000);
+        assert_eq!(offset_from_sample(1_700_000_000_300, 1_700_000_000_700, server), 0);
+        assert!(parse_http_date_ms("not a date").is_none());
+    }
+}
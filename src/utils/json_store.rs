@@ -0,0 +1,74 @@
+//! Small JSON state files (PnL ledger, skip-list, redeem queue, pending resolutions, canary settings, activity
+//! cursor): loaded with a warning rather than an error when unreadable, and replaced atomically on every write.
+
+use anyhow::{Context, Result};
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A configured state file path; None when empty, which keeps the state in memory only.
+pub fn store_path(path: &str) -> Option<PathBuf> {
+    let path = path.trim();
+    (!path.is_empty()).then(|| PathBuf::from(path))
+}
+
+/// Parse the JSON file at `path`; `what` names it in errors ("redeem queue").
+pub fn read_json<T: DeserializeOwned>(path: &Path, what: &str) -> Result<T> {
+    let data = std::fs::read_to_string(path).context(format!("Failed to read {} {}", what, path.display()))?;
+    serde_json::from_str(&data).context(format!("Failed to parse {}", what))
+}
+
+/// What is stored at `path`; the default when there is no path or file yet, or when the file can't be read.
+pub fn load_or_default<T: DeserializeOwned + Default>(path: Option<&Path>, what: &str) -> T {
+    match path {
+        Some(p) if p.exists() => read_json(p, what).unwrap_or_else(|e| {
+            warn!("{} {} unreadable, starting empty: {}", what, p.display(), e);
+            T::default()
+        }),
+        _ => T::default(),
+    }
+}
+
+/// Write via a temp file so a crash mid-write can't truncate what was there.
+pub fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T, what: &str) -> Result<()> {
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(value)?).context(format!("write {}", what))?;
+    std::fs::rename(&tmp, path).context(format!("replace {}", what))?;
+    Ok(())
+}
+
+/// `write_json` to `path` when there is one, logging a failure: state writes never stop trading.
+pub fn persist<T: Serialize + ?Sized>(path: Option<&Path>, value: &T, what: &str) {
+    let Some(path) = path else {
+        return;
+    };
+    if let Err(e) = write_json(path, value, what) {
+        warn!("{} write to {} failed: {}", what, path.display(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn round_trips_and_falls_back_to_the_default() {
+        let path = std::env::temp_dir().join(format!("json-store-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let empty: BTreeMap<String, u32> = load_or_default(Some(&path), "test store");
+        assert!(empty.is_empty());
+
+        persist(Some(&path), &BTreeMap::from([("btc".to_string(), 2u32)]), "test store");
+        let restored: BTreeMap<String, u32> = load_or_default(Some(&path), "test store");
+        assert_eq!(restored["btc"], 2);
+        assert!(!path.with_extension("tmp").exists());
+
+        std::fs::write(&path, "not json").unwrap();
+        let unreadable: BTreeMap<String, u32> = load_or_default(Some(&path), "test store");
+        assert!(unreadable.is_empty());
+        assert!(store_path("  ").is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+}
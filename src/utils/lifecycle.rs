@@ -0,0 +1,278 @@
+//! Mermaid sequence diagram of one trade's lifecycle (decision -> orders -> fills -> resolution -> redemption).
+
+use crate::domain::latency::describe_latency;
+use crate::models::{JournalEntry, JournalEvent};
+use chrono::{TimeZone, Utc};
+use std::fmt::Write;
+
+const MAX_LABEL_CHARS: usize = 120;
+
+/// Mermaid treats `;` and `#` specially and labels must stay on one line.
+fn label(text: &str) -> String {
+    let cleaned: String = text
+        .chars()
+        .map(|c| match c {
+            '\n' | '\r' => ' ',
+            ';' => ',',
+            '#' => ' ',
+            c => c,
+        })
+        .collect();
+    let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    if cleaned.chars().count() > MAX_LABEL_CHARS {
+        format!("{}...", cleaned.chars().take(MAX_LABEL_CHARS).collect::<String>())
+    } else {
+        cleaned
+    }
+}
+
+fn short(id: &str) -> &str {
+    &id[..id.len().min(12)]
+}
+
+/// Render the lifecycle of `trade_id` as a Mermaid sequence diagram. Redemptions are journaled per
+/// condition, so those matching the trade's markets are included. None when the trade is not found.
+pub fn render_lifecycle_mermaid(entries: &[JournalEntry], trade_id: &str) -> Option<String> {
+    let own: Vec<&JournalEntry> = entries
+        .iter()
+        .filter(|e| e.trade_id.as_deref() == Some(trade_id))
+        .collect();
+    let start_ms = own.iter().map(|e| e.ts_ms).min()?;
+    let cids: Vec<&str> = own
+        .iter()
+        .find_map(|e| match &e.event {
+            JournalEvent::Decision { cid_15, cid_5, .. } => Some(vec![cid_15.as_str(), cid_5.as_str()]),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let mut events: Vec<&JournalEntry> = own;
+    events.extend(entries.iter().filter(|e| {
+        e.trade_id.is_none()
+            && e.ts_ms >= start_ms
+            && match &e.event {
+                JournalEvent::Redeemed { condition_id, .. }
+                | JournalEvent::RedeemFailed { condition_id, .. } => cids.contains(&condition_id.as_str()),
+                _ => false,
+            }
+    }));
+    events.sort_by_key(|e| e.ts_ms);
+
+    let mut out = String::new();
+    let _ = writeln!(out, "sequenceDiagram");
+    let _ = writeln!(out, "    title Trade {}", label(trade_id));
+    let _ = writeln!(out, "    participant Bot");
+    let _ = writeln!(out, "    participant CLOB");
+    let _ = writeln!(out, "    participant Data as Data API");
+    let _ = writeln!(out, "    participant Chain as Polygon");
+
+    let mut prev_ms = start_ms;
+    for e in events {
+        let at = Utc
+            .timestamp_millis_opt(e.ts_ms)
+            .single()
+            .map(|t| t.format("%H:%M:%S%.3f").to_string())
+            .unwrap_or_default();
+        let timing = format!("{} UTC, t+{} ms, Δ{} ms", at, e.ts_ms - start_ms, e.ts_ms - prev_ms);
+        prev_ms = e.ts_ms;
+        let line = match &e.event {
+            JournalEvent::Decision {
+                symbol,
+                leg1_outcome,
+                leg1_price,
+                leg2_outcome,
+                leg2_price,
+                effective_sum,
+                threshold,
+                simulated,
+                ..
+            } => format!(
+                "Note over Bot: {}decision {} 15m {} @ {:.4} + 5m {} @ {:.4} (sum {:.4} < {}) [{}]",
+                if *simulated { "[SIM] " } else { "" },
+                symbol.to_uppercase(),
+                leg1_outcome,
+                leg1_price,
+                leg2_outcome,
+                leg2_price,
+                effective_sum,
+                threshold,
+                timing
+            ),
+            JournalEvent::OrderSubmitted {
+                leg, side, price, size, ..
+            } => format!("Bot->>CLOB: leg {} {} {} @ {} [{}]", leg, side, size, price, timing),
+            JournalEvent::OrderAcked { leg, order_id, status } => format!(
+                "CLOB-->>Bot: leg {} ack {} {} [{}]",
+                leg,
+                short(order_id.as_deref().unwrap_or("-")),
+                status,
+                timing
+            ),
+            JournalEvent::OrderFailed { leg, error } => {
+                format!("CLOB--xBot: leg {} failed: {} [{}]", leg, error, timing)
+            }
+            JournalEvent::OrderLookedUp {
+                leg,
+                client_order_id,
+                found,
+                order_id,
+            } => format!(
+                "Bot->>CLOB: leg {} post unanswered, {} {} [{}]",
+                leg,
+                client_order_id,
+                if *found {
+                    format!("found as {}", short(order_id.as_deref().unwrap_or("a fill")))
+                } else {
+                    "not found, posting again".to_string()
+                },
+                timing
+            ),
+            JournalEvent::OrderCancelled {
+                leg,
+                order_id,
+                size_matched,
+            } => format!(
+                "Bot->>CLOB: leg {} cancel {} unfilled after timeout, matched {} [{}]",
+                leg,
+                short(order_id),
+                size_matched.as_deref().unwrap_or("?"),
+                timing
+            ),
+            JournalEvent::LegFilled { leg, size, price } => {
+                format!("CLOB-->>Bot: leg {} filled {} @ {:.4} [{}]", leg, size, price, timing)
+            }
+            JournalEvent::Aborted { reason } => format!("Note over Bot: aborted: {} [{}]", reason, timing),
+            JournalEvent::PositionClosed { reason, proceeds, pnl } => format!(
+                "Note over Bot,CLOB: closed by {}: proceeds {:.2}, PnL {:.2} [{}]",
+                reason, proceeds, pnl, timing
+            ),
+            JournalEvent::FillsReconciled {
+                actual_pnl,
+                intended_pnl,
+                size_mismatches,
+            } => format!(
+                "Data-->>Bot: fills PnL {:.2} vs intended {:.2}, {} size mismatch(es) [{}]",
+                actual_pnl, intended_pnl, size_mismatches, timing
+            ),
+            JournalEvent::Resolved {
+                outcome_15,
+                outcome_5,
+                pnl,
+                source_15,
+                source_5,
+            } => format!(
+                "Note over Bot,CLOB: resolved 15m {} ({}) / 5m {} ({}), PnL {:.2} [{}]",
+                outcome_15,
+                source_15.as_deref().unwrap_or("?"),
+                outcome_5,
+                source_5.as_deref().unwrap_or("?"),
+                pnl,
+                timing
+            ),
+            JournalEvent::Redeemed {
+                condition_id,
+                outcome,
+                transaction_hash,
+            } => format!(
+                "Chain-->>Bot: redeemed {} {} tx {} [{}]",
+                short(condition_id),
+                outcome,
+                short(transaction_hash.as_deref().unwrap_or("-")),
+                timing
+            ),
+            JournalEvent::RedeemFailed {
+                condition_id,
+                outcome,
+                error,
+            } => format!(
+                "Chain--xBot: redeem {} {} failed: {} [{}]",
+                short(condition_id),
+                outcome,
+                error,
+                timing
+            ),
+            JournalEvent::Latency {
+                quote_at_ms,
+                decided_at_ms,
+                legs,
+            } => format!(
+                "Note over Bot,CLOB: latency {} [{}]",
+                describe_latency(*quote_at_ms, *decided_at_ms, legs),
+                timing
+            ),
+        };
+        let (head, text) = line.split_once(": ").unwrap_or((line.as_str(), ""));
+        let _ = writeln!(out, "    {}: {}", head, label(text));
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(ts_ms: i64, trade_id: Option<&str>, event: JournalEvent) -> JournalEntry {
+        JournalEntry {
+            ts_ms,
+            trade_id: trade_id.map(str::to_string),
+            event,
+        }
+    }
+
+    #[test]
+    fn renders_trade_lifecycle() {
+        let entries = vec![
+            entry(
+                1_000,
+                Some("btc-1-1"),
+                JournalEvent::Decision {
+                    symbol: "btc".into(),
+                    period_15: 1,
+                    period_5: 1,
+                    cid_15: "0xc15".into(),
+                    cid_5: "0xc5".into(),
+                    leg1_outcome: "Up".into(),
+                    leg1_price: 0.48,
+                    leg2_outcome: "Down".into(),
+                    leg2_price: 0.49,
+                    effective_sum: 0.97,
+                    threshold: 0.99,
+                    simulated: false,
+                    model: None,
+                },
+            ),
+            entry(
+                1_020,
+                Some("btc-1-1"),
+                JournalEvent::OrderFailed {
+                    leg: 1,
+                    error: "rejected;\nno balance".into(),
+                },
+            ),
+            entry(
+                5_000,
+                None,
+                JournalEvent::Redeemed {
+                    condition_id: "0xc15".into(),
+                    outcome: "Up".into(),
+                    transaction_hash: None,
+                },
+            ),
+            entry(
+                6_000,
+                None,
+                JournalEvent::Redeemed {
+                    condition_id: "0xother".into(),
+                    outcome: "Up".into(),
+                    transaction_hash: None,
+                },
+            ),
+        ];
+        let out = render_lifecycle_mermaid(&entries, "btc-1-1").expect("trade found");
+        assert!(out.starts_with("sequenceDiagram"));
+        assert!(out.contains("CLOB--xBot: leg 1 failed: rejected, no balance [00:00:01.020 UTC, t+20 ms, Δ20 ms]"));
+        assert!(out.contains("redeemed 0xc15 Up"));
+        assert!(!out.contains("0xother"));
+        assert!(render_lifecycle_mermaid(&entries, "eth-1-1").is_none());
+    }
+}
@@ -1,2 +1,9 @@
+pub mod clock;
+pub mod clock_offset;
+pub mod json_store;
+pub mod lifecycle;
+pub mod proxy;
 pub mod slug_builder;
+pub mod supervisor;
+pub mod secret;
 pub mod time_windows;
@@ -0,0 +1,373 @@
+//! Outbound proxy (`proxy` in the config), process-wide like the WS recorder. Our reqwest clients take it from
+//! `client_builder` and the RPC providers from `rpc_client`. The CLOB SDK builds its own client, which only picks
+//! it up from `HTTPS_PROXY`/`HTTP_PROXY`: `export_env` sets those, and has to run before the tokio runtime (or any
+//! other thread) starts. They carry the proxy credentials, so any child process the bot starts inherits them.
+//! WebSockets tunnel through it with an HTTP `CONNECT` or a SOCKS5 handshake before the WebSocket (and TLS)
+//! handshake.
+
+use crate::config::ProxyConfig;
+use crate::utils::secret::{redact_url, Secret};
+use anyhow::{Context, Result};
+use base64::Engine;
+use log::info;
+use std::sync::OnceLock;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+/// Longest proxy reply to a `CONNECT` we read before giving up on finding its end.
+const MAX_CONNECT_REPLY_BYTES: usize = 8 * 1024;
+
+static PROXY: OnceLock<Installed> = OnceLock::new();
+
+/// The installed proxy, and the same proxy as each reqwest version took it.
+struct Installed {
+    proxy: Proxy,
+    client: reqwest::Proxy,
+    rpc: reqwest_sdk::Proxy,
+}
+
+#[derive(Debug, Clone)]
+struct Proxy {
+    scheme: ProxyScheme,
+    host: String,
+    port: u16,
+    username: Option<String>,
+    password: Option<Secret>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProxyScheme {
+    Http,
+    Socks5,
+}
+
+impl Proxy {
+    fn parse(config: &ProxyConfig) -> Result<Option<Self>> {
+        let raw = config.url.trim();
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        let url = reqwest::Url::parse(raw).context(format!("proxy.url {} is not a URL", redact_url(raw)))?;
+        let scheme = match url.scheme() {
+            "http" => ProxyScheme::Http,
+            "socks5" | "socks5h" => ProxyScheme::Socks5,
+            other => anyhow::bail!("proxy.url: unsupported scheme {:?} (use http, socks5, or socks5h)", other),
+        };
+        let host = url.host_str().context("proxy.url has no host")?.to_string();
+        let port = url.port().unwrap_or(if scheme == ProxyScheme::Http { 80 } else { 1080 });
+        // Credentials in the URL count when the fields are not set.
+        let username = config
+            .username
+            .clone()
+            .or_else(|| Some(url.username().to_string()).filter(|u| !u.is_empty()));
+        let password = config.password.clone().or_else(|| url.password().map(Secret::from));
+        Ok(Some(Self {
+            scheme,
+            host,
+            port,
+            username,
+            password,
+        }))
+    }
+
+    /// The proxy as a URL, with credentials, for reqwest and the proxy environment variables.
+    fn url(&self) -> String {
+        let scheme = match self.scheme {
+            ProxyScheme::Http => "http",
+            ProxyScheme::Socks5 => "socks5h",
+        };
+        let mut url = format!("{}://{}:{}", scheme, self.host, self.port);
+        if let (Some(user), Ok(mut parsed)) = (&self.username, reqwest::Url::parse(&url)) {
+            // The setters percent-encode, so passwords with `@` or `:` survive.
+            let _ = parsed.set_username(user);
+            let _ = parsed.set_password(self.password.as_ref().map(Secret::expose));
+            url = parsed.to_string().trim_end_matches('/').to_string();
+        }
+        url
+    }
+
+    /// The proxy for reqwest clients; fails when reqwest can't use the URL.
+    fn client_proxy(&self) -> Result<reqwest::Proxy> {
+        let url = self.url();
+        reqwest::Proxy::all(&url).context(format!("proxy.url {} is not usable by the HTTP client", redact_url(&url)))
+    }
+
+    /// `client_proxy` for the reqwest version the RPC providers use.
+    fn rpc_proxy(&self) -> Result<reqwest_sdk::Proxy> {
+        let url = self.url();
+        reqwest_sdk::Proxy::all(&url).context(format!("proxy.url {} is not usable by the RPC client", redact_url(&url)))
+    }
+}
+
+/// Use the `proxy` config for every later connection made by our clients (see `export_env` for the CLOB SDK's).
+/// A no-op when no proxy is configured or one is installed already.
+pub fn install(config: &ProxyConfig) -> Result<()> {
+    let Some(proxy) = Proxy::parse(config)? else {
+        return Ok(());
+    };
+    let client = proxy.client_proxy()?;
+    let rpc = proxy.rpc_proxy()?;
+    if PROXY.set(Installed { proxy, client, rpc }).is_err() {
+        return Ok(());
+    }
+    info!("Connecting through proxy {}", redact_url(config.url.trim()));
+    Ok(())
+}
+
+/// Export the installed proxy as `HTTPS_PROXY`/`HTTP_PROXY` (unless already set) for the CLOB SDK's client.
+/// Setting the environment races with any thread reading it, so call this before the runtime starts, as `main`
+/// does.
+pub fn export_env() {
+    let Some(Installed { proxy, .. }) = PROXY.get() else {
+        return;
+    };
+    let url = proxy.url();
+    for var in ["HTTPS_PROXY", "HTTP_PROXY"] {
+        if std::env::var_os(var).is_none() {
+            std::env::set_var(var, &url);
+        }
+    }
+}
+
+/// `reqwest::Client::builder()` routed through the installed proxy, if any.
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+    match PROXY.get() {
+        Some(installed) => builder.proxy(installed.client.clone()),
+        None => builder,
+    }
+}
+
+/// HTTP client for the RPC providers, routed through the installed proxy, if any.
+pub fn rpc_client() -> Result<reqwest_sdk::Client> {
+    let builder = reqwest_sdk::Client::builder();
+    let builder = match PROXY.get() {
+        Some(installed) => builder.proxy(installed.rpc.clone()),
+        None => builder,
+    };
+    builder.build().context("Failed to build the RPC HTTP client")
+}
+
+/// `connect_async`, tunnelled through the installed proxy when there is one.
+pub async fn connect_ws(url: &str) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)> {
+    let Some(Installed { proxy, .. }) = PROXY.get() else {
+        return Ok(connect_async(url).await?);
+    };
+    let target = reqwest::Url::parse(url).context(format!("{} is not a URL", url))?;
+    let host = target.host_str().context("WebSocket URL has no host")?;
+    let port = target.port_or_known_default().unwrap_or(443);
+    let stream = tunnel(proxy, host, port).await?;
+    Ok(tokio_tungstenite::client_async_tls(url, stream).await?)
+}
+
+/// A TCP connection to `host:port` through `proxy`.
+async fn tunnel(proxy: &Proxy, host: &str, port: u16) -> Result<TcpStream> {
+    let mut stream = TcpStream::connect((proxy.host.as_str(), proxy.port))
+        .await
+        .context(format!("Failed to connect to proxy {}:{}", proxy.host, proxy.port))?;
+    match proxy.scheme {
+        ProxyScheme::Http => http_connect(&mut stream, proxy, host, port).await?,
+        ProxyScheme::Socks5 => socks5_connect(&mut stream, proxy, host, port).await?,
+    }
+    Ok(stream)
+}
+
+async fn http_connect(stream: &mut TcpStream, proxy: &Proxy, host: &str, port: u16) -> Result<()> {
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if let Some(user) = &proxy.username {
+        let password = proxy.password.as_ref().map(Secret::expose).unwrap_or_default();
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", credentials));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+    // Read byte by byte so nothing after the reply's blank line (the tunnelled stream) is consumed.
+    let mut reply = Vec::new();
+    while !reply.ends_with(b"\r\n\r\n") {
+        if reply.len() >= MAX_CONNECT_REPLY_BYTES {
+            anyhow::bail!("proxy reply to CONNECT is too long");
+        }
+        let byte = stream.read_u8().await.context("proxy closed the connection during CONNECT")?;
+        reply.push(byte);
+    }
+    let status_line = String::from_utf8_lossy(&reply);
+    let status_line = status_line.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => anyhow::bail!("proxy refused CONNECT {}:{}: {}", host, port, status_line),
+    }
+}
+
+async fn socks5_connect(stream: &mut TcpStream, proxy: &Proxy, host: &str, port: u16) -> Result<()> {
+    const VERSION: u8 = 5;
+    const NO_AUTH: u8 = 0;
+    const USER_PASSWORD: u8 = 2;
+    const CONNECT: u8 = 1;
+    const DOMAIN: u8 = 3;
+
+    let greeting: &[u8] = if proxy.username.is_some() {
+        &[VERSION, 2, NO_AUTH, USER_PASSWORD]
+    } else {
+        &[VERSION, 1, NO_AUTH]
+    };
+    stream.write_all(greeting).await?;
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await.context("SOCKS5 proxy closed the connection")?;
+    match choice {
+        [VERSION, NO_AUTH] => {}
+        [VERSION, USER_PASSWORD] => {
+            let user = proxy.username.as_deref().unwrap_or_default();
+            let password = proxy.password.as_ref().map(Secret::expose).unwrap_or_default();
+            if user.len() > 255 || password.len() > 255 {
+                anyhow::bail!("SOCKS5 username and password are limited to 255 bytes");
+            }
+            let mut auth = vec![1, user.len() as u8];
+            auth.extend_from_slice(user.as_bytes());
+            auth.push(password.len() as u8);
+            auth.extend_from_slice(password.as_bytes());
+            stream.write_all(&auth).await?;
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0 {
+                anyhow::bail!("SOCKS5 proxy rejected the username/password");
+            }
+        }
+        _ => anyhow::bail!("SOCKS5 proxy offers no usable authentication method"),
+    }
+
+    if host.len() > 255 {
+        anyhow::bail!("host name too long for SOCKS5: {}", host);
+    }
+    // The proxy resolves the name.
+    let mut request = vec![VERSION, CONNECT, 0, DOMAIN, host.len() as u8];
+    request.extend_from_slice(host.as_bytes());
+    request.extend_from_slice(&port.to_be_bytes());
+    stream.write_all(&request).await?;
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0 {
+        anyhow::bail!("SOCKS5 proxy refused {}:{} (reply code {})", host, port, reply[1]);
+    }
+    // Skip the bound address and port.
+    let address_len = match reply[3] {
+        1 => 4,
+        4 => 16,
+        DOMAIN => stream.read_u8().await? as usize,
+        other => anyhow::bail!("SOCKS5 reply has unknown address type {}", other),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    fn proxy_at(url: &str, username: Option<&str>, password: Option<&str>) -> Proxy {
+        Proxy::parse(&ProxyConfig {
+            url: url.to_string(),
+            username: username.map(str::to_string),
+            password: password.map(Secret::from),
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn http_connect_sends_credentials_and_leaves_the_tunnel_unread() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = vec![0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket.write_all(b"HTTP/1.1 200 Connection established\r\n\r\nhello").await.unwrap();
+            String::from_utf8_lossy(&buf[..n]).to_string()
+        });
+
+        let proxy = proxy_at(&format!("http://{}", addr), Some("user"), Some("pw"));
+        let mut stream = tunnel(&proxy, "ws-subscriptions-clob.polymarket.com", 443).await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.starts_with("CONNECT ws-subscriptions-clob.polymarket.com:443 HTTP/1.1\r\n"));
+        // base64("user:pw")
+        assert!(request.contains("Proxy-Authorization: Basic dXNlcjpwdw==\r\n"));
+        let mut tunnelled = [0u8; 5];
+        stream.read_exact(&mut tunnelled).await.unwrap();
+        assert_eq!(&tunnelled, b"hello");
+    }
+
+    #[tokio::test]
+    async fn socks5_authenticates_and_connects_by_name() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 4];
+            socket.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [5, 2, 0, 2]);
+            socket.write_all(&[5, 2]).await.unwrap();
+            let mut auth = [0u8; 9];
+            socket.read_exact(&mut auth).await.unwrap();
+            assert_eq!(&auth, b"\x01\x04user\x02pw");
+            socket.write_all(&[1, 0]).await.unwrap();
+            let mut request = [0u8; 5];
+            socket.read_exact(&mut request).await.unwrap();
+            let mut host = vec![0u8; request[4] as usize + 2];
+            socket.read_exact(&mut host).await.unwrap();
+            socket.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0x1f, 0x90]).await.unwrap();
+            (request, host)
+        });
+
+        let proxy = proxy_at(&format!("socks5h://user:pw@{}", addr), None, None);
+        assert_eq!(proxy.url(), format!("socks5h://user:pw@{}", addr));
+        tunnel(&proxy, "clob.polymarket.com", 443).await.unwrap();
+        let (request, host) = server.await.unwrap();
+        assert_eq!(&request[..4], &[5, 1, 0, 3]);
+        assert_eq!(&host[..host.len() - 2], b"clob.polymarket.com");
+        assert_eq!(&host[host.len() - 2..], &443u16.to_be_bytes());
+    }
+
+    #[tokio::test]
+    async fn http_clients_reach_a_url_through_a_socks5h_proxy() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 2];
+            socket.read_exact(&mut greeting).await.unwrap();
+            let mut methods = vec![0u8; greeting[1] as usize];
+            socket.read_exact(&mut methods).await.unwrap();
+            assert!(methods.contains(&2), "offered {:?}", methods);
+            socket.write_all(&[5, 2]).await.unwrap();
+            let mut auth = [0u8; 9];
+            socket.read_exact(&mut auth).await.unwrap();
+            assert_eq!(&auth, b"\x01\x04user\x02pw");
+            socket.write_all(&[1, 0]).await.unwrap();
+            let mut request = [0u8; 5];
+            socket.read_exact(&mut request).await.unwrap();
+            let mut host = vec![0u8; request[4] as usize + 2];
+            socket.read_exact(&mut host).await.unwrap();
+            socket.write_all(&[5, 0, 0, 1, 127, 0, 0, 1, 0, 80]).await.unwrap();
+            // The tunnelled HTTP request.
+            let mut buf = vec![0u8; 1024];
+            let n = socket.read(&mut buf).await.unwrap();
+            socket.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok").await.unwrap();
+            (request, host, String::from_utf8_lossy(&buf[..n]).to_string())
+        });
+
+        let proxy = proxy_at(&format!("socks5h://{}", addr), Some("user"), Some("pw"));
+        assert!(proxy.rpc_proxy().is_ok());
+        let client = reqwest::Client::builder().proxy(proxy.client_proxy().unwrap()).build().unwrap();
+        let body = client.get("http://clob.example/ok").send().await.unwrap().text().await.unwrap();
+        assert_eq!(body, "ok");
+        let (request, host, http) = server.await.unwrap();
+        // socks5h: the name goes to the proxy unresolved.
+        assert_eq!(&request[..4], &[5, 1, 0, 3]);
+        assert_eq!(&host[..host.len() - 2], b"clob.example");
+        assert!(http.starts_with("GET /ok HTTP/1.1\r\n"), "{}", http);
+    }
+}
@@ -0,0 +1,117 @@
+//! Credentials from the config (private key, API secret and passphrase, webhook and admin tokens). `Secret`
+//! prints and serializes as "[redacted]", so a config value interpolated into a log line, an error context, or a
+//! JSON dump can't leak; `expose` is the only way to read it.
+
+use serde::{Deserialize, Serialize, Serializer};
+use std::cell::Cell;
+
+pub const REDACTED: &str = "[redacted]";
+
+thread_local! {
+    static EXPOSED: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The real value, for signing and auth headers only.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl std::fmt::Display for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if EXPOSED.with(Cell::get) {
+            serializer.serialize_str(&self.0)
+        } else {
+            serializer.serialize_str(REDACTED)
+        }
+    }
+}
+
+/// Run `f` with secrets serializing to their real values: writing the config file, or merging config JSON
+/// (instances, wallets) that is parsed straight back.
+pub fn with_exposed<T>(f: impl FnOnce() -> T) -> T {
+    struct Restore(bool);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            EXPOSED.with(|e| e.set(self.0));
+        }
+    }
+    let _restore = Restore(EXPOSED.with(|e| e.replace(true)));
+    f()
+}
+
+/// `url` for logs: scheme and host only, since RPC providers and webhooks (Alchemy, Infura, Slack, Discord)
+/// put the API key or token in the path, query, or user info.
+pub fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if parsed.path().len() > 1 || parsed.query().is_some() || !parsed.username().is_empty() => {
+            format!("{}://{}/{}", parsed.scheme(), parsed.host_str().unwrap_or_default(), REDACTED)
+        }
+        Ok(parsed) => format!("{}://{}", parsed.scheme(), parsed.host_str().unwrap_or_default()),
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn secrets_are_redacted_unless_exposed() {
+        let secret = Secret::from("0xdeadbeef");
+        assert_eq!(format!("{} {:?}", secret, Some(&secret)), "[redacted] Some([redacted])");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"[redacted]\"");
+        assert_eq!(with_exposed(|| serde_json::to_string(&secret).unwrap()), "\"0xdeadbeef\"");
+        assert_eq!(serde_json::to_string(&secret).unwrap(), "\"[redacted]\"");
+        let parsed: Secret = serde_json::from_str("\"0xdeadbeef\"").unwrap();
+        assert_eq!(parsed.expose(), "0xdeadbeef");
+    }
+
+    #[test]
+    fn urls_keep_only_the_host() {
+        assert_eq!(redact_url("https://polygon-rpc.com"), "https://polygon-rpc.com");
+        assert_eq!(
+            redact_url("https://polygon-mainnet.g.alchemy.com/v2/abc123"),
+            "https://polygon-mainnet.g.alchemy.com/[redacted]"
+        );
+        assert_eq!(redact_url("https://hooks.example.com/?token=abc"), "https://hooks.example.com/[redacted]");
+        assert_eq!(redact_url("not a url"), "[redacted]");
+    }
+}
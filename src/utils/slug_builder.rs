@@ -1,31 +1,207 @@
-pub fn build_15m_slug(symbol: &str, period_start_unix: i64) -> String {
-    format!("{}-updown-15m-{}", symbol.to_lowercase(), period_start_unix)
+use chrono::{TimeZone, Timelike};
+use chrono_tz::America::New_York;
+
+/// Slug of the `period_mins` up/down market starting at `period_start_unix`: "btc-updown-15m-<start>", or
+/// for hourly markets the dated form "bitcoin-up-or-down-october-16-10am-et".
+pub fn build_updown_slug(symbol: &str, period_mins: i64, period_start_unix: i64) -> String {
+    let symbol = symbol.to_lowercase();
+    if period_mins == 60 {
+        if let Some(et) = New_York.timestamp_opt(period_start_unix, 0).single() {
+            let (pm, hour) = et.hour12();
+            return format!(
+                "{}-up-or-down-{}-{}-{}{}-et",
+                asset_name(&symbol),
+                et.format("%B").to_string().to_lowercase(),
+                et.format("%-d"),
+                hour,
+                if pm { "pm" } else { "am" }
+            );
+        }
+    }
+    format!("{}-{}", updown_series(&symbol, period_mins), period_start_unix)
+}
+
+/// Every `period_mins` up/down market of `symbol`, as one key: "btc-updown-15m", "btc-updown-1h".
+pub fn updown_series(symbol: &str, period_mins: i64) -> String {
+    let label = if period_mins % 60 == 0 {
+        format!("{}h", period_mins / 60)
+    } else {
+        format!("{}m", period_mins)
+    };
+    format!("{}-updown-{}", symbol.to_lowercase(), label)
 }
 
-pub fn build_5m_slug(symbol: &str, period_start_unix: i64) -> String {
-    format!("{}-updown-5m-{}", symbol.to_lowercase(), period_start_unix)
+/// (symbol, period_mins, start) of a "<symbol>-updown-<15m|4h>-<start>" slug; None for other markets.
+pub fn parse_updown_slug(slug: &str) -> Option<(String, i64, i64)> {
+    let (symbol, rest) = slug.split_once("-updown-")?;
+    let (label, start) = rest.split_once('-')?;
+    let period_mins = match label.strip_suffix('m') {
+        Some(mins) => mins.parse().ok()?,
+        None => label.strip_suffix('h')?.parse::<i64>().ok()? * 60,
+    };
+    let symbol = symbol.to_lowercase();
+    if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some((symbol, period_mins, start.parse().ok()?))
+}
+
+/// Full asset name used in dated slugs.
+fn asset_name(symbol: &str) -> &str {
+    match symbol {
+        "btc" => "bitcoin",
+        "eth" => "ethereum",
+        "sol" => "solana",
+        other => other,
+    }
 }
 
+/// Phrases that introduce the strike, checked case-insensitively (English plus common translations).
+const STRIKE_PHRASES: &[&str] = &[
+    "at or above",
+    "above",
+    "higher than",
+    "greater than",
+    "more than",
+    "over",
+    ">=",
+    "≥",
+    ">",
+    "über",
+    "au-dessus de",
+    "por encima de",
+    "acima de",
+    "sopra",
+];
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₿'];
+/// Max chars between a phrase and its number (spaces, currency, "us$", "usd").
+const MAX_PREFIX_CHARS: usize = 6;
+
+/// Price-to-beat from a market question. Last-resort fallback when Chainlink/crypto-price are unavailable,
+/// so it returns None rather than guess: no strike phrase or currency, or a range ("between $X and $Y").
 pub fn parse_price_to_beat_from_question(question: &str) -> Option<f64> {
     let q = question.to_lowercase();
-    let idx = q.find("above ").or_else(|| q.find('$'))?;
-    let after = &question[idx..];
-    let mut num_start_byte = 0;
-    for (i, c) in after.char_indices() {
-        if c == '$' || c.is_ascii_digit() {
-            num_start_byte = if c == '$' { i + c.len_utf8() } else { i };
+    if q.contains("between ") {
+        return None;
+    }
+    let mut anchors: Vec<usize> = Vec::new();
+    for phrase in STRIKE_PHRASES {
+        for (i, _) in q.match_indices(phrase) {
+            let end = i + phrase.len();
+            let word_phrase = phrase.chars().all(|c| c.is_alphabetic() || c == ' ' || c == '-');
+            let bounded = !word_phrase
+                || (q[..i].chars().last().is_none_or(|c| !c.is_alphanumeric())
+                    && q[end..].chars().next().is_none_or(|c| !c.is_alphanumeric()));
+            if bounded {
+                anchors.push(end);
+            }
+        }
+    }
+    for (i, c) in q.char_indices() {
+        if CURRENCY_SYMBOLS.contains(&c) {
+            anchors.push(i);
+        }
+    }
+    anchors.sort_unstable();
+    anchors.dedup();
+    for start in anchors {
+        match parse_number_after(&q[start..]) {
+            NumberAfter::Value(v) => return Some(v),
+            NumberAfter::Range => return None,
+            NumberAfter::Missing => {}
+        }
+    }
+    None
+}
+
+enum NumberAfter {
+    Value(f64),
+    /// "$3,100-$3,200", "$96k to $98k": not a single strike.
+    Range,
+    Missing,
+}
+
+/// First number after `text`'s short prefix, with thousands separators, decimal comma, and "k" suffix.
+fn parse_number_after(text: &str) -> NumberAfter {
+    let Some((digit_start, _)) = text.char_indices().find(|(_, c)| c.is_ascii_digit()) else {
+        return NumberAfter::Missing;
+    };
+    let prefix = &text[..digit_start];
+    let prefix_ok = prefix.chars().count() <= MAX_PREFIX_CHARS
+        && prefix
+            .trim()
+            .trim_start_matches(|c: char| CURRENCY_SYMBOLS.contains(&c) || c.is_whitespace())
+            .trim_start_matches("usd")
+            .trim_start_matches("us")
+            .trim_start_matches(|c: char| CURRENCY_SYMBOLS.contains(&c) || c.is_whitespace())
+            .is_empty();
+    if !prefix_ok {
+        return NumberAfter::Missing;
+    }
+
+    let rest = &text[digit_start..];
+    let chars: Vec<char> = rest.chars().collect();
+    let mut raw = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_ascii_digit() || c == '.' || c == ',' || c == '\'' {
+            raw.push(c);
+        } else if matches!(c, ' ' | '\u{a0}' | '\u{202f}')
+            && chars.len() >= i + 4
+            && chars[i + 1..i + 4].iter().all(|d| d.is_ascii_digit())
+            && chars.get(i + 4).is_none_or(|d| !d.is_ascii_digit())
+        {
+            // "97 500" style grouping: only a space followed by exactly three digits.
+        } else {
             break;
         }
+        i += 1;
     }
-    let num_str: String = after[num_start_byte..]
-        .chars()
-        .take_while(|c| c.is_ascii_digit() || *c == '.' || *c == ',')
-        .filter(|c| *c != ',')
-        .collect();
-    if num_str.is_empty() {
+    let raw = raw.trim_end_matches(['.', ',']);
+    let after: String = chars[i..].iter().collect();
+    let after = after.trim_start();
+    let (multiplier, after) = match after.strip_prefix('k') {
+        Some(tail) if tail.chars().next().is_none_or(|c| !c.is_alphanumeric()) => {
+            (1000.0, tail.trim_start())
+        }
+        _ => (1.0, after),
+    };
+    if after.starts_with(['-', '–']) || after.starts_with("to ") || after.starts_with("and ") {
+        return NumberAfter::Range;
+    }
+    match normalize_number(raw) {
+        Some(n) => NumberAfter::Value(n * multiplier),
+        None => NumberAfter::Missing,
+    }
+}
+
+/// "97,500.25", "97.500,25", "97'500", "3,25" -> f64. With both separators the last one is the decimal point;
+/// a lone comma is a thousands separator only when followed by groups of exactly three digits.
+fn normalize_number(raw: &str) -> Option<f64> {
+    let raw: String = raw.chars().filter(|c| *c != '\'').collect();
+    if raw.is_empty() {
         return None;
     }
-    num_str.parse::<f64>().ok()
+    let last_dot = raw.rfind('.');
+    let last_comma = raw.rfind(',');
+    let normalized = match (last_dot, last_comma) {
+        (Some(d), Some(c)) if c > d => raw.replace('.', "").replace(',', "."),
+        (Some(_), Some(_)) => raw.replace(',', ""),
+        (None, Some(_)) => {
+            let groups: Vec<&str> = raw.split(',').collect();
+            if groups.len() > 1 && groups[1..].iter().all(|g| g.len() == 3) {
+                raw.replace(',', "")
+            } else if groups.len() == 2 {
+                raw.replace(',', ".")
+            } else {
+                return None;
+            }
+        }
+        (Some(_), None) if raw.matches('.').count() > 1 => raw.replace('.', ""),
+        _ => raw,
+    };
+    normalized.parse::<f64>().ok()
 }
 
 #[cfg(test)]
@@ -34,8 +210,20 @@ mod tests {
 
     #[test]
     fn builds_expected_slugs() {
-        assert_eq!(build_15m_slug("BTC", 1700000000), "btc-updown-15m-1700000000");
-        assert_eq!(build_5m_slug("Eth", 1700000300), "eth-updown-5m-1700000300");
+        assert_eq!(build_updown_slug("BTC", 15, 1700000000), "btc-updown-15m-1700000000");
+        assert_eq!(build_updown_slug("Eth", 5, 1700000300), "eth-updown-5m-1700000300");
+        assert_eq!(build_updown_slug("btc", 240, 1700000000), "btc-updown-4h-1700000000");
+        assert_eq!(updown_series("BTC", 60), "btc-updown-1h");
+        // 2023-11-14 17:00 ET
+        assert_eq!(build_updown_slug("btc", 60, 1_699_999_200), "bitcoin-up-or-down-november-14-5pm-et");
+    }
+
+    #[test]
+    fn parses_updown_slugs_back() {
+        assert_eq!(parse_updown_slug("doge-updown-15m-1700000000"), Some(("doge".to_string(), 15, 1700000000)));
+        assert_eq!(parse_updown_slug("btc-updown-4h-1700000000"), Some(("btc".to_string(), 240, 1700000000)));
+        assert_eq!(parse_updown_slug("bitcoin-up-or-down-november-14-5pm-et"), None);
+        assert_eq!(parse_updown_slug("btc-updown-15m-soon"), None);
     }
 
     #[test]
@@ -43,4 +231,37 @@ mod tests {
         let question = "Will Bitcoin be above $97,500 at 10:15 ET?";
         assert_eq!(parse_price_to_beat_from_question(question), Some(97500.0));
     }
+
+    /// Question strings seen on Polymarket and translated variants; None = must not guess.
+    const QUESTION_CORPUS: &[(&str, Option<f64>)] = &[
+        ("Will the price of Bitcoin be above $104,000 on January 6?", Some(104000.0)),
+        ("Will Ethereum be higher than $3,250.50 at 3PM ET?", Some(3250.5)),
+        ("Will Solana close greater than $185.2 on Friday?", Some(185.2)),
+        ("Will XRP be at or above $0.5234 at 12:00 ET?", Some(0.5234)),
+        ("Will BTC be above $97.5k at 10:15 ET?", Some(97500.0)),
+        ("Will the price of Bitcoin be above US$ 97,500?", Some(97500.0)),
+        ("Will BTC be ≥ 97,500 USD at noon?", Some(97500.0)),
+        ("Will Ethereum be over $3,000 overnight?", Some(3000.0)),
+        ("Will Bitcoin be above €90.000,50 at close?", Some(90000.5)),
+        ("Wird Bitcoin über 97.500,00 € liegen?", Some(97500.0)),
+        ("Le Bitcoin sera-t-il au-dessus de 97 500 € ?", Some(97500.0)),
+        ("¿Estará Bitcoin por encima de $97,500 a las 10:15?", Some(97500.0)),
+        ("Will Bitcoin reach £70'000?", Some(70000.0)),
+        ("Bitcoin Up or Down - January 6, 10:00AM ET", None),
+        ("Will Bitcoin be between $96,000 and $98,000 on Jan 6?", None),
+        ("Will ETH trade $3,100-$3,200 at 4PM ET?", None),
+        ("Will the Fed move rates above or below expectations?", None),
+    ];
+
+    #[test]
+    fn parses_question_corpus() {
+        for (question, expected) in QUESTION_CORPUS {
+            assert_eq!(
+                parse_price_to_beat_from_question(question),
+                *expected,
+                "question: {}",
+                question
+            );
+        }
+    }
 }
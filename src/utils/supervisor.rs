@@ -0,0 +1,168 @@
+//! Supervision of long-running tasks (symbol loops, WS readers, the RTDS feed): a task that fails or panics is
+//! started again after an exponential backoff, with an `alert` event on the bus for each restart and a per-task
+//! restart count for the admin `/state`. Panics are logged with a backtrace by the hook from `install_panic_hook`.
+
+use crate::events::{ArbEvent, EventBus};
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::FutureExt;
+use log::{error, info};
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::collections::HashMap;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex, Once};
+use tokio::time::{sleep, Duration, Instant};
+
+/// Wait before the first restart; doubled for each further failure in a row.
+pub const RESTART_BACKOFF_BASE_SECS: u64 = 5;
+pub const RESTART_BACKOFF_MAX_SECS: u64 = 300;
+/// A task that ran at least this long before failing starts its backoff over.
+const HEALTHY_RUN_SECS: u64 = 600;
+
+/// Restarts since startup per supervised task.
+pub type RestartCounts = Arc<Mutex<HashMap<String, u32>>>;
+
+/// Seconds to wait before restarting after `failures` failures in a row (1 = the first).
+pub fn restart_backoff_secs(failures: u32) -> u64 {
+    let doublings = failures.saturating_sub(1).min(16);
+    RESTART_BACKOFF_BASE_SECS
+        .saturating_mul(1 << doublings)
+        .min(RESTART_BACKOFF_MAX_SECS)
+}
+
+/// Log panics through `log` with the thread, location, and a backtrace, instead of the default stderr line.
+/// Process-wide; later calls are no-ops.
+pub fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        std::panic::set_hook(Box::new(|info| {
+            let thread = std::thread::current();
+            error!(
+                "Panic in thread {}: {}\n{}",
+                thread.name().unwrap_or("<unnamed>"),
+                info,
+                Backtrace::force_capture()
+            );
+        }));
+    });
+}
+
+/// Restarts the tasks it runs and reports each restart. Cheap to clone; clones share the restart counts.
+#[derive(Debug, Clone, Default)]
+pub struct Supervisor {
+    events: Option<EventBus>,
+    restarts: RestartCounts,
+}
+
+impl Supervisor {
+    /// A supervisor publishing its alerts to `events`, when given.
+    pub fn new(events: Option<EventBus>) -> Self {
+        Self {
+            events,
+            restarts: RestartCounts::default(),
+        }
+    }
+
+    pub fn restart_counts(&self) -> HashMap<String, u32> {
+        self.restarts.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Run `run` until it returns Ok, restarting it after each error or panic. `name` labels the logs, the
+    /// alert, and the entry in the restart counts.
+    pub async fn run<F, Fut>(&self, name: &str, mut run: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let mut failures = 0u32;
+        loop {
+            let started = Instant::now();
+            let Some((kind, reason)) = run_once(run()).await else {
+                return;
+            };
+            if started.elapsed() >= Duration::from_secs(HEALTHY_RUN_SECS) {
+                failures = 0;
+            }
+            failures += 1;
+            let total = {
+                let mut restarts = self.restarts.lock().unwrap_or_else(|e| e.into_inner());
+                let count = restarts.entry(name.to_string()).or_default();
+                *count += 1;
+                *count
+            };
+            let backoff = restart_backoff_secs(failures);
+            let message = format!("{} {}; restart {} in {}s", name, reason, total, backoff);
+            error!("{}", message);
+            if let Some(events) = &self.events {
+                events.publish(ArbEvent::Alert {
+                    kind: kind.to_string(),
+                    subject: name.to_string(),
+                    message,
+                    ts_ms: Utc::now().timestamp_millis(),
+                });
+            }
+            sleep(Duration::from_secs(backoff)).await;
+            info!("Restarting {}", name);
+        }
+    }
+
+    /// `run` on a new tokio task.
+    pub fn spawn<F, Fut>(&self, name: impl Into<String>, run: F) -> tokio::task::JoinHandle<()>
+    where
+        F: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
+    {
+        let supervisor = self.clone();
+        let name = name.into();
+        tokio::spawn(async move { supervisor.run(&name, run).await })
+    }
+}
+
+/// Run `task` to completion, catching a panic: None when it returned Ok, else the alert kind and what went wrong.
+async fn run_once<Fut: Future<Output = Result<()>>>(task: Fut) -> Option<(&'static str, String)> {
+    match AssertUnwindSafe(task).catch_unwind().await {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(("loop_restart", format!("failed: {:#}", e))),
+        Err(panic) => Some(("task_panic", format!("panicked: {}", panic_message(panic.as_ref())))),
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        let backoffs: Vec<u64> = (1..=8).map(restart_backoff_secs).collect();
+        assert_eq!(backoffs, vec![5, 10, 20, 40, 80, 160, 300, 300]);
+        assert_eq!(restart_backoff_secs(u32::MAX), RESTART_BACKOFF_MAX_SECS);
+    }
+
+    async fn explode() -> Result<()> {
+        panic!("boom {}", 42)
+    }
+
+    #[test]
+    fn failures_and_panics_are_caught() {
+        let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        assert_eq!(rt.block_on(run_once(async { Ok(()) })), None);
+        assert_eq!(
+            rt.block_on(run_once(async { Err(anyhow::anyhow!("WS closed")) })),
+            Some(("loop_restart", "failed: WS closed".to_string()))
+        );
+        assert_eq!(
+            rt.block_on(run_once(explode())),
+            Some(("task_panic", "panicked: boom 42".to_string()))
+        );
+    }
+}
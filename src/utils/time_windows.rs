@@ -1,13 +1,14 @@
-use chrono::{TimeZone, Timelike};
+use crate::utils::clock::Clock;
+use chrono::{Datelike, LocalResult, TimeZone, Timelike};
 use chrono_tz::America::New_York;
 
-/// Polymarket aligns 15m/5m markets to Eastern Time (ET).
-pub fn period_start_et_unix(minutes: i64) -> i64 {
-    let utc_now = chrono::Utc::now();
-    period_start_et_unix_at(utc_now.timestamp(), minutes)
+/// Polymarket aligns up/down markets (5m, 15m, 1h, ...) to Eastern Time (ET).
+pub fn period_start_et_unix(clock: &dyn Clock, minutes: i64) -> i64 {
+    period_start_et_unix_at(clock.now(), minutes)
 }
 
-/// ET-aligned period start (Unix) that contains the given timestamp.
+/// ET-aligned period start (Unix) that contains the given timestamp. Periods are counted from ET midnight, so
+/// a 4h market starts at 00:00, 04:00, 08:00, ... ET.
 pub fn period_start_et_unix_at(ts_sec: i64, minutes: i64) -> i64 {
     let utc_dt = match chrono::Utc.timestamp_opt(ts_sec, 0).single() {
         Some(dt) => dt,
@@ -15,30 +16,60 @@ pub fn period_start_et_unix_at(ts_sec: i64, minutes: i64) -> i64 {
     };
     let et = New_York;
     let et_dt = utc_dt.with_timezone(&et);
-    let minute_floor = (et_dt.minute() as i64 / minutes) * minutes;
+    let minute_of_day = et_dt.hour() as i64 * 60 + et_dt.minute() as i64;
+    let floor = minute_of_day / minutes * minutes;
     let truncated_naive = et_dt
         .date_naive()
-        .and_hms_opt(et_dt.hour(), minute_floor as u32, 0)
+        .and_hms_opt((floor / 60) as u32, (floor % 60) as u32, 0)
         .expect("valid ET period timestamp");
-    let dt_et = et
-        .from_local_datetime(&truncated_naive)
-        .single()
-        .or_else(|| et.from_local_datetime(&truncated_naive).earliest())
-        .expect("ET period start");
-    dt_et.timestamp()
+    // In the repeated hour when DST ends, the start is the occurrence at or before `ts_sec`.
+    match et.from_local_datetime(&truncated_naive) {
+        LocalResult::Single(dt) => dt.timestamp(),
+        LocalResult::Ambiguous(_, second) if second.timestamp() <= ts_sec => second.timestamp(),
+        LocalResult::Ambiguous(first, _) => first.timestamp(),
+        LocalResult::None => ts_sec,
+    }
 }
 
-pub fn current_15m_period_start() -> i64 {
-    period_start_et_unix(15)
+/// True from `overlap_start_secs` into the long period until it ends.
+pub fn is_in_overlap(now_ts: i64, long_start: i64, overlap_start_secs: i64, long_secs: i64) -> bool {
+    let elapsed = now_ts - long_start;
+    elapsed >= overlap_start_secs && elapsed < long_secs
 }
 
-pub fn current_5m_period_start() -> i64 {
-    period_start_et_unix(5)
+/// Minutes since midnight ET at `ts_sec`.
+pub fn et_minute_of_day(ts_sec: i64) -> i64 {
+    match chrono::Utc.timestamp_opt(ts_sec, 0).single() {
+        Some(dt) => {
+            let et = dt.with_timezone(&New_York);
+            et.hour() as i64 * 60 + et.minute() as i64
+        }
+        None => 0,
+    }
+}
+
+/// Day of the week in ET at `ts_sec`.
+pub fn et_weekday(ts_sec: i64) -> chrono::Weekday {
+    match chrono::Utc.timestamp_opt(ts_sec, 0).single() {
+        Some(dt) => dt.with_timezone(&New_York).weekday(),
+        None => chrono::Weekday::Mon,
+    }
 }
 
-pub fn is_last_5min_of_15m(now_ts: i64, period_15m_start: i64) -> bool {
-    let elapsed = now_ts - period_15m_start;
-    elapsed >= 10 * 60 && elapsed < 15 * 60
+/// "HH:MM" (24h) as minutes since midnight.
+pub fn parse_hhmm(s: &str) -> Option<i64> {
+    let (h, m) = s.trim().split_once(':')?;
+    let (h, m): (i64, i64) = (h.parse().ok()?, m.parse().ok()?);
+    ((0..24).contains(&h) && (0..60).contains(&m)).then_some(h * 60 + m)
+}
+
+/// True inside the daily [start, end) window, which wraps past midnight when end <= start.
+pub fn in_daily_window(minute_of_day: i64, start: i64, end: i64) -> bool {
+    if start < end {
+        minute_of_day >= start && minute_of_day < end
+    } else {
+        minute_of_day >= start || minute_of_day < end
+    }
 }
 
 #[cfg(test)]
@@ -48,10 +79,10 @@ mod tests {
     #[test]
     fn last_five_minute_window_bounds() {
         let start = 1_700_000_000;
-        assert!(!is_last_5min_of_15m(start + 599, start));
-        assert!(is_last_5min_of_15m(start + 600, start));
-        assert!(is_last_5min_of_15m(start + 899, start));
-        assert!(!is_last_5min_of_15m(start + 900, start));
+        assert!(!is_in_overlap(start + 599, start, 600, 900));
+        assert!(is_in_overlap(start + 600, start, 600, 900));
+        assert!(is_in_overlap(start + 899, start, 600, 900));
+        assert!(!is_in_overlap(start + 900, start, 600, 900));
     }
 
     #[test]
@@ -62,4 +93,45 @@ mod tests {
         assert!(ts >= p15 && ts < p15 + 900);
         assert!(ts >= p5 && ts < p5 + 300);
     }
+
+    #[test]
+    fn multi_hour_periods_are_floored_from_et_midnight() {
+        // 2024-01-15 13:37 EST: the 4h market opened at 12:00 EST (17:00 UTC), the 1h one at 13:00.
+        let ts = 1_705_343_820;
+        assert_eq!(period_start_et_unix_at(ts, 240), 1_705_338_000);
+        assert_eq!(period_start_et_unix_at(ts, 60), 1_705_338_000 + 3600);
+        assert_eq!(period_start_et_unix_at(ts, 15), 1_705_338_000 + 3600 + 30 * 60);
+    }
+
+    #[test]
+    fn period_starts_across_dst_transitions() {
+        use crate::utils::clock::ManualClock;
+        // 2026-03-08: 01:59:59 EST is followed by 03:00:00 EDT (07:00 UTC).
+        let clock = ManualClock::new(1_772_953_199_000);
+        assert_eq!(period_start_et_unix(&clock, 15), 1_772_952_300);
+        clock.advance(1000);
+        assert_eq!(period_start_et_unix(&clock, 15), 1_772_953_200);
+        assert_eq!(period_start_et_unix(&clock, 60), 1_772_953_200);
+        // 2026-11-01: 01:00-02:00 ET runs twice, first EDT (05:00 UTC) then EST (06:00 UTC).
+        clock.set(1_793_511_600_000);
+        assert_eq!(period_start_et_unix(&clock, 15), 1_793_511_000);
+        assert_eq!(period_start_et_unix(&clock, 60), 1_793_509_200);
+        clock.advance(3600 * 1000);
+        assert_eq!(period_start_et_unix(&clock, 15), 1_793_514_600);
+        assert_eq!(period_start_et_unix(&clock, 60), 1_793_512_800);
+    }
+
+    #[test]
+    fn daily_window_wraps_past_midnight() {
+        let (stop, resume) = (parse_hhmm("16:00").unwrap(), parse_hhmm("00:00").unwrap());
+        assert!(!in_daily_window(15 * 60 + 59, stop, resume));
+        assert!(in_daily_window(16 * 60, stop, resume));
+        assert!(in_daily_window(23 * 60 + 59, stop, resume));
+        let (stop, resume) = (parse_hhmm("22:30").unwrap(), parse_hhmm("06:00").unwrap());
+        assert!(in_daily_window(2 * 60, stop, resume));
+        assert!(!in_daily_window(6 * 60, stop, resume));
+        assert!(parse_hhmm("24:00").is_none());
+        // 2023-11-14 17:00 ET
+        assert_eq!(et_minute_of_day(1_699_999_200), 17 * 60);
+    }
 }
@@ -0,0 +1,100 @@
+//! An order post that failed without an answer is looked up in the CLOB's own trades, which list a match before
+//! the data API sees it settle.
+
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use polymarket_arbitrage_bot::models::{OrderRequest, OrderType, Side};
+use serde_json::{json, Value};
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const TOKEN: &str = "123";
+
+async fn clob_with_trades(trades: Value) -> MockServer {
+    let clob = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/book"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "bids": [], "asks": [], "tick_size": "0.01", "min_order_size": "5"
+        })))
+        .mount(&clob)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [], "next_cursor": "LTE=" })))
+        .mount(&clob)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/data/trades"))
+        .and(query_param("asset_id", TOKEN))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": trades, "next_cursor": "LTE=" })))
+        .mount(&clob)
+        .await;
+    clob
+}
+
+fn api_for(clob: &MockServer) -> PolymarketApi {
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.api_key = Some("key".to_string());
+    config.polymarket.api_secret = Some("c2VjcmV0".into());
+    config.polymarket.api_passphrase = Some("pass".into());
+    PolymarketApi::from_config(&config.polymarket)
+}
+
+fn fok_buy(size: &str) -> OrderRequest {
+    OrderRequest {
+        token_id: TOKEN.to_string(),
+        side: Side::Buy,
+        size: size.to_string(),
+        price: "0.45".to_string(),
+        order_type: OrderType::Fok,
+        expiration: None,
+        client_order_id: None,
+    }
+}
+
+fn taker_trade(id: &str, taker_order_id: &str, size: &str, match_time: i64) -> Value {
+    json!({
+        "id": id,
+        "taker_order_id": taker_order_id,
+        "asset_id": TOKEN,
+        "side": "BUY",
+        "size": size,
+        "price": "0.44",
+        "status": "MATCHED",
+        "match_time": match_time.to_string(),
+        "trader_side": "TAKER"
+    })
+}
+
+#[tokio::test]
+async fn matched_fok_not_yet_on_the_data_api_is_found_once() {
+    let now = chrono::Utc::now().timestamp();
+    // One FOK crossing two makers; the data API would still list nothing.
+    let clob = clob_with_trades(json!([
+        taker_trade("t1", "0xabc", "6", now),
+        taker_trade("t2", "0xabc", "4", now),
+    ]))
+    .await;
+    let api = api_for(&clob);
+
+    let found = api.find_submitted_order(&fok_buy("10"), now - 5).await.unwrap().expect("the fill");
+    assert_eq!(found.order_id.as_deref(), Some("0xabc"));
+    assert_eq!(found.status, "matched");
+    // A second leg with the same fields doesn't get the same fill.
+    assert!(api.find_submitted_order(&fok_buy("10"), now - 5).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn fills_of_another_size_or_before_the_post_are_not_taken() {
+    let now = chrono::Utc::now().timestamp();
+    let clob = clob_with_trades(json!([
+        taker_trade("t1", "0xsmaller", "7", now),
+        taker_trade("t2", "0xolder", "10", now - 60),
+    ]))
+    .await;
+    let api = api_for(&clob);
+
+    assert!(api.find_submitted_order(&fok_buy("10"), now - 5).await.unwrap().is_none());
+}
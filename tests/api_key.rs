@@ -0,0 +1,48 @@
+//! `create_or_derive_api_key`: L1-signed requests to the CLOB auth endpoints, deriving when creating fails.
+
+use alloy::signers::local::PrivateKeySigner;
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use serde_json::json;
+use std::str::FromStr;
+use wiremock::matchers::{header, header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+#[tokio::test]
+async fn existing_credentials_are_derived_when_creating_fails() {
+    let address = PrivateKeySigner::from_str(KEY).unwrap().address().to_string();
+    let clob = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/auth/api-key"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(json!({ "error": "Could not create api key" })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/auth/derive-api-key"))
+        .and(header("POLY_ADDRESS", address.as_str()))
+        .and(header("POLY_NONCE", "3"))
+        .and(header_exists("POLY_SIGNATURE"))
+        .and(header_exists("POLY_TIMESTAMP"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "apiKey": "key-1",
+            "secret": "c2VjcmV0",
+            "passphrase": "pass"
+        })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.private_key = Some(KEY.into());
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    let credentials = api.create_or_derive_api_key(3).await.unwrap();
+    assert_eq!(credentials.api_key, "key-1");
+    assert_eq!(credentials.secret.expose(), "c2VjcmV0");
+    assert_eq!(credentials.passphrase.expose(), "pass");
+    // Credentials print redacted like any other secret.
+    assert!(!format!("{:?}", credentials).contains("c2VjcmV0"));
+}
@@ -0,0 +1,191 @@
+//! Exchange approvals: `missing_approvals` reads each USDC allowance and CTF `isApprovedForAll` over JSON-RPC,
+//! and `send_approvals` sends only what is missing, in one proxy wallet transaction.
+
+use alloy::consensus::{Transaction, TxEnvelope};
+use alloy::eips::Decodable2718;
+use alloy::primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use polymarket_arbitrage_bot::api::PolymarketApi;
+use polymarket_arbitrage_bot::config::{Config, ContractAddresses, Network};
+use serde_json::{json, Value};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const PROXY_WALLET: &str = "0x0000000000000000000000000000000000000abc";
+
+sol! {
+    function allowance(address owner, address spender) external view returns (uint256);
+    function approve(address spender, uint256 amount) external returns (bool);
+    function isApprovedForAll(address owner, address operator) external view returns (bool);
+    function setApprovalForAll(address operator, bool approved) external;
+
+    struct ProxyCall {
+        uint8 typeCode;
+        address to;
+        uint256 value;
+        bytes data;
+    }
+    function proxy(ProxyCall[] calls) external payable returns (bytes[] returnValues);
+}
+
+/// Polygon node holding the wallet's USDC allowance and CTF approval per spender, that mines every transaction
+/// it is sent at once and keeps its raw bytes.
+#[derive(Clone)]
+struct Node {
+    contracts: ContractAddresses,
+    allowances: Vec<(Address, U256)>,
+    approved: Vec<Address>,
+    sent: Arc<Mutex<Vec<Bytes>>>,
+}
+
+impl Node {
+    fn eth_call(&self, call: &Value) -> Value {
+        let to = Address::from_str(call["to"].as_str().unwrap()).unwrap();
+        let input = Bytes::from_str(call["input"].as_str().or(call["data"].as_str()).unwrap()).unwrap();
+        let word = if to == Address::from_str(&self.contracts.usdc).unwrap() {
+            let spender = allowanceCall::abi_decode(&input).unwrap().spender;
+            let allowance = self.allowances.iter().find(|(s, _)| *s == spender).map(|(_, a)| *a);
+            allowance.unwrap_or_default()
+        } else {
+            let operator = isApprovedForAllCall::abi_decode(&input).unwrap().operator;
+            U256::from(self.approved.contains(&operator) as u8)
+        };
+        json!(B256::from(word))
+    }
+
+    fn receipt(hash: &str) -> Value {
+        json!({
+            "type": "0x2",
+            "status": "0x1",
+            "cumulativeGasUsed": "0x5208",
+            "logs": [],
+            "logsBloom": format!("0x{}", "00".repeat(256)),
+            "transactionHash": hash,
+            "transactionIndex": "0x0",
+            "blockHash": B256::repeat_byte(0x64),
+            "blockNumber": "0x64",
+            "gasUsed": "0x5208",
+            "effectiveGasPrice": "0x3b9aca00",
+            "from": PrivateKeySigner::from_str(KEY).unwrap().address(),
+            "to": Address::ZERO,
+            "contractAddress": null,
+        })
+    }
+}
+
+impl Respond for Node {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let params = &body["params"];
+        let result = match body["method"].as_str().unwrap() {
+            "eth_call" => self.eth_call(&params[0]),
+            "eth_blockNumber" => json!("0x64"),
+            "eth_chainId" => json!("0x89"),
+            "eth_estimateGas" => json!("0x30000"),
+            "eth_getTransactionCount" => json!("0x7"),
+            "eth_feeHistory" => json!({
+                "oldestBlock": "0x5f",
+                "baseFeePerGas": vec!["0x3b9aca00"; 6],
+                "gasUsedRatio": vec![0.5; 5],
+                "reward": vec![vec!["0x3b9aca00"]; 5],
+            }),
+            "eth_sendRawTransaction" => {
+                let raw = Bytes::from_str(params[0].as_str().unwrap()).unwrap();
+                let hash = keccak256(&raw);
+                self.sent.lock().unwrap().push(raw);
+                json!(hash)
+            }
+            "eth_getTransactionReceipt" => Node::receipt(params[0].as_str().unwrap()),
+            other => panic!("unexpected RPC call {}", other),
+        };
+        ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": body["id"], "result": result}))
+    }
+}
+
+async fn api_on(node: Node) -> (PolymarketApi, MockServer) {
+    let server = MockServer::start().await;
+    Mock::given(wiremock::matchers::method("POST")).respond_with(node).mount(&server).await;
+    let mut config = Config::default();
+    config.polymarket.rpc_url = vec![server.uri()];
+    config.polymarket.private_key = Some(KEY.into());
+    config.polymarket.proxy_wallet_address = Some(PROXY_WALLET.to_string());
+    config.polymarket.signature_type = Some(1);
+    config.polymarket.gas.confirmations = 0;
+    (PolymarketApi::from_config(&config.polymarket), server)
+}
+
+fn address(a: &str) -> Address {
+    Address::from_str(a).unwrap()
+}
+
+#[tokio::test]
+async fn only_missing_approvals_are_reported_and_sent() {
+    let contracts = Network::Polygon.default_contracts();
+    let (exchange, neg_risk_exchange, adapter) = (
+        address(&contracts.ctf_exchange),
+        address(&contracts.neg_risk_ctf_exchange),
+        address(&contracts.neg_risk_adapter),
+    );
+    let node = Node {
+        contracts: contracts.clone(),
+        // The NegRisk CTF Exchange allowance has run down to 5 USDC; the others are unlimited.
+        allowances: vec![(exchange, U256::MAX), (neg_risk_exchange, U256::from(5_000_000)), (adapter, U256::MAX)],
+        // The NegRisk Adapter was never approved for the CTF.
+        approved: vec![exchange, neg_risk_exchange],
+        sent: Arc::default(),
+    };
+    let (api, _server) = api_on(node.clone()).await;
+
+    let missing = api.missing_approvals().await.unwrap();
+    let what: Vec<&str> = missing.iter().map(|(_, _, what)| what.as_str()).collect();
+    assert_eq!(what, vec!["USDC allowance for NegRisk CTF Exchange", "CTF approval for NegRisk Adapter"]);
+    let (usdc, ctf) = (address(&contracts.usdc), address(&contracts.ctf));
+    let expected_calls = vec![
+        (usdc, approveCall { spender: neg_risk_exchange, amount: U256::MAX }.abi_encode()),
+        (ctf, setApprovalForAllCall { operator: adapter, approved: true }.abi_encode()),
+    ];
+    let calls: Vec<(Address, Vec<u8>)> = missing.iter().map(|(to, data, _)| (*to, data.clone())).collect();
+    assert_eq!(calls, expected_calls);
+
+    // One proxy wallet transaction through the factory, carrying just those two calls.
+    let hashes = api.send_approvals(&missing).await.unwrap();
+    let sent = node.sent.lock().unwrap().clone();
+    assert_eq!((hashes.len(), sent.len()), (1, 1));
+    let tx = TxEnvelope::decode_2718(&mut sent[0].as_ref()).unwrap();
+    assert_eq!(tx.to(), Some(address(contracts.proxy_wallet_factory.as_deref().unwrap())));
+    let proxied: Vec<(Address, Vec<u8>)> = proxyCall::abi_decode(tx.input())
+        .unwrap()
+        .calls
+        .into_iter()
+        .map(|call| (call.to, call.data.to_vec()))
+        .collect();
+    assert_eq!(proxied, expected_calls);
+}
+
+#[tokio::test]
+async fn a_fully_approved_wallet_needs_nothing_sent() {
+    let contracts = Network::Polygon.default_contracts();
+    let spenders: Vec<Address> = [
+        &contracts.ctf_exchange,
+        &contracts.neg_risk_ctf_exchange,
+        &contracts.neg_risk_adapter,
+    ]
+    .iter()
+    .map(|a| address(a))
+    .collect();
+    let node = Node {
+        contracts,
+        allowances: spenders.iter().map(|s| (*s, U256::MAX)).collect(),
+        approved: spenders,
+        sent: Arc::default(),
+    };
+    let (api, _server) = api_on(node.clone()).await;
+    let missing = api.missing_approvals().await.unwrap();
+    assert!(missing.is_empty());
+    assert!(api.send_approvals(&missing).await.unwrap().is_empty());
+    assert!(node.sent.lock().unwrap().is_empty());
+}
@@ -0,0 +1,42 @@
+//! The CLOB `/balance-allowance` endpoint: signed request for the proxy wallet, amounts in base units.
+
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use polymarket_arbitrage_bot::models::BalanceAsset;
+use serde_json::json;
+use wiremock::matchers::{header_exists, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn collateral_balance_is_capped_by_the_smallest_allowance() {
+    let clob = MockServer::start().await;
+    // uint256 max, as set by `approve`.
+    let unlimited = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    Mock::given(method("GET"))
+        .and(path("/balance-allowance"))
+        .and(query_param("asset_type", "COLLATERAL"))
+        .and(query_param("signature_type", "1"))
+        .and(header_exists("POLY_SIGNATURE"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "balance": "125500000",
+            "allowances": {
+                "0x4bFb41d5B3570DeFd03C39a9A4D8dE6Bd8B8982E": unlimited,
+                "0xC5d563A36AE78145C45a50134d48A1215220f80a": "50000000"
+            }
+        })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.api_key = Some("key".to_string());
+    config.polymarket.api_secret = Some("c2VjcmV0".into());
+    config.polymarket.api_passphrase = Some("pass".into());
+    config.polymarket.proxy_wallet_address = Some("0x0000000000000000000000000000000000000001".to_string());
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    let collateral = api.get_balance_allowance(BalanceAsset::Collateral, None).await.unwrap();
+    assert_eq!(collateral.balance, 125.5);
+    assert_eq!(collateral.allowance, 50.0);
+    assert_eq!(collateral.spendable(), 50.0);
+}
@@ -0,0 +1,69 @@
+//! The CLOB batch endpoints: several books or prices in one request, keyed by token id, and batch cancels.
+
+mod support;
+
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use rust_decimal::Decimal;
+use serde_json::json;
+use support::{FakeMarket, FakePolymarket};
+use wiremock::matchers::{body_json, header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn batch_books_and_prices_cover_every_token() {
+    let markets = [FakeMarket::new("btc-updown-15m-1767726000", "0xc15", "15up", "15down")];
+    let fake = FakePolymarket::start(&markets, &[("15up", 0.44, 0.45), ("15down", 0.55, 0.56)]).await;
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = fake.clob.uri();
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    let books = api.get_orderbooks(&["15up", "15down"]).await.unwrap();
+    assert_eq!(books.len(), 2);
+    assert_eq!(books["15up"].asks[0].price, Decimal::new(45, 2));
+    assert_eq!(books["15down"].bids[0].price, Decimal::new(55, 2));
+
+    let asks = api.get_prices(&["15up", "15down"], "SELL").await.unwrap();
+    assert_eq!(asks["15up"], Decimal::new(45, 2));
+    assert_eq!(asks["15down"], Decimal::new(56, 2));
+    let bids = api.get_prices(&["15up"], "BUY").await.unwrap();
+    assert_eq!(bids["15up"], Decimal::new(44, 2));
+}
+
+#[tokio::test]
+async fn batch_cancels_send_one_signed_request() {
+    let clob = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/orders"))
+        .and(header_exists("POLY_SIGNATURE"))
+        .and(body_json(json!(["0xa", "0xb"])))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "canceled": ["0xa"],
+            "not_canceled": { "0xb": "order already matched" }
+        })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("DELETE"))
+        .and(path("/cancel-market-orders"))
+        .and(body_json(json!({ "market": "0xc15", "asset_id": "" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "canceled": ["0xc", "0xd"],
+            "not_canceled": {}
+        })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.api_key = Some("key".to_string());
+    config.polymarket.api_secret = Some("c2VjcmV0".into());
+    config.polymarket.api_passphrase = Some("pass".into());
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    let outcome = api.cancel_orders(&["0xa", "0xb"]).await.unwrap();
+    assert_eq!(outcome.canceled, vec!["0xa".to_string()]);
+    assert_eq!(outcome.not_canceled["0xb"], "order already matched");
+    let outcome = api.cancel_market_orders("0xc15").await.unwrap();
+    assert_eq!(outcome.canceled.len(), 2);
+}
@@ -0,0 +1,181 @@
+//! 401s on authenticated CLOB requests: the credentials are re-derived whenever the ones in use are refused, and
+//! an `api_credentials` alert goes out when the CLOB refuses the re-derived ones too.
+
+use futures_util::StreamExt;
+use polymarket_arbitrage_bot::adapters::polymarket::client::CredentialsRejected;
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use polymarket_arbitrage_bot::events::{ArbEvent, EventBus};
+use serde_json::json;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+fn api_for(clob: &MockServer, nonce: u64) -> PolymarketApi {
+    let mut config = Config::default();
+    config.polymarket.api_key_nonce = nonce;
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.private_key = Some(KEY.into());
+    config.polymarket.api_key = Some("revoked".to_string());
+    config.polymarket.api_secret = Some("c2VjcmV0".into());
+    config.polymarket.api_passphrase = Some("pass".into());
+    PolymarketApi::from_config(&config.polymarket)
+}
+
+#[tokio::test]
+async fn revoked_credentials_are_rederived_and_the_request_retried() {
+    let clob = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .and(header("POLY_API_KEY", "revoked"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({ "error": "Unauthorized/Invalid api key" })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/auth/api-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "apiKey": "fresh",
+            "secret": "c2VjcmV0Mg==",
+            "passphrase": "pass2"
+        })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .and(header("POLY_API_KEY", "fresh"))
+        .and(header("POLY_PASSPHRASE", "pass2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [], "next_cursor": "LTE=" })))
+        .expect(2)
+        .mount(&clob)
+        .await;
+    let api = api_for(&clob, 0);
+
+    assert!(api.get_open_orders(None).await.unwrap().is_empty());
+    // Later requests sign with the re-derived credentials straight away.
+    assert!(api.get_open_orders(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn failed_rederivation_alerts_once_and_fails_fast() {
+    let clob = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(path("/auth/api-key"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&clob)
+        .await;
+    Mock::given(path("/auth/derive-api-key"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&clob)
+        .await;
+    let api = api_for(&clob, 0);
+    let events = EventBus::new();
+    let mut alerts = Box::pin(events.subscribe());
+    api.publish_alerts(&events);
+
+    let err = api.get_open_orders(None).await.unwrap_err();
+    assert!(err.is::<CredentialsRejected>(), "{:#}", err);
+    match alerts.next().await {
+        Some(ArbEvent::Alert { kind, message, .. }) => {
+            assert_eq!(kind, "api_credentials");
+            assert!(message.contains("/data/orders"), "{}", message);
+        }
+        other => panic!("expected an alert, got {:?}", other),
+    }
+    // No second attempt and no second alert: later requests don't reach the CLOB.
+    let err = api.get_open_orders(None).await.unwrap_err();
+    assert!(err.is::<CredentialsRejected>());
+}
+
+#[tokio::test]
+async fn an_auth_outage_is_retried_with_the_configured_nonce() {
+    let clob = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .and(header("POLY_API_KEY", "revoked"))
+        .respond_with(ResponseTemplate::new(401))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/auth/api-key"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/auth/api-key"))
+        .and(header("POLY_NONCE", "7"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "apiKey": "fresh",
+            "secret": "c2VjcmV0Mg==",
+            "passphrase": "pass2"
+        })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(path("/auth/derive-api-key"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&clob)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .and(header("POLY_API_KEY", "fresh"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": [], "next_cursor": "LTE=" })))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    let api = api_for(&clob, 7);
+
+    // The 503s don't reject the credentials: the second attempt re-derives them.
+    assert!(api.get_open_orders(None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn credentials_revoked_again_after_a_recovery_are_rederived_again() {
+    let clob = MockServer::start().await;
+    let orders = |key: &'static str| {
+        Mock::given(method("GET"))
+            .and(path("/data/orders"))
+            .and(header("POLY_API_KEY", key))
+    };
+    let ok = || ResponseTemplate::new(200).set_body_json(json!({ "data": [], "next_cursor": "LTE=" }));
+    let derived = |key: &str| {
+        let credentials = json!({ "apiKey": key, "secret": "c2VjcmV0Mg==", "passphrase": "pass2" });
+        ResponseTemplate::new(200).set_body_json(credentials)
+    };
+    orders("revoked").respond_with(ResponseTemplate::new(401)).expect(1).mount(&clob).await;
+    // The first re-derived key works once, then is revoked too.
+    orders("fresh").respond_with(ok()).up_to_n_times(1).expect(1).mount(&clob).await;
+    orders("fresh").respond_with(ResponseTemplate::new(401)).expect(1).mount(&clob).await;
+    orders("fresher").respond_with(ok()).expect(1).mount(&clob).await;
+    Mock::given(method("POST"))
+        .and(path("/auth/api-key"))
+        .respond_with(derived("fresh"))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&clob)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/auth/api-key"))
+        .respond_with(derived("fresher"))
+        .expect(1)
+        .mount(&clob)
+        .await;
+    let api = api_for(&clob, 0);
+    let events = EventBus::new();
+    let mut alerts = Box::pin(events.subscribe());
+    api.publish_alerts(&events);
+
+    assert!(api.get_open_orders(None).await.unwrap().is_empty());
+    assert!(api.get_open_orders(None).await.unwrap().is_empty());
+    // Both 401s were recovered from: nothing was alerted.
+    assert!(futures_util::FutureExt::now_or_never(alerts.next()).is_none());
+}
@@ -0,0 +1,54 @@
+//! Gamma `/markets` as a resolution source: outcome prices only count once UMA has settled the market.
+
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_market(gamma: &MockServer, condition_id: &str, market: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path("/markets"))
+        .and(query_param("condition_ids", condition_id))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([market])))
+        .mount(gamma)
+        .await;
+}
+
+#[tokio::test]
+async fn gamma_winner_waits_for_uma_settlement() {
+    let gamma = MockServer::start().await;
+    mount_market(
+        &gamma,
+        "0xproposed",
+        json!({
+            "closed": true,
+            "umaResolutionStatus": "proposed",
+            "outcomes": "[\"Up\", \"Down\"]",
+            "outcomePrices": "[\"1\", \"0\"]",
+            "clobTokenIds": "[\"up\", \"down\"]"
+        }),
+    )
+    .await;
+    mount_market(
+        &gamma,
+        "0xresolved",
+        json!({
+            "closed": true,
+            "umaResolutionStatus": "resolved",
+            "outcomes": ["Up", "Down"],
+            "outcomePrices": [0, 1],
+            "clobTokenIds": ["up", "down"]
+        }),
+    )
+    .await;
+    let mut config = Config::default();
+    config.polymarket.gamma_api_url = gamma.uri();
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    assert_eq!(api.get_gamma_winner("0xproposed").await.unwrap(), None);
+    assert_eq!(
+        api.get_gamma_winner("0xresolved").await.unwrap(),
+        Some(("down".to_string(), "Down".to_string()))
+    );
+}
@@ -0,0 +1,41 @@
+//! `polymarket.http`: each endpoint category gets its own request timeout.
+
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use serde_json::json;
+use std::time::{Duration, Instant};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn quote_fetches_time_out_before_slower_authenticated_queries() {
+    let clob = MockServer::start().await;
+    let slow = Duration::from_millis(800);
+    Mock::given(method("GET"))
+        .and(path("/book"))
+        .respond_with(ResponseTemplate::new(200).set_delay(slow).set_body_json(json!({ "bids": [], "asks": [] })))
+        .mount(&clob)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/data/orders"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_delay(slow)
+                .set_body_json(json!({ "data": [], "next_cursor": "LTE=" })),
+        )
+        .mount(&clob)
+        .await;
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.api_key = Some("key".to_string());
+    config.polymarket.api_secret = Some("c2VjcmV0".into());
+    config.polymarket.api_passphrase = Some("pass".into());
+    config.polymarket.http.market_data_timeout_ms = 150;
+    config.polymarket.http.order_timeout_ms = 5_000;
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    let started = Instant::now();
+    assert!(api.get_orderbook("123").await.is_err());
+    assert!(started.elapsed() < slow, "market data waited {:?}", started.elapsed());
+    assert!(api.get_open_orders(None).await.unwrap().is_empty());
+}
@@ -0,0 +1,220 @@
+//! One simulated overlap round end to end against fake Polymarket endpoints: discovery, WS quotes, the arb
+//! decision, resolution, PnL, the redeem targets, and the journal.
+
+mod support;
+
+use chrono::Utc;
+use polymarket_arbitrage_bot::adapters::polymarket::{PolymarketApi, PolymarketClient};
+use polymarket_arbitrage_bot::config::Config;
+use polymarket_arbitrage_bot::models::JournalEvent;
+use polymarket_arbitrage_bot::services::canary::Canary;
+use polymarket_arbitrage_bot::services::controls::RuntimeControls;
+use polymarket_arbitrage_bot::services::discovery_service::MarketDiscovery;
+use polymarket_arbitrage_bot::services::arbitrage_orchestrator::PeriodContext;
+use polymarket_arbitrage_bot::services::execution_service::{run_overlap_round, RoundContext};
+use polymarket_arbitrage_bot::services::journal::{read_journal, Journal};
+use polymarket_arbitrage_bot::services::pnl_ledger::PnlLedger;
+use polymarket_arbitrage_bot::services::resolution_service::resolve_and_compute_pnl;
+use polymarket_arbitrage_bot::services::skip_list::SkipList;
+use polymarket_arbitrage_bot::services::strategy::OverlapArb;
+use polymarket_arbitrage_bot::utils::clock::SystemClock;
+use polymarket_arbitrage_bot::utils::slug_builder::build_updown_slug;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use std::sync::Arc;
+use support::{FakeMarket, FakePolymarket};
+use tokio::sync::{broadcast, RwLock};
+use tokio::time::{sleep, Duration};
+
+/// Seconds the round has left when it starts: the book cross-check warmup plus time to trade.
+const ROUND_SECS: i64 = 8;
+
+/// Paper trading against `fake`: one 10-share arb per period, tradable to the close.
+fn simulated_config(fake: &FakePolymarket) -> Config {
+    let mut config = Config::default();
+    config.polymarket.gamma_api_url = fake.gamma.uri();
+    config.polymarket.clob_api_url = fake.clob.uri();
+    config.polymarket.ws_url = fake.ws_url.clone();
+    config.strategy.simulation_mode = true;
+    config.strategy.arb_shares = "10".to_string();
+    config.strategy.max_trades_per_period = 1;
+    config.strategy.no_trade_before_close_secs = 0;
+    config
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn overlap_round_trades_resolves_and_journals() {
+    // A round ending shortly, so the test runs in seconds; only the slugs depend on the period starts.
+    let period_end = Utc::now().timestamp() + ROUND_SECS;
+    let (period_15, period_5) = (period_end - 15 * 60, period_end - 5 * 60);
+    let markets = [
+        FakeMarket::new(&build_updown_slug("btc", 15, period_15), "0xc15", "15up", "15down"),
+        FakeMarket::new(&build_updown_slug("btc", 5, period_5), "0xc5", "5up", "5down"),
+    ];
+    // 15m Up + 5m Down costs 0.95; the other pair 1.07.
+    let books = [
+        ("15up", 0.44, 0.45),
+        ("15down", 0.55, 0.56),
+        ("5up", 0.50, 0.51),
+        ("5down", 0.49, 0.50),
+    ];
+    let fake = FakePolymarket::start(&markets, &books).await;
+
+    let journal_path = std::env::temp_dir().join(format!("overlap-round-{}.jsonl", std::process::id()));
+    let _ = std::fs::remove_file(&journal_path);
+    let mut config = simulated_config(&fake);
+    config.strategy.resolution_initial_delay_secs = 0;
+    config.strategy.resolution_poll_interval_secs = 1;
+    config.strategy.resolution_max_wait_secs = 10;
+    config.strategy.journal_path = journal_path.display().to_string();
+
+    let api: Arc<dyn PolymarketClient> = Arc::new(PolymarketApi::from_config(&config.polymarket));
+    let discovery = MarketDiscovery::new(Arc::clone(&api));
+    let (cid_15, _) = discovery.get_updown_market("btc", 15, period_15).await.unwrap().unwrap();
+    let (cid_5, _) = discovery.get_updown_market("btc", 5, period_5).await.unwrap().unwrap();
+    let (t15_up, t15_down) = discovery.get_market_tokens(&cid_15).await.unwrap();
+    let (t5_up, t5_down) = discovery.get_market_tokens(&cid_5).await.unwrap();
+    assert_eq!((cid_15.as_str(), t15_up.as_str(), t5_down.as_str()), ("0xc15", "15up", "5down"));
+
+    let journal = Arc::new(Journal::new(&config.strategy.journal_path));
+    let cumulative_pnl = Arc::new(PnlLedger::load(""));
+    let period = PeriodContext {
+        symbol: "btc".to_string(),
+        period_15,
+        period_5,
+        cid_15,
+        cid_5,
+        t15_up,
+        t15_down,
+        t5_up,
+        t5_down,
+        strike_15: 100_000.0,
+        strike_5: 100_000.0,
+    };
+    let round = RoundContext {
+        api: Arc::clone(&api),
+        config: &config,
+        unrealized: Arc::new(RwLock::new(HashMap::new())),
+        journal: Arc::clone(&journal),
+        signals: &broadcast::channel(16).0,
+        canary: &Arc::new(Canary::load(&config.strategy)),
+        spot_history: &Arc::new(RwLock::new(HashMap::new())),
+        cumulative_pnl: &cumulative_pnl,
+        hedger: None,
+        strategy: &OverlapArb,
+        controls: &RuntimeControls::new(),
+        clock: &SystemClock,
+    };
+    let trades = run_overlap_round(round, &period, None).await.unwrap();
+    assert_eq!(trades.len(), 1);
+    let trade = &trades[0];
+    assert_eq!((trade.leg1_token.as_str(), trade.leg2_token.as_str()), ("15up", "5down"));
+    assert_eq!((trade.leg1_price, trade.leg2_price, trade.size), (dec!(0.45), dec!(0.50), dec!(10)));
+
+    // Both markets close Up: the 15m leg pays 10, the 5m leg nothing, against 9.50 paid.
+    fake.resolve(&markets, &books, &["Up", "Up"]).await;
+    let skip_list = SkipList::load("", 0);
+    let (redeem_targets, pnl) = resolve_and_compute_pnl(
+        Arc::clone(&api),
+        &config,
+        &trades,
+        Arc::clone(&cumulative_pnl),
+        &journal,
+        &skip_list,
+    )
+    .await
+    .unwrap()
+    .expect("both markets resolved within the wait");
+    assert_eq!(pnl, dec!(0.5), "period PnL {}", pnl);
+    assert_eq!(cumulative_pnl.total(), dec!(0.5));
+    assert_eq!(cumulative_pnl.snapshot().by_symbol["btc"], dec!(0.5));
+    assert_eq!(redeem_targets, vec![("0xc15".to_string(), "Up".to_string())]);
+
+    // The writer thread appends in the background.
+    let mut entries = Vec::new();
+    for _ in 0..50 {
+        entries = read_journal(&journal_path).unwrap_or_default();
+        if entries.len() >= 2 {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    let _ = std::fs::remove_file(&journal_path);
+    assert!(trade.trade_id.starts_with(&format!("btc-{}-", period_15)), "trade id {}", trade.trade_id);
+    assert!(entries.iter().all(|e| e.trade_id.as_deref() == Some(trade.trade_id.as_str())));
+    assert!(matches!(
+        entries.first().map(|e| &e.event),
+        Some(JournalEvent::Decision { simulated: true, .. })
+    ));
+    assert!(matches!(
+        entries.last().map(|e| &e.event),
+        Some(JournalEvent::Resolved { outcome_15, outcome_5, pnl, .. })
+            if outcome_15 == "Up" && outcome_5 == "Up" && (pnl - 0.5).abs() < 1e-9
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn a_stop_winds_the_round_up_with_its_trades() {
+    // A round with minutes to go: only the stop can end it within the test.
+    let period_end = Utc::now().timestamp() + 300;
+    let (period_15, period_5) = (period_end - 15 * 60, period_end - 5 * 60);
+    let markets = [
+        FakeMarket::new(&build_updown_slug("eth", 15, period_15), "0xe15", "e15up", "e15down"),
+        FakeMarket::new(&build_updown_slug("eth", 5, period_5), "0xe5", "e5up", "e5down"),
+    ];
+    let books = [
+        ("e15up", 0.44, 0.45),
+        ("e15down", 0.55, 0.56),
+        ("e5up", 0.50, 0.51),
+        ("e5down", 0.49, 0.50),
+    ];
+    let fake = FakePolymarket::start(&markets, &books).await;
+    let mut config = simulated_config(&fake);
+    config.strategy.journal_path = String::new();
+
+    let api: Arc<dyn PolymarketClient> = Arc::new(PolymarketApi::from_config(&config.polymarket));
+    let controls = Arc::new(RuntimeControls::new());
+    let round = tokio::spawn({
+        let (api, controls) = (Arc::clone(&api), Arc::clone(&controls));
+        async move {
+            let period = PeriodContext {
+                symbol: "eth".to_string(),
+                period_15,
+                period_5,
+                cid_15: "0xe15".to_string(),
+                cid_5: "0xe5".to_string(),
+                t15_up: "e15up".to_string(),
+                t15_down: "e15down".to_string(),
+                t5_up: "e5up".to_string(),
+                t5_down: "e5down".to_string(),
+                strike_15: 100_000.0,
+                strike_5: 100_000.0,
+            };
+            let round = RoundContext {
+                api,
+                config: &config,
+                unrealized: Arc::new(RwLock::new(HashMap::new())),
+                journal: Arc::new(Journal::new("")),
+                signals: &broadcast::channel(16).0,
+                canary: &Arc::new(Canary::load(&config.strategy)),
+                spot_history: &Arc::new(RwLock::new(HashMap::new())),
+                cumulative_pnl: &Arc::new(PnlLedger::load("")),
+                hedger: None,
+                strategy: &OverlapArb,
+                controls: &controls,
+                clock: &SystemClock,
+            };
+            run_overlap_round(round, &period, None).await
+        }
+    });
+    // Past the book cross-check warmup, so the arb has been taken.
+    sleep(Duration::from_secs(5)).await;
+    assert!(!round.is_finished());
+    controls.stop();
+    let trades = tokio::time::timeout(Duration::from_secs(5), round)
+        .await
+        .expect("the round winds up promptly after a stop")
+        .unwrap()
+        .unwrap();
+    assert_eq!(trades.len(), 1, "the trade placed before the stop is returned");
+}
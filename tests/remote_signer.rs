@@ -0,0 +1,142 @@
+//! External signers: digests go to the signing service or KMS, and the signatures are checked against the
+//! configured EOA. A signer that can't be set up stops the bot rather than falling back to `private_key`.
+
+use alloy::primitives::{keccak256, Address, Signature, U256};
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use polymarket_arbitrage_bot::adapters::signer::{
+    AwsCredentials, AwsKmsSigner, GcpKmsSigner, RemoteSigner, TxSigner,
+};
+use polymarket_arbitrage_bot::api::PolymarketApi;
+use polymarket_arbitrage_bot::bot::connect;
+use polymarket_arbitrage_bot::config::{Config, SignerKind};
+use serde_json::json;
+use std::str::FromStr;
+use wiremock::matchers::{body_partial_json, header, header_regex, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+
+#[tokio::test]
+async fn remote_signatures_are_checked_against_the_signer_address() {
+    let local = PrivateKeySigner::from_str(KEY).unwrap();
+    let hash = keccak256(b"order");
+    let signature = Signer::sign_hash(&local, &hash).await.unwrap();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/sign"))
+        .and(header("authorization", "Bearer t0ken"))
+        .and(body_partial_json(json!({ "hash": hash.to_string() })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "signature": format!("0x{}", hex::encode(signature.as_bytes()))
+        })))
+        .mount(&server)
+        .await;
+    let url = format!("{}/sign", server.uri());
+
+    let remote = RemoteSigner::new(&url, Some("t0ken".into()), &local.address().to_string()).unwrap();
+    assert_eq!(TxSigner::address(&remote), local.address());
+    assert_eq!(remote.sign_hash(hash).await.unwrap(), signature);
+
+    // The service signing with another key than the configured address is an error, not a bad order later.
+    let other = Address::from_str("0x1111111111111111111111111111111111111111").unwrap();
+    let wrong = RemoteSigner::new(&url, Some("t0ken".into()), &other.to_string()).unwrap();
+    let err = wrong.sign_hash(hash).await.unwrap_err();
+    assert!(err.to_string().contains("expected"));
+}
+
+/// `signature` as a KMS returns it: DER, without the recovery bit, and with the high `s` Ethereum rejects.
+fn kms_der(signature: &Signature) -> Vec<u8> {
+    let order = U256::from_str("0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141").unwrap();
+    let integer = |v: U256| {
+        let bytes = v.to_be_bytes::<32>();
+        let mut int: Vec<u8> = bytes.iter().copied().skip_while(|b| *b == 0).collect();
+        if int[0] & 0x80 != 0 {
+            int.insert(0, 0);
+        }
+        [vec![0x02, int.len() as u8], int].concat()
+    };
+    let body = [integer(signature.r()), integer(order - signature.s())].concat();
+    [vec![0x30, body.len() as u8], body].concat()
+}
+
+#[tokio::test]
+async fn aws_kms_signs_with_sigv4_and_returns_an_ethereum_signature() {
+    let local = PrivateKeySigner::from_str(KEY).unwrap();
+    let hash = keccak256(b"order");
+    let signature = Signer::sign_hash(&local, &hash).await.unwrap();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/"))
+        .and(header("x-amz-target", "TrentService.Sign"))
+        .and(header_regex(
+            "authorization",
+            "^AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/[0-9]{8}/us-east-1/kms/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-target, Signature=[0-9a-f]{64}$",
+        ))
+        .and(body_partial_json(json!({ "KeyId": "alias/trading", "MessageType": "DIGEST" })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "KeyId": "arn:aws:kms:us-east-1:111122223333:key/1234",
+            "Signature": BASE64.encode(kms_der(&signature)),
+            "SigningAlgorithm": "ECDSA_SHA_256",
+        })))
+        .mount(&server)
+        .await;
+    let credentials = AwsCredentials {
+        access_key_id: "AKIDEXAMPLE".to_string(),
+        secret_access_key: "secret".into(),
+        session_token: None,
+    };
+    let kms = AwsKmsSigner::new(
+        "alias/trading",
+        "us-east-1",
+        Some(&server.uri()),
+        credentials,
+        &local.address().to_string(),
+    )
+    .unwrap();
+    assert_eq!(kms.sign_hash(hash).await.unwrap(), signature);
+}
+
+#[tokio::test]
+async fn gcp_kms_signatures_recover_to_the_signer_address() {
+    let local = PrivateKeySigner::from_str(KEY).unwrap();
+    let hash = keccak256(b"safe tx");
+    let signature = Signer::sign_hash(&local, &hash).await.unwrap();
+    let key_version = "projects/p/locations/global/keyRings/bot/cryptoKeys/eoa/cryptoKeyVersions/1";
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/v1/{}:asymmetricSign", key_version)))
+        .and(header("authorization", "Bearer ya29.t0ken"))
+        .and(body_partial_json(json!({ "digest": { "sha256": BASE64.encode(hash) } })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "signature": BASE64.encode(kms_der(&signature)),
+            "name": key_version,
+        })))
+        .mount(&server)
+        .await;
+
+    let kms = GcpKmsSigner::new(key_version, Some("ya29.t0ken".into()), Some(&server.uri()), &local.address().to_string())
+        .unwrap();
+    assert_eq!(kms.sign_hash(hash).await.unwrap(), signature);
+
+    // A key version holding another key can't sign for the configured address.
+    let other = "0x1111111111111111111111111111111111111111";
+    let wrong = GcpKmsSigner::new(key_version, Some("ya29.t0ken".into()), Some(&server.uri()), other).unwrap();
+    assert!(wrong.sign_hash(hash).await.is_err());
+}
+
+#[tokio::test]
+async fn an_invalid_signer_does_not_fall_back_to_the_private_key() {
+    let mut config = Config::default();
+    config.polymarket.private_key = Some(KEY.into());
+    config.polymarket.signer.kind = SignerKind::GcpKms;
+    config.polymarket.signer.key_id = "eoa".to_string();
+    let api = PolymarketApi::from_config(&config.polymarket);
+    assert!(api.signer_error().is_some());
+    assert!(api.signer_address().is_err());
+    let err = connect(&api, &config).await.unwrap_err();
+    assert!(err.to_string().contains("polymarket.signer"), "{}", err);
+}
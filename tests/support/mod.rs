@@ -0,0 +1,181 @@
+//! Fake Polymarket endpoints for integration tests: Gamma and CLOB as wiremock HTTP servers and the market
+//! WebSocket as a local tungstenite server that answers every subscription with fixed books.
+
+// Each test crate uses only part of the fakes.
+#![allow(dead_code)]
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// One binary up/down market: condition id and (token id, outcome) per side.
+pub struct FakeMarket {
+    pub slug: String,
+    pub condition_id: String,
+    pub tokens: [(String, String); 2],
+}
+
+impl FakeMarket {
+    pub fn new(slug: &str, condition_id: &str, up_token: &str, down_token: &str) -> Self {
+        Self {
+            slug: slug.to_string(),
+            condition_id: condition_id.to_string(),
+            tokens: [
+                (up_token.to_string(), "Up".to_string()),
+                (down_token.to_string(), "Down".to_string()),
+            ],
+        }
+    }
+
+    /// CLOB `/markets/{condition_id}` body; `winner` closes the market with that outcome winning.
+    fn clob_body(&self, winner: Option<&str>) -> Value {
+        json!({
+            "condition_id": self.condition_id,
+            "question": self.slug,
+            "tokens": self.tokens.iter().map(|(token_id, outcome)| json!({
+                "outcome": outcome,
+                "token_id": token_id,
+                "winner": winner == Some(outcome.as_str()),
+            })).collect::<Vec<_>>(),
+            "active": winner.is_none(),
+            "closed": winner.is_some(),
+            "end_date_iso": "",
+        })
+    }
+}
+
+pub struct FakePolymarket {
+    pub gamma: MockServer,
+    pub clob: MockServer,
+    /// Base URL of the market WebSocket (`ws_url` in the config).
+    pub ws_url: String,
+}
+
+impl FakePolymarket {
+    /// Serve `markets` on Gamma and the CLOB, and `books` (token id, best bid, best ask) on both the CLOB
+    /// `/book`, `/books`, and `/prices` endpoints and the market WebSocket.
+    pub async fn start(markets: &[FakeMarket], books: &[(&str, f64, f64)]) -> Self {
+        let gamma = MockServer::start().await;
+        let clob = MockServer::start().await;
+        for market in markets {
+            Mock::given(method("GET"))
+                .and(path(format!("/events/slug/{}", market.slug)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "markets": [{
+                        "conditionId": market.condition_id,
+                        "id": "1",
+                        "question": market.slug,
+                        "slug": market.slug,
+                        "endDateISO": null,
+                        "active": true,
+                        "closed": false,
+                    }]
+                })))
+                .mount(&gamma)
+                .await;
+        }
+        let fake = Self {
+            gamma,
+            clob,
+            ws_url: spawn_market_ws(books).await,
+        };
+        fake.mount_clob(markets, books, &[]).await;
+        fake
+    }
+
+    /// Close `markets[i]` with `winners[i]` winning on the CLOB; the books stay as they were.
+    pub async fn resolve(&self, markets: &[FakeMarket], books: &[(&str, f64, f64)], winners: &[&str]) {
+        self.clob.reset().await;
+        self.mount_clob(markets, books, winners).await;
+    }
+
+    async fn mount_clob(&self, markets: &[FakeMarket], books: &[(&str, f64, f64)], winners: &[&str]) {
+        for (i, market) in markets.iter().enumerate() {
+            Mock::given(method("GET"))
+                .and(path(format!("/markets/{}", market.condition_id)))
+                .respond_with(ResponseTemplate::new(200).set_body_json(market.clob_body(winners.get(i).copied())))
+                .mount(&self.clob)
+                .await;
+        }
+        for &(token_id, bid, ask) in books {
+            Mock::given(method("GET"))
+                .and(path("/book"))
+                .and(query_param("token_id", token_id))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "bids": [{ "price": bid.to_string(), "size": "100" }],
+                    "asks": [{ "price": ask.to_string(), "size": "100" }],
+                })))
+                .mount(&self.clob)
+                .await;
+        }
+        // Every book whatever tokens were asked for; callers look theirs up by asset_id.
+        let all_books: Vec<Value> = books
+            .iter()
+            .map(|&(token_id, bid, ask)| {
+                json!({
+                    "asset_id": token_id,
+                    "bids": [{ "price": bid.to_string(), "size": "100" }],
+                    "asks": [{ "price": ask.to_string(), "size": "100" }],
+                })
+            })
+            .collect();
+        Mock::given(method("POST"))
+            .and(path("/books"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(all_books))
+            .mount(&self.clob)
+            .await;
+        let all_prices: serde_json::Map<String, Value> = books
+            .iter()
+            .map(|&(token_id, bid, ask)| {
+                (token_id.to_string(), json!({ "BUY": bid.to_string(), "SELL": ask.to_string() }))
+            })
+            .collect();
+        Mock::given(method("POST"))
+            .and(path("/prices"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(all_prices))
+            .mount(&self.clob)
+            .await;
+    }
+}
+
+/// Market WebSocket that sends one `book` message per token after the subscription, then stays open.
+async fn spawn_market_ws(books: &[(&str, f64, f64)]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind fake market WS");
+    let addr = listener.local_addr().expect("fake market WS address");
+    let messages: Vec<String> = books
+        .iter()
+        .map(|&(token_id, bid, ask)| {
+            json!({
+                "event_type": "book",
+                "asset_id": token_id,
+                "bids": [{ "price": bid.to_string(), "size": "100" }],
+                "asks": [{ "price": ask.to_string(), "size": "100" }],
+            })
+            .to_string()
+        })
+        .collect();
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let messages = messages.clone();
+            tokio::spawn(async move {
+                let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                    return;
+                };
+                // The first frame is the subscription.
+                if ws.next().await.is_none() {
+                    return;
+                }
+                for message in messages {
+                    if ws.send(Message::Text(message)).await.is_err() {
+                        return;
+                    }
+                }
+                while let Some(Ok(_)) = ws.next().await {}
+            });
+        }
+    });
+    format!("ws://{}", addr)
+}
@@ -0,0 +1,77 @@
+//! The authenticated CLOB client is built once and reused by every order until a post fails, so placement doesn't
+//! pay for an API-key round trip and the SDK's per-token lookups stay cached.
+
+use polymarket_arbitrage_bot::adapters::polymarket::PolymarketApi;
+use polymarket_arbitrage_bot::config::Config;
+use polymarket_arbitrage_bot::models::{OrderRequest, OrderType, Side};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318";
+const TOKEN: &str = "123";
+
+async fn clob() -> MockServer {
+    let clob = MockServer::start().await;
+    let answers = [
+        ("GET", "/time", json!(1767726000)),
+        (
+            "POST",
+            "/auth/api-key",
+            json!({ "apiKey": "00000000-0000-0000-0000-000000000001", "secret": "c2VjcmV0", "passphrase": "pass" }),
+        ),
+        ("GET", "/book", json!({ "bids": [], "asks": [], "tick_size": "0.01", "min_order_size": "5" })),
+        ("GET", "/tick-size", json!({ "minimum_tick_size": 0.01 })),
+        ("GET", "/neg-risk", json!({ "neg_risk": false })),
+        ("GET", "/fee-rate", json!({ "base_fee": 0 })),
+    ];
+    for (verb, route, body) in answers {
+        Mock::given(method(verb))
+            .and(path(route))
+            .respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .mount(&clob)
+            .await;
+    }
+    Mock::given(method("POST"))
+        .and(path("/order"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&clob)
+        .await;
+    clob
+}
+
+/// Requests the CLOB got on `route`.
+async fn hits(clob: &MockServer, route: &str) -> usize {
+    let requests = clob.received_requests().await.unwrap();
+    requests.iter().filter(|r| r.url.path() == route).count()
+}
+
+#[tokio::test]
+async fn orders_share_one_session_until_a_post_fails() {
+    let clob = clob().await;
+    let mut config = Config::default();
+    config.polymarket.clob_api_url = clob.uri();
+    config.polymarket.private_key = Some(KEY.into());
+    let api = PolymarketApi::from_config(&config.polymarket);
+
+    api.prepare_orders(&[TOKEN]).await.unwrap();
+    api.prepare_orders(&[TOKEN]).await.unwrap();
+    assert_eq!((hits(&clob, "/auth/api-key").await, hits(&clob, "/tick-size").await), (1, 1));
+
+    let order = OrderRequest {
+        token_id: TOKEN.to_string(),
+        side: Side::Buy,
+        size: "5".to_string(),
+        price: "0.45".to_string(),
+        order_type: OrderType::Gtc,
+        expiration: None,
+        client_order_id: None,
+    };
+    // Posted with the prepared session.
+    assert!(api.place_order(&order).await.is_err());
+    assert_eq!((hits(&clob, "/order").await, hits(&clob, "/auth/api-key").await), (1, 1));
+
+    // The failed post dropped the session: the next order authenticates afresh.
+    api.prepare_orders(&[TOKEN]).await.unwrap();
+    assert_eq!((hits(&clob, "/auth/api-key").await, hits(&clob, "/tick-size").await), (2, 2));
+}
@@ -0,0 +1,83 @@
+//! Deposit detection: `recent_usdc_inbound` reads USDC.e `Transfer` logs to the funder over JSON-RPC, skipping
+//! payouts and settlements from Polymarket's own contracts.
+
+use alloy::primitives::{keccak256, Address, B256, U256};
+use polymarket_arbitrage_bot::api::PolymarketApi;
+use polymarket_arbitrage_bot::config::{Config, Network};
+use serde_json::{json, Value};
+use std::str::FromStr;
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const WALLET: &str = "0x0000000000000000000000000000000000000abc";
+
+/// Polygon node at block `head` whose `eth_getLogs` returns `logs`, answering with each request's id.
+struct Node {
+    head: u64,
+    logs: Vec<Value>,
+}
+
+impl Respond for Node {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: Value = serde_json::from_slice(&request.body).unwrap();
+        let result = match body["method"].as_str() {
+            Some("eth_blockNumber") => json!(format!("{:#x}", self.head)),
+            Some("eth_getLogs") => json!(self.logs),
+            other => panic!("unexpected RPC call {:?}", other),
+        };
+        ResponseTemplate::new(200).set_body_json(json!({"jsonrpc": "2.0", "id": body["id"], "result": result}))
+    }
+}
+
+/// USDC.e `Transfer(from, WALLET, base_units)` mined in `block`.
+fn transfer(from: Address, base_units: u64, block: u64) -> Value {
+    let contracts = Network::Polygon.default_contracts();
+    let wallet = Address::from_str(WALLET).unwrap();
+    json!({
+        "address": contracts.usdc,
+        "topics": [keccak256(b"Transfer(address,address,uint256)"), from.into_word(), wallet.into_word()],
+        "data": B256::from(U256::from(base_units)),
+        "blockNumber": format!("{:#x}", block),
+        "blockHash": B256::with_last_byte(block as u8),
+        "transactionHash": B256::repeat_byte(block as u8),
+        "transactionIndex": "0x0",
+        "logIndex": "0x0",
+        "removed": false,
+    })
+}
+
+async fn api_on(node: Node) -> (PolymarketApi, MockServer) {
+    let server = MockServer::start().await;
+    Mock::given(wiremock::matchers::method("POST")).respond_with(node).mount(&server).await;
+    let mut config = Config::default();
+    config.polymarket.rpc_url = vec![server.uri()];
+    (PolymarketApi::from_config(&config.polymarket), server)
+}
+
+/// `fromBlock` of the `eth_getLogs` filter the node was sent.
+async fn logs_from_block(server: &MockServer) -> String {
+    let requests = server.received_requests().await.unwrap();
+    let get_logs = requests
+        .iter()
+        .map(|r| serde_json::from_slice::<Value>(&r.body).unwrap())
+        .find(|body| body["method"] == "eth_getLogs")
+        .expect("eth_getLogs sent");
+    get_logs["params"][0]["fromBlock"].as_str().unwrap().to_string()
+}
+
+#[tokio::test]
+async fn the_latest_external_deposit_is_reported_in_usdc() {
+    let ctf = Address::from_str(&Network::Polygon.default_contracts().ctf).unwrap();
+    // A 25.5 USDC top-up, then a redemption payout from the CTF, which isn't a deposit.
+    let logs = vec![transfer(Address::repeat_byte(0x11), 25_500_000, 3), transfer(ctf, 7_000_000, 4)];
+    // Block 5 is closer to genesis than the lookback: the filter starts at block 0 rather than wrapping.
+    let (api, server) = api_on(Node { head: 5, logs }).await;
+    assert_eq!(api.recent_usdc_inbound(WALLET, 100).await.unwrap(), Some((3, 25.5)));
+    assert_eq!(logs_from_block(&server).await, "0x0");
+}
+
+#[tokio::test]
+async fn no_deposit_in_the_lookback_is_none() {
+    let (api, server) = api_on(Node { head: 1000, logs: Vec::new() }).await;
+    assert_eq!(api.recent_usdc_inbound(WALLET, 30).await.unwrap(), None);
+    assert_eq!(logs_from_block(&server).await, format!("{:#x}", 970));
+}
@@ -0,0 +1,78 @@
+//! The webhook sink against a slow endpoint: unselected quotes are filtered out as they arrive, so a burst of them
+//! while a delivery is in flight can't push the next selected event off the event bus.
+
+use polymarket_arbitrage_bot::config::WebhookConfig;
+use polymarket_arbitrage_bot::events::{ArbEvent, EventBus};
+use polymarket_arbitrage_bot::models::{JournalEntry, JournalEvent};
+use polymarket_arbitrage_bot::services::webhook::spawn_webhook_sink;
+use std::time::Duration;
+use tokio::time::sleep;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// More quotes than the event bus holds for one subscriber.
+const QUOTE_BURST: usize = 5000;
+
+fn resolved(trade_id: &str) -> ArbEvent {
+    ArbEvent::Trade(JournalEntry {
+        ts_ms: 0,
+        trade_id: Some(trade_id.to_string()),
+        event: JournalEvent::Resolved {
+            outcome_15: "Up".to_string(),
+            outcome_5: "Down".to_string(),
+            pnl: 0.5,
+            source_15: None,
+            source_5: None,
+        },
+    })
+}
+
+fn quote_burst(events: &EventBus) {
+    for i in 0..QUOTE_BURST {
+        events.publish(ArbEvent::Quote {
+            token_id: "1".to_string(),
+            bid: Some(0.4),
+            ask: Some(0.41),
+            ts_ms: i as i64,
+        });
+    }
+}
+
+#[tokio::test]
+async fn a_quote_burst_during_a_slow_delivery_drops_no_selected_event() {
+    let hook = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(500)))
+        .mount(&hook)
+        .await;
+    let events = EventBus::new();
+    let config = WebhookConfig {
+        url: hook.uri(),
+        ..WebhookConfig::default()
+    };
+    let (shutdown, shutdown_rx) = tokio::sync::watch::channel(false);
+    let sink = spawn_webhook_sink(&config, &events, shutdown_rx).unwrap();
+
+    // The second resolution and the burst after it arrive while the first is still being delivered.
+    events.publish(resolved("btc-1-1"));
+    sleep(Duration::from_millis(100)).await;
+    events.publish(resolved("btc-1-2"));
+    sleep(Duration::from_millis(50)).await;
+    quote_burst(&events);
+
+    let mut delivered = Vec::new();
+    for _ in 0..30 {
+        delivered = hook.received_requests().await.unwrap_or_default();
+        if delivered.len() >= 2 {
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    let trade_ids: Vec<String> = delivered
+        .iter()
+        .map(|r| r.body_json::<serde_json::Value>().unwrap()["trade_id"].as_str().unwrap_or_default().to_string())
+        .collect();
+    assert_eq!(trade_ids, ["btc-1-1", "btc-1-2"]);
+    shutdown.send_replace(true);
+    tokio::time::timeout(Duration::from_secs(5), sink).await.unwrap().unwrap();
+}